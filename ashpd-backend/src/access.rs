@@ -1,18 +1,19 @@
-use std::sync::Arc;
+use std::{future::Future, sync::Arc};
 
-use async_trait::async_trait;
-
-use crate::{
-    backend::{
-        request::{Request, RequestImpl},
-        MaybeAppID, MaybeWindowIdentifier, Result,
-    },
-    desktop::{file_chooser::Choice, request::Response, HandleToken, Icon},
+use ashpd::{
+    desktop::{file_chooser::Choice, HandleToken, Icon, Response},
     zvariant::{self, DeserializeDict, OwnedObjectPath, SerializeDict},
     AppID, WindowIdentifierType,
 };
+use futures_util::future::BoxFuture;
 
-#[derive(DeserializeDict, zvariant::Type)]
+use crate::{
+    backend::PortalInterface,
+    request::{DynRequestImpl, Request, RequestImpl},
+    MaybeAppID, MaybeWindowIdentifier, RawOptions, Result,
+};
+
+#[derive(DeserializeDict, zvariant::Type, Debug)]
 #[zvariant(signature = "dict")]
 pub struct AccessOptions {
     modal: Option<bool>,
@@ -61,10 +62,9 @@ impl AccessResponse {
     }
 }
 
-#[async_trait]
 pub trait AccessImpl: RequestImpl {
     #[allow(clippy::too_many_arguments)]
-    async fn access_dialog(
+    fn access_dialog(
         &self,
         token: HandleToken,
         app_id: Option<AppID>,
@@ -72,26 +72,77 @@ pub trait AccessImpl: RequestImpl {
         title: String,
         subtitle: String,
         body: String,
-        options: AccessOptions,
-    ) -> Result<AccessResponse>;
+        options: RawOptions<AccessOptions>,
+    ) -> impl Future<Output = Result<AccessResponse>> + Send;
+}
+
+/// Object safe counterpart of [`AccessImpl`], used to store the implementation.
+pub(crate) trait DynAccessImpl: DynRequestImpl {
+    #[allow(clippy::too_many_arguments)]
+    fn access_dialog<'a>(
+        &'a self,
+        token: HandleToken,
+        app_id: Option<AppID>,
+        window_identifier: Option<WindowIdentifierType>,
+        title: String,
+        subtitle: String,
+        body: String,
+        options: RawOptions<AccessOptions>,
+    ) -> BoxFuture<'a, Result<AccessResponse>>;
+}
+
+impl<T: AccessImpl> DynAccessImpl for T {
+    #[allow(clippy::too_many_arguments)]
+    fn access_dialog<'a>(
+        &'a self,
+        token: HandleToken,
+        app_id: Option<AppID>,
+        window_identifier: Option<WindowIdentifierType>,
+        title: String,
+        subtitle: String,
+        body: String,
+        options: RawOptions<AccessOptions>,
+    ) -> BoxFuture<'a, Result<AccessResponse>> {
+        Box::pin(AccessImpl::access_dialog(
+            self,
+            token,
+            app_id,
+            window_identifier,
+            title,
+            subtitle,
+            body,
+            options,
+        ))
+    }
 }
 
 pub(crate) struct AccessInterface {
-    imp: Arc<dyn AccessImpl>,
+    imp: Arc<dyn DynAccessImpl>,
     cnx: zbus::Connection,
+    version: u32,
 }
 
 impl AccessInterface {
-    pub fn new(imp: Arc<dyn AccessImpl>, cnx: zbus::Connection) -> Self {
-        Self { imp, cnx }
+    pub fn new(imp: Arc<dyn DynAccessImpl>, cnx: zbus::Connection) -> Self {
+        Self {
+            imp,
+            cnx,
+            version: 1, // TODO: Is this correct?
+        }
+    }
+}
+
+impl PortalInterface for AccessInterface {
+    fn set_version(&mut self, version: u32) {
+        self.version = version;
     }
 }
 
 #[zbus::interface(name = "org.freedesktop.impl.portal.Access")]
 impl AccessInterface {
-    #[zbus(property(emits_changed_signal = "const"), name = "version")]
+    #[zbus(property, name = "version")]
     fn version(&self) -> u32 {
-        1 // TODO: Is this correct?
+        self.version
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -104,7 +155,7 @@ impl AccessInterface {
         title: String,
         subtitle: String,
         body: String,
-        options: AccessOptions,
+        options: RawOptions<AccessOptions>,
     ) -> Result<Response<AccessResponse>> {
         let imp = Arc::clone(&self.imp);
 
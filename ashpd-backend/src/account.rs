@@ -0,0 +1,124 @@
+use std::{future::Future, sync::Arc};
+
+use ashpd::{
+    desktop::{account::UserInformation, HandleToken, Response},
+    zvariant::{DeserializeDict, OwnedObjectPath, Type},
+    AppID, WindowIdentifierType,
+};
+use futures_util::future::BoxFuture;
+
+use crate::{
+    backend::PortalInterface,
+    request::{DynRequestImpl, Request, RequestImpl},
+    MaybeAppID, MaybeWindowIdentifier, RawOptions, Result,
+};
+
+#[derive(Debug, DeserializeDict, Type)]
+#[zvariant(signature = "dict")]
+pub struct UserInformationOptions {
+    reason: Option<String>,
+}
+
+impl UserInformationOptions {
+    pub fn reason(&self) -> Option<&str> {
+        self.reason.as_deref()
+    }
+}
+
+pub trait AccountImpl: RequestImpl {
+    fn get_user_information(
+        &self,
+        token: HandleToken,
+        app_id: Option<AppID>,
+        window_identifier: Option<WindowIdentifierType>,
+        options: RawOptions<UserInformationOptions>,
+    ) -> impl Future<Output = Result<UserInformation>> + Send;
+}
+
+/// Object safe counterpart of [`AccountImpl`], used to store the implementation.
+pub(crate) trait DynAccountImpl: DynRequestImpl {
+    fn get_user_information<'a>(
+        &'a self,
+        token: HandleToken,
+        app_id: Option<AppID>,
+        window_identifier: Option<WindowIdentifierType>,
+        options: RawOptions<UserInformationOptions>,
+    ) -> BoxFuture<'a, Result<UserInformation>>;
+}
+
+impl<T: AccountImpl> DynAccountImpl for T {
+    fn get_user_information<'a>(
+        &'a self,
+        token: HandleToken,
+        app_id: Option<AppID>,
+        window_identifier: Option<WindowIdentifierType>,
+        options: RawOptions<UserInformationOptions>,
+    ) -> BoxFuture<'a, Result<UserInformation>> {
+        Box::pin(AccountImpl::get_user_information(
+            self,
+            token,
+            app_id,
+            window_identifier,
+            options,
+        ))
+    }
+}
+
+pub(crate) struct AccountInterface {
+    imp: Arc<dyn DynAccountImpl>,
+    cnx: zbus::Connection,
+    version: u32,
+}
+
+impl AccountInterface {
+    pub fn new(imp: Arc<dyn DynAccountImpl>, cnx: zbus::Connection) -> Self {
+        Self {
+            imp,
+            cnx,
+            version: 1,
+        }
+    }
+}
+
+impl PortalInterface for AccountInterface {
+    fn set_version(&mut self, version: u32) {
+        self.version = version;
+    }
+}
+
+#[zbus::interface(name = "org.freedesktop.impl.portal.Account")]
+impl AccountInterface {
+    #[zbus(property, name = "version")]
+    fn version(&self) -> u32 {
+        self.version
+    }
+
+    #[zbus(name = "GetUserInformation")]
+    #[zbus(out_args("response", "results"))]
+    async fn get_user_information(
+        &self,
+        handle: OwnedObjectPath,
+        app_id: MaybeAppID,
+        window_identifier: MaybeWindowIdentifier,
+        options: RawOptions<UserInformationOptions>,
+    ) -> Result<Response<UserInformation>> {
+        let imp = Arc::clone(&self.imp);
+
+        Request::spawn(
+            "Account::GetUserInformation",
+            &self.cnx,
+            handle.clone(),
+            Arc::clone(&self.imp),
+            async move {
+                imp.get_user_information(
+                    HandleToken::try_from(&handle).unwrap(),
+                    app_id.inner(),
+                    window_identifier.inner(),
+                    options,
+                )
+                .await
+            },
+        )
+        .await
+    }
+}
@@ -1,17 +1,18 @@
-use std::sync::Arc;
+use std::{future::Future, sync::Arc};
 
-use async_trait::async_trait;
-
-use crate::{
-    backend::{
-        request::{Request, RequestImpl},
-        MaybeAppID, MaybeWindowIdentifier,
-    },
+use ashpd::{
     desktop::{HandleToken, Response},
     zbus::object_server::{InterfaceRef, ObjectServer},
     zvariant::{DeserializeDict, OwnedObjectPath, SerializeDict, Type},
     ActivationToken, AppID, PortalError, WindowIdentifierType,
 };
+use futures_util::future::BoxFuture;
+
+use crate::{
+    backend::PortalInterface,
+    request::{DynRequestImpl, Request, RequestImpl},
+    MaybeAppID, MaybeWindowIdentifier, RawOptions,
+};
 
 #[derive(Debug, DeserializeDict, Type)]
 #[zvariant(signature = "dict")]
@@ -75,40 +76,96 @@ impl Choice {
     }
 }
 
-#[async_trait]
 pub trait AppChooserImpl: RequestImpl {
-    async fn choose_application(
+    fn choose_application(
         &self,
         token: HandleToken,
         app_id: Option<AppID>,
         parent_window: Option<WindowIdentifierType>,
         choices: Vec<AppID>,
-        options: ChooserOptions,
-    ) -> Result<Choice, PortalError>;
+        options: RawOptions<ChooserOptions>,
+    ) -> impl Future<Output = Result<Choice, PortalError>> + Send;
 
-    async fn update_choices(
+    fn update_choices(
         &self,
         request: InterfaceRef<Request>,
         choices: Vec<AppID>,
-    ) -> Result<(), PortalError>;
+    ) -> impl Future<Output = Result<(), PortalError>> + Send;
+}
+
+/// Object safe counterpart of [`AppChooserImpl`], used to store the implementation.
+pub(crate) trait DynAppChooserImpl: DynRequestImpl {
+    fn choose_application<'a>(
+        &'a self,
+        token: HandleToken,
+        app_id: Option<AppID>,
+        parent_window: Option<WindowIdentifierType>,
+        choices: Vec<AppID>,
+        options: RawOptions<ChooserOptions>,
+    ) -> BoxFuture<'a, Result<Choice, PortalError>>;
+
+    fn update_choices<'a>(
+        &'a self,
+        request: InterfaceRef<Request>,
+        choices: Vec<AppID>,
+    ) -> BoxFuture<'a, Result<(), PortalError>>;
+}
+
+impl<T: AppChooserImpl> DynAppChooserImpl for T {
+    fn choose_application<'a>(
+        &'a self,
+        token: HandleToken,
+        app_id: Option<AppID>,
+        parent_window: Option<WindowIdentifierType>,
+        choices: Vec<AppID>,
+        options: RawOptions<ChooserOptions>,
+    ) -> BoxFuture<'a, Result<Choice, PortalError>> {
+        Box::pin(AppChooserImpl::choose_application(
+            self,
+            token,
+            app_id,
+            parent_window,
+            choices,
+            options,
+        ))
+    }
+
+    fn update_choices<'a>(
+        &'a self,
+        request: InterfaceRef<Request>,
+        choices: Vec<AppID>,
+    ) -> BoxFuture<'a, Result<(), PortalError>> {
+        Box::pin(AppChooserImpl::update_choices(self, request, choices))
+    }
 }
 
 pub(crate) struct AppChooserInterface {
-    imp: Arc<dyn AppChooserImpl>,
+    imp: Arc<dyn DynAppChooserImpl>,
     cnx: zbus::Connection,
+    version: u32,
 }
 
 impl AppChooserInterface {
-    pub fn new(imp: Arc<dyn AppChooserImpl>, cnx: zbus::Connection) -> Self {
-        Self { imp, cnx }
+    pub fn new(imp: Arc<dyn DynAppChooserImpl>, cnx: zbus::Connection) -> Self {
+        Self {
+            imp,
+            cnx,
+            version: 2,
+        }
+    }
+}
+
+impl PortalInterface for AppChooserInterface {
+    fn set_version(&mut self, version: u32) {
+        self.version = version;
     }
 }
 
 #[zbus::interface(name = "org.freedesktop.impl.portal.AppChooser")]
 impl AppChooserInterface {
-    #[zbus(property(emits_changed_signal = "const"), name = "version")]
+    #[zbus(property, name = "version")]
     fn version(&self) -> u32 {
-        2
+        self.version
     }
 
     #[zbus(out_args("response", "results"))]
@@ -118,7 +175,7 @@ impl AppChooserInterface {
         app_id: MaybeAppID,
         parent_window: MaybeWindowIdentifier,
         choices: Vec<AppID>,
-        options: ChooserOptions,
+        options: RawOptions<ChooserOptions>,
     ) -> Result<Response<Choice>, PortalError> {
         let imp = Arc::clone(&self.imp);
 
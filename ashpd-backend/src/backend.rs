@@ -0,0 +1,444 @@
+use std::{borrow::Cow, collections::HashMap, sync::Arc};
+
+use zbus::zvariant::Value;
+
+use crate::{
+    access::{AccessImpl, AccessInterface, DynAccessImpl},
+    account::{AccountImpl, AccountInterface, DynAccountImpl},
+    app_chooser::{AppChooserImpl, AppChooserInterface, DynAppChooserImpl},
+    background::{BackgroundEmitter, BackgroundImpl, BackgroundInterface, DynBackgroundImpl},
+    email::{DynEmailImpl, EmailImpl, EmailInterface},
+    file_chooser::{DynFileChooserImpl, FileChooserImpl, FileChooserInterface},
+    lockdown::{DynLockdownImpl, LockdownImpl, LockdownInterface},
+    notification::{
+        DynNotificationImpl, NotificationEmitter, NotificationImpl, NotificationInterface,
+    },
+    permission_store::{DynPermissionStoreImpl, PermissionStoreImpl, PermissionStoreInterface},
+    print::{DynPrintImpl, PrintImpl, PrintInterface},
+    screenshot::{DynScreenshotImpl, ScreenshotImpl, ScreenshotInterface},
+    secret::{DynSecretImpl, SecretImpl, SecretInterface},
+    settings::{DynSettingsImpl, SettingsEmitter, SettingsImpl, SettingsInterface},
+    wallpaper::{DynWallpaperImpl, WallpaperImpl, WallpaperInterface},
+    Result, DESKTOP_PATH,
+};
+
+/// A portal interface served by a [`Backend`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Portal {
+    Access,
+    Account,
+    AppChooser,
+    Background,
+    Email,
+    FileChooser,
+    Lockdown,
+    Notification,
+    PermissionStore,
+    Print,
+    Screenshot,
+    Secret,
+    Settings,
+    Wallpaper,
+}
+
+impl Portal {
+    /// The D-Bus name of the interface, e.g.
+    /// `org.freedesktop.impl.portal.Screenshot`.
+    pub fn interface(&self) -> &'static str {
+        match self {
+            Self::Access => "org.freedesktop.impl.portal.Access",
+            Self::Account => "org.freedesktop.impl.portal.Account",
+            Self::AppChooser => "org.freedesktop.impl.portal.AppChooser",
+            Self::Background => "org.freedesktop.impl.portal.Background",
+            Self::Email => "org.freedesktop.impl.portal.Email",
+            Self::FileChooser => "org.freedesktop.impl.portal.FileChooser",
+            Self::Lockdown => "org.freedesktop.impl.portal.Lockdown",
+            Self::Notification => "org.freedesktop.impl.portal.Notification",
+            Self::PermissionStore => "org.freedesktop.impl.portal.PermissionStore",
+            Self::Print => "org.freedesktop.impl.portal.Print",
+            Self::Screenshot => "org.freedesktop.impl.portal.Screenshot",
+            Self::Secret => "org.freedesktop.impl.portal.Secret",
+            Self::Settings => "org.freedesktop.impl.portal.Settings",
+            Self::Wallpaper => "org.freedesktop.impl.portal.Wallpaper",
+        }
+    }
+}
+
+/// The D-Bus object of a portal served by a [`Backend`].
+pub(crate) trait PortalInterface: zbus::object_server::Interface {
+    /// Changes the version of the portal, see [`Backend::set_version`].
+    fn set_version(&mut self, version: u32);
+}
+
+/// The portals served by a backend, once [built](crate::Builder::build).
+///
+/// Portals can be added, replaced or removed while the backend runs, for
+/// example to serve the Screenshot portal only once the compositor supports
+/// it, and the version they advertise can be changed.
+///
+/// ```rust,no_run
+/// use ashpd_backend::{screenshot::ScreenshotImpl, Builder, Portal};
+///
+/// async fn run(screenshot: impl ScreenshotImpl + 'static) -> ashpd_backend::Result<()> {
+///     let backend = Builder::new("org.freedesktop.impl.portal.desktop.example")?
+///         .build()
+///         .await?;
+///     // The compositor gained the screenshot capability.
+///     backend.screenshot(screenshot).await?;
+///     // And lost it.
+///     backend.remove(Portal::Screenshot).await?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Backend {
+    cnx: zbus::Connection,
+}
+
+impl Backend {
+    pub(crate) fn new(cnx: zbus::Connection) -> Self {
+        Self { cnx }
+    }
+
+    /// The connection the portals are served on.
+    pub fn connection(&self) -> &zbus::Connection {
+        &self.cnx
+    }
+
+    /// Stops serving `portal`.
+    ///
+    /// Returns whether it was served.
+    pub async fn remove(&self, portal: Portal) -> Result<bool> {
+        let removed = match portal {
+            Portal::Access => self.remove_interface::<AccessInterface>().await?,
+            Portal::Account => self.remove_interface::<AccountInterface>().await?,
+            Portal::AppChooser => self.remove_interface::<AppChooserInterface>().await?,
+            Portal::Background => self.remove_interface::<BackgroundInterface>().await?,
+            Portal::Email => self.remove_interface::<EmailInterface>().await?,
+            Portal::FileChooser => self.remove_interface::<FileChooserInterface>().await?,
+            Portal::Lockdown => self.remove_interface::<LockdownInterface>().await?,
+            Portal::Notification => self.remove_interface::<NotificationInterface>().await?,
+            Portal::PermissionStore => self.remove_interface::<PermissionStoreInterface>().await?,
+            Portal::Print => self.remove_interface::<PrintInterface>().await?,
+            Portal::Screenshot => self.remove_interface::<ScreenshotInterface>().await?,
+            Portal::Secret => self.remove_interface::<SecretInterface>().await?,
+            Portal::Settings => self.remove_interface::<SettingsInterface>().await?,
+            Portal::Wallpaper => self.remove_interface::<WallpaperInterface>().await?,
+        };
+        #[cfg(feature = "tracing")]
+        if removed {
+            tracing::debug!("Stopped serving interface `{}`", portal.interface());
+        }
+        Ok(removed)
+    }
+
+    async fn remove_interface<I: zbus::object_server::Interface>(&self) -> Result<bool> {
+        match self.cnx.object_server().remove::<I, _>(DESKTOP_PATH).await {
+            Ok(_) => Ok(true),
+            Err(zbus::Error::InterfaceNotFound) => Ok(false),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Changes the version advertised by `portal`, notifying the frontend with
+    /// the `PropertiesChanged` signal, for example once the methods added by
+    /// a newer version become available.
+    ///
+    /// Returns whether `portal` is served. Replacing its implementation
+    /// restores the default version.
+    pub async fn set_version(&self, portal: Portal, version: u32) -> Result<bool> {
+        match portal {
+            Portal::Access => self.update_version::<AccessInterface>(version).await,
+            Portal::Account => self.update_version::<AccountInterface>(version).await,
+            Portal::AppChooser => self.update_version::<AppChooserInterface>(version).await,
+            Portal::Background => self.update_version::<BackgroundInterface>(version).await,
+            Portal::Email => self.update_version::<EmailInterface>(version).await,
+            Portal::FileChooser => self.update_version::<FileChooserInterface>(version).await,
+            Portal::Lockdown => self.update_version::<LockdownInterface>(version).await,
+            Portal::Notification => self.update_version::<NotificationInterface>(version).await,
+            Portal::PermissionStore => {
+                self.update_version::<PermissionStoreInterface>(version)
+                    .await
+            }
+            Portal::Print => self.update_version::<PrintInterface>(version).await,
+            Portal::Screenshot => self.update_version::<ScreenshotInterface>(version).await,
+            Portal::Secret => self.update_version::<SecretInterface>(version).await,
+            Portal::Settings => self.update_version::<SettingsInterface>(version).await,
+            Portal::Wallpaper => self.update_version::<WallpaperInterface>(version).await,
+        }
+    }
+
+    async fn update_version<I: PortalInterface>(&self, version: u32) -> Result<bool> {
+        let iface = match self
+            .cnx
+            .object_server()
+            .interface::<_, I>(DESKTOP_PATH)
+            .await
+        {
+            Ok(iface) => iface,
+            Err(zbus::Error::InterfaceNotFound) => return Ok(false),
+            Err(err) => return Err(err.into()),
+        };
+        iface.get_mut().await.set_version(version);
+        #[cfg(feature = "tracing")]
+        tracing::debug!("Interface `{}` is now at version {version}", I::name());
+        zbus::fdo::Properties::properties_changed(
+            iface.signal_emitter(),
+            I::name(),
+            HashMap::from([("version", Value::from(version))]),
+            Cow::Borrowed(&[]),
+        )
+        .await?;
+        Ok(true)
+    }
+
+    /// Serves `portal`, replacing the previous implementation of its
+    /// interface if any.
+    async fn serve<I: zbus::object_server::Interface>(
+        &self,
+        portal: Portal,
+        iface: I,
+    ) -> Result<()> {
+        self.remove(portal).await?;
+        #[cfg(feature = "tracing")]
+        tracing::debug!("Serving interface `{}`", portal.interface());
+        self.cnx.object_server().at(DESKTOP_PATH, iface).await?;
+        Ok(())
+    }
+
+    /// Serves the Access portal with `imp`.
+    pub async fn access(&self, imp: impl AccessImpl + 'static) -> Result<()> {
+        self.serve_access(Arc::new(imp)).await
+    }
+
+    /// Serves the Account portal with `imp`.
+    pub async fn account(&self, imp: impl AccountImpl + 'static) -> Result<()> {
+        self.serve_account(Arc::new(imp)).await
+    }
+
+    /// Serves the AppChooser portal with `imp`.
+    pub async fn app_chooser(&self, imp: impl AppChooserImpl + 'static) -> Result<()> {
+        self.serve_app_chooser(Arc::new(imp)).await
+    }
+
+    /// Serves the Background portal with `imp`.
+    pub async fn background(&self, imp: impl BackgroundImpl + 'static) -> Result<()> {
+        self.serve_background(Box::new(imp)).await
+    }
+
+    /// Serves the Email portal with `imp`.
+    pub async fn email(&self, imp: impl EmailImpl + 'static) -> Result<()> {
+        self.serve_email(Arc::new(imp)).await
+    }
+
+    /// Serves the FileChooser portal with `imp`.
+    pub async fn file_chooser(&self, imp: impl FileChooserImpl + 'static) -> Result<()> {
+        self.serve_file_chooser(Arc::new(imp)).await
+    }
+
+    /// Serves the Lockdown portal with `imp`.
+    pub async fn lockdown(&self, imp: impl LockdownImpl + 'static) -> Result<()> {
+        self.serve_lockdown(Arc::new(imp)).await
+    }
+
+    /// Serves the Notification portal with `imp`.
+    pub async fn notification(&self, imp: impl NotificationImpl + 'static) -> Result<()> {
+        self.serve_notification(Box::new(imp)).await
+    }
+
+    /// Serves the PermissionStore portal with `imp`.
+    pub async fn permission_store(&self, imp: impl PermissionStoreImpl + 'static) -> Result<()> {
+        self.serve_permission_store(Arc::new(imp)).await
+    }
+
+    /// Serves the Print portal with `imp`.
+    pub async fn print(&self, imp: impl PrintImpl + 'static) -> Result<()> {
+        self.serve_print(Arc::new(imp)).await
+    }
+
+    /// Serves the Screenshot portal with `imp`.
+    pub async fn screenshot(&self, imp: impl ScreenshotImpl + 'static) -> Result<()> {
+        self.serve_screenshot(Arc::new(imp)).await
+    }
+
+    /// Serves the Secret portal with `imp`.
+    pub async fn secret(&self, imp: impl SecretImpl + 'static) -> Result<()> {
+        self.serve_secret(Arc::new(imp)).await
+    }
+
+    /// Serves the Settings portal with `imp`.
+    pub async fn settings(&self, imp: impl SettingsImpl + 'static) -> Result<()> {
+        self.serve_settings(Box::new(imp)).await
+    }
+
+    /// Serves the Wallpaper portal with `imp`.
+    pub async fn wallpaper(&self, imp: impl WallpaperImpl + 'static) -> Result<()> {
+        self.serve_wallpaper(Arc::new(imp)).await
+    }
+
+    pub(crate) async fn serve_access(&self, imp: Arc<dyn DynAccessImpl>) -> Result<()> {
+        let iface = AccessInterface::new(imp, self.cnx.clone());
+        self.serve(Portal::Access, iface).await
+    }
+
+    pub(crate) async fn serve_account(&self, imp: Arc<dyn DynAccountImpl>) -> Result<()> {
+        let iface = AccountInterface::new(imp, self.cnx.clone());
+        self.serve(Portal::Account, iface).await
+    }
+
+    pub(crate) async fn serve_app_chooser(&self, imp: Arc<dyn DynAppChooserImpl>) -> Result<()> {
+        let iface = AppChooserInterface::new(imp, self.cnx.clone());
+        self.serve(Portal::AppChooser, iface).await
+    }
+
+    pub(crate) async fn serve_background(&self, mut imp: Box<dyn DynBackgroundImpl>) -> Result<()> {
+        imp.set_signal_emitter(Arc::new(BackgroundEmitter::new(self.cnx.clone())));
+        let iface = BackgroundInterface::new(Arc::from(imp), self.cnx.clone());
+        self.serve(Portal::Background, iface).await
+    }
+
+    pub(crate) async fn serve_email(&self, imp: Arc<dyn DynEmailImpl>) -> Result<()> {
+        let iface = EmailInterface::new(imp, self.cnx.clone());
+        self.serve(Portal::Email, iface).await
+    }
+
+    pub(crate) async fn serve_file_chooser(&self, imp: Arc<dyn DynFileChooserImpl>) -> Result<()> {
+        let iface = FileChooserInterface::new(imp, self.cnx.clone());
+        self.serve(Portal::FileChooser, iface).await
+    }
+
+    pub(crate) async fn serve_lockdown(&self, imp: Arc<dyn DynLockdownImpl>) -> Result<()> {
+        let iface = LockdownInterface::new(imp, self.cnx.clone());
+        self.serve(Portal::Lockdown, iface).await
+    }
+
+    pub(crate) async fn serve_notification(
+        &self,
+        mut imp: Box<dyn DynNotificationImpl>,
+    ) -> Result<()> {
+        imp.set_signal_emitter(Arc::new(NotificationEmitter::new(self.cnx.clone())));
+        let iface = NotificationInterface::new(Arc::from(imp));
+        self.serve(Portal::Notification, iface).await
+    }
+
+    pub(crate) async fn serve_permission_store(
+        &self,
+        imp: Arc<dyn DynPermissionStoreImpl>,
+    ) -> Result<()> {
+        let iface = PermissionStoreInterface::new(imp, self.cnx.clone());
+        self.serve(Portal::PermissionStore, iface).await
+    }
+
+    pub(crate) async fn serve_print(&self, imp: Arc<dyn DynPrintImpl>) -> Result<()> {
+        let iface = PrintInterface::new(imp, self.cnx.clone());
+        self.serve(Portal::Print, iface).await
+    }
+
+    pub(crate) async fn serve_screenshot(&self, imp: Arc<dyn DynScreenshotImpl>) -> Result<()> {
+        let iface = ScreenshotInterface::new(imp, self.cnx.clone());
+        self.serve(Portal::Screenshot, iface).await
+    }
+
+    pub(crate) async fn serve_secret(&self, imp: Arc<dyn DynSecretImpl>) -> Result<()> {
+        let iface = SecretInterface::new(imp, self.cnx.clone());
+        self.serve(Portal::Secret, iface).await
+    }
+
+    pub(crate) async fn serve_settings(&self, mut imp: Box<dyn DynSettingsImpl>) -> Result<()> {
+        imp.set_signal_emitter(Arc::new(SettingsEmitter::new(self.cnx.clone())));
+        let iface = SettingsInterface::new(Arc::from(imp));
+        self.serve(Portal::Settings, iface).await
+    }
+
+    pub(crate) async fn serve_wallpaper(&self, imp: Arc<dyn DynWallpaperImpl>) -> Result<()> {
+        let iface = WallpaperInterface::new(imp, self.cnx.clone());
+        self.serve(Portal::Wallpaper, iface).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use ashpd::{
+        desktop::{account::UserInformation, HandleToken},
+        AppID, PortalError, WindowIdentifierType,
+    };
+    use futures_util::StreamExt;
+    use zbus::zvariant::OwnedValue;
+
+    use super::{Backend, Portal};
+    use crate::{
+        account::{AccountImpl, UserInformationOptions},
+        request::RequestImpl,
+        test::{block_on, peer_connections},
+        RawOptions, Result, DESKTOP_PATH,
+    };
+
+    const INTERFACE: &str = "org.freedesktop.impl.portal.Account";
+
+    struct Account;
+
+    impl RequestImpl for Account {
+        async fn close(&self, _token: HandleToken) {}
+    }
+
+    impl AccountImpl for Account {
+        async fn get_user_information(
+            &self,
+            _token: HandleToken,
+            _app_id: Option<AppID>,
+            _window_identifier: Option<WindowIdentifierType>,
+            _options: RawOptions<UserInformationOptions>,
+        ) -> Result<UserInformation> {
+            Err(PortalError::NotAllowed("No user information".to_owned()))
+        }
+    }
+
+    async fn version(cnx: &zbus::Connection) -> u32 {
+        let reply = cnx
+            .call_method(
+                None::<&str>,
+                DESKTOP_PATH,
+                Some("org.freedesktop.DBus.Properties"),
+                "Get",
+                &(INTERFACE, "version"),
+            )
+            .await
+            .unwrap();
+        let value = reply.body().deserialize::<OwnedValue>().unwrap();
+        value.try_into().unwrap()
+    }
+
+    #[test]
+    fn set_version() {
+        block_on(async {
+            let (server, client) = peer_connections().await;
+            let backend = Backend::new(server);
+            assert!(!backend.set_version(Portal::Account, 2).await.unwrap());
+
+            backend.account(Account).await.unwrap();
+            assert_eq!(version(&client).await, 1);
+
+            let mut messages = zbus::MessageStream::from(&client);
+            assert!(backend.set_version(Portal::Account, 2).await.unwrap());
+            let signal = messages.next().await.unwrap().unwrap();
+            assert_eq!(signal.header().member().unwrap(), "PropertiesChanged");
+            let (interface, changed, _) = signal
+                .body()
+                .deserialize::<(String, HashMap<String, OwnedValue>, Vec<String>)>()
+                .unwrap();
+            assert_eq!(interface, INTERFACE);
+            assert_eq!(changed["version"], OwnedValue::from(2u32));
+            assert_eq!(version(&client).await, 2);
+
+            // Replacing the implementation restores the default version.
+            backend.account(Account).await.unwrap();
+            assert_eq!(version(&client).await, 1);
+
+            assert!(backend.remove(Portal::Account).await.unwrap());
+            assert!(!backend.set_version(Portal::Account, 2).await.unwrap());
+        });
+    }
+}
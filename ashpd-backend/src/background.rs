@@ -1,16 +1,22 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, future::Future, sync::Arc};
 
-use async_trait::async_trait;
-use enumflags2::{bitflags, BitFlags};
-use serde_repr::{Deserialize_repr, Serialize_repr};
-
-use crate::{
-    backend::request::{Request, RequestImpl},
+use ashpd::{
     desktop::{HandleToken, Response},
     zbus::object_server::SignalEmitter,
     zvariant::{OwnedObjectPath, SerializeDict, Type},
     AppID, PortalError,
 };
+use enumflags2::{bitflags, BitFlags};
+use futures_util::future::BoxFuture;
+use serde_repr::{Deserialize_repr, Serialize_repr};
+
+use crate::{
+    backend::PortalInterface,
+    request::{DynRequestImpl, Request, RequestImpl},
+};
+
+mod monitor;
+pub use monitor::AppStateMonitor;
 
 #[derive(Serialize_repr, Deserialize_repr, Copy, Clone, PartialEq, Eq, Debug, Type)]
 #[repr(u32)]
@@ -47,65 +53,143 @@ pub enum AutoStartFlags {
     DBusActivation = 1,
 }
 
-#[async_trait]
 pub trait BackgroundSignalEmitter: Send + Sync {
-    async fn emit_changed(&self) -> zbus::Result<()>;
+    fn emit_changed<'a>(&'a self) -> BoxFuture<'a, zbus::Result<()>>;
 }
 
-#[async_trait]
 pub trait BackgroundImpl: RequestImpl {
-    async fn get_app_state(&self) -> Result<HashMap<AppID, AppState>, PortalError>;
+    fn get_app_state(
+        &self,
+    ) -> impl Future<Output = Result<HashMap<AppID, AppState>, PortalError>> + Send;
 
-    async fn notify_background(
+    fn notify_background(
         &self,
         token: HandleToken,
         app_id: AppID,
         name: &str,
-    ) -> Result<Background, PortalError>;
+    ) -> impl Future<Output = Result<Background, PortalError>> + Send;
 
-    async fn enable_autostart(
+    fn enable_autostart(
         &self,
         app_id: AppID,
         enable: bool,
         commandline: Vec<String>,
         flags: BitFlags<AutoStartFlags>,
-    ) -> Result<bool, PortalError>;
+    ) -> impl Future<Output = Result<bool, PortalError>> + Send;
 
     // Set the signal emitter, allowing to notify of changes.
     fn set_signal_emitter(&mut self, signal_emitter: Arc<dyn BackgroundSignalEmitter>);
 }
 
+/// Object safe counterpart of [`BackgroundImpl`], used to store the implementation.
+pub(crate) trait DynBackgroundImpl: DynRequestImpl {
+    fn get_app_state<'a>(&'a self) -> BoxFuture<'a, Result<HashMap<AppID, AppState>, PortalError>>;
+
+    fn notify_background<'a>(
+        &'a self,
+        token: HandleToken,
+        app_id: AppID,
+        name: &'a str,
+    ) -> BoxFuture<'a, Result<Background, PortalError>>;
+
+    fn enable_autostart<'a>(
+        &'a self,
+        app_id: AppID,
+        enable: bool,
+        commandline: Vec<String>,
+        flags: BitFlags<AutoStartFlags>,
+    ) -> BoxFuture<'a, Result<bool, PortalError>>;
+
+    fn set_signal_emitter(&mut self, signal_emitter: Arc<dyn BackgroundSignalEmitter>);
+}
+
+impl<T: BackgroundImpl> DynBackgroundImpl for T {
+    fn get_app_state<'a>(&'a self) -> BoxFuture<'a, Result<HashMap<AppID, AppState>, PortalError>> {
+        Box::pin(BackgroundImpl::get_app_state(self))
+    }
+
+    fn notify_background<'a>(
+        &'a self,
+        token: HandleToken,
+        app_id: AppID,
+        name: &'a str,
+    ) -> BoxFuture<'a, Result<Background, PortalError>> {
+        Box::pin(BackgroundImpl::notify_background(self, token, app_id, name))
+    }
+
+    fn enable_autostart<'a>(
+        &'a self,
+        app_id: AppID,
+        enable: bool,
+        commandline: Vec<String>,
+        flags: BitFlags<AutoStartFlags>,
+    ) -> BoxFuture<'a, Result<bool, PortalError>> {
+        Box::pin(BackgroundImpl::enable_autostart(
+            self,
+            app_id,
+            enable,
+            commandline,
+            flags,
+        ))
+    }
+
+    fn set_signal_emitter(&mut self, signal_emitter: Arc<dyn BackgroundSignalEmitter>) {
+        BackgroundImpl::set_signal_emitter(self, signal_emitter)
+    }
+}
+
 pub(crate) struct BackgroundInterface {
-    imp: Arc<dyn BackgroundImpl>,
+    imp: Arc<dyn DynBackgroundImpl>,
     cnx: zbus::Connection,
+    version: u32,
 }
 
 impl BackgroundInterface {
-    pub fn new(imp: Arc<dyn BackgroundImpl>, cnx: zbus::Connection) -> Self {
-        Self { imp, cnx }
+    pub fn new(imp: Arc<dyn DynBackgroundImpl>, cnx: zbus::Connection) -> Self {
+        Self {
+            imp,
+            cnx,
+            version: 2,
+        }
+    }
+}
+
+impl PortalInterface for BackgroundInterface {
+    fn set_version(&mut self, version: u32) {
+        self.version = version;
+    }
+}
+
+/// Emits the signals of the [`BackgroundInterface`] served on a connection.
+pub(crate) struct BackgroundEmitter {
+    cnx: zbus::Connection,
+}
+
+impl BackgroundEmitter {
+    pub fn new(cnx: zbus::Connection) -> Self {
+        Self { cnx }
     }
 
     pub async fn changed(&self) -> zbus::Result<()> {
         let object_server = self.cnx.object_server();
         let iface_ref = object_server
-            .interface::<_, Self>(crate::proxy::DESKTOP_PATH)
+            .interface::<_, BackgroundInterface>(crate::DESKTOP_PATH)
             .await?;
-        Self::running_applications_changed(iface_ref.signal_emitter()).await
+        BackgroundInterface::running_applications_changed(iface_ref.signal_emitter()).await
     }
 }
 
-#[async_trait]
-impl BackgroundSignalEmitter for BackgroundInterface {
-    async fn emit_changed(&self) -> zbus::Result<()> {
-        self.changed().await
+impl BackgroundSignalEmitter for BackgroundEmitter {
+    fn emit_changed<'a>(&'a self) -> BoxFuture<'a, zbus::Result<()>> {
+        Box::pin(self.changed())
     }
 }
 
 #[zbus::interface(name = "org.freedesktop.impl.portal.Background")]
 impl BackgroundInterface {
-    #[zbus(property(emits_changed_signal = "const"), name = "version")]
+    #[zbus(property, name = "version")]
     fn version(&self) -> u32 {
-        2
+        self.version
     }
 
     #[zbus(out_args("apps"))]
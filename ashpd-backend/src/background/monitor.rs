@@ -0,0 +1,395 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use ashpd::{flatpak::InstanceInfo, AppID};
+use futures_util::{future, stream, StreamExt};
+use serde::Deserialize;
+
+use super::{AppState, BackgroundSignalEmitter};
+
+#[derive(Default)]
+struct Inner {
+    // The session bus connection, shared by the lookups so that they don't
+    // change the names they monitor.
+    connection: tokio::sync::OnceCell<zbus::Connection>,
+    apps: Mutex<HashMap<AppID, AppState>>,
+    // The states set by the implementation, forgotten once the app stops.
+    states: Mutex<HashMap<AppID, AppState>>,
+    signal_emitter: Mutex<Option<Arc<dyn BackgroundSignalEmitter>>>,
+}
+
+/// Tracks the running applications for a
+/// [`BackgroundImpl`](super::BackgroundImpl).
+///
+/// The applications are considered running while they have a Flatpak
+/// instance, or own a D-Bus name on the session bus matching a desktop file.
+/// They are [`AppState::Running`] unless the implementation sets another
+/// state with [`AppStateMonitor::set_state`], when their windows are focused
+/// or closed for example. `RunningApplicationsChanged` is emitted whenever
+/// the table changes.
+///
+/// ```rust,no_run
+/// use std::{collections::HashMap, sync::Arc, time::Duration};
+///
+/// use ashpd::{desktop::HandleToken, AppID, PortalError};
+/// use ashpd_backend::{
+///     background::{
+///         Activity, AppState, AppStateMonitor, AutoStartFlags, Background, BackgroundImpl,
+///         BackgroundSignalEmitter,
+///     },
+///     request::RequestImpl,
+/// };
+/// use enumflags2::BitFlags;
+///
+/// struct Backend(AppStateMonitor);
+///
+/// impl RequestImpl for Backend {
+///     async fn close(&self, _token: HandleToken) {}
+/// }
+///
+/// impl BackgroundImpl for Backend {
+///     async fn get_app_state(&self) -> Result<HashMap<AppID, AppState>, PortalError> {
+///         Ok(self.0.app_state())
+///     }
+///
+///     async fn notify_background(
+///         &self,
+///         _token: HandleToken,
+///         _app_id: AppID,
+///         _name: &str,
+///     ) -> Result<Background, PortalError> {
+///         Ok(Background::new(Activity::Allow))
+///     }
+///
+///     async fn enable_autostart(
+///         &self,
+///         _app_id: AppID,
+///         _enable: bool,
+///         _commandline: Vec<String>,
+///         _flags: BitFlags<AutoStartFlags>,
+///     ) -> Result<bool, PortalError> {
+///         Ok(false)
+///     }
+///
+///     fn set_signal_emitter(&mut self, signal_emitter: Arc<dyn BackgroundSignalEmitter>) {
+///         self.0.set_signal_emitter(signal_emitter);
+///         self.0.start(Duration::from_secs(5));
+///     }
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct AppStateMonitor(Arc<Inner>);
+
+impl AppStateMonitor {
+    /// Create a new instance of [`AppStateMonitor`], without any application.
+    ///
+    /// A connection to the session bus is opened on the first lookup.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new instance of [`AppStateMonitor`] looking up the D-Bus
+    /// names on `connection`, the one of the backend for example.
+    pub fn with_connection(connection: zbus::Connection) -> Self {
+        let monitor = Self::default();
+        let _ = monitor.0.connection.set(connection);
+        monitor
+    }
+
+    async fn connection(&self) -> zbus::Result<&zbus::Connection> {
+        self.0
+            .connection
+            .get_or_try_init(zbus::Connection::session)
+            .await
+    }
+
+    /// Set the signal emitter, notified when the applications change.
+    pub fn set_signal_emitter(&self, signal_emitter: Arc<dyn BackgroundSignalEmitter>) {
+        self.0
+            .signal_emitter
+            .lock()
+            .unwrap()
+            .replace(signal_emitter);
+    }
+
+    /// The state of the running applications.
+    pub fn app_state(&self) -> HashMap<AppID, AppState> {
+        self.0.apps.lock().unwrap().clone()
+    }
+
+    /// Set the state of a running application.
+    ///
+    /// The state is kept until the application stops running.
+    pub async fn set_state(&self, app_id: AppID, state: AppState) {
+        self.0.states.lock().unwrap().insert(app_id.clone(), state);
+        let changed = match self.0.apps.lock().unwrap().get_mut(&app_id) {
+            Some(current) if *current != state => {
+                *current = state;
+                true
+            }
+            _ => false,
+        };
+        if changed {
+            self.emit_changed().await;
+        }
+    }
+
+    /// Look up the running applications, notifying the signal emitter of the
+    /// changes.
+    pub async fn refresh(&self) {
+        let mut running = match std::env::var_os("XDG_RUNTIME_DIR") {
+            Some(runtime_dir) => {
+                tokio::task::spawn_blocking(move || flatpak_instances(Path::new(&runtime_dir)))
+                    .await
+                    .unwrap_or_default()
+            }
+            None => Vec::new(),
+        };
+        if let Ok(connection) = self.connection().await {
+            if let Ok(names) = dbus_names(connection).await {
+                running.extend(names);
+            }
+        }
+
+        let apps = {
+            let mut states = self.0.states.lock().unwrap();
+            states.retain(|app_id, _| running.contains(app_id));
+            running
+                .into_iter()
+                .map(|app_id| {
+                    let state = states.get(&app_id).copied().unwrap_or(AppState::Running);
+                    (app_id, state)
+                })
+                .collect::<HashMap<_, _>>()
+        };
+        let changed = {
+            let mut current = self.0.apps.lock().unwrap();
+            let changed = *current != apps;
+            *current = apps;
+            changed
+        };
+        if changed {
+            self.emit_changed().await;
+        }
+    }
+
+    /// Keep the applications up to date, refreshing them on the changes of
+    /// the D-Bus names and every `interval` for the Flatpak instances.
+    ///
+    /// Has to be called from a tokio runtime.
+    pub fn start(&self, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let monitor = self.clone();
+        tokio::spawn(async move {
+            let name_changes = match monitor.connection().await {
+                Ok(connection) => match zbus::fdo::DBusProxy::new(connection).await {
+                    Ok(proxy) => proxy.receive_name_owner_changed().await.ok(),
+                    Err(_) => None,
+                },
+                Err(_) => None,
+            };
+            // The unique names of the connections can't match an application.
+            let name_changes = name_changes.map(|name_changes| {
+                name_changes.filter(|signal| {
+                    future::ready(
+                        signal
+                            .args()
+                            .is_ok_and(|args| !args.name().as_str().starts_with(':')),
+                    )
+                })
+            });
+            let ticks = stream::unfold((), move |()| async move {
+                tokio::time::sleep(interval).await;
+                Some(((), ()))
+            });
+            let mut changes = match name_changes {
+                Some(name_changes) => stream::select(name_changes.map(|_| ()), ticks).boxed(),
+                None => ticks.boxed(),
+            };
+
+            monitor.refresh().await;
+            while changes.next().await.is_some() {
+                monitor.refresh().await;
+            }
+        })
+    }
+
+    async fn emit_changed(&self) {
+        let signal_emitter = self.0.signal_emitter.lock().unwrap().clone();
+        if let Some(signal_emitter) = signal_emitter {
+            if let Err(_err) = signal_emitter.emit_changed().await {
+                #[cfg(feature = "tracing")]
+                tracing::warn!("Failed to emit RunningApplicationsChanged: {_err}");
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for AppStateMonitor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AppStateMonitor")
+            .field("apps", &*self.0.apps.lock().unwrap())
+            .finish()
+    }
+}
+
+#[derive(Deserialize)]
+struct BwrapInfo {
+    #[serde(rename = "child-pid")]
+    child_pid: u32,
+}
+
+/// The applications of the running Flatpak instances of `runtime_dir`.
+///
+/// Blocking, the files of the instances are read synchronously.
+fn flatpak_instances(runtime_dir: &Path) -> Vec<AppID> {
+    let Ok(instances) = std::fs::read_dir(runtime_dir.join(".flatpak")) else {
+        return Vec::new();
+    };
+    instances
+        .filter_map(|instance| {
+            let instance = instance.ok()?.path();
+            // The instances directories are kept around after the sandbox exits.
+            let bwrap_info = std::fs::read(instance.join("bwrapinfo.json")).ok()?;
+            let bwrap_info = serde_json::from_slice::<BwrapInfo>(&bwrap_info).ok()?;
+            if !Path::new("/proc")
+                .join(bwrap_info.child_pid.to_string())
+                .exists()
+            {
+                return None;
+            }
+            let info = std::fs::read_to_string(instance.join("info")).ok()?;
+            info.parse::<InstanceInfo>().ok()?.app_id()
+        })
+        .collect()
+}
+
+/// The applications owning a name on the session bus.
+async fn dbus_names(connection: &zbus::Connection) -> zbus::Result<Vec<AppID>> {
+    let proxy = zbus::fdo::DBusProxy::new(connection).await?;
+    let names = proxy
+        .list_names()
+        .await?
+        .into_iter()
+        .filter_map(|name| name.as_str().parse::<AppID>().ok())
+        .collect::<Vec<_>>();
+    let apps = tokio::task::spawn_blocking(move || installed_apps(names, &data_dirs()))
+        .await
+        .unwrap_or_default();
+    Ok(apps)
+}
+
+/// The applications of `names` with a desktop file in one of `data_dirs`.
+///
+/// Blocking, the desktop files are looked up synchronously.
+fn installed_apps(names: Vec<AppID>, data_dirs: &[PathBuf]) -> Vec<AppID> {
+    names
+        .into_iter()
+        .filter(|app_id| {
+            data_dirs.iter().any(|dir| {
+                dir.join("applications")
+                    .join(format!("{app_id}.desktop"))
+                    .exists()
+            })
+        })
+        .collect()
+}
+
+fn data_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    match std::env::var_os("XDG_DATA_HOME") {
+        Some(dir) => dirs.push(PathBuf::from(dir)),
+        None => {
+            if let Some(home) = std::env::var_os("HOME") {
+                dirs.push(Path::new(&home).join(".local/share"));
+            }
+        }
+    }
+    let system_dirs = std::env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|dirs| !dirs.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_owned());
+    dirs.extend(system_dirs.split(':').map(PathBuf::from));
+    dirs
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use ashpd::AppID;
+
+    use super::{flatpak_instances, installed_apps};
+
+    fn write_instance(runtime_dir: &Path, instance: &str, app_id: &str, child_pid: u32) {
+        let dir = runtime_dir.join(".flatpak").join(instance);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("info"),
+            format!("[Application]\nname={app_id}\nruntime=runtime/org.gnome.Platform/x86_64/47\n"),
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("bwrapinfo.json"),
+            format!("{{\"child-pid\": {child_pid}}}"),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn running_instances() {
+        let runtime_dir =
+            std::env::temp_dir().join(format!("ashpd-runtime-dir-{}", std::process::id()));
+        assert!(flatpak_instances(&runtime_dir).is_empty());
+
+        // The test process stands in for the sandboxed one.
+        write_instance(&runtime_dir, "1", "org.gnome.Maps", std::process::id());
+        // The directories of the exited instances are kept around.
+        write_instance(&runtime_dir, "2", "org.gnome.Weather", u32::MAX);
+        // As well as the ones being set up, without bwrapinfo.json yet.
+        let starting = runtime_dir.join(".flatpak/3");
+        std::fs::create_dir_all(&starting).unwrap();
+        std::fs::write(
+            starting.join("info"),
+            "[Application]\nname=org.gnome.Clocks\n",
+        )
+        .unwrap();
+        // An instance with an invalid info file.
+        write_instance(&runtime_dir, "4", "not an app id", std::process::id());
+
+        assert_eq!(
+            flatpak_instances(&runtime_dir),
+            vec!["org.gnome.Maps".parse::<AppID>().unwrap()]
+        );
+        std::fs::remove_dir_all(&runtime_dir).unwrap();
+    }
+
+    #[test]
+    fn desktop_files() {
+        let data_dir = std::env::temp_dir().join(format!("ashpd-data-dir-{}", std::process::id()));
+        let applications = data_dir.join("applications");
+        std::fs::create_dir_all(&applications).unwrap();
+        std::fs::write(applications.join("org.gnome.Maps.desktop"), "").unwrap();
+        // Only the desktop files match, not the other files of the directory.
+        std::fs::write(applications.join("org.gnome.Weather.service"), "").unwrap();
+
+        let names = [
+            "org.gnome.Maps",
+            "org.gnome.Weather",
+            "org.freedesktop.Notifications",
+        ]
+        .into_iter()
+        .map(|name| name.parse::<AppID>().unwrap())
+        .collect::<Vec<_>>();
+        let missing = data_dir.join("missing");
+        assert_eq!(
+            installed_apps(names.clone(), &[missing.clone(), data_dir.clone()]),
+            vec!["org.gnome.Maps".parse::<AppID>().unwrap()]
+        );
+        assert!(installed_apps(names, &[missing]).is_empty());
+        std::fs::remove_dir_all(&data_dir).unwrap();
+    }
+}
@@ -0,0 +1,237 @@
+use std::{path::Path, sync::Arc, time::Duration};
+
+use enumflags2::BitFlags;
+use zbus::names::{OwnedWellKnownName, WellKnownName};
+
+use crate::{
+    access::{AccessImpl, DynAccessImpl},
+    account::{AccountImpl, DynAccountImpl},
+    app_chooser::{AppChooserImpl, DynAppChooserImpl},
+    background::{BackgroundImpl, DynBackgroundImpl},
+    email::{DynEmailImpl, EmailImpl},
+    file_chooser::{DynFileChooserImpl, FileChooserImpl},
+    idle,
+    lockdown::{DynLockdownImpl, LockdownImpl},
+    notification::{DynNotificationImpl, NotificationImpl},
+    permission_store::{DynPermissionStoreImpl, PermissionStoreImpl},
+    print::{DynPrintImpl, PrintImpl},
+    screenshot::{DynScreenshotImpl, ScreenshotImpl},
+    secret::{DynSecretImpl, SecretImpl},
+    settings::{DynSettingsImpl, SettingsImpl},
+    wallpaper::{DynWallpaperImpl, WallpaperImpl},
+    Backend, Result,
+};
+
+pub struct Builder {
+    name: OwnedWellKnownName,
+    flags: BitFlags<zbus::fdo::RequestNameFlags>,
+    idle_timeout: Option<Duration>,
+    account_impl: Option<Arc<dyn DynAccountImpl>>,
+    access_impl: Option<Arc<dyn DynAccessImpl>>,
+    app_chooser_impl: Option<Arc<dyn DynAppChooserImpl>>,
+    background_impl: Option<Box<dyn DynBackgroundImpl>>,
+    email_impl: Option<Arc<dyn DynEmailImpl>>,
+    file_chooser_impl: Option<Arc<dyn DynFileChooserImpl>>,
+    lockdown_impl: Option<Arc<dyn DynLockdownImpl>>,
+    notification_impl: Option<Box<dyn DynNotificationImpl>>,
+    permission_store_impl: Option<Arc<dyn DynPermissionStoreImpl>>,
+    print_impl: Option<Arc<dyn DynPrintImpl>>,
+    screenshot_impl: Option<Arc<dyn DynScreenshotImpl>>,
+    secret_impl: Option<Arc<dyn DynSecretImpl>>,
+    settings_impl: Option<Box<dyn DynSettingsImpl>>,
+    wallpaper_impl: Option<Arc<dyn DynWallpaperImpl>>,
+}
+
+impl Builder {
+    pub fn new<'a, W>(well_known_name: W) -> zbus::Result<Self>
+    where
+        W: TryInto<WellKnownName<'a>>,
+        <W as TryInto<WellKnownName<'a>>>::Error: Into<zbus::Error>,
+    {
+        let well_known_name = well_known_name.try_into().map_err(Into::into)?;
+        Ok(Self {
+            name: well_known_name.into(),
+            // same flags as zbus::Connection::request_name
+            flags: zbus::fdo::RequestNameFlags::ReplaceExisting
+                | zbus::fdo::RequestNameFlags::DoNotQueue,
+            idle_timeout: None,
+            account_impl: None,
+            access_impl: None,
+            app_chooser_impl: None,
+            background_impl: None,
+            email_impl: None,
+            file_chooser_impl: None,
+            lockdown_impl: None,
+            notification_impl: None,
+            permission_store_impl: None,
+            print_impl: None,
+            screenshot_impl: None,
+            secret_impl: None,
+            settings_impl: None,
+            wallpaper_impl: None,
+        })
+    }
+
+    pub fn with_flags(mut self, flags: BitFlags<zbus::fdo::RequestNameFlags>) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    /// Sets how long [`Builder::run`] keeps serving the portals without any
+    /// method call, outstanding request or open
+    /// [`Session`](crate::session::Session), for backends started on demand
+    /// with D-Bus activation.
+    pub fn idle_timeout(mut self, timeout: impl Into<Option<Duration>>) -> Self {
+        self.idle_timeout = timeout.into();
+        self
+    }
+
+    /// The D-Bus service file activating the backend by running `exec`, to be
+    /// installed in `$datadir/dbus-1/services/<name>.service`.
+    pub fn service_file(&self, exec: impl AsRef<Path>) -> String {
+        format!(
+            "[D-BUS Service]\nName={}\nExec={}\n",
+            self.name,
+            exec.as_ref().display()
+        )
+    }
+
+    pub fn account(mut self, imp: impl AccountImpl + 'static) -> Self {
+        self.account_impl = Some(Arc::new(imp));
+        self
+    }
+
+    pub fn access(mut self, imp: impl AccessImpl + 'static) -> Self {
+        self.access_impl = Some(Arc::new(imp));
+        self
+    }
+
+    pub fn app_chooser(mut self, imp: impl AppChooserImpl + 'static) -> Self {
+        self.app_chooser_impl = Some(Arc::new(imp));
+        self
+    }
+
+    pub fn background(mut self, imp: impl BackgroundImpl + 'static) -> Self {
+        self.background_impl = Some(Box::new(imp));
+        self
+    }
+
+    pub fn email(mut self, imp: impl EmailImpl + 'static) -> Self {
+        self.email_impl = Some(Arc::new(imp));
+        self
+    }
+
+    pub fn file_chooser(mut self, imp: impl FileChooserImpl + 'static) -> Self {
+        self.file_chooser_impl = Some(Arc::new(imp));
+        self
+    }
+
+    pub fn lockdown(mut self, imp: impl LockdownImpl + 'static) -> Self {
+        self.lockdown_impl = Some(Arc::new(imp));
+        self
+    }
+
+    pub fn notification(mut self, imp: impl NotificationImpl + 'static) -> Self {
+        self.notification_impl = Some(Box::new(imp));
+        self
+    }
+
+    pub fn permission_store(mut self, imp: impl PermissionStoreImpl + 'static) -> Self {
+        self.permission_store_impl = Some(Arc::new(imp));
+        self
+    }
+
+    pub fn print(mut self, imp: impl PrintImpl + 'static) -> Self {
+        self.print_impl = Some(Arc::new(imp));
+        self
+    }
+
+    pub fn screenshot(mut self, imp: impl ScreenshotImpl + 'static) -> Self {
+        self.screenshot_impl = Some(Arc::new(imp));
+        self
+    }
+
+    pub fn secret(mut self, imp: impl SecretImpl + 'static) -> Self {
+        self.secret_impl = Some(Arc::new(imp));
+        self
+    }
+
+    pub fn settings(mut self, imp: impl SettingsImpl + 'static) -> Self {
+        self.settings_impl = Some(Box::new(imp));
+        self
+    }
+
+    pub fn wallpaper(mut self, imp: impl WallpaperImpl + 'static) -> Self {
+        self.wallpaper_impl = Some(Arc::new(imp));
+        self
+    }
+
+    /// Serves the portals, which can be changed later on with the returned
+    /// [`Backend`].
+    pub async fn build(self) -> Result<Backend> {
+        self.serve().await
+    }
+
+    /// Serves the portals until they are idle for the
+    /// [idle timeout](Builder::idle_timeout), or forever without one.
+    pub async fn run(self) -> Result<()> {
+        let idle_timeout = self.idle_timeout;
+        let backend = self.serve().await?;
+        match idle_timeout {
+            Some(timeout) => idle::wait_idle(backend.connection(), timeout).await,
+            None => std::future::pending().await,
+        }
+        #[cfg(feature = "tracing")]
+        tracing::debug!("Exiting after being idle for {idle_timeout:?}");
+        Ok(())
+    }
+
+    async fn serve(self) -> Result<Backend> {
+        let cnx = zbus::Connection::session().await?;
+        cnx.request_name_with_flags(self.name, self.flags).await?;
+        let backend = Backend::new(cnx);
+        if let Some(imp) = self.access_impl {
+            backend.serve_access(imp).await?;
+        }
+        if let Some(imp) = self.account_impl {
+            backend.serve_account(imp).await?;
+        }
+        if let Some(imp) = self.app_chooser_impl {
+            backend.serve_app_chooser(imp).await?;
+        }
+        if let Some(imp) = self.background_impl {
+            backend.serve_background(imp).await?;
+        }
+        if let Some(imp) = self.email_impl {
+            backend.serve_email(imp).await?;
+        }
+        if let Some(imp) = self.file_chooser_impl {
+            backend.serve_file_chooser(imp).await?;
+        }
+        if let Some(imp) = self.lockdown_impl {
+            backend.serve_lockdown(imp).await?;
+        }
+        if let Some(imp) = self.notification_impl {
+            backend.serve_notification(imp).await?;
+        }
+        if let Some(imp) = self.permission_store_impl {
+            backend.serve_permission_store(imp).await?;
+        }
+        if let Some(imp) = self.print_impl {
+            backend.serve_print(imp).await?;
+        }
+        if let Some(imp) = self.screenshot_impl {
+            backend.serve_screenshot(imp).await?;
+        }
+        if let Some(imp) = self.secret_impl {
+            backend.serve_secret(imp).await?;
+        }
+        if let Some(imp) = self.settings_impl {
+            backend.serve_settings(imp).await?;
+        }
+        if let Some(imp) = self.wallpaper_impl {
+            backend.serve_wallpaper(imp).await?;
+        }
+        Ok(backend)
+    }
+}
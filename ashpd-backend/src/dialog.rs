@@ -0,0 +1,618 @@
+//! Serve the dialog based portals from a single toolkit integration.
+//!
+//! A [`DialogDelegate`] shows the dialogs with a toolkit, GTK, Qt or Slint for
+//! example, and [`Dialogs`] implements the Access, Account, AppChooser,
+//! FileChooser and Wallpaper portals on top of it.
+//!
+//! ```rust,no_run
+//! use ashpd::{desktop::HandleToken, AppID, WindowIdentifierType};
+//! use ashpd_backend::dialog::{Dialog, DialogDelegate, DialogResponse, DialogUpdate, Dialogs};
+//!
+//! struct Toolkit;
+//!
+//! impl DialogDelegate for Toolkit {
+//!     async fn show(
+//!         &self,
+//!         _token: HandleToken,
+//!         _app_id: Option<AppID>,
+//!         _window_identifier: Option<WindowIdentifierType>,
+//!         _dialog: Dialog,
+//!     ) -> ashpd_backend::Result<()> {
+//!         // Build and present the dialog.
+//!         Ok(())
+//!     }
+//!
+//!     async fn update(
+//!         &self,
+//!         _token: HandleToken,
+//!         _update: DialogUpdate,
+//!     ) -> ashpd_backend::Result<()> {
+//!         Ok(())
+//!     }
+//!
+//!     async fn respond(&self, _token: HandleToken) -> ashpd_backend::Result<DialogResponse> {
+//!         // Wait for the user to answer the dialog.
+//!         Ok(DialogResponse::Wallpaper)
+//!     }
+//!
+//!     async fn cancel(&self, _token: HandleToken) {
+//!         // Close the dialog.
+//!     }
+//! }
+//!
+//! async fn run() -> ashpd_backend::Result<()> {
+//!     let dialogs = Dialogs::new(Toolkit);
+//!     ashpd_backend::Builder::new("org.freedesktop.impl.portal.desktop.example")?
+//!         .access(dialogs.clone())
+//!         .account(dialogs.clone())
+//!         .app_chooser(dialogs.clone())
+//!         .file_chooser(dialogs.clone())
+//!         .wallpaper(dialogs)
+//!         .build()
+//!         .await?;
+//!     Ok(())
+//! }
+//! ```
+
+use std::{future::Future, sync::Arc};
+
+use ashpd::{
+    desktop::{account::UserInformation, HandleToken},
+    zbus::object_server::InterfaceRef,
+    zvariant::OwnedObjectPath,
+    AppID, PortalError, WindowIdentifierType,
+};
+
+use crate::{
+    access::{AccessImpl, AccessOptions, AccessResponse},
+    account::{AccountImpl, UserInformationOptions},
+    app_chooser::{AppChooserImpl, Choice, ChooserOptions},
+    file_chooser::{
+        FileChooserImpl, OpenFileOptions, SaveFileOptions, SaveFilesOptions, SelectedFiles,
+    },
+    request::{Request, RequestImpl},
+    wallpaper::{WallpaperImpl, WallpaperOptions},
+    RawOptions, Result,
+};
+
+/// The content of a dialog shown by a [`DialogDelegate`].
+#[derive(Debug)]
+pub enum Dialog {
+    /// Ask the user to grant an access, for the Access portal.
+    Access {
+        title: String,
+        subtitle: String,
+        body: String,
+        options: RawOptions<AccessOptions>,
+    },
+    /// Ask the user to share their information, for the Account portal.
+    Account {
+        options: RawOptions<UserInformationOptions>,
+    },
+    /// Let the user pick an application, for the AppChooser portal.
+    AppChooser {
+        choices: Vec<AppID>,
+        options: RawOptions<ChooserOptions>,
+    },
+    /// Let the user pick files to open, for the FileChooser portal.
+    OpenFile {
+        title: String,
+        options: RawOptions<OpenFileOptions>,
+    },
+    /// Let the user pick a file to save to, for the FileChooser portal.
+    SaveFile {
+        title: String,
+        options: RawOptions<SaveFileOptions>,
+    },
+    /// Let the user pick a folder to save files to, for the FileChooser
+    /// portal.
+    SaveFiles {
+        title: String,
+        options: RawOptions<SaveFilesOptions>,
+    },
+    /// Ask the user to confirm and set a wallpaper, for the Wallpaper portal.
+    ///
+    /// The delegate sets the wallpaper itself before responding.
+    Wallpaper {
+        uri: url::Url,
+        options: RawOptions<WallpaperOptions>,
+    },
+}
+
+/// A change of a dialog already shown by a [`DialogDelegate`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum DialogUpdate {
+    /// The applications offered by an [`Dialog::AppChooser`] changed.
+    Choices(Vec<AppID>),
+}
+
+/// The answer of the user to a [`Dialog`].
+#[derive(Debug)]
+pub enum DialogResponse {
+    /// The answer to a [`Dialog::Access`].
+    Access(AccessResponse),
+    /// The answer to a [`Dialog::Account`].
+    Account(UserInformation),
+    /// The answer to a [`Dialog::AppChooser`].
+    AppChooser(Choice),
+    /// The answer to a [`Dialog::OpenFile`], [`Dialog::SaveFile`] or
+    /// [`Dialog::SaveFiles`].
+    FileChooser(SelectedFiles),
+    /// The answer to a [`Dialog::Wallpaper`].
+    Wallpaper,
+}
+
+/// The toolkit integration showing the dialogs of the portals served by
+/// [`Dialogs`].
+///
+/// The dialogs are identified by the token of their request.
+pub trait DialogDelegate: Send + Sync {
+    /// Shows `dialog`, as a transient for `window_identifier` if any.
+    fn show(
+        &self,
+        token: HandleToken,
+        app_id: Option<AppID>,
+        window_identifier: Option<WindowIdentifierType>,
+        dialog: Dialog,
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    /// Updates a shown dialog.
+    fn update(
+        &self,
+        token: HandleToken,
+        update: DialogUpdate,
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    /// Waits for the user to answer a shown dialog.
+    ///
+    /// Fails with [`PortalError::Cancelled`] if the user dismissed the
+    /// dialog.
+    fn respond(&self, token: HandleToken) -> impl Future<Output = Result<DialogResponse>> + Send;
+
+    /// Closes a shown dialog, once the application cancelled the request.
+    fn cancel(&self, token: HandleToken) -> impl Future<Output = ()> + Send;
+}
+
+/// Implements the dialog based portals with a [`DialogDelegate`].
+#[derive(Debug)]
+pub struct Dialogs<D>(Arc<D>);
+
+impl<D: DialogDelegate> Dialogs<D> {
+    /// Create a new instance of [`Dialogs`].
+    pub fn new(delegate: D) -> Self {
+        Self(Arc::new(delegate))
+    }
+
+    /// The toolkit integration.
+    pub fn delegate(&self) -> &D {
+        &self.0
+    }
+
+    async fn run(
+        &self,
+        token: HandleToken,
+        app_id: Option<AppID>,
+        window_identifier: Option<WindowIdentifierType>,
+        dialog: Dialog,
+    ) -> Result<DialogResponse> {
+        self.0
+            .show(token.clone(), app_id, window_identifier, dialog)
+            .await?;
+        self.0.respond(token).await
+    }
+}
+
+impl<D> Clone for Dialogs<D> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+fn unexpected(response: DialogResponse) -> PortalError {
+    PortalError::Failed(format!("Unexpected dialog response {response:?}"))
+}
+
+impl<D: DialogDelegate> RequestImpl for Dialogs<D> {
+    async fn close(&self, token: HandleToken) {
+        self.0.cancel(token).await;
+    }
+}
+
+impl<D: DialogDelegate> AccessImpl for Dialogs<D> {
+    async fn access_dialog(
+        &self,
+        token: HandleToken,
+        app_id: Option<AppID>,
+        window_identifier: Option<WindowIdentifierType>,
+        title: String,
+        subtitle: String,
+        body: String,
+        options: RawOptions<AccessOptions>,
+    ) -> Result<AccessResponse> {
+        let dialog = Dialog::Access {
+            title,
+            subtitle,
+            body,
+            options,
+        };
+        match self.run(token, app_id, window_identifier, dialog).await? {
+            DialogResponse::Access(response) => Ok(response),
+            response => Err(unexpected(response)),
+        }
+    }
+}
+
+impl<D: DialogDelegate> AccountImpl for Dialogs<D> {
+    async fn get_user_information(
+        &self,
+        token: HandleToken,
+        app_id: Option<AppID>,
+        window_identifier: Option<WindowIdentifierType>,
+        options: RawOptions<UserInformationOptions>,
+    ) -> Result<UserInformation> {
+        let dialog = Dialog::Account { options };
+        match self.run(token, app_id, window_identifier, dialog).await? {
+            DialogResponse::Account(information) => Ok(information),
+            response => Err(unexpected(response)),
+        }
+    }
+}
+
+impl<D: DialogDelegate> AppChooserImpl for Dialogs<D> {
+    async fn choose_application(
+        &self,
+        token: HandleToken,
+        app_id: Option<AppID>,
+        parent_window: Option<WindowIdentifierType>,
+        choices: Vec<AppID>,
+        options: RawOptions<ChooserOptions>,
+    ) -> Result<Choice> {
+        let dialog = Dialog::AppChooser { choices, options };
+        match self.run(token, app_id, parent_window, dialog).await? {
+            DialogResponse::AppChooser(choice) => Ok(choice),
+            response => Err(unexpected(response)),
+        }
+    }
+
+    async fn update_choices(
+        &self,
+        request: InterfaceRef<Request>,
+        choices: Vec<AppID>,
+    ) -> Result<()> {
+        let path = OwnedObjectPath::from(request.get().await.path());
+        let token = HandleToken::try_from(&path)
+            .map_err(|err| PortalError::InvalidArgument(err.to_string()))?;
+        self.0.update(token, DialogUpdate::Choices(choices)).await
+    }
+}
+
+impl<D: DialogDelegate> FileChooserImpl for Dialogs<D> {
+    async fn open_file(
+        &self,
+        token: HandleToken,
+        app_id: Option<AppID>,
+        window_identifier: Option<WindowIdentifierType>,
+        title: &str,
+        options: RawOptions<OpenFileOptions>,
+    ) -> Result<SelectedFiles> {
+        let dialog = Dialog::OpenFile {
+            title: title.to_owned(),
+            options,
+        };
+        match self.run(token, app_id, window_identifier, dialog).await? {
+            DialogResponse::FileChooser(files) => Ok(files),
+            response => Err(unexpected(response)),
+        }
+    }
+
+    async fn save_file(
+        &self,
+        token: HandleToken,
+        app_id: Option<AppID>,
+        window_identifier: Option<WindowIdentifierType>,
+        title: &str,
+        options: RawOptions<SaveFileOptions>,
+    ) -> Result<SelectedFiles> {
+        let dialog = Dialog::SaveFile {
+            title: title.to_owned(),
+            options,
+        };
+        match self.run(token, app_id, window_identifier, dialog).await? {
+            DialogResponse::FileChooser(files) => Ok(files),
+            response => Err(unexpected(response)),
+        }
+    }
+
+    async fn save_files(
+        &self,
+        token: HandleToken,
+        app_id: Option<AppID>,
+        window_identifier: Option<WindowIdentifierType>,
+        title: &str,
+        options: RawOptions<SaveFilesOptions>,
+    ) -> Result<SelectedFiles> {
+        let dialog = Dialog::SaveFiles {
+            title: title.to_owned(),
+            options,
+        };
+        match self.run(token, app_id, window_identifier, dialog).await? {
+            DialogResponse::FileChooser(files) => Ok(files),
+            response => Err(unexpected(response)),
+        }
+    }
+}
+
+impl<D: DialogDelegate> WallpaperImpl for Dialogs<D> {
+    async fn with_uri(
+        &self,
+        token: HandleToken,
+        app_id: Option<AppID>,
+        window_identifier: Option<WindowIdentifierType>,
+        uri: url::Url,
+        options: RawOptions<WallpaperOptions>,
+    ) -> Result<()> {
+        let dialog = Dialog::Wallpaper { uri, options };
+        match self.run(token, app_id, window_identifier, dialog).await? {
+            DialogResponse::Wallpaper => Ok(()),
+            response => Err(unexpected(response)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use ashpd::{desktop::HandleToken, zvariant::OwnedValue, AppID, WindowIdentifierType};
+    use tokio::sync::{
+        mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
+        Mutex,
+    };
+    use zbus::zvariant::OwnedObjectPath;
+
+    use super::{Dialog, DialogDelegate, DialogResponse, DialogUpdate, Dialogs};
+    use crate::{
+        app_chooser::Choice,
+        backend::Backend,
+        test::{block_on, peer_connections},
+        Result, DESKTOP_PATH,
+    };
+
+    #[derive(Debug)]
+    enum Event {
+        Show(HandleToken, Box<Dialog>),
+        Update(HandleToken, DialogUpdate),
+        Cancel(HandleToken),
+    }
+
+    struct Toolkit {
+        events: UnboundedSender<Event>,
+        responses: Mutex<UnboundedReceiver<Result<DialogResponse>>>,
+    }
+
+    impl DialogDelegate for Toolkit {
+        async fn show(
+            &self,
+            token: HandleToken,
+            _app_id: Option<AppID>,
+            _window_identifier: Option<WindowIdentifierType>,
+            dialog: Dialog,
+        ) -> Result<()> {
+            self.events
+                .send(Event::Show(token, Box::new(dialog)))
+                .unwrap();
+            Ok(())
+        }
+
+        async fn update(&self, token: HandleToken, update: DialogUpdate) -> Result<()> {
+            self.events.send(Event::Update(token, update)).unwrap();
+            Ok(())
+        }
+
+        async fn respond(&self, _token: HandleToken) -> Result<DialogResponse> {
+            self.responses.lock().await.recv().await.unwrap()
+        }
+
+        async fn cancel(&self, token: HandleToken) {
+            self.events.send(Event::Cancel(token)).unwrap();
+        }
+    }
+
+    /// Serves the dialogs of a [`Toolkit`] on a new backend, returning it with
+    /// the client side connection, the events of the toolkit and the sender
+    /// of the answers of the user.
+    async fn serve() -> (
+        Backend,
+        zbus::Connection,
+        UnboundedReceiver<Event>,
+        UnboundedSender<Result<DialogResponse>>,
+    ) {
+        let (server, client) = peer_connections().await;
+        let (events, events_receiver) = unbounded_channel();
+        let (responses_sender, responses) = unbounded_channel();
+        let dialogs = Dialogs::new(Toolkit {
+            events,
+            responses: Mutex::new(responses),
+        });
+        let backend = Backend::new(server);
+        backend.app_chooser(dialogs.clone()).await.unwrap();
+        backend.wallpaper(dialogs).await.unwrap();
+        (backend, client, events_receiver, responses_sender)
+    }
+
+    async fn shown(events: &mut UnboundedReceiver<Event>) -> (HandleToken, Dialog) {
+        match events.recv().await.unwrap() {
+            Event::Show(token, dialog) => (token, *dialog),
+            event => panic!("Unexpected event {event:?}"),
+        }
+    }
+
+    fn request_path(token: &str) -> OwnedObjectPath {
+        OwnedObjectPath::try_from(format!(
+            "/org/freedesktop/portal/desktop/request/1_42/{token}"
+        ))
+        .unwrap()
+    }
+
+    fn set_wallpaper(
+        client: &zbus::Connection,
+        handle: OwnedObjectPath,
+    ) -> tokio::task::JoinHandle<zbus::Result<u32>> {
+        let client = client.clone();
+        tokio::spawn(async move {
+            let reply = client
+                .call_method(
+                    None::<&str>,
+                    DESKTOP_PATH,
+                    Some("org.freedesktop.impl.portal.Wallpaper"),
+                    "SetWallpaperURI",
+                    &(
+                        handle,
+                        "org.gnome.Maps",
+                        "",
+                        "file:///home/user/wallpaper.png",
+                        HashMap::<&str, OwnedValue>::new(),
+                    ),
+                )
+                .await?;
+            reply.body().deserialize::<u32>()
+        })
+    }
+
+    #[test]
+    fn app_chooser() {
+        block_on(async {
+            let (_backend, client, mut events, responses) = serve().await;
+            let handle = request_path("chooser1");
+            let token = HandleToken::try_from("chooser1").unwrap();
+            let maps = AppID::try_from("org.gnome.Maps").unwrap();
+            let weather = AppID::try_from("org.gnome.Weather").unwrap();
+
+            let reply = {
+                let client = client.clone();
+                let handle = handle.clone();
+                let choices = vec![maps.clone()];
+                tokio::spawn(async move {
+                    client
+                        .call_method(
+                            None::<&str>,
+                            DESKTOP_PATH,
+                            Some("org.freedesktop.impl.portal.AppChooser"),
+                            "ChooseApplication",
+                            &(
+                                handle,
+                                "org.example.App",
+                                "",
+                                choices,
+                                HashMap::<&str, OwnedValue>::new(),
+                            ),
+                        )
+                        .await
+                        .unwrap()
+                })
+            };
+            let (shown_token, dialog) = shown(&mut events).await;
+            assert_eq!(shown_token, token);
+            let Dialog::AppChooser { choices, .. } = dialog else {
+                panic!("Unexpected dialog {dialog:?}");
+            };
+            assert_eq!(choices, vec![maps.clone()]);
+
+            // The shown dialog is updated with the new choices.
+            client
+                .call_method(
+                    None::<&str>,
+                    DESKTOP_PATH,
+                    Some("org.freedesktop.impl.portal.AppChooser"),
+                    "UpdateChoices",
+                    &(&handle, vec![maps.clone(), weather.clone()]),
+                )
+                .await
+                .unwrap();
+            match events.recv().await.unwrap() {
+                Event::Update(updated, DialogUpdate::Choices(choices)) => {
+                    assert_eq!(updated, token);
+                    assert_eq!(choices, vec![maps, weather.clone()]);
+                }
+                event => panic!("Unexpected event {event:?}"),
+            }
+
+            responses
+                .send(Ok(DialogResponse::AppChooser(Choice::new(weather))))
+                .unwrap();
+            let reply = reply.await.unwrap();
+            let (response, results) = reply
+                .body()
+                .deserialize::<(u32, HashMap<String, OwnedValue>)>()
+                .unwrap();
+            assert_eq!(response, 0);
+            assert_eq!(
+                String::try_from(results["choice"].clone()).unwrap(),
+                "org.gnome.Weather"
+            );
+        });
+    }
+
+    #[test]
+    fn cancelled() {
+        block_on(async {
+            let (_backend, client, mut events, _responses) = serve().await;
+            let handle = request_path("wallpaper1");
+            let token = HandleToken::try_from("wallpaper1").unwrap();
+
+            let reply = set_wallpaper(&client, handle.clone());
+            let (shown_token, dialog) = shown(&mut events).await;
+            assert_eq!(shown_token, token);
+            let Dialog::Wallpaper { uri, .. } = dialog else {
+                panic!("Unexpected dialog {dialog:?}");
+            };
+            assert_eq!(uri.as_str(), "file:///home/user/wallpaper.png");
+
+            // The dialog is closed once the application closes the request.
+            client
+                .call_method(
+                    None::<&str>,
+                    &handle,
+                    Some("org.freedesktop.impl.portal.Request"),
+                    "Close",
+                    &(),
+                )
+                .await
+                .unwrap();
+            match events.recv().await.unwrap() {
+                Event::Cancel(cancelled) => assert_eq!(cancelled, token),
+                event => panic!("Unexpected event {event:?}"),
+            }
+            assert_eq!(reply.await.unwrap().unwrap(), 1);
+        });
+    }
+
+    #[test]
+    fn unexpected_response() {
+        block_on(async {
+            let (_backend, client, mut events, responses) = serve().await;
+
+            let reply = set_wallpaper(&client, request_path("wallpaper2"));
+            assert!(matches!(
+                shown(&mut events).await.1,
+                Dialog::Wallpaper { .. }
+            ));
+            // The answer doesn't match the shown dialog.
+            responses
+                .send(Ok(DialogResponse::AppChooser(Choice::new(
+                    AppID::try_from("org.gnome.Maps").unwrap(),
+                ))))
+                .unwrap();
+            assert!(reply.await.unwrap().is_err());
+
+            let reply = set_wallpaper(&client, request_path("wallpaper3"));
+            assert!(matches!(
+                shown(&mut events).await.1,
+                Dialog::Wallpaper { .. }
+            ));
+            responses.send(Ok(DialogResponse::Wallpaper)).unwrap();
+            assert_eq!(reply.await.unwrap().unwrap(), 0);
+        });
+    }
+}
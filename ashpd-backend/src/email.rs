@@ -1,16 +1,17 @@
-use std::sync::Arc;
+use std::{future::Future, sync::Arc};
 
-use async_trait::async_trait;
-
-use crate::{
-    backend::{
-        request::{Request, RequestImpl},
-        MaybeAppID, MaybeWindowIdentifier, Result,
-    },
-    desktop::{request::Response, HandleToken},
+use ashpd::{
+    desktop::{HandleToken, Response},
     zvariant::{self, DeserializeDict, OwnedObjectPath},
     ActivationToken, AppID, WindowIdentifierType,
 };
+use futures_util::future::BoxFuture;
+
+use crate::{
+    backend::PortalInterface,
+    request::{DynRequestImpl, Request, RequestImpl},
+    MaybeAppID, MaybeWindowIdentifier, RawOptions, Result,
+};
 
 #[derive(DeserializeDict, zvariant::Type)]
 #[zvariant(signature = "dict")]
@@ -59,33 +60,72 @@ impl Options {
     }
 }
 
-#[async_trait]
 pub trait EmailImpl: RequestImpl {
-    async fn compose(
+    fn compose(
         &self,
         token: HandleToken,
         app_id: Option<AppID>,
         window_identifier: Option<WindowIdentifierType>,
-        options: Options,
-    ) -> Result<()>;
+        options: RawOptions<Options>,
+    ) -> impl Future<Output = Result<()>> + Send;
+}
+
+/// Object safe counterpart of [`EmailImpl`], used to store the implementation.
+pub(crate) trait DynEmailImpl: DynRequestImpl {
+    fn compose<'a>(
+        &'a self,
+        token: HandleToken,
+        app_id: Option<AppID>,
+        window_identifier: Option<WindowIdentifierType>,
+        options: RawOptions<Options>,
+    ) -> BoxFuture<'a, Result<()>>;
+}
+
+impl<T: EmailImpl> DynEmailImpl for T {
+    fn compose<'a>(
+        &'a self,
+        token: HandleToken,
+        app_id: Option<AppID>,
+        window_identifier: Option<WindowIdentifierType>,
+        options: RawOptions<Options>,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(EmailImpl::compose(
+            self,
+            token,
+            app_id,
+            window_identifier,
+            options,
+        ))
+    }
 }
 
 pub(crate) struct EmailInterface {
-    imp: Arc<dyn EmailImpl>,
+    imp: Arc<dyn DynEmailImpl>,
     cnx: zbus::Connection,
+    version: u32,
 }
 
 impl EmailInterface {
-    pub fn new(imp: Arc<dyn EmailImpl>, cnx: zbus::Connection) -> Self {
-        Self { imp, cnx }
+    pub fn new(imp: Arc<dyn DynEmailImpl>, cnx: zbus::Connection) -> Self {
+        Self {
+            imp,
+            cnx,
+            version: 4,
+        }
+    }
+}
+
+impl PortalInterface for EmailInterface {
+    fn set_version(&mut self, version: u32) {
+        self.version = version;
     }
 }
 
 #[zbus::interface(name = "org.freedesktop.impl.portal.Email")]
 impl EmailInterface {
-    #[zbus(property(emits_changed_signal = "const"), name = "version")]
+    #[zbus(property, name = "version")]
     fn version(&self) -> u32 {
-        4
+        self.version
     }
 
     #[zbus(out_args("response", "results"))]
@@ -94,7 +134,7 @@ impl EmailInterface {
         handle: OwnedObjectPath,
         app_id: MaybeAppID,
         window_identifier: MaybeWindowIdentifier,
-        options: Options,
+        options: RawOptions<Options>,
     ) -> Result<Response<()>> {
         let imp = Arc::clone(&self.imp);
 
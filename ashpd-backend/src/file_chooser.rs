@@ -1,20 +1,20 @@
-use std::sync::Arc;
+use std::{future::Future, sync::Arc};
 
-use async_trait::async_trait;
-
-use crate::{
-    backend::{
-        request::{Request, RequestImpl},
-        MaybeAppID, MaybeWindowIdentifier, Result,
-    },
+use ashpd::{
     desktop::{
         file_chooser::{Choice, FileFilter},
-        request::Response,
-        HandleToken,
+        HandleToken, Response,
     },
     zvariant::{DeserializeDict, OwnedObjectPath, SerializeDict, Type},
     AppID, FilePath, WindowIdentifierType,
 };
+use futures_util::future::BoxFuture;
+
+use crate::{
+    backend::PortalInterface,
+    request::{DynRequestImpl, Request, RequestImpl},
+    MaybeAppID, MaybeWindowIdentifier, RawOptions, Result,
+};
 
 #[derive(Debug, Type, SerializeDict, Default)]
 #[zvariant(signature = "dict")]
@@ -185,52 +185,148 @@ impl SaveFilesOptions {
     }
 }
 
-#[async_trait]
 pub trait FileChooserImpl: RequestImpl {
-    async fn open_file(
+    fn open_file(
         &self,
         token: HandleToken,
         app_id: Option<AppID>,
         window_identifier: Option<WindowIdentifierType>,
         title: &str,
-        options: OpenFileOptions,
-    ) -> Result<SelectedFiles>;
+        options: RawOptions<OpenFileOptions>,
+    ) -> impl Future<Output = Result<SelectedFiles>> + Send;
 
-    async fn save_file(
+    fn save_file(
         &self,
         token: HandleToken,
         app_id: Option<AppID>,
         window_identifier: Option<WindowIdentifierType>,
         title: &str,
-        options: SaveFileOptions,
-    ) -> Result<SelectedFiles>;
+        options: RawOptions<SaveFileOptions>,
+    ) -> impl Future<Output = Result<SelectedFiles>> + Send;
 
-    async fn save_files(
+    fn save_files(
         &self,
         token: HandleToken,
         app_id: Option<AppID>,
         window_identifier: Option<WindowIdentifierType>,
         title: &str,
-        options: SaveFilesOptions,
-    ) -> Result<SelectedFiles>;
+        options: RawOptions<SaveFilesOptions>,
+    ) -> impl Future<Output = Result<SelectedFiles>> + Send;
+}
+
+/// Object safe counterpart of [`FileChooserImpl`], used to store the implementation.
+pub(crate) trait DynFileChooserImpl: DynRequestImpl {
+    fn open_file<'a>(
+        &'a self,
+        token: HandleToken,
+        app_id: Option<AppID>,
+        window_identifier: Option<WindowIdentifierType>,
+        title: &'a str,
+        options: RawOptions<OpenFileOptions>,
+    ) -> BoxFuture<'a, Result<SelectedFiles>>;
+
+    fn save_file<'a>(
+        &'a self,
+        token: HandleToken,
+        app_id: Option<AppID>,
+        window_identifier: Option<WindowIdentifierType>,
+        title: &'a str,
+        options: RawOptions<SaveFileOptions>,
+    ) -> BoxFuture<'a, Result<SelectedFiles>>;
+
+    fn save_files<'a>(
+        &'a self,
+        token: HandleToken,
+        app_id: Option<AppID>,
+        window_identifier: Option<WindowIdentifierType>,
+        title: &'a str,
+        options: RawOptions<SaveFilesOptions>,
+    ) -> BoxFuture<'a, Result<SelectedFiles>>;
+}
+
+impl<T: FileChooserImpl> DynFileChooserImpl for T {
+    fn open_file<'a>(
+        &'a self,
+        token: HandleToken,
+        app_id: Option<AppID>,
+        window_identifier: Option<WindowIdentifierType>,
+        title: &'a str,
+        options: RawOptions<OpenFileOptions>,
+    ) -> BoxFuture<'a, Result<SelectedFiles>> {
+        Box::pin(FileChooserImpl::open_file(
+            self,
+            token,
+            app_id,
+            window_identifier,
+            title,
+            options,
+        ))
+    }
+
+    fn save_file<'a>(
+        &'a self,
+        token: HandleToken,
+        app_id: Option<AppID>,
+        window_identifier: Option<WindowIdentifierType>,
+        title: &'a str,
+        options: RawOptions<SaveFileOptions>,
+    ) -> BoxFuture<'a, Result<SelectedFiles>> {
+        Box::pin(FileChooserImpl::save_file(
+            self,
+            token,
+            app_id,
+            window_identifier,
+            title,
+            options,
+        ))
+    }
+
+    fn save_files<'a>(
+        &'a self,
+        token: HandleToken,
+        app_id: Option<AppID>,
+        window_identifier: Option<WindowIdentifierType>,
+        title: &'a str,
+        options: RawOptions<SaveFilesOptions>,
+    ) -> BoxFuture<'a, Result<SelectedFiles>> {
+        Box::pin(FileChooserImpl::save_files(
+            self,
+            token,
+            app_id,
+            window_identifier,
+            title,
+            options,
+        ))
+    }
 }
 
 pub(crate) struct FileChooserInterface {
-    imp: Arc<dyn FileChooserImpl>,
+    imp: Arc<dyn DynFileChooserImpl>,
     cnx: zbus::Connection,
+    version: u32,
 }
 
 impl FileChooserInterface {
-    pub fn new(imp: Arc<dyn FileChooserImpl>, cnx: zbus::Connection) -> Self {
-        Self { imp, cnx }
+    pub fn new(imp: Arc<dyn DynFileChooserImpl>, cnx: zbus::Connection) -> Self {
+        Self {
+            imp,
+            cnx,
+            version: 4,
+        }
+    }
+}
+
+impl PortalInterface for FileChooserInterface {
+    fn set_version(&mut self, version: u32) {
+        self.version = version;
     }
 }
 
 #[zbus::interface(name = "org.freedesktop.impl.portal.FileChooser")]
 impl FileChooserInterface {
-    #[zbus(property(emits_changed_signal = "const"), name = "version")]
+    #[zbus(property, name = "version")]
     fn version(&self) -> u32 {
-        4
+        self.version
     }
 
     #[zbus(out_args("response", "results"))]
@@ -240,7 +336,7 @@ impl FileChooserInterface {
         app_id: MaybeAppID,
         window_identifier: MaybeWindowIdentifier,
         title: String,
-        options: OpenFileOptions,
+        options: RawOptions<OpenFileOptions>,
     ) -> Result<Response<SelectedFiles>> {
         let imp = Arc::clone(&self.imp);
 
@@ -270,7 +366,7 @@ impl FileChooserInterface {
         app_id: MaybeAppID,
         window_identifier: MaybeWindowIdentifier,
         title: String,
-        options: SaveFileOptions,
+        options: RawOptions<SaveFileOptions>,
     ) -> Result<Response<SelectedFiles>> {
         let imp = Arc::clone(&self.imp);
 
@@ -300,7 +396,7 @@ impl FileChooserInterface {
         app_id: MaybeAppID,
         window_identifier: MaybeWindowIdentifier,
         title: String,
-        options: SaveFilesOptions,
+        options: RawOptions<SaveFilesOptions>,
     ) -> Result<Response<SelectedFiles>> {
         let imp = Arc::clone(&self.imp);
 
@@ -0,0 +1,113 @@
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use futures_util::{
+    future::{select, Either},
+    StreamExt,
+};
+use tokio::sync::Notify;
+
+/// The requests of the process that are still being handled and its open
+/// sessions.
+static OUTSTANDING: AtomicUsize = AtomicUsize::new(0);
+static RELEASED: Notify = Notify::const_new();
+
+/// Keeps the backend busy, so it doesn't exit while a request is handled or
+/// a session is open.
+pub(crate) struct Busy(());
+
+impl Busy {
+    pub fn new() -> Self {
+        OUTSTANDING.fetch_add(1, Ordering::SeqCst);
+        Self(())
+    }
+}
+
+impl Drop for Busy {
+    fn drop(&mut self) {
+        if OUTSTANDING.fetch_sub(1, Ordering::SeqCst) == 1 {
+            RELEASED.notify_waiters();
+        }
+    }
+}
+
+async fn wait_released() {
+    loop {
+        let notified = RELEASED.notified();
+        let mut notified = std::pin::pin!(notified);
+        notified.as_mut().enable();
+        if OUTSTANDING.load(Ordering::SeqCst) == 0 {
+            return;
+        }
+        notified.await;
+    }
+}
+
+/// Waits until no method was called on `cnx` and no request or session was
+/// outstanding for `timeout`, or until the connection is closed.
+pub(crate) async fn wait_idle(cnx: &zbus::Connection, timeout: Duration) {
+    let mut messages = zbus::MessageStream::from(cnx);
+    loop {
+        wait_released().await;
+
+        let next_call = async {
+            while let Some(msg) = messages.next().await {
+                if msg.is_ok_and(|msg| msg.message_type() == zbus::message::Type::MethodCall) {
+                    return true;
+                }
+            }
+            false
+        };
+        match select(
+            std::pin::pin!(next_call),
+            std::pin::pin!(tokio::time::sleep(timeout)),
+        )
+        .await
+        {
+            Either::Left((true, _)) => continue,
+            Either::Left((false, _)) => return,
+            Either::Right(_) if OUTSTANDING.load(Ordering::SeqCst) == 0 => return,
+            Either::Right(_) => continue,
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::time::Duration;
+
+    use tokio::{sync::Mutex, time::timeout};
+
+    use super::{wait_released, Busy, OUTSTANDING};
+    use crate::test::block_on;
+
+    /// Serializes the tests counting the outstanding requests and sessions.
+    pub(crate) static LOCK: Mutex<()> = Mutex::const_new(());
+
+    pub(crate) fn outstanding() -> usize {
+        OUTSTANDING.load(std::sync::atomic::Ordering::SeqCst)
+    }
+
+    #[test]
+    fn released() {
+        block_on(async {
+            let _lock = LOCK.lock().await;
+            let first = Busy::new();
+            let second = Busy::new();
+            assert_eq!(outstanding(), 2);
+
+            drop(first);
+            assert_eq!(outstanding(), 1);
+            let released = timeout(Duration::from_millis(50), wait_released()).await;
+            assert!(released.is_err());
+
+            drop(second);
+            assert_eq!(outstanding(), 0);
+            timeout(Duration::from_secs(1), wait_released())
+                .await
+                .unwrap();
+        });
+    }
+}
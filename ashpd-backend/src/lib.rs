@@ -1,9 +1,16 @@
+//! Build your custom portals backend.
+//!
+//! The client side types, shared with the backend implementations, come from
+//! [`ashpd`].
+#![allow(missing_docs)]
+
+use ashpd::{AppID, WindowIdentifierType};
 use serde::{de::Deserializer, Deserialize};
 use zbus::zvariant::Type;
 
-use crate::{AppID, WindowIdentifierType};
+pub(crate) const DESKTOP_PATH: &str = "/org/freedesktop/portal/desktop";
 
-pub type Result<T> = std::result::Result<T, crate::error::PortalError>;
+pub type Result<T> = std::result::Result<T, ashpd::PortalError>;
 
 #[derive(Debug, Default, Type)]
 #[zvariant(signature = "s")]
@@ -56,16 +63,30 @@ impl<'de> Deserialize<'de> for MaybeAppID {
 pub mod access;
 pub mod account;
 pub mod app_chooser;
+mod backend;
 pub mod background;
+pub use backend::{Backend, Portal};
 mod builder;
+pub mod dialog;
 pub use builder::Builder;
 pub mod email;
 pub mod file_chooser;
+mod idle;
 pub mod lockdown;
+pub mod notification;
+mod options;
+pub use options::RawOptions;
 pub mod permission_store;
 pub mod print;
 pub mod request;
+#[cfg(feature = "pipewire")]
+pub mod screencast;
 pub mod screenshot;
 pub mod secret;
+pub mod session;
 pub mod settings;
+#[cfg(test)]
+mod test;
+#[cfg(feature = "usb")]
+pub mod usb;
 pub mod wallpaper;
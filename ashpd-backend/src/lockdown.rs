@@ -0,0 +1,352 @@
+use std::{future::Future, sync::Arc};
+
+use futures_util::future::BoxFuture;
+
+use crate::backend::PortalInterface;
+
+pub trait LockdownImpl: Send + Sync {
+    fn disable_printing(&self) -> impl Future<Output = bool> + Send;
+    fn set_disable_printing(
+        &self,
+        disable_printing: bool,
+    ) -> impl Future<Output = zbus::Result<()>> + Send;
+
+    fn disable_save_to_disk(&self) -> impl Future<Output = bool> + Send;
+    fn set_disable_save_to_disk(
+        &self,
+        disable_save_to_disk: bool,
+    ) -> impl Future<Output = zbus::Result<()>> + Send;
+
+    fn disable_application_handlers(&self) -> impl Future<Output = bool> + Send;
+    fn set_disable_application_handlers(
+        &self,
+        disable_application_handlers: bool,
+    ) -> impl Future<Output = zbus::Result<()>> + Send;
+
+    fn disable_location(&self) -> impl Future<Output = bool> + Send;
+    fn set_disable_location(
+        &self,
+        disable_location: bool,
+    ) -> impl Future<Output = zbus::Result<()>> + Send;
+
+    fn disable_camera(&self) -> impl Future<Output = bool> + Send;
+    fn set_disable_camera(
+        &self,
+        disable_camera: bool,
+    ) -> impl Future<Output = zbus::Result<()>> + Send;
+
+    fn disable_microphone(&self) -> impl Future<Output = bool> + Send;
+    fn set_disable_microphone(
+        &self,
+        disable_microphone: bool,
+    ) -> impl Future<Output = zbus::Result<()>> + Send;
+
+    fn disable_sound_output(&self) -> impl Future<Output = bool> + Send;
+    fn set_disable_sound_output(
+        &self,
+        disable_sound_output: bool,
+    ) -> impl Future<Output = zbus::Result<()>> + Send;
+}
+
+/// Object safe counterpart of [`LockdownImpl`], used to store the implementation.
+pub(crate) trait DynLockdownImpl: Send + Sync {
+    fn disable_printing<'a>(&'a self) -> BoxFuture<'a, bool>;
+
+    fn set_disable_printing<'a>(
+        &'a self,
+        disable_printing: bool,
+    ) -> BoxFuture<'a, zbus::Result<()>>;
+
+    fn disable_save_to_disk<'a>(&'a self) -> BoxFuture<'a, bool>;
+
+    fn set_disable_save_to_disk<'a>(
+        &'a self,
+        disable_save_to_disk: bool,
+    ) -> BoxFuture<'a, zbus::Result<()>>;
+
+    fn disable_application_handlers<'a>(&'a self) -> BoxFuture<'a, bool>;
+
+    fn set_disable_application_handlers<'a>(
+        &'a self,
+        disable_application_handlers: bool,
+    ) -> BoxFuture<'a, zbus::Result<()>>;
+
+    fn disable_location<'a>(&'a self) -> BoxFuture<'a, bool>;
+
+    fn set_disable_location<'a>(
+        &'a self,
+        disable_location: bool,
+    ) -> BoxFuture<'a, zbus::Result<()>>;
+
+    fn disable_camera<'a>(&'a self) -> BoxFuture<'a, bool>;
+
+    fn set_disable_camera<'a>(&'a self, disable_camera: bool) -> BoxFuture<'a, zbus::Result<()>>;
+
+    fn disable_microphone<'a>(&'a self) -> BoxFuture<'a, bool>;
+
+    fn set_disable_microphone<'a>(
+        &'a self,
+        disable_microphone: bool,
+    ) -> BoxFuture<'a, zbus::Result<()>>;
+
+    fn disable_sound_output<'a>(&'a self) -> BoxFuture<'a, bool>;
+
+    fn set_disable_sound_output<'a>(
+        &'a self,
+        disable_sound_output: bool,
+    ) -> BoxFuture<'a, zbus::Result<()>>;
+}
+
+impl<T: LockdownImpl> DynLockdownImpl for T {
+    fn disable_printing<'a>(&'a self) -> BoxFuture<'a, bool> {
+        Box::pin(LockdownImpl::disable_printing(self))
+    }
+
+    fn set_disable_printing<'a>(
+        &'a self,
+        disable_printing: bool,
+    ) -> BoxFuture<'a, zbus::Result<()>> {
+        Box::pin(LockdownImpl::set_disable_printing(self, disable_printing))
+    }
+
+    fn disable_save_to_disk<'a>(&'a self) -> BoxFuture<'a, bool> {
+        Box::pin(LockdownImpl::disable_save_to_disk(self))
+    }
+
+    fn set_disable_save_to_disk<'a>(
+        &'a self,
+        disable_save_to_disk: bool,
+    ) -> BoxFuture<'a, zbus::Result<()>> {
+        Box::pin(LockdownImpl::set_disable_save_to_disk(
+            self,
+            disable_save_to_disk,
+        ))
+    }
+
+    fn disable_application_handlers<'a>(&'a self) -> BoxFuture<'a, bool> {
+        Box::pin(LockdownImpl::disable_application_handlers(self))
+    }
+
+    fn set_disable_application_handlers<'a>(
+        &'a self,
+        disable_application_handlers: bool,
+    ) -> BoxFuture<'a, zbus::Result<()>> {
+        Box::pin(LockdownImpl::set_disable_application_handlers(
+            self,
+            disable_application_handlers,
+        ))
+    }
+
+    fn disable_location<'a>(&'a self) -> BoxFuture<'a, bool> {
+        Box::pin(LockdownImpl::disable_location(self))
+    }
+
+    fn set_disable_location<'a>(
+        &'a self,
+        disable_location: bool,
+    ) -> BoxFuture<'a, zbus::Result<()>> {
+        Box::pin(LockdownImpl::set_disable_location(self, disable_location))
+    }
+
+    fn disable_camera<'a>(&'a self) -> BoxFuture<'a, bool> {
+        Box::pin(LockdownImpl::disable_camera(self))
+    }
+
+    fn set_disable_camera<'a>(&'a self, disable_camera: bool) -> BoxFuture<'a, zbus::Result<()>> {
+        Box::pin(LockdownImpl::set_disable_camera(self, disable_camera))
+    }
+
+    fn disable_microphone<'a>(&'a self) -> BoxFuture<'a, bool> {
+        Box::pin(LockdownImpl::disable_microphone(self))
+    }
+
+    fn set_disable_microphone<'a>(
+        &'a self,
+        disable_microphone: bool,
+    ) -> BoxFuture<'a, zbus::Result<()>> {
+        Box::pin(LockdownImpl::set_disable_microphone(
+            self,
+            disable_microphone,
+        ))
+    }
+
+    fn disable_sound_output<'a>(&'a self) -> BoxFuture<'a, bool> {
+        Box::pin(LockdownImpl::disable_sound_output(self))
+    }
+
+    fn set_disable_sound_output<'a>(
+        &'a self,
+        disable_sound_output: bool,
+    ) -> BoxFuture<'a, zbus::Result<()>> {
+        Box::pin(LockdownImpl::set_disable_sound_output(
+            self,
+            disable_sound_output,
+        ))
+    }
+}
+
+pub(crate) struct LockdownInterface {
+    imp: Arc<dyn DynLockdownImpl>,
+    #[allow(dead_code)]
+    cnx: zbus::Connection,
+    version: u32,
+}
+
+impl LockdownInterface {
+    pub fn new(imp: Arc<dyn DynLockdownImpl>, cnx: zbus::Connection) -> Self {
+        Self {
+            imp,
+            cnx,
+            version: 1,
+        }
+    }
+}
+
+impl PortalInterface for LockdownInterface {
+    fn set_version(&mut self, version: u32) {
+        self.version = version;
+    }
+}
+
+#[zbus::interface(name = "org.freedesktop.impl.portal.Lockdown")]
+impl LockdownInterface {
+    #[zbus(property, name = "version")]
+    fn version(&self) -> u32 {
+        self.version
+    }
+
+    #[zbus(property, name = "disable-printing")]
+    async fn disable_printing(&self) -> bool {
+        self.imp.disable_printing().await
+    }
+
+    #[zbus(property, name = "disable-printing")]
+    async fn set_disable_printing(&self, disable_printing: bool) -> zbus::Result<()> {
+        let object_server = self.cnx.object_server();
+        let iface_ref = object_server
+            .interface::<_, Self>(crate::DESKTOP_PATH)
+            .await?;
+        let ctxt = iface_ref.signal_emitter();
+
+        self.imp.set_disable_printing(disable_printing).await?;
+        self.disable_printing_changed(ctxt).await?;
+        Ok(())
+    }
+
+    #[zbus(property, name = "disable-save-to-disk")]
+    async fn disable_save_to_disk(&self) -> bool {
+        self.imp.disable_save_to_disk().await
+    }
+
+    #[zbus(property, name = "disable-save-to-disk")]
+    async fn set_disable_save_to_disk(&self, disable_save_to_disk: bool) -> zbus::Result<()> {
+        let object_server = self.cnx.object_server();
+        let iface_ref = object_server
+            .interface::<_, Self>(crate::DESKTOP_PATH)
+            .await?;
+        let ctxt = iface_ref.signal_emitter();
+
+        self.imp
+            .set_disable_save_to_disk(disable_save_to_disk)
+            .await?;
+        self.disable_save_to_disk_changed(ctxt).await?;
+        Ok(())
+    }
+
+    #[zbus(property, name = "disable-application-handlers")]
+    async fn disable_application_handlers(&self) -> bool {
+        self.imp.disable_application_handlers().await
+    }
+
+    #[zbus(property, name = "disable-application-handlers")]
+    async fn set_disable_application_handlers(
+        &self,
+        disable_application_handlers: bool,
+    ) -> zbus::Result<()> {
+        let object_server = self.cnx.object_server();
+        let iface_ref = object_server
+            .interface::<_, Self>(crate::DESKTOP_PATH)
+            .await?;
+        let ctxt = iface_ref.signal_emitter();
+
+        self.imp
+            .set_disable_application_handlers(disable_application_handlers)
+            .await?;
+        self.disable_application_handlers_changed(ctxt).await?;
+        Ok(())
+    }
+
+    #[zbus(property, name = "disable-location")]
+    async fn disable_location(&self) -> bool {
+        self.imp.disable_location().await
+    }
+
+    #[zbus(property, name = "disable-location")]
+    async fn set_disable_location(&self, disable_location: bool) -> zbus::Result<()> {
+        let object_server = self.cnx.object_server();
+        let iface_ref = object_server
+            .interface::<_, Self>(crate::DESKTOP_PATH)
+            .await?;
+        let ctxt = iface_ref.signal_emitter();
+
+        self.imp.set_disable_location(disable_location).await?;
+        self.disable_location_changed(ctxt).await?;
+        Ok(())
+    }
+
+    #[zbus(property, name = "disable-camera")]
+    async fn disable_camera(&self) -> bool {
+        self.imp.disable_camera().await
+    }
+
+    #[zbus(property, name = "disable-camera")]
+    async fn set_disable_camera(&self, disable_camera: bool) -> zbus::Result<()> {
+        let object_server = self.cnx.object_server();
+        let iface_ref = object_server
+            .interface::<_, Self>(crate::DESKTOP_PATH)
+            .await?;
+        let ctxt = iface_ref.signal_emitter();
+
+        self.imp.set_disable_camera(disable_camera).await?;
+        self.disable_camera_changed(ctxt).await?;
+        Ok(())
+    }
+
+    #[zbus(property, name = "disable-microphone")]
+    async fn disable_microphone(&self) -> bool {
+        self.imp.disable_microphone().await
+    }
+
+    #[zbus(property, name = "disable-microphone")]
+    async fn set_disable_microphone(&self, disable_microphone: bool) -> zbus::Result<()> {
+        let object_server = self.cnx.object_server();
+        let iface_ref = object_server
+            .interface::<_, Self>(crate::DESKTOP_PATH)
+            .await?;
+        let ctxt = iface_ref.signal_emitter();
+
+        self.imp.set_disable_microphone(disable_microphone).await?;
+        self.disable_microphone_changed(ctxt).await?;
+        Ok(())
+    }
+
+    #[zbus(property, name = "disable-sound-output")]
+    async fn disable_sound_output(&self) -> bool {
+        self.imp.disable_sound_output().await
+    }
+
+    #[zbus(property, name = "disable-sound-output")]
+    async fn set_disable_sound_output(&self, disable_sound_output: bool) -> zbus::Result<()> {
+        let object_server = self.cnx.object_server();
+        let iface_ref = object_server
+            .interface::<_, Self>(crate::DESKTOP_PATH)
+            .await?;
+        let ctxt = iface_ref.signal_emitter();
+
+        self.imp
+            .set_disable_sound_output(disable_sound_output)
+            .await?;
+        self.disable_sound_output_changed(ctxt).await?;
+        Ok(())
+    }
+}
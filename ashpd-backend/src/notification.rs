@@ -0,0 +1,302 @@
+use std::{collections::HashMap, future::Future, sync::Arc};
+
+use ashpd::{
+    desktop::{
+        notification::{Category, Priority},
+        Icon,
+    },
+    zbus::object_server::SignalEmitter,
+    zvariant::{DeserializeDict, OwnedValue, Type},
+    AppID,
+};
+use futures_util::future::BoxFuture;
+
+use crate::{backend::PortalInterface, MaybeAppID, RawOptions, Result};
+
+mod freedesktop;
+pub use freedesktop::DesktopNotifications;
+
+/// A notification added by an application.
+#[derive(DeserializeDict, Type, Debug)]
+#[zvariant(signature = "dict")]
+pub struct Notification {
+    title: Option<String>,
+    body: Option<String>,
+    #[zvariant(rename = "markup-body")]
+    markup_body: Option<String>,
+    icon: Option<Icon>,
+    priority: Option<String>,
+    #[zvariant(rename = "default-action")]
+    default_action: Option<String>,
+    #[zvariant(rename = "default-action-target")]
+    default_action_target: Option<OwnedValue>,
+    buttons: Option<Vec<Button>>,
+    category: Option<Category>,
+    #[zvariant(rename = "display-hint")]
+    display_hints: Option<Vec<String>>,
+    sound: Option<OwnedValue>,
+}
+
+impl Notification {
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    pub fn body(&self) -> Option<&str> {
+        self.body.as_deref()
+    }
+
+    pub fn markup_body(&self) -> Option<&str> {
+        self.markup_body.as_deref()
+    }
+
+    pub fn icon(&self) -> Option<&Icon> {
+        self.icon.as_ref()
+    }
+
+    pub fn priority(&self) -> Option<Priority> {
+        self.priority.as_deref()?.parse().ok()
+    }
+
+    pub fn default_action(&self) -> Option<&str> {
+        self.default_action.as_deref()
+    }
+
+    pub fn default_action_target(&self) -> Option<&OwnedValue> {
+        self.default_action_target.as_ref()
+    }
+
+    pub fn buttons(&self) -> &[Button] {
+        self.buttons.as_deref().unwrap_or_default()
+    }
+
+    pub fn category(&self) -> Option<&Category> {
+        self.category.as_ref()
+    }
+
+    /// The display hints, like `transient` or `persistent`.
+    pub fn display_hints(&self) -> &[String] {
+        self.display_hints.as_deref().unwrap_or_default()
+    }
+
+    /// Either `default`, `silent` or a file descriptor of the sound to play.
+    pub fn sound(&self) -> Option<&OwnedValue> {
+        self.sound.as_ref()
+    }
+}
+
+/// A button of a [`Notification`].
+#[derive(DeserializeDict, Type, Debug)]
+#[zvariant(signature = "dict")]
+pub struct Button {
+    label: Option<String>,
+    action: Option<String>,
+    target: Option<OwnedValue>,
+    purpose: Option<String>,
+}
+
+impl Button {
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    pub fn action(&self) -> Option<&str> {
+        self.action.as_deref()
+    }
+
+    pub fn target(&self) -> Option<&OwnedValue> {
+        self.target.as_ref()
+    }
+
+    pub fn purpose(&self) -> Option<&str> {
+        self.purpose.as_deref()
+    }
+}
+
+pub trait NotificationSignalEmitter: Send + Sync {
+    fn emit_action_invoked<'a>(
+        &'a self,
+        app_id: Option<&'a AppID>,
+        id: &'a str,
+        action: &'a str,
+        parameter: Vec<OwnedValue>,
+    ) -> BoxFuture<'a, zbus::Result<()>>;
+}
+
+pub trait NotificationImpl: Send + Sync {
+    fn add_notification(
+        &self,
+        app_id: Option<AppID>,
+        id: &str,
+        notification: RawOptions<Notification>,
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    fn remove_notification(
+        &self,
+        app_id: Option<AppID>,
+        id: &str,
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    // Set the signal emitter, allowing to notify of the invoked actions.
+    fn set_signal_emitter(&mut self, signal_emitter: Arc<dyn NotificationSignalEmitter>);
+}
+
+/// Object safe counterpart of [`NotificationImpl`], used to store the
+/// implementation.
+pub(crate) trait DynNotificationImpl: Send + Sync {
+    fn add_notification<'a>(
+        &'a self,
+        app_id: Option<AppID>,
+        id: &'a str,
+        notification: RawOptions<Notification>,
+    ) -> BoxFuture<'a, Result<()>>;
+
+    fn remove_notification<'a>(
+        &'a self,
+        app_id: Option<AppID>,
+        id: &'a str,
+    ) -> BoxFuture<'a, Result<()>>;
+
+    fn set_signal_emitter(&mut self, signal_emitter: Arc<dyn NotificationSignalEmitter>);
+}
+
+impl<T: NotificationImpl> DynNotificationImpl for T {
+    fn add_notification<'a>(
+        &'a self,
+        app_id: Option<AppID>,
+        id: &'a str,
+        notification: RawOptions<Notification>,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(NotificationImpl::add_notification(
+            self,
+            app_id,
+            id,
+            notification,
+        ))
+    }
+
+    fn remove_notification<'a>(
+        &'a self,
+        app_id: Option<AppID>,
+        id: &'a str,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(NotificationImpl::remove_notification(self, app_id, id))
+    }
+
+    fn set_signal_emitter(&mut self, signal_emitter: Arc<dyn NotificationSignalEmitter>) {
+        NotificationImpl::set_signal_emitter(self, signal_emitter)
+    }
+}
+
+pub(crate) struct NotificationInterface {
+    imp: Arc<dyn DynNotificationImpl>,
+    version: u32,
+}
+
+impl NotificationInterface {
+    pub fn new(imp: Arc<dyn DynNotificationImpl>) -> Self {
+        Self { imp, version: 2 }
+    }
+}
+
+impl PortalInterface for NotificationInterface {
+    fn set_version(&mut self, version: u32) {
+        self.version = version;
+    }
+}
+
+/// Emits the signals of the [`NotificationInterface`] served on a connection.
+pub(crate) struct NotificationEmitter {
+    cnx: zbus::Connection,
+}
+
+impl NotificationEmitter {
+    pub fn new(cnx: zbus::Connection) -> Self {
+        Self { cnx }
+    }
+
+    pub async fn action_invoked(
+        &self,
+        app_id: Option<&AppID>,
+        id: &str,
+        action: &str,
+        parameter: Vec<OwnedValue>,
+    ) -> zbus::Result<()> {
+        let object_server = self.cnx.object_server();
+        let iface_ref = object_server
+            .interface::<_, NotificationInterface>(crate::DESKTOP_PATH)
+            .await?;
+        NotificationInterface::action_invoked(
+            iface_ref.signal_emitter(),
+            app_id.map(|app_id| app_id.as_ref()).unwrap_or_default(),
+            id,
+            action,
+            parameter,
+        )
+        .await
+    }
+}
+
+impl NotificationSignalEmitter for NotificationEmitter {
+    fn emit_action_invoked<'a>(
+        &'a self,
+        app_id: Option<&'a AppID>,
+        id: &'a str,
+        action: &'a str,
+        parameter: Vec<OwnedValue>,
+    ) -> BoxFuture<'a, zbus::Result<()>> {
+        Box::pin(self.action_invoked(app_id, id, action, parameter))
+    }
+}
+
+#[zbus::interface(name = "org.freedesktop.impl.portal.Notification")]
+impl NotificationInterface {
+    #[zbus(property, name = "version")]
+    fn version(&self) -> u32 {
+        self.version
+    }
+
+    #[zbus(property(emits_changed_signal = "const"), name = "SupportedOptions")]
+    fn supported_options(&self) -> HashMap<String, OwnedValue> {
+        HashMap::new()
+    }
+
+    async fn add_notification(
+        &self,
+        app_id: MaybeAppID,
+        id: String,
+        notification: RawOptions<Notification>,
+    ) -> Result<()> {
+        #[cfg(feature = "tracing")]
+        tracing::debug!("Notification::AddNotification");
+
+        let response = self
+            .imp
+            .add_notification(app_id.inner(), &id, notification)
+            .await;
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!("Notification::AddNotification returned {:#?}", response);
+        response
+    }
+
+    async fn remove_notification(&self, app_id: MaybeAppID, id: String) -> Result<()> {
+        #[cfg(feature = "tracing")]
+        tracing::debug!("Notification::RemoveNotification");
+
+        let response = self.imp.remove_notification(app_id.inner(), &id).await;
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!("Notification::RemoveNotification returned {:#?}", response);
+        response
+    }
+
+    #[zbus(signal)]
+    async fn action_invoked(
+        signal_ctxt: &SignalEmitter<'_>,
+        app_id: &str,
+        id: &str,
+        action: &str,
+        parameter: Vec<OwnedValue>,
+    ) -> zbus::Result<()>;
+}
@@ -0,0 +1,493 @@
+use std::{
+    collections::HashMap,
+    fs::{DirBuilder, OpenOptions},
+    io::{ErrorKind, Read, Write},
+    os::unix::fs::{DirBuilderExt, OpenOptionsExt},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use ashpd::{
+    desktop::{
+        notification::{Category, Priority},
+        Icon,
+    },
+    zvariant::{OwnedValue, Value},
+    AppID, PortalError,
+};
+use futures_util::{stream, StreamExt};
+
+use super::{Notification, NotificationImpl, NotificationSignalEmitter};
+use crate::{RawOptions, Result};
+
+const DESTINATION: &str = "org.freedesktop.Notifications";
+const PATH: &str = "/org/freedesktop/Notifications";
+const INTERFACE: &str = "org.freedesktop.Notifications";
+
+/// The action of the daemon activating the notification itself.
+const DEFAULT_ACTION: &str = "default";
+/// The prefix of the actions of the daemon activating the buttons, followed
+/// by their index.
+const BUTTON_ACTION_PREFIX: &str = "button-";
+
+/// A notification shown by the daemon.
+struct Shown {
+    app_id: Option<AppID>,
+    id: String,
+    daemon_id: u32,
+    /// The portal actions and targets, by daemon action.
+    actions: HashMap<String, (String, Option<OwnedValue>)>,
+    /// The file the icon was written to, removed with the notification.
+    icon_file: Option<PathBuf>,
+}
+
+impl Drop for Shown {
+    fn drop(&mut self) {
+        if let Some(path) = self.icon_file.take() {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+#[derive(Default)]
+struct Inner {
+    shown: Mutex<Vec<Shown>>,
+    signal_emitter: Mutex<Option<Arc<dyn NotificationSignalEmitter>>>,
+    icon_files: AtomicU64,
+}
+
+impl Inner {
+    async fn action_invoked(&self, daemon_id: u32, daemon_action: &str) {
+        let invoked = self
+            .shown
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|shown| shown.daemon_id == daemon_id)
+            .and_then(|shown| {
+                let (action, target) = shown.actions.get(daemon_action)?;
+                let target = target.as_ref().and_then(|t| t.try_clone().ok());
+                Some((
+                    shown.app_id.clone(),
+                    shown.id.clone(),
+                    action.clone(),
+                    target,
+                ))
+            });
+        let Some((app_id, id, action, target)) = invoked else {
+            return;
+        };
+        let signal_emitter = self.signal_emitter.lock().unwrap().clone();
+        if let Some(signal_emitter) = signal_emitter {
+            let parameter = target.into_iter().collect();
+            if let Err(_err) = signal_emitter
+                .emit_action_invoked(app_id.as_ref(), &id, &action, parameter)
+                .await
+            {
+                #[cfg(feature = "tracing")]
+                tracing::warn!("Failed to emit ActionInvoked: {_err}");
+            }
+        }
+    }
+
+    fn closed(&self, daemon_id: u32) {
+        self.shown
+            .lock()
+            .unwrap()
+            .retain(|shown| shown.daemon_id != daemon_id);
+    }
+
+    /// Writes the image of a bytes or file descriptor icon to a file, as the
+    /// daemon only takes paths and names.
+    async fn write_icon(&self, icon: &Icon) -> std::io::Result<Option<PathBuf>> {
+        let data = match icon {
+            Icon::Bytes(bytes) if bytes.len() as u64 > MAX_ICON_SIZE => {
+                return Err(icon_too_large())
+            }
+            Icon::Bytes(bytes) => IconData::Bytes(bytes.to_vec()),
+            Icon::FileDescriptor(fd) => IconData::File(std::fs::File::from(fd.try_clone()?)),
+            _ => return Ok(None),
+        };
+        let index = self.icon_files.fetch_add(1, Ordering::SeqCst);
+        tokio::task::spawn_blocking(move || write_icon(data, index))
+            .await
+            .map_err(std::io::Error::other)?
+            .map(Some)
+    }
+}
+
+/// The largest icon written for the daemon, in bytes.
+const MAX_ICON_SIZE: u64 = 4 * 1024 * 1024;
+
+fn icon_too_large() -> std::io::Error {
+    std::io::Error::new(
+        ErrorKind::InvalidData,
+        format!("The icon is larger than {MAX_ICON_SIZE} bytes"),
+    )
+}
+
+enum IconData {
+    Bytes(Vec<u8>),
+    File(std::fs::File),
+}
+
+/// Writes the icon to a new file only readable by the user, in the runtime
+/// directory as it is private to the user unlike `/tmp`.
+fn write_icon(data: IconData, index: u64) -> std::io::Result<PathBuf> {
+    let data = match data {
+        IconData::Bytes(bytes) => bytes,
+        IconData::File(file) => {
+            let mut data = Vec::new();
+            file.take(MAX_ICON_SIZE + 1).read_to_end(&mut data)?;
+            if data.len() as u64 > MAX_ICON_SIZE {
+                return Err(icon_too_large());
+            }
+            data
+        }
+    };
+    let runtime_dir = std::env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .ok_or_else(|| std::io::Error::new(ErrorKind::NotFound, "XDG_RUNTIME_DIR is not set"))?;
+    let dir = runtime_dir.join("ashpd-notifications");
+    if let Err(err) = DirBuilder::new().mode(0o700).create(&dir) {
+        if err.kind() != ErrorKind::AlreadyExists {
+            return Err(err);
+        }
+    }
+    for attempt in 0.. {
+        let path = dir.join(format!("{}-{index}-{attempt}", std::process::id()));
+        match OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(0o600)
+            .open(&path)
+        {
+            Ok(mut file) => {
+                file.write_all(&data)?;
+                return Ok(path);
+            }
+            Err(err) if err.kind() == ErrorKind::AlreadyExists => continue,
+            Err(err) => return Err(err),
+        }
+    }
+    unreachable!()
+}
+
+/// A [`NotificationImpl`] showing the notifications with the
+/// `org.freedesktop.Notifications` daemon of the session.
+///
+/// The title, body, icon, buttons and the hints supported by the
+/// [notifications specification](https://specifications.freedesktop.org/notification-spec/latest/)
+/// are translated, and the actions invoked in the daemon are sent back as
+/// `ActionInvoked` signals. Notifications added again with the same id
+/// replace the previous ones.
+///
+/// ```rust,no_run
+/// use ashpd_backend::{notification::DesktopNotifications, Builder};
+///
+/// async fn run() -> ashpd_backend::Result<()> {
+///     Builder::new("org.freedesktop.impl.portal.desktop.example")?
+///         .notification(DesktopNotifications::new().await?)
+///         .run()
+///         .await
+/// }
+/// ```
+pub struct DesktopNotifications {
+    proxy: zbus::Proxy<'static>,
+    inner: Arc<Inner>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl DesktopNotifications {
+    /// Connects to the notifications daemon of the session bus.
+    ///
+    /// Has to be called from a tokio runtime.
+    pub async fn new() -> Result<Self> {
+        let cnx = zbus::Connection::session().await?;
+        let proxy = zbus::Proxy::new(&cnx, DESTINATION, PATH, INTERFACE).await?;
+        let action_invoked = proxy.receive_signal("ActionInvoked").await?;
+        let closed = proxy.receive_signal("NotificationClosed").await?;
+
+        let inner = Arc::new(Inner::default());
+        let task = tokio::spawn({
+            let inner = Arc::clone(&inner);
+            async move {
+                let mut events = stream::select(
+                    action_invoked.map(|msg| {
+                        let (daemon_id, action) = msg.body().deserialize::<(u32, String)>().ok()?;
+                        Some((daemon_id, Some(action)))
+                    }),
+                    closed.map(|msg| {
+                        let (daemon_id, _reason) = msg.body().deserialize::<(u32, u32)>().ok()?;
+                        Some((daemon_id, None))
+                    }),
+                );
+                while let Some(event) = events.next().await {
+                    match event {
+                        Some((daemon_id, Some(action))) => {
+                            inner.action_invoked(daemon_id, &action).await
+                        }
+                        Some((daemon_id, None)) => inner.closed(daemon_id),
+                        None => (),
+                    }
+                }
+            }
+        });
+        Ok(Self { proxy, inner, task })
+    }
+
+    fn daemon_id(&self, app_id: Option<&AppID>, id: &str) -> Option<u32> {
+        self.inner
+            .shown
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|shown| shown.app_id.as_ref() == app_id && shown.id == id)
+            .map(|shown| shown.daemon_id)
+    }
+}
+
+impl std::fmt::Debug for DesktopNotifications {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DesktopNotifications")
+            .field("shown", &self.inner.shown.lock().unwrap().len())
+            .finish()
+    }
+}
+
+impl Drop for DesktopNotifications {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+impl NotificationImpl for DesktopNotifications {
+    async fn add_notification(
+        &self,
+        app_id: Option<AppID>,
+        id: &str,
+        notification: RawOptions<Notification>,
+    ) -> Result<()> {
+        let replaces_id = self.daemon_id(app_id.as_ref(), id).unwrap_or(0);
+
+        let mut actions = HashMap::new();
+        let mut daemon_actions = Vec::new();
+        if let Some(action) = notification.default_action() {
+            let target = notification
+                .default_action_target()
+                .and_then(|t| t.try_clone().ok());
+            actions.insert(DEFAULT_ACTION.to_owned(), (action.to_owned(), target));
+            daemon_actions.extend([DEFAULT_ACTION.to_owned(), String::new()]);
+        }
+        for (index, button) in notification.buttons().iter().enumerate() {
+            let (Some(label), Some(action)) = (button.label(), button.action()) else {
+                continue;
+            };
+            let daemon_action = format!("{BUTTON_ACTION_PREFIX}{index}");
+            let target = button.target().and_then(|t| t.try_clone().ok());
+            actions.insert(daemon_action.clone(), (action.to_owned(), target));
+            daemon_actions.extend([daemon_action, label.to_owned()]);
+        }
+
+        let mut app_icon = String::new();
+        let mut icon_file = None;
+        match notification.icon() {
+            Some(Icon::Names(names)) => app_icon = names.first().cloned().unwrap_or_default(),
+            Some(Icon::Uri(uri)) => app_icon = uri.to_string(),
+            Some(icon) => match self.inner.write_icon(icon).await {
+                Ok(Some(path)) => {
+                    app_icon = path.to_string_lossy().into_owned();
+                    icon_file = Some(path);
+                }
+                Ok(None) => (),
+                Err(_err) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!("Failed to write the notification icon: {_err}");
+                }
+            },
+            None => (),
+        }
+
+        let body = match (notification.markup_body(), notification.body()) {
+            (Some(markup_body), _) => markup_body.to_owned(),
+            (None, Some(body)) => escape_markup(body),
+            (None, None) => String::new(),
+        };
+        let hints = hints(app_id.as_ref(), &notification);
+
+        let daemon_id = self
+            .proxy
+            .call::<_, _, u32>(
+                "Notify",
+                &(
+                    app_id
+                        .as_ref()
+                        .map(|app_id| app_id.as_ref())
+                        .unwrap_or_default(),
+                    replaces_id,
+                    app_icon.as_str(),
+                    notification.title().unwrap_or_default(),
+                    body.as_str(),
+                    &daemon_actions,
+                    &hints,
+                    -1i32,
+                ),
+            )
+            .await
+            .map_err(|err| {
+                PortalError::Failed(format!("Failed to show the notification: {err}"))
+            })?;
+
+        let mut shown = self.inner.shown.lock().unwrap();
+        shown.retain(|shown| !(shown.app_id == app_id && shown.id == id));
+        shown.push(Shown {
+            app_id,
+            id: id.to_owned(),
+            daemon_id,
+            actions,
+            icon_file,
+        });
+        Ok(())
+    }
+
+    async fn remove_notification(&self, app_id: Option<AppID>, id: &str) -> Result<()> {
+        let Some(daemon_id) = self.daemon_id(app_id.as_ref(), id) else {
+            return Ok(());
+        };
+        self.inner.closed(daemon_id);
+        self.proxy
+            .call::<_, _, ()>("CloseNotification", &(daemon_id,))
+            .await
+            .map_err(|err| PortalError::Failed(format!("Failed to remove the notification: {err}")))
+    }
+
+    fn set_signal_emitter(&mut self, signal_emitter: Arc<dyn NotificationSignalEmitter>) {
+        self.inner
+            .signal_emitter
+            .lock()
+            .unwrap()
+            .replace(signal_emitter);
+    }
+}
+
+/// The hints of the specification matching the notification.
+fn hints<'a>(
+    app_id: Option<&'a AppID>,
+    notification: &Notification,
+) -> HashMap<&'a str, Value<'a>> {
+    let mut hints = HashMap::new();
+    if let Some(app_id) = app_id {
+        hints.insert("desktop-entry", Value::from(app_id.as_ref()));
+    }
+    if let Some(priority) = notification.priority() {
+        let urgency: u8 = match priority {
+            Priority::Low => 0,
+            Priority::Normal | Priority::High => 1,
+            Priority::Urgent => 2,
+        };
+        hints.insert("urgency", Value::from(urgency));
+    }
+    let category = match notification.category() {
+        Some(Category::ImMessage) => Some("im.received"),
+        Some(Category::IncomingCall) => Some("call.incoming"),
+        Some(Category::OngoingCall) => Some("call"),
+        Some(Category::MissedCall) => Some("call.unanswered"),
+        Some(Category::Other(other)) => Some(other.as_str()),
+        _ => None,
+    };
+    if let Some(category) = category {
+        hints.insert("category", Value::from(category.to_owned()));
+    }
+    for hint in notification.display_hints() {
+        match hint.as_str() {
+            "transient" => hints.insert("transient", Value::from(true)),
+            "persistent" => hints.insert("resident", Value::from(true)),
+            _ => None,
+        };
+    }
+    if notification
+        .sound()
+        .and_then(|sound| sound.downcast_ref::<&str>().ok())
+        == Some("silent")
+    {
+        hints.insert("suppress-sound", Value::from(true));
+    }
+    hints
+}
+
+/// Escapes a plain text body, the daemons supporting markup would interpret
+/// it otherwise.
+fn escape_markup(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use ashpd::{
+        zvariant::{serialized::Context, to_bytes, Value, LE},
+        AppID,
+    };
+
+    use super::{escape_markup, hints, Notification};
+
+    fn from_options(options: HashMap<&str, Value<'_>>) -> Notification {
+        let data = to_bytes(Context::new_dbus(LE, 0), &options).unwrap();
+        data.deserialize().unwrap().0
+    }
+
+    #[test]
+    fn markup() {
+        assert_eq!(
+            escape_markup("Tom & <b>Jerry</b>"),
+            "Tom &amp; &lt;b&gt;Jerry&lt;/b&gt;"
+        );
+        assert_eq!(escape_markup("Plain text"), "Plain text");
+    }
+
+    #[test]
+    fn notification_hints() {
+        let app_id = AppID::try_from("org.example.App").unwrap();
+        let notification = from_options(HashMap::from([
+            ("priority", Value::from("urgent")),
+            ("category", Value::from("im.message")),
+            (
+                "display-hint",
+                Value::from(vec!["transient", "persistent", "show-as-new"]),
+            ),
+            ("sound", Value::from("silent")),
+        ]));
+        let daemon_hints = hints(Some(&app_id), &notification);
+        assert_eq!(daemon_hints.len(), 6);
+        assert_eq!(
+            daemon_hints["desktop-entry"],
+            Value::from("org.example.App")
+        );
+        assert_eq!(daemon_hints["urgency"], Value::from(2u8));
+        assert_eq!(daemon_hints["category"], Value::from("im.received"));
+        assert_eq!(daemon_hints["transient"], Value::from(true));
+        assert_eq!(daemon_hints["resident"], Value::from(true));
+        assert_eq!(daemon_hints["suppress-sound"], Value::from(true));
+
+        let notification = from_options(HashMap::from([
+            ("priority", Value::from("high")),
+            ("sound", Value::from("default")),
+        ]));
+        let daemon_hints = hints(None, &notification);
+        assert_eq!(daemon_hints.len(), 1);
+        assert_eq!(daemon_hints["urgency"], Value::from(1u8));
+    }
+}
@@ -0,0 +1,488 @@
+use std::{collections::HashMap, ops::Deref};
+
+use serde::{
+    de::{
+        value::BorrowedStrDeserializer, DeserializeOwned, DeserializeSeed, Deserializer, Error,
+        IntoDeserializer, MapAccess, SeqAccess, Visitor,
+    },
+    forward_to_deserialize_any, Deserialize,
+};
+use zbus::zvariant::{self, OwnedValue, Signature, Type, Value};
+
+/// The options of a request, deserialized into `T`, along with the raw
+/// dictionary they were sent as.
+///
+/// The typed options are reachable through [`Deref`]. The raw dictionary
+/// gives access to the keys `T` doesn't know about, the ones added by a newer
+/// version of the frontend or vendor specific ones.
+#[derive(Debug)]
+pub struct RawOptions<T> {
+    options: T,
+    raw: HashMap<String, OwnedValue>,
+}
+
+impl<T> RawOptions<T> {
+    /// The options as sent by the frontend.
+    pub fn raw(&self) -> &HashMap<String, OwnedValue> {
+        &self.raw
+    }
+
+    /// The value of the option `key`, if set and of type `V`.
+    pub fn get<V>(&self, key: &str) -> Option<V>
+    where
+        V: TryFrom<OwnedValue>,
+    {
+        self.raw.get(key)?.try_clone().ok()?.try_into().ok()
+    }
+
+    /// The typed options.
+    pub fn into_inner(self) -> T {
+        self.options
+    }
+
+    /// The typed options and the options as sent by the frontend.
+    pub fn into_parts(self) -> (T, HashMap<String, OwnedValue>) {
+        (self.options, self.raw)
+    }
+}
+
+impl<T> Deref for RawOptions<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.options
+    }
+}
+
+impl<T: Type> Type for RawOptions<T> {
+    const SIGNATURE: &'static Signature = T::SIGNATURE;
+}
+
+impl<'de, T> Deserialize<'de> for RawOptions<T>
+where
+    T: DeserializeOwned + Type,
+{
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let raw = HashMap::<String, OwnedValue>::deserialize(deserializer)?;
+        // The typed options are read from the values of the raw dictionary,
+        // which are kept as sent.
+        let signatures = raw.values().map(|value| Signatures::new(value)).collect();
+        let options = T::deserialize(OptionsDeserializer {
+            raw: &raw,
+            signatures: &signatures,
+        })
+        .map_err(D::Error::custom)?;
+        Ok(Self { options, raw })
+    }
+}
+
+/// The signatures of a value and of the values it contains.
+///
+/// A variant starts with the signature of its value, which is deserialized
+/// from a borrowed string, so they are computed before deserializing the
+/// value.
+struct Signatures {
+    signature: String,
+    children: Vec<Signatures>,
+}
+
+impl Signatures {
+    fn new(value: &Value<'_>) -> Self {
+        let children = match value {
+            Value::Value(value) => vec![Self::new(value)],
+            Value::Array(array) => array.iter().map(Self::new).collect(),
+            Value::Dict(dict) => dict
+                .iter()
+                .flat_map(|(key, value)| [Self::new(key), Self::new(value)])
+                .collect(),
+            Value::Structure(structure) => structure.fields().iter().map(Self::new).collect(),
+            _ => Vec::new(),
+        };
+        Self {
+            signature: value.value_signature().to_string(),
+            children,
+        }
+    }
+}
+
+/// Deserializes the options dictionary from the raw one.
+struct OptionsDeserializer<'de> {
+    raw: &'de HashMap<String, OwnedValue>,
+    signatures: &'de Vec<Signatures>,
+}
+
+impl<'de> Deserializer<'de> for OptionsDeserializer<'de> {
+    type Error = zvariant::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> zvariant::Result<V::Value> {
+        visitor.visit_map(EntriesAccess {
+            entries: self
+                .raw
+                .iter()
+                .zip(self.signatures)
+                .map(|((key, value), signatures)| {
+                    (
+                        BorrowedStrDeserializer::new(key.as_str()),
+                        VariantDeserializer { value, signatures },
+                    )
+                }),
+            value: None,
+        })
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str string bytes byte_buf
+        option unit unit_struct newtype_struct seq tuple tuple_struct map struct
+        enum identifier ignored_any
+    }
+}
+
+/// Deserializes a [`Value`], the way it is deserialized from a message.
+struct ValueDeserializer<'de> {
+    value: &'de Value<'de>,
+    signatures: &'de Signatures,
+}
+
+impl<'de> Deserializer<'de> for ValueDeserializer<'de> {
+    type Error = zvariant::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> zvariant::Result<V::Value> {
+        let children = &self.signatures.children;
+        match self.value {
+            Value::U8(v) => visitor.visit_u8(*v),
+            Value::Bool(v) => visitor.visit_bool(*v),
+            Value::I16(v) => visitor.visit_i16(*v),
+            Value::U16(v) => visitor.visit_u16(*v),
+            Value::I32(v) => visitor.visit_i32(*v),
+            Value::U32(v) => visitor.visit_u32(*v),
+            Value::I64(v) => visitor.visit_i64(*v),
+            Value::U64(v) => visitor.visit_u64(*v),
+            Value::F64(v) => visitor.visit_f64(*v),
+            Value::Str(v) => visitor.visit_borrowed_str(v.as_str()),
+            Value::Signature(v) => visitor.visit_string(v.to_string()),
+            Value::ObjectPath(v) => visitor.visit_borrowed_str(v.as_str()),
+            Value::Value(value) => VariantDeserializer {
+                value,
+                signatures: &children[0],
+            }
+            .deserialize_any(visitor),
+            Value::Array(array) => visitor.visit_seq(ElementsAccess {
+                elements: array.iter().zip(children),
+            }),
+            Value::Dict(dict) => visitor.visit_map(EntriesAccess {
+                entries: dict
+                    .iter()
+                    .zip(children.chunks(2))
+                    .map(|((key, value), s)| {
+                        (
+                            ValueDeserializer {
+                                value: key,
+                                signatures: &s[0],
+                            },
+                            ValueDeserializer {
+                                value,
+                                signatures: &s[1],
+                            },
+                        )
+                    }),
+                value: None,
+            }),
+            Value::Structure(structure) => visitor.visit_seq(ElementsAccess {
+                elements: structure.fields().iter().zip(children),
+            }),
+            #[cfg(unix)]
+            Value::Fd(fd) => {
+                use std::os::fd::AsRawFd;
+                visitor.visit_i32(fd.as_raw_fd())
+            }
+            // The maybe values of GVariant, when zvariant is built with it.
+            #[allow(unreachable_patterns)]
+            _ => Err(zvariant::Error::IncorrectType),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> zvariant::Result<V::Value> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> zvariant::Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> zvariant::Result<V::Value> {
+        match self.value {
+            Value::Str(v) => visitor.visit_enum(v.as_str().into_deserializer()),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> zvariant::Result<V::Value> {
+        match self.value {
+            Value::Array(array) if array.element_signature() == &Signature::U8 => {
+                let bytes = array
+                    .iter()
+                    .map(|byte| byte.downcast_ref::<u8>())
+                    .collect::<zvariant::Result<Vec<_>>>()?;
+                visitor.visit_byte_buf(bytes)
+            }
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> zvariant::Result<V::Value> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> zvariant::Result<V::Value> {
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str string
+        unit unit_struct seq tuple tuple_struct map struct identifier
+    }
+}
+
+/// Deserializes a variant holding `value`, as the signature of the value
+/// followed by the value.
+struct VariantDeserializer<'de> {
+    value: &'de Value<'de>,
+    signatures: &'de Signatures,
+}
+
+impl<'de> Deserializer<'de> for VariantDeserializer<'de> {
+    type Error = zvariant::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> zvariant::Result<V::Value> {
+        visitor.visit_seq(VariantAccess {
+            variant: Some(self),
+            signature: true,
+        })
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> zvariant::Result<V::Value> {
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str string bytes byte_buf
+        option unit unit_struct newtype_struct seq tuple tuple_struct map struct
+        enum identifier
+    }
+}
+
+struct VariantAccess<'de> {
+    variant: Option<VariantDeserializer<'de>>,
+    signature: bool,
+}
+
+impl<'de> SeqAccess<'de> for VariantAccess<'de> {
+    type Error = zvariant::Error;
+
+    fn next_element_seed<S: DeserializeSeed<'de>>(
+        &mut self,
+        seed: S,
+    ) -> zvariant::Result<Option<S::Value>> {
+        let Some(variant) = &self.variant else {
+            return Ok(None);
+        };
+        if std::mem::take(&mut self.signature) {
+            let signature = variant.signatures.signature.as_str();
+            return seed
+                .deserialize(BorrowedStrDeserializer::new(signature))
+                .map(Some);
+        }
+        let variant = self.variant.take().unwrap();
+        seed.deserialize(ValueDeserializer {
+            value: variant.value,
+            signatures: variant.signatures,
+        })
+        .map(Some)
+    }
+}
+
+struct ElementsAccess<I> {
+    elements: I,
+}
+
+impl<'de, I> SeqAccess<'de> for ElementsAccess<I>
+where
+    I: Iterator<Item = (&'de Value<'de>, &'de Signatures)>,
+{
+    type Error = zvariant::Error;
+
+    fn next_element_seed<S: DeserializeSeed<'de>>(
+        &mut self,
+        seed: S,
+    ) -> zvariant::Result<Option<S::Value>> {
+        self.elements
+            .next()
+            .map(|(value, signatures)| seed.deserialize(ValueDeserializer { value, signatures }))
+            .transpose()
+    }
+}
+
+struct EntriesAccess<I, V> {
+    entries: I,
+    value: Option<V>,
+}
+
+impl<'de, I, K, V> MapAccess<'de> for EntriesAccess<I, V>
+where
+    I: Iterator<Item = (K, V)>,
+    K: Deserializer<'de, Error = zvariant::Error>,
+    V: Deserializer<'de, Error = zvariant::Error>,
+{
+    type Error = zvariant::Error;
+
+    fn next_key_seed<S: DeserializeSeed<'de>>(
+        &mut self,
+        seed: S,
+    ) -> zvariant::Result<Option<S::Value>> {
+        let Some((key, value)) = self.entries.next() else {
+            return Ok(None);
+        };
+        self.value = Some(value);
+        seed.deserialize(key).map(Some)
+    }
+
+    fn next_value_seed<S: DeserializeSeed<'de>>(&mut self, seed: S) -> zvariant::Result<S::Value> {
+        let value = self
+            .value
+            .take()
+            .ok_or_else(|| zvariant::Error::custom("value requested before its key"))?;
+        seed.deserialize(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, fs::File, os::fd::AsRawFd};
+
+    use ashpd::{
+        desktop::{
+            file_chooser::{Choice, FileFilter},
+            Icon,
+        },
+        zvariant::{serialized::Context, to_bytes, Fd, OwnedValue, SerializeDict, Type, Value, LE},
+        FilePath,
+    };
+    use serde::{de::DeserializeOwned, Serialize};
+
+    use super::RawOptions;
+    use crate::{file_chooser::OpenFileOptions, notification::Notification};
+
+    fn from_options<T>(options: impl Serialize + Type) -> RawOptions<T>
+    where
+        T: DeserializeOwned + Type,
+    {
+        let data = to_bytes(Context::new_dbus(LE, 0), &options).unwrap();
+        data.deserialize().unwrap().0
+    }
+
+    #[derive(SerializeDict, Type)]
+    #[zvariant(signature = "dict")]
+    struct SentOpenFileOptions {
+        multiple: bool,
+        filters: Vec<FileFilter>,
+        current_filter: FileFilter,
+        choices: Vec<Choice>,
+        current_folder: FilePath,
+        #[zvariant(rename = "org.example.vendor")]
+        vendor: u32,
+    }
+
+    #[test]
+    fn typed_and_raw() {
+        let filter = FileFilter::new("Images")
+            .mimetype("image/png")
+            .glob("*.png");
+        let options = from_options::<OpenFileOptions>(SentOpenFileOptions {
+            multiple: true,
+            filters: vec![filter.clone()],
+            current_filter: filter.clone(),
+            choices: vec![Choice::new("encoding", "Encoding", "utf8")
+                .insert("utf8", "Unicode (UTF-8)")
+                .insert("latin15", "Western")],
+            current_folder: FilePath::new("/home/user").unwrap(),
+            vendor: 42,
+        });
+
+        assert_eq!(options.multiple(), Some(true));
+        assert_eq!(options.directory(), None);
+        assert_eq!(options.filters(), std::slice::from_ref(&filter));
+        assert_eq!(options.current_filter(), Some(&filter));
+        let choice = &options.choices()[0];
+        assert_eq!(choice.id(), "encoding");
+        assert_eq!(
+            choice.pairs(),
+            [("utf8", "Unicode (UTF-8)"), ("latin15", "Western")]
+        );
+        assert_eq!(
+            options.current_folder(),
+            Some(&FilePath::new("/home/user").unwrap())
+        );
+
+        assert_eq!(options.raw().len(), 6);
+        assert_eq!(options.get::<u32>("org.example.vendor"), Some(42));
+        assert_eq!(options.get::<bool>("multiple"), Some(true));
+    }
+
+    #[test]
+    fn variants() {
+        let file = File::open("/dev/null").unwrap();
+        let options = from_options::<Notification>(HashMap::from([
+            ("title", Value::from("Title")),
+            (
+                "icon",
+                Value::from(("file-descriptor", Value::from(Fd::from(&file)))),
+            ),
+            ("default-action", Value::from("open")),
+            (
+                "default-action-target",
+                Value::Value(Box::new(Value::from(7u32))),
+            ),
+            (
+                "buttons",
+                Value::from(vec![HashMap::from([
+                    ("label", Value::from("Reply")),
+                    ("action", Value::from("reply")),
+                    ("target", Value::from("thread")),
+                ])]),
+            ),
+            ("sound", Value::from("silent")),
+        ]));
+
+        assert_eq!(options.title(), Some("Title"));
+        assert!(matches!(
+            options.icon(),
+            Some(Icon::FileDescriptor(fd)) if fd.as_raw_fd() != file.as_raw_fd()
+        ));
+        let target = OwnedValue::try_from(Value::Value(Box::new(Value::from(7u32)))).unwrap();
+        assert_eq!(options.default_action_target(), Some(&target));
+        let button = &options.buttons()[0];
+        assert_eq!(button.label(), Some("Reply"));
+        assert_eq!(button.action(), Some("reply"));
+        assert_eq!(
+            button.target(),
+            Some(&OwnedValue::try_from(Value::from("thread")).unwrap())
+        );
+        assert_eq!(
+            options.sound(),
+            Some(&OwnedValue::try_from(Value::from("silent")).unwrap())
+        );
+        assert_eq!(options.raw()["default-action-target"], target);
+    }
+}
@@ -1,91 +1,248 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, future::Future, sync::Arc};
 
-use async_trait::async_trait;
-
-use crate::{
+use ashpd::{
     documents::{DocumentID, Permission},
     zbus::object_server::SignalEmitter,
     zvariant::{OwnedValue, Value},
     AppID, PortalError,
 };
+use futures_util::future::BoxFuture;
+
+use crate::backend::PortalInterface;
 
-#[async_trait]
 pub trait PermissionStoreEmitter: Send + Sync {
-    async fn emit_document_changed(
-        &self,
-        table: &str,
+    fn emit_document_changed<'a>(
+        &'a self,
+        table: &'a str,
         id: DocumentID,
         deleted: bool,
-        data: Value<'_>,
+        data: Value<'a>,
         permissions: HashMap<AppID, Vec<Permission>>,
-    ) -> zbus::Result<()>;
+    ) -> BoxFuture<'a, zbus::Result<()>>;
 }
 
-#[async_trait]
 pub trait PermissionStoreImpl: Send + Sync {
-    async fn lookup(
+    fn lookup(
         &self,
         table: &str,
         id: DocumentID,
-    ) -> Result<(HashMap<AppID, Vec<Permission>>, OwnedValue), PortalError>;
+    ) -> impl Future<Output = Result<(HashMap<AppID, Vec<Permission>>, OwnedValue), PortalError>> + Send;
 
-    async fn set(
+    fn set(
         &self,
         table: &str,
         create: bool,
         id: DocumentID,
         app_permissions: HashMap<AppID, Vec<Permission>>,
         data: Value<'_>,
-    ) -> Result<(), PortalError>;
+    ) -> impl Future<Output = Result<(), PortalError>> + Send;
 
-    async fn delete(&self, table: &str, id: DocumentID) -> Result<(), PortalError>;
+    fn delete(
+        &self,
+        table: &str,
+        id: DocumentID,
+    ) -> impl Future<Output = Result<(), PortalError>> + Send;
 
-    async fn set_value(
+    fn set_value(
         &self,
         table: &str,
         create: bool,
         id: DocumentID,
         data: Value<'_>,
-    ) -> Result<(), PortalError>;
+    ) -> impl Future<Output = Result<(), PortalError>> + Send;
 
-    async fn list(&self, table: &str) -> Result<Vec<DocumentID>, PortalError>;
+    fn list(
+        &self,
+        table: &str,
+    ) -> impl Future<Output = Result<Vec<DocumentID>, PortalError>> + Send;
 
-    async fn get_permission(
+    fn get_permission(
         &self,
         table: &str,
         id: DocumentID,
         app: AppID,
-    ) -> Result<Vec<Permission>, PortalError>;
+    ) -> impl Future<Output = Result<Vec<Permission>, PortalError>> + Send;
 
-    async fn set_permission(
+    fn set_permission(
         &self,
         table: &str,
         create: bool,
         id: DocumentID,
         app: AppID,
         permissions: Vec<Permission>,
-    ) -> Result<(), PortalError>;
+    ) -> impl Future<Output = Result<(), PortalError>> + Send;
 
-    async fn delete_permission(
+    fn delete_permission(
         &self,
         table: &str,
         id: DocumentID,
         app: AppID,
-    ) -> Result<(), PortalError>;
+    ) -> impl Future<Output = Result<(), PortalError>> + Send;
 
     // Set the signal emitter, allowing to notify of changes.
     fn set_signal_emitter(&mut self, signal_emitter: Arc<dyn PermissionStoreEmitter>);
 }
 
+/// Object safe counterpart of [`PermissionStoreImpl`], used to store the implementation.
+pub(crate) trait DynPermissionStoreImpl: Send + Sync {
+    #[allow(clippy::type_complexity)]
+    fn lookup<'a>(
+        &'a self,
+        table: &'a str,
+        id: DocumentID,
+    ) -> BoxFuture<'a, Result<(HashMap<AppID, Vec<Permission>>, OwnedValue), PortalError>>;
+
+    fn set<'a>(
+        &'a self,
+        table: &'a str,
+        create: bool,
+        id: DocumentID,
+        app_permissions: HashMap<AppID, Vec<Permission>>,
+        data: Value<'a>,
+    ) -> BoxFuture<'a, Result<(), PortalError>>;
+
+    fn delete<'a>(
+        &'a self,
+        table: &'a str,
+        id: DocumentID,
+    ) -> BoxFuture<'a, Result<(), PortalError>>;
+
+    fn set_value<'a>(
+        &'a self,
+        table: &'a str,
+        create: bool,
+        id: DocumentID,
+        data: Value<'a>,
+    ) -> BoxFuture<'a, Result<(), PortalError>>;
+
+    fn list<'a>(&'a self, table: &'a str) -> BoxFuture<'a, Result<Vec<DocumentID>, PortalError>>;
+
+    fn get_permission<'a>(
+        &'a self,
+        table: &'a str,
+        id: DocumentID,
+        app: AppID,
+    ) -> BoxFuture<'a, Result<Vec<Permission>, PortalError>>;
+
+    fn set_permission<'a>(
+        &'a self,
+        table: &'a str,
+        create: bool,
+        id: DocumentID,
+        app: AppID,
+        permissions: Vec<Permission>,
+    ) -> BoxFuture<'a, Result<(), PortalError>>;
+
+    fn delete_permission<'a>(
+        &'a self,
+        table: &'a str,
+        id: DocumentID,
+        app: AppID,
+    ) -> BoxFuture<'a, Result<(), PortalError>>;
+}
+
+impl<T: PermissionStoreImpl> DynPermissionStoreImpl for T {
+    fn lookup<'a>(
+        &'a self,
+        table: &'a str,
+        id: DocumentID,
+    ) -> BoxFuture<'a, Result<(HashMap<AppID, Vec<Permission>>, OwnedValue), PortalError>> {
+        Box::pin(PermissionStoreImpl::lookup(self, table, id))
+    }
+
+    fn set<'a>(
+        &'a self,
+        table: &'a str,
+        create: bool,
+        id: DocumentID,
+        app_permissions: HashMap<AppID, Vec<Permission>>,
+        data: Value<'a>,
+    ) -> BoxFuture<'a, Result<(), PortalError>> {
+        Box::pin(PermissionStoreImpl::set(
+            self,
+            table,
+            create,
+            id,
+            app_permissions,
+            data,
+        ))
+    }
+
+    fn delete<'a>(
+        &'a self,
+        table: &'a str,
+        id: DocumentID,
+    ) -> BoxFuture<'a, Result<(), PortalError>> {
+        Box::pin(PermissionStoreImpl::delete(self, table, id))
+    }
+
+    fn set_value<'a>(
+        &'a self,
+        table: &'a str,
+        create: bool,
+        id: DocumentID,
+        data: Value<'a>,
+    ) -> BoxFuture<'a, Result<(), PortalError>> {
+        Box::pin(PermissionStoreImpl::set_value(
+            self, table, create, id, data,
+        ))
+    }
+
+    fn list<'a>(&'a self, table: &'a str) -> BoxFuture<'a, Result<Vec<DocumentID>, PortalError>> {
+        Box::pin(PermissionStoreImpl::list(self, table))
+    }
+
+    fn get_permission<'a>(
+        &'a self,
+        table: &'a str,
+        id: DocumentID,
+        app: AppID,
+    ) -> BoxFuture<'a, Result<Vec<Permission>, PortalError>> {
+        Box::pin(PermissionStoreImpl::get_permission(self, table, id, app))
+    }
+
+    fn set_permission<'a>(
+        &'a self,
+        table: &'a str,
+        create: bool,
+        id: DocumentID,
+        app: AppID,
+        permissions: Vec<Permission>,
+    ) -> BoxFuture<'a, Result<(), PortalError>> {
+        Box::pin(PermissionStoreImpl::set_permission(
+            self,
+            table,
+            create,
+            id,
+            app,
+            permissions,
+        ))
+    }
+
+    fn delete_permission<'a>(
+        &'a self,
+        table: &'a str,
+        id: DocumentID,
+        app: AppID,
+    ) -> BoxFuture<'a, Result<(), PortalError>> {
+        Box::pin(PermissionStoreImpl::delete_permission(self, table, id, app))
+    }
+}
+
 pub(crate) struct PermissionStoreInterface {
-    imp: Arc<dyn PermissionStoreImpl>,
+    imp: Arc<dyn DynPermissionStoreImpl>,
     #[allow(dead_code)]
     cnx: zbus::Connection,
+    version: u32,
 }
 
 impl PermissionStoreInterface {
-    pub fn new(imp: Arc<dyn PermissionStoreImpl>, cnx: zbus::Connection) -> Self {
-        Self { imp, cnx }
+    pub fn new(imp: Arc<dyn DynPermissionStoreImpl>, cnx: zbus::Connection) -> Self {
+        Self {
+            imp,
+            cnx,
+            version: 2,
+        }
     }
 
     pub async fn document_changed(
@@ -98,7 +255,7 @@ impl PermissionStoreInterface {
     ) -> zbus::Result<()> {
         let object_server = self.cnx.object_server();
         let iface_ref = object_server
-            .interface::<_, Self>(crate::proxy::DESKTOP_PATH)
+            .interface::<_, Self>(crate::DESKTOP_PATH)
             .await?;
         Self::changed(
             iface_ref.signal_emitter(),
@@ -112,26 +269,30 @@ impl PermissionStoreInterface {
     }
 }
 
-#[async_trait]
+impl PortalInterface for PermissionStoreInterface {
+    fn set_version(&mut self, version: u32) {
+        self.version = version;
+    }
+}
+
 impl PermissionStoreEmitter for PermissionStoreInterface {
-    async fn emit_document_changed(
-        &self,
-        table: &str,
+    fn emit_document_changed<'a>(
+        &'a self,
+        table: &'a str,
         id: DocumentID,
         deleted: bool,
-        data: Value<'_>,
+        data: Value<'a>,
         permissions: HashMap<AppID, Vec<Permission>>,
-    ) -> zbus::Result<()> {
-        self.document_changed(table, id, deleted, data, permissions)
-            .await
+    ) -> BoxFuture<'a, zbus::Result<()>> {
+        Box::pin(self.document_changed(table, id, deleted, data, permissions))
     }
 }
 
 #[zbus::interface(name = "org.freedesktop.impl.portal.PermissionStore")]
 impl PermissionStoreInterface {
-    #[zbus(property(emits_changed_signal = "const"), name = "version")]
+    #[zbus(property, name = "version")]
     fn version(&self) -> u32 {
-        2
+        self.version
     }
 
     #[zbus(out_args("permissions", "data"))]
@@ -1,20 +1,25 @@
-use std::sync::Arc;
+use std::{future::Future, sync::Arc};
 
-use async_trait::async_trait;
-
-use crate::{
-    backend::{
-        request::{Request, RequestImpl},
-        MaybeAppID, MaybeWindowIdentifier, Result,
-    },
+use ashpd::{
     desktop::{
         print::{PageSetup, PreparePrint, Settings},
-        request::Response,
-        HandleToken,
+        HandleToken, Response,
     },
     zvariant::{self, DeserializeDict, OwnedObjectPath},
     AppID, WindowIdentifierType,
 };
+use futures_util::future::BoxFuture;
+
+use crate::{
+    backend::PortalInterface,
+    request::{DynRequestImpl, Request, RequestImpl},
+    MaybeAppID, MaybeWindowIdentifier, RawOptions, Result,
+};
+
+#[cfg(feature = "cups")]
+mod cups;
+#[cfg(feature = "cups")]
+pub use cups::{Cups, Printer, PrinterState};
 
 #[derive(DeserializeDict, zvariant::Type)]
 #[zvariant(signature = "dict")]
@@ -50,10 +55,9 @@ impl PrintOptions {
     }
 }
 
-#[async_trait]
 pub trait PrintImpl: RequestImpl {
     #[allow(clippy::too_many_arguments)]
-    async fn prepare_print(
+    fn prepare_print(
         &self,
         token: HandleToken,
         app_id: Option<AppID>,
@@ -61,36 +65,117 @@ pub trait PrintImpl: RequestImpl {
         title: String,
         settings: Settings,
         page_setup: PageSetup,
-        options: PreparePrintOptions,
-    ) -> Result<PreparePrint>;
+        options: RawOptions<PreparePrintOptions>,
+    ) -> impl Future<Output = Result<PreparePrint>> + Send;
 
-    async fn print(
+    fn print(
         &self,
         token: HandleToken,
         app_id: Option<AppID>,
         parent_window: Option<WindowIdentifierType>,
         title: String,
         fd: zvariant::OwnedFd,
-        options: PrintOptions,
-    ) -> Result<()>;
+        options: RawOptions<PrintOptions>,
+    ) -> impl Future<Output = Result<()>> + Send;
+}
+
+/// Object safe counterpart of [`PrintImpl`], used to store the implementation.
+pub(crate) trait DynPrintImpl: DynRequestImpl {
+    #[allow(clippy::too_many_arguments)]
+    fn prepare_print<'a>(
+        &'a self,
+        token: HandleToken,
+        app_id: Option<AppID>,
+        parent_window: Option<WindowIdentifierType>,
+        title: String,
+        settings: Settings,
+        page_setup: PageSetup,
+        options: RawOptions<PreparePrintOptions>,
+    ) -> BoxFuture<'a, Result<PreparePrint>>;
+
+    fn print<'a>(
+        &'a self,
+        token: HandleToken,
+        app_id: Option<AppID>,
+        parent_window: Option<WindowIdentifierType>,
+        title: String,
+        fd: zvariant::OwnedFd,
+        options: RawOptions<PrintOptions>,
+    ) -> BoxFuture<'a, Result<()>>;
+}
+
+impl<T: PrintImpl> DynPrintImpl for T {
+    #[allow(clippy::too_many_arguments)]
+    fn prepare_print<'a>(
+        &'a self,
+        token: HandleToken,
+        app_id: Option<AppID>,
+        parent_window: Option<WindowIdentifierType>,
+        title: String,
+        settings: Settings,
+        page_setup: PageSetup,
+        options: RawOptions<PreparePrintOptions>,
+    ) -> BoxFuture<'a, Result<PreparePrint>> {
+        Box::pin(PrintImpl::prepare_print(
+            self,
+            token,
+            app_id,
+            parent_window,
+            title,
+            settings,
+            page_setup,
+            options,
+        ))
+    }
+
+    fn print<'a>(
+        &'a self,
+        token: HandleToken,
+        app_id: Option<AppID>,
+        parent_window: Option<WindowIdentifierType>,
+        title: String,
+        fd: zvariant::OwnedFd,
+        options: RawOptions<PrintOptions>,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(PrintImpl::print(
+            self,
+            token,
+            app_id,
+            parent_window,
+            title,
+            fd,
+            options,
+        ))
+    }
 }
 
 pub(crate) struct PrintInterface {
-    imp: Arc<dyn PrintImpl>,
+    imp: Arc<dyn DynPrintImpl>,
     cnx: zbus::Connection,
+    version: u32,
 }
 
 impl PrintInterface {
-    pub fn new(imp: Arc<dyn PrintImpl>, cnx: zbus::Connection) -> Self {
-        Self { imp, cnx }
+    pub fn new(imp: Arc<dyn DynPrintImpl>, cnx: zbus::Connection) -> Self {
+        Self {
+            imp,
+            cnx,
+            version: 3,
+        }
+    }
+}
+
+impl PortalInterface for PrintInterface {
+    fn set_version(&mut self, version: u32) {
+        self.version = version;
     }
 }
 
 #[zbus::interface(name = "org.freedesktop.impl.portal.Print")]
 impl PrintInterface {
-    #[zbus(property(emits_changed_signal = "const"), name = "version")]
+    #[zbus(property, name = "version")]
     fn version(&self) -> u32 {
-        3
+        self.version
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -103,7 +188,7 @@ impl PrintInterface {
         title: String,
         settings: Settings,
         page_setup: PageSetup,
-        options: PreparePrintOptions,
+        options: RawOptions<PreparePrintOptions>,
     ) -> Result<Response<PreparePrint>> {
         let imp = Arc::clone(&self.imp);
 
@@ -137,7 +222,7 @@ impl PrintInterface {
         window_identifier: MaybeWindowIdentifier,
         title: String,
         fd: zvariant::OwnedFd,
-        options: PrintOptions,
+        options: RawOptions<PrintOptions>,
     ) -> Result<Response<()>> {
         let imp = Arc::clone(&self.imp);
 
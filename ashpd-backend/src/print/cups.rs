@@ -0,0 +1,723 @@
+use std::{io::Read, os::fd::OwnedFd, path::PathBuf};
+
+use ashpd::{
+    desktop::print::{Orientation, PageSetup, Quality, Settings},
+    PortalError,
+};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::Result;
+
+const PRINT_JOB: u16 = 0x0002;
+const CUPS_GET_DEFAULT: u16 = 0x4001;
+const CUPS_GET_PRINTERS: u16 = 0x4002;
+
+const OPERATION_ATTRIBUTES: u8 = 0x01;
+const JOB_ATTRIBUTES: u8 = 0x02;
+const END_OF_ATTRIBUTES: u8 = 0x03;
+const PRINTER_ATTRIBUTES: u8 = 0x04;
+
+const INTEGER: u8 = 0x21;
+const ENUM: u8 = 0x23;
+const RANGE_OF_INTEGER: u8 = 0x33;
+const NAME_WITHOUT_LANGUAGE: u8 = 0x42;
+const KEYWORD: u8 = 0x44;
+const URI: u8 = 0x45;
+const CHARSET: u8 = 0x47;
+const NATURAL_LANGUAGE: u8 = 0x48;
+const MIME_MEDIA_TYPE: u8 = 0x49;
+
+/// The attributes of a group, by name, with the tag and value of each of
+/// their values.
+type Attributes = Vec<(String, Vec<(u8, Vec<u8>)>)>;
+
+const PRINTER_ATTRIBUTE_NAMES: &[&str] = &[
+    "printer-name",
+    "printer-uri-supported",
+    "printer-info",
+    "printer-location",
+    "printer-make-and-model",
+    "printer-state",
+    "printer-is-accepting-jobs",
+];
+
+/// The state of a [`Printer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrinterState {
+    /// The printer is waiting for jobs.
+    Idle,
+    /// The printer is printing a job.
+    Processing,
+    /// The printer is paused.
+    Stopped,
+}
+
+/// A print queue of CUPS.
+#[derive(Debug, Clone)]
+pub struct Printer {
+    name: String,
+    uri: Option<String>,
+    info: Option<String>,
+    location: Option<String>,
+    make_and_model: Option<String>,
+    state: Option<PrinterState>,
+    accepting_jobs: bool,
+}
+
+impl Printer {
+    /// The name of the queue, to submit jobs to with [`Cups::print`].
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The URI of the printer.
+    pub fn uri(&self) -> Option<&str> {
+        self.uri.as_deref()
+    }
+
+    /// A human readable description of the printer.
+    pub fn info(&self) -> Option<&str> {
+        self.info.as_deref()
+    }
+
+    /// The location of the printer.
+    pub fn location(&self) -> Option<&str> {
+        self.location.as_deref()
+    }
+
+    /// The make and model of the printer.
+    pub fn make_and_model(&self) -> Option<&str> {
+        self.make_and_model.as_deref()
+    }
+
+    /// The state of the printer.
+    pub fn state(&self) -> Option<PrinterState> {
+        self.state
+    }
+
+    /// Whether the printer accepts new jobs.
+    pub fn is_accepting_jobs(&self) -> bool {
+        self.accepting_jobs
+    }
+
+    fn from_attributes(attributes: Attributes) -> Option<Self> {
+        let mut printer = Self {
+            name: String::new(),
+            uri: None,
+            info: None,
+            location: None,
+            make_and_model: None,
+            state: None,
+            accepting_jobs: true,
+        };
+        for (name, values) in attributes {
+            let Some((_, value)) = values.into_iter().next() else {
+                continue;
+            };
+            let text = || String::from_utf8(value.clone()).ok();
+            match name.as_str() {
+                "printer-name" => printer.name = text()?,
+                "printer-uri-supported" => printer.uri = text(),
+                "printer-info" => printer.info = text(),
+                "printer-location" => printer.location = text(),
+                "printer-make-and-model" => printer.make_and_model = text(),
+                "printer-state" => {
+                    printer.state = match integer(&value) {
+                        Some(3) => Some(PrinterState::Idle),
+                        Some(4) => Some(PrinterState::Processing),
+                        Some(5) => Some(PrinterState::Stopped),
+                        _ => None,
+                    }
+                }
+                "printer-is-accepting-jobs" => printer.accepting_jobs = value != [0],
+                _ => (),
+            }
+        }
+        (!printer.name.is_empty()).then_some(printer)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Address {
+    Socket(PathBuf),
+    Host(String),
+}
+
+/// Submits print jobs to CUPS over IPP.
+///
+/// It is meant to be used by a [`PrintImpl`](super::PrintImpl), printing the
+/// document received by [`PrintImpl::print`](super::PrintImpl::print) with the
+/// settings of the matching [`PrintImpl::prepare_print`](super::PrintImpl::prepare_print)
+/// call.
+///
+/// ```rust,no_run
+/// use ashpd::desktop::print::{PageSetup, Settings};
+/// use ashpd_backend::print::Cups;
+///
+/// async fn print(
+///     title: &str,
+///     settings: &Settings,
+///     page_setup: &PageSetup,
+///     fd: std::os::fd::OwnedFd,
+/// ) -> ashpd_backend::Result<()> {
+///     let cups = Cups::new();
+///     let printer = match cups.default_printer().await? {
+///         Some(printer) => printer,
+///         None => cups.printers().await?.remove(0),
+///     };
+///     let job_id = cups
+///         .print(printer.name(), title, settings, page_setup, fd)
+///         .await?;
+///     println!("Submitted job {job_id}");
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Cups {
+    address: Address,
+}
+
+impl Default for Cups {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cups {
+    /// Connect to the server set in `CUPS_SERVER`, or to the local scheduler.
+    pub fn new() -> Self {
+        let address = match std::env::var("CUPS_SERVER") {
+            Ok(server) if server.starts_with('/') => Address::Socket(server.into()),
+            Ok(server) if !server.is_empty() => Address::Host(if server.contains(':') {
+                server
+            } else {
+                format!("{server}:631")
+            }),
+            _ => Address::Socket("/run/cups/cups.sock".into()),
+        };
+        Self { address }
+    }
+
+    /// Connect to the scheduler listening on the Unix socket at `path`.
+    pub fn with_socket(path: impl Into<PathBuf>) -> Self {
+        Self {
+            address: Address::Socket(path.into()),
+        }
+    }
+
+    /// Connect to the scheduler listening on `host`, in the `host:port`
+    /// format.
+    pub fn with_host(host: impl Into<String>) -> Self {
+        Self {
+            address: Address::Host(host.into()),
+        }
+    }
+
+    /// The print queues of the server.
+    pub async fn printers(&self) -> Result<Vec<Printer>> {
+        let mut request = IppRequest::new(CUPS_GET_PRINTERS);
+        request.requested_attributes(PRINTER_ATTRIBUTE_NAMES);
+        let response = self.send("/", request.finish()).await?;
+        Ok(response
+            .groups
+            .into_iter()
+            .filter(|(tag, _)| *tag == PRINTER_ATTRIBUTES)
+            .filter_map(|(_, attributes)| Printer::from_attributes(attributes))
+            .collect())
+    }
+
+    /// The default print queue of the server, if any.
+    pub async fn default_printer(&self) -> Result<Option<Printer>> {
+        let mut request = IppRequest::new(CUPS_GET_DEFAULT);
+        request.requested_attributes(PRINTER_ATTRIBUTE_NAMES);
+        let response = match self.send("/", request.finish()).await {
+            Ok(response) => response,
+            // client-error-not-found
+            Err(IppError::Status(0x0406)) => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        Ok(response
+            .groups
+            .into_iter()
+            .find(|(tag, _)| *tag == PRINTER_ATTRIBUTES)
+            .and_then(|(_, attributes)| Printer::from_attributes(attributes)))
+    }
+
+    /// Print the document of `fd` on the `printer` queue, returning the ID of
+    /// the job.
+    ///
+    /// The format of the document is detected by CUPS.
+    pub async fn print(
+        &self,
+        printer: &str,
+        title: &str,
+        settings: &Settings,
+        page_setup: &PageSetup,
+        fd: OwnedFd,
+    ) -> Result<i32> {
+        let document = tokio::task::spawn_blocking(move || {
+            let mut document = Vec::new();
+            std::fs::File::from(fd)
+                .read_to_end(&mut document)
+                .map(|_| document)
+        })
+        .await
+        .map_err(|err| PortalError::Failed(format!("Failed to read the document: {err}")))?
+        .map_err(|err| PortalError::Failed(format!("Failed to read the document: {err}")))?;
+
+        let printer_uri = printer_uri(printer);
+        let mut request = IppRequest::new(PRINT_JOB);
+        request.attribute(URI, "printer-uri", printer_uri.as_str().as_bytes());
+        let user = std::env::var("USER").unwrap_or_else(|_| "anonymous".to_owned());
+        request.attribute(
+            NAME_WITHOUT_LANGUAGE,
+            "requesting-user-name",
+            user.as_bytes(),
+        );
+        request.attribute(NAME_WITHOUT_LANGUAGE, "job-name", title.as_bytes());
+        request.attribute(
+            MIME_MEDIA_TYPE,
+            "document-format",
+            b"application/octet-stream",
+        );
+        request.group(JOB_ATTRIBUTES);
+        job_attributes(&mut request, settings, page_setup);
+        let mut body = request.finish();
+        body.extend_from_slice(&document);
+
+        let response = self.send(printer_uri.path(), body).await?;
+        response
+            .groups
+            .iter()
+            .filter(|(tag, _)| *tag == JOB_ATTRIBUTES)
+            .flat_map(|(_, attributes)| attributes)
+            .find(|(name, _)| name == "job-id")
+            .and_then(|(_, values)| integer(&values.first()?.1))
+            .ok_or_else(|| PortalError::Failed("CUPS didn't return the job ID".to_owned()))
+    }
+
+    async fn send(&self, path: &str, body: Vec<u8>) -> std::result::Result<IppResponse, IppError> {
+        let response = match &self.address {
+            Address::Socket(socket) => {
+                let stream = tokio::net::UnixStream::connect(socket).await?;
+                http_post(stream, "localhost", path, &body).await?
+            }
+            Address::Host(host) => {
+                let stream = tokio::net::TcpStream::connect(host.as_str()).await?;
+                http_post(stream, host, path, &body).await?
+            }
+        };
+        let response = IppResponse::parse(&response).ok_or(IppError::Malformed)?;
+        // The successful status codes are in the 0x0000..0x00FF range.
+        if response.status > 0x00FF {
+            return Err(IppError::Status(response.status));
+        }
+        Ok(response)
+    }
+}
+
+/// The URI of the `printer` queue, with its name percent-encoded.
+fn printer_uri(printer: &str) -> url::Url {
+    let mut uri = url::Url::parse("ipp://localhost/printers").unwrap();
+    uri.path_segments_mut().unwrap().push(printer);
+    uri
+}
+
+/// Converts the print settings to the job template attributes of IPP.
+fn job_attributes(request: &mut IppRequest, settings: &Settings, page_setup: &PageSetup) {
+    if let Some(copies) = settings
+        .n_copies
+        .as_deref()
+        .and_then(|n| n.parse::<i32>().ok())
+    {
+        request.attribute(INTEGER, "copies", &copies.to_be_bytes());
+    }
+    if let Some(collate) = settings.collate.as_deref() {
+        let handling = match collate {
+            "true" => "separate-documents-collated-copies",
+            _ => "separate-documents-uncollated-copies",
+        };
+        request.attribute(KEYWORD, "multiple-document-handling", handling.as_bytes());
+    }
+    if let Some(sides) = settings.duplex.as_deref().and_then(|duplex| match duplex {
+        "simplex" => Some("one-sided"),
+        "horizontal" => Some("two-sided-short-edge"),
+        "vertical" => Some("two-sided-long-edge"),
+        _ => None,
+    }) {
+        request.attribute(KEYWORD, "sides", sides.as_bytes());
+    }
+    if let Some(orientation) = page_setup.orientation.or(settings.orientation) {
+        let orientation: i32 = match orientation {
+            Orientation::Portrait => 3,
+            Orientation::Landscape => 4,
+            Orientation::ReverseLandscape => 5,
+            Orientation::ReversePortrait => 6,
+        };
+        request.attribute(ENUM, "orientation-requested", &orientation.to_be_bytes());
+    }
+    if let Some(media) = page_setup
+        .name
+        .as_deref()
+        .or(settings.paper_format.as_deref())
+    {
+        request.attribute(KEYWORD, "media", media.as_bytes());
+    }
+    if let Some(use_color) = settings.use_color {
+        let mode = if use_color { "color" } else { "monochrome" };
+        request.attribute(KEYWORD, "print-color-mode", mode.as_bytes());
+    }
+    if let Some(quality) = settings.quality {
+        let quality: i32 = match quality {
+            Quality::Draft | Quality::Low => 3,
+            Quality::Normal => 4,
+            Quality::High => 5,
+        };
+        request.attribute(ENUM, "print-quality", &quality.to_be_bytes());
+    }
+    if let Some(number_up) = settings
+        .number_up
+        .as_deref()
+        .and_then(|n| n.parse::<i32>().ok())
+    {
+        request.attribute(INTEGER, "number-up", &number_up.to_be_bytes());
+    }
+    if let Some(output_bin) = settings.output_bin.as_deref() {
+        request.attribute(KEYWORD, "output-bin", output_bin.as_bytes());
+    }
+    if settings.print_pages.as_deref() == Some("ranges") {
+        // The ranges of the settings start at 0, the IPP ones at 1.
+        let ranges = settings
+            .page_ranges
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|range| {
+                let (start, end) = range.split_once('-').unwrap_or((range, range));
+                Some((
+                    start.trim().parse::<i32>().ok()? + 1,
+                    end.trim().parse::<i32>().ok()? + 1,
+                ))
+            })
+            .map(|(start, end)| [start.to_be_bytes(), end.to_be_bytes()].concat())
+            .collect::<Vec<_>>();
+        request.attribute_values(RANGE_OF_INTEGER, "page-ranges", &ranges);
+    }
+}
+
+#[derive(Debug)]
+enum IppError {
+    Io(std::io::Error),
+    Http(String),
+    Malformed,
+    Status(u16),
+}
+
+impl From<std::io::Error> for IppError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<IppError> for PortalError {
+    fn from(err: IppError) -> Self {
+        match err {
+            IppError::Io(err) => Self::Failed(format!("Failed to reach CUPS: {err}")),
+            IppError::Http(status) => Self::Failed(format!("CUPS replied with `{status}`")),
+            IppError::Malformed => Self::Failed("Malformed IPP response".to_owned()),
+            IppError::Status(status) => Self::Failed(format!("IPP request failed: {status:#06x}")),
+        }
+    }
+}
+
+struct IppRequest(Vec<u8>);
+
+impl IppRequest {
+    fn new(operation: u16) -> Self {
+        let mut request = Self(Vec::new());
+        // IPP 2.0, the operation and the request ID.
+        request.0.extend_from_slice(&[2, 0]);
+        request.0.extend_from_slice(&operation.to_be_bytes());
+        request.0.extend_from_slice(&1u32.to_be_bytes());
+        request.group(OPERATION_ATTRIBUTES);
+        request.attribute(CHARSET, "attributes-charset", b"utf-8");
+        request.attribute(NATURAL_LANGUAGE, "attributes-natural-language", b"en");
+        request
+    }
+
+    fn group(&mut self, tag: u8) {
+        self.0.push(tag);
+    }
+
+    fn attribute(&mut self, tag: u8, name: &str, value: &[u8]) {
+        self.attribute_values(tag, name, &[value]);
+    }
+
+    fn attribute_values(&mut self, tag: u8, name: &str, values: &[impl AsRef<[u8]>]) {
+        for (index, value) in values.iter().enumerate() {
+            // The additional values of an attribute have an empty name.
+            let name = if index == 0 { name } else { "" };
+            self.0.push(tag);
+            self.length_prefixed(name.as_bytes());
+            self.length_prefixed(value.as_ref());
+        }
+    }
+
+    /// Writes `bytes` preceded by their length, truncating them to the
+    /// longest length that can be encoded. Otherwise the rest of a long value,
+    /// like a job name chosen by the application, would be parsed as more
+    /// attributes.
+    fn length_prefixed(&mut self, bytes: &[u8]) {
+        let len = u16::try_from(bytes.len()).unwrap_or(u16::MAX);
+        self.0.extend_from_slice(&len.to_be_bytes());
+        self.0.extend_from_slice(&bytes[..usize::from(len)]);
+    }
+
+    fn requested_attributes(&mut self, names: &[&str]) {
+        self.attribute_values(KEYWORD, "requested-attributes", names);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        self.0.push(END_OF_ATTRIBUTES);
+        self.0
+    }
+}
+
+struct IppResponse {
+    status: u16,
+    groups: Vec<(u8, Attributes)>,
+}
+
+impl IppResponse {
+    fn parse(mut data: &[u8]) -> Option<Self> {
+        fn take<'a>(data: &mut &'a [u8], len: usize) -> Option<&'a [u8]> {
+            if data.len() < len {
+                return None;
+            }
+            let (head, tail) = data.split_at(len);
+            *data = tail;
+            Some(head)
+        }
+        fn take_u16(data: &mut &[u8]) -> Option<u16> {
+            Some(u16::from_be_bytes(take(data, 2)?.try_into().ok()?))
+        }
+
+        take(&mut data, 2)?;
+        let status = take_u16(&mut data)?;
+        take(&mut data, 4)?;
+        let mut groups = Vec::<(u8, Attributes)>::new();
+        loop {
+            let tag = *take(&mut data, 1)?.first()?;
+            match tag {
+                END_OF_ATTRIBUTES => break,
+                // A delimiter tag, starting a new group.
+                0x00..=0x0F => groups.push((tag, Vec::new())),
+                _ => {
+                    let name_len = take_u16(&mut data)? as usize;
+                    let name = String::from_utf8(take(&mut data, name_len)?.to_vec()).ok()?;
+                    let value_len = take_u16(&mut data)? as usize;
+                    let value = take(&mut data, value_len)?.to_vec();
+                    let (_, attributes) = groups.last_mut()?;
+                    if name.is_empty() {
+                        attributes.last_mut()?.1.push((tag, value));
+                    } else {
+                        attributes.push((name, vec![(tag, value)]));
+                    }
+                }
+            }
+        }
+        Some(Self { status, groups })
+    }
+}
+
+fn integer(value: &[u8]) -> Option<i32> {
+    Some(i32::from_be_bytes(value.try_into().ok()?))
+}
+
+async fn http_post(
+    mut stream: impl AsyncRead + AsyncWrite + Unpin,
+    host: &str,
+    path: &str,
+    body: &[u8],
+) -> std::result::Result<Vec<u8>, IppError> {
+    // HTTP/1.0 keeps the response from being chunked.
+    let header = format!(
+        "POST {path} HTTP/1.0\r\nHost: {host}\r\nContent-Type: application/ipp\r\nContent-Length: {}\r\n\r\n",
+        body.len()
+    );
+    stream.write_all(header.as_bytes()).await?;
+    stream.write_all(body).await?;
+    stream.flush().await?;
+
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).await?;
+    let end = response
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .ok_or(IppError::Malformed)?;
+    let head = String::from_utf8_lossy(&response[..end]);
+    let status = head.lines().next().unwrap_or_default();
+    if status.split_whitespace().nth(1) != Some("200") {
+        return Err(IppError::Http(status.to_owned()));
+    }
+    Ok(response.split_off(end + 4))
+}
+
+#[cfg(test)]
+mod tests {
+    use ashpd::desktop::print::{Orientation, PageSetup, Quality, Settings};
+
+    use super::*;
+
+    /// The values of `name` in the first `tag` group of `data`.
+    fn values(data: &[u8], tag: u8, name: &str) -> Vec<(u8, Vec<u8>)> {
+        IppResponse::parse(data)
+            .unwrap()
+            .groups
+            .into_iter()
+            .find(|(group, _)| *group == tag)
+            .unwrap()
+            .1
+            .into_iter()
+            .find(|(attribute, _)| attribute == name)
+            .map(|(_, values)| values)
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn request_round_trip() {
+        let mut request = IppRequest::new(CUPS_GET_PRINTERS);
+        request.requested_attributes(&["printer-name", "printer-state"]);
+        request.group(PRINTER_ATTRIBUTES);
+        request.attribute(INTEGER, "printer-state", &4i32.to_be_bytes());
+        let data = request.finish();
+
+        // The operation is where the responses store their status.
+        let response = IppResponse::parse(&data).unwrap();
+        assert_eq!(response.status, CUPS_GET_PRINTERS);
+        let tags = response.groups.iter().map(|(tag, _)| *tag);
+        assert_eq!(
+            tags.collect::<Vec<_>>(),
+            [OPERATION_ATTRIBUTES, PRINTER_ATTRIBUTES]
+        );
+
+        assert_eq!(
+            values(&data, OPERATION_ATTRIBUTES, "attributes-charset"),
+            [(CHARSET, b"utf-8".to_vec())]
+        );
+        assert_eq!(
+            values(&data, OPERATION_ATTRIBUTES, "requested-attributes"),
+            [
+                (KEYWORD, b"printer-name".to_vec()),
+                (KEYWORD, b"printer-state".to_vec())
+            ]
+        );
+        let state = values(&data, PRINTER_ATTRIBUTES, "printer-state");
+        assert_eq!(integer(&state[0].1), Some(4));
+    }
+
+    #[test]
+    fn parse_response() {
+        let mut request = IppRequest::new(0x0000);
+        request.group(PRINTER_ATTRIBUTES);
+        request.attribute(NAME_WITHOUT_LANGUAGE, "printer-name", b"Office");
+        request.attribute(ENUM, "printer-state", &5i32.to_be_bytes());
+        request.attribute(0x22, "printer-is-accepting-jobs", &[0]);
+        request.group(PRINTER_ATTRIBUTES);
+        request.attribute(NAME_WITHOUT_LANGUAGE, "printer-info", b"No name");
+        let data = request.finish();
+
+        let printers = IppResponse::parse(&data)
+            .unwrap()
+            .groups
+            .into_iter()
+            .filter(|(tag, _)| *tag == PRINTER_ATTRIBUTES)
+            .filter_map(|(_, attributes)| Printer::from_attributes(attributes))
+            .collect::<Vec<_>>();
+        assert_eq!(printers.len(), 1);
+        assert_eq!(printers[0].name(), "Office");
+        assert_eq!(printers[0].state(), Some(PrinterState::Stopped));
+        assert!(!printers[0].is_accepting_jobs());
+
+        // Truncated responses and values without a group are rejected.
+        assert!(IppResponse::parse(&data[..data.len() - 1]).is_none());
+        assert!(IppResponse::parse(&data[..7]).is_none());
+        assert!(IppResponse::parse(&[2, 0, 0, 0, 0, 0, 0, 1, KEYWORD, 0, 1, b'a', 0, 0]).is_none());
+    }
+
+    #[test]
+    fn job_template() {
+        let settings = Settings {
+            n_copies: Some("2".to_owned()),
+            collate: Some("true".to_owned()),
+            duplex: Some("vertical".to_owned()),
+            orientation: Some(Orientation::Portrait),
+            paper_format: Some("iso_a4".to_owned()),
+            use_color: Some(false),
+            quality: Some(Quality::High),
+            print_pages: Some("ranges".to_owned()),
+            page_ranges: Some("0-2, 5".to_owned()),
+            ..Default::default()
+        };
+        let page_setup = PageSetup {
+            orientation: Some(Orientation::Landscape),
+            ..Default::default()
+        };
+        let mut request = IppRequest::new(PRINT_JOB);
+        request.group(JOB_ATTRIBUTES);
+        job_attributes(&mut request, &settings, &page_setup);
+        let data = request.finish();
+
+        let value = |name| values(&data, JOB_ATTRIBUTES, name);
+        assert_eq!(integer(&value("copies")[0].1), Some(2));
+        assert_eq!(
+            value("multiple-document-handling"),
+            [(KEYWORD, b"separate-documents-collated-copies".to_vec())]
+        );
+        assert_eq!(value("sides"), [(KEYWORD, b"two-sided-long-edge".to_vec())]);
+        // The page setup takes precedence over the settings.
+        assert_eq!(integer(&value("orientation-requested")[0].1), Some(4));
+        assert_eq!(value("media"), [(KEYWORD, b"iso_a4".to_vec())]);
+        assert_eq!(
+            value("print-color-mode"),
+            [(KEYWORD, b"monochrome".to_vec())]
+        );
+        assert_eq!(integer(&value("print-quality")[0].1), Some(5));
+        assert!(value("number-up").is_empty());
+        assert_eq!(
+            value("page-ranges"),
+            [
+                (RANGE_OF_INTEGER, [1i32, 3].map(i32::to_be_bytes).concat()),
+                (RANGE_OF_INTEGER, [6i32, 6].map(i32::to_be_bytes).concat()),
+            ]
+        );
+    }
+
+    #[test]
+    fn long_values() {
+        let mut title = vec![b'a'; usize::from(u16::MAX)];
+        // An attribute that would be read after the truncated value.
+        title.extend_from_slice(&[KEYWORD, 0, 1, b'x', 0, 1, b'y']);
+        let mut request = IppRequest::new(PRINT_JOB);
+        request.attribute(NAME_WITHOUT_LANGUAGE, "job-name", &title);
+        let data = request.finish();
+
+        let response = IppResponse::parse(&data).unwrap();
+        let attributes = &response.groups[0].1;
+        assert!(attributes.iter().all(|(name, _)| name != "x"));
+        let job_name = values(&data, OPERATION_ATTRIBUTES, "job-name");
+        assert_eq!(job_name[0].1.len(), usize::from(u16::MAX));
+    }
+
+    #[test]
+    fn printer_uris() {
+        let uri = printer_uri("HP_LaserJet");
+        assert_eq!(uri.as_str(), "ipp://localhost/printers/HP_LaserJet");
+        let uri = printer_uri("Office 2/b?#");
+        assert_eq!(
+            uri.as_str(),
+            "ipp://localhost/printers/Office%202%2Fb%3F%23"
+        );
+        assert_eq!(uri.path(), "/printers/Office%202%2Fb%3F%23");
+    }
+}
@@ -1,15 +1,25 @@
 use std::{boxed::Box, future::Future, sync::Arc};
 
-use async_trait::async_trait;
-use futures_util::future::{abortable, AbortHandle};
+use ashpd::desktop::{HandleToken, Response};
+use futures_util::future::{abortable, AbortHandle, BoxFuture};
 use tokio::sync::Mutex;
 use zbus::zvariant::{ObjectPath, OwnedObjectPath};
 
-use crate::desktop::{HandleToken, Response};
+use crate::idle::Busy;
 
-#[async_trait]
 pub trait RequestImpl: Send + Sync {
-    async fn close(&self, token: HandleToken);
+    fn close(&self, token: HandleToken) -> impl Future<Output = ()> + Send;
+}
+
+/// Object safe counterpart of [`RequestImpl`], used to store the implementation.
+pub(crate) trait DynRequestImpl: Send + Sync {
+    fn close<'a>(&'a self, token: HandleToken) -> BoxFuture<'a, ()>;
+}
+
+impl<T: RequestImpl> DynRequestImpl for T {
+    fn close<'a>(&'a self, token: HandleToken) -> BoxFuture<'a, ()> {
+        Box::pin(RequestImpl::close(self, token))
+    }
 }
 
 pub struct Request {
@@ -30,19 +40,20 @@ impl Request {
         cnx: &zbus::Connection,
         path: OwnedObjectPath,
         imp: Arc<R>,
-        callback: impl Future<Output = crate::backend::Result<T>>,
-    ) -> crate::backend::Result<Response<T>>
+        callback: impl Future<Output = crate::Result<T>>,
+    ) -> crate::Result<Response<T>>
     where
-        R: RequestImpl + 'static + ?Sized,
+        R: DynRequestImpl + 'static + ?Sized,
         T: std::fmt::Debug,
     {
         #[cfg(feature = "tracing")]
         tracing::debug!("{_method}");
+        let _busy = Busy::new();
         let (fut, abort_handle) = abortable(callback);
         let token = HandleToken::try_from(&path).unwrap();
         let close_cb = || {
             tokio::spawn(async move {
-                RequestImpl::close(&*imp, token).await;
+                DynRequestImpl::close(&*imp, token).await;
             });
         };
         let request = Request::new(close_cb, path.clone(), abort_handle, cnx.clone());
@@ -54,16 +65,22 @@ impl Request {
         );
         server.at(&path, request).await?;
 
+        // The request is released before replying with an error as well, the
+        // error message being forwarded to the frontend.
         let response = match fut.await {
-            Err(_) => Response::cancelled(),
-            Ok(response) => Response::ok(response?),
+            Err(_) => Ok(Response::cancelled()),
+            Ok(response) => response.map(Response::ok),
         };
         #[cfg(feature = "tracing")]
         tracing::debug!("{_method} returned {:#?}", response);
         #[cfg(feature = "tracing")]
         tracing::debug!("Releasing request {:?}", path.as_str());
-        server.remove::<Self, _>(&path).await?;
-        Ok(response)
+        // A request closed by the frontend was already released.
+        match server.remove::<Self, _>(&path).await {
+            Ok(_) | Err(zbus::Error::InterfaceNotFound) => (),
+            Err(err) => return Err(err.into()),
+        }
+        response
     }
 
     pub(crate) fn new(
@@ -0,0 +1,585 @@
+//! Helpers for the ScreenCast and RemoteDesktop backends, creating the
+//! PipeWire video streams of the casted sources.
+//!
+//! ```rust,no_run
+//! use ashpd::desktop::screencast::{SourceType, Streams};
+//! use ashpd_backend::screencast::{StreamOptions, StreamProvider};
+//!
+//! async fn start(provider: &StreamProvider) -> ashpd_backend::Result<Streams> {
+//!     let stream = provider
+//!         .create_stream(StreamOptions::new(1920, 1080).framerate(30))
+//!         .await?;
+//!     let streams = vec![stream.to_stream(SourceType::Monitor, (0, 0), (1920, 1080))];
+//!     // Keep `stream` alive and feed it with `VideoStream::push_frame`.
+//!     Ok(Streams::new(streams, None))
+//! }
+//! ```
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    io::Cursor,
+    rc::Rc,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use ashpd::{
+    desktop::screencast::{SourceType, Stream as ScreenCastStream},
+    PortalError,
+};
+pub use pipewire::spa::param::video::VideoFormat;
+use pipewire::{
+    self as pw,
+    properties::properties,
+    spa::{
+        self,
+        param::{format::FormatProperties, video::VideoInfoRaw, ParamType},
+        pod::{serialize::PodSerializer, Object, Pod, Property, Value},
+        utils::{Fraction, Rectangle, SpaTypes},
+    },
+    stream::{Stream, StreamFlags, StreamListener, StreamState},
+};
+use tokio::sync::oneshot;
+
+use crate::Result;
+
+/// The number of buffers allocated for each stream.
+const BUFFERS: i32 = 4;
+
+enum Command {
+    Create {
+        id: u64,
+        options: StreamOptions,
+        format: Arc<Mutex<Option<NegotiatedFormat>>>,
+        reply: oneshot::Sender<Result<u32>>,
+    },
+    Frame {
+        id: u64,
+        data: Vec<u8>,
+        stride: u32,
+    },
+    Destroy(u64),
+    Quit,
+}
+
+/// The options of a stream created with [`StreamProvider::create_stream`].
+#[derive(Debug, Clone)]
+pub struct StreamOptions {
+    name: String,
+    width: u32,
+    height: u32,
+    framerate: u32,
+    formats: Vec<VideoFormat>,
+}
+
+impl StreamOptions {
+    /// Create new options for a stream of `width`x`height` pixels.
+    ///
+    /// The stream defaults to 30 frames per second, in the `BGRx`, `BGRA`,
+    /// `RGBx` or `RGBA` formats.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            name: "ashpd-screencast".to_owned(),
+            width,
+            height,
+            framerate: 30,
+            formats: vec![
+                VideoFormat::BGRx,
+                VideoFormat::BGRA,
+                VideoFormat::RGBx,
+                VideoFormat::RGBA,
+            ],
+        }
+    }
+
+    /// Sets the name of the PipeWire node.
+    #[must_use]
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = name.to_owned();
+        self
+    }
+
+    /// Sets the maximum number of frames per second.
+    #[must_use]
+    pub fn framerate(mut self, framerate: u32) -> Self {
+        self.framerate = framerate;
+        self
+    }
+
+    /// Sets the formats the frames can be provided in, by order of
+    /// preference.
+    ///
+    /// All of them must use 4 bytes per pixel.
+    #[must_use]
+    pub fn formats(mut self, formats: &[VideoFormat]) -> Self {
+        if !formats.is_empty() {
+            self.formats = formats.to_vec();
+        }
+        self
+    }
+}
+
+/// The format negotiated with the consumer of a [`VideoStream`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NegotiatedFormat {
+    /// The pixel format of the frames.
+    pub format: VideoFormat,
+    /// The width of the frames.
+    pub width: u32,
+    /// The height of the frames.
+    pub height: u32,
+}
+
+/// Runs a PipeWire main loop in a dedicated thread and creates the video
+/// streams of the casted sources on it.
+pub struct StreamProvider {
+    sender: pw::channel::Sender<Command>,
+    next_id: AtomicU64,
+}
+
+impl StreamProvider {
+    /// Connect to the PipeWire daemon.
+    pub fn new() -> Result<Self> {
+        let (sender, receiver) = pw::channel::channel();
+        let (ready_sender, ready) = std::sync::mpsc::channel();
+        std::thread::Builder::new()
+            .name("ashpd-pipewire".to_owned())
+            .spawn(move || {
+                if let Err(err) = run(receiver, &ready_sender) {
+                    let _ = ready_sender.send(Err(err));
+                }
+            })
+            .map_err(|err| PortalError::Failed(format!("Failed to spawn a thread: {err}")))?;
+        ready
+            .recv()
+            .map_err(|_| PortalError::Failed("The PipeWire thread exited".to_owned()))?
+            .map_err(|err| PortalError::Failed(format!("Failed to connect to PipeWire: {err}")))?;
+        Ok(Self {
+            sender,
+            next_id: AtomicU64::new(0),
+        })
+    }
+
+    /// Create a new video stream, returned once its node is ready to be
+    /// shared with the application.
+    pub async fn create_stream(&self, options: StreamOptions) -> Result<VideoStream> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let format = Arc::new(Mutex::new(None));
+        let (reply, node_id) = oneshot::channel();
+        self.send(Command::Create {
+            id,
+            options,
+            format: format.clone(),
+            reply,
+        })?;
+        let node_id = node_id
+            .await
+            .map_err(|_| PortalError::Failed("The PipeWire thread exited".to_owned()))??;
+        Ok(VideoStream {
+            id,
+            node_id,
+            format,
+            sender: self.sender.clone(),
+        })
+    }
+
+    fn send(&self, command: Command) -> Result<()> {
+        self.sender
+            .send(command)
+            .map_err(|_| PortalError::Failed("The PipeWire thread exited".to_owned()))
+    }
+}
+
+impl Drop for StreamProvider {
+    fn drop(&mut self) {
+        let _ = self.sender.send(Command::Quit);
+    }
+}
+
+impl std::fmt::Debug for StreamProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StreamProvider").finish_non_exhaustive()
+    }
+}
+
+/// A video stream created by a [`StreamProvider`], destroyed once dropped.
+pub struct VideoStream {
+    id: u64,
+    node_id: u32,
+    format: Arc<Mutex<Option<NegotiatedFormat>>>,
+    sender: pw::channel::Sender<Command>,
+}
+
+impl VideoStream {
+    /// The PipeWire node of the stream.
+    pub fn node_id(&self) -> u32 {
+        self.node_id
+    }
+
+    /// The format negotiated with the consumer, if any.
+    pub fn format(&self) -> Option<NegotiatedFormat> {
+        *self.format.lock().unwrap()
+    }
+
+    /// Sends a frame to the consumer, in the negotiated format.
+    ///
+    /// The frame is dropped if the consumer isn't ready or no buffer is
+    /// available.
+    pub fn push_frame(&self, data: Vec<u8>, stride: u32) -> Result<()> {
+        self.sender
+            .send(Command::Frame {
+                id: self.id,
+                data,
+                stride,
+            })
+            .map_err(|_| PortalError::Failed("The PipeWire thread exited".to_owned()))
+    }
+
+    /// The stream, as sent in the `streams` response of the `Start` request.
+    pub fn to_stream(
+        &self,
+        source_type: SourceType,
+        position: (i32, i32),
+        size: (i32, i32),
+    ) -> ScreenCastStream {
+        ScreenCastStream::builder(self.node_id)
+            .source_type(source_type)
+            .position(position)
+            .size(size)
+            .build()
+    }
+}
+
+impl Drop for VideoStream {
+    fn drop(&mut self) {
+        let _ = self.sender.send(Command::Destroy(self.id));
+    }
+}
+
+impl std::fmt::Debug for VideoStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VideoStream")
+            .field("node_id", &self.node_id)
+            .field("format", &self.format())
+            .finish()
+    }
+}
+
+struct StreamData {
+    // Shared with the caller, to report the errors happening before the
+    // stream is connected.
+    reply: Rc<RefCell<Option<oneshot::Sender<Result<u32>>>>>,
+    format: Arc<Mutex<Option<NegotiatedFormat>>>,
+}
+
+struct ActiveStream {
+    // Dropped before the stream.
+    _listener: StreamListener<StreamData>,
+    stream: Stream,
+}
+
+fn run(
+    receiver: pw::channel::Receiver<Command>,
+    ready: &std::sync::mpsc::Sender<std::result::Result<(), pw::Error>>,
+) -> std::result::Result<(), pw::Error> {
+    pw::init();
+    let mainloop = pw::main_loop::MainLoop::new(None)?;
+    let context = pw::context::Context::new(&mainloop)?;
+    let core = context.connect(None)?;
+    let streams = Rc::new(RefCell::new(HashMap::<u64, ActiveStream>::new()));
+
+    let _receiver = receiver.attach(mainloop.loop_(), {
+        let mainloop = mainloop.clone();
+        move |command| match command {
+            Command::Create {
+                id,
+                options,
+                format,
+                reply,
+            } => {
+                let reply = Rc::new(RefCell::new(Some(reply)));
+                let data = StreamData {
+                    reply: reply.clone(),
+                    format,
+                };
+                match create_stream(&core, &options, data) {
+                    Ok(stream) => {
+                        streams.borrow_mut().insert(id, stream);
+                    }
+                    Err(err) => {
+                        if let Some(reply) = reply.borrow_mut().take() {
+                            let _ = reply.send(Err(PortalError::Failed(format!(
+                                "Failed to create the stream: {err}"
+                            ))));
+                        }
+                    }
+                }
+            }
+            Command::Frame { id, data, stride } => {
+                if let Some(active) = streams.borrow().get(&id) {
+                    queue_frame(&active.stream, &data, stride);
+                }
+            }
+            Command::Destroy(id) => {
+                if let Some(active) = streams.borrow_mut().remove(&id) {
+                    let _ = active.stream.disconnect();
+                }
+            }
+            Command::Quit => mainloop.quit(),
+        }
+    });
+
+    let _ = ready.send(Ok(()));
+    mainloop.run();
+    Ok(())
+}
+
+fn create_stream(
+    core: &pw::core::Core,
+    options: &StreamOptions,
+    data: StreamData,
+) -> std::result::Result<ActiveStream, pw::Error> {
+    let stream = Stream::new(
+        core,
+        &options.name,
+        properties! {
+            *pw::keys::MEDIA_CLASS => "Video/Source",
+            *pw::keys::MEDIA_TYPE => "Video",
+            *pw::keys::MEDIA_CATEGORY => "Capture",
+            *pw::keys::MEDIA_ROLE => "Screen",
+        },
+    )?;
+
+    let listener = stream
+        .add_local_listener_with_user_data(data)
+        .state_changed(|stream, data, _old, new| match new {
+            StreamState::Paused | StreamState::Streaming => {
+                if let Some(reply) = data.reply.borrow_mut().take() {
+                    let _ = reply.send(Ok(stream.node_id()));
+                }
+            }
+            StreamState::Error(err) => {
+                if let Some(reply) = data.reply.borrow_mut().take() {
+                    let _ = reply.send(Err(PortalError::Failed(err)));
+                }
+            }
+            StreamState::Unconnected | StreamState::Connecting => (),
+        })
+        .param_changed(|stream, data, id, param| {
+            let Some(param) = param else {
+                return;
+            };
+            if id != ParamType::Format.as_raw() {
+                return;
+            }
+            let mut info = VideoInfoRaw::new();
+            if info.parse(param).is_err() {
+                return;
+            }
+            let size = info.size();
+            data.format.lock().unwrap().replace(NegotiatedFormat {
+                format: info.format(),
+                width: size.width,
+                height: size.height,
+            });
+
+            let stride = size.width as i32 * 4;
+            let buffers = serialize(Object {
+                type_: SpaTypes::ObjectParamBuffers.as_raw(),
+                id: ParamType::Buffers.as_raw(),
+                properties: vec![
+                    Property::new(spa::sys::SPA_PARAM_BUFFERS_buffers, Value::Int(BUFFERS)),
+                    Property::new(spa::sys::SPA_PARAM_BUFFERS_blocks, Value::Int(1)),
+                    Property::new(
+                        spa::sys::SPA_PARAM_BUFFERS_size,
+                        Value::Int(stride * size.height as i32),
+                    ),
+                    Property::new(spa::sys::SPA_PARAM_BUFFERS_stride, Value::Int(stride)),
+                    Property::new(
+                        spa::sys::SPA_PARAM_BUFFERS_dataType,
+                        Value::Int(1 << spa::sys::SPA_DATA_MemPtr),
+                    ),
+                ],
+            });
+            if let Some(pod) = Pod::from_bytes(&buffers) {
+                let _ = stream.update_params(&mut [pod]);
+            }
+        })
+        .register()?;
+
+    let format = serialize(enum_format(options));
+    let format = Pod::from_bytes(&format).ok_or(pw::Error::CreationFailed)?;
+    stream.connect(
+        spa::utils::Direction::Output,
+        None,
+        StreamFlags::DRIVER | StreamFlags::MAP_BUFFERS,
+        &mut [format],
+    )?;
+
+    Ok(ActiveStream {
+        _listener: listener,
+        stream,
+    })
+}
+
+fn queue_frame(stream: &Stream, frame: &[u8], stride: u32) {
+    let Some(mut buffer) = stream.dequeue_buffer() else {
+        return;
+    };
+    let Some(data) = buffer.datas_mut().first_mut() else {
+        return;
+    };
+    let size = match data.data() {
+        Some(slice) => {
+            let size = frame.len().min(slice.len());
+            slice[..size].copy_from_slice(&frame[..size]);
+            size
+        }
+        None => 0,
+    };
+    let chunk = data.chunk_mut();
+    *chunk.offset_mut() = 0;
+    *chunk.stride_mut() = stride as i32;
+    *chunk.size_mut() = size as u32;
+    // The buffer is queued back once dropped.
+}
+
+/// The formats supported by the stream, offered to the consumer.
+fn enum_format(options: &StreamOptions) -> Object {
+    let size = Rectangle {
+        width: options.width,
+        height: options.height,
+    };
+    pw::spa::pod::object!(
+        SpaTypes::ObjectParamFormat,
+        ParamType::EnumFormat,
+        pw::spa::pod::property!(
+            FormatProperties::MediaType,
+            Id,
+            pw::spa::param::format::MediaType::Video
+        ),
+        pw::spa::pod::property!(
+            FormatProperties::MediaSubtype,
+            Id,
+            pw::spa::param::format::MediaSubtype::Raw
+        ),
+        pw::spa::pod::property!(
+            FormatProperties::VideoFormat,
+            Value::Choice(spa::pod::ChoiceValue::Id(spa::utils::Choice(
+                spa::utils::ChoiceFlags::empty(),
+                spa::utils::ChoiceEnum::Enum {
+                    default: spa::utils::Id(options.formats[0].as_raw()),
+                    alternatives: options
+                        .formats
+                        .iter()
+                        .map(|format| spa::utils::Id(format.as_raw()))
+                        .collect(),
+                },
+            )))
+        ),
+        pw::spa::pod::property!(FormatProperties::VideoSize, Rectangle, size),
+        pw::spa::pod::property!(
+            FormatProperties::VideoFramerate,
+            Fraction,
+            Fraction { num: 0, denom: 1 }
+        ),
+        pw::spa::pod::property!(
+            FormatProperties::VideoMaxFramerate,
+            Choice,
+            Range,
+            Fraction,
+            Fraction {
+                num: options.framerate,
+                denom: 1
+            },
+            Fraction { num: 1, denom: 1 },
+            Fraction {
+                num: options.framerate,
+                denom: 1
+            }
+        ),
+    )
+}
+
+fn serialize(object: Object) -> Vec<u8> {
+    PodSerializer::serialize(Cursor::new(Vec::new()), &Value::Object(object))
+        .map(|(cursor, _)| cursor.into_inner())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use pipewire::spa::{
+        param::format::FormatProperties,
+        pod::{deserialize::PodDeserializer, ChoiceValue, Value},
+        utils::{Choice, ChoiceEnum, Id, Rectangle},
+    };
+
+    use super::{enum_format, serialize, StreamOptions, VideoFormat};
+
+    #[test]
+    fn options() {
+        let options = StreamOptions::new(1920, 1080)
+            .name("monitor-1")
+            .framerate(60)
+            .formats(&[]);
+        assert_eq!(options.name, "monitor-1");
+        assert_eq!(options.framerate, 60);
+        // The default formats are kept if none is given.
+        assert_eq!(options.formats.len(), 4);
+        assert_eq!(options.formats[0], VideoFormat::BGRx);
+
+        let options = options.formats(&[VideoFormat::RGBA]);
+        assert_eq!(options.formats, vec![VideoFormat::RGBA]);
+    }
+
+    #[test]
+    fn offered_formats() {
+        let options = StreamOptions::new(1920, 1080)
+            .framerate(60)
+            .formats(&[VideoFormat::RGBA, VideoFormat::BGRA]);
+        let pod = serialize(enum_format(&options));
+        let (_, value) = PodDeserializer::deserialize_any_from(&pod).unwrap();
+        let Value::Object(object) = value else {
+            panic!("Unexpected pod {value:?}");
+        };
+        let property = |key: FormatProperties| {
+            object
+                .properties
+                .iter()
+                .find(|property| property.key == key.as_raw())
+                .map(|property| property.value.clone())
+                .unwrap()
+        };
+
+        assert_eq!(
+            property(FormatProperties::VideoFormat),
+            Value::Choice(ChoiceValue::Id(Choice(
+                Default::default(),
+                ChoiceEnum::Enum {
+                    default: Id(VideoFormat::RGBA.as_raw()),
+                    alternatives: vec![
+                        Id(VideoFormat::RGBA.as_raw()),
+                        Id(VideoFormat::BGRA.as_raw())
+                    ],
+                },
+            )))
+        );
+        assert_eq!(
+            property(FormatProperties::VideoSize),
+            Value::Rectangle(Rectangle {
+                width: 1920,
+                height: 1080
+            })
+        );
+        let Value::Choice(ChoiceValue::Fraction(Choice(_, ChoiceEnum::Range { default, max, .. }))) =
+            property(FormatProperties::VideoMaxFramerate)
+        else {
+            panic!("Unexpected maximum framerate");
+        };
+        assert_eq!((default.num, max.num), (60, 60));
+    }
+}
@@ -0,0 +1,206 @@
+use std::{future::Future, sync::Arc};
+
+use ashpd::{
+    desktop::{screenshot::Screenshot as ScreenshotResponse, Color, HandleToken, Response},
+    zvariant::{DeserializeDict, OwnedObjectPath, Type},
+    AppID, WindowIdentifierType,
+};
+use futures_util::future::BoxFuture;
+
+use crate::{
+    backend::PortalInterface,
+    request::{DynRequestImpl, Request, RequestImpl},
+    MaybeAppID, MaybeWindowIdentifier, RawOptions, Result,
+};
+
+#[cfg(feature = "wayland")]
+mod capture;
+mod storage;
+#[cfg(feature = "wayland")]
+pub use capture::{capture_outputs, CapturedOutput};
+pub use storage::{save_screenshot, ScreenshotImage};
+
+#[derive(DeserializeDict, Type, Debug)]
+#[zvariant(signature = "dict")]
+pub struct ScreenshotOptions {
+    modal: Option<bool>,
+    interactive: Option<bool>,
+    permission_store_checked: Option<bool>,
+}
+
+impl ScreenshotOptions {
+    pub fn modal(&self) -> Option<bool> {
+        self.modal
+    }
+
+    pub fn interactive(&self) -> Option<bool> {
+        self.interactive
+    }
+
+    pub fn permission_store_checked(&self) -> Option<bool> {
+        self.permission_store_checked
+    }
+}
+
+#[derive(DeserializeDict, Type, Debug)]
+#[zvariant(signature = "dict")]
+pub struct ColorOptions;
+
+pub trait ScreenshotImpl: RequestImpl {
+    fn screenshot(
+        &self,
+        token: HandleToken,
+        app_id: Option<AppID>,
+        window_identifier: Option<WindowIdentifierType>,
+        options: RawOptions<ScreenshotOptions>,
+    ) -> impl Future<Output = Result<ScreenshotResponse>> + Send;
+
+    fn pick_color(
+        &self,
+        token: HandleToken,
+        app_id: Option<AppID>,
+        window_identifier: Option<WindowIdentifierType>,
+        options: RawOptions<ColorOptions>,
+    ) -> impl Future<Output = Result<Color>> + Send;
+}
+
+/// Object safe counterpart of [`ScreenshotImpl`], used to store the implementation.
+pub(crate) trait DynScreenshotImpl: DynRequestImpl {
+    fn screenshot<'a>(
+        &'a self,
+        token: HandleToken,
+        app_id: Option<AppID>,
+        window_identifier: Option<WindowIdentifierType>,
+        options: RawOptions<ScreenshotOptions>,
+    ) -> BoxFuture<'a, Result<ScreenshotResponse>>;
+
+    fn pick_color<'a>(
+        &'a self,
+        token: HandleToken,
+        app_id: Option<AppID>,
+        window_identifier: Option<WindowIdentifierType>,
+        options: RawOptions<ColorOptions>,
+    ) -> BoxFuture<'a, Result<Color>>;
+}
+
+impl<T: ScreenshotImpl> DynScreenshotImpl for T {
+    fn screenshot<'a>(
+        &'a self,
+        token: HandleToken,
+        app_id: Option<AppID>,
+        window_identifier: Option<WindowIdentifierType>,
+        options: RawOptions<ScreenshotOptions>,
+    ) -> BoxFuture<'a, Result<ScreenshotResponse>> {
+        Box::pin(ScreenshotImpl::screenshot(
+            self,
+            token,
+            app_id,
+            window_identifier,
+            options,
+        ))
+    }
+
+    fn pick_color<'a>(
+        &'a self,
+        token: HandleToken,
+        app_id: Option<AppID>,
+        window_identifier: Option<WindowIdentifierType>,
+        options: RawOptions<ColorOptions>,
+    ) -> BoxFuture<'a, Result<Color>> {
+        Box::pin(ScreenshotImpl::pick_color(
+            self,
+            token,
+            app_id,
+            window_identifier,
+            options,
+        ))
+    }
+}
+
+pub(crate) struct ScreenshotInterface {
+    imp: Arc<dyn DynScreenshotImpl>,
+    cnx: zbus::Connection,
+    version: u32,
+}
+
+impl ScreenshotInterface {
+    pub fn new(imp: Arc<dyn DynScreenshotImpl>, cnx: zbus::Connection) -> Self {
+        Self {
+            imp,
+            cnx,
+            version: 2,
+        }
+    }
+}
+
+impl PortalInterface for ScreenshotInterface {
+    fn set_version(&mut self, version: u32) {
+        self.version = version;
+    }
+}
+
+#[zbus::interface(name = "org.freedesktop.impl.portal.Screenshot")]
+impl ScreenshotInterface {
+    #[zbus(property, name = "version")]
+    fn version(&self) -> u32 {
+        self.version
+    }
+
+    #[zbus(name = "Screenshot")]
+    #[zbus(out_args("response", "results"))]
+    async fn screenshot(
+        &self,
+        handle: OwnedObjectPath,
+        app_id: MaybeAppID,
+        window_identifier: MaybeWindowIdentifier,
+        options: RawOptions<ScreenshotOptions>,
+    ) -> Result<Response<ScreenshotResponse>> {
+        let imp = Arc::clone(&self.imp);
+
+        Request::spawn(
+            "Screenshot::Screenshot",
+            &self.cnx,
+            handle.clone(),
+            Arc::clone(&self.imp),
+            async move {
+                imp.screenshot(
+                    HandleToken::try_from(&handle).unwrap(),
+                    app_id.inner(),
+                    window_identifier.inner(),
+                    options,
+                )
+                .await
+            },
+        )
+        .await
+    }
+
+    #[zbus(name = "PickColor")]
+    #[zbus(out_args("response", "results"))]
+    async fn pick_color(
+        &self,
+        handle: OwnedObjectPath,
+        app_id: MaybeAppID,
+        window_identifier: MaybeWindowIdentifier,
+        options: RawOptions<ColorOptions>,
+    ) -> Result<Response<Color>> {
+        let imp = Arc::clone(&self.imp);
+
+        Request::spawn(
+            "Screenshot::PickColor",
+            &self.cnx,
+            handle.clone(),
+            Arc::clone(&self.imp),
+            async move {
+                imp.pick_color(
+                    HandleToken::try_from(&handle).unwrap(),
+                    app_id.inner(),
+                    window_identifier.inner(),
+                    options,
+                )
+                .await
+            },
+        )
+        .await
+    }
+}
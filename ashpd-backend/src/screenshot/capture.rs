@@ -0,0 +1,558 @@
+use std::{
+    fs::File,
+    os::{
+        fd::{AsFd, FromRawFd},
+        unix::fs::FileExt,
+    },
+};
+
+use ashpd::PortalError;
+use wayland_client::{
+    delegate_noop,
+    globals::{registry_queue_init, GlobalListContents},
+    protocol::{wl_buffer, wl_output, wl_registry, wl_shm, wl_shm_pool},
+    Connection, Dispatch, EventQueue, Proxy, QueueHandle, WEnum,
+};
+use wayland_protocols::ext::{
+    image_capture_source::v1::client::{
+        ext_image_capture_source_v1, ext_output_image_capture_source_manager_v1,
+    },
+    image_copy_capture::v1::client::{
+        ext_image_copy_capture_frame_v1, ext_image_copy_capture_manager_v1,
+        ext_image_copy_capture_session_v1,
+    },
+};
+use wayland_protocols_wlr::screencopy::v1::client::{
+    zwlr_screencopy_frame_v1, zwlr_screencopy_manager_v1,
+};
+
+use super::ScreenshotImage;
+use crate::Result;
+
+use ext_image_copy_capture_manager_v1::{ExtImageCopyCaptureManagerV1, Options};
+use ext_image_copy_capture_session_v1::ExtImageCopyCaptureSessionV1;
+use ext_output_image_capture_source_manager_v1::ExtOutputImageCaptureSourceManagerV1;
+use zwlr_screencopy_frame_v1::ZwlrScreencopyFrameV1;
+use zwlr_screencopy_manager_v1::ZwlrScreencopyManagerV1;
+
+/// The content of an output, captured with [`capture_outputs`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapturedOutput {
+    name: Option<String>,
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl CapturedOutput {
+    /// The name of the output, like `DP-1`, if the compositor sent it.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// The width of the image, in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// The height of the image, in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The RGBA pixels, row by row without any padding.
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// The image to pass to [`save_screenshot`](super::save_screenshot).
+    pub fn image(&self) -> ScreenshotImage<'_> {
+        ScreenshotImage::Rgba {
+            width: self.width,
+            height: self.height,
+            pixels: &self.pixels,
+        }
+    }
+}
+
+/// Captures the content of every output of the Wayland compositor the
+/// process is connected to.
+///
+/// The outputs are captured with the `ext-image-copy-capture-v1` protocol,
+/// implemented by wlroots based compositors since wlroots 0.19, among others,
+/// or with `wlr-screencopy-unstable-v1` on the older wlroots based
+/// compositors. The images are in the orientation of the output buffers,
+/// saving them with [`save_screenshot`](super::save_screenshot) requires the
+/// `gdk-pixbuf` feature.
+///
+/// ```rust,no_run
+/// use ashpd::{desktop::screenshot::Screenshot, AppID};
+/// use ashpd_backend::screenshot::{capture_outputs, save_screenshot};
+///
+/// async fn screenshot(app_id: Option<AppID>) -> ashpd_backend::Result<Screenshot> {
+///     let outputs = capture_outputs(false).await?;
+///     let output = outputs.first().expect("No output to capture");
+///     let uri = save_screenshot(app_id.as_ref(), output.image()).await?;
+///     Ok(Screenshot::new(uri))
+/// }
+/// ```
+pub async fn capture_outputs(paint_cursors: bool) -> Result<Vec<CapturedOutput>> {
+    tokio::task::spawn_blocking(move || capture_outputs_blocking(paint_cursors))
+        .await
+        .map_err(|err| PortalError::Failed(format!("Failed to capture the screen: {err}")))?
+}
+
+fn failed(err: impl std::fmt::Display) -> PortalError {
+    PortalError::Failed(format!("Failed to capture the screen: {err}"))
+}
+
+/// The protocol the outputs are captured with.
+enum Capturer {
+    ImageCopy {
+        sources: ExtOutputImageCaptureSourceManagerV1,
+        manager: ExtImageCopyCaptureManagerV1,
+    },
+    Screencopy(ZwlrScreencopyManagerV1),
+}
+
+fn capture_outputs_blocking(paint_cursors: bool) -> Result<Vec<CapturedOutput>> {
+    let connection = Connection::connect_to_env().map_err(failed)?;
+    let (globals, mut queue) = registry_queue_init::<State>(&connection).map_err(failed)?;
+    let qh = queue.handle();
+
+    let shm: wl_shm::WlShm = globals.bind(&qh, 1..=1, ()).map_err(failed)?;
+    let image_copy = globals
+        .bind(&qh, 1..=1, ())
+        .and_then(|sources| Ok((sources, globals.bind(&qh, 1..=1, ())?)));
+    let capturer = match image_copy {
+        Ok((sources, manager)) => Capturer::ImageCopy { sources, manager },
+        Err(_) => Capturer::Screencopy(globals.bind(&qh, 1..=3, ()).map_err(|_| {
+            failed("The compositor supports neither ext-image-copy-capture nor wlr-screencopy")
+        })?),
+    };
+
+    let mut state = State::default();
+    globals.contents().with_list(|list| {
+        for global in list.iter().filter(|g| g.interface == "wl_output") {
+            let output = globals.registry().bind::<wl_output::WlOutput, _, _>(
+                global.name,
+                global.version.min(4),
+                &qh,
+                state.names.len(),
+            );
+            state.outputs.push(output);
+            state.names.push(None);
+        }
+    });
+    // Receive the names of the outputs.
+    queue.roundtrip(&mut state).map_err(failed)?;
+
+    let mut captured = Vec::with_capacity(state.outputs.len());
+    for (output, name) in std::mem::take(&mut state.outputs)
+        .into_iter()
+        .zip(std::mem::take(&mut state.names))
+    {
+        let capture = match &capturer {
+            Capturer::ImageCopy { sources, manager } => {
+                let options = if paint_cursors {
+                    Options::PaintCursors
+                } else {
+                    Options::empty()
+                };
+                let source = sources.create_source(&output, &qh, ());
+                let session = manager.create_session(&source, options, &qh, ());
+                let capture = capture_frame(&mut queue, &mut state, &shm, &session);
+                session.destroy();
+                source.destroy();
+                capture
+            }
+            Capturer::Screencopy(manager) => {
+                let frame = manager.capture_output(paint_cursors.into(), &output, &qh, ());
+                let capture = screencopy_frame(&mut queue, &mut state, &shm, &frame);
+                frame.destroy();
+                capture
+            }
+        };
+        if output.version() >= 3 {
+            output.release();
+        }
+        let (width, height, pixels) = capture?;
+        captured.push(CapturedOutput {
+            name,
+            width,
+            height,
+            pixels,
+        });
+    }
+    if let Capturer::Screencopy(manager) = capturer {
+        manager.destroy();
+    }
+    Ok(captured)
+}
+
+/// The formats the captured buffers can be converted from.
+const FORMATS: [wl_shm::Format; 2] = [wl_shm::Format::Xrgb8888, wl_shm::Format::Argb8888];
+
+/// A shared memory buffer for the compositor to copy a frame to.
+struct ShmBuffer {
+    file: File,
+    pool: wl_shm_pool::WlShmPool,
+    buffer: wl_buffer::WlBuffer,
+    size: u32,
+}
+
+impl ShmBuffer {
+    fn new(
+        shm: &wl_shm::WlShm,
+        qh: &QueueHandle<State>,
+        (width, height): (u32, u32),
+        stride: u32,
+        format: wl_shm::Format,
+    ) -> Result<Self> {
+        let size = stride
+            .checked_mul(height)
+            .filter(|size| {
+                stride >= width.saturating_mul(4) && width > 0 && *size <= i32::MAX as u32
+            })
+            .ok_or_else(|| failed("Unsupported buffer size"))?;
+        let file = memfd()?;
+        file.set_len(size as u64).map_err(failed)?;
+        let pool = shm.create_pool(file.as_fd(), size as i32, qh, ());
+        let buffer = pool.create_buffer(
+            0,
+            width as i32,
+            height as i32,
+            stride as i32,
+            format,
+            qh,
+            (),
+        );
+        Ok(Self {
+            file,
+            pool,
+            buffer,
+            size,
+        })
+    }
+
+    /// The content of the buffer, once the compositor copied the frame.
+    fn read(self) -> Result<Vec<u8>> {
+        self.buffer.destroy();
+        self.pool.destroy();
+        let mut data = vec![0; self.size as usize];
+        self.file.read_exact_at(&mut data, 0).map_err(failed)?;
+        Ok(data)
+    }
+}
+
+/// Dispatches the events until the frame is ready or failed.
+fn wait_frame(queue: &mut EventQueue<State>, state: &mut State) -> Result<()> {
+    loop {
+        if let Some(captured) = state.frame.take() {
+            return captured;
+        }
+        queue.blocking_dispatch(state).map_err(failed)?;
+    }
+}
+
+/// Captures a single frame of `session`, as RGBA pixels.
+fn capture_frame(
+    queue: &mut EventQueue<State>,
+    state: &mut State,
+    shm: &wl_shm::WlShm,
+    session: &ExtImageCopyCaptureSessionV1,
+) -> Result<(u32, u32, Vec<u8>)> {
+    let qh = queue.handle();
+    state.session = SessionState::default();
+    while !state.session.done {
+        if state.session.stopped {
+            return Err(failed("The output is gone"));
+        }
+        queue.blocking_dispatch(state).map_err(failed)?;
+    }
+
+    let (width, height) = state.session.buffer_size;
+    let format = FORMATS
+        .into_iter()
+        .find(|format| state.session.formats.contains(format))
+        .ok_or_else(|| failed("No supported buffer format"))?;
+    let stride = width
+        .checked_mul(4)
+        .ok_or_else(|| failed("The output is too large"))?;
+    let buffer = ShmBuffer::new(shm, &qh, (width, height), stride, format)?;
+    let frame = session.create_frame(&qh, ());
+    frame.attach_buffer(&buffer.buffer);
+    frame.damage_buffer(0, 0, width as i32, height as i32);
+    frame.capture();
+
+    state.frame = None;
+    let captured = wait_frame(queue, state);
+    frame.destroy();
+    let data = buffer.read();
+    captured?;
+    Ok((width, height, to_rgba(&data?, width, stride, format, false)))
+}
+
+/// Captures a single frame with `wlr-screencopy`, as RGBA pixels.
+fn screencopy_frame(
+    queue: &mut EventQueue<State>,
+    state: &mut State,
+    shm: &wl_shm::WlShm,
+    frame: &ZwlrScreencopyFrameV1,
+) -> Result<(u32, u32, Vec<u8>)> {
+    let qh = queue.handle();
+    state.screencopy = ScreencopyState::default();
+    state.frame = None;
+    // The buffers are announced before `buffer_done`, which is only sent
+    // since version 3.
+    while !(state.screencopy.buffer_done
+        || frame.version() < 3 && !state.screencopy.buffers.is_empty())
+    {
+        if let Some(Err(err)) = state.frame.take() {
+            return Err(err);
+        }
+        queue.blocking_dispatch(state).map_err(failed)?;
+    }
+
+    let (format, width, height, stride) = FORMATS
+        .into_iter()
+        .find_map(|format| {
+            state
+                .screencopy
+                .buffers
+                .iter()
+                .find(|buffer| buffer.0 == format)
+        })
+        .copied()
+        .ok_or_else(|| failed("No supported buffer format"))?;
+    let buffer = ShmBuffer::new(shm, &qh, (width, height), stride, format)?;
+    frame.copy(&buffer.buffer);
+
+    let captured = wait_frame(queue, state);
+    let data = buffer.read();
+    captured?;
+    let y_invert = state.screencopy.y_invert;
+    Ok((
+        width,
+        height,
+        to_rgba(&data?, width, stride, format, y_invert),
+    ))
+}
+
+/// Converts the rows of a 32 bits `wl_shm` buffer to RGBA pixels without
+/// padding, the first row being the top one.
+fn to_rgba(
+    data: &[u8],
+    width: u32,
+    stride: u32,
+    format: wl_shm::Format,
+    y_invert: bool,
+) -> Vec<u8> {
+    let mut rows = data.chunks_exact(stride as usize).collect::<Vec<_>>();
+    if y_invert {
+        rows.reverse();
+    }
+    let mut pixels = Vec::with_capacity(rows.len() * width as usize * 4);
+    for row in rows {
+        // The 32 bits formats of wl_shm are little endian, so the bytes of a
+        // pixel are in the BGRA order.
+        for pixel in row[..width as usize * 4].chunks_exact(4) {
+            let alpha = if format == wl_shm::Format::Argb8888 {
+                pixel[3]
+            } else {
+                u8::MAX
+            };
+            pixels.extend_from_slice(&[pixel[2], pixel[1], pixel[0], alpha]);
+        }
+    }
+    pixels
+}
+
+fn memfd() -> Result<File> {
+    // SAFETY: the name is a valid nul-terminated string.
+    let fd =
+        unsafe { libc::memfd_create(b"ashpd-screenshot\0".as_ptr().cast(), libc::MFD_CLOEXEC) };
+    if fd < 0 {
+        return Err(failed(std::io::Error::last_os_error()));
+    }
+    // SAFETY: the file descriptor was just created and is owned by the file.
+    Ok(unsafe { File::from_raw_fd(fd) })
+}
+
+#[derive(Default)]
+struct SessionState {
+    buffer_size: (u32, u32),
+    formats: Vec<wl_shm::Format>,
+    done: bool,
+    stopped: bool,
+}
+
+#[derive(Default)]
+struct ScreencopyState {
+    /// The format, width, height and stride of the supported buffers.
+    buffers: Vec<(wl_shm::Format, u32, u32, u32)>,
+    buffer_done: bool,
+    y_invert: bool,
+}
+
+#[derive(Default)]
+struct State {
+    outputs: Vec<wl_output::WlOutput>,
+    names: Vec<Option<String>>,
+    session: SessionState,
+    screencopy: ScreencopyState,
+    /// The outcome of the capture of the current frame.
+    frame: Option<Result<()>>,
+}
+
+impl Dispatch<wl_registry::WlRegistry, GlobalListContents> for State {
+    fn event(
+        _state: &mut Self,
+        _registry: &wl_registry::WlRegistry,
+        _event: wl_registry::Event,
+        _data: &GlobalListContents,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<wl_output::WlOutput, usize> for State {
+    fn event(
+        state: &mut Self,
+        _output: &wl_output::WlOutput,
+        event: wl_output::Event,
+        index: &usize,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        if let wl_output::Event::Name { name } = event {
+            state.names[*index] = Some(name);
+        }
+    }
+}
+
+impl Dispatch<ExtImageCopyCaptureSessionV1, ()> for State {
+    fn event(
+        state: &mut Self,
+        _session: &ExtImageCopyCaptureSessionV1,
+        event: ext_image_copy_capture_session_v1::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        use ext_image_copy_capture_session_v1::Event;
+
+        // The constraints are sent in any order, and again after `done`
+        // when they change.
+        if state.session.done && matches!(event, Event::BufferSize { .. } | Event::ShmFormat { .. })
+        {
+            state.session.done = false;
+            state.session.formats.clear();
+        }
+        match event {
+            Event::BufferSize { width, height } => state.session.buffer_size = (width, height),
+            Event::ShmFormat {
+                format: WEnum::Value(format),
+            } => state.session.formats.push(format),
+            Event::Done => state.session.done = true,
+            Event::Stopped => state.session.stopped = true,
+            _ => (),
+        }
+    }
+}
+
+impl Dispatch<ext_image_copy_capture_frame_v1::ExtImageCopyCaptureFrameV1, ()> for State {
+    fn event(
+        state: &mut Self,
+        _frame: &ext_image_copy_capture_frame_v1::ExtImageCopyCaptureFrameV1,
+        event: ext_image_copy_capture_frame_v1::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        use ext_image_copy_capture_frame_v1::{Event, FailureReason};
+
+        match event {
+            Event::Ready => state.frame = Some(Ok(())),
+            Event::Failed { reason } => {
+                let reason = match reason {
+                    WEnum::Value(FailureReason::BufferConstraints) => "Unsupported buffer",
+                    WEnum::Value(FailureReason::Stopped) => "The output is gone",
+                    _ => "Unknown error",
+                };
+                state.frame = Some(Err(failed(reason)));
+            }
+            _ => (),
+        }
+    }
+}
+
+impl Dispatch<ZwlrScreencopyFrameV1, ()> for State {
+    fn event(
+        state: &mut Self,
+        _frame: &ZwlrScreencopyFrameV1,
+        event: zwlr_screencopy_frame_v1::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        use zwlr_screencopy_frame_v1::{Event, Flags};
+
+        match event {
+            Event::Buffer {
+                format: WEnum::Value(format),
+                width,
+                height,
+                stride,
+            } => state
+                .screencopy
+                .buffers
+                .push((format, width, height, stride)),
+            Event::BufferDone => state.screencopy.buffer_done = true,
+            Event::Flags {
+                flags: WEnum::Value(flags),
+            } => state.screencopy.y_invert = flags.contains(Flags::YInvert),
+            Event::Ready { .. } => state.frame = Some(Ok(())),
+            Event::Failed => state.frame = Some(Err(failed("The copy failed"))),
+            _ => (),
+        }
+    }
+}
+
+delegate_noop!(State: ignore wl_shm::WlShm);
+delegate_noop!(State: wl_shm_pool::WlShmPool);
+delegate_noop!(State: ignore wl_buffer::WlBuffer);
+delegate_noop!(State: ExtOutputImageCaptureSourceManagerV1);
+delegate_noop!(State: ext_image_capture_source_v1::ExtImageCaptureSourceV1);
+delegate_noop!(State: ExtImageCopyCaptureManagerV1);
+delegate_noop!(State: ZwlrScreencopyManagerV1);
+
+#[cfg(test)]
+mod tests {
+    use wayland_client::protocol::wl_shm::Format;
+
+    use super::to_rgba;
+
+    #[test]
+    fn bgra_to_rgba() {
+        // Two rows of two pixels, padded to a stride of 12 bytes.
+        let data = [
+            [1, 2, 3, 4, 5, 6, 7, 8, 0, 0, 0, 0],
+            [9, 10, 11, 12, 13, 14, 15, 16, 0, 0, 0, 0],
+        ]
+        .concat();
+        assert_eq!(
+            to_rgba(&data, 2, 12, Format::Argb8888, false),
+            [3, 2, 1, 4, 7, 6, 5, 8, 11, 10, 9, 12, 15, 14, 13, 16]
+        );
+        assert_eq!(
+            to_rgba(&data, 2, 12, Format::Xrgb8888, false),
+            [3, 2, 1, 255, 7, 6, 5, 255, 11, 10, 9, 255, 15, 14, 13, 255]
+        );
+        assert_eq!(
+            to_rgba(&data, 2, 12, Format::Argb8888, true),
+            [11, 10, 9, 12, 15, 14, 13, 16, 3, 2, 1, 4, 7, 6, 5, 8]
+        );
+    }
+}
@@ -0,0 +1,259 @@
+use std::{
+    fs::OpenOptions,
+    io::{ErrorKind, Write},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use ashpd::{
+    documents::{DocumentAddRequest, DocumentFlags, Permission},
+    AppID, PortalError,
+};
+
+use crate::Result;
+
+/// The image of a screenshot, saved with [`save_screenshot`].
+#[derive(Debug, Clone, Copy)]
+pub enum ScreenshotImage<'a> {
+    /// An encoded PNG image.
+    Png(&'a [u8]),
+    /// Raw RGBA pixels, row by row without any padding, encoded with
+    /// gdk-pixbuf.
+    ///
+    /// Saving them fails with [`PortalError::NotSupported`] without the
+    /// `gdk-pixbuf` feature.
+    Rgba {
+        width: u32,
+        height: u32,
+        pixels: &'a [u8],
+    },
+}
+
+/// Saves the image in the screenshots directory of the user and returns the
+/// URI to send back to the caller.
+///
+/// The image is written as a PNG file in the `Screenshots` directory of the
+/// pictures directory of the user. When `app_id` is set, the file is exported
+/// in the document store with a read access for the application and the
+/// returned URI points to the document portal filesystem, `app_id` should
+/// be `None` for applications running on the host.
+///
+/// ```rust,no_run
+/// use ashpd::{desktop::screenshot::Screenshot, AppID};
+/// use ashpd_backend::screenshot::{save_screenshot, ScreenshotImage};
+///
+/// async fn screenshot(app_id: Option<AppID>, png: &[u8]) -> ashpd_backend::Result<Screenshot> {
+///     let uri = save_screenshot(app_id.as_ref(), ScreenshotImage::Png(png)).await?;
+///     Ok(Screenshot::new(uri))
+/// }
+/// ```
+pub async fn save_screenshot(
+    app_id: Option<&AppID>,
+    image: ScreenshotImage<'_>,
+) -> Result<url::Url> {
+    let data = match image {
+        ScreenshotImage::Png(data) => data.to_vec(),
+        #[cfg(feature = "gdk-pixbuf")]
+        ScreenshotImage::Rgba {
+            width,
+            height,
+            pixels,
+        } => {
+            let pixels = pixels.to_vec();
+            tokio::task::spawn_blocking(move || encode_png(width, height, pixels))
+                .await
+                .map_err(|err| {
+                    PortalError::Failed(format!("Failed to encode the screenshot: {err}"))
+                })??
+        }
+        #[cfg(not(feature = "gdk-pixbuf"))]
+        ScreenshotImage::Rgba { .. } => {
+            return Err(PortalError::NotSupported(
+                "Saving RGBA screenshots requires the gdk-pixbuf feature".to_owned(),
+            ));
+        }
+    };
+    let path = tokio::task::spawn_blocking(move || write_screenshot(&screenshots_dir(), &data))
+        .await
+        .map_err(std::io::Error::other)
+        .and_then(|result| result)
+        .map_err(|err| PortalError::Failed(format!("Failed to save the screenshot: {err}")))?;
+
+    let path = match app_id {
+        Some(app_id) => export_document(&path, app_id).await.map_err(|err| {
+            PortalError::Failed(format!("Failed to export the screenshot: {err}"))
+        })?,
+        None => path,
+    };
+    url::Url::from_file_path(&path)
+        .map_err(|_| PortalError::Failed(format!("Invalid screenshot path `{}`", path.display())))
+}
+
+async fn export_document(path: &Path, app_id: &AppID) -> ashpd::Result<PathBuf> {
+    // The file is opened with `O_PATH`, the document portal takes care of
+    // the access checks.
+    let added = DocumentAddRequest::default()
+        .path(path)
+        .flags(DocumentFlags::ReuseExisting | DocumentFlags::AsNeededByApp)
+        .app_id(app_id.clone())
+        .permissions(Permission::Read)
+        .send()
+        .await?;
+    let doc_id = added.ids().first().ok_or(ashpd::Error::ParseError(
+        "The document store didn't return the document ID",
+    ))?;
+    let mount_point = added.mountpoint().ok_or(ashpd::Error::ParseError(
+        "The document store didn't return its mount point",
+    ))?;
+    let file_name = path
+        .file_name()
+        .ok_or(ashpd::Error::ParseError("The screenshot has no file name"))?;
+    Ok(mount_point.as_path().join(doc_id.as_ref()).join(file_name))
+}
+
+/// The `Screenshots` directory of the pictures directory of the user.
+fn screenshots_dir() -> PathBuf {
+    let home = std::env::var_os("HOME")
+        .map(PathBuf::from)
+        .unwrap_or_default();
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| home.join(".config"));
+    let user_dirs = std::fs::read_to_string(config_dir.join("user-dirs.dirs")).ok();
+    pictures_dir(&home, user_dirs.as_deref()).join("Screenshots")
+}
+
+/// The pictures directory of the user, from the content of `user-dirs.dirs`.
+fn pictures_dir(home: &Path, user_dirs: Option<&str>) -> PathBuf {
+    // The user directories are stored as `XDG_PICTURES_DIR="$HOME/Pictures"`.
+    user_dirs
+        .and_then(|dirs| {
+            dirs.lines().find_map(|line| {
+                let value = line.trim().strip_prefix("XDG_PICTURES_DIR=")?;
+                let value = value.trim_matches('"');
+                Some(match value.strip_prefix("$HOME") {
+                    Some(relative) => home.join(relative.trim_start_matches('/')),
+                    None => PathBuf::from(value),
+                })
+            })
+        })
+        .unwrap_or_else(|| home.join("Pictures"))
+}
+
+fn write_screenshot(dir: &Path, data: &[u8]) -> std::io::Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    for index in 0.. {
+        let name = match index {
+            0 => format!("Screenshot-{timestamp}.png"),
+            index => format!("Screenshot-{timestamp}-{index}.png"),
+        };
+        let path = dir.join(name);
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                file.write_all(data)?;
+                return Ok(path);
+            }
+            Err(err) if err.kind() == ErrorKind::AlreadyExists => continue,
+            Err(err) => return Err(err),
+        }
+    }
+    unreachable!()
+}
+
+/// Encodes RGBA pixels as a PNG image.
+#[cfg(feature = "gdk-pixbuf")]
+fn encode_png(width: u32, height: u32, pixels: Vec<u8>) -> Result<Vec<u8>> {
+    use gdk_pixbuf::{glib::Bytes, Colorspace, Pixbuf};
+
+    let invalid = || {
+        PortalError::InvalidArgument(format!(
+            "Invalid RGBA image of {width}x{height} with {} bytes",
+            pixels.len()
+        ))
+    };
+    let (Ok(width), Ok(height)) = (i32::try_from(width), i32::try_from(height)) else {
+        return Err(invalid());
+    };
+    let stride = width as usize * 4;
+    if width == 0
+        || height == 0
+        || stride > i32::MAX as usize
+        || pixels.len() != stride * height as usize
+    {
+        return Err(invalid());
+    }
+
+    let pixbuf = Pixbuf::from_bytes(
+        &Bytes::from_owned(pixels),
+        Colorspace::Rgb,
+        true,
+        8,
+        width,
+        height,
+        stride as i32,
+    );
+    pixbuf
+        .save_to_bufferv("png", &[])
+        .map_err(|err| PortalError::Failed(format!("Failed to encode the screenshot: {err}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{pictures_dir, write_screenshot};
+
+    #[test]
+    fn pictures_directory() {
+        let home = Path::new("/home/user");
+        assert_eq!(pictures_dir(home, None), home.join("Pictures"));
+        assert_eq!(
+            pictures_dir(
+                home,
+                Some("XDG_DESKTOP_DIR=\"$HOME/Desktop\"\nXDG_PICTURES_DIR=\"$HOME/Images\"\n")
+            ),
+            home.join("Images")
+        );
+        assert_eq!(
+            pictures_dir(home, Some("XDG_PICTURES_DIR=\"/data/pictures\"")),
+            Path::new("/data/pictures")
+        );
+        assert_eq!(
+            pictures_dir(home, Some("XDG_MUSIC_DIR=\"$HOME/Music\"")),
+            home.join("Pictures")
+        );
+    }
+
+    #[test]
+    fn unique_names() {
+        let dir = std::env::temp_dir().join(format!("ashpd-screenshots-{}", std::process::id()));
+        let first = write_screenshot(&dir, b"first").unwrap();
+        let second = write_screenshot(&dir, b"second").unwrap();
+        assert_ne!(first, second);
+        assert_eq!(std::fs::read(&first).unwrap(), b"first");
+        assert_eq!(std::fs::read(&second).unwrap(), b"second");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "gdk-pixbuf")]
+    #[test]
+    fn encode_png() {
+        let pixels = [255, 0, 0, 255, 0, 255, 0, 128].repeat(3);
+        let png = super::encode_png(2, 3, pixels.clone()).unwrap();
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+
+        let loader = gdk_pixbuf::PixbufLoader::with_type("png").unwrap();
+        loader.write(&png).unwrap();
+        loader.close().unwrap();
+        let pixbuf = loader.pixbuf().unwrap();
+        assert_eq!((pixbuf.width(), pixbuf.height()), (2, 3));
+        assert_eq!(&*pixbuf.read_pixel_bytes(), &pixels[..]);
+
+        assert!(super::encode_png(2, 3, pixels[1..].to_vec()).is_err());
+        assert!(super::encode_png(0, 0, Vec::new()).is_err());
+    }
+}
@@ -0,0 +1,104 @@
+use std::{collections::HashMap, future::Future, sync::Arc};
+
+use ashpd::{
+    desktop::{HandleToken, Response},
+    AppID,
+};
+use futures_util::future::BoxFuture;
+use zbus::zvariant::{self, OwnedValue};
+
+use crate::{
+    backend::PortalInterface,
+    request::{DynRequestImpl, Request, RequestImpl},
+    Result,
+};
+
+mod keyring;
+pub use keyring::KeyringSecret;
+
+pub trait SecretImpl: RequestImpl {
+    fn retrieve(
+        &self,
+        token: HandleToken,
+        app_id: AppID,
+        fd: std::os::fd::OwnedFd,
+    ) -> impl Future<Output = Result<HashMap<String, OwnedValue>>> + Send;
+}
+
+/// Object safe counterpart of [`SecretImpl`], used to store the implementation.
+pub(crate) trait DynSecretImpl: DynRequestImpl {
+    fn retrieve<'a>(
+        &'a self,
+        token: HandleToken,
+        app_id: AppID,
+        fd: std::os::fd::OwnedFd,
+    ) -> BoxFuture<'a, Result<HashMap<String, OwnedValue>>>;
+}
+
+impl<T: SecretImpl> DynSecretImpl for T {
+    fn retrieve<'a>(
+        &'a self,
+        token: HandleToken,
+        app_id: AppID,
+        fd: std::os::fd::OwnedFd,
+    ) -> BoxFuture<'a, Result<HashMap<String, OwnedValue>>> {
+        Box::pin(SecretImpl::retrieve(self, token, app_id, fd))
+    }
+}
+
+pub(crate) struct SecretInterface {
+    imp: Arc<dyn DynSecretImpl>,
+    cnx: zbus::Connection,
+    version: u32,
+}
+
+impl SecretInterface {
+    pub fn new(imp: Arc<dyn DynSecretImpl>, cnx: zbus::Connection) -> Self {
+        Self {
+            imp,
+            cnx,
+            version: 1,
+        }
+    }
+}
+
+impl PortalInterface for SecretInterface {
+    fn set_version(&mut self, version: u32) {
+        self.version = version;
+    }
+}
+
+#[zbus::interface(name = "org.freedesktop.impl.portal.Secret")]
+impl SecretInterface {
+    #[zbus(property, name = "version")]
+    fn version(&self) -> u32 {
+        self.version
+    }
+
+    #[zbus(out_args("response", "results"))]
+    async fn retrieve_secret(
+        &self,
+        handle: zvariant::OwnedObjectPath,
+        app_id: AppID,
+        fd: zvariant::OwnedFd,
+        _options: HashMap<String, OwnedValue>,
+    ) -> Result<Response<HashMap<String, OwnedValue>>> {
+        let imp = Arc::clone(&self.imp);
+
+        Request::spawn(
+            "Secret::RetrieveSecret",
+            &self.cnx,
+            handle.clone(),
+            Arc::clone(&self.imp),
+            async move {
+                imp.retrieve(
+                    HandleToken::try_from(&handle).unwrap(),
+                    app_id,
+                    std::os::fd::OwnedFd::from(fd),
+                )
+                .await
+            },
+        )
+        .await
+    }
+}
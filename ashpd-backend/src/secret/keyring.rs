@@ -0,0 +1,460 @@
+use std::{collections::HashMap, io::Read, os::fd::OwnedFd};
+
+use ashpd::{
+    desktop::HandleToken,
+    zvariant::{OwnedObjectPath, OwnedValue, Value},
+    AppID, PortalError,
+};
+use futures_util::StreamExt;
+use tokio::sync::OnceCell;
+
+use super::SecretImpl;
+use crate::{request::RequestImpl, Result};
+
+const SERVICE: &str = "org.freedesktop.secrets";
+const SERVICE_PATH: &str = "/org/freedesktop/secrets";
+const SERVICE_INTERFACE: &str = "org.freedesktop.Secret.Service";
+const COLLECTION_INTERFACE: &str = "org.freedesktop.Secret.Collection";
+const PROMPT_INTERFACE: &str = "org.freedesktop.Secret.Prompt";
+const SECRET_SIZE: usize = 64;
+
+/// A secret as sent over the Secret Service API: the session, the parameters,
+/// the value and its content type.
+type ServiceSecret = (OwnedObjectPath, Vec<u8>, Vec<u8>, String);
+
+/// A [`SecretImpl`] storing the master secret of each application in the
+/// default collection of the host keyring.
+///
+/// The secrets are retrieved with the
+/// [Secret Service API](https://specifications.freedesktop.org/secret-service-spec/latest/),
+/// implemented by GNOME Keyring, KWallet or oo7 for example. The first request
+/// of an application generates a random secret and stores it.
+///
+/// ```rust,no_run
+/// use ashpd_backend::secret::KeyringSecret;
+///
+/// async fn run() -> ashpd_backend::Result<()> {
+///     ashpd_backend::Builder::new("org.freedesktop.impl.portal.desktop.example")?
+///         .secret(KeyringSecret::default())
+///         .build()
+///         .await?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct KeyringSecret {
+    connection: OnceCell<zbus::Connection>,
+}
+
+impl KeyringSecret {
+    /// Create a new instance of [`KeyringSecret`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new instance of [`KeyringSecret`] talking to the keyring on
+    /// `connection` instead of the session bus.
+    pub fn with_connection(connection: zbus::Connection) -> Self {
+        Self {
+            connection: OnceCell::new_with(Some(connection)),
+        }
+    }
+
+    async fn proxy(&self, path: &str, interface: &'static str) -> Result<zbus::Proxy<'static>> {
+        let connection = self
+            .connection
+            .get_or_try_init(zbus::Connection::session)
+            .await?;
+        Ok(zbus::Proxy::new(connection, SERVICE, path.to_owned(), interface).await?)
+    }
+
+    /// Runs the prompt at `path`, if any, and returns its result.
+    async fn prompt(&self, path: &OwnedObjectPath) -> Result<Option<OwnedValue>> {
+        if path.as_str() == "/" {
+            return Ok(None);
+        }
+        let proxy = self.proxy(path, PROMPT_INTERFACE).await?;
+        let mut completed = proxy.receive_signal("Completed").await?;
+        proxy.call_method("Prompt", &("",)).await?;
+        let message = completed
+            .next()
+            .await
+            .ok_or_else(|| PortalError::Failed("The prompt was not completed".to_owned()))?;
+        let (dismissed, result) = message.body().deserialize::<(bool, OwnedValue)>()?;
+        if dismissed {
+            return Err(PortalError::Cancelled(
+                "The prompt was dismissed".to_owned(),
+            ));
+        }
+        Ok(Some(result))
+    }
+
+    async fn lookup(
+        &self,
+        service: &zbus::Proxy<'_>,
+        session: &OwnedObjectPath,
+        attributes: &HashMap<&str, &str>,
+    ) -> Result<Option<Vec<u8>>> {
+        let (unlocked, locked): (Vec<OwnedObjectPath>, Vec<OwnedObjectPath>) =
+            service.call("SearchItems", &(attributes,)).await?;
+        let item = match (unlocked.into_iter().next(), locked.into_iter().next()) {
+            (Some(item), _) => item,
+            (None, Some(item)) => {
+                let (unlocked, prompt): (Vec<OwnedObjectPath>, OwnedObjectPath) = service
+                    .call("Unlock", &(std::slice::from_ref(&item),))
+                    .await?;
+                if unlocked.is_empty() {
+                    self.prompt(&prompt).await?;
+                }
+                item
+            }
+            (None, None) => return Ok(None),
+        };
+        let mut secrets: HashMap<OwnedObjectPath, ServiceSecret> = service
+            .call("GetSecrets", &(std::slice::from_ref(&item), session))
+            .await?;
+        Ok(secrets.remove(&item).map(|(_, _, value, _)| value))
+    }
+
+    async fn create(
+        &self,
+        service: &zbus::Proxy<'_>,
+        session: &OwnedObjectPath,
+        app_id: &AppID,
+        attributes: &HashMap<&str, &str>,
+    ) -> Result<Vec<u8>> {
+        let mut secret = vec![0; SECRET_SIZE];
+        std::fs::File::open("/dev/urandom")
+            .and_then(|mut urandom| urandom.read_exact(&mut secret))
+            .map_err(|err| PortalError::Failed(format!("Failed to generate a secret: {err}")))?;
+
+        let collection: OwnedObjectPath = service.call("ReadAlias", &("default",)).await?;
+        if collection.as_str() == "/" {
+            return Err(PortalError::NotFound(
+                "The keyring has no default collection".to_owned(),
+            ));
+        }
+        let collection = self.proxy(&collection, COLLECTION_INTERFACE).await?;
+        if collection.get_property::<bool>("Locked").await? {
+            let (_, prompt): (Vec<OwnedObjectPath>, OwnedObjectPath) = service
+                .call("Unlock", &(std::slice::from_ref(collection.path()),))
+                .await?;
+            self.prompt(&prompt).await?;
+        }
+
+        let label = format!("Application key for {app_id}");
+        let properties = HashMap::from([
+            ("org.freedesktop.Secret.Item.Label", Value::from(label)),
+            (
+                "org.freedesktop.Secret.Item.Attributes",
+                Value::from(attributes.clone()),
+            ),
+        ]);
+        let service_secret: ServiceSecret = (
+            session.clone(),
+            Vec::new(),
+            secret.clone(),
+            "application/octet-stream".to_owned(),
+        );
+        let (_, prompt): (OwnedObjectPath, OwnedObjectPath) = collection
+            .call("CreateItem", &(properties, service_secret, true))
+            .await?;
+        self.prompt(&prompt).await?;
+        Ok(secret)
+    }
+
+    async fn secret(&self, app_id: &AppID) -> Result<Vec<u8>> {
+        let service = self.proxy(SERVICE_PATH, SERVICE_INTERFACE).await?;
+        // The secrets are sent as is over the bus, which is already private
+        // to the user session.
+        let (_, session): (OwnedValue, OwnedObjectPath) = service
+            .call("OpenSession", &("plain", Value::from("")))
+            .await?;
+        let attributes = HashMap::from([("app_id", app_id.as_ref())]);
+
+        let secret = match self.lookup(&service, &session, &attributes).await {
+            Ok(Some(secret)) => Ok(secret),
+            Ok(None) => self.create(&service, &session, app_id, &attributes).await,
+            Err(err) => Err(err),
+        };
+        if let Ok(session) = self.proxy(&session, "org.freedesktop.Secret.Session").await {
+            let _ = session.call_method("Close", &()).await;
+        }
+        secret
+    }
+}
+
+impl RequestImpl for KeyringSecret {
+    async fn close(&self, _token: HandleToken) {}
+}
+
+impl SecretImpl for KeyringSecret {
+    async fn retrieve(
+        &self,
+        _token: HandleToken,
+        app_id: AppID,
+        fd: OwnedFd,
+    ) -> Result<HashMap<String, OwnedValue>> {
+        let secret = self.secret(&app_id).await?;
+        std::io::Write::write_all(&mut std::fs::File::from(fd), &secret)
+            .map_err(|err| PortalError::Failed(format!("Failed to write the secret: {err}")))?;
+        Ok(HashMap::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        io::Read,
+        os::unix::net::UnixStream,
+        sync::{Arc, Mutex},
+    };
+
+    use ashpd::{
+        desktop::HandleToken,
+        zvariant::{OwnedObjectPath, OwnedValue, Value},
+        AppID, PortalError,
+    };
+    use zbus::object_server::SignalEmitter;
+
+    use super::{KeyringSecret, ServiceSecret, SECRET_SIZE};
+    use crate::{
+        secret::SecretImpl,
+        test::{block_on, peer_connections},
+    };
+
+    const COLLECTION_PATH: &str = "/org/freedesktop/secrets/collection/login";
+    const PROMPT_PATH: &str = "/org/freedesktop/secrets/prompt/1";
+
+    #[derive(Default)]
+    struct Keyring {
+        // The secrets of the items, by app_id attribute.
+        items: HashMap<String, Vec<u8>>,
+        locked: bool,
+        dismiss: bool,
+    }
+
+    struct Service(Arc<Mutex<Keyring>>);
+
+    fn item_path(app_id: &str) -> OwnedObjectPath {
+        OwnedObjectPath::try_from(format!("{COLLECTION_PATH}/{}", app_id.replace('.', "_")))
+            .unwrap()
+    }
+
+    fn prompt_path() -> OwnedObjectPath {
+        OwnedObjectPath::try_from(PROMPT_PATH).unwrap()
+    }
+
+    #[zbus::interface(name = "org.freedesktop.Secret.Service")]
+    impl Service {
+        fn open_session(
+            &self,
+            algorithm: &str,
+            _input: Value<'_>,
+        ) -> (OwnedValue, OwnedObjectPath) {
+            assert_eq!(algorithm, "plain");
+            (
+                OwnedValue::from(0u8),
+                OwnedObjectPath::try_from("/org/freedesktop/secrets/session/1").unwrap(),
+            )
+        }
+
+        fn search_items(
+            &self,
+            attributes: HashMap<String, String>,
+        ) -> (Vec<OwnedObjectPath>, Vec<OwnedObjectPath>) {
+            let keyring = self.0.lock().unwrap();
+            let items = keyring
+                .items
+                .keys()
+                .filter(|app_id| attributes.get("app_id") == Some(app_id))
+                .map(|app_id| item_path(app_id))
+                .collect();
+            if keyring.locked {
+                (Vec::new(), items)
+            } else {
+                (items, Vec::new())
+            }
+        }
+
+        fn unlock(
+            &self,
+            _objects: Vec<OwnedObjectPath>,
+        ) -> (Vec<OwnedObjectPath>, OwnedObjectPath) {
+            (Vec::new(), prompt_path())
+        }
+
+        fn read_alias(&self, name: &str) -> OwnedObjectPath {
+            assert_eq!(name, "default");
+            OwnedObjectPath::try_from(COLLECTION_PATH).unwrap()
+        }
+
+        fn get_secrets(
+            &self,
+            items: Vec<OwnedObjectPath>,
+            session: OwnedObjectPath,
+        ) -> HashMap<OwnedObjectPath, ServiceSecret> {
+            let keyring = self.0.lock().unwrap();
+            assert!(!keyring.locked);
+            keyring
+                .items
+                .iter()
+                .map(|(app_id, secret)| (item_path(app_id), secret))
+                .filter(|(path, _)| items.contains(path))
+                .map(|(path, secret)| {
+                    let secret = (session.clone(), Vec::new(), secret.clone(), String::new());
+                    (path, secret)
+                })
+                .collect()
+        }
+    }
+
+    struct Collection(Arc<Mutex<Keyring>>);
+
+    #[zbus::interface(name = "org.freedesktop.Secret.Collection")]
+    impl Collection {
+        #[zbus(property)]
+        fn locked(&self) -> bool {
+            self.0.lock().unwrap().locked
+        }
+
+        fn create_item(
+            &self,
+            properties: HashMap<String, OwnedValue>,
+            secret: ServiceSecret,
+            replace: bool,
+        ) -> (OwnedObjectPath, OwnedObjectPath) {
+            assert!(replace);
+            let mut keyring = self.0.lock().unwrap();
+            assert!(!keyring.locked);
+            let attributes = HashMap::<String, String>::try_from(
+                properties["org.freedesktop.Secret.Item.Attributes"].clone(),
+            )
+            .unwrap();
+            let app_id = attributes["app_id"].clone();
+            keyring.items.insert(app_id.clone(), secret.2);
+            (item_path(&app_id), OwnedObjectPath::try_from("/").unwrap())
+        }
+    }
+
+    struct Prompt(Arc<Mutex<Keyring>>);
+
+    #[zbus::interface(name = "org.freedesktop.Secret.Prompt")]
+    impl Prompt {
+        async fn prompt(
+            &self,
+            _window_id: &str,
+            #[zbus(signal_emitter)] emitter: SignalEmitter<'_>,
+        ) -> zbus::fdo::Result<()> {
+            let dismissed = {
+                let mut keyring = self.0.lock().unwrap();
+                keyring.locked &= keyring.dismiss;
+                keyring.dismiss
+            };
+            Self::completed(&emitter, dismissed, Value::from("")).await?;
+            Ok(())
+        }
+
+        #[zbus(signal)]
+        async fn completed(
+            emitter: &SignalEmitter<'_>,
+            dismissed: bool,
+            result: Value<'_>,
+        ) -> zbus::Result<()>;
+    }
+
+    struct Session;
+
+    #[zbus::interface(name = "org.freedesktop.Secret.Session")]
+    impl Session {
+        fn close(&self) {}
+    }
+
+    /// Serves `keyring`, returning the server side of the connection, to keep
+    /// it alive, and a [`KeyringSecret`] using it.
+    async fn keyring(keyring: Keyring) -> (zbus::Connection, KeyringSecret, Arc<Mutex<Keyring>>) {
+        let (server, client) = peer_connections().await;
+        let keyring = Arc::new(Mutex::new(keyring));
+        let object_server = server.object_server();
+        object_server
+            .at(super::SERVICE_PATH, Service(keyring.clone()))
+            .await
+            .unwrap();
+        object_server
+            .at(COLLECTION_PATH, Collection(keyring.clone()))
+            .await
+            .unwrap();
+        object_server
+            .at(PROMPT_PATH, Prompt(keyring.clone()))
+            .await
+            .unwrap();
+        object_server
+            .at("/org/freedesktop/secrets/session/1", Session)
+            .await
+            .unwrap();
+        (server, KeyringSecret::with_connection(client), keyring)
+    }
+
+    async fn retrieve(secret: &KeyringSecret, app_id: &str) -> crate::Result<Vec<u8>> {
+        let (mut reader, writer) = UnixStream::pair().unwrap();
+        secret
+            .retrieve(
+                HandleToken::try_from("secret1").unwrap(),
+                AppID::try_from(app_id).unwrap(),
+                writer.into(),
+            )
+            .await?;
+        let mut value = Vec::new();
+        reader.read_to_end(&mut value).unwrap();
+        Ok(value)
+    }
+
+    #[test]
+    fn created_once() {
+        block_on(async {
+            let (_server, secret, keyring) = keyring(Keyring::default()).await;
+            let maps = retrieve(&secret, "org.gnome.Maps").await.unwrap();
+            assert_eq!(maps.len(), SECRET_SIZE);
+            assert_eq!(keyring.lock().unwrap().items["org.gnome.Maps"], maps);
+
+            // The secret is looked up after it was created.
+            assert_eq!(retrieve(&secret, "org.gnome.Maps").await.unwrap(), maps);
+            // Each application has its own secret.
+            let weather = retrieve(&secret, "org.gnome.Weather").await.unwrap();
+            assert_ne!(weather, maps);
+            assert_eq!(keyring.lock().unwrap().items.len(), 2);
+        });
+    }
+
+    #[test]
+    fn locked() {
+        block_on(async {
+            let (_server, secret, keyring) = keyring(Keyring {
+                items: HashMap::from([("org.gnome.Maps".to_owned(), b"maps".to_vec())]),
+                locked: true,
+                dismiss: false,
+            })
+            .await;
+            // The item is unlocked with a prompt.
+            assert_eq!(retrieve(&secret, "org.gnome.Maps").await.unwrap(), b"maps");
+            assert!(!keyring.lock().unwrap().locked);
+        });
+    }
+
+    #[test]
+    fn dismissed() {
+        block_on(async {
+            let (_server, secret, keyring) = keyring(Keyring {
+                items: HashMap::new(),
+                locked: true,
+                dismiss: true,
+            })
+            .await;
+            // The collection can't be unlocked to create the secret.
+            let err = retrieve(&secret, "org.gnome.Maps").await.unwrap_err();
+            assert!(matches!(err, PortalError::Cancelled(_)));
+            assert!(keyring.lock().unwrap().items.is_empty());
+        });
+    }
+}
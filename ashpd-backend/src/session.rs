@@ -0,0 +1,198 @@
+use std::{future::Future, sync::Arc};
+
+use ashpd::{desktop::HandleToken, PortalError};
+use tokio::sync::Mutex;
+use zbus::{
+    object_server::SignalEmitter,
+    zvariant::{ObjectPath, OwnedObjectPath},
+};
+
+use crate::idle::Busy;
+
+pub trait SessionImpl: Send + Sync {
+    fn close(&self, token: HandleToken) -> impl Future<Output = ()> + Send;
+}
+
+/// A session created by a portal that keeps a state across its method calls,
+/// like the ScreenCast or RemoteDesktop ones.
+///
+/// The backend doesn't exit when idle while a session is served.
+pub struct Session {
+    close_cb: Mutex<Option<Box<dyn FnOnce() + Send + Sync>>>,
+    path: OwnedObjectPath,
+    _busy: Busy,
+}
+
+impl Session {
+    pub fn path(&self) -> ObjectPath<'_> {
+        self.path.as_ref()
+    }
+
+    /// Serves the session at `path`, the session handle given by the
+    /// frontend, until it is closed by the frontend or with
+    /// [`Session::close`].
+    pub async fn serve<I: SessionImpl + 'static>(
+        cnx: &zbus::Connection,
+        path: OwnedObjectPath,
+        imp: Arc<I>,
+    ) -> crate::Result<()> {
+        let token = HandleToken::try_from(&path)
+            .map_err(|err| PortalError::InvalidArgument(err.to_string()))?;
+        let close_cb = || {
+            tokio::spawn(async move {
+                imp.close(token).await;
+            });
+        };
+        let session = Self {
+            close_cb: Mutex::new(Some(Box::new(close_cb))),
+            path: path.clone(),
+            _busy: Busy::new(),
+        };
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            "Serving `org.freedesktop.impl.portal.Session` at {:?}",
+            path.as_str()
+        );
+        cnx.object_server().at(&path, session).await?;
+        Ok(())
+    }
+
+    /// Closes the session at `path` on behalf of the backend, notifying the
+    /// frontend with the `Closed` signal.
+    pub async fn close(cnx: &zbus::Connection, path: &ObjectPath<'_>) -> crate::Result<()> {
+        let server = cnx.object_server();
+        let session = server.interface::<_, Self>(path).await?;
+        Self::closed(session.signal_emitter()).await?;
+        #[cfg(feature = "tracing")]
+        tracing::debug!("Releasing session {:?}", path.as_str());
+        server.remove::<Self, _>(path).await?;
+        Ok(())
+    }
+}
+
+#[zbus::interface(name = "org.freedesktop.impl.portal.Session")]
+impl Session {
+    #[zbus(property(emits_changed_signal = "const"), name = "version")]
+    fn version(&self) -> u32 {
+        1
+    }
+
+    #[zbus(name = "Close")]
+    async fn close_session(
+        &self,
+        #[zbus(object_server)] server: &zbus::ObjectServer,
+    ) -> zbus::fdo::Result<()> {
+        if let Some(close_cb) = self.close_cb.lock().await.take() {
+            close_cb();
+        }
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!("Releasing session {:?}", self.path.as_str());
+        server.remove::<Self, _>(&self.path).await?;
+        Ok(())
+    }
+
+    #[zbus(signal)]
+    async fn closed(signal_emitter: &SignalEmitter<'_>) -> zbus::Result<()>;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use ashpd::desktop::HandleToken;
+    use futures_util::StreamExt;
+    use tokio::{
+        sync::mpsc::{unbounded_channel, UnboundedSender},
+        time::timeout,
+    };
+    use zbus::zvariant::OwnedObjectPath;
+
+    use super::{Session, SessionImpl};
+    use crate::{
+        idle::{
+            tests::{outstanding, LOCK},
+            wait_idle,
+        },
+        test::{block_on, peer_connections},
+    };
+
+    const INTERFACE: &str = "org.freedesktop.impl.portal.Session";
+
+    struct Imp(UnboundedSender<HandleToken>);
+
+    impl SessionImpl for Imp {
+        async fn close(&self, token: HandleToken) {
+            self.0.send(token).unwrap();
+        }
+    }
+
+    fn session_path(token: &str) -> OwnedObjectPath {
+        OwnedObjectPath::try_from(format!(
+            "/org/freedesktop/portal/desktop/session/1_42/{token}"
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn closed_by_frontend() {
+        block_on(async {
+            let _lock = LOCK.lock().await;
+            let (server, client) = peer_connections().await;
+            let (sender, mut closed) = unbounded_channel();
+            let path = session_path("session1");
+            Session::serve(&server, path.clone(), Arc::new(Imp(sender)))
+                .await
+                .unwrap();
+            assert_eq!(outstanding(), 1);
+
+            // The backend isn't idle while the session is open.
+            let idle = timeout(
+                Duration::from_millis(200),
+                wait_idle(&server, Duration::from_millis(50)),
+            )
+            .await;
+            assert!(idle.is_err());
+
+            client
+                .call_method(None::<&str>, &path, Some(INTERFACE), "Close", &())
+                .await
+                .unwrap();
+            let token = closed.recv().await.unwrap();
+            assert_eq!(token, HandleToken::try_from("session1").unwrap());
+            assert_eq!(outstanding(), 0);
+
+            timeout(
+                Duration::from_secs(1),
+                wait_idle(&server, Duration::from_millis(50)),
+            )
+            .await
+            .unwrap();
+        });
+    }
+
+    #[test]
+    fn closed_by_backend() {
+        block_on(async {
+            let _lock = LOCK.lock().await;
+            let (server, client) = peer_connections().await;
+            let (sender, mut closed) = unbounded_channel();
+            let path = session_path("session2");
+            Session::serve(&server, path.clone(), Arc::new(Imp(sender)))
+                .await
+                .unwrap();
+            assert_eq!(outstanding(), 1);
+
+            let mut messages = zbus::MessageStream::from(&client);
+            Session::close(&server, &path.as_ref()).await.unwrap();
+            let signal = messages.next().await.unwrap().unwrap();
+            let header = signal.header();
+            assert_eq!(header.path(), Some(&path.as_ref()));
+            assert_eq!(header.member().unwrap(), "Closed");
+            assert_eq!(outstanding(), 0);
+            // The implementation is only told about the sessions closed by
+            // the frontend.
+            assert!(closed.try_recv().is_err());
+        });
+    }
+}
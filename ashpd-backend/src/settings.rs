@@ -0,0 +1,222 @@
+use std::{collections::HashMap, future::Future, sync::Arc};
+
+use ashpd::{
+    desktop::{
+        settings::{
+            ColorScheme, Contrast, Namespace, ACCENT_COLOR_SCHEME_KEY, APPEARANCE_NAMESPACE,
+            COLOR_SCHEME_KEY, CONTRAST_KEY,
+        },
+        Color,
+    },
+    zbus::object_server::SignalEmitter,
+    zvariant::{OwnedValue, Value},
+    PortalError,
+};
+use futures_util::future::BoxFuture;
+
+use crate::backend::PortalInterface;
+
+#[cfg(feature = "gsettings")]
+mod gsettings;
+#[cfg(feature = "gsettings")]
+pub use gsettings::{variant_to_value, GSettingsBridge};
+
+pub trait SettingsSignalEmitter: Send + Sync {
+    fn emit_changed<'a>(
+        &'a self,
+        namespace: &'a str,
+        key: &'a str,
+        value: Value<'a>,
+    ) -> BoxFuture<'a, zbus::Result<()>>;
+    fn emit_contrast_changed<'a>(&'a self, contrast: Contrast) -> BoxFuture<'a, zbus::Result<()>>;
+    fn emit_accent_color_changed<'a>(&'a self, color: Color) -> BoxFuture<'a, zbus::Result<()>>;
+    fn emit_color_scheme_changed<'a>(
+        &'a self,
+        scheme: ColorScheme,
+    ) -> BoxFuture<'a, zbus::Result<()>>;
+}
+
+pub trait SettingsImpl: Send + Sync {
+    fn read_all(
+        &self,
+        namespaces: Vec<String>,
+    ) -> impl Future<Output = Result<HashMap<String, Namespace>, PortalError>> + Send;
+
+    fn read(
+        &self,
+        namespace: &str,
+        key: &str,
+    ) -> impl Future<Output = Result<OwnedValue, PortalError>> + Send;
+
+    // Set the signal emitter, allowing to notify of changes.
+    fn set_signal_emitter(&mut self, signal_emitter: Arc<dyn SettingsSignalEmitter>);
+}
+
+/// Object safe counterpart of [`SettingsImpl`], used to store the implementation.
+pub(crate) trait DynSettingsImpl: Send + Sync {
+    fn read_all<'a>(
+        &'a self,
+        namespaces: Vec<String>,
+    ) -> BoxFuture<'a, Result<HashMap<String, Namespace>, PortalError>>;
+
+    fn read<'a>(
+        &'a self,
+        namespace: &'a str,
+        key: &'a str,
+    ) -> BoxFuture<'a, Result<OwnedValue, PortalError>>;
+
+    fn set_signal_emitter(&mut self, signal_emitter: Arc<dyn SettingsSignalEmitter>);
+}
+
+impl<T: SettingsImpl> DynSettingsImpl for T {
+    fn read_all<'a>(
+        &'a self,
+        namespaces: Vec<String>,
+    ) -> BoxFuture<'a, Result<HashMap<String, Namespace>, PortalError>> {
+        Box::pin(SettingsImpl::read_all(self, namespaces))
+    }
+
+    fn read<'a>(
+        &'a self,
+        namespace: &'a str,
+        key: &'a str,
+    ) -> BoxFuture<'a, Result<OwnedValue, PortalError>> {
+        Box::pin(SettingsImpl::read(self, namespace, key))
+    }
+
+    fn set_signal_emitter(&mut self, signal_emitter: Arc<dyn SettingsSignalEmitter>) {
+        SettingsImpl::set_signal_emitter(self, signal_emitter)
+    }
+}
+
+pub(crate) struct SettingsInterface {
+    imp: Arc<dyn DynSettingsImpl>,
+    version: u32,
+}
+
+impl SettingsInterface {
+    pub fn new(imp: Arc<dyn DynSettingsImpl>) -> Self {
+        Self { imp, version: 2 }
+    }
+}
+
+impl PortalInterface for SettingsInterface {
+    fn set_version(&mut self, version: u32) {
+        self.version = version;
+    }
+}
+
+/// Emits the signals of the [`SettingsInterface`] served on a connection.
+pub(crate) struct SettingsEmitter {
+    cnx: zbus::Connection,
+}
+
+impl SettingsEmitter {
+    pub fn new(cnx: zbus::Connection) -> Self {
+        Self { cnx }
+    }
+
+    pub async fn changed(&self, namespace: &str, key: &str, value: Value<'_>) -> zbus::Result<()> {
+        let object_server = self.cnx.object_server();
+        let iface_ref = object_server
+            .interface::<_, SettingsInterface>(crate::DESKTOP_PATH)
+            .await?;
+        SettingsInterface::setting_changed(iface_ref.signal_emitter(), namespace, key, value).await
+    }
+
+    pub async fn contrast_changed(&self, contrast: Contrast) -> zbus::Result<()> {
+        self.changed(
+            APPEARANCE_NAMESPACE,
+            CONTRAST_KEY,
+            OwnedValue::from(contrast).into(),
+        )
+        .await
+    }
+
+    pub async fn accent_color_changed(&self, color: Color) -> zbus::Result<()> {
+        self.changed(
+            APPEARANCE_NAMESPACE,
+            ACCENT_COLOR_SCHEME_KEY,
+            OwnedValue::try_from(color).unwrap().into(),
+        )
+        .await
+    }
+
+    pub async fn color_scheme_changed(&self, scheme: ColorScheme) -> zbus::Result<()> {
+        self.changed(
+            APPEARANCE_NAMESPACE,
+            COLOR_SCHEME_KEY,
+            OwnedValue::from(scheme).into(),
+        )
+        .await
+    }
+}
+
+impl SettingsSignalEmitter for SettingsEmitter {
+    fn emit_changed<'a>(
+        &'a self,
+        namespace: &'a str,
+        key: &'a str,
+        value: Value<'a>,
+    ) -> BoxFuture<'a, zbus::Result<()>> {
+        Box::pin(self.changed(namespace, key, value))
+    }
+
+    fn emit_contrast_changed<'a>(&'a self, contrast: Contrast) -> BoxFuture<'a, zbus::Result<()>> {
+        Box::pin(self.contrast_changed(contrast))
+    }
+
+    fn emit_accent_color_changed<'a>(&'a self, color: Color) -> BoxFuture<'a, zbus::Result<()>> {
+        Box::pin(self.accent_color_changed(color))
+    }
+
+    fn emit_color_scheme_changed<'a>(
+        &'a self,
+        scheme: ColorScheme,
+    ) -> BoxFuture<'a, zbus::Result<()>> {
+        Box::pin(self.color_scheme_changed(scheme))
+    }
+}
+
+#[zbus::interface(name = "org.freedesktop.impl.portal.Settings")]
+impl SettingsInterface {
+    #[zbus(property, name = "version")]
+    fn version(&self) -> u32 {
+        self.version
+    }
+
+    #[zbus(out_args("value"))]
+    async fn read_all(
+        &self,
+        namespaces: Vec<String>,
+    ) -> Result<HashMap<String, Namespace>, PortalError> {
+        #[cfg(feature = "tracing")]
+        tracing::debug!("Settings::ReadAll");
+
+        let response = self.imp.read_all(namespaces).await;
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!("Settings::ReadAll returned {:#?}", response);
+        response
+    }
+
+    #[zbus(out_args("value"))]
+    async fn read(&self, namespace: &str, key: &str) -> Result<OwnedValue, PortalError> {
+        #[cfg(feature = "tracing")]
+        tracing::debug!("Settings::Read");
+
+        let response = self.imp.read(namespace, key).await;
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!("Settings::Read returned {:#?}", response);
+        response
+    }
+
+    #[zbus(signal)]
+    async fn setting_changed(
+        signal_ctxt: &SignalEmitter<'_>,
+        namespace: &str,
+        key: &str,
+        value: Value<'_>,
+    ) -> zbus::Result<()>;
+}
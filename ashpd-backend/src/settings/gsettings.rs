@@ -0,0 +1,422 @@
+use std::{collections::HashMap, sync::Arc};
+
+use ashpd::{
+    desktop::settings::Namespace,
+    zvariant::{Array, Dict, ObjectPath, OwnedValue, Signature, StructureBuilder, Value},
+    PortalError,
+};
+use gio::prelude::*;
+
+use super::{SettingsImpl, SettingsSignalEmitter};
+
+type Converter = Arc<dyn Fn(&glib::Variant) -> Option<OwnedValue> + Send + Sync>;
+
+#[derive(Clone)]
+struct Mapping {
+    schema: String,
+    key: String,
+    namespace: String,
+    portal_key: String,
+    convert: Converter,
+}
+
+impl Mapping {
+    fn value(&self, settings: &gio::Settings) -> Option<OwnedValue> {
+        (self.convert)(&settings.value(&self.key))
+    }
+}
+
+/// A [`SettingsImpl`] exposing GSettings keys, stored in dconf for example.
+///
+/// The keys are looked up in the installed schemas, the ones that are missing
+/// are skipped. The changes of the keys are forwarded as `SettingChanged`
+/// signals once the bridge is served.
+///
+/// ```rust,no_run
+/// use ashpd::desktop::settings::{APPEARANCE_NAMESPACE, COLOR_SCHEME_KEY};
+/// use ashpd_backend::settings::GSettingsBridge;
+///
+/// async fn run() -> ashpd_backend::Result<()> {
+///     let bridge = GSettingsBridge::new()
+///         .schema("org.gnome.desktop.interface")
+///         .key("org.gnome.desktop.wm.preferences", "button-layout")
+///         .map_key(
+///             "org.gnome.desktop.interface",
+///             "color-scheme",
+///             APPEARANCE_NAMESPACE,
+///             COLOR_SCHEME_KEY,
+///             |value| match value.str()? {
+///                 "prefer-dark" => Some(1u32.into()),
+///                 "prefer-light" => Some(2u32.into()),
+///                 _ => Some(0u32.into()),
+///             },
+///         );
+///     ashpd_backend::Builder::new("org.freedesktop.impl.portal.desktop.example")?
+///         .settings(bridge)
+///         .build()
+///         .await?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Default)]
+pub struct GSettingsBridge {
+    mappings: Vec<Mapping>,
+    main_loop: Option<glib::MainLoop>,
+}
+
+impl GSettingsBridge {
+    /// Create a bridge without any key.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Expose all the keys of `schema` in a namespace of the same name.
+    pub fn schema(mut self, schema: &str) -> Self {
+        let Some(settings_schema) = lookup_schema(schema) else {
+            return self;
+        };
+        for key in settings_schema.list_keys() {
+            self.push(schema, &key, schema, &key, Arc::new(variant_to_value));
+        }
+        self
+    }
+
+    /// Expose the `key` of `schema` in a namespace of the same name.
+    pub fn key(self, schema: &str, key: &str) -> Self {
+        self.map_key(schema, key, schema, key, variant_to_value)
+    }
+
+    /// Expose the `key` of `schema` as `portal_key` of `namespace`, with the
+    /// value returned by `convert`.
+    ///
+    /// The key is omitted whenever `convert` returns `None`.
+    pub fn map_key<F>(
+        mut self,
+        schema: &str,
+        key: &str,
+        namespace: &str,
+        portal_key: &str,
+        convert: F,
+    ) -> Self
+    where
+        F: Fn(&glib::Variant) -> Option<OwnedValue> + Send + Sync + 'static,
+    {
+        if lookup_schema(schema).is_some_and(|settings_schema| settings_schema.has_key(key)) {
+            self.push(schema, key, namespace, portal_key, Arc::new(convert));
+        }
+        self
+    }
+
+    fn push(
+        &mut self,
+        schema: &str,
+        key: &str,
+        namespace: &str,
+        portal_key: &str,
+        convert: Converter,
+    ) {
+        self.mappings.push(Mapping {
+            schema: schema.to_owned(),
+            key: key.to_owned(),
+            namespace: namespace.to_owned(),
+            portal_key: portal_key.to_owned(),
+            convert,
+        });
+    }
+
+    fn read_all_sync(&self, namespaces: &[String]) -> HashMap<String, Namespace> {
+        let mut schemas = HashMap::<&str, gio::Settings>::new();
+        let mut result = HashMap::<String, Namespace>::new();
+        for mapping in &self.mappings {
+            if !matches_namespaces(namespaces, &mapping.namespace) {
+                continue;
+            }
+            let settings = schemas
+                .entry(mapping.schema.as_str())
+                .or_insert_with(|| gio::Settings::new(&mapping.schema));
+            if let Some(value) = mapping.value(settings) {
+                result
+                    .entry(mapping.namespace.clone())
+                    .or_default()
+                    .insert(mapping.portal_key.clone(), value);
+            }
+        }
+        result
+    }
+
+    fn read_sync(&self, namespace: &str, key: &str) -> Result<OwnedValue, PortalError> {
+        let mapping = self
+            .mappings
+            .iter()
+            .find(|mapping| mapping.namespace == namespace && mapping.portal_key == key)
+            .ok_or_else(|| {
+                PortalError::NotFound(format!("Unknown namespace=`{namespace}` & key=`{key}`"))
+            })?;
+        mapping
+            .value(&gio::Settings::new(&mapping.schema))
+            .ok_or_else(|| {
+                PortalError::Failed(format!(
+                    "Unsupported value of namespace=`{namespace}` & key=`{key}`"
+                ))
+            })
+    }
+}
+
+impl SettingsImpl for GSettingsBridge {
+    async fn read_all(
+        &self,
+        namespaces: Vec<String>,
+    ) -> Result<HashMap<String, Namespace>, PortalError> {
+        Ok(self.read_all_sync(&namespaces))
+    }
+
+    async fn read(&self, namespace: &str, key: &str) -> Result<OwnedValue, PortalError> {
+        self.read_sync(namespace, key)
+    }
+
+    fn set_signal_emitter(&mut self, signal_emitter: Arc<dyn SettingsSignalEmitter>) {
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            #[cfg(feature = "tracing")]
+            tracing::warn!("GSettings changes are not forwarded outside of a tokio runtime");
+            return;
+        };
+        if let Some(main_loop) = self.main_loop.take() {
+            main_loop.quit();
+        }
+
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        runtime.spawn(async move {
+            while let Some((namespace, key, value)) = receiver.recv().await {
+                let _ = signal_emitter
+                    .emit_changed(&namespace, &key, Value::from(value))
+                    .await;
+            }
+        });
+
+        // GSettings notifies the changes on the main context it was created
+        // with, run one on a dedicated thread.
+        let context = glib::MainContext::new();
+        let main_loop = glib::MainLoop::new(Some(&context), false);
+        self.main_loop = Some(main_loop.clone());
+        let mappings = self.mappings.clone();
+        std::thread::spawn(move || {
+            let _ = context.with_thread_default(|| {
+                let mut schemas = HashMap::<String, Vec<Mapping>>::new();
+                for mapping in mappings {
+                    schemas
+                        .entry(mapping.schema.clone())
+                        .or_default()
+                        .push(mapping);
+                }
+                let settings = schemas
+                    .into_iter()
+                    .map(|(schema, mappings)| {
+                        let settings = gio::Settings::new(&schema);
+                        let sender = sender.clone();
+                        settings.connect_changed(None, move |settings, key| {
+                            for mapping in mappings.iter().filter(|mapping| mapping.key == key) {
+                                if let Some(value) = mapping.value(settings) {
+                                    let _ = sender.send((
+                                        mapping.namespace.clone(),
+                                        mapping.portal_key.clone(),
+                                        value,
+                                    ));
+                                }
+                            }
+                        });
+                        settings
+                    })
+                    .collect::<Vec<_>>();
+                main_loop.run();
+                drop(settings);
+            });
+        });
+    }
+}
+
+impl Drop for GSettingsBridge {
+    fn drop(&mut self) {
+        if let Some(main_loop) = self.main_loop.take() {
+            main_loop.quit();
+        }
+    }
+}
+
+fn lookup_schema(schema: &str) -> Option<gio::SettingsSchema> {
+    // Creating a `gio::Settings` of a missing schema or reading a missing key
+    // aborts the process, check them beforehand.
+    gio::SettingsSchemaSource::default()?.lookup(schema, true)
+}
+
+fn matches_namespaces(namespaces: &[String], namespace: &str) -> bool {
+    namespaces.is_empty()
+        || namespaces.iter().any(|filter| {
+            filter.is_empty()
+                || match filter.strip_suffix('*') {
+                    Some(prefix) => namespace.starts_with(prefix),
+                    None => filter == namespace,
+                }
+        })
+}
+
+/// Convert a [`glib::Variant`] to its D-Bus value.
+///
+/// Returns `None` for the types that can't be sent over D-Bus, like maybe
+/// types.
+pub fn variant_to_value(variant: &glib::Variant) -> Option<OwnedValue> {
+    OwnedValue::try_from(to_value(variant)?).ok()
+}
+
+fn to_value(variant: &glib::Variant) -> Option<Value<'static>> {
+    use glib::VariantClass;
+
+    let value = match variant.classify() {
+        VariantClass::Boolean => Value::from(variant.get::<bool>()?),
+        VariantClass::Byte => Value::from(variant.get::<u8>()?),
+        VariantClass::Int16 => Value::from(variant.get::<i16>()?),
+        VariantClass::Uint16 => Value::from(variant.get::<u16>()?),
+        VariantClass::Int32 => Value::from(variant.get::<i32>()?),
+        VariantClass::Uint32 => Value::from(variant.get::<u32>()?),
+        VariantClass::Int64 => Value::from(variant.get::<i64>()?),
+        VariantClass::Uint64 => Value::from(variant.get::<u64>()?),
+        VariantClass::Double => Value::from(variant.get::<f64>()?),
+        VariantClass::String => Value::from(variant.str()?.to_owned()),
+        VariantClass::ObjectPath => {
+            Value::from(ObjectPath::try_from(variant.str()?.to_owned()).ok()?)
+        }
+        VariantClass::Signature => Value::Signature(Signature::try_from(variant.str()?).ok()?),
+        VariantClass::Variant => Value::new(to_value(&variant.as_variant()?)?),
+        VariantClass::Array => {
+            let element = variant.type_().element();
+            if element.is_dict_entry() {
+                let mut dict = Dict::new(
+                    &Signature::try_from(element.key().as_str()).ok()?,
+                    &Signature::try_from(element.value().as_str()).ok()?,
+                );
+                for entry in variant.iter() {
+                    dict.append(
+                        to_value(&entry.child_value(0))?,
+                        to_value(&entry.child_value(1))?,
+                    )
+                    .ok()?;
+                }
+                Value::from(dict)
+            } else {
+                let mut array = Array::new(&Signature::try_from(element.as_str()).ok()?);
+                for child in variant.iter() {
+                    array.append(to_value(&child)?).ok()?;
+                }
+                Value::from(array)
+            }
+        }
+        VariantClass::Tuple => {
+            let mut structure = StructureBuilder::new();
+            for child in variant.iter() {
+                structure = structure.append_field(to_value(&child)?);
+            }
+            Value::from(structure.build().ok()?)
+        }
+        _ => return None,
+    };
+    Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use ashpd::{zvariant::OwnedValue, PortalError};
+    use glib::prelude::*;
+
+    use super::{matches_namespaces, variant_to_value, GSettingsBridge};
+
+    #[test]
+    fn namespaces() {
+        let filters = |filters: &[&str]| {
+            filters
+                .iter()
+                .map(|filter| filter.to_string())
+                .collect::<Vec<_>>()
+        };
+        assert!(matches_namespaces(&[], "org.gnome.desktop.interface"));
+        assert!(matches_namespaces(
+            &filters(&[""]),
+            "org.gnome.desktop.interface"
+        ));
+        assert!(matches_namespaces(
+            &filters(&["org.gnome.desktop.*"]),
+            "org.gnome.desktop.interface"
+        ));
+        assert!(matches_namespaces(
+            &filters(&["org.freedesktop.appearance", "org.gnome.desktop.interface"]),
+            "org.gnome.desktop.interface"
+        ));
+        assert!(!matches_namespaces(
+            &filters(&["org.gnome.desktop"]),
+            "org.gnome.desktop.interface"
+        ));
+        assert!(!matches_namespaces(
+            &filters(&["org.kde.*"]),
+            "org.gnome.desktop.interface"
+        ));
+    }
+
+    #[test]
+    fn values() {
+        assert_eq!(
+            variant_to_value(&true.to_variant()).unwrap(),
+            OwnedValue::from(true)
+        );
+        assert_eq!(
+            variant_to_value(&42u32.to_variant()).unwrap(),
+            OwnedValue::from(42u32)
+        );
+        assert_eq!(
+            String::try_from(variant_to_value(&"prefer-dark".to_variant()).unwrap()).unwrap(),
+            "prefer-dark"
+        );
+
+        let value = variant_to_value(&vec!["appmenu", "close"].to_variant()).unwrap();
+        assert_eq!(
+            Vec::<String>::try_from(value).unwrap(),
+            ["appmenu", "close"]
+        );
+
+        let dict = HashMap::from([("font".to_owned(), 11u32)]);
+        let value = variant_to_value(&dict.to_variant()).unwrap();
+        assert_eq!(HashMap::<String, u32>::try_from(value).unwrap(), dict);
+
+        let value = variant_to_value(&("rgba", 0.5f64).to_variant()).unwrap();
+        assert_eq!(
+            <(String, f64)>::try_from(value).unwrap(),
+            ("rgba".to_owned(), 0.5)
+        );
+
+        let value = variant_to_value(&glib::Variant::from_variant(&7i32.to_variant())).unwrap();
+        assert_eq!(value.value_signature(), "v");
+
+        // The maybe types can't be sent over D-Bus.
+        assert!(variant_to_value(&Some(7i32).to_variant()).is_none());
+        assert!(variant_to_value(&vec![Some(7i32)].to_variant()).is_none());
+    }
+
+    #[test]
+    fn missing_schemas() {
+        let bridge = GSettingsBridge::new()
+            .schema("org.example.Missing")
+            .key("org.example.Missing", "color-scheme")
+            .map_key(
+                "org.example.Missing",
+                "color-scheme",
+                "org.freedesktop.appearance",
+                "color-scheme",
+                |_| Some(OwnedValue::from(1u32)),
+            );
+        assert!(bridge.mappings.is_empty());
+        assert!(bridge.read_all_sync(&[]).is_empty());
+        assert!(matches!(
+            bridge.read_sync("org.freedesktop.appearance", "color-scheme"),
+            Err(PortalError::NotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,29 @@
+//! Helpers of the tests.
+
+use std::future::Future;
+
+/// Runs `future` to completion on a new runtime.
+pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(future)
+}
+
+/// Creates a private peer-to-peer connection, returning the server side, the
+/// one of the backend, and the client side, the one of the frontend.
+pub(crate) async fn peer_connections() -> (zbus::Connection, zbus::Connection) {
+    let (server, client) = tokio::net::UnixStream::pair().unwrap();
+    let guid = zbus::Guid::generate();
+    futures_util::future::try_join(
+        zbus::connection::Builder::unix_stream(server)
+            .server(guid)
+            .unwrap()
+            .p2p()
+            .build(),
+        zbus::connection::Builder::unix_stream(client).p2p().build(),
+    )
+    .await
+    .unwrap()
+}
@@ -0,0 +1,329 @@
+//! Enumerate the USB devices with udev and follow their hotplug events, in
+//! the format used by the USB portal.
+
+use std::{
+    collections::HashMap,
+    ffi::CString,
+    io,
+    os::unix::ffi::OsStrExt,
+    path::{Path, PathBuf},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use ashpd::zvariant::OwnedValue;
+use futures_util::Stream;
+use tokio::io::unix::AsyncFd;
+
+/// A USB device, as exposed by the portal.
+#[derive(Debug, Clone)]
+pub struct UsbDevice {
+    id: String,
+    parent: Option<String>,
+    device_file: Option<PathBuf>,
+    readable: bool,
+    writable: bool,
+    properties: HashMap<String, String>,
+}
+
+impl UsbDevice {
+    fn new(device: &udev::Device) -> Self {
+        let device_file = device.devnode().map(Path::to_path_buf);
+        let (readable, writable) = device_file
+            .as_deref()
+            .map(|path| (access(path, libc::R_OK), access(path, libc::W_OK)))
+            .unwrap_or_default();
+        let properties = device
+            .properties()
+            .filter_map(|entry| {
+                Some((
+                    entry.name().to_str()?.to_owned(),
+                    entry.value().to_str()?.to_owned(),
+                ))
+            })
+            .collect();
+        Self {
+            id: device_id(device),
+            parent: device
+                .parent_with_subsystem_devtype("usb", "usb_device")
+                .ok()
+                .flatten()
+                .map(|parent| device_id(&parent)),
+            device_file,
+            readable,
+            writable,
+            properties,
+        }
+    }
+
+    /// The identifier of the device, its sysfs path.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// The identifier of the parent device, a hub for example.
+    pub fn parent(&self) -> Option<&str> {
+        self.parent.as_deref()
+    }
+
+    /// The device node of the device.
+    pub fn device_file(&self) -> Option<&Path> {
+        self.device_file.as_deref()
+    }
+
+    /// Whether the current user can read the device node.
+    pub fn is_readable(&self) -> bool {
+        self.readable
+    }
+
+    /// Whether the current user can write to the device node.
+    pub fn is_writable(&self) -> bool {
+        self.writable
+    }
+
+    /// The udev properties of the device, `ID_VENDOR_ID` or `ID_MODEL` for
+    /// example.
+    pub fn properties(&self) -> &HashMap<String, String> {
+        &self.properties
+    }
+
+    /// The properties dictionary of the device, as sent by the portal.
+    pub fn to_dict(&self) -> HashMap<String, OwnedValue> {
+        let mut dict = HashMap::new();
+        if let Some(parent) = &self.parent {
+            dict.insert("parent".to_owned(), OwnedValue::from(parent.as_str()));
+        }
+        if let Some(device_file) = self
+            .device_file
+            .as_deref()
+            .and_then(|device_file| device_file.to_str())
+        {
+            dict.insert("device-file".to_owned(), OwnedValue::from(device_file));
+        }
+        dict.insert("readable".to_owned(), OwnedValue::from(self.readable));
+        dict.insert("writable".to_owned(), OwnedValue::from(self.writable));
+        let properties = self
+            .properties
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .collect::<HashMap<_, _>>();
+        if let Ok(properties) = OwnedValue::try_from(ashpd::zvariant::Value::from(properties)) {
+            dict.insert("properties".to_owned(), properties);
+        }
+        dict
+    }
+}
+
+/// The USB devices currently plugged.
+pub fn devices() -> io::Result<Vec<UsbDevice>> {
+    let mut enumerator = udev::Enumerator::new()?;
+    enumerator.match_subsystem("usb")?;
+    enumerator.match_property("DEVTYPE", "usb_device")?;
+    Ok(enumerator
+        .scan_devices()?
+        .map(|device| UsbDevice::new(&device))
+        .collect())
+}
+
+/// A change of the plugged USB devices.
+#[derive(Debug, Clone)]
+pub enum UsbEvent {
+    /// A device was plugged.
+    Add(UsbDevice),
+    /// The properties of a device changed.
+    Change(UsbDevice),
+    /// A device was unplugged.
+    Remove(UsbDevice),
+}
+
+impl UsbEvent {
+    /// The action of the event, as sent in the `DeviceEvents` signal.
+    pub fn action(&self) -> &'static str {
+        match self {
+            Self::Add(_) => "add",
+            Self::Change(_) => "change",
+            Self::Remove(_) => "remove",
+        }
+    }
+
+    /// The device concerned by the event.
+    pub fn device(&self) -> &UsbDevice {
+        match self {
+            Self::Add(device) | Self::Change(device) | Self::Remove(device) => device,
+        }
+    }
+
+    /// The event, in the format of the `DeviceEvents` signal.
+    pub fn to_tuple(&self) -> (String, String, HashMap<String, OwnedValue>) {
+        let device = self.device();
+        (
+            self.action().to_owned(),
+            device.id().to_owned(),
+            device.to_dict(),
+        )
+    }
+}
+
+/// A stream of the [`UsbEvent`]s, reported by udev.
+///
+/// Has to be created from a tokio runtime.
+///
+/// ```rust,no_run
+/// use ashpd_backend::usb::{devices, UsbEvents};
+/// use futures_util::StreamExt;
+///
+/// async fn run() -> std::io::Result<()> {
+///     let mut events = UsbEvents::new()?;
+///     for device in devices()? {
+///         println!("{}: {:?}", device.id(), device.to_dict());
+///     }
+///     while let Some(event) = events.next().await {
+///         println!("{} {}", event.action(), event.device().id());
+///     }
+///     Ok(())
+/// }
+/// ```
+pub struct UsbEvents {
+    socket: AsyncFd<udev::MonitorSocket>,
+}
+
+impl UsbEvents {
+    /// Start listening to the events.
+    pub fn new() -> io::Result<Self> {
+        let socket = udev::MonitorBuilder::new()?
+            .match_subsystem_devtype("usb", "usb_device")?
+            .listen()?;
+        Ok(Self {
+            socket: AsyncFd::new(socket)?,
+        })
+    }
+}
+
+impl std::fmt::Debug for UsbEvents {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UsbEvents").finish_non_exhaustive()
+    }
+}
+
+impl Stream for UsbEvents {
+    type Item = UsbEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            let mut guard = match self.socket.poll_read_ready_mut(cx) {
+                Poll::Ready(Ok(guard)) => guard,
+                Poll::Ready(Err(_)) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            };
+            match guard.get_inner_mut().iter().next() {
+                Some(event) => {
+                    let device = UsbDevice::new(&event);
+                    let event = match event.event_type() {
+                        udev::EventType::Add => UsbEvent::Add(device),
+                        udev::EventType::Remove => UsbEvent::Remove(device),
+                        udev::EventType::Change => UsbEvent::Change(device),
+                        // Binding drivers doesn't change the device.
+                        _ => continue,
+                    };
+                    return Poll::Ready(Some(event));
+                }
+                None => guard.clear_ready(),
+            }
+        }
+    }
+}
+
+fn device_id(device: &udev::Device) -> String {
+    device.syspath().to_string_lossy().into_owned()
+}
+
+fn access(path: &Path, mode: libc::c_int) -> bool {
+    let Ok(path) = CString::new(path.as_os_str().as_bytes()) else {
+        return false;
+    };
+    // SAFETY: `path` is a valid nul-terminated string.
+    unsafe { libc::access(path.as_ptr(), mode) == 0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        os::unix::fs::PermissionsExt,
+        path::{Path, PathBuf},
+    };
+
+    use ashpd::zvariant::OwnedValue;
+
+    use super::{access, UsbDevice, UsbEvent};
+
+    const SYSPATH: &str = "/sys/devices/pci0000:00/0000:00:14.0/usb1/1-2";
+
+    fn device(device_file: Option<&str>) -> UsbDevice {
+        UsbDevice {
+            id: SYSPATH.to_owned(),
+            parent: Some("/sys/devices/pci0000:00/0000:00:14.0/usb1".to_owned()),
+            device_file: device_file.map(PathBuf::from),
+            readable: true,
+            writable: false,
+            properties: HashMap::from([
+                ("ID_VENDOR_ID".to_owned(), "046d".to_owned()),
+                ("ID_MODEL".to_owned(), "USB_Receiver".to_owned()),
+            ]),
+        }
+    }
+
+    #[test]
+    fn dict() {
+        let dict = device(Some("/dev/bus/usb/001/002")).to_dict();
+        assert_eq!(dict.len(), 5);
+        assert_eq!(
+            String::try_from(dict["parent"].clone()).unwrap(),
+            "/sys/devices/pci0000:00/0000:00:14.0/usb1"
+        );
+        assert_eq!(
+            String::try_from(dict["device-file"].clone()).unwrap(),
+            "/dev/bus/usb/001/002"
+        );
+        assert_eq!(dict["readable"], OwnedValue::from(true));
+        assert_eq!(dict["writable"], OwnedValue::from(false));
+        let properties = HashMap::<String, String>::try_from(dict["properties"].clone()).unwrap();
+        assert_eq!(properties["ID_VENDOR_ID"], "046d");
+        assert_eq!(properties["ID_MODEL"], "USB_Receiver");
+
+        // The devices without a node, the root hubs of some drivers for
+        // example, don't have a device file.
+        assert!(!device(None).to_dict().contains_key("device-file"));
+    }
+
+    #[test]
+    fn events() {
+        let event = UsbEvent::Remove(device(None));
+        assert_eq!(event.action(), "remove");
+        let (action, id, dict) = event.to_tuple();
+        assert_eq!(action, "remove");
+        assert_eq!(id, SYSPATH);
+        assert_eq!(dict, device(None).to_dict());
+
+        assert_eq!(UsbEvent::Add(device(None)).action(), "add");
+        assert_eq!(UsbEvent::Change(device(None)).action(), "change");
+    }
+
+    #[test]
+    fn permissions() {
+        let path = std::env::temp_dir().join(format!("ashpd-usb-{}", std::process::id()));
+        std::fs::write(&path, b"").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o400)).unwrap();
+        assert!(access(&path, libc::R_OK));
+        // SAFETY: `geteuid` has no preconditions.
+        let root = unsafe { libc::geteuid() } == 0;
+        // Root can write to any file.
+        if !root {
+            assert!(!access(&path, libc::W_OK));
+        }
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(!access(&path, libc::R_OK));
+        assert!(!access(Path::new("/dev/bus/usb\0/001"), libc::R_OK));
+    }
+}
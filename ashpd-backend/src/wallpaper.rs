@@ -0,0 +1,143 @@
+use std::{future::Future, sync::Arc};
+
+use ashpd::{
+    desktop::{wallpaper::SetOn, HandleToken, ResponseType},
+    zvariant::{DeserializeDict, OwnedObjectPath, Type},
+    AppID, WindowIdentifierType,
+};
+use futures_util::future::BoxFuture;
+
+use crate::{
+    backend::PortalInterface,
+    request::{DynRequestImpl, Request, RequestImpl},
+    MaybeAppID, MaybeWindowIdentifier, RawOptions, Result,
+};
+
+#[cfg(feature = "gdk-pixbuf")]
+mod preview;
+#[cfg(feature = "gdk-pixbuf")]
+pub use preview::{decode_preview, load_preview, PreviewImage};
+
+#[derive(DeserializeDict, Type, Debug)]
+#[zvariant(signature = "dict")]
+pub struct WallpaperOptions {
+    #[zvariant(rename = "show-preview")]
+    show_preview: Option<bool>,
+    #[zvariant(rename = "set-on")]
+    set_on: Option<SetOn>,
+}
+
+impl WallpaperOptions {
+    pub fn show_preview(&self) -> Option<bool> {
+        self.show_preview
+    }
+
+    pub fn set_on(&self) -> Option<SetOn> {
+        self.set_on
+    }
+}
+
+pub trait WallpaperImpl: RequestImpl {
+    fn with_uri(
+        &self,
+        token: HandleToken,
+        app_id: Option<AppID>,
+        window_identifier: Option<WindowIdentifierType>,
+        uri: url::Url,
+        options: RawOptions<WallpaperOptions>,
+    ) -> impl Future<Output = Result<()>> + Send;
+}
+
+/// Object safe counterpart of [`WallpaperImpl`], used to store the implementation.
+pub(crate) trait DynWallpaperImpl: DynRequestImpl {
+    fn with_uri<'a>(
+        &'a self,
+        token: HandleToken,
+        app_id: Option<AppID>,
+        window_identifier: Option<WindowIdentifierType>,
+        uri: url::Url,
+        options: RawOptions<WallpaperOptions>,
+    ) -> BoxFuture<'a, Result<()>>;
+}
+
+impl<T: WallpaperImpl> DynWallpaperImpl for T {
+    fn with_uri<'a>(
+        &'a self,
+        token: HandleToken,
+        app_id: Option<AppID>,
+        window_identifier: Option<WindowIdentifierType>,
+        uri: url::Url,
+        options: RawOptions<WallpaperOptions>,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(WallpaperImpl::with_uri(
+            self,
+            token,
+            app_id,
+            window_identifier,
+            uri,
+            options,
+        ))
+    }
+}
+
+pub(crate) struct WallpaperInterface {
+    imp: Arc<dyn DynWallpaperImpl>,
+    cnx: zbus::Connection,
+    version: u32,
+}
+
+impl WallpaperInterface {
+    pub fn new(imp: Arc<dyn DynWallpaperImpl>, cnx: zbus::Connection) -> Self {
+        Self {
+            imp,
+            cnx,
+            version: 1,
+        }
+    }
+}
+
+impl PortalInterface for WallpaperInterface {
+    fn set_version(&mut self, version: u32) {
+        self.version = version;
+    }
+}
+
+#[zbus::interface(name = "org.freedesktop.impl.portal.Wallpaper")]
+impl WallpaperInterface {
+    #[zbus(property, name = "version")]
+    fn version(&self) -> u32 {
+        self.version
+    }
+
+    #[zbus(name = "SetWallpaperURI")]
+    #[zbus(out_args("response"))]
+    async fn set_wallpaper_uri(
+        &self,
+        handle: OwnedObjectPath,
+        app_id: MaybeAppID,
+        window_identifier: MaybeWindowIdentifier,
+        uri: url::Url,
+        options: RawOptions<WallpaperOptions>,
+    ) -> Result<ResponseType> {
+        let imp = Arc::clone(&self.imp);
+
+        Request::spawn(
+            "Wallpaper::SetWallpaperURI",
+            &self.cnx,
+            handle.clone(),
+            Arc::clone(&self.imp),
+            async move {
+                imp.with_uri(
+                    HandleToken::try_from(&handle).unwrap(),
+                    app_id.inner(),
+                    window_identifier.inner(),
+                    uri,
+                    options,
+                )
+                .await
+            },
+        )
+        .await
+        .map(|r| r.response_type())
+    }
+}
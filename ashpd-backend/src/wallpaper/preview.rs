@@ -0,0 +1,278 @@
+use std::{cell::Cell, io::Read, path::Path, rc::Rc};
+
+use ashpd::PortalError;
+use gdk_pixbuf::{prelude::*, PixbufLoader};
+
+use crate::Result;
+
+/// The largest image file that is read, in bytes.
+const MAX_FILE_SIZE: u64 = 64 * 1024 * 1024;
+/// The largest image that is decoded, in pixels.
+const MAX_PIXELS: i64 = 64 * 1024 * 1024;
+/// The amount of data written to the decoder at once.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// The image of a wallpaper, decoded with [`load_preview`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreviewImage {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl PreviewImage {
+    /// The width of the image, in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// The height of the image, in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The RGBA pixels, row by row without any padding.
+    ///
+    /// They can be turned into a texture with `gdk::MemoryTexture` and the
+    /// `R8g8b8a8` format.
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// The RGBA pixels, row by row without any padding.
+    pub fn into_pixels(self) -> Vec<u8> {
+        self.pixels
+    }
+}
+
+/// The image formats that are decoded, with the name of their gdk-pixbuf
+/// loader.
+fn sniff_format(data: &[u8]) -> Option<&'static str> {
+    if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("png")
+    } else if data.starts_with(b"\xff\xd8\xff") {
+        Some("jpeg")
+    } else if data.len() >= 12 && data.starts_with(b"RIFF") && &data[8..12] == b"WEBP" {
+        Some("webp")
+    } else {
+        None
+    }
+}
+
+/// Loads the image of a `SetWallpaperURI` request, scaled down to fit in a
+/// `max_size` square, to show it in a preview dialog.
+///
+/// The image comes from an untrusted application, so only local PNG, JPEG
+/// and WebP files of a reasonable size are decoded. The format is guessed
+/// from the content of the file, not from its name, and other formats, like
+/// SVG, are rejected.
+///
+/// ```rust,no_run
+/// use ashpd_backend::wallpaper::load_preview;
+///
+/// async fn preview(uri: url::Url) -> ashpd_backend::Result<()> {
+///     let image = load_preview(&uri, 512).await?;
+///     println!("{}x{}", image.width(), image.height());
+///     Ok(())
+/// }
+/// ```
+pub async fn load_preview(uri: &url::Url, max_size: u32) -> Result<PreviewImage> {
+    let path = uri
+        .to_file_path()
+        .map_err(|_| PortalError::InvalidArgument(format!("Unsupported wallpaper URI `{uri}`")))?;
+    tokio::task::spawn_blocking(move || {
+        let data = read_file(&path).map_err(|err| {
+            PortalError::Failed(format!("Failed to read `{}`: {err}", path.display()))
+        })?;
+        decode_preview(&data, max_size)
+    })
+    .await
+    .map_err(|err| PortalError::Failed(format!("Failed to load the wallpaper: {err}")))?
+}
+
+fn read_file(path: &Path) -> std::io::Result<Vec<u8>> {
+    let file = std::fs::File::open(path)?;
+    let mut data = Vec::new();
+    file.take(MAX_FILE_SIZE + 1).read_to_end(&mut data)?;
+    if data.len() as u64 > MAX_FILE_SIZE {
+        return Err(std::io::Error::other("The file is too large"));
+    }
+    Ok(data)
+}
+
+/// Decodes an encoded image, scaled down to fit in a `max_size` square, see
+/// [`load_preview`].
+///
+/// It blocks until the image is decoded.
+pub fn decode_preview(data: &[u8], max_size: u32) -> Result<PreviewImage> {
+    let format = sniff_format(data).ok_or_else(|| {
+        PortalError::InvalidArgument("Unsupported wallpaper image format".to_owned())
+    })?;
+    let failed = |err: gdk_pixbuf::glib::Error| {
+        PortalError::Failed(format!("Failed to decode the wallpaper: {err}"))
+    };
+
+    let loader = PixbufLoader::with_type(format).map_err(failed)?;
+    let too_large = Rc::new(Cell::new(false));
+    loader.connect_size_prepared({
+        let too_large = Rc::clone(&too_large);
+        let max_size = max_size.clamp(1, i32::MAX as u32) as i32;
+        move |loader, width, height| {
+            if width <= 0 || height <= 0 || width as i64 * height as i64 > MAX_PIXELS {
+                too_large.set(true);
+                // An empty size stops the loaders before they allocate the
+                // pixels.
+                loader.set_size(0, 0);
+                return;
+            }
+            let scale = f64::min(1.0, max_size as f64 / width.max(height) as f64);
+            loader.set_size(
+                ((width as f64 * scale) as i32).max(1),
+                ((height as f64 * scale) as i32).max(1),
+            );
+        }
+    });
+    for chunk in data.chunks(CHUNK_SIZE) {
+        let written = loader.write(chunk);
+        if too_large.get() {
+            let _ = loader.close();
+            return Err(PortalError::InvalidArgument(
+                "The wallpaper image is too large".to_owned(),
+            ));
+        }
+        written.map_err(failed)?;
+    }
+    loader.close().map_err(failed)?;
+
+    let pixbuf = loader
+        .pixbuf()
+        .ok_or_else(|| PortalError::Failed("Failed to decode the wallpaper".to_owned()))?;
+    let pixbuf = pixbuf.apply_embedded_orientation().unwrap_or(pixbuf);
+    let pixbuf = pixbuf
+        .add_alpha(false, 0, 0, 0)
+        .map_err(|err| PortalError::Failed(format!("Failed to decode the wallpaper: {err}")))?;
+
+    let width = pixbuf.width() as usize;
+    let height = pixbuf.height() as usize;
+    let rowstride = pixbuf.rowstride() as usize;
+    let bytes = pixbuf.read_pixel_bytes();
+    let mut pixels = Vec::with_capacity(width * height * 4);
+    for row in 0..height {
+        let start = row * rowstride;
+        pixels.extend_from_slice(&bytes[start..start + width * 4]);
+    }
+    Ok(PreviewImage {
+        width: width as u32,
+        height: height as u32,
+        pixels,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use gdk_pixbuf::{Colorspace, Pixbuf};
+
+    use super::{decode_preview, read_file, sniff_format, MAX_FILE_SIZE};
+
+    /// The CRC of the PNG chunks.
+    fn crc32(data: &[u8]) -> u32 {
+        let mut crc = !0u32;
+        for byte in data {
+            crc ^= u32::from(*byte);
+            for _ in 0..8 {
+                crc = if crc & 1 == 1 {
+                    (crc >> 1) ^ 0xedb8_8320
+                } else {
+                    crc >> 1
+                };
+            }
+        }
+        !crc
+    }
+
+    fn push_chunk(png: &mut Vec<u8>, name: &[u8; 4], data: &[u8]) {
+        let mut chunk = name.to_vec();
+        chunk.extend_from_slice(data);
+        png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        png.extend_from_slice(&chunk);
+        png.extend_from_slice(&crc32(&chunk).to_be_bytes());
+    }
+
+    /// The header of a PNG image of `width`x`height` pixels, followed by an
+    /// empty data chunk for the loader to report the size of the image.
+    fn png_header(width: u32, height: u32) -> Vec<u8> {
+        let mut header = Vec::new();
+        header.extend_from_slice(&width.to_be_bytes());
+        header.extend_from_slice(&height.to_be_bytes());
+        // 8 bits RGBA, no interlacing.
+        header.extend_from_slice(&[8, 6, 0, 0, 0]);
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        push_chunk(&mut png, b"IHDR", &header);
+        push_chunk(&mut png, b"IDAT", &[]);
+        png
+    }
+
+    #[test]
+    fn formats() {
+        assert_eq!(sniff_format(&png_header(1, 1)), Some("png"));
+        assert_eq!(sniff_format(b"\xff\xd8\xff\xe0\0\x10JFIF"), Some("jpeg"));
+        assert_eq!(sniff_format(b"RIFF\0\0\0\0WEBPVP8 "), Some("webp"));
+        assert_eq!(sniff_format(b"RIFF\0\0\0\0WAVEfmt "), None);
+        assert_eq!(sniff_format(b"RIFF"), None);
+        assert_eq!(sniff_format(b""), None);
+        assert_eq!(
+            sniff_format(br#"<svg xmlns="http://www.w3.org/2000/svg"/>"#),
+            None
+        );
+        assert_eq!(sniff_format(b"GIF89a"), None);
+    }
+
+    #[test]
+    fn decode() {
+        let pixbuf = Pixbuf::new(Colorspace::Rgb, true, 8, 4, 2).unwrap();
+        pixbuf.fill(0xff00_00ff);
+        let png = pixbuf.save_to_bufferv("png", &[]).unwrap();
+
+        let image = decode_preview(&png, 512).unwrap();
+        assert_eq!((image.width(), image.height()), (4, 2));
+        assert_eq!(image.pixels(), [255, 0, 0, 255].repeat(8));
+
+        // The images are scaled down to fit in the preview.
+        let image = decode_preview(&png, 2).unwrap();
+        assert_eq!((image.width(), image.height()), (2, 1));
+        assert_eq!(image.pixels().len(), 2 * 4);
+    }
+
+    #[test]
+    fn rejected_images() {
+        // The SVG images could reference other files.
+        let svg = br#"<svg xmlns="http://www.w3.org/2000/svg" width="1" height="1"/>"#;
+        assert!(matches!(
+            decode_preview(svg, 512),
+            Err(ashpd::PortalError::InvalidArgument(_))
+        ));
+
+        // The decoding stops before allocating the pixels of the huge images.
+        let err = decode_preview(&png_header(16384, 16384), 512).unwrap_err();
+        assert!(
+            matches!(&err, ashpd::PortalError::InvalidArgument(message) if message.contains("too large")),
+            "{err}"
+        );
+
+        assert!(decode_preview(&png_header(4, 2), 512).is_err());
+    }
+
+    #[test]
+    fn file_size() {
+        let path = std::env::temp_dir().join(format!("ashpd-wallpaper-{}", std::process::id()));
+        let file = std::fs::File::create(&path).unwrap();
+        file.set_len(16).unwrap();
+        assert_eq!(read_file(&path).unwrap().len(), 16);
+
+        // The file is sparse, its content is never read past the limit.
+        file.set_len(MAX_FILE_SIZE + 1).unwrap();
+        let result = read_file(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert!(result.is_err());
+    }
+}
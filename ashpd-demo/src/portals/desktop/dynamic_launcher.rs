@@ -395,7 +395,7 @@ impl DynamicLauncherPage {
             .load_contents_future()
             .await
             .unwrap();
-        let icon = Icon::Bytes(data.to_vec());
+        let icon = Icon::with_bytes(data.to_vec());
 
         let response = proxy
             .prepare_install(identifier.as_ref(), &launcher_name, icon, options)
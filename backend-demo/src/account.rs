@@ -1,18 +1,16 @@
 use ashpd::{
-    backend::{
-        account::{AccountImpl, UserInformationOptions},
-        request::RequestImpl,
-        Result,
-    },
     desktop::{account::UserInformation, HandleToken},
     AppID, WindowIdentifierType,
 };
-use async_trait::async_trait;
+use ashpd_backend::{
+    account::{AccountImpl, UserInformationOptions},
+    request::RequestImpl,
+    RawOptions, Result,
+};
 
 #[derive(Default)]
 pub struct Account;
 
-#[async_trait]
 impl RequestImpl for Account {
     async fn close(&self, token: HandleToken) {
         tracing::debug!("IN Close(): {token}");
@@ -35,14 +33,13 @@ mod fdo_account {
     }
 }
 
-#[async_trait]
 impl AccountImpl for Account {
     async fn get_user_information(
         &self,
         _token: HandleToken,
         _app_id: Option<AppID>,
         _window_identifier: Option<WindowIdentifierType>,
-        _options: UserInformationOptions,
+        _options: RawOptions<UserInformationOptions>,
     ) -> Result<UserInformation> {
         // Retrieve current user information by using the
         // `org.freedesktop.Accounts` interfaces.
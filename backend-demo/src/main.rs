@@ -15,7 +15,7 @@ use wallpaper::Wallpaper;
 // const NAME: &str = "org.freedesktop.impl.portal.desktop.ashpd-backend-demo";
 const NAME: &str = "org.freedesktop.impl.portal.desktop.gnome";
 // Run with
-// RUST_LOG=ashpd_backend_demo=debug,ashpd=debug cargo run --manifest-path
+// RUST_LOG=ashpd_backend_demo=debug,ashpd=debug,ashpd_backend=debug cargo run --manifest-path
 // ./backend-demo/Cargo.toml
 
 #[tokio::main]
@@ -23,7 +23,7 @@ async fn main() -> ashpd::Result<()> {
     // Enable debug with `RUST_LOG=ashpd_backend_demo=debug COMMAND`.
     tracing_subscriber::fmt::init();
 
-    ashpd::backend::Builder::new(NAME)?
+    ashpd_backend::Builder::new(NAME)?
         .account(Account)
         .screenshot(Screenshot)
         .secret(Secret)
@@ -1,32 +1,29 @@
 use ashpd::{
-    backend::{
-        request::RequestImpl,
-        screenshot::{ColorOptions, ScreenshotImpl, ScreenshotOptions},
-        Result,
-    },
     desktop::{screenshot::Screenshot as ScreenshotResponse, Color, HandleToken},
     AppID, WindowIdentifierType,
 };
-use async_trait::async_trait;
+use ashpd_backend::{
+    request::RequestImpl,
+    screenshot::{ColorOptions, ScreenshotImpl, ScreenshotOptions},
+    RawOptions, Result,
+};
 
 #[derive(Default)]
 pub struct Screenshot;
 
-#[async_trait]
 impl RequestImpl for Screenshot {
     async fn close(&self, token: HandleToken) {
         tracing::debug!("IN Close(): {token}");
     }
 }
 
-#[async_trait]
 impl ScreenshotImpl for Screenshot {
     async fn screenshot(
         &self,
         _token: HandleToken,
         _app_id: Option<AppID>,
         _window_identifier: Option<WindowIdentifierType>,
-        _options: ScreenshotOptions,
+        _options: RawOptions<ScreenshotOptions>,
     ) -> Result<ScreenshotResponse> {
         Ok(ScreenshotResponse::new(
             url::Url::parse("file:///some/sreenshot").unwrap(),
@@ -38,7 +35,7 @@ impl ScreenshotImpl for Screenshot {
         _token: HandleToken,
         _app_id: Option<AppID>,
         _window_identifier: Option<WindowIdentifierType>,
-        _options: ColorOptions,
+        _options: RawOptions<ColorOptions>,
     ) -> Result<Color> {
         Ok(Color::new(1.0, 1.0, 1.0))
     }
@@ -1,24 +1,17 @@
 use std::collections::HashMap;
 
-use ashpd::{
-    backend::{request::RequestImpl, secret::SecretImpl, Result},
-    desktop::HandleToken,
-    zbus::zvariant::OwnedValue,
-    AppID,
-};
-use async_trait::async_trait;
+use ashpd::{desktop::HandleToken, zbus::zvariant::OwnedValue, AppID};
+use ashpd_backend::{request::RequestImpl, secret::SecretImpl, Result};
 
 #[derive(Default)]
 pub struct Secret;
 
-#[async_trait]
 impl RequestImpl for Secret {
     async fn close(&self, token: HandleToken) {
         tracing::debug!("IN Close(): {token}");
     }
 }
 
-#[async_trait]
 impl SecretImpl for Secret {
     async fn retrieve(
         &self,
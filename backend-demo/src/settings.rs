@@ -1,10 +1,6 @@
 use std::{collections::HashMap, sync::Arc};
 
 use ashpd::{
-    backend::{
-        request::RequestImpl,
-        settings::{SettingsImpl, SettingsSignalEmitter},
-    },
     desktop::{
         settings::{ColorScheme, Namespace, APPEARANCE_NAMESPACE, COLOR_SCHEME_KEY},
         HandleToken,
@@ -12,7 +8,10 @@ use ashpd::{
     zbus::zvariant::OwnedValue,
     PortalError,
 };
-use async_trait::async_trait;
+use ashpd_backend::{
+    request::RequestImpl,
+    settings::{SettingsImpl, SettingsSignalEmitter},
+};
 
 #[derive(Default, Clone)]
 pub struct Settings {
@@ -21,14 +20,12 @@ pub struct Settings {
     signal_emitter: Option<Arc<dyn SettingsSignalEmitter>>,
 }
 
-#[async_trait]
 impl RequestImpl for Settings {
     async fn close(&self, token: HandleToken) {
         tracing::debug!("IN Close(): {token}");
     }
 }
 
-#[async_trait]
 impl SettingsImpl for Settings {
     async fn read_all(
         &self,
@@ -1,25 +1,19 @@
-use ashpd::{
-    backend::{
-        request::RequestImpl,
-        wallpaper::{WallpaperImpl, WallpaperOptions},
-        Result,
-    },
-    desktop::HandleToken,
-    AppID, WindowIdentifierType,
+use ashpd::{desktop::HandleToken, AppID, WindowIdentifierType};
+use ashpd_backend::{
+    request::RequestImpl,
+    wallpaper::{WallpaperImpl, WallpaperOptions},
+    RawOptions, Result,
 };
-use async_trait::async_trait;
 
 #[derive(Default)]
 pub struct Wallpaper;
 
-#[async_trait]
 impl RequestImpl for Wallpaper {
     async fn close(&self, token: HandleToken) {
         tracing::debug!("IN Close(): {token}");
     }
 }
 
-#[async_trait]
 impl WallpaperImpl for Wallpaper {
     async fn with_uri(
         &self,
@@ -27,7 +21,7 @@ impl WallpaperImpl for Wallpaper {
         _app_id: Option<AppID>,
         _window_identifier: Option<WindowIdentifierType>,
         _uri: url::Url,
-        _options: WallpaperOptions,
+        _options: RawOptions<WallpaperOptions>,
     ) -> Result<()> {
         Ok(())
     }
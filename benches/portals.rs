@@ -0,0 +1,97 @@
+use ashpd::{
+    desktop::{background::BackgroundProxy, remote_desktop::RemoteDesktop, settings::Settings},
+    test::{MockPortal, MockResponse},
+    zvariant::Value,
+    WindowIdentifier,
+};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+fn notify_pointer_motion(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let (_mock, remote, session) = runtime.block_on(async {
+        let mock = MockPortal::new().await.unwrap();
+        mock.respond(
+            "org.freedesktop.portal.RemoteDesktop",
+            "CreateSession",
+            MockResponse::success([]),
+        );
+        mock.respond(
+            "org.freedesktop.portal.RemoteDesktop",
+            "NotifyPointerMotion",
+            MockResponse::empty(),
+        );
+        let remote = RemoteDesktop::new().await.unwrap();
+        let session = remote.create_session().await.unwrap();
+        (mock, remote, session)
+    });
+    c.bench_function("notify_pointer_motion", |b| {
+        b.to_async(&runtime).iter(|| async {
+            remote
+                .notify_pointer_motion(&session, black_box(4.0), black_box(2.0))
+                .await
+                .unwrap()
+        })
+    });
+}
+
+fn background_set_status(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let (_mock, background) = runtime.block_on(async {
+        let mock = MockPortal::new().await.unwrap();
+        mock.set_version("org.freedesktop.portal.Background", 2);
+        mock.respond(
+            "org.freedesktop.portal.Background",
+            "SetStatus",
+            MockResponse::empty(),
+        );
+        let background = BackgroundProxy::new().await.unwrap();
+        (mock, background)
+    });
+    c.bench_function("background set_status", |b| {
+        b.to_async(&runtime).iter(|| async {
+            background
+                .set_status(black_box("Syncing your files"))
+                .await
+                .unwrap()
+        })
+    });
+}
+
+fn window_identifier(c: &mut Criterion) {
+    let identifier = WindowIdentifier::from_xid(0x4a0001);
+    c.bench_function("window identifier to_string", |b| {
+        b.iter(|| black_box(&identifier).to_string())
+    });
+}
+
+fn settings_read(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let (_mock, settings) = runtime.block_on(async {
+        let mock = MockPortal::new().await.unwrap();
+        mock.set_version("org.freedesktop.portal.Settings", 2);
+        mock.respond(
+            "org.freedesktop.portal.Settings",
+            "Read",
+            MockResponse::reply((Value::new(Value::from(1u32)),)),
+        );
+        let settings = Settings::new().await.unwrap();
+        (mock, settings)
+    });
+    c.bench_function("settings read", |b| {
+        b.to_async(&runtime).iter(|| async {
+            settings
+                .read::<u32>("org.freedesktop.appearance", "color-scheme")
+                .await
+                .unwrap()
+        })
+    });
+}
+
+criterion_group!(
+    benches,
+    notify_pointer_motion,
+    background_set_status,
+    window_identifier,
+    settings_read
+);
+criterion_main!(benches);
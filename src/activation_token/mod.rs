@@ -1,4 +1,7 @@
-use std::ops::Deref;
+use std::{
+    ops::Deref,
+    sync::atomic::{AtomicU32, Ordering},
+};
 
 use serde::{Deserialize, Serialize};
 use zbus::zvariant::Type;
@@ -15,9 +18,105 @@ mod wayland;
 #[derive(Debug, Deserialize, Serialize, Type, PartialEq, Eq, Hash, Clone)]
 pub struct ActivationToken(String);
 
+impl ActivationToken {
+    /// Create an instance of [`ActivationToken`] from a token obtained
+    /// elsewhere, with `smithay-client-toolkit` or GTK directly for example.
+    ///
+    /// ```rust
+    /// use ashpd::ActivationToken;
+    ///
+    /// let token = ActivationToken::from_raw(String::from("wayland-token"));
+    /// assert_eq!(token.as_str(), "wayland-token");
+    /// ```
+    pub fn from_raw(token: String) -> Self {
+        Self(token)
+    }
+
+    /// Create an instance of [`ActivationToken`] for X11, as a
+    /// startup-notification ID like the ones passed in `DESKTOP_STARTUP_ID`.
+    ///
+    /// `timestamp` is the X server time of the event that triggered the
+    /// activation, the window manager uses it to prevent focus stealing.
+    ///
+    /// ```rust
+    /// use ashpd::ActivationToken;
+    ///
+    /// let token = ActivationToken::from_x11_timestamp(4242);
+    /// assert!(token.ends_with("_TIME4242"));
+    /// ```
+    pub fn from_x11_timestamp(timestamp: u32) -> Self {
+        static SEQUENCE: AtomicU32 = AtomicU32::new(0);
+
+        let program = std::env::current_exe()
+            .ok()
+            .and_then(|path| path.file_name()?.to_str().map(ToOwned::to_owned))
+            .unwrap_or_else(|| String::from("ashpd"));
+        Self(format!(
+            "{program}-{}-{}-{}_TIME{timestamp}",
+            std::process::id(),
+            hostname().as_deref().unwrap_or("localhost"),
+            SEQUENCE.fetch_add(1, Ordering::Relaxed),
+        ))
+    }
+
+    #[cfg(feature = "raw_handle")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "raw_handle")))]
+    /// Create an instance of [`ActivationToken`] from a
+    /// [`RawWindowHandle`](raw_window_handle::RawWindowHandle), to pass it to
+    /// the portals launching other applications like
+    /// [`dynamic_launcher`](crate::desktop::dynamic_launcher) or
+    /// [`open_uri`](crate::desktop::open_uri).
+    ///
+    /// Only Wayland handles are supported, with a
+    /// [`RawDisplayHandle`](raw_window_handle::RawDisplayHandle) and the
+    /// `wayland` feature enabled. The token is requested with the
+    /// xdg-activation protocol. Use [`ActivationToken::from_x11_timestamp`]
+    /// on X11.
+    pub async fn from_raw_handle(
+        app_id: Option<crate::AppID>,
+        window_handle: &raw_window_handle::RawWindowHandle,
+        display_handle: Option<&raw_window_handle::RawDisplayHandle>,
+    ) -> Option<Self> {
+        #[cfg(feature = "wayland")]
+        use raw_window_handle::{
+            RawDisplayHandle::Wayland as DisplayHandle, RawWindowHandle::Wayland,
+        };
+        match (window_handle, display_handle) {
+            #[cfg(feature = "wayland")]
+            (Wayland(wl_handle), Some(DisplayHandle(wl_display))) => unsafe {
+                Self::from_wayland_raw(
+                    app_id,
+                    wl_handle.surface.as_ptr(),
+                    wl_display.display.as_ptr(),
+                )
+                .await
+            },
+            _ => {
+                let _ = app_id;
+                None
+            }
+        }
+    }
+
+    /// The token as sent to the portals.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+fn hostname() -> Option<String> {
+    let mut buffer = [0u8; 256];
+    // SAFETY: the length passed is the one of the buffer.
+    if unsafe { libc::gethostname(buffer.as_mut_ptr().cast(), buffer.len()) } != 0 {
+        return None;
+    }
+    let hostname = std::ffi::CStr::from_bytes_until_nul(&buffer).ok()?;
+    hostname.to_str().ok().map(ToOwned::to_owned)
+}
+
 impl From<String> for ActivationToken {
     fn from(value: String) -> Self {
-        Self(value)
+        Self::from_raw(value)
     }
 }
 
@@ -37,13 +136,13 @@ impl Deref for ActivationToken {
     type Target = str;
 
     fn deref(&self) -> &Self::Target {
-        self.0.as_str()
+        self.as_str()
     }
 }
 
 impl AsRef<str> for ActivationToken {
     fn as_ref(&self) -> &str {
-        self.0.as_str()
+        self.as_str()
     }
 }
 
@@ -52,3 +151,18 @@ impl std::fmt::Display for ActivationToken {
         f.write_str(self.as_ref())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::ActivationToken;
+
+    #[test]
+    fn x11_startup_id() {
+        let first = ActivationToken::from_x11_timestamp(1234);
+        let second = ActivationToken::from_x11_timestamp(1234);
+        assert_ne!(first, second);
+        let (id, timestamp) = first.rsplit_once("_TIME").unwrap();
+        assert_eq!(timestamp, "1234");
+        assert!(id.contains(&format!("-{}-", std::process::id())));
+    }
+}
@@ -161,7 +161,7 @@ fn wayland_export_token(
     event_queue.roundtrip(&mut state)?;
 
     if let Some(wl_token) = state.wl_token.take() {
-        if let Some(app_id) = app_id {
+        if let Some(app_id) = app_id.as_ref().or(crate::app_id()) {
             wl_token.set_app_id(app_id.to_string());
         }
         wl_token.set_surface(surface);
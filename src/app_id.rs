@@ -9,7 +9,159 @@ use zbus::zvariant::Type;
 #[derive(Debug, Serialize, Type, PartialEq, Eq, Hash, Clone)]
 pub struct AppID(String);
 
+/// How the IDs are validated when converted to an [`AppID`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AppIDValidation {
+    /// The ID has to be a valid application ID as is.
+    #[default]
+    Strict,
+    /// The IDs derived from domain names are fixed up first, as recommended
+    /// for the application IDs: the `-` of all the segments but the last one
+    /// are replaced with `_`, and the segments starting with a digit are
+    /// prefixed with `_`.
+    Lenient,
+}
+
+impl AppIDValidation {
+    fn parse(self, value: &str) -> Result<AppID, crate::Error> {
+        match self {
+            Self::Strict => value.parse(),
+            Self::Lenient => {
+                let segments = value.split('.').collect::<Vec<_>>();
+                let last = segments.len() - 1;
+                segments
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, segment)| {
+                        let segment = if idx < last {
+                            segment.replace('-', "_")
+                        } else {
+                            (*segment).to_owned()
+                        };
+                        if segment.starts_with(|c: char| c.is_ascii_digit()) {
+                            format!("_{segment}")
+                        } else {
+                            segment
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join(".")
+                    .parse()
+            }
+        }
+    }
+}
+
 impl AppID {
+    /// Detects the ID of the running application, so it doesn't have to be
+    /// hardcoded.
+    ///
+    /// The ID is, in order of preference:
+    /// - the one set with [`set_app_id`](crate::set_app_id).
+    /// - the `name` of the `/.flatpak-info` file of a Flatpak sandbox.
+    /// - the `FLATPAK_ID` environment variable.
+    /// - `snap.` followed by the instance name of a snap, as used by the
+    ///   portals.
+    /// - the name of the desktop file the application was launched from, as
+    ///   set by GLib in `GIO_LAUNCHED_DESKTOP_FILE`.
+    ///
+    /// ```rust,no_run
+    /// use ashpd::{register_host_app, AppID};
+    ///
+    /// async fn run() -> ashpd::Result<()> {
+    ///     if let Some(app_id) = AppID::detect().await {
+    ///         register_host_app(app_id).await?;
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn detect() -> Option<Self> {
+        if let Some(app_id) = crate::app_id() {
+            return Some(app_id.clone());
+        }
+        if let Some(app_id) = crate::flatpak::InstanceInfo::current()
+            .await
+            .ok()
+            .and_then(|info| info.app_id())
+        {
+            return Some(app_id);
+        }
+        Self::from_env(crate::helpers::env_vars().collect())
+    }
+
+    /// Parses `value` with the given `validation`.
+    ///
+    /// ```rust
+    /// use ashpd::{AppID, AppIDValidation};
+    ///
+    /// assert!(AppID::parse_with("com.my-company.App", AppIDValidation::Strict).is_err());
+    /// let app_id = AppID::parse_with("com.my-company.App", AppIDValidation::Lenient).unwrap();
+    /// assert_eq!(app_id.as_ref(), "com.my_company.App");
+    /// ```
+    pub fn parse_with(value: &str, validation: AppIDValidation) -> Result<Self, crate::Error> {
+        validation.parse(value)
+    }
+
+    /// Converts a D-Bus well-known name, the one an application owns on the
+    /// session bus for example, to an [`AppID`].
+    ///
+    /// Fails with [`Error::InvalidAppID`](crate::Error::InvalidAppID) for
+    /// the unique names and the names that are not valid IDs with the given
+    /// `validation`.
+    pub fn from_bus_name(name: &str, validation: AppIDValidation) -> Result<Self, crate::Error> {
+        let name =
+            zbus::names::WellKnownName::try_from(name).map_err(|_| crate::Error::InvalidAppID)?;
+        validation.parse(name.as_str())
+    }
+
+    /// The ID as a D-Bus well-known name.
+    ///
+    /// The application IDs are always valid well-known names.
+    pub fn bus_name(&self) -> zbus::names::WellKnownName<'_> {
+        zbus::names::WellKnownName::from_str_unchecked(&self.0)
+    }
+
+    /// Converts a desktop file ID, like `org.example.App.desktop`, to an
+    /// [`AppID`].
+    ///
+    /// The `.desktop` suffix is optional. The subdirectory prefix of the
+    /// desktop files installed in a subdirectory of `applications`, joined
+    /// with a `-`, can't be told apart from the ID and is kept as is.
+    pub fn from_desktop_file_id(
+        desktop_file_id: &str,
+        validation: AppIDValidation,
+    ) -> Result<Self, crate::Error> {
+        let app_id = desktop_file_id
+            .strip_suffix(".desktop")
+            .unwrap_or(desktop_file_id);
+        validation.parse(app_id)
+    }
+
+    /// The ID of the desktop file of the application, the ID followed by the
+    /// `.desktop` suffix.
+    pub fn desktop_file_id(&self) -> String {
+        format!("{}.desktop", self.0)
+    }
+
+    fn from_env(env: Vec<(String, String)>) -> Option<Self> {
+        let var = |name: &str| {
+            env.iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.as_str())
+        };
+        if let Some(app_id) = var("FLATPAK_ID").and_then(|id| id.parse().ok()) {
+            return Some(app_id);
+        }
+        if let Some(snap) = crate::SnapInfo::from_env(env.iter().cloned()) {
+            if let Ok(app_id) = format!("snap.{}", snap.instance_name()).parse() {
+                return Some(app_id);
+            }
+        }
+        let desktop_file = std::path::Path::new(var("GIO_LAUNCHED_DESKTOP_FILE")?);
+        let desktop_file_id = desktop_file.file_name()?.to_str()?;
+        Self::from_desktop_file_id(desktop_file_id, AppIDValidation::Strict).ok()
+    }
+
     #[cfg(all(
         feature = "backend",
         any(feature = "gtk4_x11", feature = "gtk4_wayland")
@@ -23,8 +175,7 @@ impl AppID {
     )]
     /// Retrieves the associated `gio::DesktopAppInfo` if found
     pub fn app_info(&self) -> Option<gtk4::gio::DesktopAppInfo> {
-        let desktop_file = format!("{}.desktop", self.0);
-        gtk4::gio::DesktopAppInfo::new(&desktop_file)
+        gtk4::gio::DesktopAppInfo::new(&self.desktop_file_id())
     }
 }
 
@@ -185,6 +336,89 @@ fn is_valid_app_id_char(c: char) -> bool {
 mod tests {
     use super::*;
 
+    #[test]
+    fn formats() {
+        let app_id = AppID::from_str("org.example.App").unwrap();
+        assert_eq!(app_id.bus_name().as_str(), "org.example.App");
+        assert_eq!(app_id.desktop_file_id(), "org.example.App.desktop");
+        assert_eq!(
+            AppID::from_desktop_file_id("org.example.App.desktop", AppIDValidation::Strict)
+                .unwrap(),
+            app_id
+        );
+        assert_eq!(
+            AppID::from_bus_name("org.example.App", AppIDValidation::Strict).unwrap(),
+            app_id
+        );
+
+        assert!(AppID::from_bus_name(":1.42", AppIDValidation::Lenient).is_err());
+        assert!(AppID::from_bus_name("org.my-site.App", AppIDValidation::Strict).is_err());
+        assert_eq!(
+            AppID::from_bus_name("org.my-site.App", AppIDValidation::Lenient)
+                .unwrap()
+                .as_ref(),
+            "org.my_site.App"
+        );
+        assert_eq!(
+            AppID::from_desktop_file_id("org.my-site.App", AppIDValidation::Lenient)
+                .unwrap()
+                .desktop_file_id(),
+            "org.my_site.App.desktop"
+        );
+        assert_eq!(
+            AppID::parse_with("org.7zip.App-Name", AppIDValidation::Lenient)
+                .unwrap()
+                .as_ref(),
+            "org._7zip.App-Name"
+        );
+        assert!(AppID::parse_with("firefox", AppIDValidation::Lenient).is_err());
+        assert!(AppID::parse_with("org.example.App é", AppIDValidation::Lenient).is_err());
+    }
+
+    #[test]
+    fn from_env() {
+        let env = |vars: &[(&str, &str)]| {
+            vars.iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            AppID::from_env(env(&[
+                ("FLATPAK_ID", "org.example.App"),
+                (
+                    "GIO_LAUNCHED_DESKTOP_FILE",
+                    "/usr/share/applications/org.other.App.desktop"
+                ),
+            ])),
+            Some(AppID::from_str("org.example.App").unwrap())
+        );
+        assert_eq!(
+            AppID::from_env(env(&[
+                ("SNAP_NAME", "portal-test"),
+                ("SNAP_INSTANCE_NAME", "portal-test_beta"),
+            ])),
+            Some(AppID::from_str("snap.portal-test_beta").unwrap())
+        );
+        assert_eq!(
+            AppID::from_env(env(&[(
+                "GIO_LAUNCHED_DESKTOP_FILE",
+                "/usr/share/applications/org.other.App.desktop"
+            )])),
+            Some(AppID::from_str("org.other.App").unwrap())
+        );
+        assert_eq!(
+            AppID::from_env(env(&[
+                ("FLATPAK_ID", "invalid"),
+                (
+                    "GIO_LAUNCHED_DESKTOP_FILE",
+                    "/usr/share/applications/firefox.desktop"
+                ),
+            ])),
+            None
+        );
+    }
+
     #[test]
     fn test_is_valid_app_id() {
         assert!(is_valid_app_id("a.b"));
@@ -0,0 +1,281 @@
+//! A small command line client of the portals, meant for scripting inside
+//! of a sandbox and for debugging the behavior of a portal backend.
+
+use std::process::ExitCode;
+
+use ashpd::{
+    desktop::{
+        notification::{Notification, NotificationProxy},
+        open_uri::OpenFileRequest,
+        screencast::{CursorMode, Screencast, SourceType},
+        screenshot::Screenshot,
+        settings::Settings,
+        Color, PersistMode,
+    },
+    url::Url,
+    zvariant::{OwnedValue, Value},
+};
+use futures_util::StreamExt;
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+const USAGE: &str = "Usage: ashpd-cli <COMMAND> [OPTIONS]
+
+Commands:
+  screenshot [--interactive]          Take a screenshot and print its URI
+  pick-color                          Pick a color and print its RGB components
+  read-setting <NAMESPACE> <KEY>      Print the value of a setting
+  open-uri <URI> [--ask]              Open a URI with the preferred application
+  notify <ID> <TITLE> [BODY]          Send a notification
+  screencast [--window] [--multiple]  Start a screencast and print the PipeWire node IDs
+  help                                Print this message";
+
+#[derive(Debug)]
+enum Command {
+    Screenshot {
+        interactive: bool,
+    },
+    PickColor,
+    ReadSetting {
+        namespace: String,
+        key: String,
+    },
+    OpenUri {
+        uri: Url,
+        ask: bool,
+    },
+    Notify {
+        id: String,
+        title: String,
+        body: Option<String>,
+    },
+    Screencast {
+        window: bool,
+        multiple: bool,
+    },
+    Help,
+}
+
+impl Command {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let Some(command) = args.next() else {
+            return Ok(Self::Help);
+        };
+        let (flags, positional): (Vec<_>, Vec<_>) = args.partition(|arg| arg.starts_with("--"));
+        let flag = |name: &str| flags.iter().any(|f| f == name);
+        let allowed = |names: &[&str]| match flags.iter().find(|f| !names.contains(&f.as_str())) {
+            Some(unknown) => Err(format!("Unknown option `{unknown}`")),
+            None => Ok(()),
+        };
+        let arity = |min: usize, max: usize| {
+            if (min..=max).contains(&positional.len()) {
+                Ok(())
+            } else {
+                Err(format!("Wrong number of arguments for `{command}`"))
+            }
+        };
+
+        match command.as_str() {
+            "screenshot" => {
+                allowed(&["--interactive"])?;
+                arity(0, 0)?;
+                Ok(Self::Screenshot {
+                    interactive: flag("--interactive"),
+                })
+            }
+            "pick-color" => {
+                allowed(&[])?;
+                arity(0, 0)?;
+                Ok(Self::PickColor)
+            }
+            "read-setting" => {
+                allowed(&[])?;
+                arity(2, 2)?;
+                Ok(Self::ReadSetting {
+                    namespace: positional[0].clone(),
+                    key: positional[1].clone(),
+                })
+            }
+            "open-uri" => {
+                allowed(&["--ask"])?;
+                arity(1, 1)?;
+                let uri = Url::parse(&positional[0]).map_err(|e| format!("Invalid URI: {e}"))?;
+                Ok(Self::OpenUri {
+                    uri,
+                    ask: flag("--ask"),
+                })
+            }
+            "notify" => {
+                allowed(&[])?;
+                arity(2, 3)?;
+                Ok(Self::Notify {
+                    id: positional[0].clone(),
+                    title: positional[1].clone(),
+                    body: positional.get(2).cloned(),
+                })
+            }
+            "screencast" => {
+                allowed(&["--window", "--multiple"])?;
+                arity(0, 0)?;
+                Ok(Self::Screencast {
+                    window: flag("--window"),
+                    multiple: flag("--multiple"),
+                })
+            }
+            "help" | "--help" | "-h" => Ok(Self::Help),
+            _ => Err(format!("Unknown command `{command}`")),
+        }
+    }
+
+    async fn run(self) -> ashpd::Result<()> {
+        match self {
+            Self::Screenshot { interactive } => {
+                let response = Screenshot::request()
+                    .interactive(interactive)
+                    .send()
+                    .await?
+                    .response()?;
+                println!("{}", response.uri());
+            }
+            Self::PickColor => {
+                let color = Color::pick().send().await?.response()?;
+                println!("{} {} {}", color.red(), color.green(), color.blue());
+            }
+            Self::ReadSetting { namespace, key } => {
+                let proxy = Settings::new().await?;
+                let value = proxy.read::<OwnedValue>(&namespace, &key).await?;
+                println!("{}", Value::from(value));
+            }
+            Self::OpenUri { uri, ask } => {
+                OpenFileRequest::default()
+                    .ask(ask)
+                    .send_uri(&uri)
+                    .await?
+                    .response()?;
+            }
+            Self::Notify { id, title, body } => {
+                let proxy = NotificationProxy::new().await?;
+                let notification = Notification::new(&title).body(body.as_deref());
+                proxy.add_notification(&id, notification).await?;
+            }
+            Self::Screencast { window, multiple } => {
+                let types = if window {
+                    SourceType::Monitor | SourceType::Window
+                } else {
+                    SourceType::Monitor.into()
+                };
+                let proxy = Screencast::new().await?;
+                let session = proxy.create_session().await?;
+                proxy
+                    .select_sources(
+                        &session,
+                        CursorMode::Embedded,
+                        types,
+                        multiple,
+                        None,
+                        PersistMode::DoNot,
+                    )
+                    .await?
+                    .response()?;
+                let mut closed = std::pin::pin!(session.receive_closed().await?);
+                let response = proxy.start(&session, None).await?.response()?;
+                for stream in response.streams() {
+                    println!("{}", stream.pipe_wire_node_id());
+                }
+                // The streams are only valid as long as the session is open.
+                eprintln!("Press Enter to stop the screencast");
+                let mut lines = BufReader::new(tokio::io::stdin()).lines();
+                tokio::select! {
+                    _ = lines.next_line() => session.close().await?,
+                    _ = closed.next() => eprintln!("The screencast was stopped"),
+                }
+            }
+            Self::Help => println!("{USAGE}"),
+        }
+        Ok(())
+    }
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> ExitCode {
+    let command = match Command::parse(std::env::args().skip(1)) {
+        Ok(command) => command,
+        Err(err) => {
+            eprintln!("{err}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    match command.run().await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Command;
+
+    fn parse(args: &[&str]) -> Result<Command, String> {
+        Command::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn commands() {
+        assert!(matches!(parse(&[]), Ok(Command::Help)));
+        assert!(matches!(parse(&["--help"]), Ok(Command::Help)));
+        assert!(matches!(
+            parse(&["screenshot", "--interactive"]),
+            Ok(Command::Screenshot { interactive: true })
+        ));
+        assert!(matches!(parse(&["pick-color"]), Ok(Command::PickColor)));
+        assert!(matches!(
+            parse(&["read-setting", "org.freedesktop.appearance", "color-scheme"]),
+            Ok(Command::ReadSetting { namespace, key })
+                if namespace == "org.freedesktop.appearance" && key == "color-scheme"
+        ));
+        assert!(matches!(
+            parse(&["open-uri", "https://example.org", "--ask"]),
+            Ok(Command::OpenUri { uri, ask: true }) if uri.as_str() == "https://example.org/"
+        ));
+        assert!(matches!(
+            parse(&["notify", "id", "Title"]),
+            Ok(Command::Notify { id, title, body: None }) if id == "id" && title == "Title"
+        ));
+        assert!(matches!(
+            parse(&["notify", "id", "Title", "Body"]),
+            Ok(Command::Notify { body: Some(body), .. }) if body == "Body"
+        ));
+        assert!(matches!(
+            parse(&["screencast", "--multiple"]),
+            Ok(Command::Screencast {
+                window: false,
+                multiple: true
+            })
+        ));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            parse(&["unknown"]).unwrap_err(),
+            "Unknown command `unknown`"
+        );
+        assert_eq!(
+            parse(&["screenshot", "--window"]).unwrap_err(),
+            "Unknown option `--window`"
+        );
+        assert_eq!(
+            parse(&["read-setting", "org.freedesktop.appearance"]).unwrap_err(),
+            "Wrong number of arguments for `read-setting`"
+        );
+        assert_eq!(
+            parse(&["notify", "id", "Title", "Body", "Extra"]).unwrap_err(),
+            "Wrong number of arguments for `notify`"
+        );
+        assert!(parse(&["open-uri", "not a uri"])
+            .unwrap_err()
+            .starts_with("Invalid URI"));
+    }
+}
@@ -0,0 +1,434 @@
+//! The versions of the portals and a few values that rarely change, such as
+//! the mount point of the documents store or whether a camera is present,
+//! are fetched again on every call by default, unless the proxy that
+//! fetched the version is [shared](#shared-proxies).
+//!
+//! Once enabled, they are fetched once per connection and reused until they are
+//! invalidated, or until the portal serving them restarts. The cached values
+//! are:
+//!
+//! | Interface | Member |
+//! |-----------|--------|
+//! | any | `version` |
+//! | `org.freedesktop.portal.Camera` | `IsCameraPresent` |
+//! | `org.freedesktop.portal.Documents` | `GetMountPoint` |
+//! | `org.freedesktop.portal.RemoteDesktop` | `AvailableDeviceTypes` |
+//! | `org.freedesktop.portal.ScreenCast` | `AvailableCursorModes`, `AvailableSourceTypes` |
+//!
+//! ```rust,no_run
+//! use ashpd::desktop::camera::Camera;
+//!
+//! async fn run() -> ashpd::Result<()> {
+//!     ashpd::cache::enable();
+//!     let camera = Camera::new().await?;
+//!     println!("{}", camera.is_present().await?);
+//!     // A camera got plugged.
+//!     ashpd::cache::invalidate("org.freedesktop.portal.Camera", "IsCameraPresent");
+//!     println!("{}", camera.is_present().await?);
+//!     Ok(())
+//! }
+//! ```
+//!
+//! # Shared proxies
+//!
+//! The proxies of the portals, along with their version, can be shared with
+//! [`share_proxies`](crate::cache::share_proxies): creating the same portal
+//! wrapper twice on a connection, such as calling
+//! [`Camera::new`](crate::desktop::camera::Camera::new) or
+//! [`Settings::new`](crate::desktop::settings::Settings::new) several times,
+//! then reuses the proxy created the first time.
+//!
+//! The shared proxies keep their connection alive. They are dropped when the
+//! portal serving them restarts or leaves the bus, or when sharing is
+//! disabled again.
+
+use std::{
+    any::Any,
+    collections::BTreeMap,
+    future::Future,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+};
+
+use futures_util::StreamExt;
+
+use crate::{proxy::Proxy, Error};
+
+/// The server GUID and unique name of the connection, then the destination,
+/// interface and member of the value.
+type ValueKey = (String, Option<String>, String, String, String);
+
+type Values = BTreeMap<ValueKey, Box<dyn Any + Send + Sync>>;
+
+/// The server GUID and unique name of the connection, then the destination,
+/// path and interface of the proxy.
+pub(crate) type ProxyKey = (String, Option<String>, String, String, String);
+
+/// The server GUID and unique name of the connection, then the watched
+/// destination.
+type WatcherKey = (String, Option<String>, String);
+
+/// Watches the owner of a destination, to drop what was kept of it once it
+/// changes.
+#[derive(Debug)]
+struct Watcher {
+    task: zbus::Task<()>,
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static VALUES: Mutex<Values> = Mutex::new(BTreeMap::new());
+static SHARE_PROXIES: AtomicBool = AtomicBool::new(false);
+static PROXIES: Mutex<BTreeMap<ProxyKey, Proxy<'static>>> = Mutex::new(BTreeMap::new());
+static WATCHERS: Mutex<BTreeMap<WatcherKey, Watcher>> = Mutex::new(BTreeMap::new());
+
+/// Starts caching the values.
+pub fn enable() {
+    ENABLED.store(true, Ordering::SeqCst);
+}
+
+/// Stops caching the values and drops the cached ones.
+pub fn disable() {
+    ENABLED.store(false, Ordering::SeqCst);
+    clear();
+    stop_unused_watchers();
+}
+
+/// Whether the values are cached.
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::SeqCst)
+}
+
+/// Drops the cached values of `member`, a property or a method, of
+/// `interface`, on every connection.
+pub fn invalidate(interface: &str, member: &str) {
+    VALUES
+        .lock()
+        .unwrap()
+        .retain(|(_, _, _, i, m), _| i != interface || m != member);
+}
+
+/// Drops all the cached values.
+pub fn clear() {
+    VALUES.lock().unwrap().clear();
+}
+
+/// Whether to share the proxies of the portals, `false` by default.
+///
+/// Disabling it drops the shared proxies.
+pub fn share_proxies(share: bool) {
+    SHARE_PROXIES.store(share, Ordering::SeqCst);
+    if !share {
+        PROXIES.lock().unwrap().clear();
+        stop_unused_watchers();
+    }
+}
+
+/// Whether the proxies of the portals are shared.
+pub fn are_proxies_shared() -> bool {
+    SHARE_PROXIES.load(Ordering::SeqCst)
+}
+
+fn connection_key(connection: &zbus::Connection) -> (String, Option<String>) {
+    (
+        connection.server_guid().to_string(),
+        connection.unique_name().map(|name| name.to_string()),
+    )
+}
+
+pub(crate) fn proxy_key(
+    connection: &zbus::Connection,
+    destination: &str,
+    path: &str,
+    interface: &str,
+) -> ProxyKey {
+    let (guid, unique_name) = connection_key(connection);
+    (
+        guid,
+        unique_name,
+        destination.to_owned(),
+        path.to_owned(),
+        interface.to_owned(),
+    )
+}
+
+/// The shared proxy for `key`, if any.
+pub(crate) fn shared_proxy(key: &ProxyKey) -> Option<Proxy<'static>> {
+    if !are_proxies_shared() {
+        return None;
+    }
+    PROXIES.lock().unwrap().get(key).cloned()
+}
+
+/// Shares `proxy` if sharing is enabled, and watches the owner of its
+/// destination if anything about it is kept.
+pub(crate) async fn share_proxy(key: ProxyKey, proxy: &Proxy<'static>) {
+    let shared = are_proxies_shared();
+    if !shared && !is_enabled() {
+        return;
+    }
+    let (guid, unique_name, destination, _, _) = &key;
+    let watcher_key = (guid.clone(), unique_name.clone(), destination.clone());
+    if !WATCHERS.lock().unwrap().contains_key(&watcher_key) {
+        // A peer-to-peer connection has no bus to report the owner changes,
+        // there is nothing to watch then.
+        if let Ok(task) = watch_owner(proxy.connection(), watcher_key.clone()).await {
+            WATCHERS
+                .lock()
+                .unwrap()
+                .entry(watcher_key)
+                .or_insert(Watcher { task });
+        }
+    }
+    if shared {
+        PROXIES.lock().unwrap().insert(key, proxy.clone());
+    }
+}
+
+/// Forgets what was kept of the destination of `key` once its owner changes,
+/// the portal having restarted or left the bus.
+async fn watch_owner(
+    connection: &zbus::Connection,
+    key: WatcherKey,
+) -> zbus::Result<zbus::Task<()>> {
+    let dbus = zbus::fdo::DBusProxy::builder(connection)
+        .cache_properties(zbus::proxy::CacheProperties::No)
+        .build()
+        .await?;
+    let mut changes = dbus
+        .receive_name_owner_changed_with_args(&[(0, key.2.as_str())])
+        .await?;
+    Ok(connection.executor().spawn(
+        async move {
+            // The stream also ends once the connection is closed.
+            changes.next().await;
+            forget_destination(&key);
+        },
+        "ashpd portal owner watcher",
+    ))
+}
+
+fn forget_destination(key: &WatcherKey) {
+    let (guid, unique_name, destination) = key;
+    PROXIES
+        .lock()
+        .unwrap()
+        .retain(|(g, u, d, _, _), _| g != guid || u != unique_name || d != destination);
+    VALUES
+        .lock()
+        .unwrap()
+        .retain(|(g, u, d, _, _), _| g != guid || u != unique_name || d != destination);
+    let Some(watcher) = WATCHERS.lock().unwrap().remove(key) else {
+        return;
+    };
+    // The watcher is the running task, it ends on its own.
+    watcher.task.detach();
+}
+
+/// Stops watching the destinations once nothing is kept anymore.
+fn stop_unused_watchers() {
+    if !is_enabled() && !are_proxies_shared() {
+        WATCHERS.lock().unwrap().clear();
+    }
+}
+
+/// Drops the shared proxies of `connection`, which is no longer used.
+#[cfg(feature = "test")]
+pub(crate) fn forget_connection(connection: &zbus::Connection) {
+    let (guid, unique_name) = connection_key(connection);
+    PROXIES
+        .lock()
+        .unwrap()
+        .retain(|key, _| key.0 != guid || key.1 != unique_name);
+    VALUES
+        .lock()
+        .unwrap()
+        .retain(|key, _| key.0 != guid || key.1 != unique_name);
+    WATCHERS
+        .lock()
+        .unwrap()
+        .retain(|key, _| key.0 != guid || key.1 != unique_name);
+}
+
+/// The cached value of `member` of the interface of `proxy`, fetched with
+/// `fetch` if missing or if caching is disabled.
+///
+/// The values are cached per connection and destination, like the proxies.
+pub(crate) async fn get_or_fetch<T, F>(
+    proxy: &zbus::Proxy<'_>,
+    member: &str,
+    fetch: F,
+) -> Result<T, Error>
+where
+    T: Clone + Send + Sync + 'static,
+    F: Future<Output = Result<T, Error>>,
+{
+    let (guid, unique_name) = connection_key(proxy.connection());
+    let key = (
+        guid,
+        unique_name,
+        proxy.destination().to_string(),
+        proxy.interface().to_string(),
+        member.to_owned(),
+    );
+    get_or_fetch_key(key, fetch).await
+}
+
+async fn get_or_fetch_key<T, F>(key: ValueKey, fetch: F) -> Result<T, Error>
+where
+    T: Clone + Send + Sync + 'static,
+    F: Future<Output = Result<T, Error>>,
+{
+    if !is_enabled() {
+        return fetch.await;
+    }
+    if let Some(value) = VALUES
+        .lock()
+        .unwrap()
+        .get(&key)
+        .and_then(|value| value.downcast_ref::<T>())
+    {
+        return Ok(value.clone());
+    }
+    let value = fetch.await?;
+    VALUES.lock().unwrap().insert(key, Box::new(value.clone()));
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Restores the default settings when dropped, even if a test panics.
+    struct Reset;
+
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            disable();
+            share_proxies(false);
+        }
+    }
+
+    #[tokio::test]
+    async fn cache() {
+        // Mock portals serve different versions across the tests.
+        #[cfg(feature = "test")]
+        let _lock = crate::test::CONNECTION_LOCK.lock().await;
+        let key = |connection: &str| {
+            (
+                connection.to_owned(),
+                Some(":1.1".to_owned()),
+                "org.freedesktop.portal.Desktop".to_owned(),
+                "a.b".to_owned(),
+                "c".to_owned(),
+            )
+        };
+        let get = |connection: &str, value: u32| {
+            get_or_fetch_key(key(connection), async move { Ok::<_, Error>(value) })
+        };
+        assert_eq!(get("a", 1).await.unwrap(), 1);
+        assert_eq!(get("a", 2).await.unwrap(), 2);
+
+        let _reset = Reset;
+        enable();
+        assert_eq!(get("a", 3).await.unwrap(), 3);
+        assert_eq!(get("a", 4).await.unwrap(), 3);
+        let failing = async { Err::<u32, _>(Error::NoResponse) };
+        assert_eq!(get_or_fetch_key(key("a"), failing).await.unwrap(), 3);
+        // Another connection has its own values.
+        assert_eq!(get("b", 5).await.unwrap(), 5);
+        assert_eq!(get("a", 6).await.unwrap(), 3);
+        invalidate("a.b", "c");
+        assert_eq!(get("a", 7).await.unwrap(), 7);
+        assert_eq!(get("b", 8).await.unwrap(), 8);
+        disable();
+        assert_eq!(get("a", 9).await.unwrap(), 9);
+    }
+
+    #[cfg(feature = "test")]
+    #[tokio::test]
+    async fn shared_proxies() {
+        use crate::{proxy::Proxy, test::MockPortal};
+
+        let _lock = crate::test::CONNECTION_LOCK.lock().await;
+        let mock = MockPortal::new().await.unwrap();
+        let interface = "org.freedesktop.portal.Screenshot";
+        mock.set_version(interface, 2);
+        assert_eq!(Proxy::new_desktop(interface).await.unwrap().version(), 2);
+        mock.set_version(interface, 3);
+        assert_eq!(Proxy::new_desktop(interface).await.unwrap().version(), 3);
+
+        let _reset = Reset;
+        share_proxies(true);
+        assert_eq!(Proxy::new_desktop(interface).await.unwrap().version(), 3);
+        mock.set_version(interface, 4);
+        assert_eq!(Proxy::new_desktop(interface).await.unwrap().version(), 3);
+        drop(mock);
+
+        let mock = MockPortal::new().await.unwrap();
+        mock.set_version(interface, 5);
+        assert_eq!(Proxy::new_desktop(interface).await.unwrap().version(), 5);
+        share_proxies(false);
+        assert!(PROXIES.lock().unwrap().is_empty());
+    }
+
+    #[cfg(feature = "test")]
+    #[tokio::test]
+    async fn portal_restart() {
+        use std::time::Duration;
+
+        use crate::{proxy::Proxy, test::PrivateBus};
+
+        struct Screenshot(u32);
+
+        #[zbus::interface(name = "org.freedesktop.portal.Screenshot")]
+        impl Screenshot {
+            #[zbus(property, name = "version")]
+            fn version(&self) -> u32 {
+                self.0
+            }
+        }
+
+        let _lock = crate::test::CONNECTION_LOCK.lock().await;
+        let Some(bus) = PrivateBus::start_or_skip().await else {
+            return;
+        };
+        let address = bus.address();
+        let portal = |version: u32| async move {
+            zbus::connection::Builder::address(address)
+                .unwrap()
+                .name("org.freedesktop.portal.Desktop")
+                .unwrap()
+                .serve_at("/org/freedesktop/portal/desktop", Screenshot(version))
+                .unwrap()
+                .build()
+                .await
+                .unwrap()
+        };
+        let interface = "org.freedesktop.portal.Screenshot";
+
+        let _reset = Reset;
+        enable();
+        share_proxies(true);
+        let connection = portal(1).await;
+        assert_eq!(Proxy::new_desktop(interface).await.unwrap().version(), 1);
+        assert_eq!(PROXIES.lock().unwrap().len(), 1);
+
+        // The portal restarts with a newer version.
+        connection.close().await.unwrap();
+        let _connection = portal(2).await;
+        let mut version = 1;
+        for _ in 0..100 {
+            version = Proxy::new_desktop(interface).await.unwrap().version();
+            if version == 2 {
+                break;
+            }
+            crate::test::sleep(Duration::from_millis(10)).await;
+        }
+        disable();
+        share_proxies(false);
+        assert_eq!(version, 2);
+        assert!(WATCHERS.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,163 @@
+use std::sync::{Arc, Mutex};
+
+use futures_channel::oneshot;
+
+#[derive(Debug, Default)]
+struct State {
+    cancelled: bool,
+    waiters: Vec<oneshot::Sender<()>>,
+}
+
+/// A token to cancel the portal requests and close the sessions it was given
+/// to, for example from the "Cancel" button of an application.
+///
+/// The token doesn't depend on the async runtime. Its clones share the same
+/// state: cancelling one of them cancels all of them.
+///
+/// ```rust,no_run
+/// use ashpd::{desktop::screenshot::Screenshot, CancellationToken};
+///
+/// async fn run(token: CancellationToken) -> ashpd::Result<()> {
+///     // Call `token.cancel()` elsewhere to dismiss the dialog.
+///     let response = Screenshot::request()
+///         .cancel_with(&token)
+///         .send()
+///         .await?
+///         .response()?;
+///     println!("{}", response.uri());
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<Mutex<State>>);
+
+impl CancellationToken {
+    /// Create a new token, not cancelled yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel the requests and close the sessions tied to the token.
+    pub fn cancel(&self) {
+        let mut state = self.0.lock().unwrap();
+        state.cancelled = true;
+        for waiter in state.waiters.drain(..) {
+            let _ = waiter.send(());
+        }
+    }
+
+    /// Whether [`CancellationToken::cancel`] was called.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.lock().unwrap().cancelled
+    }
+
+    /// Wait until the token is cancelled.
+    pub async fn cancelled(&self) {
+        let receiver = {
+            let mut state = self.0.lock().unwrap();
+            if state.cancelled {
+                return;
+            }
+            // Forget the waits that were given up.
+            state.waiters.retain(|waiter| !waiter.is_canceled());
+            let (sender, receiver) = oneshot::channel();
+            state.waiters.push(sender);
+            receiver
+        };
+        // The sender is only dropped once it sent the cancellation.
+        let _ = receiver.await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn cancel() {
+        let token = CancellationToken::new();
+        let clone = token.clone();
+        assert!(!clone.is_cancelled());
+        let (_, ()) = futures_util::future::join(clone.cancelled(), async { token.cancel() }).await;
+        assert!(clone.is_cancelled());
+        // Already cancelled.
+        token.cancelled().await;
+    }
+
+    #[cfg(feature = "test")]
+    #[tokio::test]
+    async fn requests_and_sessions() {
+        use std::time::Duration;
+
+        use crate::{
+            desktop::{
+                remote_desktop::RemoteDesktop, screenshot::Screenshot, ResponseError, Session,
+            },
+            test::{MockPortal, MockResponse},
+            Error,
+        };
+
+        let _lock = crate::test::CONNECTION_LOCK.lock().await;
+        let mock = MockPortal::new().await.unwrap();
+        mock.respond(
+            "org.freedesktop.portal.Screenshot",
+            "Screenshot",
+            MockResponse::cancelled().delay(Duration::from_secs(1)),
+        );
+        mock.respond(
+            "org.freedesktop.portal.Session",
+            "Close",
+            MockResponse::empty(),
+        );
+        let close_calls = |interface: &str| {
+            mock.calls()
+                .iter()
+                .filter(|message| {
+                    let header = message.header();
+                    header.interface().is_some_and(|i| i == interface)
+                        && header.member().is_some_and(|m| m == "Close")
+                })
+                .count()
+        };
+        let closed = |interface: &str| close_calls(interface) > 0;
+
+        let token = CancellationToken::new();
+        let request = Screenshot::request().cancel_with(&token).send();
+        let (response, ()) = futures_util::future::join(request, async {
+            crate::test::sleep(Duration::from_millis(50)).await;
+            token.cancel();
+        })
+        .await;
+        assert!(matches!(
+            response,
+            Err(Error::Response(ResponseError::Cancelled))
+        ));
+        assert!(closed("org.freedesktop.portal.Request"));
+
+        let session =
+            Session::<RemoteDesktop>::new("/org/freedesktop/portal/desktop/session/1_0/t")
+                .await
+                .unwrap();
+        let token = CancellationToken::new();
+        session.close_with(&token);
+        crate::test::sleep(Duration::from_millis(50)).await;
+        assert!(!closed("org.freedesktop.portal.Session"));
+        token.cancel();
+        crate::test::sleep(Duration::from_millis(50)).await;
+        assert_eq!(close_calls("org.freedesktop.portal.Session"), 1);
+
+        // An explicitly closed session stops waiting for the token.
+        let session =
+            Session::<RemoteDesktop>::new("/org/freedesktop/portal/desktop/session/1_0/u")
+                .await
+                .unwrap();
+        let token = CancellationToken::new();
+        session.close_with(&token);
+        crate::test::sleep(Duration::from_millis(50)).await;
+        session.close().await.unwrap();
+        crate::test::sleep(Duration::from_millis(50)).await;
+        token.cancel();
+        crate::test::sleep(Duration::from_millis(50)).await;
+        assert_eq!(close_calls("org.freedesktop.portal.Session"), 2);
+    }
+}
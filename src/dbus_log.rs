@@ -0,0 +1,157 @@
+//! Logging of the D-Bus traffic of the portals, enabled with the `log_dbus`
+//! feature.
+//!
+//! The headers and body signatures are logged at the debug level under the
+//! `ashpd::dbus` target, the bodies at the trace level. The values of the
+//! options that hold secrets, like restore tokens, are redacted.
+
+use std::fmt::Write;
+
+use futures_util::StreamExt;
+use serde::Serialize;
+use zbus::{
+    message::Type as MessageType,
+    zvariant::{self, Value},
+    Message, MessageStream,
+};
+
+const TARGET: &str = "ashpd::dbus";
+
+/// The keys of the options and results whose values are never logged.
+const SECRET_KEYS: &[&str] = &["token", "restore_token", "activation_token"];
+
+const REDACTED: &str = "<redacted>";
+
+/// Logs an outgoing method call.
+pub(crate) fn method_call<B>(proxy: &zbus::Proxy<'_>, method_name: &str, body: &B)
+where
+    B: Serialize + zvariant::Type,
+{
+    tracing::debug!(
+        target: TARGET,
+        "→ {}.{} on {} ({})",
+        proxy.interface(),
+        method_name,
+        proxy.path(),
+        B::SIGNATURE,
+    );
+    if tracing::enabled!(target: TARGET, tracing::Level::TRACE) {
+        let msg = Message::method_call(proxy.path().clone(), method_name)
+            .and_then(|builder| builder.build(body));
+        if let Ok(msg) = msg {
+            tracing::trace!(target: TARGET, "  body {}", redacted_body(&msg));
+        }
+    }
+}
+
+/// Logs the incoming messages of `connection` until it is closed.
+///
+/// Called once for each connection used by the proxies, the session one or
+/// the one of a [`MockPortal`](crate::test::MockPortal) for example.
+pub(crate) fn watch(connection: &zbus::Connection) {
+    let mut stream = MessageStream::from(connection);
+    connection
+        .executor()
+        .spawn(
+            async move {
+                while let Some(Ok(msg)) = stream.next().await {
+                    incoming(&msg);
+                }
+            },
+            "ashpd dbus log",
+        )
+        .detach();
+}
+
+fn incoming(msg: &Message) {
+    let header = msg.header();
+    let signature = msg.body().signature().to_string();
+    let reply_serial = header.reply_serial().map(|s| s.get()).unwrap_or_default();
+    match header.message_type() {
+        MessageType::MethodReturn => {
+            tracing::debug!(target: TARGET, "← reply to #{reply_serial} ({signature})")
+        }
+        MessageType::Error => tracing::debug!(
+            target: TARGET,
+            "← error {} to #{reply_serial} ({signature})",
+            header.error_name().map(|n| n.as_str()).unwrap_or_default(),
+        ),
+        MessageType::Signal => tracing::debug!(
+            target: TARGET,
+            "← signal {}.{} on {} ({signature})",
+            header.interface().map(|i| i.as_str()).unwrap_or_default(),
+            header.member().map(|m| m.as_str()).unwrap_or_default(),
+            header.path().map(|p| p.as_str()).unwrap_or_default(),
+        ),
+        // Method calls are only received by the backends.
+        MessageType::MethodCall => return,
+    }
+    tracing::trace!(target: TARGET, "  body {}", redacted_body(msg));
+}
+
+/// Formats the body of `msg` with the secrets replaced.
+fn redacted_body(msg: &Message) -> String {
+    let body = msg.body();
+    if body.signature().to_string().is_empty() {
+        return "()".to_owned();
+    }
+    let Ok(structure) = body.deserialize::<zvariant::Structure<'_>>() else {
+        return format!("<failed to deserialize {}>", body.signature());
+    };
+    let mut formatted = String::from("(");
+    for (i, mut field) in structure.into_fields().into_iter().enumerate() {
+        if i > 0 {
+            formatted.push_str(", ");
+        }
+        redact(&mut field);
+        let _ = write!(formatted, "{field}");
+    }
+    formatted.push(')');
+    formatted
+}
+
+/// Replaces the values of the [`SECRET_KEYS`] in the dictionaries of `value`.
+fn redact(value: &mut Value<'_>) {
+    match value {
+        Value::Value(inner) => redact(inner),
+        Value::Dict(dict) => {
+            for (key, value) in dict.iter_mut() {
+                let is_secret =
+                    matches!(key, Value::Str(key) if SECRET_KEYS.contains(&key.as_str()));
+                if !is_secret {
+                    redact(value);
+                } else if let Value::Value(_) = value {
+                    *value = Value::Value(Box::new(Value::from(REDACTED)));
+                } else {
+                    *value = Value::from(REDACTED);
+                }
+            }
+        }
+        _ => (),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use zbus::{zvariant::Value, Message};
+
+    use super::redacted_body;
+
+    #[test]
+    fn redact_secrets() {
+        let options = HashMap::from([
+            ("restore_token", Value::from("a-secret")),
+            ("handle_token", Value::from("ashpd_1234")),
+        ]);
+        let msg = Message::method_call("/org/freedesktop/portal/desktop", "SelectSources")
+            .unwrap()
+            .build(&("/org/freedesktop/portal/desktop/session/1", options))
+            .unwrap();
+        let body = redacted_body(&msg);
+        assert!(!body.contains("a-secret"));
+        assert!(body.contains("<redacted>"));
+        assert!(body.contains("ashpd_1234"));
+    }
+}
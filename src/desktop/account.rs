@@ -22,10 +22,12 @@
 //! }
 //! ```
 
-use zbus::zvariant::{DeserializeDict, SerializeDict, Type};
+use std::time::Duration;
+
+use zbus::zvariant::{SerializeDict, Type};
 
 use super::HandleToken;
-use crate::{desktop::request::Request, proxy::Proxy, Error, WindowIdentifier};
+use crate::{desktop::request::Request, proxy::Proxy, CancellationToken, Error, WindowIdentifier};
 
 #[derive(SerializeDict, Type, Debug, Default)]
 #[zvariant(signature = "dict")]
@@ -34,13 +36,16 @@ struct UserInformationOptions {
     reason: Option<String>,
 }
 
-#[derive(Debug, DeserializeDict, SerializeDict, Type)]
-/// The response of a [`UserInformationRequest`] request.
-#[zvariant(signature = "dict")]
-pub struct UserInformation {
-    id: String,
-    name: String,
-    image: url::Url,
+crate::helpers::dict! {
+    #[derive(Clone, Debug)]
+    #[cfg_attr(feature = "glib", derive(glib::Boxed))]
+    #[cfg_attr(feature = "glib", boxed_type(name = "AshpdUserInformation"))]
+    /// The response of a [`UserInformationRequest`] request.
+    pub struct UserInformation {
+        id: String,
+        name: String,
+        image: url::Url,
+    }
 }
 
 impl UserInformation {
@@ -79,15 +84,24 @@ impl UserInformation {
     }
 }
 
-struct AccountProxy<'a>(Proxy<'a>);
+/// The interface lets sandboxed applications query basic information about
+/// the user, like their name and avatar picture.
+///
+/// The requests are sent with the builders, like [`UserInformation::request`], the proxy
+/// gives access to the version of the interface.
+///
+/// Wrapper of the DBus interface: [`org.freedesktop.portal.Account`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Account.html).
+#[doc(alias = "org.freedesktop.portal.Account")]
+pub struct AccountProxy<'a>(Proxy<'a>);
 
 impl<'a> AccountProxy<'a> {
+    /// Create a new instance of [`AccountProxy`].
     pub async fn new() -> Result<AccountProxy<'a>, Error> {
         let proxy = Proxy::new_desktop("org.freedesktop.portal.Account").await?;
         Ok(Self(proxy))
     }
 
-    pub async fn user_information(
+    async fn user_information(
         &self,
         identifier: Option<&WindowIdentifier>,
         options: UserInformationOptions,
@@ -111,6 +125,18 @@ impl<'a> std::ops::Deref for AccountProxy<'a> {
     }
 }
 
+impl crate::Versioned for AccountProxy<'_> {
+    fn version(&self) -> u32 {
+        self.0.version()
+    }
+}
+
+impl crate::Timeout for AccountProxy<'_> {
+    fn set_timeout(&mut self, timeout: impl Into<Option<std::time::Duration>>) {
+        self.0.set_timeout(timeout.into());
+    }
+}
+
 #[doc(alias = "xdp_portal_get_user_information")]
 #[doc(alias = "org.freedesktop.portal.Account")]
 #[derive(Debug, Default)]
@@ -120,6 +146,9 @@ impl<'a> std::ops::Deref for AccountProxy<'a> {
 pub struct UserInformationRequest {
     options: UserInformationOptions,
     identifier: Option<WindowIdentifier>,
+    timeout: Option<Duration>,
+    cancel_on_drop: bool,
+    cancellation: Option<CancellationToken>,
 }
 
 impl UserInformationRequest {
@@ -137,11 +166,45 @@ impl UserInformationRequest {
         self
     }
 
+    #[must_use]
+    /// Sets how long to wait for the request to complete, including the user
+    /// interaction. The request is closed once the timeout expires.
+    pub fn timeout(mut self, timeout: impl Into<Option<Duration>>) -> Self {
+        self.timeout = timeout.into();
+        self
+    }
+
+    #[must_use]
+    /// Sets whether to close the request if the future returned by `send` is
+    /// dropped before the user interaction ends, dismissing the dialog.
+    pub fn cancel_on_drop(mut self, cancel_on_drop: bool) -> Self {
+        self.cancel_on_drop = cancel_on_drop;
+        self
+    }
+
+    #[must_use]
+    /// Closes the request once `token` is cancelled, the request then fails
+    /// with [`ResponseError::Cancelled`](crate::desktop::ResponseError::Cancelled).
+    pub fn cancel_with(mut self, token: &CancellationToken) -> Self {
+        self.cancellation = Some(token.clone());
+        self
+    }
+
     /// Build the [`UserInformation`].
     pub async fn send(self) -> Result<Request<UserInformation>, Error> {
-        let proxy = AccountProxy::new().await?;
+        let mut proxy = AccountProxy::new().await?;
+        proxy.0.set_timeout(self.timeout);
+        proxy.0.set_cancel_on_drop(self.cancel_on_drop);
+        proxy.0.set_cancellation(self.cancellation);
         proxy
             .user_information(self.identifier.as_ref(), self.options)
             .await
     }
 }
+
+#[cfg(test)]
+pub(crate) const SIGNATURES: &[crate::proxy::WrapperSignature] = crate::proxy::wrapper_signatures! {
+    "org.freedesktop.portal.Account" {
+        "GetUserInformation": (&str, &UserInformationOptions) => zbus::zvariant::OwnedObjectPath,
+    }
+};
@@ -30,11 +30,13 @@
 //! If no `command` is provided, the [`Exec`](https://specifications.freedesktop.org/desktop-entry-spec/desktop-entry-spec-latest.html#exec-variables) line from the [desktop
 //! file](https://specifications.freedesktop.org/desktop-entry-spec/desktop-entry-spec-latest.html#introduction) will be used.
 
+use std::time::Duration;
+
 use serde::Serialize;
-use zbus::zvariant::{DeserializeDict, SerializeDict, Type};
+use zbus::zvariant::{SerializeDict, Type};
 
 use super::{HandleToken, Request};
-use crate::{proxy::Proxy, Error, WindowIdentifier};
+use crate::{proxy::Proxy, CancellationToken, Error, WindowIdentifier};
 
 #[derive(SerializeDict, Type, Debug, Default)]
 #[zvariant(signature = "dict")]
@@ -48,12 +50,13 @@ struct BackgroundOptions {
     command: Option<Vec<String>>,
 }
 
-#[derive(DeserializeDict, Type, Debug)]
-/// The response of a [`BackgroundRequest`] request.
-#[zvariant(signature = "dict")]
-pub struct Background {
-    background: bool,
-    autostart: bool,
+crate::helpers::dict! {
+    #[derive(Debug)]
+    /// The response of a [`BackgroundRequest`] request.
+    pub struct Background {
+        background: bool,
+        autostart: bool,
+    }
 }
 
 impl Background {
@@ -78,8 +81,8 @@ impl Background {
 
 #[derive(SerializeDict, Type, Debug, Default)]
 #[zvariant(signature = "dict")]
-struct SetStatusOptions {
-    message: String,
+struct SetStatusOptions<'a> {
+    message: &'a str,
 }
 
 /// The interface lets sandboxed applications request that the application
@@ -114,13 +117,7 @@ impl<'a> BackgroundProxy<'a> {
     /// See also [`SetStatus`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Background.html#org-freedesktop-portal-background-setstatus).
     pub async fn set_status(&self, message: &str) -> Result<(), Error> {
         self.0
-            .call_versioned(
-                "SetStatus",
-                &(SetStatusOptions {
-                    message: message.to_owned(),
-                }),
-                2,
-            )
+            .call_versioned("SetStatus", &(SetStatusOptions { message }), 2)
             .await
     }
 
@@ -148,6 +145,18 @@ impl<'a> std::ops::Deref for BackgroundProxy<'a> {
     }
 }
 
+impl crate::Versioned for BackgroundProxy<'_> {
+    fn version(&self) -> u32 {
+        self.0.version()
+    }
+}
+
+impl crate::Timeout for BackgroundProxy<'_> {
+    fn set_timeout(&mut self, timeout: impl Into<Option<std::time::Duration>>) {
+        self.0.set_timeout(timeout.into());
+    }
+}
+
 #[doc(alias = "xdp_portal_request_background")]
 /// A [builder-pattern] type to construct [`Background`].
 ///
@@ -155,6 +164,9 @@ impl<'a> std::ops::Deref for BackgroundProxy<'a> {
 #[derive(Debug, Default)]
 pub struct BackgroundRequest {
     identifier: Option<WindowIdentifier>,
+    timeout: Option<Duration>,
+    cancel_on_drop: bool,
+    cancellation: Option<CancellationToken>,
     options: BackgroundOptions,
 }
 
@@ -166,6 +178,30 @@ impl BackgroundRequest {
         self
     }
 
+    #[must_use]
+    /// Sets how long to wait for the request to complete, including the user
+    /// interaction. The request is closed once the timeout expires.
+    pub fn timeout(mut self, timeout: impl Into<Option<Duration>>) -> Self {
+        self.timeout = timeout.into();
+        self
+    }
+
+    #[must_use]
+    /// Sets whether to close the request if the future returned by `send` is
+    /// dropped before the user interaction ends, dismissing the dialog.
+    pub fn cancel_on_drop(mut self, cancel_on_drop: bool) -> Self {
+        self.cancel_on_drop = cancel_on_drop;
+        self
+    }
+
+    #[must_use]
+    /// Closes the request once `token` is cancelled, the request then fails
+    /// with [`ResponseError::Cancelled`](crate::desktop::ResponseError::Cancelled).
+    pub fn cancel_with(mut self, token: &CancellationToken) -> Self {
+        self.cancellation = Some(token.clone());
+        self
+    }
+
     #[must_use]
     /// Sets whether to auto start the application or not.
     pub fn auto_start(mut self, auto_start: impl Into<Option<bool>>) -> Self {
@@ -203,9 +239,20 @@ impl BackgroundRequest {
 
     /// Build the [`Background`].
     pub async fn send(self) -> Result<Request<Background>, Error> {
-        let proxy = BackgroundProxy::new().await?;
+        let mut proxy = BackgroundProxy::new().await?;
+        proxy.0.set_timeout(self.timeout);
+        proxy.0.set_cancel_on_drop(self.cancel_on_drop);
+        proxy.0.set_cancellation(self.cancellation);
         proxy
             .request_background(self.identifier.as_ref(), self.options)
             .await
     }
 }
+
+#[cfg(test)]
+pub(crate) const SIGNATURES: &[crate::proxy::WrapperSignature] = crate::proxy::wrapper_signatures! {
+    "org.freedesktop.portal.Background" {
+        "RequestBackground": (&str, &BackgroundOptions) => zbus::zvariant::OwnedObjectPath,
+        "SetStatus": SetStatusOptions<'_> => (),
+    }
+};
@@ -16,6 +16,11 @@
 //!     Ok(())
 //! }
 //! ```
+//!
+//! Applications that also run outside of a sandbox can use
+//! [`request_with_fallback`], which talks to PipeWire directly when no camera
+//! portal is available on the host.
+//!
 //! An example on how to connect with Pipewire can be found [here](https://github.com/bilelmoussaoui/ashpd/blob/master/examples/screen_cast_pw.rs).
 //! Although the example's primary focus is screen casting, stream connection
 //! logic remains the same -- with one accessibility change:
@@ -31,14 +36,21 @@
 //! )?;
 //! ```
 
-use std::{collections::HashMap, os::fd::OwnedFd};
+use std::{
+    collections::HashMap,
+    os::{fd::OwnedFd, unix::net::UnixStream},
+    path::PathBuf,
+};
 
 #[cfg(feature = "pipewire")]
 use pipewire::{context::Context, main_loop::MainLoop};
 use zbus::zvariant::{self, SerializeDict, Type, Value};
 
-use super::{HandleToken, Request};
-use crate::{proxy::Proxy, Error};
+use super::{
+    device_access::{Device, DeviceAccess, DevicePermission},
+    HandleToken, Request,
+};
+use crate::{proxy::Proxy, AppID, Error};
 
 #[derive(SerializeDict, Type, Debug, Default)]
 #[zvariant(signature = "dict")]
@@ -106,7 +118,22 @@ impl<'a> Camera<'a> {
     #[doc(alias = "IsCameraPresent")]
     #[doc(alias = "xdp_portal_is_camera_present")]
     pub async fn is_present(&self) -> Result<bool, Error> {
-        self.0.property("IsCameraPresent").await
+        self.0.cached_property("IsCameraPresent").await
+    }
+
+    /// The decision the user made the last time `app_id` requested access to
+    /// the camera, or [`None`] if it was never asked.
+    ///
+    /// **Note** This relies on the permission store which is not accessible
+    /// to sandboxed applications, see [`DeviceAccess`].
+    pub async fn access_permission(
+        &self,
+        app_id: &AppID,
+    ) -> Result<Option<DevicePermission>, Error> {
+        DeviceAccess::new()
+            .await?
+            .permission(Device::Camera, app_id)
+            .await
     }
 }
 
@@ -118,6 +145,18 @@ impl<'a> std::ops::Deref for Camera<'a> {
     }
 }
 
+impl crate::Versioned for Camera<'_> {
+    fn version(&self) -> u32 {
+        self.0.version()
+    }
+}
+
+impl crate::Timeout for Camera<'_> {
+    fn set_timeout(&mut self, timeout: impl Into<Option<std::time::Duration>>) {
+        self.0.set_timeout(timeout.into());
+    }
+}
+
 #[cfg(feature = "pipewire")]
 /// A PipeWire camera stream returned by [`pipewire_streams`].
 #[derive(Debug)]
@@ -279,3 +318,90 @@ pub async fn request() -> Result<Option<(OwnedFd, Vec<Stream>)>, Error> {
         Ok(None)
     }
 }
+
+#[cfg(not(feature = "pipewire"))]
+#[cfg_attr(docsrs, doc(cfg(not(feature = "pipewire"))))]
+/// Same as [`request`], but connects to the PipeWire daemon of the session
+/// directly when the application is not sandboxed and the camera portal is
+/// missing.
+///
+/// The cameras are then looked up in `/dev/video*`.
+pub async fn request_with_fallback() -> Result<Option<OwnedFd>, Error> {
+    match request().await {
+        Err(err) if err.is_portal_missing() && !crate::is_sandboxed().await => {
+            if !has_video_devices() {
+                return Ok(None);
+            }
+            Ok(Some(open_pipewire_socket()?))
+        }
+        result => result,
+    }
+}
+
+#[cfg(feature = "pipewire")]
+#[cfg_attr(docsrs, doc(cfg(feature = "pipewire")))]
+/// Same as [`request`], but connects to the PipeWire daemon of the session
+/// directly when the application is not sandboxed and the camera portal is
+/// missing.
+///
+/// The cameras are then looked up among the PipeWire nodes, like with
+/// [`pipewire_streams`].
+pub async fn request_with_fallback() -> Result<Option<(OwnedFd, Vec<Stream>)>, Error> {
+    match request().await {
+        Err(err) if err.is_portal_missing() && !crate::is_sandboxed().await => {
+            let fd = open_pipewire_socket()?;
+            let streams = pipewire_streams(fd.try_clone()?).await?;
+            if streams.is_empty() {
+                return Ok(None);
+            }
+            Ok(Some((fd, streams)))
+        }
+        result => result,
+    }
+}
+
+/// Connects to the PipeWire daemon of the session, the same way
+/// `pw_context_connect` looks up its socket.
+fn open_pipewire_socket() -> Result<OwnedFd, Error> {
+    let remote = std::env::var_os("PIPEWIRE_REMOTE")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("pipewire-0"));
+    let path = if remote.is_absolute() {
+        remote
+    } else {
+        let runtime_dir = std::env::var_os("PIPEWIRE_RUNTIME_DIR")
+            .or_else(|| std::env::var_os("XDG_RUNTIME_DIR"))
+            .ok_or_else(|| {
+                Error::IO(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    "Failed to find the PipeWire runtime directory",
+                ))
+            })?;
+        PathBuf::from(runtime_dir).join(remote)
+    };
+    Ok(UnixStream::connect(path)?.into())
+}
+
+#[cfg(not(feature = "pipewire"))]
+/// Whether any V4L2 device is available.
+fn has_video_devices() -> bool {
+    std::fs::read_dir("/dev")
+        .map(|entries| {
+            entries.flatten().any(|entry| {
+                entry
+                    .file_name()
+                    .to_str()
+                    .and_then(|name| name.strip_prefix("video"))
+                    .is_some_and(|index| index.parse::<u32>().is_ok())
+            })
+        })
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+pub(crate) const SIGNATURES: &[crate::proxy::WrapperSignature] = crate::proxy::wrapper_signatures! {
+    "org.freedesktop.portal.Camera" {
+        "AccessCamera": CameraAccessOptions => zbus::zvariant::OwnedObjectPath,
+        "OpenPipeWireRemote": std::collections::HashMap<&str, zbus::zvariant::Value<'_>> => zbus::zvariant::OwnedFd,
+    }
+};
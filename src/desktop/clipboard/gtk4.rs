@@ -0,0 +1,259 @@
+use std::{
+    cell::RefCell,
+    future::Future,
+    os::fd::OwnedFd,
+    pin::Pin,
+    rc::{Rc, Weak},
+};
+
+use ::gtk4::{gdk, gio, glib, prelude::*, subclass::prelude::*};
+use futures_util::StreamExt;
+
+use super::Clipboard;
+use crate::{
+    desktop::{remote_desktop::RemoteDesktop, Session},
+    Result,
+};
+
+struct Inner {
+    proxy: Clipboard<'static>,
+    session: Session<'static, RemoteDesktop<'static>>,
+    clipboard: gdk::Clipboard,
+    /// The content of the remote clipboard, while it is set on the local one.
+    remote_content: RefCell<Option<RemoteContent>>,
+}
+
+impl Inner {
+    /// Advertises the content of the local clipboard to the remote session.
+    async fn offer_local_content(&self) -> Result<()> {
+        let mime_types = self.clipboard.formats().mime_types();
+        let mime_types = mime_types.iter().map(|m| m.as_str()).collect::<Vec<_>>();
+        self.proxy.set_selection(&self.session, &mime_types).await
+    }
+
+    /// Sets the content of the remote clipboard on the local one.
+    fn set_remote_content(self: &Rc<Self>, mime_types: Vec<String>) {
+        let content = RemoteContent::new(self, mime_types);
+        // Replace the content first so the `changed` handler skips it.
+        let previous = self.remote_content.replace(Some(content.clone()));
+        if self.clipboard.set_content(Some(&content)).is_err() {
+            self.remote_content.replace(previous);
+        }
+    }
+
+    /// Sends the content of the local clipboard as `mime_type` to the remote
+    /// session.
+    async fn transfer(&self, mime_type: &str, serial: u32) -> Result<()> {
+        let fd = self.proxy.selection_write(&self.session, serial).await?;
+        let transferred = async {
+            let (input, _) = self
+                .clipboard
+                .read_future(&[mime_type], glib::Priority::DEFAULT)
+                .await?;
+            // SAFETY: the file descriptor is owned by the stream from now on.
+            let output = unsafe { gio::UnixOutputStream::take_fd(OwnedFd::from(fd)) };
+            output
+                .splice_future(
+                    &input,
+                    gio::OutputStreamSpliceFlags::CLOSE_SOURCE
+                        | gio::OutputStreamSpliceFlags::CLOSE_TARGET,
+                    glib::Priority::DEFAULT,
+                )
+                .await?;
+            Ok::<_, glib::Error>(())
+        }
+        .await;
+        self.proxy
+            .selection_write_done(&self.session, serial, transferred.is_ok())
+            .await?;
+        transferred.map_err(From::from)
+    }
+}
+
+/// Keeps a [`gdk::Clipboard`] in sync with the clipboard of a remote desktop
+/// session.
+///
+/// The content copied in the remote session is offered on the local
+/// clipboard, and the content copied locally is offered to the remote
+/// session. The data itself is only transferred when it is pasted.
+///
+/// The clipboard of the session has to be enabled with
+/// [`Clipboard::request`] before the session is started. The bridge runs on
+/// the default [`glib::MainContext`] until it is dropped.
+///
+/// ```rust,ignore
+/// use ashpd::desktop::clipboard::{Clipboard, ClipboardBridge};
+///
+/// let clipboard = Clipboard::new().await?;
+/// clipboard.request(&session).await?;
+/// remote_desktop.start(&session, None).await?.response()?;
+/// let bridge = ClipboardBridge::new(&widget.clipboard(), &session).await?;
+/// ```
+#[cfg_attr(docsrs, doc(cfg(any(feature = "gtk4_wayland", feature = "gtk4_x11"))))]
+pub struct ClipboardBridge {
+    inner: Rc<Inner>,
+    changed_handler: Option<glib::SignalHandlerId>,
+    tasks: Vec<glib::JoinHandle<()>>,
+}
+
+impl ClipboardBridge {
+    /// Starts synchronizing `clipboard` with the clipboard of `session`.
+    pub async fn new(
+        clipboard: &gdk::Clipboard,
+        session: &Session<'_, RemoteDesktop<'_>>,
+    ) -> Result<Self> {
+        let proxy = Clipboard::new().await?;
+        let owner_changed = proxy.receive_selection_owner_changed().await?;
+        let transfers = proxy.receive_selection_transfer().await?;
+        let inner = Rc::new(Inner {
+            proxy,
+            session: Session::new(session.path().to_owned()).await?,
+            clipboard: clipboard.clone(),
+            remote_content: RefCell::default(),
+        });
+
+        let context = glib::MainContext::default();
+        let weak = Rc::downgrade(&inner);
+        let owner_changed = context.spawn_local(async move {
+            let mut owner_changed = std::pin::pin!(owner_changed);
+            while let Some((session, changed)) = owner_changed.next().await {
+                let Some(inner) = weak.upgrade() else { break };
+                if session.path() != inner.session.path()
+                    || changed.session_is_owner().unwrap_or_default()
+                {
+                    continue;
+                }
+                inner.set_remote_content(changed.mime_types());
+            }
+        });
+        let weak = Rc::downgrade(&inner);
+        let transfers = context.spawn_local(async move {
+            let mut transfers = std::pin::pin!(transfers);
+            while let Some((session, mime_type, serial)) = transfers.next().await {
+                let Some(inner) = weak.upgrade() else { break };
+                if session.path() != inner.session.path() {
+                    continue;
+                }
+                glib::MainContext::default().spawn_local(async move {
+                    if let Err(_err) = inner.transfer(&mime_type, serial).await {
+                        #[cfg(feature = "tracing")]
+                        tracing::warn!("Failed to transfer the clipboard content: {_err}");
+                    }
+                });
+            }
+        });
+
+        let weak = Rc::downgrade(&inner);
+        let changed_handler = clipboard.connect_changed(move |clipboard| {
+            let Some(inner) = weak.upgrade() else { return };
+            let content = clipboard.content();
+            let remote_content = inner.remote_content.borrow().clone();
+            if content.is_some() && content == remote_content.map(|c| c.upcast()) {
+                return;
+            }
+            inner.remote_content.replace(None);
+            glib::MainContext::default().spawn_local(async move {
+                if let Err(_err) = inner.offer_local_content().await {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!("Failed to offer the clipboard content: {_err}");
+                }
+            });
+        });
+
+        Ok(Self {
+            inner,
+            changed_handler: Some(changed_handler),
+            tasks: vec![owner_changed, transfers],
+        })
+    }
+}
+
+impl std::fmt::Debug for ClipboardBridge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ClipboardBridge")
+            .field("session", &self.inner.session)
+            .finish()
+    }
+}
+
+impl Drop for ClipboardBridge {
+    fn drop(&mut self) {
+        for task in self.tasks.drain(..) {
+            task.abort();
+        }
+        if let Some(handler) = self.changed_handler.take() {
+            self.inner.clipboard.disconnect(handler);
+        }
+    }
+}
+
+mod imp {
+    use super::*;
+
+    #[derive(Default)]
+    pub struct RemoteContent {
+        pub(super) mime_types: RefCell<Vec<String>>,
+        pub(super) bridge: RefCell<Weak<Inner>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for RemoteContent {
+        const NAME: &'static str = "AshpdRemoteClipboardContent";
+        type Type = super::RemoteContent;
+        type ParentType = gdk::ContentProvider;
+    }
+
+    impl ObjectImpl for RemoteContent {}
+
+    impl ContentProviderImpl for RemoteContent {
+        fn formats(&self) -> gdk::ContentFormats {
+            let mime_types = self.mime_types.borrow();
+            gdk::ContentFormats::new(&mime_types.iter().map(String::as_str).collect::<Vec<_>>())
+        }
+
+        fn write_mime_type_future(
+            &self,
+            mime_type: &str,
+            stream: &gio::OutputStream,
+            io_priority: glib::Priority,
+        ) -> Pin<Box<dyn Future<Output = std::result::Result<(), glib::Error>> + 'static>> {
+            let bridge = self.bridge.borrow().upgrade();
+            let mime_type = mime_type.to_owned();
+            let stream = stream.clone();
+            Box::pin(async move {
+                let bridge = bridge.ok_or_else(|| {
+                    glib::Error::new(gio::IOErrorEnum::Closed, "The clipboard bridge was dropped")
+                })?;
+                let fd = bridge
+                    .proxy
+                    .selection_read(&bridge.session, &mime_type)
+                    .await?;
+                // SAFETY: the file descriptor is owned by the stream from now on.
+                let input = unsafe { gio::UnixInputStream::take_fd(OwnedFd::from(fd)) };
+                stream
+                    .splice_future(
+                        &input,
+                        gio::OutputStreamSpliceFlags::CLOSE_SOURCE,
+                        io_priority,
+                    )
+                    .await?;
+                Ok(())
+            })
+        }
+    }
+}
+
+glib::wrapper! {
+    /// The content of the clipboard of the remote session, read when pasted.
+    struct RemoteContent(ObjectSubclass<imp::RemoteContent>)
+        @extends gdk::ContentProvider;
+}
+
+impl RemoteContent {
+    fn new(bridge: &Rc<Inner>, mime_types: Vec<String>) -> Self {
+        let content = glib::Object::new::<Self>();
+        content.imp().mime_types.replace(mime_types);
+        content.imp().bridge.replace(Rc::downgrade(bridge));
+        content
+    }
+}
@@ -6,23 +6,30 @@
 use std::collections::HashMap;
 
 use futures_util::{Stream, StreamExt};
-use zbus::zvariant::{DeserializeDict, OwnedFd, OwnedObjectPath, SerializeDict, Type, Value};
+use zbus::zvariant::{OwnedFd, OwnedObjectPath, SerializeDict, Type, Value};
 
 use super::{remote_desktop::RemoteDesktop, Session};
 use crate::{proxy::Proxy, Result};
 
+#[cfg(any(feature = "gtk4_wayland", feature = "gtk4_x11"))]
+mod gtk4;
+
+#[cfg(any(feature = "gtk4_wayland", feature = "gtk4_x11"))]
+pub use self::gtk4::ClipboardBridge;
+
 #[derive(Debug, Type, SerializeDict)]
 #[zvariant(signature = "dict")]
 struct SetSelectionOptions<'a> {
     mime_types: &'a [&'a str],
 }
 
-#[derive(Debug, Type, DeserializeDict)]
-#[zvariant(signature = "dict")]
-/// The details of a new clipboard selection.
-pub struct SelectionOwnerChanged {
-    mime_types: Option<Vec<String>>,
-    session_is_owner: Option<bool>,
+crate::helpers::dict! {
+    #[derive(Debug)]
+    /// The details of a new clipboard selection.
+    pub struct SelectionOwnerChanged {
+        mime_types: Option<Vec<String>>,
+        session_is_owner: Option<bool>,
+    }
 }
 
 impl SelectionOwnerChanged {
@@ -125,6 +132,36 @@ impl<'a> Clipboard<'a> {
         Ok(fd)
     }
 
+    /// Reads the content of the clipboard selection as `mime_type`.
+    ///
+    /// A handy wrapper around [`Clipboard::selection_read`].
+    pub async fn read_selection(
+        &self,
+        session: &Session<'_, RemoteDesktop<'_>>,
+        mime_type: &str,
+    ) -> Result<Vec<u8>> {
+        let fd = self.selection_read(session, mime_type).await?;
+        Ok(crate::io::read_to_end(fd).await?)
+    }
+
+    /// Answers a [`SelectionTransfer`][Self::receive_selection_transfer]
+    /// with `data`.
+    ///
+    /// A handy wrapper around [`Clipboard::selection_write`] and
+    /// [`Clipboard::selection_write_done`].
+    pub async fn write_selection(
+        &self,
+        session: &Session<'_, RemoteDesktop<'_>>,
+        serial: u32,
+        data: &[u8],
+    ) -> Result<()> {
+        let fd = self.selection_write(session, serial).await?;
+        let written = crate::io::write_all(fd, data).await;
+        self.selection_write_done(session, serial, written.is_ok())
+            .await?;
+        Ok(written?)
+    }
+
     /// Notifies the session that the clipboard selection has changed.
     /// # Specifications
     ///
@@ -167,3 +204,26 @@ impl<'a> std::ops::Deref for Clipboard<'a> {
         &self.0
     }
 }
+
+impl crate::Versioned for Clipboard<'_> {
+    fn version(&self) -> u32 {
+        self.0.version()
+    }
+}
+
+impl crate::Timeout for Clipboard<'_> {
+    fn set_timeout(&mut self, timeout: impl Into<Option<std::time::Duration>>) {
+        self.0.set_timeout(timeout.into());
+    }
+}
+
+#[cfg(test)]
+pub(crate) const SIGNATURES: &[crate::proxy::WrapperSignature] = crate::proxy::wrapper_signatures! {
+    "org.freedesktop.portal.Clipboard" {
+        "RequestClipboard": (crate::desktop::Session<'_, RemoteDesktop<'_>>, std::collections::HashMap<&str, zbus::zvariant::Value<'_>>) => (),
+        "SetSelection": (crate::desktop::Session<'_, RemoteDesktop<'_>>, SetSelectionOptions<'_>) => (),
+        "SelectionWrite": (crate::desktop::Session<'_, RemoteDesktop<'_>>, u32) => zbus::zvariant::OwnedFd,
+        "SelectionWriteDone": (crate::desktop::Session<'_, RemoteDesktop<'_>>, u32, bool) => (),
+        "SelectionRead": (crate::desktop::Session<'_, RemoteDesktop<'_>>, &str) => zbus::zvariant::OwnedFd,
+    }
+};
@@ -1,21 +1,15 @@
-use crate::zvariant::{self, DeserializeDict, SerializeDict, Type};
-
-#[derive(
-    SerializeDict,
-    DeserializeDict,
-    Clone,
-    Copy,
-    PartialEq,
-    Type,
-    zvariant::Value,
-    zvariant::OwnedValue,
-)]
-/// A color as a RGB tuple.
-///
-/// **Note** the values are normalized in the [0.0, 1.0] range.
-#[zvariant(signature = "dict")]
-pub struct Color {
-    color: (f64, f64, f64),
+use crate::{zvariant, Error};
+
+crate::helpers::dict! {
+    #[derive(Clone, Copy, PartialEq, zvariant::Value, zvariant::OwnedValue)]
+    #[cfg_attr(feature = "glib", derive(glib::Boxed))]
+    #[cfg_attr(feature = "glib", boxed_type(name = "AshpdColor"))]
+    /// A color as a RGB tuple.
+    ///
+    /// **Note** the values are normalized in the [0.0, 1.0] range.
+    pub struct Color {
+        color: (f64, f64, f64),
+    }
 }
 
 impl From<(f64, f64, f64)> for Color {
@@ -46,6 +40,118 @@ impl Color {
     pub fn blue(&self) -> f64 {
         self.color.2
     }
+
+    /// Parses a color in the hexadecimal notation, `#rrggbb` or `#rgb`, with
+    /// or without the leading `#`.
+    ///
+    /// ```rust
+    /// use ashpd::desktop::Color;
+    ///
+    /// let color = Color::from_hex("#3584e4").unwrap();
+    /// assert_eq!(<[u8; 3]>::from(color), [0x35, 0x84, 0xe4]);
+    /// assert_eq!(color.to_hex(), "#3584e4");
+    /// assert_eq!(color.to_css(), "rgb(53, 132, 228)");
+    /// ```
+    pub fn from_hex(hex: &str) -> Result<Self, Error> {
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        // `from_str_radix` accepts a leading sign, only digits are valid.
+        if !digits.bytes().all(|digit| digit.is_ascii_hexdigit()) {
+            return Err(Error::ParseError("Invalid hexadecimal color"));
+        }
+        let channel = |digits: &str| {
+            u8::from_str_radix(digits, 16)
+                .map_err(|_| Error::ParseError("Invalid hexadecimal color"))
+        };
+        let rgb = match digits.len() {
+            3 => [
+                channel(&digits[0..1])? * 0x11,
+                channel(&digits[1..2])? * 0x11,
+                channel(&digits[2..3])? * 0x11,
+            ],
+            6 => [
+                channel(&digits[0..2])?,
+                channel(&digits[2..4])?,
+                channel(&digits[4..6])?,
+            ],
+            _ => return Err(Error::ParseError("Invalid hexadecimal color")),
+        };
+        Ok(Self::from(rgb))
+    }
+
+    /// The color in the hexadecimal notation, `#rrggbb`.
+    pub fn to_hex(&self) -> String {
+        let [red, green, blue] = <[u8; 3]>::from(*self);
+        format!("#{red:02x}{green:02x}{blue:02x}")
+    }
+
+    /// The color in the CSS `rgb()` notation, `rgb(53, 132, 228)`.
+    pub fn to_css(&self) -> String {
+        let [red, green, blue] = <[u8; 3]>::from(*self);
+        format!("rgb({red}, {green}, {blue})")
+    }
+
+    /// Converts the color, sRGB encoded like the ones sent by the portals, to
+    /// linear RGB, for blending or lighting computations.
+    pub fn to_linear(&self) -> Self {
+        let linear = |c: f64| {
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        Self::new(
+            linear(self.red()),
+            linear(self.green()),
+            linear(self.blue()),
+        )
+    }
+
+    /// Converts the color from linear RGB back to sRGB, see
+    /// [`Color::to_linear`].
+    pub fn to_srgb(&self) -> Self {
+        let srgb = |c: f64| {
+            if c <= 0.0031308 {
+                c * 12.92
+            } else {
+                1.055 * c.powf(1.0 / 2.4) - 0.055
+            }
+        };
+        Self::new(srgb(self.red()), srgb(self.green()), srgb(self.blue()))
+    }
+}
+
+fn to_u8(channel: f64) -> u8 {
+    (channel.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+impl From<[u8; 3]> for Color {
+    fn from([red, green, blue]: [u8; 3]) -> Self {
+        Self::new(
+            f64::from(red) / 255.0,
+            f64::from(green) / 255.0,
+            f64::from(blue) / 255.0,
+        )
+    }
+}
+
+impl From<Color> for [u8; 3] {
+    /// The channels are clamped to the [0.0, 1.0] range, then scaled.
+    fn from(color: Color) -> Self {
+        [
+            to_u8(color.red()),
+            to_u8(color.green()),
+            to_u8(color.blue()),
+        ]
+    }
+}
+
+impl From<Color> for [u8; 4] {
+    /// Same as the `[u8; 3]` conversion, with an opaque alpha channel.
+    fn from(color: Color) -> Self {
+        let [red, green, blue] = <[u8; 3]>::from(color);
+        [red, green, blue, u8::MAX]
+    }
 }
 
 #[cfg(feature = "gtk4")]
@@ -79,3 +185,45 @@ impl std::fmt::Display for Color {
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Color;
+
+    #[test]
+    fn hex() {
+        let color = Color::from_hex("3584E4").unwrap();
+        assert_eq!(color.to_hex(), "#3584e4");
+        assert_eq!(Color::from_hex("#fa0").unwrap().to_hex(), "#ffaa00");
+        assert_eq!(<[u8; 4]>::from(color), [0x35, 0x84, 0xe4, 0xff]);
+
+        assert!(Color::from_hex("").is_err());
+        assert!(Color::from_hex("#12345").is_err());
+        assert!(Color::from_hex("#12345g").is_err());
+        assert!(Color::from_hex("#1é345").is_err());
+        assert!(Color::from_hex("#+f+f+f").is_err());
+        assert!(Color::from_hex("+ff").is_err());
+    }
+
+    #[test]
+    fn conversions() {
+        // The portals may send values slightly out of range.
+        let color = Color::new(1.2, -0.1, 0.5);
+        assert_eq!(<[u8; 3]>::from(color), [255, 0, 128]);
+        assert_eq!(color.to_css(), "rgb(255, 0, 128)");
+
+        let color = Color::from([0x35, 0x84, 0xe4]);
+        let linear = color.to_linear();
+        assert!((linear.red() - 0.0356).abs() < 1e-3);
+        assert!((linear.blue() - 0.7758).abs() < 1e-3);
+        assert_eq!(linear.to_srgb().to_hex(), "#3584e4");
+        assert_eq!(
+            Color::new(0.0, 0.0, 0.0).to_linear(),
+            Color::new(0.0, 0.0, 0.0)
+        );
+        assert_eq!(
+            Color::new(1.0, 1.0, 1.0).to_linear(),
+            Color::new(1.0, 1.0, 1.0)
+        );
+    }
+}
@@ -0,0 +1,512 @@
+//! Inspect and update the permissions applications have to access devices
+//! such as the camera, the microphone or the speakers.
+//!
+//! The decisions made by the user when the [`Camera`] portal asks for access
+//! are stored in the `devices` table of the permission store. This module
+//! offers a typed wrapper around that table, replacing the deprecated
+//! [`device`](crate::desktop::device) portal.
+//!
+//! **Note** The permission store is not accessible to sandboxed applications.
+//!
+//! ### Examples
+//!
+//! ```rust,no_run
+//! use std::str::FromStr;
+//!
+//! use ashpd::{
+//!     desktop::device_access::{Device, DeviceAccess, DevicePermission},
+//!     AppID,
+//! };
+//!
+//! async fn run() -> ashpd::Result<()> {
+//!     let proxy = DeviceAccess::new().await?;
+//!     let app_id = AppID::from_str("org.gnome.Snapshot").unwrap();
+//!
+//!     println!("{:#?}", proxy.permission(Device::Camera, &app_id).await?);
+//!     proxy
+//!         .set_permission(Device::Camera, &app_id, DevicePermission::Denied)
+//!         .await?;
+//!     Ok(())
+//! }
+//! ```
+//!
+//! [`Camera`]: crate::desktop::camera::Camera
+
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use futures_util::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use zbus::zvariant::{OwnedValue, Type};
+
+use crate::{proxy::Proxy, AppID, Error};
+
+const DEVICES_TABLE: &str = "devices";
+const USB_TABLE: &str = "usb";
+const USB_ID: &str = "usb";
+
+#[cfg_attr(feature = "glib", derive(glib::Enum))]
+#[cfg_attr(feature = "glib", enum_type(name = "AshpdDeviceAccessDevice"))]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Type)]
+#[zvariant(signature = "s")]
+#[serde(rename_all = "lowercase")]
+/// A device whose access is recorded in the permission store.
+pub enum Device {
+    /// A microphone.
+    Microphone,
+    /// Speakers.
+    Speakers,
+    /// A Camera.
+    Camera,
+}
+
+impl Device {
+    fn id(&self) -> &'static str {
+        match self {
+            Self::Microphone => "microphone",
+            Self::Speakers => "speakers",
+            Self::Camera => "camera",
+        }
+    }
+}
+
+impl fmt::Display for Device {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Microphone => write!(f, "Microphone"),
+            Self::Speakers => write!(f, "Speakers"),
+            Self::Camera => write!(f, "Camera"),
+        }
+    }
+}
+
+impl FromStr for Device {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Microphone" | "microphone" => Ok(Device::Microphone),
+            "Speakers" | "speakers" => Ok(Device::Speakers),
+            "Camera" | "camera" => Ok(Device::Camera),
+            _ => Err(Error::ParseError("Failed to parse device, invalid value")),
+        }
+    }
+}
+
+#[cfg_attr(feature = "glib", derive(glib::Enum))]
+#[cfg_attr(feature = "glib", enum_type(name = "AshpdDevicePermission"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// The access an application has been given to a [`Device`].
+pub enum DevicePermission {
+    /// The application is allowed to access the device.
+    Granted,
+    /// The application is not allowed to access the device.
+    Denied,
+}
+
+impl DevicePermission {
+    fn from_store(permissions: &[String]) -> Option<Self> {
+        match permissions.first().map(String::as_str) {
+            Some("yes") => Some(Self::Granted),
+            Some("no") => Some(Self::Denied),
+            _ => None,
+        }
+    }
+
+    fn as_store(&self) -> &'static str {
+        match self {
+            Self::Granted => "yes",
+            Self::Denied => "no",
+        }
+    }
+}
+
+impl fmt::Display for DevicePermission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Granted => write!(f, "Granted"),
+            Self::Denied => write!(f, "Denied"),
+        }
+    }
+}
+
+/// The USB devices an application wants to access, see
+/// [`DeviceAccess::usb_permission`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct UsbDeviceFilter {
+    vendor_id: u16,
+    product_id: Option<u16>,
+}
+
+impl UsbDeviceFilter {
+    /// Matches the devices of the vendor `vendor_id`.
+    pub fn new(vendor_id: u16) -> Self {
+        Self {
+            vendor_id,
+            product_id: None,
+        }
+    }
+
+    /// Only matches the devices with the product ID `product_id`.
+    #[must_use]
+    pub fn product_id(mut self, product_id: impl Into<Option<u16>>) -> Self {
+        self.product_id = product_id.into();
+        self
+    }
+
+    /// Whether the udev properties of a device match the filter.
+    fn matches(&self, properties: &HashMap<String, String>) -> bool {
+        let matches = |key: &str, id: u16| {
+            properties
+                .get(key)
+                .and_then(|value| u16::from_str_radix(value, 16).ok())
+                == Some(id)
+        };
+        matches("ID_VENDOR_ID", self.vendor_id)
+            && self
+                .product_id
+                .map_or(true, |product_id| matches("ID_MODEL_ID", product_id))
+    }
+}
+
+/// A device recorded in the data of the `usb` entry: its permission, its ID
+/// and its udev properties.
+type UsbDeviceEntry = (String, String, HashMap<String, String>);
+
+/// The permission recorded for `app_id` for the first device of `data`
+/// matching `filter`.
+fn usb_device_permission(
+    data: &OwnedValue,
+    app_id: &AppID,
+    filter: &UsbDeviceFilter,
+) -> Option<UsbPermission> {
+    let apps = HashMap::<String, OwnedValue>::try_from(data.try_clone().ok()?).ok()?;
+    let devices =
+        Vec::<UsbDeviceEntry>::try_from(apps.get(app_id.as_ref())?.try_clone().ok()?).ok()?;
+    devices
+        .iter()
+        .find(|(_, _, properties)| filter.matches(properties))
+        .and_then(|(permission, _, _)| UsbPermission::from_store(std::slice::from_ref(permission)))
+}
+
+#[cfg_attr(feature = "glib", derive(glib::Enum))]
+#[cfg_attr(feature = "glib", enum_type(name = "AshpdUsbPermission"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// The access an application has been given to USB devices.
+pub enum UsbPermission {
+    /// The application is allowed to access the devices.
+    Granted,
+    /// The user will be asked when the application accesses the devices.
+    Ask,
+    /// The application is not allowed to access the devices.
+    Denied,
+}
+
+impl UsbPermission {
+    fn from_store(permissions: &[String]) -> Option<Self> {
+        match permissions.first().map(String::as_str) {
+            Some("yes") => Some(Self::Granted),
+            Some("ask") => Some(Self::Ask),
+            Some("no") => Some(Self::Denied),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for UsbPermission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Granted => write!(f, "Granted"),
+            Self::Ask => write!(f, "Ask"),
+            Self::Denied => write!(f, "Denied"),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Type)]
+/// A change of the devices permissions, see
+/// [`DeviceAccess::receive_changed`].
+pub struct DevicePermissionsChanged {
+    table: String,
+    id: String,
+    deleted: bool,
+    #[allow(dead_code)]
+    data: OwnedValue,
+    permissions: HashMap<String, Vec<String>>,
+}
+
+impl DevicePermissionsChanged {
+    /// The device whose permissions changed, if known.
+    pub fn device(&self) -> Option<Device> {
+        self.id.parse().ok()
+    }
+
+    /// Whether the entry of the device was removed from the store.
+    pub fn is_deleted(&self) -> bool {
+        self.deleted
+    }
+
+    /// The new permissions of every application for this device.
+    pub fn permissions(&self) -> HashMap<AppID, DevicePermission> {
+        parse_permissions(&self.permissions)
+    }
+}
+
+fn parse_permissions(
+    permissions: &HashMap<String, Vec<String>>,
+) -> HashMap<AppID, DevicePermission> {
+    permissions
+        .iter()
+        .filter_map(|(app_id, permissions)| {
+            let app_id = app_id.parse::<AppID>().ok()?;
+            let permission = DevicePermission::from_store(permissions)?;
+            Some((app_id, permission))
+        })
+        .collect()
+}
+
+/// A wrapper around the `devices` table of the permission store.
+///
+/// Wrapper of the DBus interface: `org.freedesktop.impl.portal.PermissionStore`.
+#[derive(Debug)]
+#[doc(alias = "org.freedesktop.impl.portal.PermissionStore")]
+pub struct DeviceAccess<'a>(Proxy<'a>);
+
+impl<'a> DeviceAccess<'a> {
+    /// Create a new instance of [`DeviceAccess`].
+    pub async fn new() -> Result<DeviceAccess<'a>, Error> {
+        let proxy =
+            Proxy::new_permission_store("org.freedesktop.impl.portal.PermissionStore").await?;
+        Ok(Self(proxy))
+    }
+
+    /// The permission `app_id` has for `device`, or [`None`] if the user was
+    /// never asked.
+    #[doc(alias = "GetPermission")]
+    pub async fn permission(
+        &self,
+        device: Device,
+        app_id: &AppID,
+    ) -> Result<Option<DevicePermission>, Error> {
+        let permissions = self
+            .0
+            .call::<Vec<String>>("GetPermission", &(DEVICES_TABLE, device.id(), app_id))
+            .await;
+        match permissions {
+            Ok(permissions) => Ok(DevicePermission::from_store(&permissions)),
+            // The store fails if the device has no entry yet.
+            Err(Error::Portal(crate::PortalError::NotFound(_))) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// The permissions of every application that has been asked for
+    /// `device`.
+    #[doc(alias = "Lookup")]
+    pub async fn permissions(
+        &self,
+        device: Device,
+    ) -> Result<HashMap<AppID, DevicePermission>, Error> {
+        let response = self
+            .0
+            .call::<(HashMap<String, Vec<String>>, OwnedValue)>(
+                "Lookup",
+                &(DEVICES_TABLE, device.id()),
+            )
+            .await;
+        match response {
+            Ok((permissions, _)) => Ok(parse_permissions(&permissions)),
+            Err(Error::Portal(crate::PortalError::NotFound(_))) => Ok(HashMap::new()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// The permission `app_id` has for the USB devices matching `filter`,
+    /// recorded in the `usb` entry of the `usb` table of the permission store.
+    ///
+    /// The permission of the first device of the entry data matching the
+    /// filter takes precedence over the permission of the application for
+    /// the whole portal. Defaults to [`UsbPermission::Ask`] if the user was
+    /// never asked.
+    ///
+    /// Uses the application ID set with [`set_app_id`][crate::set_app_id] if
+    /// `app_id` is [`None`].
+    ///
+    /// **Note** Only available on the host, to portal backends or settings
+    /// applications for example, sandboxed applications can't reach the
+    /// permission store.
+    pub async fn usb_permission(
+        &self,
+        app_id: Option<&AppID>,
+        filter: UsbDeviceFilter,
+    ) -> Result<UsbPermission, Error> {
+        let app_id = app_id.or(crate::app_id()).ok_or_else(|| {
+            Error::InvalidArgument("No application ID to look the permissions up".to_owned())
+        })?;
+        let response = self
+            .0
+            .call::<(HashMap<String, Vec<String>>, OwnedValue)>("Lookup", &(USB_TABLE, USB_ID))
+            .await;
+        let (permissions, data) = match response {
+            Ok(response) => response,
+            Err(Error::Portal(crate::PortalError::NotFound(_))) => return Ok(UsbPermission::Ask),
+            Err(err) => return Err(err),
+        };
+        Ok(usb_device_permission(&data, app_id, &filter)
+            .or_else(|| UsbPermission::from_store(permissions.get(app_id.as_ref())?))
+            .unwrap_or(UsbPermission::Ask))
+    }
+
+    /// Grant or deny `app_id` access to `device`.
+    #[doc(alias = "SetPermission")]
+    pub async fn set_permission(
+        &self,
+        device: Device,
+        app_id: &AppID,
+        permission: DevicePermission,
+    ) -> Result<(), Error> {
+        self.0
+            .call(
+                "SetPermission",
+                &(
+                    DEVICES_TABLE,
+                    true,
+                    device.id(),
+                    app_id,
+                    &[permission.as_store()],
+                ),
+            )
+            .await
+    }
+
+    /// Forget the decision made for `app_id`, the user will be asked again
+    /// the next time the application requests access to `device`.
+    #[doc(alias = "DeletePermission")]
+    pub async fn reset_permission(&self, device: Device, app_id: &AppID) -> Result<(), Error> {
+        self.0
+            .call("DeletePermission", &(DEVICES_TABLE, device.id(), app_id))
+            .await
+    }
+
+    /// Signal emitted when the devices permissions change.
+    #[doc(alias = "Changed")]
+    pub async fn receive_changed(
+        &self,
+    ) -> Result<impl Stream<Item = DevicePermissionsChanged>, Error> {
+        Ok(self
+            .0
+            .signal_with_args::<DevicePermissionsChanged>("Changed", &[(0, DEVICES_TABLE)])
+            .await?
+            .filter(|change| std::future::ready(change.table == DEVICES_TABLE)))
+    }
+}
+
+impl<'a> std::ops::Deref for DeviceAccess<'a> {
+    type Target = zbus::Proxy<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl crate::Versioned for DeviceAccess<'_> {
+    fn version(&self) -> u32 {
+        self.0.version()
+    }
+}
+
+impl crate::Timeout for DeviceAccess<'_> {
+    fn set_timeout(&mut self, timeout: impl Into<Option<std::time::Duration>>) {
+        self.0.set_timeout(timeout.into());
+    }
+}
+
+#[cfg(all(test, feature = "test"))]
+mod tests {
+    use std::{collections::HashMap, str::FromStr};
+
+    use zbus::zvariant::Value;
+
+    use super::{DeviceAccess, UsbDeviceFilter, UsbPermission};
+    use crate::{
+        test::{MockPortal, MockResponse},
+        AppID,
+    };
+
+    const INTERFACE: &str = "org.freedesktop.impl.portal.PermissionStore";
+
+    #[tokio::test]
+    async fn usb_permission() {
+        let _lock = crate::test::CONNECTION_LOCK.lock().await;
+        let mock = MockPortal::new().await.unwrap();
+        let device = |permission: &str, vendor_id: &str, model_id: &str| {
+            (
+                permission.to_owned(),
+                format!("/sys/bus/usb/devices/{vendor_id}:{model_id}"),
+                HashMap::from([
+                    ("ID_VENDOR_ID".to_owned(), vendor_id.to_owned()),
+                    ("ID_MODEL_ID".to_owned(), model_id.to_owned()),
+                ]),
+            )
+        };
+        let data = HashMap::from([(
+            "org.example.App",
+            Value::from(vec![
+                device("no", "1d6b", "0002"),
+                device("yes", "046d", "c52b"),
+            ]),
+        )]);
+        let lookup = (
+            HashMap::from([("org.example.App", vec!["ask"])]),
+            Value::from(data),
+        );
+        for _ in 0..3 {
+            mock.respond(INTERFACE, "Lookup", MockResponse::reply(lookup.clone()));
+        }
+        mock.respond(
+            INTERFACE,
+            "Lookup",
+            MockResponse::error("org.freedesktop.portal.Error.NotFound", ""),
+        );
+
+        let app_id = AppID::from_str("org.example.App").unwrap();
+        let proxy = DeviceAccess::new().await.unwrap();
+        let permission = |filter| proxy.usb_permission(Some(&app_id), filter);
+        assert_eq!(
+            permission(UsbDeviceFilter::new(0x1d6b).product_id(0x0002))
+                .await
+                .unwrap(),
+            UsbPermission::Denied
+        );
+        assert_eq!(
+            permission(UsbDeviceFilter::new(0x046d)).await.unwrap(),
+            UsbPermission::Granted
+        );
+        // Falls back to the permission of the application.
+        assert_eq!(
+            permission(UsbDeviceFilter::new(0x1d6b).product_id(0x0003))
+                .await
+                .unwrap(),
+            UsbPermission::Ask
+        );
+        let call = &mock.calls()[0];
+        assert_eq!(
+            call.body().deserialize::<(String, String)>().unwrap(),
+            ("usb".to_owned(), "usb".to_owned())
+        );
+
+        assert_eq!(
+            permission(UsbDeviceFilter::new(0x1d6b)).await.unwrap(),
+            UsbPermission::Ask
+        );
+    }
+}
+
+#[cfg(test)]
+pub(crate) const SIGNATURES: &[crate::proxy::WrapperSignature] = crate::proxy::wrapper_signatures! {
+    "org.freedesktop.impl.portal.PermissionStore" {
+        "GetPermission": (&str, &str, &AppID) => Vec<String>,
+        "Lookup": (&str, &str) => (HashMap<String, Vec<String>>, OwnedValue),
+        "SetPermission": (&str, bool, &str, &AppID, &[&str]) => (),
+        "DeletePermission": (&str, &str, &AppID) => (),
+    }
+};
@@ -3,7 +3,6 @@
 //! # Examples
 //!
 //! ```rust,no_run
-//! use std::io::Read;
 //! use ashpd::{
 //!     desktop::{
 //!         dynamic_launcher::{DynamicLauncherProxy, PrepareInstallOptions},
@@ -16,12 +15,7 @@
 //!     let proxy = DynamicLauncherProxy::new().await?;
 //!
 //!     let filename = "/home/bilalelmoussaoui/Projects/ashpd/ashpd-demo/data/icons/com.belmoussaoui.ashpd.demo.svg";
-//!     let mut f = std::fs::File::open(&filename).expect("no file found");
-//!     let metadata = std::fs::metadata(&filename).expect("unable to read metadata");
-//!     let mut buffer = vec![0; metadata.len() as usize];
-//!     f.read(&mut buffer).expect("buffer overflow");
-//!
-//!     let icon = Icon::Bytes(buffer);
+//!     let icon = Icon::from_path(filename).await?;
 //!     let response = proxy
 //!         .prepare_install(
 //!             None,
@@ -55,7 +49,7 @@ use std::collections::HashMap;
 use enumflags2::{bitflags, BitFlags};
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
-use zbus::zvariant::{self, DeserializeDict, OwnedValue, SerializeDict, Type, Value};
+use zbus::zvariant::{self, OwnedValue, SerializeDict, Type, Value};
 
 use super::{HandleToken, Icon, Request};
 use crate::{proxy::Proxy, ActivationToken, Error, WindowIdentifier};
@@ -157,13 +151,13 @@ impl PrepareInstallOptions {
     }
 }
 
-#[derive(DeserializeDict, Type)]
-#[zvariant(signature = "dict")]
-/// A response of [`DynamicLauncherProxy::prepare_install`]
-pub struct PrepareInstallResponse {
-    name: String,
-    icon: OwnedValue,
-    token: String,
+crate::helpers::dict! {
+    /// A response of [`DynamicLauncherProxy::prepare_install`]
+    pub struct PrepareInstallResponse {
+        name: String,
+        icon: OwnedValue,
+        token: String,
+    }
 }
 
 impl PrepareInstallResponse {
@@ -239,6 +233,15 @@ impl<'a> DynamicLauncherProxy<'a> {
         Ok(Self(proxy))
     }
 
+    /// The ID of the desktop file of the launcher `name`, prefixed by the
+    /// application ID set with [`set_app_id`][crate::set_app_id] as required
+    /// by [`install`][Self::install].
+    ///
+    /// Returns [`None`] if no application ID was set.
+    pub fn desktop_file_id(name: &str) -> Option<String> {
+        crate::app_id().map(|app_id| format!("{app_id}.{name}.desktop"))
+    }
+
     /// *Note* Only `Icon::Bytes` is accepted.
     ///
     ///  # Specifications
@@ -335,6 +338,30 @@ impl<'a> DynamicLauncherProxy<'a> {
         self.0.call("GetIcon", &(desktop_file_id)).await
     }
 
+    /// Launches the installed launcher `desktop_file_id`.
+    ///
+    /// The launcher is focused with the activation token of `options`, if
+    /// any. The portal doesn't forward URIs to launchers, they are started
+    /// with the `Exec` line of their desktop entry.
+    ///
+    /// ```rust,no_run
+    /// use ashpd::{
+    ///     desktop::dynamic_launcher::{DynamicLauncherProxy, LaunchOptions},
+    ///     ActivationToken,
+    /// };
+    ///
+    /// async fn run(token: ActivationToken) -> ashpd::Result<()> {
+    ///     let proxy = DynamicLauncherProxy::new().await?;
+    ///     let desktop_file_id = DynamicLauncherProxy::desktop_file_id("game").unwrap();
+    ///     proxy
+    ///         .launch(
+    ///             &desktop_file_id,
+    ///             LaunchOptions::default().activation_token(token),
+    ///         )
+    ///         .await
+    /// }
+    /// ```
+    ///
     /// # Specifications
     ///
     /// See also [`Launch`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.DynamicLauncher.html#org-freedesktop-portal-dynamiclauncher-launch).
@@ -363,6 +390,31 @@ impl<'a> std::ops::Deref for DynamicLauncherProxy<'a> {
     }
 }
 
+impl crate::Versioned for DynamicLauncherProxy<'_> {
+    fn version(&self) -> u32 {
+        self.0.version()
+    }
+}
+
+impl crate::Timeout for DynamicLauncherProxy<'_> {
+    fn set_timeout(&mut self, timeout: impl Into<Option<std::time::Duration>>) {
+        self.0.set_timeout(timeout.into());
+    }
+}
+
+#[cfg(test)]
+pub(crate) const SIGNATURES: &[crate::proxy::WrapperSignature] = crate::proxy::wrapper_signatures! {
+    "org.freedesktop.portal.DynamicLauncher" {
+        "PrepareInstall": (String, &str, super::icon::IconValue<'_>, &PrepareInstallOptions) => zbus::zvariant::OwnedObjectPath,
+        "RequestInstallToken": (&str, super::icon::IconValue<'_>, HashMap<&str, Value<'_>>) => String,
+        "Install": (&str, &str, &str, HashMap<&str, Value<'_>>) => (),
+        "Uninstall": (&str, HashMap<&str, Value<'_>>) => (),
+        "GetDesktopEntry": &str => String,
+        "GetIcon": &str => LauncherIcon,
+        "Launch": (&str, &LaunchOptions) => (),
+    }
+};
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -374,4 +426,39 @@ mod test {
         let icon = vec![IconType::Png];
         assert_eq!(serde_json::to_string(&icon).unwrap(), "[\"png\"]");
     }
+
+    #[cfg(feature = "test")]
+    #[tokio::test]
+    async fn launch() {
+        use crate::test::{MockPortal, MockResponse};
+
+        let _lock = crate::test::CONNECTION_LOCK.lock().await;
+        let mock = MockPortal::new().await.unwrap();
+        mock.respond(
+            "org.freedesktop.portal.DynamicLauncher",
+            "Launch",
+            MockResponse::empty(),
+        );
+
+        let proxy = DynamicLauncherProxy::new().await.unwrap();
+        let token = ActivationToken::from_raw(String::from("wayland-token"));
+        proxy
+            .launch(
+                "org.example.App.game.desktop",
+                LaunchOptions::default().activation_token(token),
+            )
+            .await
+            .unwrap();
+
+        let calls = mock.calls();
+        let (desktop_file_id, options) = calls[0]
+            .body()
+            .deserialize::<(String, HashMap<String, OwnedValue>)>()
+            .unwrap();
+        assert_eq!(desktop_file_id, "org.example.App.game.desktop");
+        assert_eq!(
+            String::try_from(options["activation_token"].clone()).unwrap(),
+            "wayland-token"
+        );
+    }
 }
@@ -24,13 +24,16 @@
 //! }
 //! ```
 
-use std::os::fd::OwnedFd;
+use std::{os::fd::OwnedFd, time::Duration};
 
 use serde::Serialize;
 use zbus::zvariant::{self, SerializeDict, Type};
 
 use super::{HandleToken, Request};
-use crate::{proxy::Proxy, ActivationToken, Error, WindowIdentifier};
+use crate::{
+    proxy::Proxy, uri::percent_encode, ActivationToken, CancellationToken, Error, Feature,
+    WindowIdentifier,
+};
 
 #[derive(SerializeDict, Type, Debug, Default)]
 #[zvariant(signature = "dict")]
@@ -46,11 +49,24 @@ struct EmailOptions {
     activation_token: Option<ActivationToken>,
 }
 
+/// The interface lets sandboxed applications request sending an email.
+///
+/// The requests are sent with the builders, like [`EmailRequest`], the proxy
+/// gives access to the version of the interface.
+///
+/// Wrapper of the DBus interface: [`org.freedesktop.portal.Email`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Email.html).
 #[derive(Debug)]
 #[doc(alias = "org.freedesktop.portal.Email")]
-struct EmailProxy<'a>(Proxy<'a>);
+pub struct EmailProxy<'a>(Proxy<'a>);
 
 impl<'a> EmailProxy<'a> {
+    /// The [`EmailRequest::addresses`], [`EmailRequest::cc`] and
+    /// [`EmailRequest::bcc`] options.
+    pub const ADDRESSES: Feature = Feature::since(3);
+
+    /// The [`EmailRequest::activation_token`] option.
+    pub const ACTIVATION_TOKEN: Feature = Feature::since(4);
+
     /// Create a new instance of [`EmailProxy`].
     pub async fn new() -> Result<EmailProxy<'a>, Error> {
         let proxy = Proxy::new_desktop("org.freedesktop.portal.Email").await?;
@@ -71,17 +87,17 @@ impl<'a> EmailProxy<'a> {
     ///
     /// See also [`ComposeEmail`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Email.html#org-freedesktop-portal-email-composeemail).
     #[doc(alias = "ComposeEmail")]
-    pub async fn compose(
+    async fn compose(
         &self,
         identifier: Option<&WindowIdentifier>,
-        options: EmailOptions,
+        options: &EmailOptions,
     ) -> Result<Request<()>, Error> {
         let identifier = identifier.map(|i| i.to_string()).unwrap_or_default();
         self.0
             .empty_request(
                 &options.handle_token,
                 "ComposeEmail",
-                &(&identifier, &options),
+                &(&identifier, options),
             )
             .await
     }
@@ -95,6 +111,18 @@ impl<'a> std::ops::Deref for EmailProxy<'a> {
     }
 }
 
+impl crate::Versioned for EmailProxy<'_> {
+    fn version(&self) -> u32 {
+        self.0.version()
+    }
+}
+
+impl crate::Timeout for EmailProxy<'_> {
+    fn set_timeout(&mut self, timeout: impl Into<Option<std::time::Duration>>) {
+        self.0.set_timeout(timeout.into());
+    }
+}
+
 #[derive(Debug, Default)]
 #[doc(alias = "xdp_portal_compose_email")]
 /// A [builder-pattern] type to compose an email.
@@ -102,6 +130,9 @@ impl<'a> std::ops::Deref for EmailProxy<'a> {
 /// [builder-pattern]: https://doc.rust-lang.org/1.0.0/style/ownership/builders.html
 pub struct EmailRequest {
     identifier: Option<WindowIdentifier>,
+    timeout: Option<Duration>,
+    cancel_on_drop: bool,
+    cancellation: Option<CancellationToken>,
     options: EmailOptions,
 }
 
@@ -113,6 +144,30 @@ impl EmailRequest {
         self
     }
 
+    /// Sets how long to wait for the request to complete, including the user
+    /// interaction. The request is closed once the timeout expires.
+    #[must_use]
+    pub fn timeout(mut self, timeout: impl Into<Option<Duration>>) -> Self {
+        self.timeout = timeout.into();
+        self
+    }
+
+    /// Sets whether to close the request if the future returned by `send` is
+    /// dropped before the user interaction ends, dismissing the dialog.
+    #[must_use]
+    pub fn cancel_on_drop(mut self, cancel_on_drop: bool) -> Self {
+        self.cancel_on_drop = cancel_on_drop;
+        self
+    }
+
+    /// Closes the request once `token` is cancelled, the request then fails
+    /// with [`ResponseError::Cancelled`](crate::desktop::ResponseError::Cancelled).
+    #[must_use]
+    pub fn cancel_with(mut self, token: &CancellationToken) -> Self {
+        self.cancellation = Some(token.clone());
+        self
+    }
+
     /// Sets the email address to send the email to.
     #[must_use]
     pub fn address<'a>(mut self, address: impl Into<Option<&'a str>>) -> Self {
@@ -199,9 +254,137 @@ impl EmailRequest {
         };
     }
 
+    /// The `mailto:` URI, as defined by RFC 6068, composing the same email.
+    ///
+    /// The attachments can't be part of the URI and are left out.
+    pub fn mailto_uri(&self) -> Result<url::Url, Error> {
+        let options = &self.options;
+        let to = options
+            .address
+            .iter()
+            .chain(options.addresses.iter().flatten())
+            .map(|address| percent_encode(address, b"@"))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        let mut fields = Vec::new();
+        for (name, addresses) in [("cc", &options.cc), ("bcc", &options.bcc)] {
+            if let Some(addresses) = addresses.as_ref().filter(|a| !a.is_empty()) {
+                let addresses = addresses
+                    .iter()
+                    .map(|address| percent_encode(address, b"@"))
+                    .collect::<Vec<_>>();
+                fields.push(format!("{name}={}", addresses.join(",")));
+            }
+        }
+        if let Some(subject) = &options.subject {
+            fields.push(format!("subject={}", percent_encode(subject, b"")));
+        }
+        if let Some(body) = &options.body {
+            // Line breaks have to be sent as CRLF.
+            let body = body.replace("\r\n", "\n").replace('\n', "\r\n");
+            fields.push(format!("body={}", percent_encode(&body, b"")));
+        }
+
+        let mut uri = format!("mailto:{to}");
+        if !fields.is_empty() {
+            uri.push('?');
+            uri.push_str(&fields.join("&"));
+        }
+        url::Url::parse(&uri).map_err(|_| Error::ParseError("Failed to build the mailto: URI"))
+    }
+
     /// Send the request.
     pub async fn send(self) -> Result<Request<()>, Error> {
-        let proxy = EmailProxy::new().await?;
-        proxy.compose(self.identifier.as_ref(), self.options).await
+        let mut proxy = EmailProxy::new().await?;
+        proxy.0.set_timeout(self.timeout);
+        proxy.0.set_cancel_on_drop(self.cancel_on_drop);
+        proxy.0.set_cancellation(self.cancellation);
+        proxy.compose(self.identifier.as_ref(), &self.options).await
+    }
+
+    #[cfg(feature = "glib")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "glib")))]
+    /// Send the request and wait for the email to be composed.
+    ///
+    /// The email is composed from its [`mailto:` URI][`Self::mailto_uri`]
+    /// instead when no frontend provides the portal, with the OpenURI portal
+    /// or `gio::AppInfo` otherwise. The attachments can't be sent that way,
+    /// [`Error::InvalidArgument`] is returned if there are any.
+    pub async fn send_with_fallback(self) -> Result<(), Error> {
+        let request = async {
+            let mut proxy = EmailProxy::new().await?;
+            proxy.0.set_timeout(self.timeout);
+            proxy.0.set_cancel_on_drop(self.cancel_on_drop);
+            proxy.0.set_cancellation(self.cancellation.clone());
+            proxy.compose(self.identifier.as_ref(), &self.options).await
+        }
+        .await;
+        match request {
+            Ok(request) => request.response(),
+            Err(err) if err.is_portal_missing() => self.send_mailto().await,
+            Err(err) => Err(err),
+        }
+    }
+
+    #[cfg(feature = "glib")]
+    async fn send_mailto(self) -> Result<(), Error> {
+        if self
+            .options
+            .attachment_fds
+            .as_ref()
+            .is_some_and(|fds| !fds.is_empty())
+        {
+            return Err(Error::InvalidArgument(
+                "Attachments can't be sent without the Email portal".to_owned(),
+            ));
+        }
+        let uri = self.mailto_uri()?;
+        #[cfg(feature = "tracing")]
+        tracing::info!("Email portal is missing, falling back to {}", uri);
+        let request = crate::desktop::open_uri::OpenFileRequest::default()
+            .identifier(self.identifier)
+            .activation_token(self.options.activation_token)
+            .send_uri(&uri)
+            .await;
+        match request {
+            Ok(request) => request.response(),
+            Err(err) if err.is_portal_missing() => {
+                gio::AppInfo::launch_default_for_uri(uri.as_str(), None::<&gio::AppLaunchContext>)
+                    .map_err(|err| Error::IO(std::io::Error::other(err.to_string())))
+            }
+            Err(err) => Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) const SIGNATURES: &[crate::proxy::WrapperSignature] = crate::proxy::wrapper_signatures! {
+    "org.freedesktop.portal.Email" {
+        "ComposeEmail": (&str, &EmailOptions) => zbus::zvariant::OwnedObjectPath,
+    }
+};
+
+#[cfg(test)]
+mod tests {
+    use super::EmailRequest;
+
+    #[test]
+    fn mailto_uri() {
+        let uri = EmailRequest::default()
+            .address("test@example.org")
+            .addresses(["other+tag@example.org"])
+            .cc(["cc@example.org"])
+            .subject("Feedback & bugs")
+            .body("Hello,\nit works?")
+            .mailto_uri()
+            .unwrap();
+        assert_eq!(
+            uri.as_str(),
+            "mailto:test@example.org,other%2Btag@example.org?cc=cc@example.org&subject=Feedback%20%26%20bugs&body=Hello%2C%0D%0Ait%20works%3F"
+        );
+
+        let uri = EmailRequest::default().mailto_uri().unwrap();
+        assert_eq!(uri.as_str(), "mailto:");
     }
 }
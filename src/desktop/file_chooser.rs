@@ -79,14 +79,14 @@
 //! }
 //! ```
 
-use std::path::Path;
+use std::{path::Path, time::Duration};
 
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
-use zbus::zvariant::{DeserializeDict, SerializeDict, Type};
+use zbus::zvariant::{SerializeDict, Type};
 
 use super::{HandleToken, Request};
-use crate::{proxy::Proxy, Error, FilePath, WindowIdentifier};
+use crate::{proxy::Proxy, CancellationToken, Error, Feature, FilePath, WindowIdentifier};
 
 #[derive(Clone, Serialize, Deserialize, Type, Debug, PartialEq)]
 /// A file filter, to limit the available file choices to a mimetype or a glob
@@ -262,13 +262,16 @@ struct SaveFilesOptions {
     files: Option<Vec<FilePath>>,
 }
 
-#[derive(Debug, Type, DeserializeDict)]
-/// A response of [`OpenFileRequest`], [`SaveFileRequest`] or
-/// [`SaveFilesRequest`].
-#[zvariant(signature = "dict")]
-pub struct SelectedFiles {
-    uris: Vec<url::Url>,
-    choices: Option<Vec<(String, String)>>,
+crate::helpers::dict! {
+    #[derive(Clone, Debug)]
+    #[cfg_attr(feature = "glib", derive(glib::Boxed))]
+    #[cfg_attr(feature = "glib", boxed_type(name = "AshpdSelectedFiles"))]
+    /// A response of [`OpenFileRequest`], [`SaveFileRequest`] or
+    /// [`SaveFilesRequest`].
+    pub struct SelectedFiles {
+        uris: Vec<url::Url>,
+        choices: Option<Vec<(String, String)>>,
+    }
 }
 
 impl SelectedFiles {
@@ -298,33 +301,46 @@ impl SelectedFiles {
     }
 }
 
+/// The interface lets sandboxed applications ask the user for access to files
+/// outside the sandbox.
+///
+/// The requests are sent with the builders, like [`SelectedFiles::open_file`], the proxy
+/// gives access to the version of the interface.
+///
+/// Wrapper of the DBus interface: [`org.freedesktop.portal.FileChooser`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.FileChooser.html).
 #[doc(alias = "org.freedesktop.portal.FileChooser")]
-struct FileChooserProxy<'a>(Proxy<'a>);
+pub struct FileChooserProxy<'a>(Proxy<'a>);
 
 impl<'a> FileChooserProxy<'a> {
+    /// The [`OpenFileRequest::directory`] option.
+    pub const DIRECTORY: Feature = Feature::since(3);
+
+    /// The [`OpenFileRequest::current_folder`] option.
+    pub const CURRENT_FOLDER: Feature = Feature::since(4);
+
     /// Create a new instance of [`FileChooserProxy`].
     pub async fn new() -> Result<FileChooserProxy<'a>, Error> {
         let proxy = Proxy::new_desktop("org.freedesktop.portal.FileChooser").await?;
         Ok(Self(proxy))
     }
 
-    pub async fn open_file(
+    async fn open_file(
         &self,
         identifier: Option<&WindowIdentifier>,
         title: &str,
-        options: OpenFileOptions,
+        options: &OpenFileOptions,
     ) -> Result<Request<SelectedFiles>, Error> {
         let identifier = identifier.map(|i| i.to_string()).unwrap_or_default();
         self.0
             .request(
                 &options.handle_token,
                 "OpenFile",
-                &(&identifier, title, &options),
+                &(&identifier, title, options),
             )
             .await
     }
 
-    pub async fn save_file(
+    async fn save_file(
         &self,
         identifier: Option<&WindowIdentifier>,
         title: &str,
@@ -340,7 +356,7 @@ impl<'a> FileChooserProxy<'a> {
             .await
     }
 
-    pub async fn save_files(
+    async fn save_files(
         &self,
         identifier: Option<&WindowIdentifier>,
         title: &str,
@@ -365,6 +381,18 @@ impl<'a> std::ops::Deref for FileChooserProxy<'a> {
     }
 }
 
+impl crate::Versioned for FileChooserProxy<'_> {
+    fn version(&self) -> u32 {
+        self.0.version()
+    }
+}
+
+impl crate::Timeout for FileChooserProxy<'_> {
+    fn set_timeout(&mut self, timeout: impl Into<Option<std::time::Duration>>) {
+        self.0.set_timeout(timeout.into());
+    }
+}
+
 #[derive(Debug, Default)]
 #[doc(alias = "xdp_portal_open_file")]
 /// A [builder-pattern] type to open a file.
@@ -372,6 +400,9 @@ impl<'a> std::ops::Deref for FileChooserProxy<'a> {
 /// [builder-pattern]: https://doc.rust-lang.org/1.0.0/style/ownership/builders.html
 pub struct OpenFileRequest {
     identifier: Option<WindowIdentifier>,
+    timeout: Option<Duration>,
+    cancel_on_drop: bool,
+    cancellation: Option<CancellationToken>,
     title: String,
     options: OpenFileOptions,
 }
@@ -384,6 +415,30 @@ impl OpenFileRequest {
         self
     }
 
+    #[must_use]
+    /// Sets how long to wait for the request to complete, including the user
+    /// interaction. The request is closed once the timeout expires.
+    pub fn timeout(mut self, timeout: impl Into<Option<Duration>>) -> Self {
+        self.timeout = timeout.into();
+        self
+    }
+
+    #[must_use]
+    /// Sets whether to close the request if the future returned by `send` is
+    /// dropped before the user interaction ends, dismissing the dialog.
+    pub fn cancel_on_drop(mut self, cancel_on_drop: bool) -> Self {
+        self.cancel_on_drop = cancel_on_drop;
+        self
+    }
+
+    #[must_use]
+    /// Closes the request once `token` is cancelled, the request then fails
+    /// with [`ResponseError::Cancelled`](crate::desktop::ResponseError::Cancelled).
+    pub fn cancel_with(mut self, token: &CancellationToken) -> Self {
+        self.cancellation = Some(token.clone());
+        self
+    }
+
     /// Sets a title for the file chooser dialog.
     #[must_use]
     pub fn title<'a>(mut self, title: impl Into<Option<&'a str>>) -> Self {
@@ -462,20 +517,52 @@ impl OpenFileRequest {
         mut self,
         current_folder: impl Into<Option<P>>,
     ) -> Result<Self, crate::Error> {
-        self.options.current_folder = current_folder
-            .into()
-            .map(|c| FilePath::new(c))
-            .transpose()?;
+        let current_folder = current_folder.into();
+        self.options.current_folder =
+            FilePath::optional(current_folder.as_ref().map(AsRef::as_ref))?;
         Ok(self)
     }
 
     /// Send the request.
     pub async fn send(self) -> Result<Request<SelectedFiles>, Error> {
-        let proxy = FileChooserProxy::new().await?;
+        let mut proxy = FileChooserProxy::new().await?;
+        proxy.0.set_timeout(self.timeout);
+        proxy.0.set_cancel_on_drop(self.cancel_on_drop);
+        proxy.0.set_cancellation(self.cancellation);
         proxy
-            .open_file(self.identifier.as_ref(), &self.title, self.options)
+            .open_file(self.identifier.as_ref(), &self.title, &self.options)
             .await
     }
+
+    #[cfg(feature = "gtk4_fallback")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "gtk4_fallback")))]
+    /// Send the request and wait for the selected files.
+    ///
+    /// A `gtk4::FileDialog`, transient for the window the identifier was
+    /// created from, is shown instead when no frontend provides the portal,
+    /// on minimal desktops for example. The dialog runs on the default
+    /// `glib::MainContext`, which GTK has to be iterating.
+    pub async fn send_with_fallback(self) -> Result<SelectedFiles, Error> {
+        let request = async {
+            let mut proxy = FileChooserProxy::new().await?;
+            proxy.0.set_timeout(self.timeout);
+            proxy.0.set_cancel_on_drop(self.cancel_on_drop);
+            proxy.0.set_cancellation(self.cancellation.clone());
+            proxy
+                .open_file(self.identifier.as_ref(), &self.title, &self.options)
+                .await
+        }
+        .await;
+        match request {
+            Ok(request) => request.response(),
+            Err(err) if err.is_portal_missing() => {
+                #[cfg(feature = "tracing")]
+                tracing::info!("FileChooser portal is missing, falling back to gtk4::FileDialog");
+                gtk4_fallback::open_file(self.identifier, self.title, self.options).await
+            }
+            Err(err) => Err(err),
+        }
+    }
 }
 
 #[derive(Debug, Default)]
@@ -485,6 +572,9 @@ impl OpenFileRequest {
 /// [builder-pattern]: https://doc.rust-lang.org/1.0.0/style/ownership/builders.html
 pub struct SaveFilesRequest {
     identifier: Option<WindowIdentifier>,
+    timeout: Option<Duration>,
+    cancel_on_drop: bool,
+    cancellation: Option<CancellationToken>,
     title: String,
     options: SaveFilesOptions,
 }
@@ -497,6 +587,30 @@ impl SaveFilesRequest {
         self
     }
 
+    #[must_use]
+    /// Sets how long to wait for the request to complete, including the user
+    /// interaction. The request is closed once the timeout expires.
+    pub fn timeout(mut self, timeout: impl Into<Option<Duration>>) -> Self {
+        self.timeout = timeout.into();
+        self
+    }
+
+    #[must_use]
+    /// Sets whether to close the request if the future returned by `send` is
+    /// dropped before the user interaction ends, dismissing the dialog.
+    pub fn cancel_on_drop(mut self, cancel_on_drop: bool) -> Self {
+        self.cancel_on_drop = cancel_on_drop;
+        self
+    }
+
+    #[must_use]
+    /// Closes the request once `token` is cancelled, the request then fails
+    /// with [`ResponseError::Cancelled`](crate::desktop::ResponseError::Cancelled).
+    pub fn cancel_with(mut self, token: &CancellationToken) -> Self {
+        self.cancellation = Some(token.clone());
+        self
+    }
+
     /// Sets a title for the file chooser dialog.
     #[must_use]
     pub fn title<'a>(mut self, title: impl Into<Option<&'a str>>) -> Self {
@@ -540,10 +654,9 @@ impl SaveFilesRequest {
         mut self,
         current_folder: impl Into<Option<P>>,
     ) -> Result<Self, crate::Error> {
-        self.options.current_folder = current_folder
-            .into()
-            .map(|c| FilePath::new(c))
-            .transpose()?;
+        let current_folder = current_folder.into();
+        self.options.current_folder =
+            FilePath::optional(current_folder.as_ref().map(AsRef::as_ref))?;
         Ok(self)
     }
 
@@ -554,14 +667,22 @@ impl SaveFilesRequest {
     ) -> Result<Self, crate::Error> {
         self.options.files = files
             .into()
-            .map(|files| files.into_iter().map(|s| FilePath::new(s)).collect())
+            .map(|files| {
+                files
+                    .into_iter()
+                    .map(|s| FilePath::new(s.as_ref()))
+                    .collect()
+            })
             .transpose()?;
         Ok(self)
     }
 
     /// Send the request.
     pub async fn send(self) -> Result<Request<SelectedFiles>, Error> {
-        let proxy = FileChooserProxy::new().await?;
+        let mut proxy = FileChooserProxy::new().await?;
+        proxy.0.set_timeout(self.timeout);
+        proxy.0.set_cancel_on_drop(self.cancel_on_drop);
+        proxy.0.set_cancellation(self.cancellation);
         proxy
             .save_files(self.identifier.as_ref(), &self.title, self.options)
             .await
@@ -575,6 +696,9 @@ impl SaveFilesRequest {
 /// [builder-pattern]: https://doc.rust-lang.org/1.0.0/style/ownership/builders.html
 pub struct SaveFileRequest {
     identifier: Option<WindowIdentifier>,
+    timeout: Option<Duration>,
+    cancel_on_drop: bool,
+    cancellation: Option<CancellationToken>,
     title: String,
     options: SaveFileOptions,
 }
@@ -587,6 +711,30 @@ impl SaveFileRequest {
         self
     }
 
+    #[must_use]
+    /// Sets how long to wait for the request to complete, including the user
+    /// interaction. The request is closed once the timeout expires.
+    pub fn timeout(mut self, timeout: impl Into<Option<Duration>>) -> Self {
+        self.timeout = timeout.into();
+        self
+    }
+
+    #[must_use]
+    /// Sets whether to close the request if the future returned by `send` is
+    /// dropped before the user interaction ends, dismissing the dialog.
+    pub fn cancel_on_drop(mut self, cancel_on_drop: bool) -> Self {
+        self.cancel_on_drop = cancel_on_drop;
+        self
+    }
+
+    #[must_use]
+    /// Closes the request once `token` is cancelled, the request then fails
+    /// with [`ResponseError::Cancelled`](crate::desktop::ResponseError::Cancelled).
+    pub fn cancel_with(mut self, token: &CancellationToken) -> Self {
+        self.cancellation = Some(token.clone());
+        self
+    }
+
     /// Sets a title for the file chooser dialog.
     #[must_use]
     pub fn title<'a>(mut self, title: impl Into<Option<&'a str>>) -> Self {
@@ -620,10 +768,9 @@ impl SaveFileRequest {
         mut self,
         current_folder: impl Into<Option<P>>,
     ) -> Result<Self, crate::Error> {
-        self.options.current_folder = current_folder
-            .into()
-            .map(|c| FilePath::new(c))
-            .transpose()?;
+        let current_folder = current_folder.into();
+        self.options.current_folder =
+            FilePath::optional(current_folder.as_ref().map(AsRef::as_ref))?;
         Ok(self)
     }
 
@@ -632,7 +779,8 @@ impl SaveFileRequest {
         mut self,
         current_file: impl Into<Option<P>>,
     ) -> Result<Self, crate::Error> {
-        self.options.current_file = current_file.into().map(|c| FilePath::new(c)).transpose()?;
+        let current_file = current_file.into();
+        self.options.current_file = FilePath::optional(current_file.as_ref().map(AsRef::as_ref))?;
         Ok(self)
     }
 
@@ -676,9 +824,139 @@ impl SaveFileRequest {
 
     /// Send the request.
     pub async fn send(self) -> Result<Request<SelectedFiles>, Error> {
-        let proxy = FileChooserProxy::new().await?;
+        let mut proxy = FileChooserProxy::new().await?;
+        proxy.0.set_timeout(self.timeout);
+        proxy.0.set_cancel_on_drop(self.cancel_on_drop);
+        proxy.0.set_cancellation(self.cancellation);
         proxy
             .save_file(self.identifier.as_ref(), &self.title, self.options)
             .await
     }
 }
+
+#[cfg(feature = "gtk4_fallback")]
+mod gtk4_fallback {
+    use gtk4::{gio, glib, prelude::*};
+
+    use super::{FileFilter, OpenFileOptions, SelectedFiles};
+    use crate::{desktop::ResponseError, Error, WindowIdentifier};
+
+    /// Shows the request with a [`gtk4::FileDialog`], on the default main
+    /// context.
+    pub(super) async fn open_file(
+        identifier: Option<WindowIdentifier>,
+        title: String,
+        options: OpenFileOptions,
+    ) -> Result<SelectedFiles, Error> {
+        // The dialog has no choices, report their initial selection instead.
+        let choices = options.choices.as_ref().map(|choices| {
+            choices
+                .iter()
+                .map(|choice| {
+                    (
+                        choice.id().to_owned(),
+                        choice.initial_selection().to_owned(),
+                    )
+                })
+                .collect()
+        });
+        let uris = glib::MainContext::default()
+            .spawn_from_within(move || async move {
+                let parent = identifier.as_ref().and_then(parent_window);
+                let files = show_open_dialog(parent.as_ref(), &title, &options)
+                    .await
+                    .map_err(|err| {
+                        if err.matches(gtk4::DialogError::Dismissed)
+                            || err.matches(gtk4::DialogError::Cancelled)
+                        {
+                            ResponseError::Cancelled
+                        } else {
+                            ResponseError::Other
+                        }
+                    })?;
+                Ok::<_, ResponseError>(
+                    files
+                        .iter()
+                        .map(|file| file.uri().to_string())
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .await
+            .map_err(|_| ResponseError::Other)??;
+        let uris = uris
+            .iter()
+            .map(|uri| url::Url::parse(uri))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| Error::ParseError("Failed to parse the URI of a selected file"))?;
+        Ok(SelectedFiles { uris, choices })
+    }
+
+    /// The window the identifier was created from, if any.
+    fn parent_window(identifier: &WindowIdentifier) -> Option<gtk4::Window> {
+        match identifier {
+            WindowIdentifier::Gtk4(identifier) => {
+                identifier.native().clone().downcast::<gtk4::Window>().ok()
+            }
+            _ => None,
+        }
+    }
+
+    async fn show_open_dialog(
+        parent: Option<&gtk4::Window>,
+        title: &str,
+        options: &OpenFileOptions,
+    ) -> Result<Vec<gio::File>, glib::Error> {
+        let dialog = gtk4::FileDialog::new();
+        dialog.set_title(title);
+        dialog.set_accept_label(options.accept_label.as_deref());
+        dialog.set_modal(options.modal.unwrap_or(true));
+        if !options.filters.is_empty() {
+            let filters = gio::ListStore::new::<gtk4::FileFilter>();
+            for filter in &options.filters {
+                filters.append(&native_filter(filter));
+            }
+            dialog.set_filters(Some(&filters));
+        }
+        if let Some(filter) = &options.current_filter {
+            dialog.set_default_filter(Some(&native_filter(filter)));
+        }
+        if let Some(folder) = &options.current_folder {
+            dialog.set_initial_folder(Some(&gio::File::for_path(folder)));
+        }
+
+        let multiple = options.multiple.unwrap_or(false);
+        match (options.directory.unwrap_or(false), multiple) {
+            (true, true) => Ok(files(&dialog.select_multiple_folders_future(parent).await?)),
+            (true, false) => Ok(vec![dialog.select_folder_future(parent).await?]),
+            (false, true) => Ok(files(&dialog.open_multiple_future(parent).await?)),
+            (false, false) => Ok(vec![dialog.open_future(parent).await?]),
+        }
+    }
+
+    fn native_filter(filter: &FileFilter) -> gtk4::FileFilter {
+        let native = gtk4::FileFilter::new();
+        native.set_name(Some(filter.label()));
+        for mimetype in filter.mimetype_filters() {
+            native.add_mime_type(mimetype);
+        }
+        for pattern in filter.pattern_filters() {
+            native.add_pattern(pattern);
+        }
+        native
+    }
+
+    fn files(model: &gio::ListModel) -> Vec<gio::File> {
+        (0..model.n_items())
+            .filter_map(|position| model.item(position)?.downcast::<gio::File>().ok())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+pub(crate) const SIGNATURES: &[crate::proxy::WrapperSignature] = crate::proxy::wrapper_signatures! {
+    "org.freedesktop.portal.FileChooser" {
+        "OpenFile": (&str, &str, &OpenFileOptions) => zbus::zvariant::OwnedObjectPath,
+        "SaveFile": (&str, &str, &SaveFileOptions) => zbus::zvariant::OwnedObjectPath,
+        "SaveFiles": (&str, &str, &SaveFilesOptions) => zbus::zvariant::OwnedObjectPath,
+    }
+};
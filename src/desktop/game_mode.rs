@@ -17,14 +17,14 @@
 
 use std::{fmt::Debug, os::fd::AsFd};
 
-use serde_repr::Deserialize_repr;
+use serde_repr::{Deserialize_repr, Serialize_repr};
 use zbus::zvariant::{Fd, Type};
 
 use crate::{error::PortalError, proxy::Proxy, Error, Pid};
 
 #[cfg_attr(feature = "glib", derive(glib::Enum))]
 #[cfg_attr(feature = "glib", enum_type(name = "AshpdGameModeStatus"))]
-#[derive(Deserialize_repr, PartialEq, Eq, Debug, Clone, Copy, Type)]
+#[derive(Serialize_repr, Deserialize_repr, PartialEq, Eq, Debug, Clone, Copy, Type)]
 #[repr(i32)]
 /// The status of the game mode.
 pub enum Status {
@@ -103,7 +103,8 @@ impl<'a> GameMode<'a> {
     ///
     /// # Arguments
     ///
-    /// * `target` - Pid file descriptor to query the GameMode status of.
+    /// * `target` - Pid file descriptor to query the GameMode status of, a
+    ///   [`Process`](crate::Process) for example.
     /// * `requester` - Pid file descriptor of the process requesting the
     ///   information.
     ///
@@ -169,7 +170,8 @@ impl<'a> GameMode<'a> {
     ///
     /// # Arguments
     ///
-    /// * `target` - Process file descriptor of the game to register.
+    /// * `target` - Process file descriptor of the game to register, a
+    ///   [`Process`](crate::Process) for example.
     /// * `requester` - Process file descriptor of the process requesting the
     ///   registration.
     ///
@@ -249,7 +251,8 @@ impl<'a> GameMode<'a> {
     ///
     /// # Arguments
     ///
-    /// * `target` - Pid file descriptor of the game to un-register.
+    /// * `target` - Pid file descriptor of the game to un-register, a
+    ///   [`Process`](crate::Process) for example.
     /// * `requester` - Pid file descriptor of the process requesting the
     ///   un-registration.
     ///
@@ -310,3 +313,30 @@ impl<'a> std::ops::Deref for GameMode<'a> {
         &self.0
     }
 }
+
+impl crate::Versioned for GameMode<'_> {
+    fn version(&self) -> u32 {
+        self.0.version()
+    }
+}
+
+impl crate::Timeout for GameMode<'_> {
+    fn set_timeout(&mut self, timeout: impl Into<Option<std::time::Duration>>) {
+        self.0.set_timeout(timeout.into());
+    }
+}
+
+#[cfg(test)]
+pub(crate) const SIGNATURES: &[crate::proxy::WrapperSignature] = crate::proxy::wrapper_signatures! {
+    "org.freedesktop.portal.GameMode" {
+        "QueryStatus": i32 => Status,
+        "QueryStatusByPIDFd": (zbus::zvariant::Fd<'_>, zbus::zvariant::Fd<'_>) => Status,
+        "QueryStatusByPid": (i32, i32) => Status,
+        "RegisterGame": i32 => RegisterStatus,
+        "RegisterGameByPIDFd": (zbus::zvariant::Fd<'_>, zbus::zvariant::Fd<'_>) => RegisterStatus,
+        "RegisterGameByPid": (i32, i32) => RegisterStatus,
+        "UnregisterGame": i32 => RegisterStatus,
+        "UnregisterGameByPIDFd": (zbus::zvariant::Fd<'_>, zbus::zvariant::Fd<'_>) => RegisterStatus,
+        "UnregisterGameByPid": (i32, i32) => RegisterStatus,
+    }
+};
@@ -1,12 +1,15 @@
 //! Register global shortcuts
 
-use std::{collections::HashMap, fmt::Debug, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+    future::ready,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
-use futures_util::{Stream, TryFutureExt};
+use futures_util::{Stream, StreamExt, TryFutureExt};
 use serde::{Deserialize, Serialize};
-use zbus::zvariant::{
-    DeserializeDict, ObjectPath, OwnedObjectPath, OwnedValue, SerializeDict, Type,
-};
+use zbus::zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, SerializeDict, Type};
 
 use super::{session::SessionPortal, HandleToken, Request, Session};
 use crate::{desktop::session::CreateSessionResponse, proxy::Proxy, Error, WindowIdentifier};
@@ -47,21 +50,22 @@ impl NewShortcut {
     }
 }
 
-#[derive(Clone, DeserializeDict, Type, Debug, Default)]
-#[zvariant(signature = "dict")]
-struct ShortcutInfo {
-    /// User-readable text describing what the shortcut does.
-    description: String,
-    /// User-readable text describing how to trigger the shortcut for the client
-    /// to render.
-    trigger_description: String,
+crate::helpers::dict! {
+    #[derive(Clone, Debug, Default)]
+    struct ShortcutInfo {
+        /// User-readable text describing what the shortcut does.
+        description: String,
+        /// User-readable text describing how to trigger the shortcut for the client
+        /// to render.
+        trigger_description: String,
+    }
 }
 
 /// Struct that contains information about existing binded shortcut.
 ///
 /// If you need to create a new shortcuts, take a look at [`NewShortcut`]
 /// instead.
-#[derive(Clone, Deserialize, Type, Debug)]
+#[derive(Clone, Serialize, Deserialize, Type, Debug)]
 pub struct Shortcut(String, ShortcutInfo);
 
 impl Shortcut {
@@ -100,11 +104,12 @@ struct BindShortcutsOptions {
     handle_token: HandleToken,
 }
 
-/// A response to a [`GlobalShortcuts::bind_shortcuts`] request.
-#[derive(DeserializeDict, Type, Debug)]
-#[zvariant(signature = "dict")]
-pub struct BindShortcuts {
-    shortcuts: Vec<Shortcut>,
+crate::helpers::dict! {
+    /// A response to a [`GlobalShortcuts::bind_shortcuts`] request.
+    #[derive(Debug)]
+    pub struct BindShortcuts {
+        shortcuts: Vec<Shortcut>,
+    }
 }
 
 impl BindShortcuts {
@@ -122,12 +127,13 @@ struct ListShortcutsOptions {
     handle_token: HandleToken,
 }
 
-/// A response to a [`GlobalShortcuts::list_shortcuts`] request.
-#[derive(DeserializeDict, Type, Debug)]
-#[zvariant(signature = "dict")]
-pub struct ListShortcuts {
-    /// A list of shortcuts.
-    shortcuts: Vec<Shortcut>,
+crate::helpers::dict! {
+    /// A response to a [`GlobalShortcuts::list_shortcuts`] request.
+    #[derive(Debug)]
+    pub struct ListShortcuts {
+        /// A list of shortcuts.
+        shortcuts: Vec<Shortcut>,
+    }
 }
 
 impl ListShortcuts {
@@ -138,7 +144,7 @@ impl ListShortcuts {
 }
 
 /// Notifies about a shortcut becoming active.
-#[derive(Debug, Deserialize, Type)]
+#[derive(Debug, Serialize, Deserialize, Type)]
 pub struct Activated(OwnedObjectPath, String, u64, HashMap<String, OwnedValue>);
 
 impl Activated {
@@ -157,6 +163,11 @@ impl Activated {
         Duration::from_millis(self.2)
     }
 
+    /// The timestamp as a [`SystemTime`].
+    pub fn system_time(&self) -> SystemTime {
+        UNIX_EPOCH + self.timestamp()
+    }
+
     /// Optional information
     pub fn options(&self) -> &HashMap<String, OwnedValue> {
         &self.3
@@ -164,7 +175,7 @@ impl Activated {
 }
 
 /// Notifies that a shortcut is not active anymore.
-#[derive(Debug, Deserialize, Type)]
+#[derive(Debug, Serialize, Deserialize, Type)]
 pub struct Deactivated(OwnedObjectPath, String, u64, HashMap<String, OwnedValue>);
 
 impl Deactivated {
@@ -183,15 +194,78 @@ impl Deactivated {
         Duration::from_millis(self.2)
     }
 
+    /// The timestamp as a [`SystemTime`].
+    pub fn system_time(&self) -> SystemTime {
+        UNIX_EPOCH + self.timestamp()
+    }
+
     /// Optional information
     pub fn options(&self) -> &HashMap<String, OwnedValue> {
         &self.3
     }
 }
 
+/// A shortcut becoming active or inactive, see
+/// [`GlobalShortcuts::receive_shortcut_events`].
+#[derive(Debug)]
+pub enum ShortcutEvent {
+    /// The shortcut became active.
+    Activated(Activated),
+    /// The shortcut is not active anymore.
+    Deactivated(Deactivated),
+}
+
+impl ShortcutEvent {
+    fn from_message(msg: &zbus::Message) -> Option<Self> {
+        let header = msg.header();
+        match header.member()?.as_str() {
+            "Activated" => msg.body().deserialize().ok().map(Self::Activated),
+            "Deactivated" => msg.body().deserialize().ok().map(Self::Deactivated),
+            _ => None,
+        }
+    }
+
+    /// Session that requested the shortcut.
+    pub fn session_handle(&self) -> ObjectPath<'_> {
+        match self {
+            Self::Activated(activated) => activated.session_handle(),
+            Self::Deactivated(deactivated) => deactivated.session_handle(),
+        }
+    }
+
+    /// The application-provided ID for the shortcut.
+    pub fn shortcut_id(&self) -> &str {
+        match self {
+            Self::Activated(activated) => activated.shortcut_id(),
+            Self::Deactivated(deactivated) => deactivated.shortcut_id(),
+        }
+    }
+
+    /// The timestamp, as seconds and microseconds since the Unix epoch.
+    pub fn timestamp(&self) -> Duration {
+        match self {
+            Self::Activated(activated) => activated.timestamp(),
+            Self::Deactivated(deactivated) => deactivated.timestamp(),
+        }
+    }
+
+    /// Whether the event is not a repeated activation of a shortcut that is
+    /// already `active`, updating the latter.
+    fn is_edge(&self, active: &mut HashSet<(OwnedObjectPath, String)>) -> bool {
+        let key = (self.session_handle().into(), self.shortcut_id().to_owned());
+        match self {
+            Self::Activated(_) => active.insert(key),
+            Self::Deactivated(_) => {
+                active.remove(&key);
+                true
+            }
+        }
+    }
+}
+
 /// Indicates that the information associated with some of the shortcuts has
 /// changed.
-#[derive(Debug, Deserialize, Type)]
+#[derive(Debug, Serialize, Deserialize, Type)]
 pub struct ShortcutsChanged(OwnedObjectPath, Vec<Shortcut>);
 
 impl ShortcutsChanged {
@@ -226,12 +300,13 @@ impl<'a> GlobalShortcuts<'a> {
     #[doc(alias = "CreateSession")]
     pub async fn create_session(&self) -> Result<Session<'a, Self>, Error> {
         let options = CreateSessionOptions::default();
-        let (request, proxy) = futures_util::try_join!(
+        let (request, proxy) = futures_util::future::try_join(
             self.0
                 .request::<CreateSessionResponse>(&options.handle_token, "CreateSession", &options)
                 .into_future(),
             Session::from_unique_name(&options.session_handle_token).into_future(),
-        )?;
+        )
+        .await?;
         assert_eq!(proxy.path(), &request.response()?.session_handle.as_ref());
         Ok(proxy)
     }
@@ -285,6 +360,20 @@ impl<'a> GlobalShortcuts<'a> {
         self.0.signal("Activated").await
     }
 
+    /// Same as [`Self::receive_activated`] but only receives the signals of
+    /// `session`.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`Activated`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.GlobalShortcuts.html#org-freedesktop-portal-globalshortcuts-activated).
+    #[doc(alias = "Activated")]
+    pub async fn receive_activated_for_session(
+        &self,
+        session: &Session<'_, Self>,
+    ) -> Result<impl Stream<Item = Activated>, Error> {
+        self.0.signal_for_session("Activated", session.path()).await
+    }
+
     /// Signal emitted when shortcut is not active anymore.
     ///
     /// # Specifications
@@ -295,6 +384,46 @@ impl<'a> GlobalShortcuts<'a> {
         self.0.signal("Deactivated").await
     }
 
+    /// Signals emitted when shortcuts become active or not active anymore, in
+    /// the order they were emitted.
+    ///
+    /// If `deduplicate` is `true`, the repeated [`Activated`] signals of a
+    /// shortcut that is held are skipped, so that each activation is followed
+    /// by a deactivation, as needed for push-to-talk for example.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`Activated`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.GlobalShortcuts.html#org-freedesktop-portal-globalshortcuts-activated)
+    /// and [`Deactivated`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.GlobalShortcuts.html#org-freedesktop-portal-globalshortcuts-deactivated).
+    pub async fn receive_shortcut_events(
+        &self,
+        deduplicate: bool,
+    ) -> Result<impl Stream<Item = ShortcutEvent>, Error> {
+        // A single stream keeps the order of the activations and deactivations.
+        let mut active = HashSet::new();
+        Ok(self.0.receive_all_signals().await?.filter_map(move |msg| {
+            let event = ShortcutEvent::from_message(&msg)
+                .filter(|event| !deduplicate || event.is_edge(&mut active));
+            ready(event)
+        }))
+    }
+
+    /// Same as [`Self::receive_deactivated`] but only receives the signals of
+    /// `session`.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`Deactivated`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.GlobalShortcuts.html#org-freedesktop-portal-globalshortcuts-deactivated).
+    #[doc(alias = "Deactivated")]
+    pub async fn receive_deactivated_for_session(
+        &self,
+        session: &Session<'_, Self>,
+    ) -> Result<impl Stream<Item = Deactivated>, Error> {
+        self.0
+            .signal_for_session("Deactivated", session.path())
+            .await
+    }
+
     /// Signal emitted when information associated with some of the shortcuts
     /// has changed.
     ///
@@ -307,6 +436,22 @@ impl<'a> GlobalShortcuts<'a> {
     ) -> Result<impl Stream<Item = ShortcutsChanged>, Error> {
         self.0.signal("ShortcutsChanged").await
     }
+
+    /// Same as [`Self::receive_shortcuts_changed`] but only receives the signals of
+    /// `session`.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`ShortcutsChanged`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.GlobalShortcuts.html#org-freedesktop-portal-globalshortcuts-shortcutschanged).
+    #[doc(alias = "ShortcutsChanged")]
+    pub async fn receive_shortcuts_changed_for_session(
+        &self,
+        session: &Session<'_, Self>,
+    ) -> Result<impl Stream<Item = ShortcutsChanged>, Error> {
+        self.0
+            .signal_for_session("ShortcutsChanged", session.path())
+            .await
+    }
 }
 
 impl<'a> std::ops::Deref for GlobalShortcuts<'a> {
@@ -317,5 +462,72 @@ impl<'a> std::ops::Deref for GlobalShortcuts<'a> {
     }
 }
 
+impl crate::Versioned for GlobalShortcuts<'_> {
+    fn version(&self) -> u32 {
+        self.0.version()
+    }
+}
+
+impl crate::Timeout for GlobalShortcuts<'_> {
+    fn set_timeout(&mut self, timeout: impl Into<Option<std::time::Duration>>) {
+        self.0.set_timeout(timeout.into());
+    }
+}
+
 impl crate::Sealed for GlobalShortcuts<'_> {}
 impl SessionPortal for GlobalShortcuts<'_> {}
+
+#[cfg(test)]
+pub(crate) const SIGNATURES: &[crate::proxy::WrapperSignature] = crate::proxy::wrapper_signatures! {
+    "org.freedesktop.portal.GlobalShortcuts" {
+        "CreateSession": &CreateSessionOptions => zbus::zvariant::OwnedObjectPath,
+        "BindShortcuts": (crate::desktop::Session<'_, GlobalShortcuts<'_>>, &[NewShortcut], String, &BindShortcutsOptions) => zbus::zvariant::OwnedObjectPath,
+        "ListShortcuts": (crate::desktop::Session<'_, GlobalShortcuts<'_>>, &ListShortcutsOptions) => zbus::zvariant::OwnedObjectPath,
+    }
+};
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    use zbus::zvariant::OwnedObjectPath;
+
+    use super::{Activated, Deactivated, ShortcutEvent};
+
+    #[test]
+    fn deduplicate_activations() {
+        let session =
+            OwnedObjectPath::try_from("/org/freedesktop/portal/desktop/session/1").unwrap();
+        let activated = |id: &str, timestamp| {
+            ShortcutEvent::Activated(Activated(
+                session.clone(),
+                id.to_owned(),
+                timestamp,
+                HashMap::new(),
+            ))
+        };
+        let deactivated = |id: &str, timestamp| {
+            ShortcutEvent::Deactivated(Deactivated(
+                session.clone(),
+                id.to_owned(),
+                timestamp,
+                HashMap::new(),
+            ))
+        };
+
+        let mut active = HashSet::new();
+        let edges = [
+            activated("talk", 1),
+            activated("talk", 2),
+            activated("mute", 3),
+            activated("talk", 4),
+            deactivated("talk", 5),
+            activated("talk", 6),
+        ]
+        .into_iter()
+        .filter(|event| event.is_edge(&mut active))
+        .map(|event| event.timestamp().as_millis())
+        .collect::<Vec<_>>();
+        assert_eq!(edges, [1, 3, 5, 6]);
+    }
+}
@@ -1,4 +1,8 @@
-use std::os::fd::AsFd;
+use std::{
+    os::fd::AsFd,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use serde::{
     de,
@@ -7,8 +11,13 @@ use serde::{
 };
 use zbus::zvariant::{self, OwnedValue, Type, Value};
 
+use super::dynamic_launcher::IconType;
 use crate::Error;
 
+/// The largest icon file read by [`Icon::from_path`], the portals only
+/// accept icons of a few hundred pixels anyway.
+const MAX_ICON_SIZE: u64 = 4 * 1024 * 1024;
+
 #[derive(Debug, Type)]
 #[zvariant(signature = "(sv)")]
 /// A representation of an icon.
@@ -20,7 +29,10 @@ pub enum Icon {
     /// A list of icon names.
     Names(Vec<String>),
     /// Icon bytes.
-    Bytes(Vec<u8>),
+    ///
+    /// The bytes are shared, cloning the icon with [`Icon::try_clone`] or
+    /// sending it doesn't copy them.
+    Bytes(Arc<[u8]>),
     /// A file descriptor.
     FileDescriptor(std::os::fd::OwnedFd),
 }
@@ -34,40 +46,167 @@ impl Icon {
         Self::Names(names.into_iter().map(|name| name.to_string()).collect())
     }
 
-    pub(crate) fn is_bytes(&self) -> bool {
-        matches!(self, Self::Bytes(_))
+    /// Create a themed icon from `name` and its fallbacks, the names with
+    /// the dash-separated parts removed one by one from the end, like
+    /// `g_themed_icon_new_with_default_fallbacks` does.
+    ///
+    /// ```rust
+    /// use ashpd::desktop::Icon;
+    ///
+    /// let icon = Icon::with_fallback_names("network-wireless-symbolic");
+    /// assert!(matches!(
+    ///     icon,
+    ///     Icon::Names(names) if names == [
+    ///         "network-wireless-symbolic",
+    ///         "network-symbolic",
+    ///         "network-wireless",
+    ///         "network",
+    ///     ]
+    /// ));
+    /// ```
+    pub fn with_fallback_names(name: &str) -> Self {
+        let (base, symbolic) = match name.strip_suffix("-symbolic") {
+            Some(base) => (base, true),
+            None => (name, false),
+        };
+        let mut fallbacks = vec![base];
+        while let Some((rest, _)) = fallbacks.last().and_then(|name| name.rsplit_once('-')) {
+            fallbacks.push(rest);
+        }
+        let mut names = Vec::with_capacity(fallbacks.len() * 2);
+        if symbolic {
+            names.extend(fallbacks.iter().map(|name| format!("{name}-symbolic")));
+        }
+        names.extend(fallbacks.into_iter().map(ToOwned::to_owned));
+        Self::Names(names)
+    }
+
+    /// Create an icon from its bytes.
+    pub fn with_bytes(bytes: impl Into<Arc<[u8]>>) -> Self {
+        Self::Bytes(bytes.into())
+    }
+
+    /// Create an icon from the bytes of the image file at `path`.
+    ///
+    /// Unlike an [`Icon::Uri`], the icon is usable by the portals that can't
+    /// access the file, from a sandbox, or that only accept bytes like the
+    /// [`dynamic_launcher`](crate::desktop::dynamic_launcher) one.
+    ///
+    /// The files larger than 4 MiB are rejected with an
+    /// [`std::io::ErrorKind::InvalidData`] error.
+    pub async fn from_path(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let bytes = crate::io::read_file_capped(path, MAX_ICON_SIZE).await?;
+        Ok(Self::with_bytes(bytes))
+    }
+
+    /// Create an icon from the bytes of the themed icon `name`, preferably
+    /// of `size`x`size` pixels.
+    ///
+    /// The icon is looked up in the `hicolor` theme of the XDG data
+    /// directories, where the applications install their icons, the PNG
+    /// image of the requested size being preferred to the scalable SVG one.
+    /// Like with [`Icon::from_path`], the icon is usable by the portals that
+    /// only accept bytes. Fails with an [`std::io::ErrorKind::NotFound`]
+    /// error if the theme has no such icon.
+    pub async fn from_theme(name: &str, size: u32) -> Result<Self, Error> {
+        if name.is_empty() || name.contains('/') || name.starts_with('.') {
+            return Err(Error::InvalidArgument(format!(
+                "{name} is not a valid icon name"
+            )));
+        }
+        if let Some(path) = themed_icon_path(&data_dirs(), name, size).await {
+            return Self::from_path(path).await;
+        }
+        Err(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("No {name} icon in the hicolor theme"),
+        )
+        .into())
     }
 
-    pub(crate) fn inner_bytes(&self) -> Value {
+    /// The bytes of an [`Icon::Bytes`].
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Self::Bytes(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    /// The format of the image of an [`Icon::Bytes`], guessed from its
+    /// content.
+    ///
+    /// Returns `None` for the other variants and the formats not supported
+    /// by the portals.
+    pub fn bytes_type(&self) -> Option<IconType> {
+        let bytes = self.as_bytes()?;
+        if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+            return Some(IconType::Png);
+        }
+        if bytes.starts_with(&[0xff, 0xd8, 0xff]) {
+            return Some(IconType::Jpeg);
+        }
+        // Look for the root element, after the optional XML declaration,
+        // doctype and comments.
+        let head = String::from_utf8_lossy(&bytes[..bytes.len().min(1024)]);
+        head.contains("<svg").then_some(IconType::Svg)
+    }
+
+    #[cfg(feature = "gtk4")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "gtk4")))]
+    /// Decodes an [`Icon::Bytes`] or loads an [`Icon::Uri`] to a
+    /// [`gdk::Texture`](gtk4::gdk::Texture).
+    ///
+    /// Fails with [`Error::UnexpectedIcon`] for the themed icons and the
+    /// file descriptors.
+    pub fn to_texture(&self) -> Result<gtk4::gdk::Texture, Error> {
         match self {
             Self::Bytes(bytes) => {
-                let mut array = zvariant::Array::new(u8::SIGNATURE);
-                for byte in bytes.iter() {
-                    // Safe to unwrap because we are sure it is of the correct type
-                    array.append(Value::from(*byte)).unwrap();
-                }
-                Value::from(array)
+                let bytes = gtk4::glib::Bytes::from(&**bytes);
+                Ok(gtk4::gdk::Texture::from_bytes(&bytes)?)
             }
-            _ => panic!("Only bytes icons can be converted to a bytes variant"),
+            Self::Uri(uri) => {
+                let file = gtk4::gio::File::for_uri(uri.as_str());
+                Ok(gtk4::gdk::Texture::from_file(&file)?)
+            }
+            Self::Names(_) | Self::FileDescriptor(_) => Err(Error::UnexpectedIcon),
         }
     }
 
-    pub(crate) fn as_value(&self) -> Value {
-        let tuple = match self {
-            Self::Uri(uri) => ("file", Value::from(uri.as_str())),
-            Self::Names(names) => {
-                let mut array = zvariant::Array::new(String::SIGNATURE);
-                for name in names.iter() {
-                    // Safe to unwrap because we are sure it is of the correct type
-                    array.append(Value::from(name)).unwrap();
-                }
+    /// Clones the icon, duplicating the file descriptor of an
+    /// [`Icon::FileDescriptor`].
+    pub fn try_clone(&self) -> std::io::Result<Self> {
+        Ok(match self {
+            Self::Uri(uri) => Self::Uri(uri.clone()),
+            Self::Names(names) => Self::Names(names.clone()),
+            Self::Bytes(bytes) => Self::Bytes(Arc::clone(bytes)),
+            Self::FileDescriptor(fd) => Self::FileDescriptor(fd.try_clone()?),
+        })
+    }
 
-                ("themed", Value::from(array))
-            }
-            Self::Bytes(_) => ("bytes", self.inner_bytes()),
-            Self::FileDescriptor(fd) => ("file-descriptor", zvariant::Fd::from(fd).into()),
-        };
-        Value::new(tuple)
+    pub(crate) fn is_bytes(&self) -> bool {
+        matches!(self, Self::Bytes(_))
+    }
+
+    /// The icon wrapped in a variant, without copying its content.
+    pub(crate) fn as_value(&self) -> IconValue<'_> {
+        IconValue(self)
+    }
+}
+
+/// An [`Icon`] serialized as a variant, see [`Icon::as_value`].
+#[derive(Debug)]
+pub(crate) struct IconValue<'a>(&'a Icon);
+
+impl Type for IconValue<'_> {
+    const SIGNATURE: &'static zvariant::Signature = &zvariant::Signature::Variant;
+}
+
+impl Serialize for IconValue<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        zvariant::as_value::Serialize(self.0).serialize(serializer)
     }
 }
 
@@ -91,9 +230,9 @@ impl Serialize for Icon {
                 }
                 tuple.serialize_element(&Value::from(array))?;
             }
-            Self::Bytes(_) => {
+            Self::Bytes(bytes) => {
                 tuple.serialize_element("bytes")?;
-                tuple.serialize_element(&self.inner_bytes())?;
+                tuple.serialize_element(&zvariant::as_value::Serialize(&&**bytes))?;
             }
             Self::FileDescriptor(fd) => {
                 tuple.serialize_element("file-descriptor")?;
@@ -119,10 +258,11 @@ impl<'de> Deserialize<'de> for Icon {
             }
             "bytes" => {
                 let array = data.downcast_ref::<zvariant::Array>().unwrap();
-                let mut bytes = Vec::with_capacity(array.len());
-                for byte in array.inner() {
-                    bytes.push(byte.downcast_ref::<u8>().unwrap());
-                }
+                let bytes = array
+                    .inner()
+                    .iter()
+                    .map(|byte| byte.downcast_ref::<u8>().unwrap())
+                    .collect();
                 Ok(Self::Bytes(bytes))
             }
             "themed" => {
@@ -165,10 +305,11 @@ impl TryFrom<&OwnedValue> for Icon {
             }
             "bytes" => {
                 let array = fields[1].downcast_ref::<zvariant::Array>().unwrap();
-                let mut bytes = Vec::with_capacity(array.len());
-                for byte in array.inner() {
-                    bytes.push(byte.downcast_ref::<u8>().unwrap());
-                }
+                let bytes = array
+                    .inner()
+                    .iter()
+                    .map(|byte| byte.downcast_ref::<u8>().unwrap())
+                    .collect();
                 Ok(Self::Bytes(bytes))
             }
             "themed" => {
@@ -213,12 +354,136 @@ impl TryFrom<&Value<'_>> for Icon {
     }
 }
 
+/// The XDG data directories, by order of priority.
+fn data_dirs() -> Vec<PathBuf> {
+    let data_home = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")));
+    let data_dirs = std::env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|dirs| !dirs.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_owned());
+    data_home
+        .into_iter()
+        .chain(data_dirs.split(':').map(PathBuf::from))
+        .collect()
+}
+
+/// The path of the icon `name` of the `hicolor` theme of `data_dirs`, the
+/// PNG image of `size`x`size` pixels being preferred to the scalable one.
+async fn themed_icon_path(data_dirs: &[PathBuf], name: &str, size: u32) -> Option<PathBuf> {
+    let candidates = [
+        (format!("{size}x{size}"), format!("{name}.png")),
+        ("scalable".to_owned(), format!("{name}.svg")),
+    ];
+    for (size_dir, file_name) in &candidates {
+        for data_dir in data_dirs {
+            // The icons are sorted by context, `apps` or `status` for example.
+            let theme_dir = data_dir.join("icons/hicolor").join(size_dir);
+            let Ok(contexts) = crate::io::read_dir(theme_dir).await else {
+                continue;
+            };
+            for context in contexts {
+                let path = context.join(file_name);
+                if crate::io::exists(&path).await {
+                    return Some(path);
+                }
+            }
+        }
+    }
+    None
+}
+
 #[cfg(test)]
 mod test {
     use zbus::zvariant::{serialized::Context, to_bytes, Endian};
 
     use super::*;
 
+    #[test]
+    fn fallback_names() {
+        let icon = Icon::with_fallback_names("dialog-question");
+        assert!(matches!(icon, Icon::Names(names) if names == ["dialog-question", "dialog"]));
+        let icon = Icon::with_fallback_names("application-x-executable");
+        assert!(matches!(
+            icon,
+            Icon::Names(names) if names == ["application-x-executable", "application-x", "application"]
+        ));
+        let icon = Icon::with_fallback_names("symbolic");
+        assert!(matches!(icon, Icon::Names(names) if names == ["symbolic"]));
+    }
+
+    #[tokio::test]
+    async fn from_path() {
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+        let path = std::env::temp_dir().join(format!("ashpd-icon-{}.png", std::process::id()));
+        std::fs::write(&path, &png).unwrap();
+        let icon = Icon::from_path(&path).await.unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(icon.as_bytes(), Some(png.as_slice()));
+        assert_eq!(icon.bytes_type(), Some(IconType::Png));
+
+        assert!(matches!(Icon::from_path(&path).await, Err(Error::IO(_))));
+
+        // The icons larger than the portals accept are not read.
+        std::fs::write(&path, vec![0; MAX_ICON_SIZE as usize + 1]).unwrap();
+        let err = Icon::from_path(&path).await;
+        std::fs::remove_file(&path).unwrap();
+        assert!(
+            matches!(err, Err(Error::IO(err)) if err.kind() == std::io::ErrorKind::InvalidData)
+        );
+    }
+
+    #[tokio::test]
+    async fn themed_icon() {
+        let dir = std::env::temp_dir().join(format!("ashpd-icons-{}", std::process::id()));
+        let theme = dir.join("icons/hicolor");
+        for icon in [
+            "48x48/apps/app.png",
+            "scalable/apps/app.svg",
+            "scalable/status/other.svg",
+        ] {
+            let path = theme.join(icon);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, b"icon").unwrap();
+        }
+        let data_dirs = [PathBuf::from("/nonexistent"), dir.clone()];
+
+        assert_eq!(
+            themed_icon_path(&data_dirs, "app", 48).await,
+            Some(theme.join("48x48/apps/app.png"))
+        );
+        assert_eq!(
+            themed_icon_path(&data_dirs, "app", 64).await,
+            Some(theme.join("scalable/apps/app.svg"))
+        );
+        assert_eq!(
+            themed_icon_path(&data_dirs, "other", 48).await,
+            Some(theme.join("scalable/status/other.svg"))
+        );
+        assert_eq!(themed_icon_path(&data_dirs, "missing", 48).await, None);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(matches!(
+            Icon::from_theme("../app", 48).await,
+            Err(Error::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn bytes_type() {
+        let svg = br#"<?xml version="1.0"?><svg xmlns="http://www.w3.org/2000/svg"/>"#;
+        assert_eq!(Icon::with_bytes(&svg[..]).bytes_type(), Some(IconType::Svg));
+        let jpeg = [0xff, 0xd8, 0xff, 0xe0, 0, 0x10];
+        assert_eq!(
+            Icon::with_bytes(&jpeg[..]).bytes_type(),
+            Some(IconType::Jpeg)
+        );
+        assert_eq!(Icon::with_bytes(vec![1, 0, 1, 0]).bytes_type(), None);
+        assert_eq!(Icon::with_names(["dialog"]).bytes_type(), None);
+        assert_eq!(Icon::with_names(["dialog"]).as_bytes(), None);
+    }
+
     #[test]
     fn check_icon_signature() {
         assert_eq!(Icon::SIGNATURE, "(sv)");
@@ -239,10 +504,15 @@ mod test {
         let decoded: Icon = encoded.deserialize().unwrap().0;
         assert!(matches!(decoded, Icon::Uri(_)));
 
-        let icon = Icon::Bytes(vec![1, 0, 1, 0]);
+        let icon = Icon::with_bytes(vec![1, 0, 1, 0]);
         let encoded = to_bytes(ctxt, &icon).unwrap();
         let decoded: Icon = encoded.deserialize().unwrap().0;
-        assert!(matches!(decoded, Icon::Bytes(_)));
+        assert!(matches!(decoded, Icon::Bytes(bytes) if *bytes == [1, 0, 1, 0]));
+
+        let encoded = to_bytes(ctxt, &icon.as_value()).unwrap();
+        let decoded: OwnedValue = encoded.deserialize().unwrap().0;
+        let decoded = Icon::try_from(decoded).unwrap();
+        assert!(matches!(decoded, Icon::Bytes(bytes) if *bytes == [1, 0, 1, 0]));
 
         let fd = std::fs::File::open("/tmp").unwrap();
         let icon = Icon::FileDescriptor(fd.as_fd().try_clone_to_owned().unwrap());
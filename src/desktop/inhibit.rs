@@ -36,9 +36,9 @@
 
 use enumflags2::{bitflags, BitFlags};
 use futures_util::{Stream, TryFutureExt};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
-use zbus::zvariant::{DeserializeDict, ObjectPath, OwnedObjectPath, SerializeDict, Type};
+use zbus::zvariant::{ObjectPath, OwnedObjectPath, SerializeDict, Type};
 
 use super::{session::SessionPortal, HandleToken, Request, Session};
 use crate::{desktop::session::CreateSessionResponse, proxy::Proxy, Error, WindowIdentifier};
@@ -56,11 +56,11 @@ struct CreateMonitorOptions {
 #[derive(SerializeDict, Type, Debug, Default)]
 /// Specified options for a [`InhibitProxy::inhibit`] request.
 #[zvariant(signature = "dict")]
-struct InhibitOptions {
+struct InhibitOptions<'a> {
     /// A string that will be used as the last element of the handle.
     handle_token: HandleToken,
     /// User-visible reason for the inhibition.
-    reason: Option<String>,
+    reason: Option<&'a str>,
 }
 
 #[bitflags]
@@ -83,16 +83,17 @@ pub enum InhibitFlags {
     Idle,
 }
 
-#[derive(Debug, DeserializeDict, Type)]
-#[zvariant(signature = "dict")]
-struct State {
-    #[zvariant(rename = "screensaver-active")]
-    screensaver_active: bool,
-    #[zvariant(rename = "session-state")]
-    session_state: SessionState,
+crate::helpers::dict! {
+    #[derive(Debug)]
+    struct State {
+        #[serde(rename = "screensaver-active")]
+        screensaver_active: bool,
+        #[serde(rename = "session-state")]
+        session_state: SessionState,
+    }
 }
 
-#[derive(Debug, Deserialize, Type)]
+#[derive(Debug, Serialize, Deserialize, Type)]
 /// A response received when the `state_changed` signal is received.
 pub struct InhibitState(OwnedObjectPath, State);
 
@@ -166,12 +167,13 @@ impl<'a> InhibitProxy<'a> {
         let options = CreateMonitorOptions::default();
         let identifier = identifier.map(|i| i.to_string()).unwrap_or_default();
         let body = &(&identifier, &options);
-        let (monitor, proxy) = futures_util::try_join!(
+        let (monitor, proxy) = futures_util::future::try_join(
             self.0
                 .request::<CreateSessionResponse>(&options.handle_token, "CreateMonitor", body)
                 .into_future(),
             Session::from_unique_name(&options.session_handle_token).into_future(),
-        )?;
+        )
+        .await?;
         assert_eq!(proxy.path(), &monitor.response()?.session_handle.as_ref());
         Ok(proxy)
     }
@@ -196,7 +198,7 @@ impl<'a> InhibitProxy<'a> {
         reason: &str,
     ) -> Result<Request<()>, Error> {
         let options = InhibitOptions {
-            reason: Some(reason.to_owned()),
+            reason: Some(reason),
             handle_token: Default::default(),
         };
         let identifier = identifier.map(|i| i.to_string()).unwrap_or_default();
@@ -248,5 +250,26 @@ impl<'a> std::ops::Deref for InhibitProxy<'a> {
     }
 }
 
+impl crate::Versioned for InhibitProxy<'_> {
+    fn version(&self) -> u32 {
+        self.0.version()
+    }
+}
+
+impl crate::Timeout for InhibitProxy<'_> {
+    fn set_timeout(&mut self, timeout: impl Into<Option<std::time::Duration>>) {
+        self.0.set_timeout(timeout.into());
+    }
+}
+
 impl crate::Sealed for InhibitProxy<'_> {}
 impl SessionPortal for InhibitProxy<'_> {}
+
+#[cfg(test)]
+pub(crate) const SIGNATURES: &[crate::proxy::WrapperSignature] = crate::proxy::wrapper_signatures! {
+    "org.freedesktop.portal.Inhibit" {
+        "CreateMonitor": (&str, &CreateMonitorOptions) => zbus::zvariant::OwnedObjectPath,
+        "Inhibit": (&str, BitFlags<InhibitFlags>, &InhibitOptions<'_>) => zbus::zvariant::OwnedObjectPath,
+        "QueryEndResponse": crate::desktop::Session<'_, InhibitProxy<'_>> => (),
+    }
+};
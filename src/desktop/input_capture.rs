@@ -271,11 +271,9 @@ use std::{collections::HashMap, num::NonZeroU32, os::fd::OwnedFd};
 
 use enumflags2::{bitflags, BitFlags};
 use futures_util::{Stream, TryFutureExt};
-use serde::{de::Visitor, Deserialize};
+use serde::{de::Visitor, Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
-use zbus::zvariant::{
-    self, DeserializeDict, ObjectPath, OwnedObjectPath, OwnedValue, SerializeDict, Type, Value,
-};
+use zbus::zvariant::{self, ObjectPath, OwnedObjectPath, OwnedValue, SerializeDict, Type, Value};
 
 use super::{session::SessionPortal, HandleToken, Request, Session};
 use crate::{proxy::Proxy, Error, WindowIdentifier};
@@ -301,11 +299,12 @@ struct CreateSessionOptions {
     capabilities: BitFlags<Capabilities>,
 }
 
-#[derive(Debug, DeserializeDict, Type)]
-#[zvariant(signature = "dict")]
-struct CreateSessionResponse {
-    session_handle: OwnedObjectPath,
-    capabilities: BitFlags<Capabilities>,
+crate::helpers::dict! {
+    #[derive(Debug)]
+    struct CreateSessionResponse {
+        session_handle: OwnedObjectPath,
+        capabilities: BitFlags<Capabilities>,
+    }
 }
 
 #[derive(Default, Debug, SerializeDict, Type)]
@@ -336,7 +335,7 @@ struct ReleaseOptions {
 }
 
 /// Indicates that an input capturing session was disabled.
-#[derive(Debug, Deserialize, Type)]
+#[derive(Debug, Serialize, Deserialize, Type)]
 #[zvariant(signature = "(oa{sv})")]
 pub struct Disabled(OwnedObjectPath, HashMap<String, OwnedValue>);
 
@@ -352,14 +351,15 @@ impl Disabled {
     }
 }
 
-#[derive(Debug, DeserializeDict, Type)]
-#[zvariant(signature = "dict")]
-struct DeactivatedOptions {
-    activation_id: Option<u32>,
+crate::helpers::dict! {
+    #[derive(Debug)]
+    struct DeactivatedOptions {
+        activation_id: Option<u32>,
+    }
 }
 
 /// Indicates that an input capturing session was deactivated.
-#[derive(Debug, Deserialize, Type)]
+#[derive(Debug, Serialize, Deserialize, Type)]
 #[zvariant(signature = "(oa{sv})")]
 pub struct Deactivated(OwnedObjectPath, DeactivatedOptions);
 
@@ -376,16 +376,17 @@ impl Deactivated {
     }
 }
 
-#[derive(Debug, DeserializeDict, Type)]
-#[zvariant(signature = "dict")]
-struct ActivatedOptions {
-    activation_id: Option<u32>,
-    cursor_position: Option<(f32, f32)>,
-    barrier_id: Option<ActivatedBarrier>,
+crate::helpers::dict! {
+    #[derive(Debug)]
+    struct ActivatedOptions {
+        activation_id: Option<u32>,
+        cursor_position: Option<(f32, f32)>,
+        barrier_id: Option<ActivatedBarrier>,
+    }
 }
 
 /// Indicates that an input capturing session was activated.
-#[derive(Debug, Deserialize, Type)]
+#[derive(Debug, Serialize, Deserialize, Type)]
 #[zvariant(signature = "(oa{sv})")]
 pub struct Activated(OwnedObjectPath, ActivatedOptions);
 
@@ -423,6 +424,18 @@ pub enum ActivatedBarrier {
     UnknownBarrier,
 }
 
+impl Serialize for ActivatedBarrier {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            Self::Barrier(id) => serializer.serialize_u32(id.get()),
+            Self::UnknownBarrier => serializer.serialize_u32(0),
+        }
+    }
+}
+
 impl<'de> Deserialize<'de> for ActivatedBarrier {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -451,16 +464,25 @@ impl Visitor<'_> for ActivatedBarrierVisitor {
             None => Ok(ActivatedBarrier::UnknownBarrier),
         }
     }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        let v = u32::try_from(v).map_err(E::custom)?;
+        self.visit_u32(v)
+    }
 }
 
-#[derive(Debug, DeserializeDict, Type)]
-#[zvariant(signature = "dict")]
-struct ZonesChangedOptions {
-    zone_set: Option<u32>,
+crate::helpers::dict! {
+    #[derive(Debug)]
+    struct ZonesChangedOptions {
+        zone_set: Option<u32>,
+    }
 }
 
 /// Indicates that zones available to this session changed.
-#[derive(Debug, Deserialize, Type)]
+#[derive(Debug, Serialize, Deserialize, Type)]
 #[zvariant(signature = "(oa{sv})")]
 pub struct ZonesChanged(OwnedObjectPath, ZonesChangedOptions);
 
@@ -477,7 +499,7 @@ impl ZonesChanged {
 }
 
 /// A region of a [`Zones`].
-#[derive(Debug, Clone, Copy, Deserialize, Type)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type)]
 #[zvariant(signature = "(uuii)")]
 pub struct Region(u32, u32, i32, i32);
 
@@ -503,12 +525,13 @@ impl Region {
     }
 }
 
-/// A response of [`InputCapture::zones`].
-#[derive(Debug, Type, DeserializeDict)]
-#[zvariant(signature = "dict")]
-pub struct Zones {
-    zones: Vec<Region>,
-    zone_set: u32,
+crate::helpers::dict! {
+    /// A response of [`InputCapture::zones`].
+    #[derive(Debug)]
+    pub struct Zones {
+        zones: Vec<Region>,
+        zone_set: u32,
+    }
 }
 
 impl Zones {
@@ -544,11 +567,12 @@ impl Barrier {
     }
 }
 
-/// A response to [`InputCapture::set_pointer_barriers`]
-#[derive(Debug, DeserializeDict, Type)]
-#[zvariant(signature = "dict")]
-pub struct SetPointerBarriersResponse {
-    failed_barriers: Vec<BarrierID>,
+crate::helpers::dict! {
+    /// A response to [`InputCapture::set_pointer_barriers`]
+    #[derive(Debug)]
+    pub struct SetPointerBarriersResponse {
+        failed_barriers: Vec<BarrierID>,
+    }
 }
 
 impl SetPointerBarriersResponse {
@@ -585,16 +609,17 @@ impl<'a> InputCapture<'a> {
             capabilities,
         };
         let identifier = identifier.map(|i| i.to_string()).unwrap_or_default();
-        let (request, proxy) = futures_util::try_join!(
+        let (request, proxy) = futures_util::future::try_join(
             self.0
                 .request::<CreateSessionResponse>(
                     &options.handle_token,
                     "CreateSession",
-                    (identifier, &options)
+                    (identifier, &options),
                 )
                 .into_future(),
             Session::from_unique_name(&options.session_handle_token).into_future(),
-        )?;
+        )
+        .await?;
         let response = request.response()?;
         assert_eq!(proxy.path(), &response.session_handle.as_ref());
         Ok((proxy, response.capabilities))
@@ -700,6 +725,20 @@ impl<'a> InputCapture<'a> {
         self.0.signal("Disabled").await
     }
 
+    /// Same as [`Self::receive_disabled`] but only receives the signals of
+    /// `session`.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`Disabled`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.InputCapture.html#org-freedesktop-portal-inputcapture-disabled).
+    #[doc(alias = "Disabled")]
+    pub async fn receive_disabled_for_session(
+        &self,
+        session: &Session<'_, Self>,
+    ) -> Result<impl Stream<Item = Disabled>, Error> {
+        self.0.signal_for_session("Disabled", session.path()).await
+    }
+
     /// Signal emitted when input capture starts and
     /// input events are about to be sent to the application.
     ///
@@ -711,6 +750,20 @@ impl<'a> InputCapture<'a> {
         self.0.signal("Activated").await
     }
 
+    /// Same as [`Self::receive_activated`] but only receives the signals of
+    /// `session`.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`Activated`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.InputCapture.html#org-freedesktop-portal-inputcapture-activated).
+    #[doc(alias = "Activated")]
+    pub async fn receive_activated_for_session(
+        &self,
+        session: &Session<'_, Self>,
+    ) -> Result<impl Stream<Item = Activated>, Error> {
+        self.0.signal_for_session("Activated", session.path()).await
+    }
+
     /// Signal emitted when input capture stopped and input events
     /// are no longer sent to the application.
     ///
@@ -722,6 +775,22 @@ impl<'a> InputCapture<'a> {
         self.0.signal("Deactivated").await
     }
 
+    /// Same as [`Self::receive_deactivated`] but only receives the signals of
+    /// `session`.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`Deactivated`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.InputCapture.html#org-freedesktop-portal-inputcapture-deactivated).
+    #[doc(alias = "Deactivated")]
+    pub async fn receive_deactivated_for_session(
+        &self,
+        session: &Session<'_, Self>,
+    ) -> Result<impl Stream<Item = Deactivated>, Error> {
+        self.0
+            .signal_for_session("Deactivated", session.path())
+            .await
+    }
+
     /// Signal emitted when the set of zones available to this session change.
     ///
     /// # Specifications
@@ -732,6 +801,22 @@ impl<'a> InputCapture<'a> {
         self.0.signal("ZonesChanged").await
     }
 
+    /// Same as [`Self::receive_zones_changed`] but only receives the signals of
+    /// `session`.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`ZonesChanged`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.InputCapture.html#org-freedesktop-portal-inputcapture-zoneschanged).
+    #[doc(alias = "ZonesChanged")]
+    pub async fn receive_zones_changed_for_session(
+        &self,
+        session: &Session<'_, Self>,
+    ) -> Result<impl Stream<Item = ZonesChanged>, Error> {
+        self.0
+            .signal_for_session("ZonesChanged", session.path())
+            .await
+    }
+
     /// Supported capabilities.
     ///
     /// # Specifications
@@ -751,5 +836,30 @@ impl<'a> std::ops::Deref for InputCapture<'a> {
     }
 }
 
+impl crate::Versioned for InputCapture<'_> {
+    fn version(&self) -> u32 {
+        self.0.version()
+    }
+}
+
+impl crate::Timeout for InputCapture<'_> {
+    fn set_timeout(&mut self, timeout: impl Into<Option<std::time::Duration>>) {
+        self.0.set_timeout(timeout.into());
+    }
+}
+
 impl crate::Sealed for InputCapture<'_> {}
 impl SessionPortal for InputCapture<'_> {}
+
+#[cfg(test)]
+pub(crate) const SIGNATURES: &[crate::proxy::WrapperSignature] = crate::proxy::wrapper_signatures! {
+    "org.freedesktop.portal.InputCapture" {
+        "CreateSession": (String, &CreateSessionOptions) => zbus::zvariant::OwnedObjectPath,
+        "GetZones": (crate::desktop::Session<'_, InputCapture<'_>>, &GetZonesOptions) => zbus::zvariant::OwnedObjectPath,
+        "SetPointerBarriers": (crate::desktop::Session<'_, InputCapture<'_>>, &SetPointerBarriersOptions, &[Barrier], u32) => zbus::zvariant::OwnedObjectPath,
+        "Enable": (crate::desktop::Session<'_, InputCapture<'_>>, &EnableOptions) => (),
+        "Disable": (crate::desktop::Session<'_, InputCapture<'_>>, &DisableOptions) => (),
+        "Release": (crate::desktop::Session<'_, InputCapture<'_>>, &ReleaseOptions) => (),
+        "ConnectToEIS": (crate::desktop::Session<'_, InputCapture<'_>>, std::collections::HashMap<&str, zbus::zvariant::Value<'_>>) => zbus::zvariant::OwnedFd,
+    }
+};
@@ -10,12 +10,13 @@
 //!         .create_session(None, None, Some(Accuracy::Street))
 //!         .await?;
 //!     let mut stream = proxy.receive_location_updated().await?;
-//!     let (_, location) = futures_util::join!(
+//!     let (_, location) = futures_util::future::join(
 //!         proxy
 //!             .start(&session, None)
 //!             .map(|e| e.expect("Couldn't start session")),
-//!         stream.next().map(|e| e.expect("Stream is exhausted"))
-//!     );
+//!         stream.next().map(|e| e.expect("Stream is exhausted")),
+//!     )
+//!     .await;
 //!     println!("{}", location.accuracy());
 //!     println!("{}", location.longitude());
 //!     println!("{}", location.latitude());
@@ -27,9 +28,9 @@
 use std::fmt::Debug;
 
 use futures_util::{Stream, TryFutureExt};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_repr::Serialize_repr;
-use zbus::zvariant::{DeserializeDict, ObjectPath, OwnedObjectPath, SerializeDict, Type};
+use zbus::zvariant::{ObjectPath, OwnedObjectPath, SerializeDict, Type};
 
 use super::{session::SessionPortal, HandleToken, Request, Session};
 use crate::{proxy::Proxy, Error, WindowIdentifier};
@@ -85,7 +86,9 @@ struct SessionStartOptions {
     handle_token: HandleToken,
 }
 
-#[derive(Deserialize, Type)]
+#[derive(Serialize, Deserialize, Clone, Type)]
+#[cfg_attr(feature = "glib", derive(glib::Boxed))]
+#[cfg_attr(feature = "glib", boxed_type(name = "AshpdLocation"))]
 /// The response received on a `location_updated` signal.
 pub struct Location(OwnedObjectPath, LocationInner);
 
@@ -168,25 +171,26 @@ impl Debug for Location {
     }
 }
 
-#[derive(Debug, SerializeDict, DeserializeDict, Type)]
-#[zvariant(signature = "dict")]
-struct LocationInner {
-    #[zvariant(rename = "Accuracy")]
-    accuracy: f64,
-    #[zvariant(rename = "Altitude")]
-    altitude: f64,
-    #[zvariant(rename = "Speed")]
-    speed: f64,
-    #[zvariant(rename = "Heading")]
-    heading: f64,
-    #[zvariant(rename = "Description")]
-    description: String,
-    #[zvariant(rename = "Latitude")]
-    latitude: f64,
-    #[zvariant(rename = "Longitude")]
-    longitude: f64,
-    #[zvariant(rename = "Timestamp")]
-    timestamp: (u64, u64),
+crate::helpers::dict! {
+    #[derive(Clone, Debug)]
+    struct LocationInner {
+        #[serde(rename = "Accuracy")]
+        accuracy: f64,
+        #[serde(rename = "Altitude")]
+        altitude: f64,
+        #[serde(rename = "Speed")]
+        speed: f64,
+        #[serde(rename = "Heading")]
+        heading: f64,
+        #[serde(rename = "Description")]
+        description: String,
+        #[serde(rename = "Latitude")]
+        latitude: f64,
+        #[serde(rename = "Longitude")]
+        longitude: f64,
+        #[serde(rename = "Timestamp")]
+        timestamp: (u64, u64),
+    }
 }
 
 /// The interface lets sandboxed applications query basic information about the
@@ -215,6 +219,22 @@ impl<'a> LocationProxy<'a> {
         self.0.signal("LocationUpdated").await
     }
 
+    /// Same as [`Self::receive_location_updated`] but only receives the signals of
+    /// `session`.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`LocationUpdated`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Location.html#org-freedesktop-portal-location-locationupdated).
+    #[doc(alias = "LocationUpdated")]
+    pub async fn receive_location_updated_for_session(
+        &self,
+        session: &Session<'_, Self>,
+    ) -> Result<impl Stream<Item = Location>, Error> {
+        self.0
+            .signal_for_session("LocationUpdated", session.path())
+            .await
+    }
+
     /// Create a location session.
     ///
     /// # Arguments
@@ -241,12 +261,13 @@ impl<'a> LocationProxy<'a> {
             accuracy,
             ..Default::default()
         };
-        let (path, proxy) = futures_util::try_join!(
+        let (path, proxy) = futures_util::future::try_join(
             self.0
                 .call::<OwnedObjectPath>("CreateSession", &(options))
                 .into_future(),
             Session::from_unique_name(&options.session_handle_token).into_future(),
-        )?;
+        )
+        .await?;
         assert_eq!(proxy.path(), &path.into_inner());
         Ok(proxy)
     }
@@ -292,3 +313,23 @@ impl<'a> std::ops::Deref for LocationProxy<'a> {
         &self.0
     }
 }
+
+impl crate::Versioned for LocationProxy<'_> {
+    fn version(&self) -> u32 {
+        self.0.version()
+    }
+}
+
+impl crate::Timeout for LocationProxy<'_> {
+    fn set_timeout(&mut self, timeout: impl Into<Option<std::time::Duration>>) {
+        self.0.set_timeout(timeout.into());
+    }
+}
+
+#[cfg(test)]
+pub(crate) const SIGNATURES: &[crate::proxy::WrapperSignature] = crate::proxy::wrapper_signatures! {
+    "org.freedesktop.portal.Location" {
+        "CreateSession": CreateSessionOptions => zbus::zvariant::OwnedObjectPath,
+        "Start": (crate::desktop::Session<'_, LocationProxy<'_>>, &str, &SessionStartOptions) => zbus::zvariant::OwnedObjectPath,
+    }
+};
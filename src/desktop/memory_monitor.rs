@@ -19,7 +19,7 @@
 
 use futures_util::Stream;
 
-use crate::{proxy::Proxy, Error};
+use crate::{proxy::Proxy, Error, SignalEvent};
 
 /// The interface provides information about low system memory to sandboxed
 /// applications.
@@ -50,6 +50,15 @@ impl<'a> MemoryMonitor<'a> {
     pub async fn receive_low_memory_warning(&self) -> Result<impl Stream<Item = i32>, Error> {
         self.0.signal("LowMemoryWarning").await
     }
+
+    /// Similar to [Self::receive_low_memory_warning] but keeps receiving the
+    /// warnings once the portal restarted.
+    #[doc(alias = "LowMemoryWarning")]
+    pub async fn receive_low_memory_warning_reconnecting(
+        &self,
+    ) -> Result<impl Stream<Item = SignalEvent<i32>> + Unpin + 'a, Error> {
+        self.0.signal_reconnecting("LowMemoryWarning").await
+    }
 }
 
 impl<'a> std::ops::Deref for MemoryMonitor<'a> {
@@ -59,3 +68,15 @@ impl<'a> std::ops::Deref for MemoryMonitor<'a> {
         &self.0
     }
 }
+
+impl crate::Versioned for MemoryMonitor<'_> {
+    fn version(&self) -> u32 {
+        self.0.version()
+    }
+}
+
+impl crate::Timeout for MemoryMonitor<'_> {
+    fn set_timeout(&mut self, timeout: impl Into<Option<std::time::Duration>>) {
+        self.0.set_timeout(timeout.into());
+    }
+}
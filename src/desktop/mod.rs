@@ -10,6 +10,8 @@ pub use self::{
     request::{Request, Response, ResponseError, ResponseType},
     session::{Session, SessionPortal},
 };
+#[cfg(test)]
+pub(crate) use session::SIGNATURES as SESSION_SIGNATURES;
 mod color;
 pub use color::Color;
 mod icon;
@@ -19,8 +21,9 @@ pub mod account;
 pub mod background;
 pub mod camera;
 pub mod clipboard;
-#[deprecated = "The portal does not serve any purpose as nothing really can make use of it as is."]
+#[deprecated = "The portal does not serve any purpose as nothing really can make use of it as is. Use `device_access` instead."]
 pub mod device;
+pub mod device_access;
 pub mod dynamic_launcher;
 pub mod email;
 /// Open/save file(s) chooser.
@@ -61,6 +64,32 @@ pub mod settings;
 pub mod trash;
 pub mod wallpaper;
 
+/// Whether the portal frontend implements `interface`, like
+/// `org.freedesktop.portal.Screenshot`.
+///
+/// Applications can use it to hide the features relying on a missing portal
+/// instead of failing with [`Error::PortalNotFound`](crate::Error::PortalNotFound)
+/// once used.
+///
+/// ```rust,no_run
+/// use ashpd::desktop::is_portal_available;
+///
+/// async fn run() -> ashpd::Result<()> {
+///     if is_portal_available("org.freedesktop.portal.Screenshot").await? {
+///         println!("Screenshots can be taken");
+///     }
+///     Ok(())
+/// }
+/// ```
+pub async fn is_portal_available(interface: &str) -> Result<bool, crate::Error> {
+    crate::proxy::Proxy::exists(
+        interface,
+        crate::proxy::DESKTOP_PATH,
+        crate::proxy::DESKTOP_DESTINATION,
+    )
+    .await
+}
+
 #[cfg_attr(feature = "glib", derive(glib::Enum))]
 #[cfg_attr(feature = "glib", enum_type(name = "AshpdPersistMode"))]
 #[derive(
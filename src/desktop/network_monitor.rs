@@ -19,22 +19,23 @@
 
 use std::fmt;
 
-use futures_util::Stream;
-use serde_repr::Deserialize_repr;
-use zbus::zvariant::{DeserializeDict, Type};
-
-use crate::{proxy::Proxy, Error};
-
-#[derive(DeserializeDict, Type, Debug)]
-/// The network status, composed of the availability, metered & connectivity
-#[zvariant(signature = "dict")]
-pub struct NetworkStatus {
-    /// Whether the network is considered available.
-    available: bool,
-    /// Whether the network is considered metered.
-    metered: bool,
-    /// More detailed information about the host's network connectivity
-    connectivity: Connectivity,
+use futures_util::{future::Either, Stream, StreamExt};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+use zbus::zvariant::Type;
+
+use crate::{proxy::Proxy, Error, SignalEvent};
+
+crate::helpers::dict! {
+    #[derive(Debug)]
+    /// The network status, composed of the availability, metered & connectivity
+    pub struct NetworkStatus {
+        /// Whether the network is considered available.
+        available: bool,
+        /// Whether the network is considered metered.
+        metered: bool,
+        /// More detailed information about the host's network connectivity
+        connectivity: Connectivity,
+    }
 }
 
 impl NetworkStatus {
@@ -56,7 +57,7 @@ impl NetworkStatus {
 
 #[cfg_attr(feature = "glib", derive(glib::Enum))]
 #[cfg_attr(feature = "glib", enum_type(name = "AshpdConnectivity"))]
-#[derive(Deserialize_repr, PartialEq, Eq, Debug, Clone, Copy, Type)]
+#[derive(Serialize_repr, Deserialize_repr, PartialEq, Eq, Debug, Clone, Copy, Type)]
 #[repr(u32)]
 /// Host's network activity
 pub enum Connectivity {
@@ -70,6 +71,20 @@ pub enum Connectivity {
     FullNetwork = 4,
 }
 
+impl TryFrom<u32> for Connectivity {
+    type Error = Error;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(Self::Local),
+            2 => Ok(Self::Limited),
+            3 => Ok(Self::CaptivePortal),
+            4 => Ok(Self::FullNetwork),
+            _ => Err(Error::ParseError("Failed to parse connectivity")),
+        }
+    }
+}
+
 impl fmt::Display for Connectivity {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let connectivity = match self {
@@ -88,6 +103,10 @@ impl fmt::Display for Connectivity {
 /// interaction. Applications are expected to use this interface indirectly,
 /// via a library API such as the GLib [`gio::NetworkMonitor`](https://gtk-rs.org/gtk-rs-core/stable/latest/docs/gio/struct.NetworkMonitor.html) interface.
 ///
+/// The interface changed across its versions, the wrapper uses the
+/// properties of the 1st version and composes [`NetworkMonitor::status`] out
+/// of the separate getters of the 2nd one when talking to older portals.
+///
 /// Wrapper of the DBus interface: [`org.freedesktop.portal.NetworkMonitor`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.NetworkMonitor.html).
 #[derive(Debug)]
 #[doc(alias = "org.freedesktop.portal.NetworkMonitor")]
@@ -126,10 +145,7 @@ impl<'a> NetworkMonitor<'a> {
     /// That is, whether the system as a default route for at least one of IPv4
     /// or IPv6.
     ///
-    /// # Required version
-    ///
-    /// The method requires the 2nd version implementation of the portal and
-    /// would fail with [`Error::RequiresVersion`] otherwise.
+    /// The `available` property is used with the 1st version of the portal.
     ///
     /// # Specifications
     ///
@@ -137,15 +153,16 @@ impl<'a> NetworkMonitor<'a> {
     #[doc(alias = "GetAvailable")]
     #[doc(alias = "get_available")]
     pub async fn is_available(&self) -> Result<bool, Error> {
-        self.0.call_versioned("GetAvailable", &(), 2).await
+        if self.0.version() >= 2 {
+            self.0.call("GetAvailable", &()).await
+        } else {
+            self.0.property("available").await
+        }
     }
 
     /// Returns more detailed information about the host's network connectivity.
     ///
-    /// # Required version
-    ///
-    /// The method requires the 2nd version implementation of the portal and
-    /// would fail with [`Error::RequiresVersion`] otherwise.
+    /// The `connectivity` property is used with the 1st version of the portal.
     ///
     /// # Specifications
     ///
@@ -153,17 +170,18 @@ impl<'a> NetworkMonitor<'a> {
     #[doc(alias = "GetConnectivity")]
     #[doc(alias = "get_connectivity")]
     pub async fn connectivity(&self) -> Result<Connectivity, Error> {
-        self.0.call_versioned("GetConnectivity", &(), 2).await
+        if self.0.version() >= 2 {
+            self.0.call("GetConnectivity", &()).await
+        } else {
+            self.0.property::<u32>("connectivity").await?.try_into()
+        }
     }
 
     /// Returns whether the network is considered metered.
     /// That is, whether the system as traffic flowing through the default
     /// connection that is subject to limitations by service providers.
     ///
-    /// # Required version
-    ///
-    /// The method requires the 2nd version implementation of the portal and
-    /// would fail with [`Error::RequiresVersion`] otherwise.
+    /// The `metered` property is used with the 1st version of the portal.
     ///
     /// # Specifications
     ///
@@ -171,15 +189,17 @@ impl<'a> NetworkMonitor<'a> {
     #[doc(alias = "GetMetered")]
     #[doc(alias = "get_metered")]
     pub async fn is_metered(&self) -> Result<bool, Error> {
-        self.0.call_versioned("GetMetered", &(), 2).await
+        if self.0.version() >= 2 {
+            self.0.call("GetMetered", &()).await
+        } else {
+            self.0.property("metered").await
+        }
     }
 
     /// Returns the three values all at once.
     ///
-    /// # Required version
-    ///
-    /// The method requires the 3nd version implementation of the portal and
-    /// would fail with [`Error::RequiresVersion`] otherwise.
+    /// The values are retrieved one by one with the older versions of the
+    /// portal.
     ///
     /// # Specifications
     ///
@@ -187,16 +207,58 @@ impl<'a> NetworkMonitor<'a> {
     #[doc(alias = "GetStatus")]
     #[doc(alias = "get_status")]
     pub async fn status(&self) -> Result<NetworkStatus, Error> {
-        self.0.call_versioned("GetStatus", &(), 3).await
+        if self.0.version() >= 3 {
+            return self.0.call("GetStatus", &()).await;
+        }
+        Ok(NetworkStatus {
+            available: self.is_available().await?,
+            metered: self.is_metered().await?,
+            connectivity: self.connectivity().await?,
+        })
     }
 
     /// Emitted when the network configuration changes.
     ///
+    /// The availability sent along the signal by the 1st version of the
+    /// portal is ignored.
+    ///
     /// # Specifications
     ///
     /// See also [`changed`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.NetworkMonitor.html#org-freedesktop-portal-networkmonitor-changed).
     pub async fn receive_changed(&self) -> Result<impl Stream<Item = ()>, Error> {
-        self.0.signal("changed").await
+        if self.0.version() >= 2 {
+            Ok(Either::Left(self.0.signal::<()>("changed").await?))
+        } else {
+            Ok(Either::Right(
+                self.0.signal::<bool>("changed").await?.map(|_| ()),
+            ))
+        }
+    }
+
+    /// Similar to [Self::receive_changed] but keeps receiving the changes
+    /// once the portal restarted.
+    ///
+    /// The network configuration might have changed while the portal was not
+    /// running, it should be read again on [`SignalEvent::Reconnected`].
+    #[doc(alias = "changed")]
+    pub async fn receive_changed_reconnecting(
+        &self,
+    ) -> Result<impl Stream<Item = SignalEvent<()>> + Unpin + 'a, Error> {
+        if self.0.version() >= 2 {
+            Ok(Either::Left(
+                self.0.signal_reconnecting::<()>("changed").await?,
+            ))
+        } else {
+            Ok(Either::Right(
+                self.0
+                    .signal_reconnecting::<bool>("changed")
+                    .await?
+                    .map(|event| match event {
+                        SignalEvent::Signal(_) => SignalEvent::Signal(()),
+                        SignalEvent::Reconnected => SignalEvent::Reconnected,
+                    }),
+            ))
+        }
     }
 }
 
@@ -207,3 +269,75 @@ impl<'a> std::ops::Deref for NetworkMonitor<'a> {
         &self.0
     }
 }
+
+impl crate::Versioned for NetworkMonitor<'_> {
+    fn version(&self) -> u32 {
+        self.0.version()
+    }
+}
+
+impl crate::Timeout for NetworkMonitor<'_> {
+    fn set_timeout(&mut self, timeout: impl Into<Option<std::time::Duration>>) {
+        self.0.set_timeout(timeout.into());
+    }
+}
+
+#[cfg(all(test, feature = "test"))]
+mod tests {
+    use super::{Connectivity, NetworkMonitor};
+    use crate::{
+        test::{MockPortal, MockResponse},
+        Error,
+    };
+
+    const INTERFACE: &str = "org.freedesktop.portal.NetworkMonitor";
+
+    #[tokio::test]
+    async fn version_1_properties() {
+        let _lock = crate::test::CONNECTION_LOCK.lock().await;
+        let mock = MockPortal::new().await.unwrap();
+        mock.set_property(INTERFACE, "available", true);
+        mock.set_property(INTERFACE, "metered", false);
+        mock.set_property(INTERFACE, "connectivity", 3u32);
+
+        let monitor = NetworkMonitor::new().await.unwrap();
+        let status = monitor.status().await.unwrap();
+        assert!(status.is_available());
+        assert!(!status.is_metered());
+        assert_eq!(status.connectivity(), Connectivity::CaptivePortal);
+        assert!(mock.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn version_2_getters() {
+        let _lock = crate::test::CONNECTION_LOCK.lock().await;
+        let mock = MockPortal::new().await.unwrap();
+        mock.set_version(INTERFACE, 2);
+        mock.respond(INTERFACE, "GetAvailable", MockResponse::reply((true,)));
+        mock.respond(INTERFACE, "GetMetered", MockResponse::reply((true,)));
+        mock.respond(INTERFACE, "GetConnectivity", MockResponse::reply((4u32,)));
+
+        let monitor = NetworkMonitor::new().await.unwrap();
+        let status = monitor.status().await.unwrap();
+        assert!(status.is_available());
+        assert!(status.is_metered());
+        assert_eq!(status.connectivity(), Connectivity::FullNetwork);
+        assert_eq!(mock.calls().len(), 3);
+
+        // `CanReach` was added in the 3rd version, it isn't called.
+        let err = monitor.can_reach("example.org", 80).await.unwrap_err();
+        assert!(matches!(err, Error::RequiresVersion(3, 2)));
+        assert_eq!(mock.calls().len(), 3);
+    }
+}
+
+#[cfg(test)]
+pub(crate) const SIGNATURES: &[crate::proxy::WrapperSignature] = crate::proxy::wrapper_signatures! {
+    "org.freedesktop.portal.NetworkMonitor" {
+        "CanReach": (&str, u32) => bool,
+        "GetAvailable": () => bool,
+        "GetConnectivity": () => Connectivity,
+        "GetMetered": () => bool,
+        "GetStatus": () => NetworkStatus,
+    }
+};
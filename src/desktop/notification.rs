@@ -50,11 +50,11 @@
 //! }
 //! ```
 
-use std::{fmt, os::fd::AsFd, str::FromStr};
+use std::{fmt, os::fd::AsFd, str::FromStr, sync::Mutex};
 
 use futures_util::Stream;
 use serde::{self, Deserialize, Serialize};
-use zbus::zvariant::{DeserializeDict, OwnedValue, SerializeDict, Type, Value};
+use zbus::zvariant::{OwnedValue, SerializeDict, Type, Value};
 
 use super::Icon;
 use crate::{proxy::Proxy, Error};
@@ -319,6 +319,28 @@ impl Notification {
         }
     }
 
+    /// Clones the notification, duplicating the file descriptors of its
+    /// icon and values.
+    pub fn try_clone(&self) -> Result<Self, Error> {
+        Ok(Self {
+            title: self.title.clone(),
+            body: self.body.clone(),
+            markup_body: self.markup_body.clone(),
+            icon: self.icon.as_ref().map(Icon::try_clone).transpose()?,
+            priority: self.priority,
+            default_action: self.default_action.clone(),
+            default_action_target: try_clone_value(&self.default_action_target)?,
+            buttons: self
+                .buttons
+                .as_ref()
+                .map(|buttons| buttons.iter().map(Button::try_clone).collect())
+                .transpose()?,
+            category: self.category.clone(),
+            display_hints: self.display_hints.clone(),
+            sound: try_clone_value(&self.sound)?,
+        })
+    }
+
     /// Sets the notification body.
     #[must_use]
     pub fn body<'a>(mut self, body: impl Into<Option<&'a str>>) -> Self {
@@ -405,6 +427,83 @@ impl Notification {
         };
         self
     }
+
+    /// Adapts the notification to the `version` of the portal, dropping the
+    /// fields it doesn't support.
+    ///
+    /// The markup body is turned into a plain text body when no body is
+    /// set. Returns the fields that were adapted.
+    pub fn downgrade(&mut self, version: u32) -> Vec<Downgrade> {
+        let mut downgrades = Vec::new();
+        if version >= 2 {
+            return downgrades;
+        }
+        if let Some(markup_body) = self.markup_body.take() {
+            if self.body.is_none() {
+                self.body = Some(strip_markup(&markup_body));
+            }
+            downgrades.push(Downgrade::MarkupBody);
+        }
+        if self.sound.take().is_some() {
+            downgrades.push(Downgrade::Sound);
+        }
+        if self.category.take().is_some() {
+            downgrades.push(Downgrade::Category);
+        }
+        if self.display_hints.take().is_some() {
+            downgrades.push(Downgrade::DisplayHints);
+        }
+        if matches!(self.icon, Some(Icon::FileDescriptor(_))) {
+            self.icon = None;
+            downgrades.push(Downgrade::Icon);
+        }
+        let mut purposes = false;
+        for button in self.buttons.iter_mut().flatten() {
+            purposes |= button.purpose.take().is_some();
+        }
+        if purposes {
+            downgrades.push(Downgrade::ButtonPurpose);
+        }
+        downgrades
+    }
+}
+
+/// A field of a [`Notification`] adapted to an older version of the portal,
+/// see [`Notification::downgrade`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Downgrade {
+    /// The markup body was dropped, or replaced by its plain text when no
+    /// body was set.
+    MarkupBody,
+    /// The sound was dropped.
+    Sound,
+    /// The category was dropped.
+    Category,
+    /// The display hints were dropped.
+    DisplayHints,
+    /// The file descriptor icon was dropped.
+    Icon,
+    /// The purposes of the buttons were dropped.
+    ButtonPurpose,
+}
+
+/// Removes the tags of a markup body and unescapes its entities.
+fn strip_markup(markup: &str) -> String {
+    let mut text = String::with_capacity(markup.len());
+    let mut in_tag = false;
+    for c in markup.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => (),
+        }
+    }
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Type)]
@@ -499,6 +598,15 @@ pub struct Button {
 }
 
 impl Button {
+    fn try_clone(&self) -> Result<Self, Error> {
+        Ok(Self {
+            label: self.label.clone(),
+            action: self.action.clone(),
+            target: try_clone_value(&self.target)?,
+            purpose: self.purpose.clone(),
+        })
+    }
+
     /// Create a new notification button.
     ///
     /// # Arguments
@@ -530,7 +638,11 @@ impl Button {
     }
 }
 
-#[derive(Debug, Deserialize, Type)]
+fn try_clone_value(value: &Option<OwnedValue>) -> Result<Option<OwnedValue>, Error> {
+    Ok(value.as_ref().map(OwnedValue::try_clone).transpose()?)
+}
+
+#[derive(Debug, Serialize, Deserialize, Type)]
 /// An invoked action.
 pub struct Action(String, String, Vec<OwnedValue>);
 
@@ -551,13 +663,15 @@ impl Action {
     }
 }
 
-#[derive(DeserializeDict, Type, Debug, OwnedValue)]
-#[zvariant(signature = "dict")]
-// TODO: figure out why this can't use the enums
-struct SupportedOptions {
-    category: Vec<String>,
-    #[zvariant(rename = "button-purpose")]
-    button_purpose: Vec<String>,
+crate::helpers::dict! {
+    #[derive(Debug, OwnedValue)]
+    // TODO: figure out why this can't use the enums
+    struct SupportedOptions {
+        category: Vec<String>,
+        #[serde(rename = "button-purpose")]
+        #[zvariant(rename = "button-purpose")]
+        button_purpose: Vec<String>,
+    }
 }
 
 /// The interface lets sandboxed applications send and withdraw notifications.
@@ -621,7 +735,26 @@ impl<'a> NotificationProxy<'a> {
         id: &str,
         notification: Notification,
     ) -> Result<(), Error> {
-        self.0.call("AddNotification", &(id, notification)).await
+        self.add_notification_downgraded(id, notification)
+            .await
+            .map(|_| ())
+    }
+
+    /// Same as [`NotificationProxy::add_notification`], returning the fields
+    /// that were adapted to the version of the portal.
+    ///
+    /// The fields introduced in the 2nd version of the portal are dropped
+    /// when sending to an older version, see [`Notification::downgrade`].
+    pub async fn add_notification_downgraded(
+        &self,
+        id: &str,
+        mut notification: Notification,
+    ) -> Result<Vec<Downgrade>, Error> {
+        let downgrades = notification.downgrade(self.0.version());
+        self.0
+            .call::<()>("AddNotification", &(id, notification))
+            .await?;
+        Ok(downgrades)
     }
 
     /// Withdraws a notification.
@@ -675,3 +808,258 @@ impl<'a> std::ops::Deref for NotificationProxy<'a> {
         &self.0
     }
 }
+
+impl crate::Versioned for NotificationProxy<'_> {
+    fn version(&self) -> u32 {
+        self.0.version()
+    }
+}
+
+impl crate::Timeout for NotificationProxy<'_> {
+    fn set_timeout(&mut self, timeout: impl Into<Option<std::time::Duration>>) {
+        self.0.set_timeout(timeout.into());
+    }
+}
+
+/// Keeps track of the notifications sent through a [`NotificationProxy`].
+///
+/// The portal doesn't tell which notifications of an application are still
+/// displayed. The manager remembers the ones it sent and didn't withdraw, so
+/// they can be withdrawn all at once, on shutdown for example, or sent again
+/// once the portal is restarted.
+///
+/// ```rust,no_run
+/// use ashpd::desktop::notification::{Notification, NotificationManager};
+/// use futures_util::StreamExt;
+///
+/// async fn run() -> ashpd::Result<()> {
+///     let manager = NotificationManager::new().await?;
+///     manager
+///         .add_notification("new-email", Notification::new("New email"))
+///         .await?;
+///
+///     // Send the notifications again whenever the portal is restarted.
+///     let mut owner_changed = manager.proxy().receive_owner_changed().await?;
+///     while let Some(owner) = owner_changed.next().await {
+///         if owner.is_some() {
+///             manager.resend_notifications().await?;
+///         }
+///     }
+///
+///     manager.remove_all_notifications().await?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct NotificationManager<'a> {
+    proxy: NotificationProxy<'a>,
+    active: Mutex<Vec<(String, Notification)>>,
+}
+
+impl<'a> NotificationManager<'a> {
+    /// Create a new instance of [`NotificationManager`].
+    pub async fn new() -> Result<NotificationManager<'a>, Error> {
+        Ok(NotificationProxy::new().await?.into())
+    }
+
+    /// The proxy used to send the notifications.
+    pub fn proxy(&self) -> &NotificationProxy<'a> {
+        &self.proxy
+    }
+
+    /// Sends a notification and remembers it, see
+    /// [`NotificationProxy::add_notification`].
+    pub async fn add_notification(
+        &self,
+        id: &str,
+        notification: Notification,
+    ) -> Result<(), Error> {
+        self.proxy
+            .add_notification(id, notification.try_clone()?)
+            .await?;
+        let mut active = self.active.lock().unwrap();
+        match active.iter_mut().find(|(active_id, _)| active_id == id) {
+            Some((_, active)) => *active = notification,
+            None => active.push((id.to_owned(), notification)),
+        }
+        Ok(())
+    }
+
+    /// Withdraws a notification and forgets it, see
+    /// [`NotificationProxy::remove_notification`].
+    pub async fn remove_notification(&self, id: &str) -> Result<(), Error> {
+        self.proxy.remove_notification(id).await?;
+        self.forget(id);
+        Ok(())
+    }
+
+    /// Withdraws all the notifications that are still active.
+    ///
+    /// All of them are withdrawn even if some fail, the first error is
+    /// returned and the failed ones are kept active.
+    pub async fn remove_all_notifications(&self) -> Result<(), Error> {
+        let mut result = Ok(());
+        for id in self.active_notifications() {
+            let removed = self.remove_notification(&id).await;
+            if result.is_ok() {
+                result = removed;
+            }
+        }
+        result
+    }
+
+    /// Sends the active notifications again, after a restart of the portal
+    /// for example.
+    pub async fn resend_notifications(&self) -> Result<(), Error> {
+        let active = self
+            .active
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(id, notification)| Ok((id.clone(), notification.try_clone()?)))
+            .collect::<Result<Vec<_>, Error>>()?;
+        for (id, notification) in active {
+            self.proxy.add_notification(&id, notification).await?;
+        }
+        Ok(())
+    }
+
+    /// Forgets a notification without withdrawing it, when it was dismissed
+    /// or one of its actions was invoked for example.
+    pub fn forget(&self, id: &str) {
+        self.active
+            .lock()
+            .unwrap()
+            .retain(|(active_id, _)| active_id != id);
+    }
+
+    /// The IDs of the active notifications, in the order they were sent.
+    pub fn active_notifications(&self) -> Vec<String> {
+        self.active
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+}
+
+impl<'a> From<NotificationProxy<'a>> for NotificationManager<'a> {
+    fn from(proxy: NotificationProxy<'a>) -> Self {
+        Self {
+            proxy,
+            active: Default::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) const SIGNATURES: &[crate::proxy::WrapperSignature] = crate::proxy::wrapper_signatures! {
+    "org.freedesktop.portal.Notification" {
+        "AddNotification": (&str, Notification) => (),
+        "RemoveNotification": &str => (),
+    }
+};
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "test")]
+    use super::NotificationManager;
+    use super::{Button, ButtonPurpose, Category, Downgrade, Notification};
+    #[cfg(feature = "test")]
+    use crate::test::{MockPortal, MockResponse};
+
+    #[test]
+    fn downgrade() {
+        let notification = Notification::new("Call")
+            .markup_body("<b>Alice</b> &amp; Bob")
+            .category(Category::IncomingCall)
+            .button(Button::new("Accept", "accept").purpose(ButtonPurpose::CallAccept));
+
+        let mut v2 = notification.try_clone().unwrap();
+        assert!(v2.downgrade(2).is_empty());
+        assert!(v2.markup_body.is_some());
+
+        let mut v1 = notification;
+        assert_eq!(
+            v1.downgrade(1),
+            [
+                Downgrade::MarkupBody,
+                Downgrade::Category,
+                Downgrade::ButtonPurpose
+            ]
+        );
+        assert_eq!(v1.body.as_deref(), Some("Alice & Bob"));
+        assert!(v1.markup_body.is_none());
+        assert!(v1.category.is_none());
+        assert!(v1.buttons.unwrap()[0].purpose.is_none());
+    }
+
+    #[test]
+    fn try_clone() {
+        use std::os::fd::{AsRawFd, OwnedFd};
+
+        use crate::desktop::Icon;
+
+        let (fd, _) = std::os::unix::net::UnixStream::pair().unwrap();
+        let notification = Notification::new("Download")
+            .icon(Icon::FileDescriptor(OwnedFd::from(fd)))
+            .button(Button::new("Open", "open").target("file.txt"));
+        let clone = notification.try_clone().unwrap();
+        let (Some(Icon::FileDescriptor(fd)), Some(Icon::FileDescriptor(cloned_fd))) =
+            (&notification.icon, &clone.icon)
+        else {
+            panic!("The icon is not a file descriptor");
+        };
+        assert_ne!(fd.as_raw_fd(), cloned_fd.as_raw_fd());
+        assert_eq!(
+            clone.buttons.unwrap()[0].target,
+            notification.buttons.unwrap()[0].target
+        );
+    }
+
+    #[cfg(feature = "test")]
+    #[tokio::test]
+    async fn notification_manager() {
+        let _lock = crate::test::CONNECTION_LOCK.lock().await;
+        let mock = MockPortal::new().await.unwrap();
+        let interface = "org.freedesktop.portal.Notification";
+        mock.respond(interface, "AddNotification", MockResponse::empty());
+        mock.respond(interface, "RemoveNotification", MockResponse::empty());
+
+        let manager = NotificationManager::new().await.unwrap();
+        for id in ["first", "second", "first", "third"] {
+            manager
+                .add_notification(id, Notification::new(id))
+                .await
+                .unwrap();
+        }
+        assert_eq!(manager.active_notifications(), ["first", "second", "third"]);
+
+        manager.remove_notification("second").await.unwrap();
+        manager.forget("third");
+        assert_eq!(manager.active_notifications(), ["first"]);
+
+        manager.resend_notifications().await.unwrap();
+        manager.remove_all_notifications().await.unwrap();
+        assert!(manager.active_notifications().is_empty());
+
+        let members = mock
+            .calls()
+            .iter()
+            .map(|call| call.header().member().unwrap().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            members,
+            [
+                "AddNotification",
+                "AddNotification",
+                "AddNotification",
+                "AddNotification",
+                "RemoveNotification",
+                "AddNotification",
+                "RemoveNotification",
+            ]
+        );
+    }
+}
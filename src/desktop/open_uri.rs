@@ -25,11 +25,10 @@
 //!
 //!
 //! ```rust,no_run
-//! use ashpd::desktop::open_uri::OpenFileRequest;
+//! use ashpd::{desktop::open_uri::OpenFileRequest, Uri};
 //!
 //! async fn run() -> ashpd::Result<()> {
-//!     let uri =
-//!         url::Url::parse("file:///home/bilelmoussaoui/Downloads/adwaita-night.jpg").unwrap();
+//!     let uri = Uri::from_file_path("/home/bilelmoussaoui/Downloads/adwaita-night.jpg")?;
 //!     OpenFileRequest::default().ask(true).send_uri(&uri).await?;
 //!     Ok(())
 //! }
@@ -51,13 +50,13 @@
 //! }
 //! ```
 
-use std::os::fd::AsFd;
+use std::{os::fd::AsFd, time::Duration};
 
 use url::Url;
 use zbus::zvariant::{Fd, SerializeDict, Type};
 
 use super::{HandleToken, Request};
-use crate::{proxy::Proxy, ActivationToken, Error, WindowIdentifier};
+use crate::{proxy::Proxy, ActivationToken, CancellationToken, Error, Feature, WindowIdentifier};
 
 #[derive(SerializeDict, Type, Debug, Default)]
 #[zvariant(signature = "dict")]
@@ -75,16 +74,35 @@ struct OpenFileOptions {
     activation_token: Option<ActivationToken>,
 }
 
+/// The interface lets sandboxed applications open URIs, files and directories
+/// with the applications chosen by the user.
+///
+/// The requests are sent with the builders, like [`OpenFileRequest`], the proxy
+/// gives access to the version of the interface.
+///
+/// Wrapper of the DBus interface: [`org.freedesktop.portal.OpenURI`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.OpenURI.html).
 #[derive(Debug)]
-struct OpenURIProxy<'a>(Proxy<'a>);
+#[doc(alias = "org.freedesktop.portal.OpenURI")]
+pub struct OpenURIProxy<'a>(Proxy<'a>);
 
 impl<'a> OpenURIProxy<'a> {
+    /// The [`OpenFileRequest::ask`] option.
+    pub const ASK: Feature = Feature::since(3);
+
+    /// The [`OpenDirectoryRequest`].
+    pub const OPEN_DIRECTORY: Feature = Feature::since(3);
+
+    /// The `activation_token` option of [`OpenFileRequest`] and
+    /// [`OpenDirectoryRequest`].
+    pub const ACTIVATION_TOKEN: Feature = Feature::since(4);
+
+    /// Create a new instance of [`OpenURIProxy`].
     pub async fn new() -> Result<OpenURIProxy<'a>, Error> {
         let proxy = Proxy::new_desktop("org.freedesktop.portal.OpenURI").await?;
         Ok(Self(proxy))
     }
 
-    pub async fn open_directory(
+    async fn open_directory(
         &self,
         identifier: Option<&WindowIdentifier>,
         directory: &impl AsFd,
@@ -100,7 +118,7 @@ impl<'a> OpenURIProxy<'a> {
             .await
     }
 
-    pub async fn open_file(
+    async fn open_file(
         &self,
         identifier: Option<&WindowIdentifier>,
         file: &impl AsFd,
@@ -116,7 +134,7 @@ impl<'a> OpenURIProxy<'a> {
             .await
     }
 
-    pub async fn open_uri(
+    async fn open_uri(
         &self,
         identifier: Option<&WindowIdentifier>,
         uri: &url::Url,
@@ -141,6 +159,18 @@ impl<'a> std::ops::Deref for OpenURIProxy<'a> {
     }
 }
 
+impl crate::Versioned for OpenURIProxy<'_> {
+    fn version(&self) -> u32 {
+        self.0.version()
+    }
+}
+
+impl crate::Timeout for OpenURIProxy<'_> {
+    fn set_timeout(&mut self, timeout: impl Into<Option<std::time::Duration>>) {
+        self.0.set_timeout(timeout.into());
+    }
+}
+
 #[derive(Debug, Default)]
 #[doc(alias = "org.freedesktop.portal.OpenURI")]
 #[doc(alias = "xdp_portal_open_uri")]
@@ -149,6 +179,9 @@ impl<'a> std::ops::Deref for OpenURIProxy<'a> {
 /// [builder-pattern]: https://doc.rust-lang.org/1.0.0/style/ownership/builders.html
 pub struct OpenFileRequest {
     identifier: Option<WindowIdentifier>,
+    timeout: Option<Duration>,
+    cancel_on_drop: bool,
+    cancellation: Option<CancellationToken>,
     options: OpenFileOptions,
 }
 
@@ -160,6 +193,30 @@ impl OpenFileRequest {
         self
     }
 
+    #[must_use]
+    /// Sets how long to wait for the request to complete, including the user
+    /// interaction. The request is closed once the timeout expires.
+    pub fn timeout(mut self, timeout: impl Into<Option<Duration>>) -> Self {
+        self.timeout = timeout.into();
+        self
+    }
+
+    #[must_use]
+    /// Sets whether to close the request if the future returned by `send` is
+    /// dropped before the user interaction ends, dismissing the dialog.
+    pub fn cancel_on_drop(mut self, cancel_on_drop: bool) -> Self {
+        self.cancel_on_drop = cancel_on_drop;
+        self
+    }
+
+    #[must_use]
+    /// Closes the request once `token` is cancelled, the request then fails
+    /// with [`ResponseError::Cancelled`](crate::desktop::ResponseError::Cancelled).
+    pub fn cancel_with(mut self, token: &CancellationToken) -> Self {
+        self.cancellation = Some(token.clone());
+        self
+    }
+
     #[must_use]
     /// Whether the file should be writeable or not.
     pub fn writeable(mut self, writeable: impl Into<Option<bool>>) -> Self {
@@ -186,7 +243,10 @@ impl OpenFileRequest {
 
     /// Send the request for a file.
     pub async fn send_file(self, file: &impl AsFd) -> Result<Request<()>, Error> {
-        let proxy = OpenURIProxy::new().await?;
+        let mut proxy = OpenURIProxy::new().await?;
+        proxy.0.set_timeout(self.timeout);
+        proxy.0.set_cancel_on_drop(self.cancel_on_drop);
+        proxy.0.set_cancellation(self.cancellation);
         proxy
             .open_file(self.identifier.as_ref(), file, self.options)
             .await
@@ -194,7 +254,10 @@ impl OpenFileRequest {
 
     /// Send the request for a URI.
     pub async fn send_uri(self, uri: &Url) -> Result<Request<()>, Error> {
-        let proxy = OpenURIProxy::new().await?;
+        let mut proxy = OpenURIProxy::new().await?;
+        proxy.0.set_timeout(self.timeout);
+        proxy.0.set_cancel_on_drop(self.cancel_on_drop);
+        proxy.0.set_cancellation(self.cancellation);
         proxy
             .open_uri(self.identifier.as_ref(), uri, self.options)
             .await
@@ -209,6 +272,9 @@ impl OpenFileRequest {
 /// [builder-pattern]: https://doc.rust-lang.org/1.0.0/style/ownership/builders.html
 pub struct OpenDirectoryRequest {
     identifier: Option<WindowIdentifier>,
+    timeout: Option<Duration>,
+    cancel_on_drop: bool,
+    cancellation: Option<CancellationToken>,
     options: OpenDirOptions,
 }
 
@@ -220,6 +286,30 @@ impl OpenDirectoryRequest {
         self
     }
 
+    #[must_use]
+    /// Sets how long to wait for the request to complete, including the user
+    /// interaction. The request is closed once the timeout expires.
+    pub fn timeout(mut self, timeout: impl Into<Option<Duration>>) -> Self {
+        self.timeout = timeout.into();
+        self
+    }
+
+    #[must_use]
+    /// Sets whether to close the request if the future returned by `send` is
+    /// dropped before the user interaction ends, dismissing the dialog.
+    pub fn cancel_on_drop(mut self, cancel_on_drop: bool) -> Self {
+        self.cancel_on_drop = cancel_on_drop;
+        self
+    }
+
+    #[must_use]
+    /// Closes the request once `token` is cancelled, the request then fails
+    /// with [`ResponseError::Cancelled`](crate::desktop::ResponseError::Cancelled).
+    pub fn cancel_with(mut self, token: &CancellationToken) -> Self {
+        self.cancellation = Some(token.clone());
+        self
+    }
+
     /// Sets the token that can be used to activate the chosen application.
     #[must_use]
     pub fn activation_token(
@@ -232,9 +322,21 @@ impl OpenDirectoryRequest {
 
     /// Send the request.
     pub async fn send(self, directory: &impl AsFd) -> Result<Request<()>, Error> {
-        let proxy = OpenURIProxy::new().await?;
+        let mut proxy = OpenURIProxy::new().await?;
+        proxy.0.set_timeout(self.timeout);
+        proxy.0.set_cancel_on_drop(self.cancel_on_drop);
+        proxy.0.set_cancellation(self.cancellation);
         proxy
             .open_directory(self.identifier.as_ref(), directory, self.options)
             .await
     }
 }
+
+#[cfg(test)]
+pub(crate) const SIGNATURES: &[crate::proxy::WrapperSignature] = crate::proxy::wrapper_signatures! {
+    "org.freedesktop.portal.OpenURI" {
+        "OpenDirectory": (&str, zbus::zvariant::Fd<'_>, &OpenDirOptions) => zbus::zvariant::OwnedObjectPath,
+        "OpenFile": (&str, zbus::zvariant::Fd<'_>, &OpenFileOptions) => zbus::zvariant::OwnedObjectPath,
+        "OpenURI": (&str, &url::Url, &OpenFileOptions) => zbus::zvariant::OwnedObjectPath,
+    }
+};
@@ -39,3 +39,15 @@ impl<'a> std::ops::Deref for PowerProfileMonitor<'a> {
         &self.0
     }
 }
+
+impl crate::Versioned for PowerProfileMonitor<'_> {
+    fn version(&self) -> u32 {
+        self.0.version()
+    }
+}
+
+impl crate::Timeout for PowerProfileMonitor<'_> {
+    fn set_timeout(&mut self, timeout: impl Into<Option<std::time::Duration>>) {
+        self.0.set_timeout(timeout.into());
+    }
+}
@@ -31,10 +31,15 @@
 //! }
 //! ```
 
-use std::{fmt, os::fd::AsFd, str::FromStr};
+use std::{
+    fmt,
+    io::{Seek, Write},
+    os::fd::{AsFd, BorrowedFd, FromRawFd, OwnedFd},
+    str::FromStr,
+};
 
 use serde::{Deserialize, Serialize};
-use zbus::zvariant::{DeserializeDict, Fd, SerializeDict, Type};
+use zbus::zvariant::{Fd, SerializeDict, Type};
 
 use super::{HandleToken, Request};
 use crate::{proxy::Proxy, Error, WindowIdentifier};
@@ -169,86 +174,87 @@ impl FromStr for Quality {
     }
 }
 
-#[derive(SerializeDict, DeserializeDict, Type, Debug, Default)]
-/// Print settings to set in the print dialog.
-#[zvariant(signature = "dict")]
-pub struct Settings {
-    /// One of landscape, portrait, reverse_landscape or reverse_portrait.
-    pub orientation: Option<Orientation>,
-    /// A paper name according to [PWG 5101.1-2002](ftp://ftp.pwg.org/pub/pwg/candidates/cs-pwgmsn10-20020226-5101.1.pdf)
-    #[zvariant(rename = "paper-format")]
-    pub paper_format: Option<String>,
-    /// Paper width, in millimeters.
-    #[zvariant(rename = "paper-width")]
-    pub paper_width: Option<String>,
-    /// Paper height, in millimeters.
-    #[zvariant(rename = "paper-height")]
-    pub paper_height: Option<String>,
-    /// The number of copies to print.
-    #[zvariant(rename = "n-copies")]
-    pub n_copies: Option<String>,
-    /// The default paper source.
-    #[zvariant(rename = "default-source")]
-    pub default_source: Option<String>,
-    /// Print quality.
-    pub quality: Option<Quality>,
-    /// The resolution, sets both resolution-x & resolution-y
-    pub resolution: Option<String>,
-    /// Whether to use color.
-    #[zvariant(rename = "use-color")]
-    pub use_color: Option<bool>,
-    /// Duplex printing mode, one of simplex, horizontal or vertical.
-    pub duplex: Option<String>,
-    /// Whether to collate copies.
-    pub collate: Option<String>,
-    /// Whether to reverse the order of printed pages.
-    pub reverse: Option<String>,
-    /// A media type according to [PWG 5101.1-2002](ftp://ftp.pwg.org/pub/pwg/candidates/cs-pwgmsn10-20020226-5101.1.pdf)
-    #[zvariant(rename = "media-type")]
-    pub media_type: Option<String>,
-    /// The dithering to use, one of fine, none, coarse, lineart, grayscale or
-    /// error-diffusion.
-    pub dither: Option<String>,
-    /// The scale in percent
-    pub scale: Option<String>,
-    /// What pages to print, one of all, selection, current or ranges.
-    #[zvariant(rename = "print-pages")]
-    pub print_pages: Option<String>,
-    /// A list of page ranges, formatted like this: 0-2,4,9-11.
-    #[zvariant(rename = "page-ranges")]
-    pub page_ranges: Option<String>,
-    /// What pages to print, one of all, even or odd.
-    #[zvariant(rename = "page-set")]
-    pub page_set: Option<String>,
-    /// The finishings.
-    pub finishings: Option<String>,
-    /// The number of pages per sheet.
-    #[zvariant(rename = "number-up")]
-    pub number_up: Option<String>,
-    /// One of lrtb, lrbt, rltb, rlbt, tblr, tbrl, btlr, btrl.
-    #[zvariant(rename = "number-up-layout")]
-    pub number_up_layout: Option<String>,
-    #[zvariant(rename = "output-bin")]
-    /// The output bin.
-    pub output_bin: Option<String>,
-    /// The horizontal resolution in dpi.
-    #[zvariant(rename = "resolution-x")]
-    pub resolution_x: Option<String>,
-    /// The vertical resolution in dpi.
-    #[zvariant(rename = "resolution-y")]
-    pub resolution_y: Option<String>,
-    /// The resolution in lpi (lines per inch).
-    #[zvariant(rename = "printer-lpi")]
-    pub print_lpi: Option<String>,
-    /// Basename to use for print-to-file.
-    #[zvariant(rename = "output-basename")]
-    pub output_basename: Option<String>,
-    /// Format to use for print-to-file, one of PDF, PS, SVG
-    #[zvariant(rename = "output-file-format")]
-    pub output_file_format: Option<String>,
-    /// The uri used for print-to file.
-    #[zvariant(rename = "output-uri")]
-    pub output_uri: Option<url::Url>,
+crate::helpers::dict! {
+    #[derive(Debug, Default)]
+    /// Print settings to set in the print dialog.
+    pub struct Settings {
+        /// One of landscape, portrait, reverse_landscape or reverse_portrait.
+        pub orientation: Option<Orientation>,
+        /// A paper name according to [PWG 5101.1-2002](ftp://ftp.pwg.org/pub/pwg/candidates/cs-pwgmsn10-20020226-5101.1.pdf)
+        #[serde(rename = "paper-format")]
+        pub paper_format: Option<String>,
+        /// Paper width, in millimeters.
+        #[serde(rename = "paper-width")]
+        pub paper_width: Option<String>,
+        /// Paper height, in millimeters.
+        #[serde(rename = "paper-height")]
+        pub paper_height: Option<String>,
+        /// The number of copies to print.
+        #[serde(rename = "n-copies")]
+        pub n_copies: Option<String>,
+        /// The default paper source.
+        #[serde(rename = "default-source")]
+        pub default_source: Option<String>,
+        /// Print quality.
+        pub quality: Option<Quality>,
+        /// The resolution, sets both resolution-x & resolution-y
+        pub resolution: Option<String>,
+        /// Whether to use color.
+        #[serde(rename = "use-color")]
+        pub use_color: Option<bool>,
+        /// Duplex printing mode, one of simplex, horizontal or vertical.
+        pub duplex: Option<String>,
+        /// Whether to collate copies.
+        pub collate: Option<String>,
+        /// Whether to reverse the order of printed pages.
+        pub reverse: Option<String>,
+        /// A media type according to [PWG 5101.1-2002](ftp://ftp.pwg.org/pub/pwg/candidates/cs-pwgmsn10-20020226-5101.1.pdf)
+        #[serde(rename = "media-type")]
+        pub media_type: Option<String>,
+        /// The dithering to use, one of fine, none, coarse, lineart, grayscale or
+        /// error-diffusion.
+        pub dither: Option<String>,
+        /// The scale in percent
+        pub scale: Option<String>,
+        /// What pages to print, one of all, selection, current or ranges.
+        #[serde(rename = "print-pages")]
+        pub print_pages: Option<String>,
+        /// A list of page ranges, formatted like this: 0-2,4,9-11.
+        #[serde(rename = "page-ranges")]
+        pub page_ranges: Option<String>,
+        /// What pages to print, one of all, even or odd.
+        #[serde(rename = "page-set")]
+        pub page_set: Option<String>,
+        /// The finishings.
+        pub finishings: Option<String>,
+        /// The number of pages per sheet.
+        #[serde(rename = "number-up")]
+        pub number_up: Option<String>,
+        /// One of lrtb, lrbt, rltb, rlbt, tblr, tbrl, btlr, btrl.
+        #[serde(rename = "number-up-layout")]
+        pub number_up_layout: Option<String>,
+        /// The output bin.
+        #[serde(rename = "output-bin")]
+        pub output_bin: Option<String>,
+        /// The horizontal resolution in dpi.
+        #[serde(rename = "resolution-x")]
+        pub resolution_x: Option<String>,
+        /// The vertical resolution in dpi.
+        #[serde(rename = "resolution-y")]
+        pub resolution_y: Option<String>,
+        /// The resolution in lpi (lines per inch).
+        #[serde(rename = "printer-lpi")]
+        pub print_lpi: Option<String>,
+        /// Basename to use for print-to-file.
+        #[serde(rename = "output-basename")]
+        pub output_basename: Option<String>,
+        /// Format to use for print-to-file, one of PDF, PS, SVG
+        #[serde(rename = "output-file-format")]
+        pub output_file_format: Option<String>,
+        /// The uri used for print-to file.
+        #[serde(rename = "output-uri")]
+        pub output_uri: Option<url::Url>,
+    }
 }
 
 impl Settings {
@@ -453,31 +459,32 @@ impl Settings {
     }
 }
 
-#[derive(SerializeDict, DeserializeDict, Type, Debug, Default)]
-/// Setup the printed pages.
-#[zvariant(signature = "dict")]
-pub struct PageSetup {
-    /// the PPD name. It's the name to select a given driver.
-    #[zvariant(rename = "PPDName")]
-    pub ppdname: Option<String>,
-    /// The name of the page setup.
-    pub name: Option<String>,
-    /// The user-visible name of the page setup.
-    pub display_name: Option<String>,
-    /// Paper width in millimeters.
-    pub width: Option<f64>,
-    /// Paper height in millimeters.
-    pub height: Option<f64>,
-    /// Top margin in millimeters.
-    pub margin_top: Option<f64>,
-    /// Bottom margin in millimeters.
-    pub margin_bottom: Option<f64>,
-    /// Right margin in millimeters.
-    pub margin_right: Option<f64>,
-    /// Left margin in millimeters.
-    pub margin_left: Option<f64>,
-    /// The page orientation.
-    pub orientation: Option<Orientation>,
+crate::helpers::dict! {
+    #[derive(Debug, Default)]
+    /// Setup the printed pages.
+    pub struct PageSetup {
+        /// the PPD name. It's the name to select a given driver.
+        #[serde(rename = "PPDName")]
+        pub ppdname: Option<String>,
+        /// The name of the page setup.
+        pub name: Option<String>,
+        /// The user-visible name of the page setup.
+        pub display_name: Option<String>,
+        /// Paper width in millimeters.
+        pub width: Option<f64>,
+        /// Paper height in millimeters.
+        pub height: Option<f64>,
+        /// Top margin in millimeters.
+        pub margin_top: Option<f64>,
+        /// Bottom margin in millimeters.
+        pub margin_bottom: Option<f64>,
+        /// Right margin in millimeters.
+        pub margin_right: Option<f64>,
+        /// Left margin in millimeters.
+        pub margin_left: Option<f64>,
+        /// The page orientation.
+        pub orientation: Option<Orientation>,
+    }
 }
 
 impl PageSetup {
@@ -555,16 +562,16 @@ impl PageSetup {
 #[derive(SerializeDict, Type, Debug, Default)]
 /// Specified options for a [`PrintProxy::prepare_print`] request.
 #[zvariant(signature = "dict")]
-struct PreparePrintOptions {
+struct PreparePrintOptions<'a> {
     /// A string that will be used as the last element of the handle.
     handle_token: HandleToken,
     /// Whether to make the dialog modal.
     modal: Option<bool>,
     /// Label for the accept button. Mnemonic underlines are allowed.
-    accept_label: Option<String>,
+    accept_label: Option<&'a str>,
 }
 
-impl PreparePrintOptions {
+impl<'a> PreparePrintOptions<'a> {
     /// Sets whether the dialog should be a modal.
     #[must_use]
     pub fn modal(mut self, modal: impl Into<Option<bool>>) -> Self {
@@ -574,8 +581,8 @@ impl PreparePrintOptions {
 
     /// Label for the accept button. Mnemonic underlines are allowed.
     #[must_use]
-    pub fn accept_label<'a>(mut self, accept_label: impl Into<Option<&'a str>>) -> Self {
-        self.accept_label = accept_label.into().map(ToOwned::to_owned);
+    pub fn accept_label(mut self, accept_label: impl Into<Option<&'a str>>) -> Self {
+        self.accept_label = accept_label.into();
         self
     }
 }
@@ -607,17 +614,69 @@ impl PrintOptions {
     }
 }
 
-#[derive(DeserializeDict, SerializeDict, Type, Debug)]
-/// A response to a [`PrintProxy::prepare_print`] request.
-#[zvariant(signature = "dict")]
-pub struct PreparePrint {
-    /// The printing settings.
-    pub settings: Settings,
-    #[zvariant(rename = "page-setup")]
-    /// The printed pages setup.
-    pub page_setup: PageSetup,
-    /// A token to pass to the print request.
-    pub token: u32,
+crate::helpers::dict! {
+    #[derive(Debug)]
+    /// A response to a [`PrintProxy::prepare_print`] request.
+    pub struct PreparePrint {
+        /// The printing settings.
+        pub settings: Settings,
+        /// The printed pages setup.
+        #[serde(rename = "page-setup")]
+        pub page_setup: PageSetup,
+        /// A token to pass to the print request.
+        pub token: u32,
+    }
+}
+
+/// A document printed by [`PrintProxy::print_documents`].
+#[derive(Debug, Clone, Copy)]
+pub enum PrintDocument<'a> {
+    /// A file descriptor open for reading the content to print.
+    Fd(BorrowedFd<'a>),
+    /// The content to print.
+    Bytes(&'a [u8]),
+}
+
+impl PrintDocument<'_> {
+    fn to_fd(self) -> std::io::Result<OwnedFd> {
+        match self {
+            Self::Fd(fd) => fd.try_clone_to_owned(),
+            Self::Bytes(data) => memfd(data),
+        }
+    }
+}
+
+impl<'a> From<BorrowedFd<'a>> for PrintDocument<'a> {
+    fn from(fd: BorrowedFd<'a>) -> Self {
+        Self::Fd(fd)
+    }
+}
+
+impl<'a> From<&'a std::fs::File> for PrintDocument<'a> {
+    fn from(file: &'a std::fs::File) -> Self {
+        Self::Fd(file.as_fd())
+    }
+}
+
+impl<'a> From<&'a [u8]> for PrintDocument<'a> {
+    fn from(data: &'a [u8]) -> Self {
+        Self::Bytes(data)
+    }
+}
+
+/// An anonymous file holding `data`, to send it as a file descriptor.
+fn memfd(data: &[u8]) -> std::io::Result<OwnedFd> {
+    // SAFETY: the name is a nul-terminated string.
+    let fd = unsafe { libc::memfd_create(b"ashpd-print\0".as_ptr().cast(), libc::MFD_CLOEXEC) };
+    if fd < 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    // SAFETY: `memfd_create` succeeded, so the file descriptor is open and
+    // owned by us.
+    let mut file = std::fs::File::from(unsafe { OwnedFd::from_raw_fd(fd) });
+    file.write_all(data)?;
+    file.rewind()?;
+    Ok(file.into())
 }
 
 /// The interface lets sandboxed applications print.
@@ -715,6 +774,77 @@ impl<'a> PrintProxy<'a> {
             )
             .await
     }
+
+    /// Prints several documents with the settings chosen once by the user.
+    ///
+    /// The print dialog is presented with [`PrintProxy::prepare_print`], then
+    /// the documents are printed in order with the returned token, so the user
+    /// is not prompted for each of them. A document failing to print doesn't
+    /// stop the following ones, the result of each document is returned along
+    /// with the chosen settings.
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier` - Identifier for the application window.
+    /// * `title` - Title for the print dialog.
+    /// * `settings` - [`Settings`].
+    /// * `page_setup` - [`PageSetup`].
+    /// * `documents` - The title and the content of the documents.
+    /// * `modal` - Whether the dialogs should be modal.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use std::fs::File;
+    ///
+    /// use ashpd::desktop::print::PrintProxy;
+    ///
+    /// async fn run() -> ashpd::Result<()> {
+    ///     let proxy = PrintProxy::new().await?;
+    ///     let invoice = File::open("invoice.pdf")?;
+    ///     let receipt = b"Paid".as_slice();
+    ///     let (_, results) = proxy
+    ///         .print_documents(
+    ///             None,
+    ///             "Print the invoices",
+    ///             Default::default(),
+    ///             Default::default(),
+    ///             [("invoice.pdf", (&invoice).into()), ("receipt", receipt.into())],
+    ///             true,
+    ///         )
+    ///         .await?;
+    ///     for result in results {
+    ///         result?;
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn print_documents<'d>(
+        &self,
+        identifier: Option<&WindowIdentifier>,
+        title: &str,
+        settings: Settings,
+        page_setup: PageSetup,
+        documents: impl IntoIterator<Item = (&'d str, PrintDocument<'d>)>,
+        modal: bool,
+    ) -> Result<(PreparePrint, Vec<Result<(), Error>>), Error> {
+        let prepared = self
+            .prepare_print(identifier, title, settings, page_setup, None, modal)
+            .await?
+            .response()?;
+        let mut results = Vec::new();
+        for (title, document) in documents {
+            let result = async {
+                let fd = document.to_fd()?;
+                self.print(identifier, title, &fd, Some(prepared.token), modal)
+                    .await?
+                    .response()
+            }
+            .await;
+            results.push(result);
+        }
+        Ok((prepared, results))
+    }
 }
 
 impl<'a> std::ops::Deref for PrintProxy<'a> {
@@ -724,3 +854,146 @@ impl<'a> std::ops::Deref for PrintProxy<'a> {
         &self.0
     }
 }
+
+impl crate::Versioned for PrintProxy<'_> {
+    fn version(&self) -> u32 {
+        self.0.version()
+    }
+}
+
+impl crate::Timeout for PrintProxy<'_> {
+    fn set_timeout(&mut self, timeout: impl Into<Option<std::time::Duration>>) {
+        self.0.set_timeout(timeout.into());
+    }
+}
+
+#[cfg(all(test, feature = "test"))]
+mod tests {
+    use std::{collections::HashMap, io::Read};
+
+    use zbus::zvariant::{serialized::Context, to_bytes, OwnedFd, OwnedValue, Value, LE};
+
+    use super::{Orientation, PageSetup, PrintDocument, PrintProxy, Quality, Settings};
+    use crate::test::{MockPortal, MockResponse};
+
+    const INTERFACE: &str = "org.freedesktop.portal.Print";
+
+    #[test]
+    fn settings_serialization() {
+        let settings = Settings::default()
+            .orientation(Orientation::Landscape)
+            .paper_format("iso_a4")
+            .n_copies("2")
+            .quality(Quality::High);
+        let encoded = to_bytes(Context::new_dbus(LE, 0), &settings).unwrap();
+
+        let dict: HashMap<String, OwnedValue> = encoded.deserialize().unwrap().0;
+        assert_eq!(dict.len(), 4);
+        assert_eq!(<&str>::try_from(&dict["orientation"]).unwrap(), "landscape");
+        assert_eq!(<&str>::try_from(&dict["paper-format"]).unwrap(), "iso_a4");
+        assert_eq!(<&str>::try_from(&dict["n-copies"]).unwrap(), "2");
+        assert_eq!(<&str>::try_from(&dict["quality"]).unwrap(), "high");
+
+        let settings: Settings = encoded.deserialize().unwrap().0;
+        assert_eq!(settings.orientation, Some(Orientation::Landscape));
+        assert_eq!(settings.paper_format.as_deref(), Some("iso_a4"));
+        assert_eq!(settings.n_copies.as_deref(), Some("2"));
+        assert_eq!(settings.quality, Some(Quality::High));
+        assert_eq!(settings.paper_width, None);
+    }
+
+    #[test]
+    fn page_setup_serialization() {
+        let page_setup = PageSetup::default()
+            .ppdname("A4")
+            .display_name("A4")
+            .width(210.0)
+            .margin_top(6.35)
+            .orientation(Orientation::Portrait);
+        let encoded = to_bytes(Context::new_dbus(LE, 0), &page_setup).unwrap();
+
+        let dict: HashMap<String, OwnedValue> = encoded.deserialize().unwrap().0;
+        assert_eq!(dict.len(), 5);
+        assert_eq!(<&str>::try_from(&dict["PPDName"]).unwrap(), "A4");
+        assert_eq!(<&str>::try_from(&dict["display_name"]).unwrap(), "A4");
+        assert_eq!(f64::try_from(&dict["width"]).unwrap(), 210.0);
+        assert_eq!(f64::try_from(&dict["margin_top"]).unwrap(), 6.35);
+        assert_eq!(<&str>::try_from(&dict["orientation"]).unwrap(), "portrait");
+
+        let page_setup: PageSetup = encoded.deserialize().unwrap().0;
+        assert_eq!(page_setup.ppdname.as_deref(), Some("A4"));
+        assert_eq!(page_setup.display_name.as_deref(), Some("A4"));
+        assert_eq!(page_setup.width, Some(210.0));
+        assert_eq!(page_setup.margin_top, Some(6.35));
+        assert_eq!(page_setup.orientation, Some(Orientation::Portrait));
+        assert_eq!(page_setup.height, None);
+    }
+
+    #[tokio::test]
+    async fn print_documents() {
+        let _lock = crate::test::CONNECTION_LOCK.lock().await;
+        let mock = MockPortal::new().await.unwrap();
+        mock.respond(
+            INTERFACE,
+            "PreparePrint",
+            MockResponse::success([
+                ("settings", Value::from(HashMap::<&str, Value<'_>>::new())),
+                ("page-setup", Value::from(HashMap::<&str, Value<'_>>::new())),
+                ("token", Value::from(42u32)),
+            ]),
+        );
+        mock.respond(INTERFACE, "Print", MockResponse::success([]));
+        mock.respond(INTERFACE, "Print", MockResponse::cancelled());
+
+        let (read, _write) = crate::io::pipe().unwrap();
+        let proxy = PrintProxy::new().await.unwrap();
+        let (prepared, results) = proxy
+            .print_documents(
+                None,
+                "Invoices",
+                Default::default(),
+                Default::default(),
+                [
+                    ("first", PrintDocument::from(b"first".as_slice())),
+                    ("second", PrintDocument::Fd(std::os::fd::AsFd::as_fd(&read))),
+                ],
+                false,
+            )
+            .await
+            .unwrap();
+        assert_eq!(prepared.token, 42);
+        assert!(results[0].is_ok());
+        assert!(results[1].is_err());
+
+        let calls = mock.calls();
+        assert_eq!(calls[0].header().member().unwrap(), "PreparePrint");
+        let prints = calls[1..]
+            .iter()
+            .map(|call| {
+                call.body()
+                    .deserialize::<(String, String, OwnedFd, HashMap<String, OwnedValue>)>()
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(prints.len(), 2);
+        for (print, title) in prints.iter().zip(["first", "second"]) {
+            assert_eq!(print.1, title);
+            assert_eq!(u32::try_from(&print.3["token"]).unwrap(), 42);
+        }
+        let mut content = String::new();
+        std::fs::File::from(std::os::fd::OwnedFd::from(
+            prints.into_iter().next().unwrap().2,
+        ))
+        .read_to_string(&mut content)
+        .unwrap();
+        assert_eq!(content, "first");
+    }
+}
+
+#[cfg(test)]
+pub(crate) const SIGNATURES: &[crate::proxy::WrapperSignature] = crate::proxy::wrapper_signatures! {
+    "org.freedesktop.portal.Print" {
+        "PreparePrint": (&str, &str, Settings, PageSetup, &PreparePrintOptions<'_>) => zbus::zvariant::OwnedObjectPath,
+        "Print": (&str, &str, zbus::zvariant::Fd<'_>, &PrintOptions) => zbus::zvariant::OwnedObjectPath,
+    }
+};
@@ -58,3 +58,22 @@ impl<'a> std::ops::Deref for ProxyResolver<'a> {
         &self.0
     }
 }
+
+impl crate::Versioned for ProxyResolver<'_> {
+    fn version(&self) -> u32 {
+        self.0.version()
+    }
+}
+
+impl crate::Timeout for ProxyResolver<'_> {
+    fn set_timeout(&mut self, timeout: impl Into<Option<std::time::Duration>>) {
+        self.0.set_timeout(timeout.into());
+    }
+}
+
+#[cfg(test)]
+pub(crate) const SIGNATURES: &[crate::proxy::WrapperSignature] = crate::proxy::wrapper_signatures! {
+    "org.freedesktop.portal.ProxyResolver" {
+        "Lookup": &url::Url => Vec<url::Url>,
+    }
+};
@@ -50,6 +50,44 @@ impl<'a> Realtime<'a> {
             .await
     }
 
+    /// Same as [`Realtime::max_thread_realtime_with_pid`] but targets
+    /// `process`.
+    ///
+    /// The portal only takes a PID, the call fails without reaching it if
+    /// `process` exited already and its PID might have been reused. The
+    /// process can still exit while the call is in flight.
+    #[cfg(target_os = "linux")]
+    #[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+    #[doc(alias = "MakeThreadRealtimeWithPID")]
+    pub async fn max_thread_realtime_with_process(
+        &self,
+        process: &crate::Process,
+        thread: u64,
+        priority: u32,
+    ) -> Result<(), Error> {
+        self.max_thread_realtime_with_pid(process.running_pid()?, thread, priority)
+            .await
+    }
+
+    /// Same as [`Realtime::max_thread_high_priority_with_pid`] but targets
+    /// `process`.
+    ///
+    /// The portal only takes a PID, the call fails without reaching it if
+    /// `process` exited already and its PID might have been reused. The
+    /// process can still exit while the call is in flight.
+    #[cfg(target_os = "linux")]
+    #[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+    #[doc(alias = "MakeThreadHighPriorityWithPID")]
+    pub async fn max_thread_high_priority_with_process(
+        &self,
+        process: &crate::Process,
+        thread: u64,
+        priority: i32,
+    ) -> Result<(), Error> {
+        self.max_thread_high_priority_with_pid(process.running_pid()?, thread, priority)
+            .await
+    }
+
     #[doc(alias = "MaxRealtimePriority")]
     #[allow(missing_docs)]
     pub async fn max_realtime_priority(&self) -> Result<i64, Error> {
@@ -76,3 +114,66 @@ impl<'a> std::ops::Deref for Realtime<'a> {
         &self.0
     }
 }
+
+impl crate::Versioned for Realtime<'_> {
+    fn version(&self) -> u32 {
+        self.0.version()
+    }
+}
+
+impl crate::Timeout for Realtime<'_> {
+    fn set_timeout(&mut self, timeout: impl Into<Option<std::time::Duration>>) {
+        self.0.set_timeout(timeout.into());
+    }
+}
+
+#[cfg(test)]
+pub(crate) const SIGNATURES: &[crate::proxy::WrapperSignature] = crate::proxy::wrapper_signatures! {
+    "org.freedesktop.portal.Realtime" {
+        "MakeThreadRealtimeWithPID": (u64, u64, u32) => (),
+        "MakeThreadHighPriorityWithPID": (u64, u64, i32) => (),
+    }
+};
+
+#[cfg(all(test, feature = "test", target_os = "linux"))]
+mod tests {
+    use super::Realtime;
+    use crate::{
+        test::{MockPortal, MockResponse},
+        Process,
+    };
+
+    const INTERFACE: &str = "org.freedesktop.portal.Realtime";
+
+    #[tokio::test]
+    async fn process() {
+        let _lock = crate::test::CONNECTION_LOCK.lock().await;
+        let mock = MockPortal::new().await.unwrap();
+        mock.respond(
+            INTERFACE,
+            "MakeThreadRealtimeWithPID",
+            MockResponse::empty(),
+        );
+
+        let realtime = Realtime::new().await.unwrap();
+        let current = Process::current().unwrap();
+        realtime
+            .max_thread_realtime_with_process(&current, 1, 10)
+            .await
+            .unwrap();
+        let calls = mock.calls();
+        assert_eq!(calls.len(), 1);
+        let (pid, thread, priority): (u64, u64, u32) = calls[0].body().deserialize().unwrap();
+        assert_eq!((pid, thread, priority), (std::process::id() as u64, 1, 10));
+
+        // The PID of an exited child is never sent.
+        let mut child = std::process::Command::new("true").spawn().unwrap();
+        let exited = Process::from_child(&child).unwrap();
+        child.wait().unwrap();
+        assert!(realtime
+            .max_thread_realtime_with_process(&exited, 1, 10)
+            .await
+            .is_err());
+        assert_eq!(mock.calls().len(), 1);
+    }
+}
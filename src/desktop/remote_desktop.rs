@@ -87,7 +87,7 @@ use std::{collections::HashMap, os::fd::OwnedFd};
 use enumflags2::{bitflags, BitFlags};
 use futures_util::TryFutureExt;
 use serde_repr::{Deserialize_repr, Serialize_repr};
-use zbus::zvariant::{self, DeserializeDict, SerializeDict, Type, Value};
+use zbus::zvariant::{self, SerializeDict, Type, Value};
 
 use super::{
     screencast::Stream, session::SessionPortal, HandleToken, PersistMode, Request, Session,
@@ -181,6 +181,17 @@ impl SelectDevicesOptions {
     }
 }
 
+#[derive(SerializeDict, Type, Debug, Default)]
+/// Specified options for the `Notify` methods of [`RemoteDesktop`].
+///
+/// Sent for each input event, it is serialized without any allocation.
+#[zvariant(signature = "dict")]
+struct NotifyOptions {
+    /// Whether it is the last axis event, only used by
+    /// [`RemoteDesktop::notify_pointer_axis`].
+    finish: Option<bool>,
+}
+
 #[derive(SerializeDict, Type, Debug, Default)]
 /// Specified options for a [`RemoteDesktop::start`] request.
 #[zvariant(signature = "dict")]
@@ -189,13 +200,14 @@ struct StartRemoteOptions {
     handle_token: HandleToken,
 }
 
-#[derive(DeserializeDict, Type, Debug, Default)]
-/// A response to a [`RemoteDesktop::select_devices`] request.
-#[zvariant(signature = "dict")]
-pub struct SelectedDevices {
-    devices: BitFlags<DeviceType>,
-    streams: Option<Vec<Stream>>,
-    restore_token: Option<String>,
+crate::helpers::dict! {
+    #[derive(Debug, Default)]
+    /// A response to a [`RemoteDesktop::select_devices`] request.
+    pub struct SelectedDevices {
+        devices: BitFlags<DeviceType>,
+        streams: Option<Vec<Stream>>,
+        restore_token: Option<String>,
+    }
 }
 
 impl SelectedDevices {
@@ -240,12 +252,13 @@ impl<'a> RemoteDesktop<'a> {
     #[doc(alias = "xdp_portal_create_remote_desktop_session")]
     pub async fn create_session(&self) -> Result<Session<'a, Self>, Error> {
         let options = CreateRemoteOptions::default();
-        let (request, proxy) = futures_util::try_join!(
+        let (request, proxy) = futures_util::future::try_join(
             self.0
                 .request::<CreateSessionResponse>(&options.handle_token, "CreateSession", &options)
                 .into_future(),
-            Session::from_unique_name(&options.session_handle_token).into_future()
-        )?;
+            Session::from_unique_name(&options.session_handle_token).into_future(),
+        )
+        .await?;
         assert_eq!(proxy.path(), &request.response()?.session_handle.as_ref());
         Ok(proxy)
     }
@@ -332,9 +345,7 @@ impl<'a> RemoteDesktop<'a> {
         keycode: i32,
         state: KeyState,
     ) -> Result<(), Error> {
-        // The `notify` methods don't take any options for now
-        // see https://github.com/flatpak/xdg-desktop-portal/blob/master/src/remote-desktop.c#L723
-        let options: HashMap<&str, Value<'_>> = HashMap::new();
+        let options = NotifyOptions::default();
         self.0
             .call("NotifyKeyboardKeycode", &(session, options, keycode, state))
             .await
@@ -362,9 +373,7 @@ impl<'a> RemoteDesktop<'a> {
         keysym: i32,
         state: KeyState,
     ) -> Result<(), Error> {
-        // The `notify` methods don't take any options for now
-        // see https://github.com/flatpak/xdg-desktop-portal/blob/master/src/remote-desktop.c#L723
-        let options: HashMap<&str, Value<'_>> = HashMap::new();
+        let options = NotifyOptions::default();
         self.0
             .call("NotifyKeyboardKeysym", &(session, options, keysym, state))
             .await
@@ -390,9 +399,7 @@ impl<'a> RemoteDesktop<'a> {
         session: &Session<'_, Self>,
         slot: u32,
     ) -> Result<(), Error> {
-        // The `notify` methods don't take any options for now
-        // see https://github.com/flatpak/xdg-desktop-portal/blob/master/src/remote-desktop.c#L723
-        let options: HashMap<&str, Value<'_>> = HashMap::new();
+        let options = NotifyOptions::default();
         self.0
             .call("NotifyTouchUp", &(session, options, slot))
             .await
@@ -426,9 +433,7 @@ impl<'a> RemoteDesktop<'a> {
         x: f64,
         y: f64,
     ) -> Result<(), Error> {
-        // The `notify` methods don't take any options for now
-        // see https://github.com/flatpak/xdg-desktop-portal/blob/master/src/remote-desktop.c#L723
-        let options: HashMap<&str, Value<'_>> = HashMap::new();
+        let options = NotifyOptions::default();
         self.0
             .call("NotifyTouchDown", &(session, options, stream, slot, x, y))
             .await
@@ -462,9 +467,7 @@ impl<'a> RemoteDesktop<'a> {
         x: f64,
         y: f64,
     ) -> Result<(), Error> {
-        // The `notify` methods don't take any options for now
-        // see https://github.com/flatpak/xdg-desktop-portal/blob/master/src/remote-desktop.c#L723
-        let options: HashMap<&str, Value<'_>> = HashMap::new();
+        let options = NotifyOptions::default();
         self.0
             .call("NotifyTouchMotion", &(session, options, stream, slot, x, y))
             .await
@@ -493,9 +496,7 @@ impl<'a> RemoteDesktop<'a> {
         x: f64,
         y: f64,
     ) -> Result<(), Error> {
-        // The `notify` methods don't take any options for now
-        // see https://github.com/flatpak/xdg-desktop-portal/blob/master/src/remote-desktop.c#L723
-        let options: HashMap<&str, Value<'_>> = HashMap::new();
+        let options = NotifyOptions::default();
         self.0
             .call(
                 "NotifyPointerMotionAbsolute",
@@ -525,9 +526,7 @@ impl<'a> RemoteDesktop<'a> {
         dx: f64,
         dy: f64,
     ) -> Result<(), Error> {
-        // The `notify` methods don't take any options for now
-        // see https://github.com/flatpak/xdg-desktop-portal/blob/master/src/remote-desktop.c#L723
-        let options: HashMap<&str, Value<'_>> = HashMap::new();
+        let options = NotifyOptions::default();
         self.0
             .call("NotifyPointerMotion", &(session, options, dx, dy))
             .await
@@ -557,9 +556,7 @@ impl<'a> RemoteDesktop<'a> {
         button: i32,
         state: KeyState,
     ) -> Result<(), Error> {
-        // The `notify` methods don't take any options for now
-        // see https://github.com/flatpak/xdg-desktop-portal/blob/master/src/remote-desktop.c#L723
-        let options: HashMap<&str, Value<'_>> = HashMap::new();
+        let options = NotifyOptions::default();
         self.0
             .call("NotifyPointerButton", &(session, options, button, state))
             .await
@@ -586,9 +583,7 @@ impl<'a> RemoteDesktop<'a> {
         axis: Axis,
         steps: i32,
     ) -> Result<(), Error> {
-        // The `notify` methods don't take any options for now
-        // see https://github.com/flatpak/xdg-desktop-portal/blob/master/src/remote-desktop.c#L723
-        let options: HashMap<&str, Value<'_>> = HashMap::new();
+        let options = NotifyOptions::default();
         self.0
             .call(
                 "NotifyPointerAxisDiscrete",
@@ -625,9 +620,9 @@ impl<'a> RemoteDesktop<'a> {
         dy: f64,
         finish: bool,
     ) -> Result<(), Error> {
-        // see https://github.com/flatpak/xdg-desktop-portal/blob/master/src/remote-desktop.c#L911
-        let mut options: HashMap<&str, Value<'_>> = HashMap::new();
-        options.insert("finish", Value::Bool(finish));
+        let options = NotifyOptions {
+            finish: Some(finish),
+        };
         self.0
             .call("NotifyPointerAxis", &(session, options, dx, dy))
             .await
@@ -671,7 +666,7 @@ impl<'a> RemoteDesktop<'a> {
     /// See also [`AvailableDeviceTypes`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.RemoteDesktop.html#org-freedesktop-portal-remotedesktop-availabledevicetypes).
     #[doc(alias = "AvailableDeviceTypes")]
     pub async fn available_device_types(&self) -> Result<BitFlags<DeviceType>, Error> {
-        self.0.property("AvailableDeviceTypes").await
+        self.0.cached_property("AvailableDeviceTypes").await
     }
 }
 
@@ -683,5 +678,37 @@ impl<'a> std::ops::Deref for RemoteDesktop<'a> {
     }
 }
 
+impl crate::Versioned for RemoteDesktop<'_> {
+    fn version(&self) -> u32 {
+        self.0.version()
+    }
+}
+
+impl crate::Timeout for RemoteDesktop<'_> {
+    fn set_timeout(&mut self, timeout: impl Into<Option<std::time::Duration>>) {
+        self.0.set_timeout(timeout.into());
+    }
+}
+
 impl crate::Sealed for RemoteDesktop<'_> {}
 impl SessionPortal for RemoteDesktop<'_> {}
+
+#[cfg(test)]
+pub(crate) const SIGNATURES: &[crate::proxy::WrapperSignature] = crate::proxy::wrapper_signatures! {
+    "org.freedesktop.portal.RemoteDesktop" {
+        "CreateSession": &CreateRemoteOptions => zbus::zvariant::OwnedObjectPath,
+        "SelectDevices": (crate::desktop::Session<'_, RemoteDesktop<'_>>, &SelectDevicesOptions) => zbus::zvariant::OwnedObjectPath,
+        "Start": (crate::desktop::Session<'_, RemoteDesktop<'_>>, &str, &StartRemoteOptions) => zbus::zvariant::OwnedObjectPath,
+        "NotifyKeyboardKeycode": (crate::desktop::Session<'_, RemoteDesktop<'_>>, NotifyOptions, i32, KeyState) => (),
+        "NotifyKeyboardKeysym": (crate::desktop::Session<'_, RemoteDesktop<'_>>, NotifyOptions, i32, KeyState) => (),
+        "NotifyTouchUp": (crate::desktop::Session<'_, RemoteDesktop<'_>>, NotifyOptions, u32) => (),
+        "NotifyTouchDown": (crate::desktop::Session<'_, RemoteDesktop<'_>>, NotifyOptions, u32, u32, f64, f64) => (),
+        "NotifyTouchMotion": (crate::desktop::Session<'_, RemoteDesktop<'_>>, NotifyOptions, u32, u32, f64, f64) => (),
+        "NotifyPointerMotionAbsolute": (crate::desktop::Session<'_, RemoteDesktop<'_>>, NotifyOptions, u32, f64, f64) => (),
+        "NotifyPointerMotion": (crate::desktop::Session<'_, RemoteDesktop<'_>>, NotifyOptions, f64, f64) => (),
+        "NotifyPointerButton": (crate::desktop::Session<'_, RemoteDesktop<'_>>, NotifyOptions, i32, KeyState) => (),
+        "NotifyPointerAxisDiscrete": (crate::desktop::Session<'_, RemoteDesktop<'_>>, NotifyOptions, Axis, i32) => (),
+        "NotifyPointerAxis": (crate::desktop::Session<'_, RemoteDesktop<'_>>, NotifyOptions, f64, f64) => (),
+        "ConnectToEIS": (crate::desktop::Session<'_, RemoteDesktop<'_>>, HashMap<&str, Value<'_>>) => zbus::zvariant::OwnedFd,
+    }
+};
@@ -216,9 +216,8 @@ where
         P: TryInto<ObjectPath<'static>>,
         P::Error: Into<zbus::Error>,
     {
-        let proxy = Proxy::new_desktop_with_path("org.freedesktop.portal.Request", path).await?;
-        // Start listening for a response signal the moment request is created
-        let stream = proxy.receive_signal("Response").await?;
+        let path = path.try_into().map_err(Into::into)?;
+        let (proxy, stream) = subscribe_response(path).await?;
         Ok(Self(proxy, stream, Default::default(), PhantomData))
     }
 
@@ -239,7 +238,11 @@ where
             Response::Ok(r) => Ok(r),
         };
         #[cfg(feature = "tracing")]
-        tracing::debug!("Received response {:#?}", response);
+        tracing::debug!(
+            "Received response {} {:#?}",
+            std::any::type_name::<T>(),
+            response
+        );
         let r = response as Result<T, Error>;
         *self.2.get_mut().unwrap() = Some(r);
         Ok(())
@@ -271,6 +274,46 @@ where
     pub(crate) fn path(&self) -> &ObjectPath<'_> {
         self.0.path()
     }
+
+    /// A guard closing the request when dropped, unless it is disarmed.
+    pub(crate) fn close_on_drop(&self) -> CloseOnDrop {
+        CloseOnDrop(Some(self.0.clone()))
+    }
+}
+
+/// Creates the proxy of the request at `path` and subscribes to its
+/// `Response` signal, outside of [`Request`] so that it is compiled once for
+/// all the response types.
+async fn subscribe_response(
+    path: ObjectPath<'static>,
+) -> Result<(Proxy<'static>, SignalStream<'static>), Error> {
+    let proxy = Proxy::new_desktop_with_path("org.freedesktop.portal.Request", path).await?;
+    // Start listening for a response signal the moment request is created
+    let stream = proxy.receive_signal("Response").await?;
+    Ok((proxy, stream))
+}
+
+/// Closes a pending request when dropped, see [`Request::close_on_drop`].
+pub(crate) struct CloseOnDrop(Option<Proxy<'static>>);
+
+impl CloseOnDrop {
+    /// Keeps the request open, once its response was received.
+    pub(crate) fn disarm(mut self) {
+        self.0 = None;
+    }
+}
+
+impl Drop for CloseOnDrop {
+    fn drop(&mut self) {
+        let Some(proxy) = self.0.take() else {
+            return;
+        };
+        let connection = proxy.connection().clone();
+        crate::helpers::spawn(&connection, "ashpd request close", async move {
+            // The request might have never been made or be over already.
+            let _ = proxy.call::<()>("Close", &()).await;
+        });
+    }
 }
 
 impl<T> Debug for Request<T>
@@ -284,6 +327,13 @@ where
     }
 }
 
+#[cfg(test)]
+pub(crate) const SIGNATURES: &[crate::proxy::WrapperSignature] = crate::proxy::wrapper_signatures! {
+    "org.freedesktop.portal.Request" {
+        "Close": () => (),
+    }
+};
+
 #[cfg(test)]
 mod tests {
     use zbus::zvariant::Value;
@@ -303,4 +353,41 @@ mod tests {
         );
         assert_eq!(Response::<()>::SIGNATURE, "(ua{sv})");
     }
+
+    #[cfg(feature = "test")]
+    #[tokio::test]
+    async fn cancel_on_drop() {
+        use std::time::Duration;
+
+        use crate::{
+            desktop::screenshot::Screenshot,
+            test::{MockPortal, MockResponse},
+        };
+
+        let _lock = crate::test::CONNECTION_LOCK.lock().await;
+        let mock = MockPortal::new().await.unwrap();
+        mock.respond(
+            "org.freedesktop.portal.Screenshot",
+            "Screenshot",
+            MockResponse::cancelled().delay(Duration::from_secs(1)),
+        );
+        let is_close =
+            |message: &zbus::Message| message.header().member().is_some_and(|m| m == "Close");
+
+        let request = Screenshot::request().send();
+        assert!(matches!(
+            crate::helpers::timeout(Duration::from_millis(50), request).await,
+            Err(crate::Error::Timeout)
+        ));
+        crate::test::sleep(Duration::from_millis(50)).await;
+        assert!(!mock.calls().iter().any(is_close));
+
+        let request = Screenshot::request().cancel_on_drop(true).send();
+        assert!(matches!(
+            crate::helpers::timeout(Duration::from_millis(50), request).await,
+            Err(crate::Error::Timeout)
+        ));
+        crate::test::sleep(Duration::from_millis(50)).await;
+        assert!(mock.calls().iter().any(is_close));
+    }
 }
@@ -39,10 +39,10 @@
 use std::{collections::HashMap, fmt::Debug, os::fd::OwnedFd};
 
 use enumflags2::{bitflags, BitFlags};
-use futures_util::TryFutureExt;
-use serde::Deserialize;
+use futures_util::{StreamExt, TryFutureExt};
+use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
-use zbus::zvariant::{self, DeserializeDict, SerializeDict, Type, Value};
+use zbus::zvariant::{self, SerializeDict, Type, Value};
 
 use super::{
     remote_desktop::RemoteDesktop, session::SessionPortal, HandleToken, PersistMode, Request,
@@ -81,7 +81,8 @@ pub enum CursorMode {
     Embedded,
     #[doc(alias = "XDP_CURSOR_MODE_METADATA")]
     /// The cursor is not part of the screen cast stream, but sent as PipeWire
-    /// stream metadata.
+    /// stream metadata. With the `pipewire` feature, `CursorUpdate` extracts
+    /// it from the stream buffers.
     Metadata,
 }
 
@@ -154,15 +155,28 @@ struct StartCastOptions {
     handle_token: HandleToken,
 }
 
-#[derive(DeserializeDict, Type)]
-/// A response to a [`Screencast::start`] request.
-#[zvariant(signature = "dict")]
-pub struct Streams {
-    streams: Vec<Stream>,
-    restore_token: Option<String>,
+crate::helpers::dict! {
+    #[derive(Clone)]
+    #[cfg_attr(feature = "glib", derive(glib::Boxed))]
+    #[cfg_attr(feature = "glib", boxed_type(name = "AshpdStreams"))]
+    /// A response to a [`Screencast::start`] request.
+    pub struct Streams {
+        streams: Vec<Stream>,
+        restore_token: Option<String>,
+    }
 }
 
 impl Streams {
+    #[cfg(feature = "backend")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "backend")))]
+    /// Create a new instance of the streams.
+    pub fn new<'a>(streams: Vec<Stream>, restore_token: impl Into<Option<&'a str>>) -> Self {
+        Self {
+            streams,
+            restore_token: restore_token.into().map(ToOwned::to_owned),
+        }
+    }
+
     /// The session restore token.
     pub fn restore_token(&self) -> Option<&str> {
         self.restore_token.as_deref()
@@ -183,11 +197,30 @@ impl Debug for Streams {
     }
 }
 
-#[derive(Clone, Deserialize, Type)]
+#[derive(Clone, Serialize, Deserialize, Type)]
+#[cfg_attr(feature = "glib", derive(glib::Boxed))]
+#[cfg_attr(feature = "glib", boxed_type(name = "AshpdStream"))]
 /// A PipeWire stream.
 pub struct Stream(u32, StreamProperties);
 
 impl Stream {
+    #[cfg(feature = "backend")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "backend")))]
+    /// Creates a new builder-pattern struct instance to construct [`Stream`]
+    /// for the PipeWire node `pipe_wire_node_id`.
+    pub fn builder(pipe_wire_node_id: u32) -> StreamBuilder {
+        StreamBuilder(Self(
+            pipe_wire_node_id,
+            StreamProperties {
+                id: None,
+                position: None,
+                size: None,
+                source_type: None,
+                mapping_id: None,
+            },
+        ))
+    }
+
     /// The PipeWire stream Node ID
     pub fn pipe_wire_node_id(&self) -> u32 {
         self.0
@@ -240,15 +273,65 @@ impl Debug for Stream {
             .finish()
     }
 }
-#[derive(Clone, DeserializeDict, Type, Debug)]
-/// The stream properties.
-#[zvariant(signature = "dict")]
-struct StreamProperties {
-    id: Option<String>,
-    position: Option<(i32, i32)>,
-    size: Option<(i32, i32)>,
-    source_type: Option<SourceType>,
-    mapping_id: Option<String>,
+#[cfg(feature = "backend")]
+#[cfg_attr(docsrs, doc(cfg(feature = "backend")))]
+/// A builder-pattern struct to construct a [`Stream`].
+#[derive(Debug)]
+pub struct StreamBuilder(Stream);
+
+#[cfg(feature = "backend")]
+impl StreamBuilder {
+    /// Sets the stream identifier.
+    #[must_use]
+    pub fn id<'a>(mut self, id: impl Into<Option<&'a str>>) -> Self {
+        self.0 .1.id = id.into().map(ToOwned::to_owned);
+        self
+    }
+
+    /// Sets the position of the stream in the compositor coordinate space.
+    #[must_use]
+    pub fn position(mut self, position: impl Into<Option<(i32, i32)>>) -> Self {
+        self.0 .1.position = position.into();
+        self
+    }
+
+    /// Sets the size of the stream in the compositor coordinate space.
+    #[must_use]
+    pub fn size(mut self, size: impl Into<Option<(i32, i32)>>) -> Self {
+        self.0 .1.size = size.into();
+        self
+    }
+
+    /// Sets the source type of the stream.
+    #[must_use]
+    pub fn source_type(mut self, source_type: impl Into<Option<SourceType>>) -> Self {
+        self.0 .1.source_type = source_type.into();
+        self
+    }
+
+    /// Sets the stream mapping id.
+    #[must_use]
+    pub fn mapping_id<'a>(mut self, mapping_id: impl Into<Option<&'a str>>) -> Self {
+        self.0 .1.mapping_id = mapping_id.into().map(ToOwned::to_owned);
+        self
+    }
+
+    /// Build the [`Stream`].
+    pub fn build(self) -> Stream {
+        self.0
+    }
+}
+
+crate::helpers::dict! {
+    #[derive(Clone, Debug)]
+    /// The stream properties.
+    struct StreamProperties {
+        id: Option<String>,
+        position: Option<(i32, i32)>,
+        size: Option<(i32, i32)>,
+        source_type: Option<SourceType>,
+        mapping_id: Option<String>,
+    }
 }
 
 /// The interface lets sandboxed applications create screen cast sessions.
@@ -274,12 +357,13 @@ impl<'a> Screencast<'a> {
     #[doc(alias = "xdp_portal_create_screencast_session")]
     pub async fn create_session(&self) -> Result<Session<'a, Self>, Error> {
         let options = CreateSessionOptions::default();
-        let (request, proxy) = futures_util::try_join!(
+        let (request, proxy) = futures_util::future::try_join(
             self.0
                 .request::<CreateSessionResponse>(&options.handle_token, "CreateSession", &options)
                 .into_future(),
             Session::from_unique_name(&options.session_handle_token).into_future(),
-        )?;
+        )
+        .await?;
         assert_eq!(proxy.path(), &request.response()?.session_handle.as_ref());
         Ok(proxy)
     }
@@ -380,13 +464,21 @@ impl<'a> Screencast<'a> {
         session: &Session<'_, impl HasScreencastSession>,
         identifier: Option<&WindowIdentifier>,
     ) -> Result<Request<Streams>, Error> {
-        let options = StartCastOptions::default();
         let identifier = identifier.map(|i| i.to_string()).unwrap_or_default();
+        self.start_with_handle(session, &identifier).await
+    }
+
+    async fn start_with_handle(
+        &self,
+        session: &Session<'_, impl HasScreencastSession>,
+        identifier: &str,
+    ) -> Result<Request<Streams>, Error> {
+        let options = StartCastOptions::default();
         self.0
             .request(
                 &options.handle_token,
                 "Start",
-                &(session, &identifier, &options),
+                &(session, identifier, &options),
             )
             .await
     }
@@ -398,7 +490,12 @@ impl<'a> Screencast<'a> {
     /// See also [`AvailableCursorModes`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.ScreenCast.html#org-freedesktop-portal-screencast-availablecursormodes).
     #[doc(alias = "AvailableCursorModes")]
     pub async fn available_cursor_modes(&self) -> Result<BitFlags<CursorMode>, Error> {
-        self.0.property_versioned("AvailableCursorModes", 2).await
+        crate::cache::get_or_fetch(
+            &self.0,
+            "AvailableCursorModes",
+            self.0.property_versioned("AvailableCursorModes", 2),
+        )
+        .await
     }
 
     /// Available source types.
@@ -408,7 +505,7 @@ impl<'a> Screencast<'a> {
     /// See also [`AvailableSourceTypes`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.ScreenCast.html#org-freedesktop-portal-screencast-availablesourcetypes).
     #[doc(alias = "AvailableSourceTypes")]
     pub async fn available_source_types(&self) -> Result<BitFlags<SourceType>, Error> {
-        self.0.property("AvailableSourceTypes").await
+        self.0.cached_property("AvailableSourceTypes").await
     }
 }
 
@@ -420,6 +517,18 @@ impl<'a> std::ops::Deref for Screencast<'a> {
     }
 }
 
+impl crate::Versioned for Screencast<'_> {
+    fn version(&self) -> u32 {
+        self.0.version()
+    }
+}
+
+impl crate::Timeout for Screencast<'_> {
+    fn set_timeout(&mut self, timeout: impl Into<Option<std::time::Duration>>) {
+        self.0.set_timeout(timeout.into());
+    }
+}
+
 impl crate::Sealed for Screencast<'_> {}
 impl SessionPortal for Screencast<'_> {}
 
@@ -427,3 +536,529 @@ impl SessionPortal for Screencast<'_> {}
 pub trait HasScreencastSession: SessionPortal {}
 impl HasScreencastSession for Screencast<'_> {}
 impl HasScreencastSession for RemoteDesktop<'_> {}
+
+/// A screen cast that is negotiated again each time its session gets closed,
+/// for example once the user stopped sharing or a shared monitor got
+/// unplugged.
+///
+/// The restore token of the previous negotiation is reused, so the portal
+/// can restore the selection without asking the user again.
+///
+/// ```rust,no_run
+/// use ashpd::desktop::screencast::{CursorMode, PersistentScreencast, SourceType};
+/// use futures_util::StreamExt;
+///
+/// async fn run() -> ashpd::Result<()> {
+///     let mut casts = PersistentScreencast::new(CursorMode::Embedded, SourceType::Monitor.into())
+///         .start(None)
+///         .await?;
+///     while let Some(cast) = casts.next().await {
+///         let (fd, streams) = cast?;
+///         println!("{} streams on the PipeWire remote {fd:?}", streams.streams().len());
+///     }
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct PersistentScreencast {
+    cursor_mode: CursorMode,
+    types: BitFlags<SourceType>,
+    multiple: bool,
+    restore_token: Option<String>,
+    persist_mode: PersistMode,
+}
+
+impl PersistentScreencast {
+    /// Create a new instance of [`PersistentScreencast`].
+    ///
+    /// The selection persists while the application is running by default.
+    pub fn new(cursor_mode: CursorMode, types: BitFlags<SourceType>) -> Self {
+        Self {
+            cursor_mode,
+            types,
+            multiple: false,
+            restore_token: None,
+            persist_mode: PersistMode::Application,
+        }
+    }
+
+    /// Sets whether to allow selecting multiple sources.
+    #[must_use]
+    pub fn multiple(mut self, multiple: bool) -> Self {
+        self.multiple = multiple;
+        self
+    }
+
+    /// Sets the restore token of a previous screen cast, for the first
+    /// negotiation.
+    #[must_use]
+    pub fn restore_token<'a>(mut self, token: impl Into<Option<&'a str>>) -> Self {
+        self.restore_token = token.into().map(ToOwned::to_owned);
+        self
+    }
+
+    /// Sets how long the selection persists.
+    ///
+    /// [`PersistMode::DoNot`] makes the user select the sources on each
+    /// negotiation.
+    #[must_use]
+    pub fn persist_mode(mut self, persist_mode: PersistMode) -> Self {
+        self.persist_mode = persist_mode;
+        self
+    }
+
+    /// Negotiates the screen cast, then keeps negotiating it again in the
+    /// background each time its session gets closed.
+    ///
+    /// Each negotiation is emitted by the returned [`PersistentStreams`].
+    /// Once a negotiation fails, the user dismissed the dialog for example,
+    /// its error is emitted and the screen cast ends.
+    pub async fn start(
+        mut self,
+        identifier: Option<&WindowIdentifier>,
+    ) -> Result<PersistentStreams, Error> {
+        let proxy = Screencast::new().await?;
+        let identifier = identifier.map(|i| i.to_string()).unwrap_or_default();
+        let (session, closed, fd, streams) = self.negotiate(&proxy, &identifier).await?;
+
+        let (sender, receiver) = futures_channel::mpsc::unbounded();
+        let (stop_sender, stop_receiver) = futures_channel::oneshot::channel();
+        let _ = sender.unbounded_send(Ok((fd, streams)));
+        let connection = proxy.connection().clone();
+        crate::helpers::spawn(
+            &connection,
+            "ashpd screencast renegotiation",
+            self.renegotiate(proxy, session, closed, identifier, sender, stop_receiver),
+        );
+        Ok(PersistentStreams {
+            receiver,
+            _stop: stop_sender,
+        })
+    }
+
+    async fn negotiate(
+        &mut self,
+        proxy: &Screencast<'static>,
+        identifier: &str,
+    ) -> Result<
+        (
+            Session<'static, Screencast<'static>>,
+            SessionClosed,
+            OwnedFd,
+            Streams,
+        ),
+        Error,
+    > {
+        let session = proxy.create_session().await?;
+        // Subscribe before starting the session, it can be closed right away.
+        let closed = session.receive_closed_owned().await?;
+        proxy
+            .select_sources(
+                &session,
+                self.cursor_mode,
+                self.types,
+                self.multiple,
+                self.restore_token.as_deref(),
+                self.persist_mode,
+            )
+            .await?
+            .response()?;
+        let streams = proxy
+            .start_with_handle(&session, identifier)
+            .await?
+            .response()?;
+        let fd = proxy.open_pipe_wire_remote(&session).await?;
+        // Restore tokens are single use, the next negotiation needs the new
+        // one.
+        self.restore_token = streams.restore_token().map(ToOwned::to_owned);
+        Ok((session, Box::pin(closed), fd, streams))
+    }
+
+    async fn renegotiate(
+        mut self,
+        proxy: Screencast<'static>,
+        mut session: Session<'static, Screencast<'static>>,
+        mut closed: SessionClosed,
+        identifier: String,
+        sender: futures_channel::mpsc::UnboundedSender<Result<(OwnedFd, Streams), Error>>,
+        mut stop: futures_channel::oneshot::Receiver<()>,
+    ) {
+        loop {
+            if let futures_util::future::Either::Right(_) =
+                futures_util::future::select(closed.next(), &mut stop).await
+            {
+                let _ = session.close().await;
+                return;
+            }
+
+            match self.negotiate(&proxy, &identifier).await {
+                Ok((new_session, new_closed, fd, streams)) => {
+                    session = new_session;
+                    closed = new_closed;
+                    if sender.unbounded_send(Ok((fd, streams))).is_err() {
+                        let _ = session.close().await;
+                        return;
+                    }
+                }
+                Err(err) => {
+                    let _ = sender.unbounded_send(Err(err));
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// The `Closed` signals of a [`PersistentScreencast`] session.
+type SessionClosed = std::pin::Pin<Box<dyn futures_util::Stream<Item = ()> + Send>>;
+
+/// The negotiations of a [`PersistentScreencast`]: the file descriptor of the
+/// PipeWire remote and the streams of each of them.
+///
+/// Dropping it closes the screen cast session.
+#[derive(Debug)]
+pub struct PersistentStreams {
+    receiver: futures_channel::mpsc::UnboundedReceiver<Result<(OwnedFd, Streams), Error>>,
+    _stop: futures_channel::oneshot::Sender<()>,
+}
+
+impl futures_util::Stream for PersistentStreams {
+    type Item = Result<(OwnedFd, Streams), Error>;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        self.receiver.poll_next_unpin(cx)
+    }
+}
+
+#[cfg(feature = "pipewire")]
+#[cfg_attr(docsrs, doc(cfg(feature = "pipewire")))]
+/// A cursor update, sent as metadata of the stream buffers when the
+/// [`CursorMode::Metadata`] mode is used.
+///
+/// The stream has to request the `SPA_META_Cursor` metadata with a
+/// `SPA_PARAM_Meta` parameter for the buffers to carry it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CursorUpdate {
+    id: u32,
+    position: (i32, i32),
+    hotspot: (i32, i32),
+    bitmap: Option<CursorBitmap>,
+}
+
+#[cfg(feature = "pipewire")]
+impl CursorUpdate {
+    /// Extracts the cursor update of a buffer, if it has one.
+    ///
+    /// # Safety
+    ///
+    /// `buffer` has to be null or a buffer returned by
+    /// [`pipewire::stream::StreamRef::dequeue_raw_buffer`] that was not
+    /// queued back yet.
+    pub unsafe fn from_raw_buffer(buffer: *const pipewire::sys::pw_buffer) -> Option<Self> {
+        let buffer = buffer.as_ref()?.buffer.as_ref()?;
+        if buffer.metas.is_null() {
+            return None;
+        }
+        let metas = std::slice::from_raw_parts(buffer.metas, buffer.n_metas as usize);
+        let meta = metas
+            .iter()
+            .find(|meta| meta.type_ == pipewire::spa::sys::SPA_META_Cursor)?;
+        if meta.data.is_null() {
+            return None;
+        }
+        Self::from_meta(std::slice::from_raw_parts(
+            meta.data as *const u8,
+            meta.size as usize,
+        ))
+    }
+
+    /// Parses the content of a `SPA_META_Cursor` metadata.
+    ///
+    /// Returns `None` if the metadata is invalid or doesn't carry a new cursor
+    /// state.
+    pub fn from_meta(meta: &[u8]) -> Option<Self> {
+        use pipewire::spa::sys::spa_meta_cursor;
+
+        if meta.len() < std::mem::size_of::<spa_meta_cursor>() {
+            return None;
+        }
+        // SAFETY: the slice is large enough and the structure is plain data.
+        let cursor = unsafe { std::ptr::read_unaligned(meta.as_ptr() as *const spa_meta_cursor) };
+        // An ID of 0 means there is no new cursor data.
+        if cursor.id == 0 {
+            return None;
+        }
+        Some(Self {
+            id: cursor.id,
+            position: (cursor.position.x, cursor.position.y),
+            hotspot: (cursor.hotspot.x, cursor.hotspot.y),
+            bitmap: CursorBitmap::from_meta(meta, cursor.bitmap_offset as usize),
+        })
+    }
+
+    /// The ID of the cursor.
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// The position of the cursor in the stream.
+    pub fn position(&self) -> (i32, i32) {
+        self.position
+    }
+
+    /// The position of the hotspot in the cursor bitmap.
+    pub fn hotspot(&self) -> (i32, i32) {
+        self.hotspot
+    }
+
+    /// The new bitmap of the cursor, `None` if it didn't change.
+    pub fn bitmap(&self) -> Option<&CursorBitmap> {
+        self.bitmap.as_ref()
+    }
+}
+
+#[cfg(feature = "pipewire")]
+#[cfg_attr(docsrs, doc(cfg(feature = "pipewire")))]
+/// The bitmap of a [`CursorUpdate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CursorBitmap {
+    /// The cursor is invisible.
+    Hidden,
+    /// The cursor image.
+    Image(CursorImage),
+}
+
+#[cfg(feature = "pipewire")]
+impl CursorBitmap {
+    /// Parses the `spa_meta_bitmap` at `offset` of a cursor metadata.
+    fn from_meta(meta: &[u8], offset: usize) -> Option<Self> {
+        use pipewire::spa::sys::{spa_meta_bitmap, spa_meta_cursor};
+
+        // An offset pointing inside of the cursor metadata means there is no
+        // new bitmap.
+        if offset < std::mem::size_of::<spa_meta_cursor>() {
+            return None;
+        }
+        let bytes = meta.get(offset..)?;
+        if bytes.len() < std::mem::size_of::<spa_meta_bitmap>() {
+            return None;
+        }
+        // SAFETY: the slice is large enough and the structure is plain data.
+        let bitmap = unsafe { std::ptr::read_unaligned(bytes.as_ptr() as *const spa_meta_bitmap) };
+        // SPA_VIDEO_FORMAT_UNKNOWN means there is no new bitmap.
+        if bitmap.format == 0 {
+            return None;
+        }
+        if bitmap.offset == 0 {
+            return Some(Self::Hidden);
+        }
+        let len = bitmap.stride.unsigned_abs() as usize * bitmap.size.height as usize;
+        let data = bytes.get(bitmap.offset as usize..)?.get(..len)?;
+        Some(Self::Image(CursorImage {
+            format: pipewire::spa::param::video::VideoFormat::from_raw(bitmap.format),
+            size: (bitmap.size.width, bitmap.size.height),
+            stride: bitmap.stride,
+            data: data.to_vec(),
+        }))
+    }
+}
+
+#[cfg(feature = "pipewire")]
+#[cfg_attr(docsrs, doc(cfg(feature = "pipewire")))]
+/// A cursor image.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CursorImage {
+    format: pipewire::spa::param::video::VideoFormat,
+    size: (u32, u32),
+    stride: i32,
+    data: Vec<u8>,
+}
+
+#[cfg(feature = "pipewire")]
+impl CursorImage {
+    /// The pixel format of the image.
+    pub fn format(&self) -> pipewire::spa::param::video::VideoFormat {
+        self.format
+    }
+
+    /// The width and height of the image.
+    pub fn size(&self) -> (u32, u32) {
+        self.size
+    }
+
+    /// The number of bytes of a row of pixels.
+    pub fn stride(&self) -> i32 {
+        self.stride
+    }
+
+    /// The pixels of the image.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+#[cfg(test)]
+pub(crate) const SIGNATURES: &[crate::proxy::WrapperSignature] = crate::proxy::wrapper_signatures! {
+    "org.freedesktop.portal.ScreenCast" {
+        "CreateSession": &CreateSessionOptions => zbus::zvariant::OwnedObjectPath,
+        "OpenPipeWireRemote": (crate::desktop::Session<'_, Screencast<'_>>, HashMap<&str, Value<'_>>) => zbus::zvariant::OwnedFd,
+        "SelectSources": (crate::desktop::Session<'_, Screencast<'_>>, &SelectSourcesOptions) => zbus::zvariant::OwnedObjectPath,
+        "Start": (crate::desktop::Session<'_, Screencast<'_>>, &str, &StartCastOptions) => zbus::zvariant::OwnedObjectPath,
+    }
+};
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use zbus::zvariant::{serialized::Context, to_bytes, Endian, Value, LE};
+
+    use super::*;
+
+    #[cfg(feature = "backend")]
+    #[test]
+    fn built_streams() {
+        let stream = Stream::builder(42)
+            .id("monitor-1")
+            .position((0, 0))
+            .size((1920, 1080))
+            .source_type(SourceType::Monitor)
+            .build();
+        let streams = Streams::new(vec![stream, Stream::builder(43).build()], "token");
+        let encoded = to_bytes(Context::new_dbus(LE, 0), &streams).unwrap();
+        let streams: Streams = encoded.deserialize().unwrap().0;
+        assert_eq!(streams.restore_token(), Some("token"));
+
+        let stream = &streams.streams()[0];
+        assert_eq!(stream.pipe_wire_node_id(), 42);
+        assert_eq!(stream.id(), Some("monitor-1"));
+        assert_eq!(stream.position(), Some((0, 0)));
+        assert_eq!(stream.size(), Some((1920, 1080)));
+        assert_eq!(stream.source_type(), Some(SourceType::Monitor));
+        assert_eq!(stream.mapping_id(), None);
+
+        // The properties that aren't set are left out.
+        let stream = &streams.streams()[1];
+        assert_eq!(stream.pipe_wire_node_id(), 43);
+        assert_eq!(stream.id(), None);
+        assert_eq!(stream.size(), None);
+        assert_eq!(stream.source_type(), None);
+
+        let streams = Streams::new(Vec::new(), None);
+        let encoded = to_bytes(Context::new_dbus(LE, 0), &streams).unwrap();
+        let streams: Streams = encoded.deserialize().unwrap().0;
+        assert!(streams.streams().is_empty());
+        assert_eq!(streams.restore_token(), None);
+    }
+
+    #[test]
+    fn streams_serialization() {
+        let properties = HashMap::from([
+            ("id", Value::from("monitor-1")),
+            ("size", Value::from((1920, 1080))),
+            ("source_type", Value::from(SourceType::Monitor as u32)),
+        ]);
+        let response = HashMap::from([
+            ("streams", Value::from(vec![(42u32, properties)])),
+            ("restore_token", Value::from("token")),
+        ]);
+        let ctxt = Context::new_dbus(LE, 0);
+        let encoded = to_bytes(ctxt, &response).unwrap();
+        let streams: Streams = encoded.deserialize().unwrap().0;
+        assert_eq!(streams.restore_token(), Some("token"));
+        assert_eq!(streams.streams()[0].pipe_wire_node_id(), 42);
+        assert_eq!(streams.streams()[0].size(), Some((1920, 1080)));
+
+        let json = serde_json::to_string(&streams).unwrap();
+        assert_eq!(
+            json,
+            r#"{"streams":[[42,{"id":"monitor-1","size":[1920,1080],"source_type":1}]],"restore_token":"token"}"#
+        );
+        let streams: Streams = serde_json::from_str(&json).unwrap();
+        assert_eq!(streams.restore_token(), Some("token"));
+        assert_eq!(streams.streams()[0].id(), Some("monitor-1"));
+        assert_eq!(
+            streams.streams()[0].source_type(),
+            Some(SourceType::Monitor)
+        );
+        assert_eq!(streams.streams()[0].position(), None);
+
+        // The responses stored as JSON can still be sent over D-Bus.
+        let encoded = to_bytes(Context::new_dbus(Endian::Big, 0), &streams).unwrap();
+        let streams: Streams = encoded.deserialize().unwrap().0;
+        assert_eq!(streams.streams()[0].size(), Some((1920, 1080)));
+    }
+
+    #[cfg(feature = "test")]
+    #[tokio::test]
+    async fn persistent_screencast() {
+        use zbus::zvariant::{OwnedObjectPath, OwnedValue};
+
+        use crate::test::{MockPortal, MockResponse};
+
+        const INTERFACE: &str = "org.freedesktop.portal.ScreenCast";
+
+        let _lock = crate::test::CONNECTION_LOCK.lock().await;
+        let mock = MockPortal::new().await.unwrap();
+        mock.set_version(INTERFACE, 5);
+        mock.respond(INTERFACE, "CreateSession", MockResponse::success([]));
+        mock.respond(INTERFACE, "SelectSources", MockResponse::success([]));
+        let streams = |token: &'static str| {
+            MockResponse::success([
+                (
+                    "streams",
+                    Value::from(vec![(42u32, HashMap::<&str, Value>::new())]),
+                ),
+                ("restore_token", Value::from(token)),
+            ])
+        };
+        mock.respond(INTERFACE, "Start", streams("first"));
+        mock.respond(INTERFACE, "Start", streams("second"));
+        let (remote, _) = std::os::unix::net::UnixStream::pair().unwrap();
+        mock.respond(
+            INTERFACE,
+            "OpenPipeWireRemote",
+            MockResponse::reply((zvariant::Fd::from(OwnedFd::from(remote)),)),
+        );
+
+        let mut casts = PersistentScreencast::new(CursorMode::Hidden, SourceType::Monitor.into())
+            .start(None)
+            .await
+            .unwrap();
+        let (_, streams) = casts.next().await.unwrap().unwrap();
+        assert_eq!(streams.restore_token(), Some("first"));
+
+        let calls = |member: &str| {
+            mock.calls()
+                .into_iter()
+                .filter(|call| call.header().member().unwrap() == member)
+                .collect::<Vec<_>>()
+        };
+        let session = calls("Start")[0]
+            .body()
+            .deserialize::<(OwnedObjectPath, String, HashMap<String, OwnedValue>)>()
+            .unwrap()
+            .0;
+        // Closed right away, before the next negotiation could subscribe.
+        mock.emit_signal(&session, "org.freedesktop.portal.Session", "Closed", &())
+            .await
+            .unwrap();
+
+        let (_, streams) = casts.next().await.unwrap().unwrap();
+        assert_eq!(streams.restore_token(), Some("second"));
+        assert_eq!(calls("CreateSession").len(), 2);
+        // The restore token of the first negotiation is passed to the second.
+        let options = calls("SelectSources")[1]
+            .body()
+            .deserialize::<(OwnedObjectPath, HashMap<String, OwnedValue>)>()
+            .unwrap()
+            .1;
+        assert_eq!(
+            options.get("restore_token").unwrap().downcast_ref::<&str>(),
+            Ok("first")
+        );
+    }
+}
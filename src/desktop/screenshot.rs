@@ -33,12 +33,12 @@
 //!     Ok(())
 //! }
 //! ```
-use std::fmt::Debug;
+use std::{fmt::Debug, time::Duration};
 
-use zbus::zvariant::{DeserializeDict, SerializeDict, Type};
+use zbus::zvariant::{SerializeDict, Type};
 
 use super::{HandleToken, Request};
-use crate::{desktop::Color, proxy::Proxy, Error, WindowIdentifier};
+use crate::{desktop::Color, proxy::Proxy, CancellationToken, Error, Feature, WindowIdentifier};
 
 #[derive(SerializeDict, Type, Debug, Default)]
 #[zvariant(signature = "dict")]
@@ -48,11 +48,11 @@ struct ScreenshotOptions {
     interactive: Option<bool>,
 }
 
-#[derive(SerializeDict, DeserializeDict, Type)]
-#[zvariant(signature = "dict")]
-/// The response of a [`ScreenshotRequest`] request.
-pub struct Screenshot {
-    uri: url::Url,
+crate::helpers::dict! {
+    /// The response of a [`ScreenshotRequest`] request.
+    pub struct Screenshot {
+        uri: url::Url,
+    }
 }
 
 impl Screenshot {
@@ -89,11 +89,25 @@ struct ColorOptions {
     handle_token: HandleToken,
 }
 
+/// The interface lets sandboxed applications request a screenshot or pick a
+/// color.
+///
+/// The requests are sent with the builders, like [`Screenshot::request`], the proxy
+/// gives access to the version of the interface.
+///
+/// Wrapper of the DBus interface: [`org.freedesktop.portal.Screenshot`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Screenshot.html).
 #[derive(Debug)]
 #[doc(alias = "org.freedesktop.portal.Screenshot")]
-struct ScreenshotProxy<'a>(Proxy<'a>);
+pub struct ScreenshotProxy<'a>(Proxy<'a>);
 
 impl<'a> ScreenshotProxy<'a> {
+    /// The [`Color::pick`] request.
+    pub const PICK_COLOR: Feature = Feature::since(2);
+
+    /// The [`ScreenshotRequest::modal`] and [`ScreenshotRequest::interactive`]
+    /// options.
+    pub const INTERACTIVE: Feature = Feature::since(2);
+
     /// Create a new instance of [`ScreenshotProxy`].
     pub async fn new() -> Result<ScreenshotProxy<'a>, Error> {
         let proxy = Proxy::new_desktop("org.freedesktop.portal.Screenshot").await?;
@@ -111,7 +125,7 @@ impl<'a> ScreenshotProxy<'a> {
     /// See also [`PickColor`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Screenshot.html#org-freedesktop-portal-screenshot-pickcolor).
     #[doc(alias = "PickColor")]
     #[doc(alias = "xdp_portal_pick_color")]
-    pub async fn pick_color(
+    async fn pick_color(
         &self,
         identifier: Option<&WindowIdentifier>,
         options: ColorOptions,
@@ -140,7 +154,7 @@ impl<'a> ScreenshotProxy<'a> {
     /// See also [`Screenshot`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Screenshot.html#org-freedesktop-portal-screenshot-screenshot).
     #[doc(alias = "Screenshot")]
     #[doc(alias = "xdp_portal_take_screenshot")]
-    pub async fn screenshot(
+    async fn screenshot(
         &self,
         identifier: Option<&WindowIdentifier>,
         options: ScreenshotOptions,
@@ -164,6 +178,18 @@ impl<'a> std::ops::Deref for ScreenshotProxy<'a> {
     }
 }
 
+impl crate::Versioned for ScreenshotProxy<'_> {
+    fn version(&self) -> u32 {
+        self.0.version()
+    }
+}
+
+impl crate::Timeout for ScreenshotProxy<'_> {
+    fn set_timeout(&mut self, timeout: impl Into<Option<std::time::Duration>>) {
+        self.0.set_timeout(timeout.into());
+    }
+}
+
 #[derive(Debug, Default)]
 #[doc(alias = "xdp_portal_pick_color")]
 /// A [builder-pattern] type to construct [`Color`].
@@ -171,6 +197,9 @@ impl<'a> std::ops::Deref for ScreenshotProxy<'a> {
 /// [builder-pattern]: https://doc.rust-lang.org/1.0.0/style/ownership/builders.html
 pub struct ColorRequest {
     identifier: Option<WindowIdentifier>,
+    timeout: Option<Duration>,
+    cancel_on_drop: bool,
+    cancellation: Option<CancellationToken>,
     options: ColorOptions,
 }
 
@@ -182,9 +211,36 @@ impl ColorRequest {
         self
     }
 
+    #[must_use]
+    /// Sets how long to wait for the request to complete, including the user
+    /// interaction. The request is closed once the timeout expires.
+    pub fn timeout(mut self, timeout: impl Into<Option<Duration>>) -> Self {
+        self.timeout = timeout.into();
+        self
+    }
+
+    #[must_use]
+    /// Sets whether to close the request if the future returned by `send` is
+    /// dropped before the user interaction ends, dismissing the dialog.
+    pub fn cancel_on_drop(mut self, cancel_on_drop: bool) -> Self {
+        self.cancel_on_drop = cancel_on_drop;
+        self
+    }
+
+    #[must_use]
+    /// Closes the request once `token` is cancelled, the request then fails
+    /// with [`ResponseError::Cancelled`](crate::desktop::ResponseError::Cancelled).
+    pub fn cancel_with(mut self, token: &CancellationToken) -> Self {
+        self.cancellation = Some(token.clone());
+        self
+    }
+
     /// Build the [`Color`].
     pub async fn send(self) -> Result<Request<Color>, Error> {
-        let proxy = ScreenshotProxy::new().await?;
+        let mut proxy = ScreenshotProxy::new().await?;
+        proxy.0.set_timeout(self.timeout);
+        proxy.0.set_cancel_on_drop(self.cancel_on_drop);
+        proxy.0.set_cancellation(self.cancellation);
         proxy
             .pick_color(self.identifier.as_ref(), self.options)
             .await
@@ -209,6 +265,9 @@ impl Color {
 pub struct ScreenshotRequest {
     options: ScreenshotOptions,
     identifier: Option<WindowIdentifier>,
+    timeout: Option<Duration>,
+    cancel_on_drop: bool,
+    cancellation: Option<CancellationToken>,
 }
 
 impl ScreenshotRequest {
@@ -219,6 +278,30 @@ impl ScreenshotRequest {
         self
     }
 
+    #[must_use]
+    /// Sets how long to wait for the request to complete, including the user
+    /// interaction. The request is closed once the timeout expires.
+    pub fn timeout(mut self, timeout: impl Into<Option<Duration>>) -> Self {
+        self.timeout = timeout.into();
+        self
+    }
+
+    #[must_use]
+    /// Sets whether to close the request if the future returned by `send` is
+    /// dropped before the user interaction ends, dismissing the dialog.
+    pub fn cancel_on_drop(mut self, cancel_on_drop: bool) -> Self {
+        self.cancel_on_drop = cancel_on_drop;
+        self
+    }
+
+    #[must_use]
+    /// Closes the request once `token` is cancelled, the request then fails
+    /// with [`ResponseError::Cancelled`](crate::desktop::ResponseError::Cancelled).
+    pub fn cancel_with(mut self, token: &CancellationToken) -> Self {
+        self.cancellation = Some(token.clone());
+        self
+    }
+
     /// Sets whether the dialog should be a modal.
     #[must_use]
     pub fn modal(mut self, modal: impl Into<Option<bool>>) -> Self {
@@ -236,9 +319,20 @@ impl ScreenshotRequest {
 
     /// Build the [`Screenshot`].
     pub async fn send(self) -> Result<Request<Screenshot>, Error> {
-        let proxy = ScreenshotProxy::new().await?;
+        let mut proxy = ScreenshotProxy::new().await?;
+        proxy.0.set_timeout(self.timeout);
+        proxy.0.set_cancel_on_drop(self.cancel_on_drop);
+        proxy.0.set_cancellation(self.cancellation);
         proxy
             .screenshot(self.identifier.as_ref(), self.options)
             .await
     }
 }
+
+#[cfg(test)]
+pub(crate) const SIGNATURES: &[crate::proxy::WrapperSignature] = crate::proxy::wrapper_signatures! {
+    "org.freedesktop.portal.Screenshot" {
+        "PickColor": (&str, &ColorOptions) => zbus::zvariant::OwnedObjectPath,
+        "Screenshot": (&str, &ScreenshotOptions) => zbus::zvariant::OwnedObjectPath,
+    }
+};
@@ -18,14 +18,11 @@
 //! }
 //! ```
 
-use std::os::fd::AsFd;
+use std::{
+    net::Shutdown,
+    os::{fd::AsFd, unix::net::UnixStream},
+};
 
-#[cfg(feature = "async-std")]
-use async_net::{unix::UnixStream, Shutdown};
-#[cfg(feature = "async-std")]
-use futures_util::AsyncReadExt;
-#[cfg(feature = "tokio")]
-use tokio::{io::AsyncReadExt, io::AsyncWriteExt, net::UnixStream};
 use zbus::zvariant::{Fd, SerializeDict, Type};
 
 use super::{HandleToken, Request};
@@ -88,29 +85,36 @@ impl<'a> std::ops::Deref for Secret<'a> {
     }
 }
 
+impl crate::Versioned for Secret<'_> {
+    fn version(&self) -> u32 {
+        self.0.version()
+    }
+}
+
+impl crate::Timeout for Secret<'_> {
+    fn set_timeout(&mut self, timeout: impl Into<Option<std::time::Duration>>) {
+        self.0.set_timeout(timeout.into());
+    }
+}
+
 /// A handy wrapper around [`Secret::retrieve`].
 ///
 /// It crates a UnixStream internally for receiving the secret.
 pub async fn retrieve() -> Result<Vec<u8>, Error> {
     let proxy = Secret::new().await?;
-    let mut buf = Vec::with_capacity(64);
-
-    #[cfg(feature = "tokio")]
-    let mut x1 = {
-        let (x1, mut x2) = UnixStream::pair()?;
-        proxy.retrieve(&x2).await?;
-        x2.shutdown().await?;
-        x1
-    };
-    #[cfg(feature = "async-std")]
-    let mut x1 = {
-        let (x1, x2) = UnixStream::pair()?;
-        proxy.retrieve(&x2).await?;
-        x2.shutdown(Shutdown::Write)?;
-        x1
-    };
-
-    x1.read_to_end(&mut buf).await?;
+    let (x1, x2) = UnixStream::pair()?;
+    proxy.retrieve(&x2).await?;
+    // The secret was written once the request is done, end the stream for
+    // the portal copy of the socket as well.
+    x2.shutdown(Shutdown::Write)?;
+    drop(x2);
 
-    Ok(buf)
+    Ok(crate::io::read_to_end(x1).await?)
 }
+
+#[cfg(test)]
+pub(crate) const SIGNATURES: &[crate::proxy::WrapperSignature] = crate::proxy::wrapper_signatures! {
+    "org.freedesktop.portal.Secret" {
+        "RetrieveSecret": (zbus::zvariant::Fd<'_>, &RetrieveOptions) => zbus::zvariant::OwnedObjectPath,
+    }
+};
@@ -1,10 +1,13 @@
-use std::{collections::HashMap, fmt::Debug, marker::PhantomData};
+use std::{collections::HashMap, fmt::Debug, marker::PhantomData, pin::pin};
 
-use futures_util::Stream;
+use futures_util::{
+    future::{select, Either},
+    Stream, StreamExt,
+};
 use serde::{Deserialize, Serialize, Serializer};
 use zbus::zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Type};
 
-use crate::{desktop::HandleToken, proxy::Proxy, Error};
+use crate::{desktop::HandleToken, proxy::Proxy, CancellationToken, Error};
 
 /// Shared by all portal interfaces that involve long lived sessions.
 ///
@@ -21,7 +24,12 @@ use crate::{desktop::HandleToken, proxy::Proxy, Error};
 #[derive(Type)]
 #[doc(alias = "org.freedesktop.portal.Session")]
 #[zvariant(signature = "o")]
-pub struct Session<'a, T>(Proxy<'a>, PhantomData<T>)
+pub struct Session<'a, T>(
+    Proxy<'a>,
+    // Cancelled by `Session::close`, which doesn't emit `Closed`.
+    CancellationToken,
+    PhantomData<T>,
+)
 where
     T: SessionPortal;
 
@@ -38,7 +46,7 @@ where
         P::Error: Into<zbus::Error>,
     {
         let proxy = Proxy::new_desktop_with_path("org.freedesktop.portal.Session", path).await?;
-        Ok(Self(proxy, PhantomData))
+        Ok(Self(proxy, CancellationToken::new(), PhantomData))
     }
 
     pub(crate) async fn from_unique_name(
@@ -61,6 +69,14 @@ where
         self.0.signal("Closed").await
     }
 
+    /// Same as [`Session::receive_closed`] but the stream doesn't borrow the
+    /// session, for it to be kept along with it.
+    pub(crate) async fn receive_closed_owned(
+        &self,
+    ) -> Result<impl Stream<Item = ()> + Unpin + Send + 'static, Error> {
+        Ok(self.0.receive_signal("Closed").await?.map(|_| ()))
+    }
+
     /// Closes the portal session to which this object refers and ends all
     /// related user interaction (dialogs, etc).
     ///
@@ -69,7 +85,34 @@ where
     /// See also [`Close`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Session.html#org-freedesktop-portal-session-close).
     #[doc(alias = "Close")]
     pub async fn close(&self) -> Result<(), Error> {
-        self.0.call("Close", &()).await
+        self.0.call::<()>("Close", &()).await?;
+        self.1.cancel();
+        Ok(())
+    }
+
+    /// Closes the session once `token` is cancelled, unless the session is
+    /// closed before, by the portal or with [`Session::close`].
+    pub fn close_with(&self, token: &CancellationToken) {
+        let path = self.path().clone().into_owned();
+        let token = token.clone();
+        let session_closed = self.1.clone();
+        let connection = self.0.connection().clone();
+        crate::helpers::spawn(&connection, "ashpd session close", async move {
+            let Ok(proxy) =
+                Proxy::new_desktop_with_path("org.freedesktop.portal.Session", path).await
+            else {
+                return;
+            };
+            let Ok(closed) = proxy.signal::<()>("Closed").await else {
+                return;
+            };
+            let mut closed = pin!(closed);
+            let session_closed = pin!(session_closed.cancelled());
+            let closed = select(closed.next(), session_closed);
+            if let Either::Left(_) = select(pin!(token.cancelled()), closed).await {
+                let _ = proxy.call::<()>("Close", &()).await;
+            }
+        });
     }
 
     pub(crate) fn path(&self) -> &ObjectPath<'_> {
@@ -147,3 +190,10 @@ impl<'de> Deserialize<'de> for CreateSessionResponse {
         })
     }
 }
+
+#[cfg(test)]
+pub(crate) const SIGNATURES: &[crate::proxy::WrapperSignature] = crate::proxy::wrapper_signatures! {
+    "org.freedesktop.portal.Session" {
+        "Close": () => (),
+    }
+};
@@ -31,14 +31,14 @@ use std::{collections::HashMap, convert::TryFrom, fmt::Debug, future::ready};
 
 use futures_util::{Stream, StreamExt};
 use serde::{Deserialize, Serialize};
-use zbus::zvariant::{OwnedValue, Type, Value};
+use zbus::zvariant::{OwnedValue, Signature, Type, Value};
 
-use crate::{desktop::Color, proxy::Proxy, Error};
+use crate::{desktop::Color, proxy::Proxy, Error, PortalError, SignalEvent};
 
 /// A HashMap of the <key, value> settings found on a specific namespace.
 pub type Namespace = HashMap<String, OwnedValue>;
 
-#[derive(Deserialize, Type)]
+#[derive(Serialize, Deserialize, Type)]
 /// A specific `namespace.key = value` setting.
 pub struct Setting(String, String, OwnedValue);
 
@@ -155,6 +155,55 @@ impl TryFrom<Value<'_>> for Contrast {
     }
 }
 
+/// A setting read with [`Settings::read`] that can't be converted to the
+/// requested type.
+#[derive(Debug)]
+pub struct InvalidSetting {
+    namespace: String,
+    key: String,
+    expected: &'static str,
+    found: Signature,
+    source: Box<Error>,
+}
+
+impl InvalidSetting {
+    /// The namespace of the setting.
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    /// The key of the setting.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// The name of the requested type, `u32` for example.
+    pub fn expected(&self) -> &str {
+        self.expected
+    }
+
+    /// The signature of the value sent by the portal.
+    pub fn found(&self) -> &Signature {
+        &self.found
+    }
+}
+
+impl std::error::Error for InvalidSetting {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+impl std::fmt::Display for InvalidSetting {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Expected `{}` `{}` as `{}`, found a value of type `{}`: {}",
+            self.namespace, self.key, self.expected, self.found, self.source
+        )
+    }
+}
+
 /// Appearance namespace
 pub const APPEARANCE_NAMESPACE: &str = "org.freedesktop.appearance";
 /// Color scheme key
@@ -214,7 +263,8 @@ impl<'a> Settings<'a> {
     ///
     /// # Returns
     ///
-    /// The value for `key` as a `zvariant::OwnedValue`.
+    /// The value for `key`, converted to `T`. A value that can't be converted
+    /// fails with an [`Error::InvalidSetting`].
     ///
     /// # Specifications
     ///
@@ -227,10 +277,36 @@ impl<'a> Settings<'a> {
         Error: From<<T as TryFrom<OwnedValue>>::Error>,
     {
         let value = self.0.call::<OwnedValue>("Read", &(namespace, key)).await?;
-        if let Ok(v) = value.downcast_ref::<Value>() {
-            T::try_from(v.try_to_owned()?).map_err(From::from)
-        } else {
-            T::try_from(value).map_err(From::from)
+        // The deprecated `Read` method wraps the value in an extra variant,
+        // unwrap it without copying the inner value.
+        let value = match Value::from(value) {
+            Value::Value(inner) => OwnedValue::try_from(*inner)?,
+            value => OwnedValue::try_from(value)?,
+        };
+        let found = value.value_signature().clone();
+        T::try_from(value).map_err(|err| {
+            InvalidSetting {
+                namespace: namespace.to_owned(),
+                key: key.to_owned(),
+                expected: std::any::type_name::<T>(),
+                found,
+                source: Box::new(Error::from(err)),
+            }
+            .into()
+        })
+    }
+
+    /// Same as [`Settings::read`], but returns [`None`] if the namespace or
+    /// the key is unknown.
+    pub async fn read_optional<T>(&self, namespace: &str, key: &str) -> Result<Option<T>, Error>
+    where
+        T: TryFrom<OwnedValue>,
+        Error: From<<T as TryFrom<OwnedValue>>::Error>,
+    {
+        match self.read(namespace, key).await {
+            Ok(value) => Ok(Some(value)),
+            Err(Error::Portal(PortalError::NotFound(_))) => Ok(None),
+            Err(err) => Err(err),
         }
     }
 
@@ -292,6 +368,51 @@ impl<'a> Settings<'a> {
         self.0.signal("SettingChanged").await
     }
 
+    /// Similar to [Self::receive_setting_changed] but keeps receiving the
+    /// changes once the portal restarted.
+    ///
+    /// The settings might have changed while the portal was not running,
+    /// they should be read again on [`SignalEvent::Reconnected`].
+    ///
+    /// ```rust,no_run
+    /// use ashpd::{desktop::settings::Settings, SignalEvent};
+    /// use futures_util::StreamExt;
+    ///
+    /// async fn run() -> ashpd::Result<()> {
+    ///     let settings = Settings::new().await?;
+    ///     let mut changes = settings.receive_setting_changed_reconnecting().await?;
+    ///     while let Some(event) = changes.next().await {
+    ///         match event {
+    ///             SignalEvent::Signal(setting) => println!("{} changed", setting.key()),
+    ///             SignalEvent::Reconnected => println!("{:?}", settings.color_scheme().await?),
+    ///         }
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    #[doc(alias = "SettingChanged")]
+    pub async fn receive_setting_changed_reconnecting(
+        &self,
+    ) -> Result<impl Stream<Item = SignalEvent<Setting>> + Unpin + 'a, Error> {
+        self.0.signal_reconnecting("SettingChanged").await
+    }
+
+    /// Similar to [Self::receive_setting_changed] but only receives the
+    /// settings of `namespace`.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`SettingChanged`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Settings.html#org-freedesktop-portal-settings-settingchanged).
+    #[doc(alias = "SettingChanged")]
+    pub async fn receive_namespace_changed(
+        &self,
+        namespace: &str,
+    ) -> Result<impl Stream<Item = Setting>, Error> {
+        self.0
+            .signal_with_args("SettingChanged", &[(0, namespace)])
+            .await
+    }
+
     /// Similar to [Self::receive_setting_changed]
     /// but allows you to filter specific settings.
     ///
@@ -340,3 +461,73 @@ impl<'a> std::ops::Deref for Settings<'a> {
         &self.0
     }
 }
+
+impl crate::Versioned for Settings<'_> {
+    fn version(&self) -> u32 {
+        self.0.version()
+    }
+}
+
+impl crate::Timeout for Settings<'_> {
+    fn set_timeout(&mut self, timeout: impl Into<Option<std::time::Duration>>) {
+        self.0.set_timeout(timeout.into());
+    }
+}
+
+#[cfg(all(test, feature = "test"))]
+mod tests {
+    use zbus::zvariant::Value;
+
+    use super::{ColorScheme, Settings, APPEARANCE_NAMESPACE, COLOR_SCHEME_KEY};
+    use crate::{
+        test::{MockPortal, MockResponse},
+        Error,
+    };
+
+    const INTERFACE: &str = "org.freedesktop.portal.Settings";
+
+    #[tokio::test]
+    async fn read() {
+        let _lock = crate::test::CONNECTION_LOCK.lock().await;
+        let mock = MockPortal::new().await.unwrap();
+        let reply = |value: Value<'static>| MockResponse::reply((Value::Value(Box::new(value)),));
+        mock.respond(INTERFACE, "Read", reply(Value::from(1u32)));
+        mock.respond(INTERFACE, "Read", reply(Value::from("dark")));
+        mock.respond(
+            INTERFACE,
+            "Read",
+            MockResponse::error("org.freedesktop.portal.Error.NotFound", "Unknown key"),
+        );
+
+        let settings = Settings::new().await.unwrap();
+        assert_eq!(
+            settings.color_scheme().await.unwrap(),
+            ColorScheme::PreferDark
+        );
+
+        let err = settings
+            .read::<u32>(APPEARANCE_NAMESPACE, COLOR_SCHEME_KEY)
+            .await
+            .unwrap_err();
+        let Error::InvalidSetting(err) = err else {
+            panic!("Unexpected error {err:?}");
+        };
+        assert_eq!(err.key(), COLOR_SCHEME_KEY);
+        assert_eq!(err.expected(), "u32");
+        assert_eq!(err.found().to_string(), "s");
+
+        let missing = settings
+            .read_optional::<u32>(APPEARANCE_NAMESPACE, "missing")
+            .await
+            .unwrap();
+        assert_eq!(missing, None);
+    }
+}
+
+#[cfg(test)]
+pub(crate) const SIGNATURES: &[crate::proxy::WrapperSignature] = crate::proxy::wrapper_signatures! {
+    "org.freedesktop.portal.Settings" {
+        "ReadAll": &[&str] => HashMap<String, Namespace>,
+        "Read": (&str, &str) => OwnedValue,
+    }
+};
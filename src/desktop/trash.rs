@@ -90,6 +90,18 @@ impl<'a> std::ops::Deref for TrashProxy<'a> {
     }
 }
 
+impl crate::Versioned for TrashProxy<'_> {
+    fn version(&self) -> u32 {
+        self.0.version()
+    }
+}
+
+impl crate::Timeout for TrashProxy<'_> {
+    fn set_timeout(&mut self, timeout: impl Into<Option<std::time::Duration>>) {
+        self.0.set_timeout(timeout.into());
+    }
+}
+
 #[doc(alias = "xdp_portal_trash_file")]
 /// A handy wrapper around [`TrashProxy::trash_file`].
 pub async fn trash_file(fd: &impl AsFd) -> Result<(), Error> {
@@ -97,6 +109,13 @@ pub async fn trash_file(fd: &impl AsFd) -> Result<(), Error> {
     proxy.trash_file(fd).await
 }
 
+#[cfg(test)]
+pub(crate) const SIGNATURES: &[crate::proxy::WrapperSignature] = crate::proxy::wrapper_signatures! {
+    "org.freedesktop.portal.Trash" {
+        "TrashFile": zbus::zvariant::Fd<'_> => TrashStatus,
+    }
+};
+
 #[cfg(test)]
 mod test {
     use super::TrashStatus;
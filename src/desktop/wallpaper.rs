@@ -25,11 +25,13 @@
 //! ## Sets a wallpaper from a URI:
 //!
 //! ```rust,no_run
-//! use ashpd::desktop::wallpaper::{SetOn, WallpaperRequest};
+//! use ashpd::{
+//!     desktop::wallpaper::{SetOn, WallpaperRequest},
+//!     Uri,
+//! };
 //!
 //! async fn run() -> ashpd::Result<()> {
-//!     let uri =
-//!         url::Url::parse("file:///home/bilelmoussaoui/Downloads/adwaita-night.jpg").unwrap();
+//!     let uri = Uri::from_file_path("/home/bilelmoussaoui/Downloads/adwaita-night.jpg")?;
 //!     WallpaperRequest::default()
 //!         .set_on(SetOn::Both)
 //!         .show_preview(true)
@@ -39,13 +41,13 @@
 //! }
 //! ```
 
-use std::{fmt, os::fd::AsFd, str::FromStr};
+use std::{fmt, os::fd::AsFd, str::FromStr, time::Duration};
 
 use serde::{self, Deserialize, Serialize};
 use zbus::zvariant::{Fd, SerializeDict, Type};
 
 use super::Request;
-use crate::{desktop::HandleToken, proxy::Proxy, Error, WindowIdentifier};
+use crate::{desktop::HandleToken, proxy::Proxy, CancellationToken, Error, WindowIdentifier};
 
 #[cfg_attr(feature = "glib", derive(glib::Enum))]
 #[cfg_attr(feature = "glib", enum_type(name = "AshpdSetOn"))]
@@ -115,15 +117,24 @@ struct WallpaperOptions {
     set_on: Option<SetOn>,
 }
 
-struct WallpaperProxy<'a>(Proxy<'a>);
+/// The interface lets sandboxed applications set the user's desktop
+/// background picture.
+///
+/// The requests are sent with the builders, like [`WallpaperRequest`], the proxy
+/// gives access to the version of the interface.
+///
+/// Wrapper of the DBus interface: [`org.freedesktop.portal.Wallpaper`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Wallpaper.html).
+#[doc(alias = "org.freedesktop.portal.Wallpaper")]
+pub struct WallpaperProxy<'a>(Proxy<'a>);
 
 impl<'a> WallpaperProxy<'a> {
+    /// Create a new instance of [`WallpaperProxy`].
     pub async fn new() -> Result<WallpaperProxy<'a>, Error> {
         let proxy = Proxy::new_desktop("org.freedesktop.portal.Wallpaper").await?;
         Ok(Self(proxy))
     }
 
-    pub async fn set_wallpaper_file(
+    async fn set_wallpaper_file(
         &self,
         identifier: Option<&WindowIdentifier>,
         file: &impl AsFd,
@@ -139,7 +150,7 @@ impl<'a> WallpaperProxy<'a> {
             .await
     }
 
-    pub async fn set_wallpaper_uri(
+    async fn set_wallpaper_uri(
         &self,
         identifier: Option<&WindowIdentifier>,
         uri: &url::Url,
@@ -164,6 +175,18 @@ impl<'a> std::ops::Deref for WallpaperProxy<'a> {
     }
 }
 
+impl crate::Versioned for WallpaperProxy<'_> {
+    fn version(&self) -> u32 {
+        self.0.version()
+    }
+}
+
+impl crate::Timeout for WallpaperProxy<'_> {
+    fn set_timeout(&mut self, timeout: impl Into<Option<std::time::Duration>>) {
+        self.0.set_timeout(timeout.into());
+    }
+}
+
 #[derive(Debug, Default)]
 #[doc(alias = "xdp_portal_set_wallpaper")]
 #[doc(alias = "org.freedesktop.portal.Wallpaper")]
@@ -173,6 +196,8 @@ impl<'a> std::ops::Deref for WallpaperProxy<'a> {
 pub struct WallpaperRequest {
     identifier: Option<WindowIdentifier>,
     options: WallpaperOptions,
+    timeout: Option<Duration>,
+    cancellation: Option<CancellationToken>,
 }
 
 impl WallpaperRequest {
@@ -199,9 +224,27 @@ impl WallpaperRequest {
         self
     }
 
+    #[must_use]
+    /// Sets how long to wait for the request to complete, including the user
+    /// interaction. The request is closed once the timeout expires.
+    pub fn timeout(mut self, timeout: impl Into<Option<Duration>>) -> Self {
+        self.timeout = timeout.into();
+        self
+    }
+
+    #[must_use]
+    /// Closes the request once `token` is cancelled, the request then fails
+    /// with [`ResponseError::Cancelled`](crate::desktop::ResponseError::Cancelled).
+    pub fn cancel_with(mut self, token: &CancellationToken) -> Self {
+        self.cancellation = Some(token.clone());
+        self
+    }
+
     /// Build using a URI.
     pub async fn build_uri(self, uri: &url::Url) -> Result<Request<()>, Error> {
-        let proxy = WallpaperProxy::new().await?;
+        let mut proxy = WallpaperProxy::new().await?;
+        proxy.0.set_timeout(self.timeout);
+        proxy.0.set_cancellation(self.cancellation);
         proxy
             .set_wallpaper_uri(self.identifier.as_ref(), uri, self.options)
             .await
@@ -209,12 +252,22 @@ impl WallpaperRequest {
 
     /// Build using a file.
     pub async fn build_file(self, file: &impl AsFd) -> Result<Request<()>, Error> {
-        let proxy = WallpaperProxy::new().await?;
+        let mut proxy = WallpaperProxy::new().await?;
+        proxy.0.set_timeout(self.timeout);
+        proxy.0.set_cancellation(self.cancellation);
         proxy
             .set_wallpaper_file(self.identifier.as_ref(), file, self.options)
             .await
     }
 }
+#[cfg(test)]
+pub(crate) const SIGNATURES: &[crate::proxy::WrapperSignature] = crate::proxy::wrapper_signatures! {
+    "org.freedesktop.portal.Wallpaper" {
+        "SetWallpaperFile": (&str, zbus::zvariant::Fd<'_>, &WallpaperOptions) => zbus::zvariant::OwnedObjectPath,
+        "SetWallpaperURI": (&str, &url::Url, &WallpaperOptions) => zbus::zvariant::OwnedObjectPath,
+    }
+};
+
 #[cfg(test)]
 mod tests {
     use super::SetOn;
@@ -21,13 +21,106 @@
 //!     Ok(())
 //! }
 //! ```
+//!
+//! Or using the high level helpers, which take care of opening the files and
+//! of the calls ordering
+//!
+//! ```rust,no_run
+//! use ashpd::documents::{retrieve_files, send_files};
+//!
+//! async fn run() -> ashpd::Result<()> {
+//!     let key = send_files(&["/home/bilelmoussaoui/Downloads/adwaita-night.jpg"], false).await?;
+//!
+//!     // The key would be sent to another process, through the clipboard for example
+//!     let files = retrieve_files(&key).await?;
+//!     println!("{:#?}", files);
+//!
+//!     Ok(())
+//! }
+//! ```
 
-use std::{collections::HashMap, os::fd::AsFd};
+use std::{
+    collections::HashMap,
+    fmt,
+    ops::Deref,
+    os::fd::AsFd,
+    path::{Path, PathBuf},
+};
 
 use futures_util::Stream;
+use serde::{Deserialize, Serialize};
 use zbus::zvariant::{Fd, SerializeDict, Type, Value};
 
-use crate::{proxy::Proxy, Error};
+use super::open_o_path;
+use crate::{proxy::Proxy, Error, PortalError};
+
+/// The key identifying a file transfer, as returned by [`send_files`] or
+/// [`FileTransfer::start_transfer`].
+///
+/// It is meant to be sent to the receiving application using the
+/// `application/vnd.portal.filetransfer` mimetype.
+#[derive(Debug, Serialize, Deserialize, Type, PartialEq, Eq, Hash, Clone)]
+pub struct TransferKey(String);
+
+impl From<String> for TransferKey {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&str> for TransferKey {
+    fn from(value: &str) -> Self {
+        Self(value.to_owned())
+    }
+}
+
+impl From<TransferKey> for String {
+    fn from(value: TransferKey) -> String {
+        value.0
+    }
+}
+
+impl AsRef<str> for TransferKey {
+    fn as_ref(&self) -> &str {
+        self.0.as_ref()
+    }
+}
+
+impl Deref for TransferKey {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl fmt::Display for TransferKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_ref())
+    }
+}
+
+#[derive(Debug)]
+/// The errors specific to the [`send_files`] and [`retrieve_files`] helpers.
+pub enum FileTransferError {
+    /// Only regular files can be transferred, directories are not supported.
+    NotRegularFile(PathBuf),
+    /// The transfer doesn't exist or was already stopped.
+    InvalidKey(TransferKey),
+}
+
+impl std::error::Error for FileTransferError {}
+
+impl fmt::Display for FileTransferError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotRegularFile(path) => {
+                write!(f, "`{}` is not a regular file", path.display())
+            }
+            Self::InvalidKey(key) => write!(f, "No file transfer found for key `{key}`"),
+        }
+    }
+}
 
 #[derive(SerializeDict, Debug, Type, Default)]
 /// Specified options for a [`FileTransfer::start_transfer`] request.
@@ -200,3 +293,75 @@ impl<'a> std::ops::Deref for FileTransfer<'a> {
         &self.0
     }
 }
+
+impl crate::Versioned for FileTransfer<'_> {
+    fn version(&self) -> u32 {
+        self.0.version()
+    }
+}
+
+impl crate::Timeout for FileTransfer<'_> {
+    fn set_timeout(&mut self, timeout: impl Into<Option<std::time::Duration>>) {
+        self.0.set_timeout(timeout.into());
+    }
+}
+
+/// Start a file transfer and add `paths` to it.
+///
+/// The transfer is stopped automatically once the files have been retrieved.
+///
+/// # Arguments
+///
+/// * `paths` - The files to transfer. Only regular files are supported.
+/// * `writeable` - Whether the receiving application can write to the files.
+///
+/// # Returns
+///
+/// The [`TransferKey`] to send to the receiving application.
+pub async fn send_files<P: AsRef<Path>>(
+    paths: impl IntoIterator<Item = P>,
+    writeable: bool,
+) -> Result<TransferKey, Error> {
+    let mut fds = Vec::new();
+    for path in paths {
+        let path = path.as_ref();
+        if !path.is_file() {
+            return Err(FileTransferError::NotRegularFile(path.to_owned()).into());
+        }
+        fds.push(open_o_path(path)?);
+    }
+
+    let proxy = FileTransfer::new().await?;
+    let key = TransferKey::from(proxy.start_transfer(writeable, true).await?);
+    if let Err(err) = proxy.add_files(&key, &fds).await {
+        let _ = proxy.stop_transfer(&key).await;
+        return Err(err);
+    }
+    Ok(key)
+}
+
+/// Retrieve the files of a transfer started by another application with
+/// [`send_files`].
+///
+/// The files are exported in the document portal as needed to make them
+/// accessible to the caller.
+pub async fn retrieve_files(key: &TransferKey) -> Result<Vec<PathBuf>, Error> {
+    let proxy = FileTransfer::new().await?;
+    match proxy.retrieve_files(key).await {
+        Ok(files) => Ok(files.into_iter().map(PathBuf::from).collect()),
+        Err(Error::Portal(PortalError::NotAllowed(_) | PortalError::NotFound(_))) => {
+            Err(FileTransferError::InvalidKey(key.clone()).into())
+        }
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(test)]
+pub(crate) const SIGNATURES: &[crate::proxy::WrapperSignature] = crate::proxy::wrapper_signatures! {
+    "org.freedesktop.portal.FileTransfer" {
+        "AddFiles": (&str, Vec<zbus::zvariant::Fd<'_>>, HashMap<&str, Value<'_>>) => (),
+        "RetrieveFiles": (&str, HashMap<&str, Value<'_>>) => Vec<String>,
+        "StartTransfer": TransferOptions => String,
+        "StopTransfer": &str => (),
+    }
+};
@@ -0,0 +1,59 @@
+use ::gtk4::{gdk, gio, glib, prelude::*};
+
+use super::TransferKey;
+
+/// The mimetype used to exchange a [`TransferKey`] through drag-and-drop or
+/// copy-paste.
+pub const FILE_TRANSFER_MIME_TYPE: &str = "application/vnd.portal.filetransfer";
+
+impl TransferKey {
+    /// Creates a [`gdk::ContentProvider`] exposing the key with the
+    /// [`FILE_TRANSFER_MIME_TYPE`] mimetype.
+    ///
+    /// The provider is meant to be returned from a
+    /// [`gtk4::DragSource::connect_prepare`](https://gtk-rs.org/gtk4-rs/stable/latest/docs/gtk4/struct.DragSource.html#method.connect_prepare)
+    /// callback, or set on a [`gdk::Clipboard`].
+    #[cfg_attr(docsrs, doc(cfg(any(feature = "gtk4_wayland", feature = "gtk4_x11"))))]
+    pub fn content_provider(&self) -> gdk::ContentProvider {
+        // GTK sends the key as a nul-terminated string.
+        let mut bytes = self.as_bytes().to_vec();
+        bytes.push(0);
+        gdk::ContentProvider::for_bytes(FILE_TRANSFER_MIME_TYPE, &glib::Bytes::from_owned(bytes))
+    }
+
+    /// Whether `drop` offers a [`TransferKey`].
+    #[cfg_attr(docsrs, doc(cfg(any(feature = "gtk4_wayland", feature = "gtk4_x11"))))]
+    pub fn is_offered_by(drop: &gdk::Drop) -> bool {
+        drop.formats().contain_mime_type(FILE_TRANSFER_MIME_TYPE)
+    }
+
+    /// Reads the [`TransferKey`] offered by `drop`.
+    ///
+    /// The files can then be retrieved with
+    /// [`retrieve_files`](crate::documents::retrieve_files).
+    #[cfg_attr(docsrs, doc(cfg(any(feature = "gtk4_wayland", feature = "gtk4_x11"))))]
+    pub async fn from_drop(drop: &gdk::Drop) -> Result<Self, glib::Error> {
+        let (stream, _mime_type) = drop
+            .read_future(&[FILE_TRANSFER_MIME_TYPE], glib::Priority::DEFAULT)
+            .await?;
+        let bytes = read_to_end(&stream).await?;
+        let key = String::from_utf8(bytes)
+            .map_err(|_| glib::Error::new(gio::IOErrorEnum::InvalidData, "Invalid transfer key"))?;
+        Ok(Self::from(key.trim_end_matches('\0')))
+    }
+}
+
+async fn read_to_end(stream: &gio::InputStream) -> Result<Vec<u8>, glib::Error> {
+    let mut buffer = Vec::new();
+    loop {
+        let bytes = stream
+            .read_bytes_future(4096, glib::Priority::DEFAULT)
+            .await?;
+        if bytes.is_empty() {
+            break;
+        }
+        buffer.extend_from_slice(&bytes);
+    }
+    stream.close_future(glib::Priority::DEFAULT).await?;
+    Ok(buffer)
+}
@@ -21,7 +21,11 @@
 //!     }
 //!
 //!     proxy
-//!         .grant_permissions("f2ee988d", &app_id, &[Permission::GrantPermissions])
+//!         .grant_permissions(
+//!             "f2ee988d",
+//!             &app_id,
+//!             [Permission::Read, Permission::GrantPermissions],
+//!         )
 //!         .await?;
 //!     proxy
 //!         .revoke_permissions("f2ee988d", &app_id, &[Permission::Write])
@@ -33,10 +37,20 @@
 //! }
 //! ```
 
-use std::{collections::HashMap, fmt, os::fd::AsFd, path::Path, str::FromStr};
+use std::{
+    collections::HashMap,
+    fmt,
+    fs::OpenOptions,
+    os::{
+        fd::{AsFd, OwnedFd},
+        unix::fs::OpenOptionsExt,
+    },
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
 use enumflags2::{bitflags, BitFlags};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 use zbus::zvariant::{Fd, OwnedValue, Type};
 
@@ -127,6 +141,227 @@ impl FromStr for Permission {
     }
 }
 
+impl Permission {
+    const ALL: [Permission; 4] = [
+        Permission::Read,
+        Permission::Write,
+        Permission::GrantPermissions,
+        Permission::Delete,
+    ];
+
+    fn bit(self) -> u8 {
+        1 << (self as u8)
+    }
+}
+
+/// A set of [`Permission`]s, without duplicates.
+///
+/// It can be created from a single [`Permission`], a slice or an array of
+/// them, or using one of the helpers.
+///
+/// ```rust
+/// use ashpd::documents::{Permission, PermissionSet};
+///
+/// let permissions = PermissionSet::read_write().with(Permission::Delete);
+/// assert!(permissions.contains(Permission::Write));
+/// assert_eq!(
+///     PermissionSet::from(&[Permission::Read, Permission::Read]),
+///     PermissionSet::read_only()
+/// );
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct PermissionSet(u8);
+
+impl PermissionSet {
+    /// An empty set.
+    pub fn empty() -> Self {
+        Self(0)
+    }
+
+    /// [`Permission::Read`] only.
+    pub fn read_only() -> Self {
+        Self::from(Permission::Read)
+    }
+
+    /// [`Permission::Read`] and [`Permission::Write`].
+    pub fn read_write() -> Self {
+        Self::read_only().with(Permission::Write)
+    }
+
+    /// Every permission.
+    pub fn all() -> Self {
+        Permission::ALL.into_iter().collect()
+    }
+
+    #[must_use]
+    /// Returns the set with `permission` added.
+    pub fn with(mut self, permission: Permission) -> Self {
+        self.insert(permission);
+        self
+    }
+
+    #[must_use]
+    /// Returns the set with `permission` removed.
+    pub fn without(mut self, permission: Permission) -> Self {
+        self.remove(permission);
+        self
+    }
+
+    /// Adds `permission` to the set.
+    pub fn insert(&mut self, permission: Permission) {
+        self.0 |= permission.bit();
+    }
+
+    /// Removes `permission` from the set.
+    pub fn remove(&mut self, permission: Permission) {
+        self.0 &= !permission.bit();
+    }
+
+    /// Whether the set contains `permission`.
+    pub fn contains(&self, permission: Permission) -> bool {
+        self.0 & permission.bit() != 0
+    }
+
+    /// Whether the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Iterates over the permissions of the set.
+    pub fn iter(&self) -> impl Iterator<Item = Permission> + '_ {
+        Permission::ALL
+            .into_iter()
+            .filter(move |permission| self.contains(*permission))
+    }
+
+    /// Checks that the set is meaningful when granted to an application.
+    ///
+    /// An application can't share a document it can't read, so
+    /// [`Permission::GrantPermissions`] requires [`Permission::Read`].
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.contains(Permission::GrantPermissions) && !self.contains(Permission::Read) {
+            Err(Error::InvalidPermissions(*self))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl fmt::Debug for PermissionSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl From<Permission> for PermissionSet {
+    fn from(permission: Permission) -> Self {
+        Self(permission.bit())
+    }
+}
+
+impl From<&[Permission]> for PermissionSet {
+    fn from(permissions: &[Permission]) -> Self {
+        permissions.iter().copied().collect()
+    }
+}
+
+impl<const N: usize> From<[Permission; N]> for PermissionSet {
+    fn from(permissions: [Permission; N]) -> Self {
+        permissions.into_iter().collect()
+    }
+}
+
+impl<const N: usize> From<&[Permission; N]> for PermissionSet {
+    fn from(permissions: &[Permission; N]) -> Self {
+        permissions.iter().copied().collect()
+    }
+}
+
+impl From<Vec<Permission>> for PermissionSet {
+    fn from(permissions: Vec<Permission>) -> Self {
+        permissions.into_iter().collect()
+    }
+}
+
+impl From<&Vec<Permission>> for PermissionSet {
+    fn from(permissions: &Vec<Permission>) -> Self {
+        permissions.iter().copied().collect()
+    }
+}
+
+impl FromIterator<Permission> for PermissionSet {
+    fn from_iter<T: IntoIterator<Item = Permission>>(iter: T) -> Self {
+        let mut set = Self::empty();
+        for permission in iter {
+            set.insert(permission);
+        }
+        set
+    }
+}
+
+impl Type for PermissionSet {
+    const SIGNATURE: &'static zbus::zvariant::Signature = <Vec<Permission>>::SIGNATURE;
+}
+
+impl Serialize for PermissionSet {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de> Deserialize<'de> for PermissionSet {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(Vec::<Permission>::deserialize(deserializer)?.into())
+    }
+}
+
+/// The extra information returned by [`Documents::add_full`] and
+/// [`Documents::add_named_full`].
+#[derive(Debug, Type)]
+#[zvariant(signature = "a{sv}")]
+pub struct AddFullResult {
+    mountpoint: Option<FilePath>,
+    raw: HashMap<String, OwnedValue>,
+}
+
+impl AddFullResult {
+    /// The mount point of the document portal fuse filesystem.
+    pub fn mountpoint(&self) -> Option<&FilePath> {
+        self.mountpoint.as_ref()
+    }
+
+    /// The extra information as sent by the portal, including keys
+    /// not covered by the typed accessors.
+    pub fn raw(&self) -> &HashMap<String, OwnedValue> {
+        &self.raw
+    }
+}
+
+impl<'de> Deserialize<'de> for AddFullResult {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let raw = HashMap::<String, OwnedValue>::deserialize(deserializer)?;
+        let mountpoint = match raw.get("mountpoint") {
+            Some(value) => {
+                let bytes =
+                    Vec::<u8>::try_from(value.try_clone().map_err(serde::de::Error::custom)?)
+                        .map_err(serde::de::Error::custom)?;
+                Some(FilePath::from_bytes(bytes).map_err(serde::de::Error::custom)?)
+            }
+            None => None,
+        };
+        Ok(Self { mountpoint, raw })
+    }
+}
+
 /// The interface lets sandboxed applications make files from the outside world
 /// available to sandboxed applications in a controlled way.
 ///
@@ -187,6 +422,32 @@ impl<'a> Documents<'a> {
             .await
     }
 
+    /// Adds a file to the document store from its path.
+    ///
+    /// Same as [`Documents::add`] but takes care of opening the file with
+    /// `O_PATH`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file to add.
+    /// * `reuse_existing` - Whether to reuse an existing document store entry
+    ///   for the file.
+    /// * `persistent` - Whether to add the file only for this session or
+    ///   permanently.
+    ///
+    /// # Returns
+    ///
+    /// The ID of the file in the document store.
+    pub async fn add_path(
+        &self,
+        path: impl AsRef<Path>,
+        reuse_existing: bool,
+        persistent: bool,
+    ) -> Result<DocumentID, Error> {
+        let fd = open_o_path(path)?;
+        self.add(&fd, reuse_existing, persistent).await
+    }
+
     /// Adds multiple files to the document store.
     /// The files are passed in the form of an open file descriptor
     /// to prove that the caller has access to the file.
@@ -200,7 +461,8 @@ impl<'a> Documents<'a> {
     ///
     /// # Returns
     ///
-    /// The IDs of the files in the document store along with other extra info.
+    /// The IDs of the files in the document store along with an
+    /// [`AddFullResult`].
     ///
     /// # Required version
     ///
@@ -216,8 +478,10 @@ impl<'a> Documents<'a> {
         o_path_fds: &[impl AsFd],
         flags: BitFlags<DocumentFlags>,
         app_id: Option<&AppID>,
-        permissions: &[Permission],
-    ) -> Result<(Vec<DocumentID>, HashMap<String, OwnedValue>), Error> {
+        permissions: impl Into<PermissionSet>,
+    ) -> Result<(Vec<DocumentID>, AddFullResult), Error> {
+        let permissions = permissions.into();
+        permissions.validate()?;
         let o_path: Vec<Fd> = o_path_fds.iter().map(Fd::from).collect();
         let app_id = app_id.map(|id| id.as_ref()).unwrap_or("");
         self.0
@@ -265,6 +529,36 @@ impl<'a> Documents<'a> {
             .await
     }
 
+    /// Creates an entry in the document store for writing a new file from
+    /// the path of its parent directory.
+    ///
+    /// Same as [`Documents::add_named`] but takes care of opening the parent
+    /// directory with `O_PATH`.
+    ///
+    /// # Arguments
+    ///
+    /// * `parent` - The path of the parent directory.
+    /// * `filename` - The basename for the file.
+    /// * `reuse_existing` - Whether to reuse an existing document store entry
+    ///   for the file.
+    /// * `persistent` - Whether to add the file only for this session or
+    ///   permanently.
+    ///
+    /// # Returns
+    ///
+    /// The ID of the file in the document store.
+    pub async fn add_named_path(
+        &self,
+        parent: impl AsRef<Path>,
+        filename: impl AsRef<Path>,
+        reuse_existing: bool,
+        persistent: bool,
+    ) -> Result<DocumentID, Error> {
+        let fd = open_o_path(parent)?;
+        self.add_named(&fd, filename, reuse_existing, persistent)
+            .await
+    }
+
     /// Adds multiple files to the document store.
     /// The files are passed in the form of an open file descriptor
     /// to prove that the caller has access to the file.
@@ -279,7 +573,8 @@ impl<'a> Documents<'a> {
     ///
     /// # Returns
     ///
-    /// The ID of the file in the document store along with other extra info.
+    /// The ID of the file in the document store along with an
+    /// [`AddFullResult`].
     ///
     /// # Required version
     ///
@@ -296,8 +591,10 @@ impl<'a> Documents<'a> {
         filename: impl AsRef<Path>,
         flags: BitFlags<DocumentFlags>,
         app_id: Option<&AppID>,
-        permissions: &[Permission],
-    ) -> Result<(DocumentID, HashMap<String, OwnedValue>), Error> {
+        permissions: impl Into<PermissionSet>,
+    ) -> Result<(DocumentID, AddFullResult), Error> {
+        let permissions = permissions.into();
+        permissions.validate()?;
         let app_id = app_id.map(|id| id.as_ref()).unwrap_or("");
         let filename = FilePath::new(filename)?;
         self.0
@@ -309,6 +606,60 @@ impl<'a> Documents<'a> {
             .await
     }
 
+    /// Exports a directory to the document store.
+    ///
+    /// The directory is opened with `O_PATH` and added with
+    /// [`DocumentFlags::ExportDirectory`]. The root directory has no name in
+    /// the document store, it fails with [`Error::ParseError`] without being
+    /// exported.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the directory to export.
+    /// * `persistent` - Whether to add the directory only for this session or
+    ///   permanently.
+    ///
+    /// # Returns
+    ///
+    /// The ID of the directory in the document store along with the path at
+    /// which it is exposed in the document portal fuse filesystem.
+    ///
+    /// # Required version
+    ///
+    /// The method requires the 4th version implementation of the portal and
+    /// would fail with [`Error::RequiresVersion`] otherwise.
+    pub async fn export_directory(
+        &self,
+        path: impl AsRef<Path>,
+        persistent: bool,
+    ) -> Result<(DocumentID, PathBuf), Error> {
+        let version = self.0.version();
+        if version < 4 {
+            return Err(Error::RequiresVersion(4, version));
+        }
+        // The directory is exported under the name of the directory it
+        // resolves to, which is checked first not to leave an entry behind
+        // for paths like `/`.
+        let path = std::fs::canonicalize(path)?;
+        if path.file_name().is_none() {
+            return Err(Error::ParseError("Failed to compute the document path"));
+        }
+        let fd = open_o_path(&path)?;
+        let mut flags = DocumentFlags::ReuseExisting | DocumentFlags::ExportDirectory;
+        flags.set(DocumentFlags::Persistent, persistent);
+        let (mut doc_ids, extra) = self
+            .add_full(&[fd], flags, None, PermissionSet::empty())
+            .await?;
+        let doc_id = doc_ids.pop().ok_or(Error::NoResponse)?;
+        let mount_point = match extra.mountpoint() {
+            Some(mount_point) => mount_point.as_ref().to_owned(),
+            None => self.mount_point().await?.as_ref().to_owned(),
+        };
+        let document_path = document_path(&mount_point, &doc_id, &path)
+            .ok_or(Error::ParseError("Failed to compute the document path"))?;
+        Ok((doc_id, document_path))
+    }
+
     /// Removes an entry from the document store. The file itself is not
     /// deleted.
     ///
@@ -336,7 +687,28 @@ impl<'a> Documents<'a> {
     #[doc(alias = "GetMountPoint")]
     #[doc(alias = "get_mount_point")]
     pub async fn mount_point(&self) -> Result<FilePath, Error> {
-        self.0.call("GetMountPoint", &()).await
+        crate::cache::get_or_fetch(&self.0, "GetMountPoint", self.0.call("GetMountPoint", &()))
+            .await
+    }
+
+    /// Verifies that the document store fuse filesystem is mounted and
+    /// readable, returning its mount point.
+    ///
+    /// A broken document portal otherwise surfaces as `ENOENT` or `ENOTCONN`
+    /// errors when accessing the exported files. The failures are reported as
+    /// an [`Error::Mount`].
+    pub async fn check_mount() -> Result<PathBuf, Error> {
+        let mount_point = match Documents::new().await {
+            Ok(proxy) => proxy.mount_point().await,
+            Err(err) => Err(err),
+        };
+        let mount_point = match mount_point {
+            Ok(mount_point) => mount_point.as_ref().to_owned(),
+            Err(err) if err.is_portal_missing() => return Err(MountError::NotRunning.into()),
+            Err(err) => return Err(err),
+        };
+        check_mount_point(&mount_point)?;
+        Ok(mount_point)
     }
 
     /// Grants access permissions for a file in the document store to an
@@ -359,8 +731,10 @@ impl<'a> Documents<'a> {
         &self,
         doc_id: impl Into<DocumentID>,
         app_id: &AppID,
-        permissions: &[Permission],
+        permissions: impl Into<PermissionSet>,
     ) -> Result<(), Error> {
+        let permissions = permissions.into();
+        permissions.validate()?;
         self.0
             .call("GrantPermissions", &(doc_id.into(), app_id, permissions))
             .await
@@ -472,8 +846,9 @@ impl<'a> Documents<'a> {
         &self,
         doc_id: impl Into<DocumentID>,
         app_id: &AppID,
-        permissions: &[Permission],
+        permissions: impl Into<PermissionSet>,
     ) -> Result<(), Error> {
+        let permissions = permissions.into();
         self.0
             .call("RevokePermissions", &(doc_id.into(), app_id, permissions))
             .await
@@ -499,6 +874,173 @@ impl<'a> Documents<'a> {
     ) -> Result<HashMap<DocumentID, FilePath>, Error> {
         self.0.call_versioned("GetHostPaths", &(doc_ids,), 5).await
     }
+
+    /// Computes the path at which a document is exposed in the document
+    /// portal fuse filesystem.
+    ///
+    /// # Arguments
+    ///
+    /// * `doc_id` - The ID of the file in the document store.
+    /// * `host_path` - The path of the file in the host filesystem, as
+    ///   returned by [`Documents::info`] or [`Documents::list`].
+    pub async fn document_path(
+        &self,
+        doc_id: &DocumentID,
+        host_path: impl AsRef<Path>,
+    ) -> Result<PathBuf, Error> {
+        let mount_point = self.mount_point().await?;
+        document_path(mount_point.as_ref(), doc_id, host_path.as_ref())
+            .ok_or(Error::ParseError("Failed to compute the document path"))
+    }
+
+    /// Resolves a path inside the document portal fuse filesystem to the
+    /// path of the file in the host filesystem.
+    ///
+    /// Returns [`None`] if `path` is not part of the document store.
+    ///
+    /// # Required version
+    ///
+    /// The method relies on [`Documents::host_paths`] and requires the 5th
+    /// version implementation of the portal.
+    pub async fn resolve_host_path(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> Result<Option<PathBuf>, Error> {
+        let mount_point = self.mount_point().await?;
+        let Some((doc_id, relative)) = split_document_path(mount_point.as_ref(), path.as_ref())
+        else {
+            return Ok(None);
+        };
+        let mut host_paths = self.host_paths(std::slice::from_ref(&doc_id)).await?;
+        Ok(host_paths
+            .remove(&doc_id)
+            .map(|host_path| host_path.as_ref().join(relative)))
+    }
+}
+
+/// The directories a Flatpak sandbox always provides its own version of,
+/// whatever the permissions of the application.
+///
+/// See also [Filesystem permissions](https://docs.flatpak.org/en/latest/sandbox-permissions.html#filesystem-access).
+const SANDBOX_RESERVED_DIRS: &[&str] = &[
+    "/.flatpak-info",
+    "/app",
+    "/bin",
+    "/dev",
+    "/etc",
+    "/lib",
+    "/lib32",
+    "/lib64",
+    "/proc",
+    "/run/flatpak",
+    "/run/host",
+    "/sbin",
+    "/tmp",
+    "/usr",
+    "/var",
+];
+
+/// Whether `host_path` is hidden by the version the sandbox provides of the
+/// same directory.
+fn is_shadowed_by_sandbox(host_path: &Path) -> bool {
+    SANDBOX_RESERVED_DIRS
+        .iter()
+        .any(|dir| host_path.starts_with(dir))
+}
+
+/// Whether the current process can access `host_path` directly, without
+/// going through the document portal.
+///
+/// Only the existence of the path is checked, not whether it can be read.
+/// Sandboxed applications only see the host path if it was shared with them,
+/// with `--filesystem=home` for example. As the sandbox has its own view of
+/// the filesystem, the result only reflects that view: the paths the sandbox
+/// always provides itself, like `/usr`, are reported as not accessible, but a
+/// path of the sandbox could still be a different file than the one at the
+/// same location on the host.
+pub async fn is_host_path_accessible(host_path: impl AsRef<Path>) -> bool {
+    let host_path = host_path.as_ref();
+    if crate::is_sandboxed().await && is_shadowed_by_sandbox(host_path) {
+        return false;
+    }
+    host_path.exists()
+}
+
+#[derive(Debug)]
+/// The problems found by [`Documents::check_mount`].
+pub enum MountError {
+    /// The document portal is not running, `xdg-document-portal` is either
+    /// not installed or failed to start.
+    NotRunning,
+    /// The mount point doesn't exist.
+    Missing(PathBuf),
+    /// Nothing is mounted on the mount point.
+    NotMounted(PathBuf),
+    /// The fuse filesystem is mounted but the daemon serving it is gone,
+    /// `xdg-document-portal` likely crashed.
+    Disconnected(PathBuf),
+    /// The mount point can't be read.
+    Unreadable(PathBuf, std::io::Error),
+}
+
+impl std::error::Error for MountError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Unreadable(_, e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for MountError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotRunning => f.write_str("xdg-document-portal is not running"),
+            Self::Missing(path) => write!(f, "`{}` doesn't exist", path.display()),
+            Self::NotMounted(path) => write!(f, "`{}` is not mounted", path.display()),
+            Self::Disconnected(path) => write!(
+                f,
+                "`{}` is not served by xdg-document-portal anymore",
+                path.display()
+            ),
+            Self::Unreadable(path, e) => write!(f, "Failed to read `{}`: {e}", path.display()),
+        }
+    }
+}
+
+fn check_mount_point(mount_point: &Path) -> Result<(), MountError> {
+    use std::os::unix::fs::MetadataExt;
+
+    let to_error = |err: std::io::Error| match err.raw_os_error() {
+        Some(libc::ENOENT) => MountError::Missing(mount_point.to_owned()),
+        Some(libc::ENOTCONN) => MountError::Disconnected(mount_point.to_owned()),
+        _ => MountError::Unreadable(mount_point.to_owned(), err),
+    };
+    let metadata = std::fs::metadata(mount_point).map_err(to_error)?;
+    // A mount point lives on a different device than its parent.
+    if let Some(parent) = mount_point.parent() {
+        let parent = std::fs::metadata(parent).map_err(to_error)?;
+        if parent.dev() == metadata.dev() {
+            return Err(MountError::NotMounted(mount_point.to_owned()));
+        }
+    }
+    std::fs::read_dir(mount_point).map_err(to_error)?;
+    Ok(())
+}
+
+fn document_path(mount_point: &Path, doc_id: &DocumentID, host_path: &Path) -> Option<PathBuf> {
+    let file_name = host_path.file_name()?;
+    Some(mount_point.join(doc_id.as_ref()).join(file_name))
+}
+
+/// Splits a path inside the document portal fuse filesystem into the ID of
+/// the document and the path relative to the exported file.
+fn split_document_path(mount_point: &Path, path: &Path) -> Option<(DocumentID, PathBuf)> {
+    let mut components = path.strip_prefix(mount_point).ok()?.components();
+    let doc_id = components.next()?.as_os_str().to_str()?;
+    // Skip the exported file name, the rest is only set for directories.
+    components.next()?;
+    Some((DocumentID::from(doc_id), components.as_path().to_owned()))
 }
 
 impl<'a> std::ops::Deref for Documents<'a> {
@@ -509,18 +1051,278 @@ impl<'a> std::ops::Deref for Documents<'a> {
     }
 }
 
+impl crate::Versioned for Documents<'_> {
+    fn version(&self) -> u32 {
+        self.0.version()
+    }
+}
+
+impl crate::Timeout for Documents<'_> {
+    fn set_timeout(&mut self, timeout: impl Into<Option<std::time::Duration>>) {
+        self.0.set_timeout(timeout.into());
+    }
+}
+
+/// Open `path` with `O_PATH`, as expected by the various `Add` methods.
+pub(crate) fn open_o_path(path: impl AsRef<Path>) -> std::io::Result<OwnedFd> {
+    let file = OpenOptions::new()
+        .read(true)
+        .custom_flags(libc::O_PATH | libc::O_CLOEXEC)
+        .open(path)?;
+    Ok(file.into())
+}
+
+/// The documents added to the store by a [`DocumentAddRequest`].
+#[derive(Debug)]
+pub struct AddedDocuments {
+    ids: Vec<DocumentID>,
+    extra: Option<AddFullResult>,
+}
+
+impl AddedDocuments {
+    /// The IDs of the documents, in the same order as the added paths.
+    pub fn ids(&self) -> &[DocumentID] {
+        &self.ids
+    }
+
+    /// The mount point of the document portal fuse filesystem.
+    ///
+    /// Only available when the portal supports [`Documents::add_full`] or
+    /// [`Documents::add_named_full`].
+    pub fn mountpoint(&self) -> Option<&FilePath> {
+        self.extra.as_ref().and_then(AddFullResult::mountpoint)
+    }
+
+    /// The extra information returned by the portal, if any.
+    pub fn extra(&self) -> Option<&AddFullResult> {
+        self.extra.as_ref()
+    }
+}
+
+#[derive(Debug, Default)]
+#[doc(alias = "org.freedesktop.portal.Documents")]
+/// A [builder-pattern] type to add files to the document store.
+///
+/// The request picks between [`Documents::add`], [`Documents::add_full`],
+/// [`Documents::add_named`] and [`Documents::add_named_full`] depending on
+/// the options used and the version of the portal.
+///
+/// ```rust,no_run
+/// use std::str::FromStr;
+///
+/// use ashpd::{
+///     documents::{DocumentAddRequest, Permission},
+///     AppID,
+/// };
+///
+/// async fn run() -> ashpd::Result<()> {
+///     let added = DocumentAddRequest::default()
+///         .path("/home/user/Pictures/adwaita-day.jpg")
+///         .persistent(true)
+///         .app_id(AppID::from_str("org.gnome.Loupe").unwrap())
+///         .permissions(Permission::Read)
+///         .send()
+///         .await?;
+///     println!("{:#?}", added.ids());
+///     Ok(())
+/// }
+/// ```
+///
+/// [builder-pattern]: https://doc.rust-lang.org/1.0.0/style/ownership/builders.html
+pub struct DocumentAddRequest {
+    paths: Vec<PathBuf>,
+    flags: BitFlags<DocumentFlags>,
+    app_id: Option<AppID>,
+    permissions: PermissionSet,
+}
+
+impl DocumentAddRequest {
+    #[must_use]
+    /// Adds a file to export.
+    pub fn path(mut self, path: impl AsRef<Path>) -> Self {
+        self.paths.push(path.as_ref().to_owned());
+        self
+    }
+
+    #[must_use]
+    /// Adds multiple files to export.
+    pub fn paths<P: AsRef<Path>>(mut self, paths: impl IntoIterator<Item = P>) -> Self {
+        self.paths
+            .extend(paths.into_iter().map(|p| p.as_ref().to_owned()));
+        self
+    }
+
+    #[must_use]
+    /// Sets the [`DocumentFlags`], replacing the ones set previously.
+    pub fn flags(mut self, flags: impl Into<BitFlags<DocumentFlags>>) -> Self {
+        self.flags = flags.into();
+        self
+    }
+
+    #[must_use]
+    /// Whether to reuse an existing document store entry for the file.
+    pub fn reuse_existing(mut self, reuse_existing: bool) -> Self {
+        self.flags.set(DocumentFlags::ReuseExisting, reuse_existing);
+        self
+    }
+
+    #[must_use]
+    /// Whether to add the file permanently or only for this session.
+    pub fn persistent(mut self, persistent: bool) -> Self {
+        self.flags.set(DocumentFlags::Persistent, persistent);
+        self
+    }
+
+    #[must_use]
+    /// Sets the application the permissions are granted to.
+    pub fn app_id(mut self, app_id: impl Into<Option<AppID>>) -> Self {
+        self.app_id = app_id.into();
+        self
+    }
+
+    #[must_use]
+    /// Sets the permissions to grant to the application.
+    pub fn permissions(mut self, permissions: impl Into<PermissionSet>) -> Self {
+        self.permissions = permissions.into();
+        self
+    }
+
+    /// Whether the request can be fulfilled by the version 1 methods.
+    fn is_basic(&self) -> bool {
+        self.app_id.is_none()
+            && self.permissions.is_empty()
+            && (self.flags & !(DocumentFlags::ReuseExisting | DocumentFlags::Persistent)).is_empty()
+    }
+
+    /// Add the files to the document store.
+    pub async fn send(self) -> Result<AddedDocuments, Error> {
+        let fds = self
+            .paths
+            .iter()
+            .map(open_o_path)
+            .collect::<Result<Vec<_>, _>>()?;
+        let proxy = Documents::new().await?;
+        if proxy.0.version() >= 2 {
+            let (ids, extra) = proxy
+                .add_full(&fds, self.flags, self.app_id.as_ref(), self.permissions)
+                .await?;
+            Ok(AddedDocuments {
+                ids,
+                extra: Some(extra),
+            })
+        } else if self.is_basic() {
+            let mut ids = Vec::with_capacity(fds.len());
+            for fd in &fds {
+                let id = proxy
+                    .add(
+                        fd,
+                        self.flags.contains(DocumentFlags::ReuseExisting),
+                        self.flags.contains(DocumentFlags::Persistent),
+                    )
+                    .await?;
+                ids.push(id);
+            }
+            Ok(AddedDocuments { ids, extra: None })
+        } else {
+            Err(Error::RequiresVersion(2, proxy.0.version()))
+        }
+    }
+
+    /// Creates an entry in the document store for writing a new file named
+    /// `filename` inside of `parent`.
+    ///
+    /// The paths set with [`DocumentAddRequest::path`] are ignored.
+    pub async fn send_named(
+        self,
+        parent: impl AsRef<Path>,
+        filename: impl AsRef<Path>,
+    ) -> Result<AddedDocuments, Error> {
+        let fd = open_o_path(parent)?;
+        let proxy = Documents::new().await?;
+        if proxy.0.version() >= 3 {
+            let (id, extra) = proxy
+                .add_named_full(
+                    &fd,
+                    filename,
+                    self.flags,
+                    self.app_id.as_ref(),
+                    self.permissions,
+                )
+                .await?;
+            Ok(AddedDocuments {
+                ids: vec![id],
+                extra: Some(extra),
+            })
+        } else if self.is_basic() {
+            let id = proxy
+                .add_named(
+                    &fd,
+                    filename,
+                    self.flags.contains(DocumentFlags::ReuseExisting),
+                    self.flags.contains(DocumentFlags::Persistent),
+                )
+                .await?;
+            Ok(AddedDocuments {
+                ids: vec![id],
+                extra: None,
+            })
+        } else {
+            Err(Error::RequiresVersion(3, proxy.0.version()))
+        }
+    }
+}
+
 /// Interact with `org.freedesktop.portal.FileTransfer` interface.
 mod file_transfer;
+#[cfg(test)]
+pub(crate) use file_transfer::SIGNATURES as FILE_TRANSFER_SIGNATURES;
+
+#[cfg(any(feature = "gtk4_wayland", feature = "gtk4_x11"))]
+mod gtk4;
+
+#[cfg(any(feature = "gtk4_wayland", feature = "gtk4_x11"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "gtk4_wayland", feature = "gtk4_x11"))))]
+pub use self::gtk4::FILE_TRANSFER_MIME_TYPE;
 
-pub use file_transfer::FileTransfer;
+pub use file_transfer::{retrieve_files, send_files, FileTransfer, FileTransferError, TransferKey};
+
+#[cfg(test)]
+pub(crate) const SIGNATURES: &[crate::proxy::WrapperSignature] = crate::proxy::wrapper_signatures! {
+    "org.freedesktop.portal.Documents" {
+        "Add": (zbus::zvariant::Fd<'_>, bool, bool) => DocumentID,
+        "AddFull": (Vec<zbus::zvariant::Fd<'_>>, BitFlags<DocumentFlags>, &str, PermissionSet) => (Vec<DocumentID>, AddFullResult),
+        "AddNamed": (zbus::zvariant::Fd<'_>, FilePath, bool, bool) => DocumentID,
+        "AddNamedFull": (zbus::zvariant::Fd<'_>, FilePath, BitFlags<DocumentFlags>, &str, PermissionSet) => (DocumentID, AddFullResult),
+        "Delete": DocumentID => (),
+        "GetMountPoint": () => FilePath,
+        "GrantPermissions": (DocumentID, &AppID, PermissionSet) => (),
+        "Info": DocumentID => (FilePath, Permissions),
+        "List": &str => HashMap<String, FilePath>,
+        "Lookup": FilePath => String,
+        "RevokePermissions": (DocumentID, &AppID, PermissionSet) => (),
+        "GetHostPaths": (&[DocumentID],) => HashMap<DocumentID, FilePath>,
+    }
+};
 
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
 
-    use zbus::zvariant::Type;
+    use zbus::zvariant::{
+        serialized::{Context, Data},
+        to_bytes, Endian, OwnedValue, Type, Value,
+    };
+
+    use std::path::Path;
 
-    use crate::{app_id::DocumentID, documents::Permission, FilePath};
+    use crate::{
+        app_id::DocumentID,
+        documents::{
+            check_mount_point, document_path, is_shadowed_by_sandbox, split_document_path,
+            AddFullResult, MountError, Permission, PermissionSet,
+        },
+        FilePath,
+    };
 
     #[test]
     fn serialize_deserialize() {
@@ -533,4 +1335,139 @@ mod tests {
 
         assert_eq!(HashMap::<DocumentID, FilePath>::SIGNATURE, "a{say}");
     }
+
+    #[test]
+    fn deserialize_add_full_result() {
+        let mut extra: HashMap<&str, Value<'_>> = HashMap::new();
+        extra.insert("mountpoint", Value::from(b"/run/user/1000/doc\0".to_vec()));
+        extra.insert("unknown", Value::from(42u32));
+
+        let ctxt = Context::new_dbus(Endian::Little, 0);
+        let encoded = to_bytes(ctxt, &extra).unwrap().to_vec();
+        let result: AddFullResult = Data::new(encoded, ctxt).deserialize().unwrap().0;
+
+        assert_eq!(
+            result.mountpoint(),
+            Some(&FilePath::new("/run/user/1000/doc").unwrap())
+        );
+        assert_eq!(result.raw().get("unknown"), Some(&OwnedValue::from(42u32)));
+        assert_eq!(AddFullResult::SIGNATURE, "a{sv}");
+    }
+
+    #[test]
+    fn document_paths() {
+        let mount_point = Path::new("/run/user/1000/doc");
+        let doc_id = DocumentID::from("f2ee988d");
+
+        assert_eq!(
+            document_path(mount_point, &doc_id, Path::new("/home/user/file.txt")).unwrap(),
+            Path::new("/run/user/1000/doc/f2ee988d/file.txt")
+        );
+        assert!(document_path(mount_point, &doc_id, Path::new("/")).is_none());
+
+        let (id, relative) = split_document_path(
+            mount_point,
+            Path::new("/run/user/1000/doc/f2ee988d/file.txt"),
+        )
+        .unwrap();
+        assert_eq!(id, doc_id);
+        assert_eq!(relative, Path::new(""));
+
+        let (id, relative) = split_document_path(
+            mount_point,
+            Path::new("/run/user/1000/doc/f2ee988d/Music/Album/song.ogg"),
+        )
+        .unwrap();
+        assert_eq!(id, doc_id);
+        assert_eq!(relative, Path::new("Album/song.ogg"));
+
+        assert!(split_document_path(mount_point, Path::new("/home/user/file.txt")).is_none());
+        assert!(
+            split_document_path(mount_point, Path::new("/run/user/1000/doc/f2ee988d")).is_none()
+        );
+    }
+
+    #[test]
+    fn mount_point_checks() {
+        let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        assert!(matches!(
+            check_mount_point(&manifest_dir.join("missing")),
+            Err(MountError::Missing(_))
+        ));
+        assert!(matches!(
+            check_mount_point(&manifest_dir.join("src")),
+            Err(MountError::NotMounted(_))
+        ));
+        assert!(check_mount_point(Path::new("/proc")).is_ok());
+    }
+
+    #[test]
+    fn permission_set() {
+        let set = PermissionSet::from(&[Permission::Write, Permission::Read, Permission::Write]);
+        assert_eq!(set, PermissionSet::read_write());
+        assert_eq!(
+            set.iter().collect::<Vec<_>>(),
+            vec![Permission::Read, Permission::Write]
+        );
+        // The methods taking permissions accept the same arguments as when
+        // they took a slice.
+        fn into_set(permissions: impl Into<PermissionSet>) -> PermissionSet {
+            permissions.into()
+        }
+        let permissions = vec![Permission::Read, Permission::Write];
+        assert_eq!(into_set(&permissions), set);
+        assert_eq!(into_set(permissions.as_slice()), set);
+        assert_eq!(into_set(&permissions[..1]), PermissionSet::read_only());
+        assert!(set.validate().is_ok());
+        assert!(PermissionSet::empty().validate().is_ok());
+        assert!(PermissionSet::from(Permission::GrantPermissions)
+            .validate()
+            .is_err());
+        assert!(PermissionSet::all().validate().is_ok());
+        assert!(PermissionSet::all()
+            .without(Permission::Read)
+            .validate()
+            .is_err());
+
+        assert_eq!(PermissionSet::SIGNATURE, "as");
+        let string = serde_json::to_string(&PermissionSet::all()).unwrap();
+        assert_eq!(
+            string,
+            "[\"read\",\"write\",\"grant-permissions\",\"delete\"]"
+        );
+        let decoded: PermissionSet = serde_json::from_str(&string).unwrap();
+        assert_eq!(decoded, PermissionSet::all());
+    }
+
+    #[test]
+    fn shadowed_by_sandbox() {
+        assert!(is_shadowed_by_sandbox(Path::new("/usr/share/fonts")));
+        assert!(is_shadowed_by_sandbox(Path::new("/app")));
+        assert!(is_shadowed_by_sandbox(Path::new("/tmp/file.txt")));
+        assert!(is_shadowed_by_sandbox(Path::new("/var/tmp")));
+        assert!(!is_shadowed_by_sandbox(Path::new("/usrlocal")));
+        assert!(!is_shadowed_by_sandbox(Path::new("/home/user/file.txt")));
+        assert!(!is_shadowed_by_sandbox(Path::new("/run/media/user")));
+    }
+
+    #[cfg(feature = "test")]
+    #[tokio::test]
+    async fn export_root_directory() {
+        use crate::{documents::Documents, test::MockPortal, Error};
+
+        let _lock = crate::test::CONNECTION_LOCK.lock().await;
+        let mock = MockPortal::new().await.unwrap();
+        mock.set_version("org.freedesktop.portal.Documents", 4);
+
+        // The root directory has no name, it is never exported.
+        let documents = Documents::new().await.unwrap();
+        assert!(matches!(
+            documents.export_directory("/", false).await,
+            Err(Error::ParseError(_))
+        ));
+        assert!(!mock
+            .calls()
+            .iter()
+            .any(|call| call.header().member().unwrap() == "AddFull"));
+    }
 }
@@ -1,16 +1,30 @@
-use zbus::DBusError;
+use zbus::{message::Header, names::ErrorName, DBusError, Message};
 
-use crate::desktop::{dynamic_launcher::UnexpectedIconError, request::ResponseError};
+use crate::{
+    desktop::{
+        dynamic_launcher::UnexpectedIconError, request::ResponseError, settings::InvalidSetting,
+    },
+    documents::{FileTransferError, MountError, PermissionSet},
+};
 
 /// An error type that describes the various DBus errors.
 ///
+/// The message of the error is sent as its description when replying to a
+/// method call and read back when the reply is received, so it survives the
+/// trip between a portal backend and its clients.
+///
 /// See <https://github.com/flatpak/xdg-desktop-portal/blob/master/src/xdp-utils.h#L119-L127>.
+///
+/// New variants can be added for the D-Bus errors that gain a meaning for the
+/// portals, so matching on it requires a wildcard arm.
 #[allow(missing_docs)]
-#[derive(DBusError, Debug)]
-#[zbus(prefix = "org.freedesktop.portal.Error")]
+#[derive(Debug)]
+#[non_exhaustive]
 pub enum PortalError {
-    #[zbus(error)]
     /// ZBus specific error.
+    ///
+    /// Errors replied with a name that has no variant of its own keep their
+    /// name and description.
     ZBus(zbus::Error),
     /// Request failed.
     Failed(String),
@@ -26,6 +40,122 @@ pub enum PortalError {
     Cancelled(String),
     /// Window destroyed.
     WindowDestroyed(String),
+    /// The caller is not allowed to use the service, like when a sandboxed
+    /// application calls a backend directly.
+    ///
+    /// Replied as `org.freedesktop.DBus.Error.AccessDenied`.
+    AccessDenied(String),
+    /// The operation is not supported, usually by the portal backend.
+    ///
+    /// Replied as `org.freedesktop.DBus.Error.NotSupported`.
+    NotSupported(String),
+    /// No service provides the called name, the portal frontend is not
+    /// running and can't be activated.
+    ///
+    /// Replied as `org.freedesktop.DBus.Error.ServiceUnknown`.
+    ServiceUnknown(String),
+}
+
+impl PortalError {
+    fn from_name(name: &str, description: Option<&str>) -> Option<Self> {
+        let description = description.unwrap_or_default().to_owned();
+        let error = match name {
+            "org.freedesktop.portal.Error.Failed" => Self::Failed(description),
+            "org.freedesktop.portal.Error.InvalidArgument" => Self::InvalidArgument(description),
+            "org.freedesktop.portal.Error.NotFound" => Self::NotFound(description),
+            "org.freedesktop.portal.Error.Exist" => Self::Exist(description),
+            "org.freedesktop.portal.Error.NotAllowed" => Self::NotAllowed(description),
+            "org.freedesktop.portal.Error.Cancelled" => Self::Cancelled(description),
+            "org.freedesktop.portal.Error.WindowDestroyed" => Self::WindowDestroyed(description),
+            "org.freedesktop.DBus.Error.AccessDenied" => Self::AccessDenied(description),
+            "org.freedesktop.DBus.Error.NotSupported" => Self::NotSupported(description),
+            "org.freedesktop.DBus.Error.ServiceUnknown" => Self::ServiceUnknown(description),
+            _ => return None,
+        };
+        Some(error)
+    }
+}
+
+impl DBusError for PortalError {
+    fn create_reply(&self, call: &Header<'_>) -> zbus::Result<Message> {
+        let reply = Message::error(call, self.name())?;
+        match self {
+            Self::ZBus(zbus::Error::MethodError(..) | zbus::Error::FDO(_)) => (),
+            // The local errors have no D-Bus description.
+            Self::ZBus(e) => return reply.build(&(e.to_string(),)),
+            _ => (),
+        }
+        match self.description() {
+            Some(description) => reply.build(&(description,)),
+            None => reply.build(&()),
+        }
+    }
+
+    fn name(&self) -> ErrorName<'_> {
+        let name = match self {
+            Self::ZBus(zbus::Error::MethodError(name, ..)) => return name.into(),
+            Self::ZBus(zbus::Error::FDO(e)) => return e.name(),
+            Self::ZBus(_) => "org.freedesktop.zbus.Error",
+            Self::Failed(_) => "org.freedesktop.portal.Error.Failed",
+            Self::InvalidArgument(_) => "org.freedesktop.portal.Error.InvalidArgument",
+            Self::NotFound(_) => "org.freedesktop.portal.Error.NotFound",
+            Self::Exist(_) => "org.freedesktop.portal.Error.Exist",
+            Self::NotAllowed(_) => "org.freedesktop.portal.Error.NotAllowed",
+            Self::Cancelled(_) => "org.freedesktop.portal.Error.Cancelled",
+            Self::WindowDestroyed(_) => "org.freedesktop.portal.Error.WindowDestroyed",
+            Self::AccessDenied(_) => "org.freedesktop.DBus.Error.AccessDenied",
+            Self::NotSupported(_) => "org.freedesktop.DBus.Error.NotSupported",
+            Self::ServiceUnknown(_) => "org.freedesktop.DBus.Error.ServiceUnknown",
+        };
+        ErrorName::from_static_str_unchecked(name)
+    }
+
+    fn description(&self) -> Option<&str> {
+        match self {
+            Self::ZBus(zbus::Error::MethodError(_, description, _)) => description.as_deref(),
+            Self::ZBus(zbus::Error::FDO(e)) => e.description(),
+            Self::ZBus(e) => e.description(),
+            Self::Failed(description)
+            | Self::InvalidArgument(description)
+            | Self::NotFound(description)
+            | Self::Exist(description)
+            | Self::NotAllowed(description)
+            | Self::Cancelled(description)
+            | Self::WindowDestroyed(description)
+            | Self::AccessDenied(description)
+            | Self::NotSupported(description)
+            | Self::ServiceUnknown(description) => Some(description),
+        }
+    }
+}
+
+impl From<zbus::Error> for PortalError {
+    fn from(e: zbus::Error) -> Self {
+        let error = match &e {
+            zbus::Error::MethodError(name, description, _) => {
+                Self::from_name(name.as_str(), description.as_deref())
+            }
+            zbus::Error::FDO(fdo) => Self::from_name(fdo.name().as_str(), fdo.description()),
+            _ => None,
+        };
+        error.unwrap_or(Self::ZBus(e))
+    }
+}
+
+impl std::fmt::Display for PortalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let description = self.description().unwrap_or("no description");
+        write!(f, "{}: {description}", self.name())
+    }
+}
+
+impl std::error::Error for PortalError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::ZBus(e) => Some(e),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -40,6 +170,9 @@ pub enum Error {
     Zbus(zbus::Error),
     /// A signal returned no response.
     NoResponse,
+    /// The portal didn't reply to a method call within the timeout set on the
+    /// request.
+    Timeout,
     /// Failed to parse a string into an enum variant
     ParseError(&'static str),
     /// Input/Output
@@ -47,6 +180,10 @@ pub enum Error {
     /// A pipewire error
     #[cfg(feature = "pipewire")]
     Pipewire(pipewire::Error),
+    /// A GLib error that doesn't map to another variant, see
+    /// [`ErrorCode`](crate::glib_async::ErrorCode).
+    #[cfg(feature = "glib")]
+    Glib(glib::Error),
     /// Invalid AppId
     ///
     /// See <https://developer.gnome.org/documentation/tutorials/application-id.html#rules-for-application-ids>
@@ -64,12 +201,123 @@ pub enum Error {
     /// An error indicating that a Icon::Bytes was expected but wrong type was
     /// passed
     UnexpectedIcon,
+    /// A file transfer failed.
+    FileTransfer(FileTransferError),
+    /// The document portal fuse filesystem is not usable.
+    Mount(MountError),
+    /// A setting doesn't have the requested type.
+    InvalidSetting(InvalidSetting),
+    /// An argument was rejected before being sent to the portal.
+    InvalidArgument(String),
+    /// The set of document permissions is not valid, see
+    /// [`PermissionSet::validate`].
+    InvalidPermissions(PermissionSet),
     #[cfg(feature = "backend")]
     /// Failed to parse a URL.
     Url(url::ParseError),
 }
 
-impl std::error::Error for Error {}
+impl Error {
+    /// The D-Bus error replied by the portal, if the failure is one.
+    ///
+    /// It gives access to the error name and description without matching
+    /// on the variants wrapping it, including the errors that are neither
+    /// defined by the portals nor by the D-Bus specification.
+    pub fn as_dbus_error(&self) -> Option<DBusErrorRef<'_>> {
+        match self {
+            Self::Portal(PortalError::ZBus(e)) | Self::Zbus(e) => match e {
+                zbus::Error::FDO(e) => Some(DBusErrorRef::new(e.as_ref())),
+                zbus::Error::MethodError(name, description, _) => Some(DBusErrorRef {
+                    name: name.into(),
+                    description: description.as_deref(),
+                }),
+                _ => None,
+            },
+            Self::Portal(e) => Some(DBusErrorRef::new(e)),
+            _ => None,
+        }
+    }
+
+    /// The name of the D-Bus error replied by the portal, like
+    /// `org.freedesktop.portal.Error.NotAllowed`.
+    ///
+    /// Shortcut for the name of [`Error::as_dbus_error`].
+    pub fn dbus_error_name(&self) -> Option<ErrorName<'_>> {
+        self.as_dbus_error().map(|e| e.name)
+    }
+
+    /// Whether the error means that no frontend provides the portal.
+    pub(crate) fn is_portal_missing(&self) -> bool {
+        if matches!(self, Self::PortalNotFound(_)) {
+            return true;
+        }
+        self.dbus_error_name().is_some_and(|name| {
+            matches!(
+                name.as_str(),
+                "org.freedesktop.DBus.Error.ServiceUnknown"
+                    | "org.freedesktop.DBus.Error.NameHasNoOwner"
+                    | "org.freedesktop.DBus.Error.UnknownInterface"
+                    | "org.freedesktop.DBus.Error.UnknownObject"
+                    | "org.freedesktop.DBus.Error.UnknownMethod"
+            )
+        })
+    }
+}
+
+/// A D-Bus error borrowed from an [`Error`], see [`Error::as_dbus_error`].
+#[derive(Debug, Clone)]
+pub struct DBusErrorRef<'a> {
+    name: ErrorName<'a>,
+    description: Option<&'a str>,
+}
+
+impl<'a> DBusErrorRef<'a> {
+    fn new(e: &'a impl DBusError) -> Self {
+        Self {
+            name: e.name(),
+            description: e.description(),
+        }
+    }
+}
+
+impl DBusError for DBusErrorRef<'_> {
+    fn create_reply(&self, call: &Header<'_>) -> zbus::Result<Message> {
+        let reply = Message::error(call, &self.name)?;
+        match self.description {
+            Some(description) => reply.build(&(description,)),
+            None => reply.build(&()),
+        }
+    }
+
+    fn name(&self) -> ErrorName<'_> {
+        self.name.as_ref()
+    }
+
+    fn description(&self) -> Option<&str> {
+        self.description
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Response(e) => Some(e),
+            Self::Portal(e) => Some(e),
+            Self::Zbus(e) => Some(e),
+            Self::IO(e) => Some(e),
+            #[cfg(feature = "pipewire")]
+            Self::Pipewire(e) => Some(e),
+            #[cfg(feature = "glib")]
+            Self::Glib(e) => Some(e),
+            Self::FileTransfer(e) => Some(e),
+            Self::Mount(e) => Some(e),
+            Self::InvalidSetting(e) => Some(e),
+            #[cfg(feature = "backend")]
+            Self::Url(e) => Some(e),
+            _ => None,
+        }
+    }
+}
 
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -78,9 +326,12 @@ impl std::fmt::Display for Error {
             Self::Zbus(e) => f.write_str(&format!("ZBus Error: {e}")),
             Self::Portal(e) => f.write_str(&format!("Portal request failed: {e}")),
             Self::NoResponse => f.write_str("Portal error: no response"),
+            Self::Timeout => f.write_str("Portal error: timed out"),
             Self::IO(e) => f.write_str(&format!("IO: {e}")),
             #[cfg(feature = "pipewire")]
             Self::Pipewire(e) => f.write_str(&format!("Pipewire: {e}")),
+            #[cfg(feature = "glib")]
+            Self::Glib(e) => f.write_str(&format!("GLib: {e}")),
             Self::ParseError(e) => f.write_str(e),
             Self::InvalidAppID => f.write_str("Invalid app id"),
             Self::NulTerminated(u) => write!(f, "Nul byte found in provided data at position {u}"),
@@ -95,6 +346,11 @@ impl std::fmt::Display for Error {
                 f,
                 "Expected icon of type Icon::Bytes but a different type was used."
             ),
+            Self::FileTransfer(e) => f.write_str(&format!("File transfer: {e}")),
+            Self::Mount(e) => f.write_str(&format!("Document portal: {e}")),
+            Self::InvalidSetting(e) => f.write_str(&format!("Settings: {e}")),
+            Self::InvalidArgument(e) => write!(f, "Invalid argument: {e}"),
+            Self::InvalidPermissions(p) => write!(f, "Invalid document permissions: {p:?}"),
             #[cfg(feature = "backend")]
             Self::Url(e) => f.write_str(&format!("Parse error: {e}")),
         }
@@ -122,13 +378,22 @@ impl From<pipewire::Error> for Error {
 
 impl From<zbus::fdo::Error> for Error {
     fn from(e: zbus::fdo::Error) -> Self {
-        Self::Zbus(zbus::Error::FDO(Box::new(e)))
+        Self::from(zbus::Error::FDO(Box::new(e)))
     }
 }
 
+/// The errors with a name known by [`PortalError`] are decoded into its
+/// variants, the others are kept as is.
+///
+/// `org.freedesktop.DBus.Error.AccessDenied`, `NotSupported` and
+/// `ServiceUnknown` used to be kept as [`Error::Zbus`], they are now decoded
+/// into [`Error::Portal`] like the portal errors.
 impl From<zbus::Error> for Error {
     fn from(e: zbus::Error) -> Self {
-        Self::Zbus(e)
+        match PortalError::from(e) {
+            PortalError::ZBus(e) => Self::Zbus(e),
+            e => Self::Portal(e),
+        }
     }
 }
 
@@ -138,6 +403,12 @@ impl From<zbus::zvariant::Error> for Error {
     }
 }
 
+impl From<std::convert::Infallible> for Error {
+    fn from(e: std::convert::Infallible) -> Self {
+        match e {}
+    }
+}
+
 impl From<std::io::Error> for Error {
     fn from(e: std::io::Error) -> Self {
         Self::IO(e)
@@ -149,9 +420,145 @@ impl From<UnexpectedIconError> for Error {
         Self::UnexpectedIcon
     }
 }
+impl From<FileTransferError> for Error {
+    fn from(e: FileTransferError) -> Self {
+        Self::FileTransfer(e)
+    }
+}
+
+impl From<InvalidSetting> for Error {
+    fn from(e: InvalidSetting) -> Self {
+        Self::InvalidSetting(e)
+    }
+}
+
+impl From<MountError> for Error {
+    fn from(e: MountError) -> Self {
+        Self::Mount(e)
+    }
+}
+
 #[cfg(feature = "backend")]
 impl From<url::ParseError> for Error {
     fn from(e: url::ParseError) -> Self {
         Self::Url(e)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error as _;
+
+    use zbus::{names::OwnedErrorName, DBusError, Message};
+
+    use super::{Error, PortalError};
+
+    fn assert_send_sync<T: Send + Sync + 'static>() {}
+
+    #[test]
+    fn dbus_error() {
+        assert_send_sync::<Error>();
+        assert_send_sync::<PortalError>();
+
+        let err = Error::from(PortalError::NotAllowed("Denied".to_owned()));
+        assert_eq!(
+            err.dbus_error_name().unwrap(),
+            "org.freedesktop.portal.Error.NotAllowed"
+        );
+        assert_eq!(err.as_dbus_error().unwrap().description(), Some("Denied"));
+
+        let msg = Message::method_call("/org/freedesktop/portal/desktop", "Open")
+            .unwrap()
+            .build(&())
+            .unwrap();
+        let name = OwnedErrorName::try_from("org.gnome.Error.Custom").unwrap();
+        let err = Error::from(PortalError::ZBus(zbus::Error::MethodError(
+            name,
+            Some("Oops".to_owned()),
+            msg.clone(),
+        )));
+        assert_eq!(err.dbus_error_name().unwrap(), "org.gnome.Error.Custom");
+        assert_eq!(err.as_dbus_error().unwrap().description(), Some("Oops"));
+        assert!(err
+            .source()
+            .and_then(|e| e.source())
+            .unwrap()
+            .downcast_ref::<zbus::Error>()
+            .is_some());
+
+        // The unknown names are kept by the conversions.
+        let name = OwnedErrorName::try_from("org.gnome.Error.Unknown").unwrap();
+        let err = Error::from(zbus::Error::MethodError(name, None, msg.clone()));
+        assert!(matches!(err, Error::Zbus(zbus::Error::MethodError(..))));
+        let dbus_error = err.as_dbus_error().unwrap();
+        assert_eq!(dbus_error.name(), "org.gnome.Error.Unknown");
+        assert_eq!(dbus_error.description(), None);
+        assert_eq!(err.dbus_error_name().unwrap(), "org.gnome.Error.Unknown");
+        let reply = dbus_error.create_reply(&msg.header()).unwrap();
+        assert_eq!(
+            reply.header().error_name().unwrap(),
+            "org.gnome.Error.Unknown"
+        );
+
+        let err = Error::from(zbus::Error::InvalidReply);
+        assert!(err.as_dbus_error().is_none());
+        assert!(err.dbus_error_name().is_none());
+    }
+
+    #[test]
+    fn reply_round_trip() {
+        let call = Message::method_call("/org/freedesktop/portal/desktop", "Open")
+            .unwrap()
+            .build(&())
+            .unwrap();
+        let round_trip = |error: PortalError| {
+            let reply = error.create_reply(&call.header()).unwrap();
+            PortalError::from(zbus::Error::from(reply))
+        };
+
+        let error = round_trip(PortalError::InvalidArgument("Invalid URI".to_owned()));
+        assert!(matches!(error, PortalError::InvalidArgument(ref m) if m == "Invalid URI"));
+
+        let access_denied = zbus::fdo::Error::AccessDenied("Not allowed".to_owned());
+        let error = round_trip(PortalError::ZBus(zbus::Error::FDO(Box::new(access_denied))));
+        assert_eq!(error.name(), "org.freedesktop.DBus.Error.AccessDenied");
+        assert_eq!(DBusError::description(&error), Some("Not allowed"));
+    }
+
+    #[test]
+    fn well_known_names() {
+        let error = Error::from(zbus::fdo::Error::AccessDenied("Sandboxed".to_owned()));
+        assert!(matches!(
+            error,
+            Error::Portal(PortalError::AccessDenied(ref m)) if m == "Sandboxed"
+        ));
+        assert_eq!(
+            error.dbus_error_name().unwrap(),
+            "org.freedesktop.DBus.Error.AccessDenied"
+        );
+
+        let error = Error::from(zbus::fdo::Error::NotSupported("No backend".to_owned()));
+        assert!(matches!(error, Error::Portal(PortalError::NotSupported(_))));
+
+        let error = Error::from(zbus::fdo::Error::ServiceUnknown("Gone".to_owned()));
+        assert!(matches!(
+            error,
+            Error::Portal(PortalError::ServiceUnknown(_))
+        ));
+
+        let error = Error::from(zbus::fdo::Error::UnknownMethod("Nope".to_owned()));
+        assert!(matches!(error, Error::Zbus(zbus::Error::FDO(_))));
+    }
+
+    #[test]
+    fn portal_missing() {
+        let not_found = Error::PortalNotFound("org.freedesktop.portal.Camera".try_into().unwrap());
+        assert!(not_found.is_portal_missing());
+
+        let unknown = Error::from(zbus::fdo::Error::ServiceUnknown("gone".to_owned()));
+        assert!(unknown.is_portal_missing());
+
+        let denied = Error::Portal(PortalError::NotAllowed("denied".to_owned()));
+        assert!(!denied.is_portal_missing());
+    }
+}
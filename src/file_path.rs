@@ -1,14 +1,17 @@
 use std::{
-    ffi::{CString, OsStr},
-    os::unix::ffi::OsStrExt,
-    path::Path,
+    ffi::{CString, OsStr, OsString},
+    os::unix::ffi::{OsStrExt, OsStringExt},
+    path::{Path, PathBuf},
 };
 
 use serde::{Deserialize, Serialize};
 use zbus::zvariant::Type;
 
 /// A file name represented as a nul-terminated byte array.
-#[derive(Type, Debug, Default, PartialEq)]
+///
+/// The portals transmit the paths as bytes, so the paths that are not valid
+/// UTF-8 are passed as is.
+#[derive(Type, Debug, Default, Clone, PartialEq, Eq)]
 #[zvariant(signature = "ay")]
 pub struct FilePath(CString);
 
@@ -19,12 +22,79 @@ impl AsRef<Path> for FilePath {
 }
 
 impl FilePath {
-    pub(crate) fn new<T: AsRef<Path>>(s: T) -> Result<Self, crate::Error> {
-        let c_string = CString::new(s.as_ref().as_os_str().as_bytes())
-            .map_err(|err| crate::Error::NulTerminated(err.nul_position()))?;
+    /// Create a [`FilePath`] from a path.
+    ///
+    /// Fails with [`Error::NulTerminated`](crate::Error::NulTerminated) if
+    /// the path contains a nul byte.
+    pub fn new<T: AsRef<Path>>(s: T) -> Result<Self, crate::Error> {
+        Self::from_bytes(s.as_ref().as_os_str().as_bytes().to_vec())
+    }
+
+    /// Converts the optional path given to a builder setter.
+    ///
+    /// The setters only turn their generic argument into a `&Path`, the
+    /// conversion itself is compiled once.
+    pub(crate) fn optional(path: Option<&Path>) -> Result<Option<Self>, crate::Error> {
+        path.map(Self::new).transpose()
+    }
+
+    /// Create a [`FilePath`] from the bytes of a path, with or without the
+    /// trailing nul byte.
+    ///
+    /// Fails with [`Error::NulTerminated`](crate::Error::NulTerminated) if
+    /// the path contains another nul byte.
+    pub fn from_bytes(mut bytes: Vec<u8>) -> Result<Self, crate::Error> {
+        if bytes.last() == Some(&0) {
+            bytes.pop();
+        }
+        let c_string =
+            CString::new(bytes).map_err(|err| crate::Error::NulTerminated(err.nul_position()))?;
 
         Ok(Self(c_string))
     }
+
+    /// The bytes of the path, without the trailing nul byte.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+
+    /// The path.
+    pub fn as_path(&self) -> &Path {
+        self.as_ref()
+    }
+
+    /// Converts the [`FilePath`] to a [`PathBuf`].
+    pub fn into_path_buf(self) -> PathBuf {
+        OsString::from_vec(self.0.into_bytes()).into()
+    }
+}
+
+impl From<FilePath> for PathBuf {
+    fn from(value: FilePath) -> Self {
+        value.into_path_buf()
+    }
+}
+
+impl TryFrom<PathBuf> for FilePath {
+    type Error = crate::Error;
+
+    fn try_from(value: PathBuf) -> Result<Self, Self::Error> {
+        Self::from_bytes(value.into_os_string().into_vec())
+    }
+}
+
+impl TryFrom<&Path> for FilePath {
+    type Error = crate::Error;
+
+    fn try_from(value: &Path) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl From<CString> for FilePath {
+    fn from(value: CString) -> Self {
+        Self(value)
+    }
 }
 
 impl Serialize for FilePath {
@@ -41,11 +111,9 @@ impl<'de> Deserialize<'de> for FilePath {
     where
         D: serde::Deserializer<'de>,
     {
+        // Some implementations of the portals omit the trailing nul byte.
         let bytes = <Vec<u8>>::deserialize(deserializer)?;
-        let c_string = CString::from_vec_with_nul(bytes)
-            .map_err(|_| serde::de::Error::custom("Bytes are not nul-terminated"))?;
-
-        Ok(Self(c_string))
+        Self::from_bytes(bytes).map_err(serde::de::Error::custom)
     }
 }
 
@@ -85,4 +153,35 @@ mod tests {
         assert_eq!(decoded, file_path);
         assert_eq!(decoded, file_path_2);
     }
+
+    #[test]
+    fn non_utf8() {
+        let ctxt = Context::new_dbus(Endian::Little, 0);
+        let path = Path::new(OsStr::from_bytes(b"/tmp/caf\xe9.txt"));
+        assert!(path.to_str().is_none());
+
+        let file_path = FilePath::new(path).unwrap();
+        assert_eq!(file_path.as_bytes(), b"/tmp/caf\xe9.txt");
+        let encoded = to_bytes(ctxt, &file_path).unwrap().to_vec();
+        assert_eq!(
+            encoded,
+            to_bytes(ctxt, &b"/tmp/caf\xe9.txt\0".to_vec())
+                .unwrap()
+                .to_vec()
+        );
+        let decoded: FilePath = Data::new(encoded, ctxt).deserialize().unwrap().0;
+        assert_eq!(decoded.into_path_buf(), path);
+
+        // Without the trailing nul byte.
+        let encoded = to_bytes(ctxt, &b"/tmp/caf\xe9.txt".to_vec())
+            .unwrap()
+            .to_vec();
+        let decoded: FilePath = Data::new(encoded, ctxt).deserialize().unwrap().0;
+        assert_eq!(decoded.as_path(), path);
+
+        assert!(matches!(
+            FilePath::from_bytes(b"a\0b".to_vec()),
+            Err(crate::Error::NulTerminated(1))
+        ));
+    }
 }
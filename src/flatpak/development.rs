@@ -1,16 +1,78 @@
 //! The Development interface lets any client, possibly in a sandbox if it has
 //! access to the session helper, spawn a process on the host, outside any
 //! sandbox.
+//!
+//! # Examples
+//!
+//! Run a command on the host, like `flatpak-spawn --host` would.
+//!
+//! ```rust,no_run
+//! use ashpd::flatpak::HostCommandRequest;
+//!
+//! async fn run() -> ashpd::Result<()> {
+//!     let mut process = HostCommandRequest::default()
+//!         .argv(["flatpak", "list"])
+//!         .login_shell(true)
+//!         .spawn()
+//!         .await?;
+//!     println!("{}", process.wait().await?);
+//!
+//!     Ok(())
+//! }
+//! ```
 
-use std::{collections::HashMap, os::fd::AsFd, path::Path};
+use std::{
+    collections::HashMap,
+    ffi::OsString,
+    os::fd::{AsFd, OwnedFd},
+    path::{Path, PathBuf},
+};
 
 use enumflags2::{bitflags, BitFlags};
 use futures_util::Stream;
+use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 use zbus::zvariant::{Fd, Type};
 
+use super::{
+    spawned_process::{Pipes, Spawner},
+    SpawnedProcess,
+};
 use crate::{proxy::Proxy, Error, FilePath, Pid};
 
+/// Environment variables of the caller that are forwarded to the host, on
+/// top of the `LC_*` ones. The others, like `DBUS_SESSION_BUS_ADDRESS` or
+/// `LD_PRELOAD`, might only make sense inside of the sandbox.
+const HOST_ENV: &[&str] = &[
+    "LANG",
+    "LANGUAGE",
+    "TERM",
+    "COLORTERM",
+    "DISPLAY",
+    "WAYLAND_DISPLAY",
+    "TZ",
+];
+
+fn host_env(env: impl IntoIterator<Item = (OsString, OsString)>) -> HashMap<String, String> {
+    env.into_iter()
+        // The variables that are not valid UTF-8 can't be sent to the portal.
+        .filter_map(|(key, value)| Some((key.into_string().ok()?, value.into_string().ok()?)))
+        .filter(|(key, _)| key.starts_with("LC_") || HOST_ENV.contains(&key.as_str()))
+        .collect()
+}
+
+/// Wraps `argv` so that the executable is looked up in the `PATH` of a login
+/// shell on the host.
+fn login_shell_argv(argv: Vec<PathBuf>) -> Vec<PathBuf> {
+    let mut wrapped = vec![
+        PathBuf::from("sh"),
+        PathBuf::from("-lc"),
+        PathBuf::from("exec \"$0\" \"$@\""),
+    ];
+    wrapped.extend(argv);
+    wrapped
+}
+
 #[bitflags]
 #[derive(Serialize_repr, Deserialize_repr, PartialEq, Eq, Copy, Clone, Debug, Type)]
 #[repr(u32)]
@@ -24,6 +86,37 @@ pub enum HostCommandFlags {
     WatchBus,
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq, Eq)]
+/// A process started with [`Development::host_command`] that exited.
+pub struct HostCommandExited {
+    pid: u32,
+    wait_status: u32,
+}
+
+impl HostCommandExited {
+    /// The PID of the process that exited.
+    pub fn pid(&self) -> Pid {
+        self.pid
+    }
+
+    /// The raw wait status of the process, as returned by `waitpid`.
+    pub fn wait_status(&self) -> u32 {
+        self.wait_status
+    }
+
+    /// The exit code of the process, if it exited normally.
+    pub fn exit_code(&self) -> Option<i32> {
+        let status = self.wait_status as i32;
+        libc::WIFEXITED(status).then(|| libc::WEXITSTATUS(status))
+    }
+
+    /// The signal that terminated the process, if any.
+    pub fn signal(&self) -> Option<i32> {
+        let status = self.wait_status as i32;
+        libc::WIFSIGNALED(status).then(|| libc::WTERMSIG(status))
+    }
+}
+
 /// The Development interface lets any client, possibly in a sandbox if it has
 /// access to the session helper, spawn a process on the host, outside any
 /// sandbox.
@@ -51,6 +144,22 @@ impl<'a> Development<'a> {
         self.0.signal("HostCommandExited").await
     }
 
+    /// Emitted when a process started by
+    /// [`host_command()`][`Development::host_command`] exits.
+    ///
+    /// Same as [`receive_spawn_exited()`][`Development::receive_spawn_exited`]
+    /// but with a typed signal body.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`HostCommandExited`](https://docs.flatpak.org/en/latest/libflatpak-api-reference.html#gdbus-signal-org-freedesktop-Flatpak-Development.HostCommandExited).
+    #[doc(alias = "HostCommandExited")]
+    pub async fn receive_host_command_exited(
+        &self,
+    ) -> Result<impl Stream<Item = HostCommandExited>, Error> {
+        self.0.signal("HostCommandExited").await
+    }
+
     /// This method lets trusted applications (insider or outside a sandbox) run
     /// arbitrary commands in the user's session, outside any sandbox.
     ///
@@ -114,6 +223,25 @@ impl<'a> Development<'a> {
             .call("HostCommandSignal", &(pid, signal, to_process_group))
             .await
     }
+
+    /// Same as [`Development::host_command_signal`] but targets `process`.
+    ///
+    /// The session helper only takes a PID, the signal is not sent if
+    /// `process` exited already and its PID might have been reused. The
+    /// process can still exit while the call is in flight. It is only useful
+    /// when the caller shares the PID namespace of the host.
+    #[cfg(target_os = "linux")]
+    #[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+    #[doc(alias = "HostCommandSignal")]
+    pub async fn host_command_signal_process(
+        &self,
+        process: &crate::Process,
+        signal: u32,
+        to_process_group: bool,
+    ) -> Result<(), Error> {
+        self.host_command_signal(process.running_pid()?, signal, to_process_group)
+            .await
+    }
 }
 
 impl<'a> std::ops::Deref for Development<'a> {
@@ -123,3 +251,266 @@ impl<'a> std::ops::Deref for Development<'a> {
         &self.0
     }
 }
+
+impl crate::Versioned for Development<'_> {
+    fn version(&self) -> u32 {
+        self.0.version()
+    }
+}
+
+impl crate::Timeout for Development<'_> {
+    fn set_timeout(&mut self, timeout: impl Into<Option<std::time::Duration>>) {
+        self.0.set_timeout(timeout.into());
+    }
+}
+
+#[derive(Debug)]
+#[doc(alias = "flatpak-spawn")]
+/// A [builder-pattern] type to run a command on the host with
+/// [`Development::host_command`].
+///
+/// By default, the process receives the locale and display variables of the
+/// caller, on top of the environment of the session on the host, and is
+/// killed if the caller disappears from the session bus.
+///
+/// [builder-pattern]: https://doc.rust-lang.org/1.0.0/style/ownership/builders.html
+pub struct HostCommandRequest {
+    cwd: Option<PathBuf>,
+    argv: Vec<PathBuf>,
+    envs: HashMap<String, String>,
+    inherit_env: bool,
+    fds: HashMap<u32, OwnedFd>,
+    flags: BitFlags<HostCommandFlags>,
+    login_shell: bool,
+    pipe_stdin: bool,
+    pipe_stdout: bool,
+    pipe_stderr: bool,
+}
+
+impl Default for HostCommandRequest {
+    fn default() -> Self {
+        Self {
+            cwd: None,
+            argv: Vec::new(),
+            envs: HashMap::new(),
+            inherit_env: true,
+            fds: HashMap::new(),
+            flags: HostCommandFlags::WatchBus.into(),
+            login_shell: false,
+            pipe_stdin: false,
+            pipe_stdout: false,
+            pipe_stderr: false,
+        }
+    }
+}
+
+impl HostCommandRequest {
+    #[must_use]
+    /// Sets the working directory for the new process.
+    ///
+    /// Defaults to the current directory.
+    pub fn cwd(mut self, cwd: impl AsRef<Path>) -> Self {
+        self.cwd = Some(cwd.as_ref().to_owned());
+        self
+    }
+
+    #[must_use]
+    /// Sets the argv for the new process, starting with the executable to
+    /// launch.
+    pub fn argv<P: AsRef<Path>>(mut self, argv: impl IntoIterator<Item = P>) -> Self {
+        self.argv = argv.into_iter().map(|a| a.as_ref().to_owned()).collect();
+        self
+    }
+
+    #[must_use]
+    /// Adds an environment variable for the new process.
+    pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.envs.insert(key.into(), value.into());
+        self
+    }
+
+    #[must_use]
+    /// Whether to forward the locale and display variables of the caller,
+    /// `LANG`, `LC_*`, `TERM` or `WAYLAND_DISPLAY` for example. The rest of
+    /// the environment of the caller is never forwarded, the variables the
+    /// process needs have to be set with [`HostCommandRequest::env`].
+    /// Defaults to `true`.
+    pub fn inherit_env(mut self, inherit_env: bool) -> Self {
+        self.inherit_env = inherit_env;
+        self
+    }
+
+    #[must_use]
+    /// Passes `fd` to the new process, as the file descriptor number
+    /// `target`.
+    pub fn fd(mut self, target: u32, fd: impl Into<OwnedFd>) -> Self {
+        self.fds.insert(target, fd.into());
+        self
+    }
+
+    #[must_use]
+    /// Sets the flags, replacing the default [`HostCommandFlags::WatchBus`].
+    pub fn flags(mut self, flags: impl Into<BitFlags<HostCommandFlags>>) -> Self {
+        self.flags = flags.into();
+        self
+    }
+
+    #[must_use]
+    /// Whether to run the command through `sh -lc`, so the executable is
+    /// looked up in the `PATH` of the user's login shell on the host.
+    pub fn login_shell(mut self, login_shell: bool) -> Self {
+        self.login_shell = login_shell;
+        self
+    }
+
+    #[must_use]
+    /// Whether to connect the standard input of the process to a pipe.
+    pub fn pipe_stdin(mut self, pipe_stdin: bool) -> Self {
+        self.pipe_stdin = pipe_stdin;
+        self
+    }
+
+    #[must_use]
+    /// Whether to connect the standard output of the process to a pipe.
+    pub fn pipe_stdout(mut self, pipe_stdout: bool) -> Self {
+        self.pipe_stdout = pipe_stdout;
+        self
+    }
+
+    #[must_use]
+    /// Whether to connect the standard error of the process to a pipe.
+    pub fn pipe_stderr(mut self, pipe_stderr: bool) -> Self {
+        self.pipe_stderr = pipe_stderr;
+        self
+    }
+
+    /// Run the command on the host.
+    pub async fn spawn(mut self) -> Result<SpawnedProcess, Error> {
+        if self.argv.is_empty() {
+            return Err(Error::InvalidArgument(
+                "argv must contain at least the executable to launch".to_owned(),
+            ));
+        }
+        let pipes = Pipes::new(
+            &mut self.fds,
+            self.pipe_stdin,
+            self.pipe_stdout,
+            self.pipe_stderr,
+        )?;
+        let cwd = match self.cwd {
+            Some(cwd) => cwd,
+            None => std::env::current_dir()?,
+        };
+        let argv = if self.login_shell {
+            login_shell_argv(self.argv)
+        } else {
+            self.argv
+        };
+        let mut envs = if self.inherit_env {
+            host_env(std::env::vars_os())
+        } else {
+            HashMap::new()
+        };
+        envs.extend(self.envs);
+        let envs: HashMap<&str, &str> =
+            envs.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+
+        let proxy = Development::new().await?;
+        // Subscribe before spawning so the exit of short lived processes is not
+        // missed.
+        let exited = proxy.receive_signal("HostCommandExited").await?;
+        let pid = proxy
+            .host_command(cwd, &argv, self.fds, envs, self.flags)
+            .await?;
+        Ok(SpawnedProcess::new(
+            pid,
+            Spawner::Host(proxy),
+            None,
+            exited,
+            pipes,
+        ))
+    }
+}
+
+/// Run `argv` on the host, similar to `flatpak-spawn --host`.
+///
+/// The executable is looked up in the `PATH` of the user's login shell.
+/// See [`HostCommandRequest`] for more control over the spawned process.
+pub async fn spawn_host<P: AsRef<Path>>(
+    argv: impl IntoIterator<Item = P>,
+) -> Result<SpawnedProcess, Error> {
+    HostCommandRequest::default()
+        .argv(argv)
+        .login_shell(true)
+        .spawn()
+        .await
+}
+
+#[cfg(test)]
+pub(crate) const SIGNATURES: &[crate::proxy::WrapperSignature] = crate::proxy::wrapper_signatures! {
+    "org.freedesktop.Flatpak.Development" {
+        "HostCommand": (FilePath, Vec<FilePath>, HashMap<u32, zbus::zvariant::Fd<'_>>, HashMap<&str, &str>, BitFlags<HostCommandFlags>) => u32,
+        "HostCommandSignal": (crate::Pid, u32, bool) => (),
+    }
+};
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{host_env, login_shell_argv, HostCommandExited};
+
+    #[test]
+    fn host_environment() {
+        use std::{ffi::OsString, os::unix::ffi::OsStringExt};
+
+        let env = host_env(
+            [
+                ("HOME", "/home/user"),
+                ("PATH", "/app/bin:/usr/bin"),
+                ("FLATPAK_ID", "org.example.App"),
+                ("DBUS_SESSION_BUS_ADDRESS", "unix:path=/run/flatpak/bus"),
+                ("LD_PRELOAD", "/app/lib/libhook.so"),
+                ("LANG", "en_US.UTF-8"),
+                ("LC_TIME", "C"),
+                ("WAYLAND_DISPLAY", "wayland-0"),
+            ]
+            .into_iter()
+            .map(|(key, value)| (OsString::from(key), OsString::from(value)))
+            .chain([(OsString::from("TERM"), OsString::from_vec(b"\xff".to_vec()))]),
+        );
+        assert_eq!(env.len(), 3);
+        assert_eq!(env.get("LANG").unwrap(), "en_US.UTF-8");
+        assert_eq!(env.get("LC_TIME").unwrap(), "C");
+        assert_eq!(env.get("WAYLAND_DISPLAY").unwrap(), "wayland-0");
+    }
+
+    #[test]
+    fn login_shell() {
+        let argv = login_shell_argv(vec![PathBuf::from("ls"), PathBuf::from("-l")]);
+        assert_eq!(
+            argv,
+            ["sh", "-lc", "exec \"$0\" \"$@\"", "ls", "-l"]
+                .iter()
+                .map(PathBuf::from)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn host_command_exited() {
+        let exited = HostCommandExited {
+            pid: 42,
+            wait_status: 3 << 8,
+        };
+        assert_eq!(exited.exit_code(), Some(3));
+        assert_eq!(exited.signal(), None);
+
+        let killed = HostCommandExited {
+            pid: 42,
+            wait_status: libc::SIGKILL as u32,
+        };
+        assert_eq!(killed.exit_code(), None);
+        assert_eq!(killed.signal(), Some(libc::SIGKILL));
+    }
+}
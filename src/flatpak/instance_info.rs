@@ -0,0 +1,231 @@
+//! # Examples
+//!
+//! ```rust,no_run
+//! use ashpd::flatpak::InstanceInfo;
+//!
+//! async fn run() -> ashpd::Result<()> {
+//!     let info = InstanceInfo::current().await?;
+//!     println!("{:#?}", info.app_id());
+//!     if info.shared().iter().any(|s| s == "network") {
+//!         println!("The network is available");
+//!     }
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use std::str::FromStr;
+
+use crate::{keyfile, AppID, Error};
+
+const FLATPAK_INFO_PATH: &str = "/.flatpak-info";
+
+#[cfg_attr(feature = "glib", derive(glib::Enum))]
+#[cfg_attr(feature = "glib", enum_type(name = "AshpdFilesystemMode"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// How a filesystem location is shared with the sandbox.
+pub enum FilesystemMode {
+    /// Read and write access.
+    ReadWrite,
+    /// Read-only access.
+    ReadOnly,
+    /// Read and write access, the location is created if missing.
+    Create,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// A filesystem location the sandbox has access to, as set with
+/// `--filesystem`.
+pub struct FilesystemPermission {
+    location: String,
+    mode: FilesystemMode,
+}
+
+impl FilesystemPermission {
+    /// The location, either a path or a special value like `home` or
+    /// `xdg-download`.
+    pub fn location(&self) -> &str {
+        &self.location
+    }
+
+    /// The access mode.
+    pub fn mode(&self) -> FilesystemMode {
+        self.mode
+    }
+}
+
+impl FromStr for FilesystemPermission {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (location, mode) = match s.rsplit_once(':') {
+            Some((location, "ro")) => (location, FilesystemMode::ReadOnly),
+            Some((location, "rw")) => (location, FilesystemMode::ReadWrite),
+            Some((location, "create")) => (location, FilesystemMode::Create),
+            _ => (s, FilesystemMode::ReadWrite),
+        };
+        if location.is_empty() {
+            return Err(Error::ParseError(
+                "Failed to parse filesystem, empty location",
+            ));
+        }
+        Ok(Self {
+            location: location.to_owned(),
+            mode,
+        })
+    }
+}
+
+/// The metadata of the running Flatpak instance, as found in
+/// `/.flatpak-info`.
+#[derive(Debug, Clone, Default)]
+pub struct InstanceInfo {
+    sections: keyfile::KeyFile,
+}
+
+impl InstanceInfo {
+    /// Reads the metadata of the current instance.
+    ///
+    /// Fails with [`Error::IO`] if the application is not running inside
+    /// of a Flatpak sandbox.
+    pub async fn current() -> Result<Self, Error> {
+        crate::io::read_file_to_string(FLATPAK_INFO_PATH)
+            .await?
+            .parse()
+    }
+
+    /// The value of `key` in `section`, for the keys not covered by the
+    /// typed accessors.
+    pub fn get(&self, section: &str, key: &str) -> Option<&str> {
+        self.sections.get(section)?.get(key).map(String::as_str)
+    }
+
+    fn list(&self, section: &str, key: &str) -> Vec<String> {
+        self.get(section, key)
+            .map(keyfile::list)
+            .unwrap_or_default()
+    }
+
+    /// The ID of the application.
+    pub fn app_id(&self) -> Option<AppID> {
+        self.get("Application", "name")?.parse().ok()
+    }
+
+    /// The runtime reference, e.g. `runtime/org.gnome.Platform/x86_64/47`.
+    pub fn runtime(&self) -> Option<&str> {
+        self.get("Application", "runtime")
+    }
+
+    /// The ID of the running instance.
+    pub fn instance_id(&self) -> Option<&str> {
+        self.get("Instance", "instance-id")
+    }
+
+    /// The architecture of the application.
+    pub fn arch(&self) -> Option<&str> {
+        self.get("Instance", "arch")
+    }
+
+    /// The branch of the application.
+    pub fn branch(&self) -> Option<&str> {
+        self.get("Instance", "branch")
+    }
+
+    /// The version of Flatpak that started the instance.
+    pub fn flatpak_version(&self) -> Option<&str> {
+        self.get("Instance", "flatpak-version")
+    }
+
+    /// Whether the instance runs with `--devel`.
+    pub fn is_devel(&self) -> bool {
+        self.get("Instance", "devel") == Some("true")
+    }
+
+    /// The subsystems shared with the host, such as `network` or `ipc`.
+    pub fn shared(&self) -> Vec<String> {
+        self.list("Context", "shared")
+    }
+
+    /// The sockets the sandbox has access to, such as `wayland` or
+    /// `pulseaudio`.
+    pub fn sockets(&self) -> Vec<String> {
+        self.list("Context", "sockets")
+    }
+
+    /// The devices the sandbox has access to, such as `dri` or `all`.
+    pub fn devices(&self) -> Vec<String> {
+        self.list("Context", "devices")
+    }
+
+    /// The filesystem locations the sandbox has access to.
+    ///
+    /// Locations that are explicitly denied, with a `!` prefix, are skipped.
+    pub fn filesystems(&self) -> Vec<FilesystemPermission> {
+        self.list("Context", "filesystems")
+            .iter()
+            .filter(|fs| !fs.starts_with('!'))
+            .filter_map(|fs| fs.parse().ok())
+            .collect()
+    }
+}
+
+impl FromStr for InstanceInfo {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let sections = keyfile::parse(s).ok_or(Error::ParseError(
+            "Failed to parse the Flatpak instance info",
+        ))?;
+        Ok(Self { sections })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FilesystemMode, InstanceInfo};
+
+    const FLATPAK_INFO: &str = "[Application]
+name=com.belmoussaoui.ashpd.demo
+runtime=runtime/org.gnome.Platform/x86_64/47
+
+[Instance]
+instance-id=1828293931
+branch=master
+arch=x86_64
+flatpak-version=1.15.10
+devel=true
+
+[Context]
+shared=network;ipc;
+sockets=x11;wayland;pulseaudio;
+devices=dri;
+filesystems=xdg-download;home:ro;~/Games:create;!host;
+";
+
+    #[test]
+    fn parse_instance_info() {
+        let info = FLATPAK_INFO.parse::<InstanceInfo>().unwrap();
+        assert_eq!(
+            info.app_id().unwrap().as_ref(),
+            "com.belmoussaoui.ashpd.demo"
+        );
+        assert_eq!(info.runtime(), Some("runtime/org.gnome.Platform/x86_64/47"));
+        assert_eq!(info.instance_id(), Some("1828293931"));
+        assert_eq!(info.arch(), Some("x86_64"));
+        assert!(info.is_devel());
+        assert_eq!(info.shared(), vec!["network", "ipc"]);
+        assert_eq!(info.sockets(), vec!["x11", "wayland", "pulseaudio"]);
+        assert_eq!(info.devices(), vec!["dri"]);
+
+        let filesystems = info.filesystems();
+        assert_eq!(filesystems.len(), 3);
+        assert_eq!(filesystems[0].location(), "xdg-download");
+        assert_eq!(filesystems[0].mode(), FilesystemMode::ReadWrite);
+        assert_eq!(filesystems[1].location(), "home");
+        assert_eq!(filesystems[1].mode(), FilesystemMode::ReadOnly);
+        assert_eq!(filesystems[2].location(), "~/Games");
+        assert_eq!(filesystems[2].mode(), FilesystemMode::Create);
+
+        assert!("key=value".parse::<InstanceInfo>().is_err());
+    }
+}
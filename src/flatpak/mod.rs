@@ -25,12 +25,30 @@
 //!     Ok(())
 //! }
 //! ```
+//!
+//! Or using the [`SpawnRequest`](crate::flatpak::SpawnRequest) builder
+//!
+//! ```rust,no_run
+//! use ashpd::flatpak::{SpawnFlags, SpawnRequest};
+//!
+//! async fn run() -> ashpd::Result<()> {
+//!     let pid = SpawnRequest::default()
+//!         .argv(["contrast"])
+//!         .cwd("/")
+//!         .flags(SpawnFlags::ClearEnv | SpawnFlags::NoNetwork)
+//!         .send()
+//!         .await?;
+//!     println!("{pid}");
+//!
+//!     Ok(())
+//! }
+//! ```
 
 use std::{
     collections::HashMap,
     fmt::Debug,
     os::fd::{AsFd, OwnedFd},
-    path::Path,
+    path::{Path, PathBuf},
 };
 
 use enumflags2::{bitflags, BitFlags};
@@ -230,6 +248,21 @@ impl SpawnOptions {
     }
 }
 
+impl SpawnOptions {
+    /// Checks that the exposed files are plain file names.
+    fn validate(&self) -> Result<(), Error> {
+        let exposed = self.sandbox_expose.iter().chain(&self.sandbox_expose_ro);
+        for name in exposed.flatten() {
+            if name.is_empty() || name.contains('/') {
+                return Err(Error::InvalidArgument(format!(
+                    "`{name}` can't be exposed, only file names relative to the sandbox are allowed"
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
 #[derive(SerializeDict, Type, Debug, Default)]
 /// Specified options for a [`Flatpak::create_update_monitor`] request.
 ///
@@ -367,6 +400,25 @@ impl<'a> Flatpak<'a> {
             .await
     }
 
+    /// Same as [`Flatpak::spawn_signal`] but targets `process`.
+    ///
+    /// The portal only takes a PID, the signal is not sent if `process`
+    /// exited already and its PID might have been reused. The process can
+    /// still exit while the call is in flight. It is only useful when the
+    /// caller shares the PID namespace of the portal.
+    #[cfg(target_os = "linux")]
+    #[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+    #[doc(alias = "SpawnSignal")]
+    pub async fn spawn_signal_process(
+        &self,
+        process: &crate::Process,
+        signal: u32,
+        to_process_group: bool,
+    ) -> Result<(), Error> {
+        self.spawn_signal(process.running_pid()?, signal, to_process_group)
+            .await
+    }
+
     /// Flags marking what optional features are available.
     ///
     /// # Specifications
@@ -387,10 +439,263 @@ impl<'a> std::ops::Deref for Flatpak<'a> {
     }
 }
 
+impl crate::Versioned for Flatpak<'_> {
+    fn version(&self) -> u32 {
+        self.0.version()
+    }
+}
+
+impl crate::Timeout for Flatpak<'_> {
+    fn set_timeout(&mut self, timeout: impl Into<Option<std::time::Duration>>) {
+        self.0.set_timeout(timeout.into());
+    }
+}
+
+#[derive(Debug, Default)]
+#[doc(alias = "xdp_portal_spawn")]
+/// A [builder-pattern] type to spawn a new instance of the application with
+/// [`Flatpak::spawn`].
+///
+/// [builder-pattern]: https://doc.rust-lang.org/1.0.0/style/ownership/builders.html
+pub struct SpawnRequest {
+    cwd: Option<PathBuf>,
+    argv: Vec<PathBuf>,
+    envs: HashMap<String, String>,
+    fds: HashMap<u32, OwnedFd>,
+    flags: BitFlags<SpawnFlags>,
+    options: SpawnOptions,
+    pipe_stdin: bool,
+    pipe_stdout: bool,
+    pipe_stderr: bool,
+}
+
+impl SpawnRequest {
+    #[must_use]
+    /// Sets the working directory for the new process.
+    ///
+    /// Defaults to the current directory.
+    pub fn cwd(mut self, cwd: impl AsRef<Path>) -> Self {
+        self.cwd = Some(cwd.as_ref().to_owned());
+        self
+    }
+
+    #[must_use]
+    /// Sets the argv for the new process, starting with the executable to
+    /// launch.
+    pub fn argv<P: AsRef<Path>>(mut self, argv: impl IntoIterator<Item = P>) -> Self {
+        self.argv = argv.into_iter().map(|a| a.as_ref().to_owned()).collect();
+        self
+    }
+
+    #[must_use]
+    /// Adds an environment variable for the new process.
+    pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.envs.insert(key.into(), value.into());
+        self
+    }
+
+    #[must_use]
+    /// Adds multiple environment variables for the new process.
+    pub fn envs<K: Into<String>, V: Into<String>>(
+        mut self,
+        envs: impl IntoIterator<Item = (K, V)>,
+    ) -> Self {
+        self.envs
+            .extend(envs.into_iter().map(|(k, v)| (k.into(), v.into())));
+        self
+    }
+
+    #[must_use]
+    /// Passes `fd` to the new process, as the file descriptor number
+    /// `target`.
+    pub fn fd(mut self, target: u32, fd: impl Into<OwnedFd>) -> Self {
+        self.fds.insert(target, fd.into());
+        self
+    }
+
+    #[must_use]
+    /// Sets the flags affecting the created sandbox.
+    pub fn flags(mut self, flags: impl Into<BitFlags<SpawnFlags>>) -> Self {
+        self.flags = flags.into();
+        self
+    }
+
+    #[must_use]
+    /// Sets the sandbox options.
+    pub fn options(mut self, options: SpawnOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    #[must_use]
+    /// Whether to connect the standard input of the process to a pipe, only
+    /// used by [`SpawnRequest::spawn`].
+    pub fn pipe_stdin(mut self, pipe_stdin: bool) -> Self {
+        self.pipe_stdin = pipe_stdin;
+        self
+    }
+
+    #[must_use]
+    /// Whether to connect the standard output of the process to a pipe, only
+    /// used by [`SpawnRequest::spawn`].
+    pub fn pipe_stdout(mut self, pipe_stdout: bool) -> Self {
+        self.pipe_stdout = pipe_stdout;
+        self
+    }
+
+    #[must_use]
+    /// Whether to connect the standard error of the process to a pipe, only
+    /// used by [`SpawnRequest::spawn`].
+    pub fn pipe_stderr(mut self, pipe_stderr: bool) -> Self {
+        self.pipe_stderr = pipe_stderr;
+        self
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        if self.argv.is_empty() {
+            return Err(Error::InvalidArgument(
+                "argv must contain at least the executable to launch".to_owned(),
+            ));
+        }
+        self.options.validate()
+    }
+
+    /// Spawn the new process.
+    ///
+    /// # Returns
+    ///
+    /// The PID of the new process.
+    pub async fn send(self) -> Result<u32, Error> {
+        self.validate()?;
+        let cwd = match self.cwd {
+            Some(cwd) => cwd,
+            None => std::env::current_dir()?,
+        };
+        let envs: HashMap<&str, &str> = self
+            .envs
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+        let proxy = Flatpak::new().await?;
+        proxy
+            .spawn(cwd, &self.argv, self.fds, envs, self.flags, self.options)
+            .await
+    }
+
+    /// Spawn the new process and return a handle to it.
+    ///
+    /// The standard streams set to be piped are connected to the returned
+    /// [`SpawnedProcess`], overriding the file descriptors 0, 1 and 2 set
+    /// with [`SpawnRequest::fd`].
+    pub async fn spawn(mut self) -> Result<SpawnedProcess, Error> {
+        self.validate()?;
+        let pipes = Pipes::new(
+            &mut self.fds,
+            self.pipe_stdin,
+            self.pipe_stdout,
+            self.pipe_stderr,
+        )?;
+
+        let cwd = match self.cwd {
+            Some(cwd) => cwd,
+            None => std::env::current_dir()?,
+        };
+        let envs: HashMap<&str, &str> = self
+            .envs
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+        let proxy = Flatpak::new().await?;
+        // Subscribe before spawning so the exit of short lived processes is not
+        // missed.
+        let exited = proxy.receive_signal("SpawnExited").await?;
+        let started = if self
+            .flags
+            .intersects(SpawnFlags::ExposePids | SpawnFlags::SharePids)
+        {
+            Some(proxy.receive_signal("SpawnStarted").await?)
+        } else {
+            None
+        };
+        let pid = proxy
+            .spawn(cwd, &self.argv, self.fds, envs, self.flags, self.options)
+            .await?;
+        Ok(SpawnedProcess::new(
+            pid,
+            Spawner::Sandbox(proxy),
+            started,
+            exited,
+            pipes,
+        ))
+    }
+
+    /// Spawn the new process and return a guard that terminates it once
+    /// dropped.
+    ///
+    /// The [`SpawnFlags::WatchBus`] flag is always set, so the process is also
+    /// killed if the application exits without dropping the guard.
+    pub async fn spawn_guarded(mut self) -> Result<SpawnGuard, Error> {
+        self.flags.insert(SpawnFlags::WatchBus);
+        Ok(self.spawn().await?.kill_on_drop())
+    }
+}
+
+/// Read the metadata of the running Flatpak instance.
+mod instance_info;
+pub use instance_info::{FilesystemMode, FilesystemPermission, InstanceInfo};
+
+/// Interact with a process started by [`SpawnRequest::spawn`].
+mod spawned_process;
+pub use spawned_process::{namespace_pids, ProcessPipe, SpawnGuard, SpawnedProcess};
+use spawned_process::{Pipes, Spawner};
+
 /// Monitor if there's an update it and install it.
 mod update_monitor;
+#[cfg(test)]
+pub(crate) use update_monitor::SIGNATURES as UPDATE_MONITOR_SIGNATURES;
 pub use update_monitor::{UpdateInfo, UpdateMonitor, UpdateProgress, UpdateStatus};
 
 /// Provide for a way to execute processes outside of the sandbox
 mod development;
-pub use development::{Development, HostCommandFlags};
+#[cfg(test)]
+pub(crate) use development::SIGNATURES as DEVELOPMENT_SIGNATURES;
+pub use development::{
+    spawn_host, Development, HostCommandExited, HostCommandFlags, HostCommandRequest,
+};
+
+#[cfg(test)]
+pub(crate) const SIGNATURES: &[crate::proxy::WrapperSignature] = crate::proxy::wrapper_signatures! {
+    "org.freedesktop.portal.Flatpak" {
+        "CreateUpdateMonitor": CreateMonitorOptions => zbus::zvariant::OwnedObjectPath,
+        "Spawn": (FilePath, Vec<FilePath>, HashMap<u32, zbus::zvariant::Fd<'_>>, HashMap<&str, &str>, BitFlags<SpawnFlags>, SpawnOptions) => u32,
+        "SpawnSignal": (crate::Pid, u32, bool) => (),
+    }
+};
+
+#[cfg(test)]
+mod tests {
+    use super::{SpawnOptions, SpawnRequest};
+
+    #[test]
+    fn spawn_request_validation() {
+        assert!(SpawnRequest::default().validate().is_err());
+
+        let request = SpawnRequest::default().argv(["contrast"]);
+        assert!(request.validate().is_ok());
+
+        let request = SpawnRequest::default()
+            .argv(["contrast"])
+            .options(SpawnOptions::default().sandbox_expose(["output.png"]));
+        assert!(request.validate().is_ok());
+
+        let request = SpawnRequest::default()
+            .argv(["contrast"])
+            .options(SpawnOptions::default().sandbox_expose_ro(["/etc/passwd"]));
+        assert!(request.validate().is_err());
+
+        let request = SpawnRequest::default()
+            .argv(["contrast"])
+            .options(SpawnOptions::default().sandbox_expose(["data/output.png"]));
+        assert!(request.validate().is_err());
+    }
+}
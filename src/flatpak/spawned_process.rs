@@ -0,0 +1,411 @@
+//! # Examples
+//!
+//! Run a command in a new sandbox and read its output.
+//!
+//! ```rust,no_run
+//! use ashpd::flatpak::{SpawnFlags, SpawnRequest};
+//! use tokio::io::AsyncReadExt;
+//!
+//! async fn run() -> ashpd::Result<()> {
+//!     let mut process = SpawnRequest::default()
+//!         .argv(["ls", "/app"])
+//!         .flags(SpawnFlags::Sandbox)
+//!         .pipe_stdout(true)
+//!         .spawn()
+//!         .await?;
+//!
+//!     let mut output = String::new();
+//!     if let Some(mut stdout) = process.take_stdout() {
+//!         stdout.read_to_string(&mut output).await?;
+//!     }
+//!     let status = process.wait().await?;
+//!     println!("{output} exited with {status}");
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use std::{collections::HashMap, os::fd::OwnedFd};
+
+use futures_util::StreamExt;
+use zbus::proxy::SignalStream;
+
+use super::{Development, Flatpak};
+use crate::{
+    io::{into_async, pipe, AsyncPipe},
+    Error, Pid,
+};
+
+/// An asynchronous pipe connected to the standard input or output of a
+/// [`SpawnedProcess`].
+pub type ProcessPipe = AsyncPipe;
+
+/// The standard streams of a process connected to pipes.
+#[derive(Debug, Default)]
+pub(super) struct Pipes {
+    stdin: Option<ProcessPipe>,
+    stdout: Option<ProcessPipe>,
+    stderr: Option<ProcessPipe>,
+}
+
+impl Pipes {
+    /// Creates the requested pipes, inserting the ends meant for the child
+    /// process in `fds`.
+    pub(super) fn new(
+        fds: &mut HashMap<u32, OwnedFd>,
+        stdin: bool,
+        stdout: bool,
+        stderr: bool,
+    ) -> std::io::Result<Self> {
+        let mut pipes = Self::default();
+        if stdin {
+            let (read, write) = pipe()?;
+            fds.insert(0, read);
+            pipes.stdin = Some(into_async(write)?);
+        }
+        if stdout {
+            let (read, write) = pipe()?;
+            fds.insert(1, write);
+            pipes.stdout = Some(into_async(read)?);
+        }
+        if stderr {
+            let (read, write) = pipe()?;
+            fds.insert(2, write);
+            pipes.stderr = Some(into_async(read)?);
+        }
+        Ok(pipes)
+    }
+}
+
+/// The service that started a [`SpawnedProcess`].
+#[derive(Debug)]
+pub(super) enum Spawner {
+    /// Started in a sandbox by the Flatpak portal.
+    Sandbox(Flatpak<'static>),
+    /// Started on the host by the Flatpak session helper.
+    Host(Development<'static>),
+}
+
+/// A process started with [`SpawnRequest::spawn`](super::SpawnRequest::spawn)
+/// or [`HostCommandRequest::spawn`](super::HostCommandRequest::spawn).
+///
+/// Similar to a `std::process::Child`, the handle gives access to the piped
+/// standard streams of the process and allows to wait for it to exit or to
+/// send it a signal.
+#[derive(Debug)]
+pub struct SpawnedProcess {
+    pid: Pid,
+    relative_pid: Option<Pid>,
+    spawner: Spawner,
+    started: Option<SignalStream<'static>>,
+    exited: SignalStream<'static>,
+    exit_status: Option<u32>,
+    stdin: Option<ProcessPipe>,
+    stdout: Option<ProcessPipe>,
+    stderr: Option<ProcessPipe>,
+}
+
+impl SpawnedProcess {
+    pub(super) fn new(
+        pid: Pid,
+        spawner: Spawner,
+        started: Option<SignalStream<'static>>,
+        exited: SignalStream<'static>,
+        pipes: Pipes,
+    ) -> Self {
+        Self {
+            pid,
+            relative_pid: None,
+            spawner,
+            started,
+            exited,
+            exit_status: None,
+            stdin: pipes.stdin,
+            stdout: pipes.stdout,
+            stderr: pipes.stderr,
+        }
+    }
+
+    /// The PID of the process, as returned by the portal.
+    pub fn pid(&self) -> Pid {
+        self.pid
+    }
+
+    /// The PID of the process in the PID namespace of the caller, to
+    /// correlate it with the processes listed in `/proc` for example.
+    ///
+    /// Only available for the processes spawned in a sandbox with the
+    /// [`SpawnFlags::ExposePids`](super::SpawnFlags::ExposePids) or [`SpawnFlags::SharePids`](super::SpawnFlags::SharePids) flags, it is
+    /// reported by the portal once the process started.
+    ///
+    /// Fails with [`Error::NoResponse`] if the portal doesn't report it.
+    pub async fn relative_pid(&mut self) -> Result<Pid, Error> {
+        if let Some(relative_pid) = self.relative_pid {
+            return Ok(relative_pid);
+        }
+        let Some(started) = &mut self.started else {
+            return Err(Error::NoResponse);
+        };
+        while let Some(msg) = started.next().await {
+            let Ok((pid, relative_pid)) = msg.body().deserialize::<(u32, u32)>() else {
+                continue;
+            };
+            if pid == self.pid {
+                self.relative_pid = Some(relative_pid);
+                self.started = None;
+                return Ok(relative_pid);
+            }
+        }
+        Err(Error::NoResponse)
+    }
+
+    /// The standard input of the process, if it was piped.
+    pub fn stdin(&mut self) -> Option<&mut ProcessPipe> {
+        self.stdin.as_mut()
+    }
+
+    /// The standard output of the process, if it was piped.
+    pub fn stdout(&mut self) -> Option<&mut ProcessPipe> {
+        self.stdout.as_mut()
+    }
+
+    /// The standard error of the process, if it was piped.
+    pub fn stderr(&mut self) -> Option<&mut ProcessPipe> {
+        self.stderr.as_mut()
+    }
+
+    /// Takes ownership of the standard input of the process.
+    ///
+    /// Dropping it closes the pipe, which signals an end of file to the
+    /// process.
+    pub fn take_stdin(&mut self) -> Option<ProcessPipe> {
+        self.stdin.take()
+    }
+
+    /// Takes ownership of the standard output of the process.
+    pub fn take_stdout(&mut self) -> Option<ProcessPipe> {
+        self.stdout.take()
+    }
+
+    /// Takes ownership of the standard error of the process.
+    pub fn take_stderr(&mut self) -> Option<ProcessPipe> {
+        self.stderr.take()
+    }
+
+    /// Waits for the process to exit.
+    ///
+    /// The standard input is closed before waiting, to avoid deadlocks.
+    ///
+    /// # Returns
+    ///
+    /// The wait status of the process, as returned by `waitpid`.
+    pub async fn wait(&mut self) -> Result<u32, Error> {
+        drop(self.stdin.take());
+        if let Some(status) = self.exit_status {
+            return Ok(status);
+        }
+        while let Some(msg) = self.exited.next().await {
+            let Ok((pid, status)) = msg.body().deserialize::<(u32, u32)>() else {
+                continue;
+            };
+            if pid == self.pid {
+                self.exit_status = Some(status);
+                return Ok(status);
+            }
+        }
+        Err(Error::NoResponse)
+    }
+
+    /// Turns the handle into a [`SpawnGuard`] that terminates the process
+    /// when dropped.
+    pub fn kill_on_drop(self) -> SpawnGuard {
+        SpawnGuard {
+            process: Some(self),
+            signal: libc::SIGTERM as u32,
+        }
+    }
+
+    /// Sends `signal` to the process without waiting for the reply, used
+    /// when the handle can't await anymore.
+    fn signal_detached(&self, signal: u32) {
+        if self.exit_status.is_some() {
+            return;
+        }
+        let (proxy, method) = match &self.spawner {
+            Spawner::Sandbox(proxy) => ((**proxy).clone(), "SpawnSignal"),
+            Spawner::Host(proxy) => ((**proxy).clone(), "HostCommandSignal"),
+        };
+        let pid = self.pid;
+        let connection = proxy.connection().clone();
+        crate::helpers::spawn(&connection, "ashpd spawn guard", async move {
+            // The process might have exited in the meantime.
+            let _ = proxy.call_method(method, &(pid, signal, false)).await;
+        });
+    }
+
+    /// Sends the Unix `signal` to the process.
+    ///
+    /// See also [`Flatpak::spawn_signal`] and
+    /// [`Development::host_command_signal`].
+    pub async fn kill(&self, signal: u32) -> Result<(), Error> {
+        match &self.spawner {
+            Spawner::Sandbox(proxy) => proxy.spawn_signal(self.pid, signal, false).await,
+            Spawner::Host(proxy) => proxy.host_command_signal(self.pid, signal, false).await,
+        }
+    }
+}
+
+/// The PIDs of the process `pid` in each of the nested PID namespaces it
+/// belongs to, from the namespace of the caller to the innermost one.
+///
+/// A process spawned with [`SpawnFlags::ExposePids`](super::SpawnFlags::ExposePids) is visible from the
+/// caller's namespace, this translates its PID to the one it has inside of
+/// its own sandbox. The PIDs are read from the `NSpid` field of
+/// `/proc/<pid>/status`.
+pub async fn namespace_pids(pid: Pid) -> Result<Vec<Pid>, Error> {
+    let status = crate::io::read_file_to_string(format!("/proc/{pid}/status")).await?;
+    parse_namespace_pids(&status).ok_or(Error::ParseError("Missing NSpid field"))
+}
+
+fn parse_namespace_pids(status: &str) -> Option<Vec<Pid>> {
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("NSpid:"))?
+        .split_whitespace()
+        .map(|pid| pid.parse().ok())
+        .collect()
+}
+
+/// A [`SpawnedProcess`] that is sent a signal, `SIGTERM` by default, when the
+/// guard is dropped.
+///
+/// Together with the `WatchBus` flags, which kill the process once the caller
+/// disconnects from the session bus, it ensures helper processes do not
+/// outlive the application that started them.
+///
+/// Dropping the guard is best-effort: the signal is sent from a background
+/// task without waiting for the reply, so the process may still be running
+/// once the guard is gone. Use [`SpawnGuard::kill`] to know that the process
+/// got signaled. With the `tokio` feature, the guard has to be dropped within
+/// the context of the runtime, like the signal streams it holds.
+///
+/// See [`SpawnRequest::spawn_guarded`](super::SpawnRequest::spawn_guarded)
+/// and [`SpawnedProcess::kill_on_drop`].
+#[derive(Debug)]
+pub struct SpawnGuard {
+    process: Option<SpawnedProcess>,
+    signal: u32,
+}
+
+impl SpawnGuard {
+    /// Sets the Unix signal sent to the process when the guard is dropped.
+    #[must_use]
+    pub fn signal(mut self, signal: u32) -> Self {
+        self.signal = signal;
+        self
+    }
+
+    /// Sends the signal of the guard to the process and waits for the
+    /// portal to reply.
+    pub async fn kill(mut self) -> Result<(), Error> {
+        let process = self.process.take().expect("SpawnGuard without a process");
+        if process.exit_status.is_some() {
+            return Ok(());
+        }
+        process.kill(self.signal).await
+    }
+
+    /// Releases the process, it is no longer signaled once the handle is
+    /// dropped.
+    pub fn into_inner(mut self) -> SpawnedProcess {
+        self.process.take().expect("SpawnGuard without a process")
+    }
+}
+
+impl std::ops::Deref for SpawnGuard {
+    type Target = SpawnedProcess;
+
+    fn deref(&self) -> &Self::Target {
+        self.process.as_ref().expect("SpawnGuard without a process")
+    }
+}
+
+impl std::ops::DerefMut for SpawnGuard {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.process.as_mut().expect("SpawnGuard without a process")
+    }
+}
+
+impl Drop for SpawnGuard {
+    fn drop(&mut self) {
+        if let Some(process) = self.process.take() {
+            process.signal_detached(self.signal);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_nspid() {
+        let status = "Name:\tbash\nPid:\t4321\nNSpid:\t4321\t2\nNSpgid:\t4321\t2\n";
+        assert_eq!(parse_namespace_pids(status), Some(vec![4321, 2]));
+        assert_eq!(parse_namespace_pids("Pid:\t1\n"), None);
+    }
+
+    #[tokio::test]
+    async fn own_namespace_pids() {
+        let pid = std::process::id();
+        assert_eq!(namespace_pids(pid).await.unwrap()[0], pid);
+    }
+
+    #[cfg(feature = "test")]
+    #[test]
+    fn spawn_guard() {
+        use crate::{
+            flatpak::SpawnRequest,
+            test::{MockPortal, MockResponse},
+        };
+
+        const INTERFACE: &str = "org.freedesktop.portal.Flatpak";
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let _lock = runtime.block_on(crate::test::CONNECTION_LOCK.lock());
+        let mock = runtime.block_on(MockPortal::new()).unwrap();
+        mock.respond(INTERFACE, "Spawn", MockResponse::reply((42u32,)));
+        mock.respond(INTERFACE, "SpawnSignal", MockResponse::empty());
+        let signals = || {
+            mock.calls()
+                .into_iter()
+                .filter(|call| call.header().member().unwrap() == "SpawnSignal")
+                .map(|call| call.body().deserialize::<(u32, u32, bool)>().unwrap())
+                .collect::<Vec<_>>()
+        };
+        let spawn = || {
+            SpawnRequest::default()
+                .argv(["sleep", "10"])
+                .spawn_guarded()
+        };
+
+        runtime.block_on(async {
+            let guard = spawn().await.unwrap().signal(libc::SIGKILL as u32);
+            guard.kill().await.unwrap();
+        });
+        assert_eq!(signals(), [(42, libc::SIGKILL as u32, false)]);
+
+        // Dropped while the runtime is not polled, the signal is sent once it
+        // runs again.
+        let guard = runtime.block_on(spawn()).unwrap();
+        {
+            let _runtime = runtime.enter();
+            drop(guard);
+        }
+        runtime.block_on(crate::test::sleep(std::time::Duration::from_millis(50)));
+        assert_eq!(signals().len(), 2);
+    }
+}
@@ -25,10 +25,28 @@
 //!     Ok(())
 //! }
 //! ```
+//!
+//! Or install it and wait for the installation to finish.
+//!
+//! ```rust,no_run
+//! use ashpd::flatpak::{Flatpak, UpdateStatus};
+//!
+//! async fn run() -> ashpd::Result<()> {
+//!     let proxy = Flatpak::new().await?;
+//!
+//!     let monitor = proxy.create_update_monitor().await?;
+//!     let progress = monitor.update_and_wait(None).await?;
+//!     if progress.status == Some(UpdateStatus::Done) {
+//!         println!("Updated, restart to use the new version");
+//!     }
+//!
+//!     Ok(())
+//! }
+//! ```
 
-use futures_util::Stream;
+use futures_util::{Stream, StreamExt};
 use serde_repr::{Deserialize_repr, Serialize_repr};
-use zbus::zvariant::{DeserializeDict, ObjectPath, SerializeDict, Type};
+use zbus::zvariant::{ObjectPath, SerializeDict, Type};
 
 use crate::{proxy::Proxy, Error, WindowIdentifier};
 
@@ -39,16 +57,17 @@ use crate::{proxy::Proxy, Error, WindowIdentifier};
 #[zvariant(signature = "dict")]
 struct UpdateOptions {}
 
-#[derive(DeserializeDict, Type, Debug)]
-/// A response containing the update information when an update is available.
-#[zvariant(signature = "dict")]
-pub struct UpdateInfo {
-    #[zvariant(rename = "running-commit")]
-    running_commit: String,
-    #[zvariant(rename = "local-commit")]
-    local_commit: String,
-    #[zvariant(rename = "remote-commit")]
-    remote_commit: String,
+crate::helpers::dict! {
+    #[derive(Debug)]
+    /// A response containing the update information when an update is available.
+    pub struct UpdateInfo {
+        #[serde(rename = "running-commit")]
+        running_commit: String,
+        #[serde(rename = "local-commit")]
+        local_commit: String,
+        #[serde(rename = "remote-commit")]
+        remote_commit: String,
+    }
 }
 
 impl UpdateInfo {
@@ -88,23 +107,49 @@ pub enum UpdateStatus {
     Failed = 3,
 }
 
-#[derive(DeserializeDict, Type, Debug)]
-/// A response of the update progress signal.
-#[zvariant(signature = "dict")]
-pub struct UpdateProgress {
-    /// The number of operations that the update consists of.
-    pub n_ops: Option<u32>,
-    /// The position of the currently active operation.
-    pub op: Option<u32>,
-    /// The progress of the currently active operation, as a number between 0
-    /// and 100.
-    pub progress: Option<u32>,
-    /// The overall status of the update.
-    pub status: Option<UpdateStatus>,
-    /// The error name, sent when status is `UpdateStatus::Failed`.
-    pub error: Option<String>,
-    /// The error message, sent when status is `UpdateStatus::Failed`.
-    pub error_message: Option<String>,
+crate::helpers::dict! {
+    #[derive(Debug)]
+    /// A response of the update progress signal.
+    pub struct UpdateProgress {
+        /// The number of operations that the update consists of.
+        pub n_ops: Option<u32>,
+        /// The position of the currently active operation.
+        pub op: Option<u32>,
+        /// The progress of the currently active operation, as a number between 0
+        /// and 100.
+        pub progress: Option<u32>,
+        /// The overall status of the update.
+        pub status: Option<UpdateStatus>,
+        /// The error name, sent when status is `UpdateStatus::Failed`.
+        pub error: Option<String>,
+        /// The error message, sent when status is `UpdateStatus::Failed`.
+        pub error_message: Option<String>,
+    }
+}
+
+impl UpdateProgress {
+    /// Whether the update is over, either because it is done, failed or
+    /// there was nothing to install.
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self.status,
+            Some(UpdateStatus::Done | UpdateStatus::Failed | UpdateStatus::Empty)
+        )
+    }
+
+    /// The overall progress of the update, as a number between 0 and 1.
+    ///
+    /// It combines the position of the active operation with its own
+    /// progress.
+    pub fn fraction(&self) -> Option<f64> {
+        if let Some(UpdateStatus::Done | UpdateStatus::Empty) = self.status {
+            return Some(1.0);
+        }
+        let n_ops = self.n_ops.filter(|n| *n > 0)?;
+        let op = self.op.unwrap_or_default().min(n_ops);
+        let progress = self.progress.unwrap_or_default().min(100);
+        Some(((op as f64) + (progress as f64) / 100.0).min(n_ops as f64) / (n_ops as f64))
+    }
 }
 
 /// The interface exposes some interactions with Flatpak on the host to the
@@ -139,6 +184,24 @@ impl<'a> UpdateMonitor<'a> {
         self.0.signal("Progress").await
     }
 
+    /// Same as [`UpdateMonitor::receive_progress`] but the stream ends after
+    /// yielding the progress that finishes the update, see
+    /// [`UpdateProgress::is_finished`].
+    pub async fn receive_progress_until_finished(
+        &self,
+    ) -> Result<impl Stream<Item = UpdateProgress>, Error> {
+        Ok(self
+            .receive_progress()
+            .await?
+            .scan(false, |finished, progress| {
+                if *finished {
+                    return std::future::ready(None);
+                }
+                *finished = progress.is_finished();
+                std::future::ready(Some(progress))
+            }))
+    }
+
     /// A signal received when there's an application update.
     ///
     /// # Specifications
@@ -167,6 +230,27 @@ impl<'a> UpdateMonitor<'a> {
         self.0.call("Update", &(&identifier, options)).await
     }
 
+    /// Asks to install an update of the calling app and waits for the
+    /// installation to finish.
+    ///
+    /// # Returns
+    ///
+    /// The last [`UpdateProgress`], its status tells whether the update
+    /// succeeded or failed.
+    pub async fn update_and_wait(
+        &self,
+        identifier: Option<&WindowIdentifier>,
+    ) -> Result<UpdateProgress, Error> {
+        let progress = self.receive_progress_until_finished().await?;
+        futures_util::pin_mut!(progress);
+        self.update(identifier).await?;
+        let mut last = None;
+        while let Some(item) = progress.next().await {
+            last = Some(item);
+        }
+        last.ok_or(Error::NoResponse)
+    }
+
     /// Ends the update monitoring and cancels any ongoing installation.
     ///
     /// # Specifications
@@ -185,3 +269,53 @@ impl<'a> std::ops::Deref for UpdateMonitor<'a> {
         &self.0
     }
 }
+
+impl crate::Versioned for UpdateMonitor<'_> {
+    fn version(&self) -> u32 {
+        self.0.version()
+    }
+}
+
+impl crate::Timeout for UpdateMonitor<'_> {
+    fn set_timeout(&mut self, timeout: impl Into<Option<std::time::Duration>>) {
+        self.0.set_timeout(timeout.into());
+    }
+}
+
+#[cfg(test)]
+pub(crate) const SIGNATURES: &[crate::proxy::WrapperSignature] = crate::proxy::wrapper_signatures! {
+    "org.freedesktop.portal.Flatpak.UpdateMonitor" {
+        "Update": (&str, UpdateOptions) => (),
+        "Close": () => (),
+    }
+};
+
+#[cfg(test)]
+mod tests {
+    use super::{UpdateProgress, UpdateStatus};
+
+    fn progress(n_ops: u32, op: u32, progress: u32, status: UpdateStatus) -> UpdateProgress {
+        UpdateProgress {
+            n_ops: Some(n_ops),
+            op: Some(op),
+            progress: Some(progress),
+            status: Some(status),
+            error: None,
+            error_message: None,
+        }
+    }
+
+    #[test]
+    fn update_progress() {
+        let running = progress(4, 1, 50, UpdateStatus::Running);
+        assert!(!running.is_finished());
+        assert_eq!(running.fraction(), Some(0.375));
+
+        let done = progress(4, 4, 100, UpdateStatus::Done);
+        assert!(done.is_finished());
+        assert_eq!(done.fraction(), Some(1.0));
+
+        assert!(progress(4, 2, 0, UpdateStatus::Failed).is_finished());
+        assert_eq!(progress(0, 0, 0, UpdateStatus::Running).fraction(), None);
+    }
+}
@@ -0,0 +1,349 @@
+//! A gio-style API over the main portals, for applications driven by a
+//! [`glib::MainContext`].
+//!
+//! The functions follow the conventions of the `_future` variants of the gio
+//! bindings: they take an optional [`gio::Cancellable`] and return a
+//! `'static` future that can be spawned with
+//! [`glib::MainContext::spawn_local`], resolving to a [`glib::Error`] on
+//! failure. Cancelled operations, either through the cancellable or by the
+//! user, fail with [`gio::IOErrorEnum::Cancelled`]; the other errors use the
+//! [`ErrorCode`] domain, except the [`Error::Glib`] ones that are passed
+//! through.
+//!
+//! The futures are polled by the main context, but the D-Bus connection and
+//! the pipes still rely on the runtime selected by the features of the
+//! crate. With the default `tokio` feature, a multi-threaded tokio runtime
+//! has to be alive and entered, with `Runtime::enter`, on the thread of the
+//! main context. With the `async-io` feature instead, the reactor runs on its
+//! own thread and nothing else is needed.
+//!
+//! # Examples
+//!
+//! ```rust,ignore
+//! use ashpd::glib_async;
+//!
+//! let cancellable = gio::Cancellable::new();
+//! glib::MainContext::default().spawn_local(async move {
+//!     match glib_async::pick_color_future(None, Some(&cancellable)).await {
+//!         Ok(color) => println!("{color:#?}"),
+//!         Err(err) if err.matches(gio::IOErrorEnum::Cancelled) => (),
+//!         Err(err) => eprintln!("Failed to pick a color: {err}"),
+//!     }
+//! });
+//! ```
+
+use std::{future::Future, pin::Pin};
+
+use crate::{
+    desktop::{
+        file_chooser::SelectedFiles, open_uri::OpenFileRequest, screenshot::Screenshot, Color,
+        ResponseError,
+    },
+    Error, PortalError, WindowIdentifier,
+};
+
+/// A future returned by the functions of this module.
+pub type PortalFuture<T> = Pin<Box<dyn Future<Output = Result<T, glib::Error>> + 'static>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, glib::ErrorDomain)]
+#[error_domain(name = "ashpd-error-quark")]
+/// The error codes of the [`glib::Error`]s created from an [`Error`].
+///
+/// Each code covers a category of [`Error`]s, the message of the
+/// [`glib::Error`] describing the error itself. The errors replied by the
+/// portals keep their description as message, so they can be converted back
+/// into an [`Error`].
+pub enum ErrorCode {
+    /// The request failed.
+    Failed,
+    /// The portal did not allow the request.
+    NotAllowed,
+    /// The requested resource was not found.
+    NotFound,
+    /// Invalid arguments were passed.
+    InvalidArgument,
+    /// The portal is not available.
+    PortalNotFound,
+    /// The portal is too old for the request.
+    RequiresVersion,
+    /// The resource exists already.
+    Exist,
+    /// The window the request was made for was destroyed.
+    WindowDestroyed,
+    /// The portal sent no response.
+    NoResponse,
+    /// The D-Bus communication with the portal failed.
+    DBus,
+    /// Reading or writing a file, a pipe or a socket failed.
+    Io,
+    /// The portal didn't reply in time.
+    TimedOut,
+}
+
+impl From<Error> for glib::Error {
+    fn from(err: Error) -> Self {
+        let code = match &err {
+            Error::Response(ResponseError::Cancelled)
+            | Error::Portal(PortalError::Cancelled(_)) => None,
+            Error::Response(ResponseError::Other) | Error::Portal(PortalError::Failed(_)) => {
+                Some(ErrorCode::Failed)
+            }
+            Error::Portal(PortalError::NotAllowed(_) | PortalError::AccessDenied(_)) => {
+                Some(ErrorCode::NotAllowed)
+            }
+            Error::Portal(PortalError::NotSupported(_)) => Some(ErrorCode::Failed),
+            Error::Portal(PortalError::ServiceUnknown(_)) => Some(ErrorCode::PortalNotFound),
+            Error::Portal(PortalError::NotFound(_)) => Some(ErrorCode::NotFound),
+            Error::Portal(PortalError::Exist(_)) => Some(ErrorCode::Exist),
+            Error::Portal(PortalError::WindowDestroyed(_)) => Some(ErrorCode::WindowDestroyed),
+            Error::Portal(PortalError::InvalidArgument(_))
+            | Error::ParseError(_)
+            | Error::InvalidAppID
+            | Error::NulTerminated(_)
+            | Error::UnexpectedIcon
+            | Error::InvalidSetting(_)
+            | Error::InvalidArgument(_)
+            | Error::InvalidPermissions(_) => Some(ErrorCode::InvalidArgument),
+            #[cfg(feature = "backend")]
+            Error::Url(_) => Some(ErrorCode::InvalidArgument),
+            Error::PortalNotFound(_) => Some(ErrorCode::PortalNotFound),
+            Error::RequiresVersion(_, _) => Some(ErrorCode::RequiresVersion),
+            Error::NoResponse => Some(ErrorCode::NoResponse),
+            Error::Timeout => Some(ErrorCode::TimedOut),
+            Error::Portal(PortalError::ZBus(_)) | Error::Zbus(_) => Some(ErrorCode::DBus),
+            Error::IO(_) | Error::FileTransfer(_) | Error::Mount(_) => Some(ErrorCode::Io),
+            #[cfg(feature = "pipewire")]
+            Error::Pipewire(_) => Some(ErrorCode::Io),
+            Error::Glib(_) => None,
+        };
+        let message = match &err {
+            Error::Portal(PortalError::ZBus(_)) => err.to_string(),
+            Error::Portal(e) => zbus::DBusError::description(e)
+                .unwrap_or_default()
+                .to_owned(),
+            _ => err.to_string(),
+        };
+        match (code, err) {
+            (_, Error::Glib(err)) => err,
+            (Some(code), _) => glib::Error::new(code, &message),
+            (None, _) => glib::Error::new(gio::IOErrorEnum::Cancelled, &message),
+        }
+    }
+}
+
+/// The errors created from an [`Error`] replied by a portal, or from an
+/// [`Error::NoResponse`] or an [`Error::Timeout`], are converted back. The
+/// cancelled operations become a [`PortalError::Cancelled`] and the other
+/// errors are wrapped in an [`Error::Glib`], so that converting the result
+/// into a [`glib::Error`] again gives the same domain, code and message.
+impl From<glib::Error> for Error {
+    fn from(err: glib::Error) -> Self {
+        let message = err.message().to_owned();
+        if err.matches(gio::IOErrorEnum::Cancelled) {
+            return Self::Portal(PortalError::Cancelled(message));
+        }
+        let error = match err.kind::<ErrorCode>() {
+            Some(ErrorCode::Failed) => PortalError::Failed(message),
+            Some(ErrorCode::NotAllowed) => PortalError::NotAllowed(message),
+            Some(ErrorCode::NotFound) => PortalError::NotFound(message),
+            Some(ErrorCode::InvalidArgument) => PortalError::InvalidArgument(message),
+            Some(ErrorCode::Exist) => PortalError::Exist(message),
+            Some(ErrorCode::WindowDestroyed) => PortalError::WindowDestroyed(message),
+            // These errors have no message of their own, they are only
+            // converted back if the message is theirs.
+            Some(ErrorCode::NoResponse) if message == Self::NoResponse.to_string() => {
+                return Self::NoResponse
+            }
+            Some(ErrorCode::TimedOut) if message == Self::Timeout.to_string() => {
+                return Self::Timeout
+            }
+            _ => return Self::Glib(err),
+        };
+        Self::Portal(error)
+    }
+}
+
+/// Runs `future` until it completes or `cancellable` is cancelled.
+///
+/// This is the building block of the other functions of the module, and can
+/// be used to wrap any other portal call.
+pub fn cancellable_future<T, F>(
+    future: F,
+    cancellable: Option<&gio::Cancellable>,
+) -> PortalFuture<T>
+where
+    F: Future<Output = Result<T, Error>> + 'static,
+    T: 'static,
+{
+    let cancellable = cancellable.cloned().unwrap_or_else(gio::Cancellable::new);
+    Box::pin(async move {
+        match gio::CancellableFuture::new(future, cancellable).await {
+            Ok(result) => result.map_err(From::from),
+            Err(gio::Cancelled) => Err(glib::Error::new(
+                gio::IOErrorEnum::Cancelled,
+                "Operation was cancelled",
+            )),
+        }
+    })
+}
+
+/// Asks the user to pick a color, see [`Color::pick`].
+pub fn pick_color_future(
+    identifier: Option<WindowIdentifier>,
+    cancellable: Option<&gio::Cancellable>,
+) -> PortalFuture<Color> {
+    cancellable_future(
+        async move {
+            Color::pick()
+                .identifier(identifier)
+                .send()
+                .await?
+                .response()
+        },
+        cancellable,
+    )
+}
+
+/// Takes a screenshot, returning its URI, see [`Screenshot::request`].
+pub fn screenshot_future(
+    identifier: Option<WindowIdentifier>,
+    interactive: bool,
+    cancellable: Option<&gio::Cancellable>,
+) -> PortalFuture<url::Url> {
+    cancellable_future(
+        async move {
+            let response = Screenshot::request()
+                .identifier(identifier)
+                .interactive(interactive)
+                .send()
+                .await?
+                .response()?;
+            Ok(response.uri().clone())
+        },
+        cancellable,
+    )
+}
+
+/// Opens `uri` with the default application, see
+/// [`OpenFileRequest::send_uri`].
+pub fn open_uri_future(
+    identifier: Option<WindowIdentifier>,
+    uri: url::Url,
+    cancellable: Option<&gio::Cancellable>,
+) -> PortalFuture<()> {
+    cancellable_future(
+        async move {
+            OpenFileRequest::default()
+                .identifier(identifier)
+                .send_uri(&uri)
+                .await?
+                .response()
+        },
+        cancellable,
+    )
+}
+
+/// Asks the user to select files to open, returning their URIs, see
+/// [`SelectedFiles::open_file`].
+pub fn open_file_future(
+    identifier: Option<WindowIdentifier>,
+    title: &str,
+    multiple: bool,
+    cancellable: Option<&gio::Cancellable>,
+) -> PortalFuture<Vec<url::Url>> {
+    let title = title.to_owned();
+    cancellable_future(
+        async move {
+            let files = SelectedFiles::open_file()
+                .identifier(identifier)
+                .title(title.as_str())
+                .multiple(multiple)
+                .send()
+                .await?
+                .response()?;
+            Ok(files.uris().to_vec())
+        },
+        cancellable,
+    )
+}
+
+/// Asks the user to select a location to save a file to, returning its URI,
+/// see [`SelectedFiles::save_file`].
+pub fn save_file_future(
+    identifier: Option<WindowIdentifier>,
+    title: &str,
+    current_name: Option<&str>,
+    cancellable: Option<&gio::Cancellable>,
+) -> PortalFuture<Option<url::Url>> {
+    let title = title.to_owned();
+    let current_name = current_name.map(ToOwned::to_owned);
+    cancellable_future(
+        async move {
+            let files = SelectedFiles::save_file()
+                .identifier(identifier)
+                .title(title.as_str())
+                .current_name(current_name.as_deref())
+                .send()
+                .await?
+                .response()?;
+            Ok(files.uris().first().cloned())
+        },
+        cancellable,
+    )
+}
+
+/// Retrieves the secret of the application, see
+/// [`secret::retrieve`](crate::desktop::secret::retrieve).
+pub fn retrieve_secret_future(cancellable: Option<&gio::Cancellable>) -> PortalFuture<Vec<u8>> {
+    cancellable_future(crate::desktop::secret::retrieve(), cancellable)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The domain, code and message of `err`.
+    fn parts(err: &glib::Error) -> String {
+        format!("{err:?}")
+    }
+
+    #[test]
+    fn glib_error_round_trip() {
+        let errors = [
+            glib::Error::new(ErrorCode::Failed, "Failed"),
+            glib::Error::new(ErrorCode::NotAllowed, "Not allowed"),
+            glib::Error::new(ErrorCode::NotFound, "Not found"),
+            glib::Error::new(ErrorCode::InvalidArgument, "Invalid argument"),
+            glib::Error::new(ErrorCode::PortalNotFound, "Portal not found"),
+            glib::Error::new(ErrorCode::RequiresVersion, "Requires version"),
+            glib::Error::new(ErrorCode::Exist, "Exists"),
+            glib::Error::new(ErrorCode::WindowDestroyed, "Window destroyed"),
+            glib::Error::new(ErrorCode::NoResponse, &Error::NoResponse.to_string()),
+            glib::Error::new(ErrorCode::NoResponse, "No response in time"),
+            glib::Error::new(ErrorCode::DBus, "D-Bus error"),
+            glib::Error::new(ErrorCode::Io, "I/O error"),
+            glib::Error::new(ErrorCode::TimedOut, &Error::Timeout.to_string()),
+            glib::Error::new(ErrorCode::TimedOut, "Timed out"),
+            glib::Error::new(gio::IOErrorEnum::Cancelled, "Cancelled by the user"),
+            glib::Error::new(gio::IOErrorEnum::NotFound, "No such file"),
+            glib::Error::new(glib::FileError::Noent, "No such file"),
+        ];
+        for err in errors {
+            let round_trip = glib::Error::from(Error::from(err.clone()));
+            assert_eq!(parts(&round_trip), parts(&err));
+        }
+
+        assert!(matches!(
+            Error::from(glib::Error::new(ErrorCode::NotAllowed, "Not allowed")),
+            Error::Portal(PortalError::NotAllowed(message)) if message == "Not allowed"
+        ));
+        assert!(matches!(
+            Error::from(glib::Error::from(Error::Timeout)),
+            Error::Timeout
+        ));
+        assert!(matches!(
+            Error::from(glib::Error::new(ErrorCode::TimedOut, "Timed out")),
+            Error::Glib(_)
+        ));
+    }
+}
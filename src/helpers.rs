@@ -1,32 +1,59 @@
-#[cfg(feature = "async-std")]
-use async_fs::File;
-#[cfg(feature = "async-std")]
-use futures_util::AsyncReadExt;
-#[cfg(feature = "tokio")]
-use tokio::{fs::File, io::AsyncReadExt};
+use std::{future::Future, time::Duration};
 
-pub(crate) async fn is_flatpak() -> bool {
-    #[cfg(feature = "async-std")]
+use crate::{io, Error};
+
+/// Runs `future` in the background on the executor of `connection`.
+///
+/// It is tokio with the `tokio` feature and the internal executor of zbus
+/// with the `async-io` one, which doesn't depend on the runtime of the
+/// application.
+pub(crate) fn spawn(
+    connection: &zbus::Connection,
+    name: &str,
+    future: impl Future<Output = ()> + Send + 'static,
+) {
+    connection.executor().spawn(future, name).detach();
+}
+
+/// The environment variables of the process that are valid UTF-8, unlike
+/// [`std::env::vars`] which panics on the other ones.
+pub(crate) fn env_vars() -> impl Iterator<Item = (String, String)> {
+    std::env::vars_os()
+        .filter_map(|(key, value)| Some((key.into_string().ok()?, value.into_string().ok()?)))
+}
+
+/// Runs `future`, failing with [`Error::Timeout`] if it doesn't complete
+/// within `duration`.
+pub(crate) async fn timeout<T>(
+    duration: Duration,
+    future: impl Future<Output = Result<T, Error>>,
+) -> Result<T, Error> {
+    #[cfg(feature = "tokio")]
     {
-        async_fs::metadata("/.flatpak-info").await.is_ok()
+        tokio::time::timeout(duration, future)
+            .await
+            .map_err(|_| Error::Timeout)?
     }
-    #[cfg(not(feature = "async-std"))]
+    #[cfg(all(feature = "async-io", not(feature = "tokio")))]
     {
-        std::path::PathBuf::from("/.flatpak-info").exists()
+        use futures_util::future::{select, Either};
+
+        let future = std::pin::pin!(future);
+        match select(future, async_io::Timer::after(duration)).await {
+            Either::Left((result, _)) => result,
+            Either::Right(_) => Err(Error::Timeout),
+        }
     }
 }
 
+pub(crate) async fn is_flatpak() -> bool {
+    io::exists("/.flatpak-info").await
+}
+
 pub(crate) async fn is_snap() -> bool {
     let pid = std::process::id();
-    let path = format!("/proc/{pid}/cgroup");
-    let mut file = match File::open(path).await {
-        Ok(file) => file,
-        Err(_) => return false,
-    };
-
-    let mut buffer = String::new();
-    match file.read_to_string(&mut buffer).await {
-        Ok(_) => cgroup_v2_is_snap(&buffer),
+    match io::read_file_to_string(format!("/proc/{pid}/cgroup")).await {
+        Ok(cgroups) => cgroup_v2_is_snap(&cgroups),
         Err(_) => false,
     }
 }
@@ -51,6 +78,110 @@ fn cgroup_v2_is_snap(cgroups: &str) -> bool {
         .any(|x| x.unwrap_or(false))
 }
 
+/// (De)serializes the values of the structs mapped to `a{sv}` dictionaries,
+/// for `#[serde(with = "crate::helpers::as_value")]`.
+///
+/// The values are wrapped in variants over D-Bus, like
+/// [`zbus::zvariant::as_value`] does, but not with the human-readable formats
+/// such as JSON, so the responses of the portals can be stored as is.
+pub(crate) mod as_value {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use zbus::zvariant::{self, Type};
+
+    pub(crate) fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize + Type,
+        S: Serializer,
+    {
+        if serializer.is_human_readable() {
+            value.serialize(serializer)
+        } else {
+            zvariant::as_value::serialize(value, serializer)
+        }
+    }
+
+    pub(crate) fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: Deserialize<'de> + Type + 'de,
+        D: Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            T::deserialize(deserializer)
+        } else {
+            zvariant::as_value::deserialize(deserializer)
+        }
+    }
+
+    /// Same as the parent module for the optional values, which have to be
+    /// skipped when they are [`None`].
+    pub(crate) mod optional {
+        use super::*;
+
+        pub(crate) fn serialize<T, S>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            T: Serialize + Type,
+            S: Serializer,
+        {
+            match value {
+                Some(value) => super::serialize(value, serializer),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        pub(crate) fn deserialize<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+        where
+            T: Deserialize<'de> + Type + 'de,
+            D: Deserializer<'de>,
+        {
+            if deserializer.is_human_readable() {
+                Option::<T>::deserialize(deserializer)
+            } else {
+                super::deserialize(deserializer).map(Some)
+            }
+        }
+    }
+}
+
+/// Defines a struct mapped to an `a{sv}` dictionary, whose fields are
+/// (de)serialized with [`as_value`], the optional ones being skipped when
+/// they are [`None`].
+///
+/// The struct derives [`serde::Serialize`], [`serde::Deserialize`] and
+/// [`zbus::zvariant::Type`], the other attributes are kept as is.
+macro_rules! dict {
+    (@fields [$($head:tt)*] [$($done:tt)*]) => {
+        #[derive(serde::Serialize, serde::Deserialize, zbus::zvariant::Type)]
+        #[zvariant(signature = "dict")]
+        $($head)* { $($done)* }
+    };
+    (@fields $head:tt [$($done:tt)*]
+        $(#[$field_meta:meta])* $field_vis:vis $field:ident: Option<$ty:ty> $(, $($rest:tt)*)?
+    ) => {
+        $crate::helpers::dict!(@fields $head [
+            $($done)*
+            #[serde(
+                default,
+                with = "crate::helpers::as_value::optional",
+                skip_serializing_if = "Option::is_none"
+            )]
+            $(#[$field_meta])* $field_vis $field: Option<$ty>,
+        ] $($($rest)*)?);
+    };
+    (@fields $head:tt [$($done:tt)*]
+        $(#[$field_meta:meta])* $field_vis:vis $field:ident: $ty:ty $(, $($rest:tt)*)?
+    ) => {
+        $crate::helpers::dict!(@fields $head [
+            $($done)*
+            #[serde(with = "crate::helpers::as_value")]
+            $(#[$field_meta])* $field_vis $field: $ty,
+        ] $($($rest)*)?);
+    };
+    ($(#[$meta:meta])* $vis:vis struct $name:ident { $($fields:tt)* }) => {
+        $crate::helpers::dict!(@fields [$(#[$meta])* $vis struct $name] [] $($fields)*);
+    };
+}
+pub(crate) use dict;
+
 #[cfg(test)]
 mod tests {
     use super::*;
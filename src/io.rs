@@ -0,0 +1,313 @@
+//! The pipes and sockets sent to or received from the portals, the
+//! clipboard content or a secret for example, are read and written through
+//! an [`AsyncPipe`](crate::io::AsyncPipe), without blocking the executor.
+//!
+//! ```rust,no_run
+//! use ashpd::io;
+//!
+//! async fn run() -> std::io::Result<()> {
+//!     let (read, write) = io::pipe()?;
+//!     io::write_all(write, b"content").await?;
+//!     assert_eq!(io::read_to_end(read).await?, b"content");
+//!     Ok(())
+//! }
+//! ```
+
+use std::{
+    fs::File,
+    io,
+    os::{
+        fd::{FromRawFd, OwnedFd},
+        unix::net::UnixStream,
+    },
+    path::{Path, PathBuf},
+    pin::Pin,
+    task::{Context, Poll},
+};
+#[cfg(feature = "tokio")]
+use std::{
+    io::{Read, Write},
+    os::fd::AsRawFd,
+};
+
+#[cfg(all(feature = "async-io", not(feature = "tokio")))]
+pub(crate) use futures_util::{AsyncReadExt, AsyncWriteExt};
+#[cfg(feature = "tokio")]
+pub(crate) use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+/// An asynchronous pipe or socket.
+///
+/// The file descriptor is switched to the non-blocking mode and polled by the
+/// reactor of the runtime, reading or writing it never blocks a thread. It
+/// implements `AsyncRead` and `AsyncWrite` from `tokio` with the `tokio`
+/// feature, and from `futures-io` with the `async-io` feature.
+#[derive(Debug)]
+pub struct AsyncPipe {
+    #[cfg(feature = "tokio")]
+    inner: tokio::io::unix::AsyncFd<File>,
+    #[cfg(all(feature = "async-io", not(feature = "tokio")))]
+    inner: async_io::Async<File>,
+}
+
+impl AsyncPipe {
+    fn new(fd: OwnedFd) -> io::Result<Self> {
+        #[cfg(feature = "tokio")]
+        {
+            set_nonblocking(&fd)?;
+            Ok(Self {
+                inner: tokio::io::unix::AsyncFd::new(File::from(fd))?,
+            })
+        }
+        #[cfg(all(feature = "async-io", not(feature = "tokio")))]
+        {
+            Ok(Self {
+                inner: async_io::Async::new(File::from(fd))?,
+            })
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+fn set_nonblocking(fd: &OwnedFd) -> io::Result<()> {
+    // SAFETY: `fd` is a valid file descriptor, F_GETFL takes no argument.
+    let flags = unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_GETFL) };
+    if flags < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // SAFETY: `fd` is a valid file descriptor, F_SETFL takes the new flags.
+    if unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_SETFL, flags | libc::O_NONBLOCK) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(feature = "tokio")]
+impl tokio::io::AsyncRead for AsyncPipe {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        loop {
+            let mut guard = std::task::ready!(self.inner.poll_read_ready(cx))?;
+            let unfilled = buf.initialize_unfilled();
+            match guard.try_io(|inner| inner.get_ref().read(unfilled)) {
+                Ok(Ok(len)) => {
+                    buf.advance(len);
+                    return Poll::Ready(Ok(()));
+                }
+                Ok(Err(err)) => return Poll::Ready(Err(err)),
+                // Not readable anymore, wait for the next readiness event.
+                Err(_would_block) => continue,
+            }
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl tokio::io::AsyncWrite for AsyncPipe {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        loop {
+            let mut guard = std::task::ready!(self.inner.poll_write_ready(cx))?;
+            match guard.try_io(|inner| inner.get_ref().write(buf)) {
+                Ok(result) => return Poll::Ready(result),
+                Err(_would_block) => continue,
+            }
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(all(feature = "async-io", not(feature = "tokio")))]
+impl futures_util::AsyncRead for AsyncPipe {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_read(cx, buf)
+    }
+}
+
+#[cfg(all(feature = "async-io", not(feature = "tokio")))]
+impl futures_util::AsyncWrite for AsyncPipe {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_close(cx)
+    }
+}
+
+/// Creates a pipe, returning the read and write ends.
+pub fn pipe() -> io::Result<(OwnedFd, OwnedFd)> {
+    let mut fds = [0; 2];
+    // SAFETY: `fds` is a valid array of two file descriptors.
+    if unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // SAFETY: `pipe2` succeeded, so both file descriptors are open and owned
+    // by us.
+    unsafe { Ok((OwnedFd::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1]))) }
+}
+
+/// Creates a pair of connected Unix sockets.
+pub fn socket_pair() -> io::Result<(OwnedFd, OwnedFd)> {
+    let (first, second) = UnixStream::pair()?;
+    Ok((first.into(), second.into()))
+}
+
+/// Wraps `fd`, a pipe or a socket, into an [`AsyncPipe`].
+///
+/// Fails for the regular files, which can't be polled.
+pub fn into_async(fd: impl Into<OwnedFd>) -> io::Result<AsyncPipe> {
+    AsyncPipe::new(fd.into())
+}
+
+/// Reads `fd`, a pipe or a socket, until its end.
+pub async fn read_to_end(fd: impl Into<OwnedFd>) -> io::Result<Vec<u8>> {
+    let mut pipe = into_async(fd)?;
+    let mut buffer = Vec::new();
+    pipe.read_to_end(&mut buffer).await?;
+    Ok(buffer)
+}
+
+/// Writes `data` to `fd`, a pipe or a socket, then closes it.
+pub async fn write_all(fd: impl Into<OwnedFd>, data: &[u8]) -> io::Result<()> {
+    let mut pipe = into_async(fd)?;
+    pipe.write_all(data).await?;
+    pipe.flush().await
+}
+
+/// Reads the file at `path` to a string.
+pub(crate) async fn read_file_to_string(path: impl AsRef<Path>) -> io::Result<String> {
+    #[cfg(feature = "tokio")]
+    {
+        tokio::fs::read_to_string(path).await
+    }
+    #[cfg(all(feature = "async-io", not(feature = "tokio")))]
+    {
+        async_fs::read_to_string(path).await
+    }
+}
+
+/// Reads the file at `path`, failing with [`io::ErrorKind::InvalidData`] if
+/// it is larger than `max_size` bytes.
+pub(crate) async fn read_file_capped(path: impl AsRef<Path>, max_size: u64) -> io::Result<Vec<u8>> {
+    #[cfg(feature = "tokio")]
+    let file = tokio::fs::File::open(path).await?;
+    #[cfg(all(feature = "async-io", not(feature = "tokio")))]
+    let file = async_fs::File::open(path).await?;
+    // One more byte is read to tell the files of `max_size` bytes apart.
+    let mut bytes = Vec::new();
+    file.take(max_size + 1).read_to_end(&mut bytes).await?;
+    if bytes.len() as u64 > max_size {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("The file is larger than {max_size} bytes"),
+        ));
+    }
+    Ok(bytes)
+}
+
+/// Whether `path` exists.
+pub(crate) async fn exists(path: impl AsRef<Path>) -> bool {
+    #[cfg(feature = "tokio")]
+    {
+        tokio::fs::metadata(path).await.is_ok()
+    }
+    #[cfg(all(feature = "async-io", not(feature = "tokio")))]
+    {
+        async_fs::metadata(path).await.is_ok()
+    }
+}
+
+/// The entries of the directory `dir`.
+pub(crate) async fn read_dir(dir: impl AsRef<Path>) -> io::Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+    #[cfg(feature = "tokio")]
+    {
+        let mut entries = tokio::fs::read_dir(dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            paths.push(entry.path());
+        }
+    }
+    #[cfg(all(feature = "async-io", not(feature = "tokio")))]
+    {
+        use futures_util::StreamExt;
+
+        let mut entries = async_fs::read_dir(dir).await?;
+        while let Some(entry) = entries.next().await {
+            paths.push(entry?.path());
+        }
+    }
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn fd_round_trip() {
+        let data = vec![42; 128 * 1024];
+        for (read, write) in [pipe().unwrap(), socket_pair().unwrap()] {
+            let (written, read) =
+                futures_util::future::join(write_all(write, &data), read_to_end(read)).await;
+            written.unwrap();
+            assert_eq!(read.unwrap(), data);
+        }
+    }
+
+    #[cfg(feature = "async-io")]
+    #[test]
+    fn without_tokio() {
+        async_io::block_on(async {
+            let (read, write) = pipe().unwrap();
+            let (written, read) =
+                futures_util::future::join(write_all(write, b"content"), read_to_end(read)).await;
+            written.unwrap();
+            assert_eq!(read.unwrap(), b"content");
+        });
+    }
+
+    #[tokio::test]
+    async fn regular_file() {
+        let file =
+            std::fs::File::open(Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml")).unwrap();
+        assert!(into_async(file).is_err());
+    }
+
+    #[tokio::test]
+    async fn files() {
+        let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        assert!(exists(manifest_dir.join("Cargo.toml")).await);
+        assert!(!exists(manifest_dir.join("missing")).await);
+        let content = read_file_to_string(manifest_dir.join("Cargo.toml"))
+            .await
+            .unwrap();
+        assert!(content.contains("name = \"ashpd\""));
+        let entries = read_dir(manifest_dir.join("src")).await.unwrap();
+        assert!(entries.contains(&manifest_dir.join("src").join("io.rs")));
+    }
+}
@@ -0,0 +1,71 @@
+//! A minimal parser of the key files used by Flatpak and xdg-desktop-portal,
+//! like `/.flatpak-info` or the `.portal` files, in the format of the desktop
+//! entries.
+
+use std::collections::HashMap;
+
+/// The keys of each group, by group name.
+pub(crate) type KeyFile = HashMap<String, HashMap<String, String>>;
+
+/// Parses the groups of `content`.
+///
+/// Returns `None` if a line is neither empty, a comment, a group header nor
+/// a key belonging to a group.
+pub(crate) fn parse(content: &str) -> Option<KeyFile> {
+    let mut groups = KeyFile::new();
+    let mut current = None;
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(group) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            groups.entry(group.to_owned()).or_default();
+            current = Some(group.to_owned());
+            continue;
+        }
+        let (group, (key, value)) = (current.as_ref()?, line.split_once('=')?);
+        groups
+            .entry(group.clone())
+            .or_default()
+            .insert(key.trim().to_owned(), value.trim().to_owned());
+    }
+    Some(groups)
+}
+
+/// The items of a `;` separated list, skipping the empty ones.
+pub(crate) fn list(value: &str) -> Vec<String> {
+    value
+        .split(';')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(ToOwned::to_owned)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{list, parse};
+
+    #[test]
+    fn parse_groups() {
+        let groups = parse(
+            "# A comment
+[Application]
+name = org.example.App
+
+[Context]
+shared=network;ipc;
+[Empty]
+",
+        )
+        .unwrap();
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups["Application"]["name"], "org.example.App");
+        assert_eq!(list(&groups["Context"]["shared"]), ["network", "ipc"]);
+        assert!(groups["Empty"].is_empty());
+
+        assert!(parse("name=org.example.App").is_none());
+        assert!(parse("[Application]\nname").is_none());
+        assert!(parse("").unwrap().is_empty());
+    }
+}
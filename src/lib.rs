@@ -6,10 +6,12 @@
     html_favicon_url = "https://raw.githubusercontent.com/bilelmoussaoui/ashpd/master/ashpd-demo/data/icons/com.belmoussaoui.ashpd.demo-symbolic.svg"
 )]
 #![doc = include_str!("../README.md")]
-#[cfg(all(all(feature = "tokio", feature = "async-std"), not(doc)))]
-compile_error!("You can't enable both async-std & tokio features at once");
-#[cfg(all(not(feature = "tokio"), not(feature = "async-std"), not(doc)))]
-compile_error!("Either the `async-std` or the `tokio` feature has to be enabled");
+#[cfg(all(all(feature = "tokio", feature = "async-io"), not(doc)))]
+compile_error!("You can't enable both async-io (async-std, smol) & tokio features at once");
+#[cfg(all(not(feature = "tokio"), not(feature = "async-io"), not(doc)))]
+compile_error!(
+    "Either the `async-io` (or its `async-std` and `smol` aliases) or the `tokio` feature has to be enabled"
+);
 
 /// Alias for a [`Result`] with the error type `ashpd::Error`.
 pub type Result<T> = std::result::Result<T, Error>;
@@ -17,6 +19,10 @@ pub type Result<T> = std::result::Result<T, Error>;
 static IS_SANDBOXED: OnceLock<bool> = OnceLock::new();
 
 mod activation_token;
+mod cancellation_token;
+pub use self::cancellation_token::CancellationToken;
+/// Opt-in caching of the portal values that rarely change.
+pub mod cache;
 /// Interact with the user's desktop such as taking a screenshot, setting a
 /// background or querying the user's location.
 pub mod desktop;
@@ -25,32 +31,63 @@ pub mod documents;
 mod error;
 mod window_identifier;
 
+#[cfg(feature = "x11")]
+#[cfg_attr(docsrs, doc(cfg(feature = "x11")))]
+pub use self::window_identifier::X11Error;
+#[cfg(feature = "wayland")]
+#[cfg_attr(docsrs, doc(cfg(feature = "wayland")))]
+pub use self::window_identifier::{WaylandExportData, WaylandExporter};
 pub use self::{activation_token::ActivationToken, window_identifier::WindowIdentifier};
 mod app_id;
 mod registry;
-pub use self::{app_id::AppID, registry::register_host_app};
+pub use self::{
+    app_id::{AppID, AppIDValidation},
+    registry::{app_id, register_host_app, set_app_id},
+};
 mod file_path;
 pub use self::file_path::FilePath;
+mod uri;
+pub use self::uri::Uri;
+/// Find out which backend serves each portal interface on the current
+/// desktop.
+pub mod portal_config;
+mod snap;
+pub use self::snap::SnapInfo;
+#[cfg(target_os = "linux")]
+mod process;
+#[cfg(target_os = "linux")]
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+pub use self::process::Process;
 
+#[cfg(feature = "log_dbus")]
+mod dbus_log;
 mod proxy;
+pub use self::proxy::{Feature, SignalEvent, Timeout, Versioned};
+#[cfg(feature = "raw")]
+#[cfg_attr(docsrs, doc(cfg(feature = "raw")))]
+pub mod raw;
 
 #[cfg(feature = "backend")]
 #[cfg_attr(docsrs, doc(cfg(feature = "backend")))]
 pub use self::window_identifier::WindowIdentifierType;
-#[cfg(feature = "backend")]
-#[cfg_attr(docsrs, doc(cfg(feature = "backend")))]
-#[allow(missing_docs)]
-/// Build your custom portals backend.
-pub mod backend;
 /// Spawn commands outside the sandbox or monitor if the running application has
 /// received an update & install it.
 pub mod flatpak;
+#[cfg(feature = "glib")]
+#[cfg_attr(docsrs, doc(cfg(feature = "glib")))]
+pub mod glib_async;
 mod helpers;
+/// Read and write the pipes and sockets exchanged with the portals, with
+/// either the `tokio` runtime or the `async-io` reactor used by smol and
+/// async-std.
+pub mod io;
+mod keyfile;
+pub mod prelude;
+#[cfg(feature = "test")]
+#[cfg_attr(docsrs, doc(cfg(feature = "test")))]
+pub mod test;
 use std::sync::OnceLock;
 
-#[cfg(feature = "backend")]
-#[cfg_attr(docsrs, doc(cfg(feature = "backend")))]
-pub use async_trait;
 pub use enumflags2;
 pub use url;
 pub use zbus::{self, zvariant};
@@ -61,6 +98,10 @@ pub use zbus::{self, zvariant};
 /// is running as a snap, or if the environment variable `GTK_USE_PORTAL` is set
 /// to `1`. As the return value of this function will not change during the
 /// runtime of a program; it is cached for future calls.
+///
+/// See [`SnapInfo::current`] and
+/// [`InstanceInfo::current`](crate::flatpak::InstanceInfo::current) to
+/// retrieve the metadata of the sandbox.
 pub async fn is_sandboxed() -> bool {
     if let Some(cached_value) = IS_SANDBOXED.get() {
         return *cached_value;
@@ -74,7 +115,7 @@ pub async fn is_sandboxed() -> bool {
     *IS_SANDBOXED.get_or_init(|| new_value)
 }
 
-pub use self::error::{Error, PortalError};
+pub use self::error::{DBusErrorRef, Error, PortalError};
 
 mod sealed {
     /// Use as a supertrait for public traits that users should not be able to
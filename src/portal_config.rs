@@ -0,0 +1,379 @@
+//! # Examples
+//!
+//! ```rust,no_run
+//! use ashpd::portal_config::PortalConfig;
+//!
+//! async fn run() -> ashpd::Result<()> {
+//!     let config = PortalConfig::load().await?;
+//!     if let Some(backend) = config.backend_for("org.freedesktop.impl.portal.FileChooser") {
+//!         println!("The file chooser is provided by {}", backend.dbus_name());
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use crate::{
+    io,
+    keyfile::{self, list},
+    Error,
+};
+
+const PORTALS_DIR: &str = "/usr/share/xdg-desktop-portal/portals";
+
+/// A portal backend, as described by a
+/// `/usr/share/xdg-desktop-portal/portals/<name>.portal` file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortalDefinition {
+    name: String,
+    dbus_name: String,
+    interfaces: Vec<String>,
+    use_in: Vec<String>,
+}
+
+impl PortalDefinition {
+    /// Parses the content of the `<name>.portal` file of the backend `name`.
+    pub fn parse(name: &str, content: &str) -> Result<Self, Error> {
+        let mut sections = parse_keyfile(content)?;
+        let mut portal = sections
+            .remove("portal")
+            .ok_or(Error::ParseError("Missing [portal] section"))?;
+        let dbus_name = portal
+            .remove("DBusName")
+            .filter(|name| !name.is_empty())
+            .ok_or(Error::ParseError("Missing DBusName key"))?;
+        Ok(Self {
+            name: name.to_owned(),
+            dbus_name,
+            interfaces: portal
+                .get("Interfaces")
+                .map(|v| list(v))
+                .unwrap_or_default(),
+            use_in: portal.get("UseIn").map(|v| list(v)).unwrap_or_default(),
+        })
+    }
+
+    /// The name of the backend, e.g. `gtk` for `gtk.portal`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The D-Bus name the backend is activated with, e.g.
+    /// `org.freedesktop.impl.portal.desktop.gtk`.
+    pub fn dbus_name(&self) -> &str {
+        &self.dbus_name
+    }
+
+    /// The interfaces implemented by the backend.
+    pub fn interfaces(&self) -> &[String] {
+        &self.interfaces
+    }
+
+    /// The desktops the backend is meant for, as listed in `UseIn`.
+    ///
+    /// `UseIn` is deprecated in favor of `portals.conf` and only used when no
+    /// configuration file exists.
+    pub fn use_in(&self) -> &[String] {
+        &self.use_in
+    }
+
+    /// Whether the backend implements `interface`.
+    pub fn implements(&self, interface: &str) -> bool {
+        self.interfaces.iter().any(|i| i == interface)
+    }
+}
+
+/// The preferred backends, as set in a `portals.conf` file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PortalsConf {
+    default: Vec<String>,
+    interfaces: HashMap<String, Vec<String>>,
+}
+
+impl PortalsConf {
+    /// The backends used for the interfaces without a specific preference.
+    ///
+    /// `*` stands for any backend and `none` for none of them.
+    pub fn default(&self) -> &[String] {
+        &self.default
+    }
+
+    /// The backends preferred for `interface`, if set.
+    pub fn preferred(&self, interface: &str) -> Option<&[String]> {
+        self.interfaces.get(interface).map(Vec::as_slice)
+    }
+}
+
+impl FromStr for PortalsConf {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut preferred = parse_keyfile(s)?.remove("preferred").unwrap_or_default();
+        let default = preferred
+            .remove("default")
+            .map(|v| list(&v))
+            .unwrap_or_default();
+        Ok(Self {
+            default,
+            interfaces: preferred
+                .into_iter()
+                .map(|(interface, backends)| (interface, list(&backends)))
+                .collect(),
+        })
+    }
+}
+
+/// The portal backends installed on the system and the configuration picking
+/// which of them serves each interface on the current desktop.
+#[derive(Debug, Clone, Default)]
+pub struct PortalConfig {
+    definitions: Vec<PortalDefinition>,
+    conf: Option<PortalsConf>,
+    desktops: Vec<String>,
+}
+
+impl PortalConfig {
+    /// Create a configuration from already parsed files.
+    ///
+    /// `desktops` are the current desktops, as listed in
+    /// `XDG_CURRENT_DESKTOP`.
+    pub fn new(
+        definitions: Vec<PortalDefinition>,
+        conf: Option<PortalsConf>,
+        desktops: &[&str],
+    ) -> Self {
+        let mut definitions = definitions;
+        definitions.sort_by(|a, b| a.name.cmp(&b.name));
+        Self {
+            definitions,
+            conf,
+            desktops: desktops.iter().map(|d| d.to_lowercase()).collect(),
+        }
+    }
+
+    /// Loads the configuration the same way `xdg-desktop-portal` does.
+    ///
+    /// The backends are read from `XDG_DESKTOP_PORTAL_DIR`, or
+    /// `/usr/share/xdg-desktop-portal/portals`. The first
+    /// `<desktop>-portals.conf` or `portals.conf` file found in the
+    /// `xdg-desktop-portal` directory of the user configuration, the system
+    /// configuration or the data directories is used.
+    pub async fn load() -> Result<Self, Error> {
+        let desktops = std::env::var("XDG_CURRENT_DESKTOP").unwrap_or_default();
+        let desktops = desktops
+            .split(':')
+            .filter(|d| !d.is_empty())
+            .map(|d| d.to_lowercase())
+            .collect::<Vec<_>>();
+
+        let portals_dir = std::env::var_os("XDG_DESKTOP_PORTAL_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from(PORTALS_DIR));
+        let mut definitions = Vec::new();
+        for path in portal_files(&portals_dir).await? {
+            let Some(name) = path.file_stem().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            let Ok(content) = io::read_file_to_string(&path).await else {
+                continue;
+            };
+            // Broken files are skipped, like xdg-desktop-portal does.
+            if let Ok(definition) = PortalDefinition::parse(name, &content) {
+                definitions.push(definition);
+            }
+        }
+
+        let mut conf = None;
+        'dirs: for dir in conf_dirs() {
+            let names = desktops
+                .iter()
+                .map(|desktop| format!("{desktop}-portals.conf"))
+                .chain(std::iter::once("portals.conf".to_owned()));
+            for name in names {
+                if let Ok(content) = io::read_file_to_string(dir.join(name)).await {
+                    conf = Some(content.parse()?);
+                    break 'dirs;
+                }
+            }
+        }
+
+        let desktops = desktops.iter().map(String::as_str).collect::<Vec<_>>();
+        Ok(Self::new(definitions, conf, &desktops))
+    }
+
+    /// The installed backends, sorted by name.
+    pub fn definitions(&self) -> &[PortalDefinition] {
+        &self.definitions
+    }
+
+    /// The `portals.conf` configuration in use, if any.
+    pub fn conf(&self) -> Option<&PortalsConf> {
+        self.conf.as_ref()
+    }
+
+    /// The backends that can serve `interface`, by order of preference.
+    pub fn backends_for(&self, interface: &str) -> Vec<&PortalDefinition> {
+        let implementing = self
+            .definitions
+            .iter()
+            .filter(|definition| definition.implements(interface));
+
+        let Some(conf) = &self.conf else {
+            // Without any configuration, fallback to the backends meant for
+            // the current desktop.
+            return implementing
+                .filter(|definition| {
+                    definition.use_in.iter().any(|desktop| {
+                        self.desktops
+                            .iter()
+                            .any(|current| current.eq_ignore_ascii_case(desktop))
+                    })
+                })
+                .collect();
+        };
+
+        let preferred = conf.preferred(interface).unwrap_or(&conf.default);
+        let mut backends = Vec::new();
+        for name in preferred {
+            match name.as_str() {
+                "none" => return Vec::new(),
+                "*" => backends.extend(implementing.clone()),
+                name => backends.extend(implementing.clone().filter(|d| d.name == name)),
+            }
+        }
+        let mut seen = Vec::<&str>::new();
+        backends.retain(|definition| {
+            let first = !seen.contains(&definition.name.as_str());
+            seen.push(&definition.name);
+            first
+        });
+        backends
+    }
+
+    /// The backend serving `interface`, if any.
+    pub fn backend_for(&self, interface: &str) -> Option<&PortalDefinition> {
+        self.backends_for(interface).into_iter().next()
+    }
+}
+
+fn parse_keyfile(content: &str) -> Result<keyfile::KeyFile, Error> {
+    keyfile::parse(content).ok_or(Error::ParseError(
+        "Failed to parse the portal configuration",
+    ))
+}
+
+/// The `xdg-desktop-portal` directories `portals.conf` is looked up in, by
+/// order of priority.
+fn conf_dirs() -> Vec<PathBuf> {
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| home.map(|home| home.join(".config")));
+    let config_dirs = std::env::var("XDG_CONFIG_DIRS")
+        .ok()
+        .filter(|dirs| !dirs.is_empty())
+        .unwrap_or_else(|| "/etc/xdg".to_owned());
+    let data_dirs = std::env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|dirs| !dirs.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_owned());
+
+    config_home
+        .into_iter()
+        .chain(config_dirs.split(':').map(PathBuf::from))
+        .chain(data_dirs.split(':').map(PathBuf::from))
+        .map(|dir| dir.join("xdg-desktop-portal"))
+        .collect()
+}
+
+/// The `.portal` files of `dir`, or none if it doesn't exist.
+async fn portal_files(dir: &Path) -> Result<Vec<PathBuf>, Error> {
+    let Ok(mut files) = io::read_dir(dir).await else {
+        return Ok(Vec::new());
+    };
+    files.retain(|path| path.extension().is_some_and(|ext| ext == "portal"));
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PortalConfig, PortalDefinition, PortalsConf};
+
+    const FILE_CHOOSER: &str = "org.freedesktop.impl.portal.FileChooser";
+    const SCREENSHOT: &str = "org.freedesktop.impl.portal.Screenshot";
+    const SETTINGS: &str = "org.freedesktop.impl.portal.Settings";
+
+    fn definitions() -> Vec<PortalDefinition> {
+        let gnome = PortalDefinition::parse(
+            "gnome",
+            "[portal]
+DBusName=org.freedesktop.impl.portal.desktop.gnome
+Interfaces=org.freedesktop.impl.portal.Screenshot;org.freedesktop.impl.portal.Settings;
+UseIn=gnome
+",
+        )
+        .unwrap();
+        let gtk = PortalDefinition::parse(
+            "gtk",
+            "[portal]
+DBusName=org.freedesktop.impl.portal.desktop.gtk
+Interfaces=org.freedesktop.impl.portal.FileChooser;org.freedesktop.impl.portal.Settings;
+UseIn=gnome;xfce
+",
+        )
+        .unwrap();
+        vec![gtk, gnome]
+    }
+
+    #[test]
+    fn parse_portal_definition() {
+        let definitions = definitions();
+        let gtk = &definitions[0];
+        assert_eq!(gtk.name(), "gtk");
+        assert_eq!(gtk.dbus_name(), "org.freedesktop.impl.portal.desktop.gtk");
+        assert_eq!(gtk.interfaces(), [FILE_CHOOSER, SETTINGS]);
+        assert_eq!(gtk.use_in(), ["gnome", "xfce"]);
+        assert!(gtk.implements(FILE_CHOOSER));
+        assert!(!gtk.implements(SCREENSHOT));
+
+        assert!(PortalDefinition::parse("broken", "[portal]\nInterfaces=a;").is_err());
+        assert!(PortalDefinition::parse("broken", "DBusName=a").is_err());
+    }
+
+    #[test]
+    fn resolve_backends() {
+        let conf = "[preferred]
+default=gnome;*;
+org.freedesktop.impl.portal.FileChooser=kde;gtk;
+org.freedesktop.impl.portal.Screenshot=none
+"
+        .parse::<PortalsConf>()
+        .unwrap();
+        assert_eq!(conf.default(), ["gnome", "*"]);
+        assert_eq!(conf.preferred(FILE_CHOOSER).unwrap(), ["kde", "gtk"]);
+
+        let config = PortalConfig::new(definitions(), Some(conf), &["GNOME"]);
+        assert_eq!(config.definitions()[0].name(), "gnome");
+        assert_eq!(config.backend_for(FILE_CHOOSER).unwrap().name(), "gtk");
+        assert!(config.backend_for(SCREENSHOT).is_none());
+        let settings = config.backends_for(SETTINGS);
+        assert_eq!(settings.len(), 2);
+        assert_eq!(settings[0].name(), "gnome");
+        assert_eq!(settings[1].name(), "gtk");
+    }
+
+    #[test]
+    fn resolve_backends_without_conf() {
+        let config = PortalConfig::new(definitions(), None, &["XFCE"]);
+        assert_eq!(config.backend_for(FILE_CHOOSER).unwrap().name(), "gtk");
+        assert!(config.backend_for(SCREENSHOT).is_none());
+
+        let config = PortalConfig::new(definitions(), None, &["KDE"]);
+        assert!(config.backend_for(SETTINGS).is_none());
+    }
+}
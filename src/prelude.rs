@@ -0,0 +1,25 @@
+//! The types and traits needed by most of the applications using the portals.
+//!
+//! ```rust,no_run
+//! use ashpd::{desktop::screenshot::Screenshot, prelude::*};
+//!
+//! async fn run(identifier: Option<WindowIdentifier>) -> ashpd::Result<()> {
+//!     let request: Request<_> = Screenshot::request()
+//!         .identifier(identifier)
+//!         .send()
+//!         .await?;
+//!     match request.response() {
+//!         Ok(screenshot) => println!("{}", screenshot.uri()),
+//!         Err(Error::Response(ResponseError::Cancelled)) => println!("Cancelled"),
+//!         Err(err) => return Err(err),
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+pub use futures_util::StreamExt as _;
+
+pub use crate::{
+    desktop::{PersistMode, Request, Response, ResponseError, Session},
+    ActivationToken, CancellationToken, Error, Timeout, Versioned, WindowIdentifier,
+};
@@ -0,0 +1,158 @@
+use std::{
+    io,
+    os::fd::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd},
+};
+
+use crate::Pid;
+
+/// A process, referenced by a pidfd.
+///
+/// Unlike its [`Pid`], which can be reused by another process once it
+/// exited, the pidfd always refers to the same process. It can be passed to
+/// the portal methods taking a pidfd, like
+/// [`GameMode::register_by_pidfd`](crate::desktop::game_mode::GameMode::register_by_pidfd).
+///
+/// ```rust,no_run
+/// use ashpd::{desktop::game_mode::GameMode, Process};
+///
+/// async fn run(game: &std::process::Child) -> ashpd::Result<()> {
+///     let game = Process::from_child(game)?;
+///     let requester = Process::current()?;
+///     GameMode::new()
+///         .await?
+///         .register_by_pidfd(&game, &requester)
+///         .await?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Process {
+    fd: OwnedFd,
+    pid: Pid,
+}
+
+impl Process {
+    /// The current process.
+    pub fn current() -> io::Result<Self> {
+        Self::from_pid(std::process::id())
+    }
+
+    /// A child process spawned with [`std::process::Command`].
+    ///
+    /// The child keeps its PID until it is waited for, so the pidfd refers
+    /// to it even if it already exited.
+    pub fn from_child(child: &std::process::Child) -> io::Result<Self> {
+        Self::from_pid(child.id())
+    }
+
+    /// The process with the ID `pid`.
+    ///
+    /// The process is only guaranteed to be the one expected if it can't
+    /// have exited in the meantime, prefer [`Process::from_child`] for the
+    /// children.
+    pub fn from_pid(pid: Pid) -> io::Result<Self> {
+        let raw_pid =
+            libc::pid_t::try_from(pid).map_err(|_| io::Error::from_raw_os_error(libc::EINVAL))?;
+        // SAFETY: pidfd_open takes a PID and flags, and returns a new file
+        // descriptor.
+        let fd = unsafe { libc::syscall(libc::SYS_pidfd_open, raw_pid, 0) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: the file descriptor was just opened and is owned by nobody
+        // else.
+        let fd = unsafe { OwnedFd::from_raw_fd(fd as i32) };
+        Ok(Self { fd, pid })
+    }
+
+    /// The ID of the process, in the PID namespace the pidfd was opened in.
+    pub fn pid(&self) -> Pid {
+        self.pid
+    }
+
+    /// The ID of the process, failing with [`io::ErrorKind::NotFound`] if it
+    /// exited already and the ID might refer to another process.
+    ///
+    /// Used by the portal methods that only take a PID. This only narrows the
+    /// window in which the ID can be reused: the process can still exit
+    /// between this check and the moment the portal uses the ID.
+    pub(crate) fn running_pid(&self) -> io::Result<Pid> {
+        // The signal 0 only checks that the process exists, the processes of
+        // other users exist but can't be signalled.
+        match self.send_signal(0) {
+            Err(err) if err.raw_os_error() == Some(libc::EPERM) => Ok(self.pid),
+            result => result.map(|()| self.pid),
+        }
+    }
+
+    /// Sends `signal` to the process, failing with
+    /// [`io::ErrorKind::NotFound`] if it exited already.
+    pub fn send_signal(&self, signal: i32) -> io::Result<()> {
+        // SAFETY: the pidfd is valid, no siginfo nor flags are passed.
+        let ret = unsafe {
+            libc::syscall(
+                libc::SYS_pidfd_send_signal,
+                self.fd.as_raw_fd(),
+                signal,
+                std::ptr::null::<libc::siginfo_t>(),
+                0,
+            )
+        };
+        if ret < 0 {
+            let err = io::Error::last_os_error();
+            if err.raw_os_error() == Some(libc::ESRCH) {
+                return Err(io::Error::new(io::ErrorKind::NotFound, err));
+            }
+            return Err(err);
+        }
+        Ok(())
+    }
+}
+
+impl AsFd for Process {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.fd.as_fd()
+    }
+}
+
+impl From<Process> for OwnedFd {
+    fn from(value: Process) -> Self {
+        value.fd
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process::Command;
+
+    use super::Process;
+
+    #[test]
+    fn current() {
+        let process = Process::current().unwrap();
+        assert_eq!(process.pid(), std::process::id());
+        assert_eq!(process.running_pid().unwrap(), std::process::id());
+    }
+
+    #[test]
+    fn init() {
+        // Running as an unprivileged user, init can't be signalled.
+        let process = Process::from_pid(1).unwrap();
+        assert_eq!(process.running_pid().unwrap(), 1);
+    }
+
+    #[test]
+    fn child() {
+        let mut child = Command::new("sleep").arg("10").spawn().unwrap();
+        let process = Process::from_child(&child).unwrap();
+        assert_eq!(process.pid(), child.id());
+        process.send_signal(libc::SIGKILL).unwrap();
+        child.wait().unwrap();
+
+        // The PID may be reused from now on, but not the pidfd.
+        let err = process.send_signal(0).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+        let err = process.running_pid().unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+    }
+}
@@ -1,17 +1,31 @@
 #![allow(missing_docs)]
-use std::{fmt::Debug, future::ready, ops::Deref, sync::OnceLock};
+use std::{
+    fmt::Debug,
+    future::{ready, Future},
+    ops::Deref,
+    pin::pin,
+    time::Duration,
+};
 
-use futures_util::{Stream, StreamExt};
+use futures_util::{
+    future::{select, Either},
+    Stream, StreamExt,
+};
 use serde::{Deserialize, Serialize};
 use zbus::zvariant::{ObjectPath, OwnedValue, Type};
 #[cfg(feature = "tracing")]
 use zbus::Message;
 
 use crate::{
-    desktop::{HandleToken, Request},
-    Error, PortalError,
+    desktop::{HandleToken, Request, ResponseError},
+    CancellationToken, Error, PortalError,
 };
 
+/// Generated from the interfaces XML files, see `ashpd::raw`.
+#[cfg(test)]
+#[path = "raw/signatures.rs"]
+mod signatures;
+
 pub(crate) const DESKTOP_DESTINATION: &str = "org.freedesktop.portal.Desktop";
 pub(crate) const DESKTOP_PATH: &str = "/org/freedesktop/portal/desktop";
 
@@ -24,23 +38,166 @@ pub(crate) const FLATPAK_PATH: &str = "/org/freedesktop/portal/Flatpak";
 pub(crate) const FLATPAK_DEVELOPMENT_DESTINATION: &str = "org.freedesktop.Flatpak";
 pub(crate) const FLATPAK_DEVELOPMENT_PATH: &str = "/org/freedesktop/Flatpak/Development";
 
-static SESSION: OnceLock<zbus::Connection> = OnceLock::new();
+pub(crate) const PERMISSION_STORE_DESTINATION: &str = "org.freedesktop.impl.portal.PermissionStore";
+pub(crate) const PERMISSION_STORE_PATH: &str = "/org/freedesktop/impl/portal/PermissionStore";
+
+const SHARED_PATHS: [&str; 5] = [
+    DESKTOP_PATH,
+    DOCUMENTS_PATH,
+    FLATPAK_PATH,
+    FLATPAK_DEVELOPMENT_PATH,
+    PERMISSION_STORE_PATH,
+];
+
+static SESSION: async_lock::OnceCell<zbus::Connection> = async_lock::OnceCell::new();
+/// A connection used instead of the session bus, see
+/// [`MockPortal`](crate::test::MockPortal).
+#[cfg(feature = "test")]
+static CONNECTION_OVERRIDE: std::sync::RwLock<Option<zbus::Connection>> =
+    std::sync::RwLock::new(None);
+
+#[cfg(feature = "test")]
+pub(crate) fn set_connection_override(connection: Option<zbus::Connection>) {
+    // The traffic of each connection is logged, not only the session one.
+    #[cfg(feature = "log_dbus")]
+    if let Some(connection) = &connection {
+        crate::dbus_log::watch(connection);
+    }
+    *CONNECTION_OVERRIDE.write().unwrap() = connection;
+}
+
+/// Stops using `connection` if it is still the one in use.
+#[cfg(feature = "test")]
+pub(crate) fn clear_connection_override(connection: &zbus::Connection) {
+    let mut current = CONNECTION_OVERRIDE.write().unwrap();
+    if current
+        .as_ref()
+        .is_some_and(|c| c.server_guid() == connection.server_guid())
+    {
+        *current = None;
+    }
+    crate::cache::forget_connection(connection);
+}
+
+/// An item of the signal streams that keep working after the portal
+/// restarted.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SignalEvent<T> {
+    /// The signal was received.
+    Signal(T),
+    /// The portal restarted, the signals it emitted in the meantime were
+    /// missed and the state it holds, like the sessions, was lost.
+    Reconnected,
+}
+
+/// A method or an option added by a version of a portal interface, see
+/// [`Versioned::supports`].
+///
+/// The proxies provide the features of their interface as constants, like
+/// [`ScreenshotProxy::PICK_COLOR`](crate::desktop::screenshot::ScreenshotProxy::PICK_COLOR).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Feature(u32);
+
+impl Feature {
+    /// A feature introduced by `version` of the interface.
+    pub const fn since(version: u32) -> Self {
+        Self(version)
+    }
 
-#[derive(Debug)]
+    /// The version of the interface that introduced the feature.
+    pub fn version(self) -> u32 {
+        self.0
+    }
+}
+
+/// Implemented by the portal proxies, to gate the methods and arguments added
+/// by the later versions of their interface.
+///
+/// ```rust,no_run
+/// use ashpd::{desktop::screenshot::ScreenshotProxy, Versioned};
+///
+/// async fn run() -> ashpd::Result<()> {
+///     let screenshot = ScreenshotProxy::new().await?;
+///     println!("Version {}", screenshot.version());
+///     if screenshot.supports(ScreenshotProxy::PICK_COLOR) {
+///         println!("Colors can be picked");
+///     }
+///     Ok(())
+/// }
+/// ```
+pub trait Versioned {
+    /// The version of the interface served by the portal.
+    fn version(&self) -> u32;
+
+    /// Whether the interface provides `feature`.
+    fn supports(&self, feature: Feature) -> bool {
+        self.version() >= feature.version()
+    }
+
+    /// Fails with [`Error::RequiresVersion`] if the interface is older than
+    /// `version`.
+    fn requires_version(&self, version: u32) -> Result<(), Error> {
+        let current = self.version();
+        if current >= version {
+            Ok(())
+        } else {
+            Err(Error::RequiresVersion(version, current))
+        }
+    }
+}
+
+/// Implemented by the portal proxies, to stop waiting for a portal that
+/// doesn't answer, because its backend hangs for example.
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+///
+/// use ashpd::{desktop::settings::Settings, Timeout};
+///
+/// async fn run() -> ashpd::Result<()> {
+///     let mut settings = Settings::new().await?;
+///     settings.set_timeout(Duration::from_secs(5));
+///     println!("{:?}", settings.color_scheme().await?);
+///     Ok(())
+/// }
+/// ```
+pub trait Timeout {
+    /// Fails the method calls, the property reads and the requests made
+    /// through the proxy with [`Error::Timeout`] if the portal doesn't answer
+    /// within `timeout`.
+    ///
+    /// For the requests, the timeout covers the whole round-trip, including
+    /// the user interaction ending with the `Response` signal. The request is
+    /// closed once the timeout expired, dismissing the dialog of the portal.
+    fn set_timeout(&mut self, timeout: impl Into<Option<Duration>>);
+}
+
+#[derive(Debug, Clone)]
 pub struct Proxy<'a> {
     inner: zbus::Proxy<'a>,
     version: u32,
+    timeout: Option<Duration>,
+    cancel_on_drop: bool,
+    cancellation: Option<CancellationToken>,
 }
 
 impl<'a> Proxy<'a> {
     pub(crate) async fn connection() -> zbus::Result<zbus::Connection> {
-        if let Some(cnx) = SESSION.get() {
-            Ok(cnx.clone())
-        } else {
-            let cnx = zbus::Connection::session().await?;
-            // during `await` another task may have initialized the cell
-            Ok(SESSION.get_or_init(|| cnx).clone())
+        #[cfg(feature = "test")]
+        if let Some(cnx) = CONNECTION_OVERRIDE.read().unwrap().clone() {
+            return Ok(cnx);
         }
+        // The first task sets the connection up, the others wait for it.
+        SESSION
+            .get_or_try_init(|| async {
+                let cnx = zbus::Connection::session().await?;
+                crate::registry::register_default_app(&cnx).await;
+                #[cfg(feature = "log_dbus")]
+                crate::dbus_log::watch(&cnx);
+                Ok(cnx)
+            })
+            .await
+            .cloned()
     }
 
     pub async fn unique_name(
@@ -63,34 +220,99 @@ impl<'a> Proxy<'a> {
         P: TryInto<ObjectPath<'a>>,
         P::Error: Into<zbus::Error>,
     {
+        let path: ObjectPath<'a> = path.try_into().map_err(Into::into)?;
+        Self::with_path(interface, path, destination).await
+    }
+
+    /// The part of [`Proxy::new`] that doesn't depend on the type of the path,
+    /// compiled once for all of them.
+    async fn with_path(
+        interface: &'a str,
+        path: ObjectPath<'a>,
+        destination: &'a str,
+    ) -> Result<Proxy<'a>, Error> {
         let connection = Self::connection().await?;
-        let inner: zbus::Proxy = zbus::proxy::Builder::new(&connection)
-            .interface(interface)?
-            .path(path)?
-            .destination(destination)?
+        // Requests, sessions and the other objects with a path of their own are
+        // short lived, only the main objects of the portals are shared.
+        let key = SHARED_PATHS
+            .contains(&path.as_str())
+            .then(|| crate::cache::proxy_key(&connection, destination, &path, interface));
+        if let Some(proxy) = key.as_ref().and_then(crate::cache::shared_proxy) {
+            return Ok(proxy);
+        }
+        // Properties that rarely change are cached by `ashpd::cache` instead, the
+        // others are fetched on every read as with a new proxy.
+        let inner: zbus::Proxy<'static> = zbus::proxy::Builder::new(&connection)
+            .interface(interface.to_owned())?
+            .path(path.into_owned())?
+            .destination(destination.to_owned())?
+            .cache_properties(zbus::proxy::CacheProperties::No)
             .build()
             .await?;
 
-        let version = match inner
-            .get_property::<u32>("version")
-            .await
-            .map_err(zbus::fdo::Error::from)
-        {
-            Ok(v) => Ok(v),
-            Err(zbus::fdo::Error::InvalidArgs(details)) => {
-                if details.contains(interface) {
-                    Err(crate::Error::PortalNotFound(
-                        // We are sure it is a valid interface name, should fix the type system
-                        // here
-                        zbus::names::OwnedInterfaceName::try_from(interface).unwrap(),
-                    ))
-                } else {
-                    Ok(1)
+        let version = crate::cache::get_or_fetch(&inner, "version", async {
+            match inner
+                .get_property::<u32>("version")
+                .await
+                .map_err(zbus::fdo::Error::from)
+            {
+                Ok(v) => Ok(v),
+                Err(zbus::fdo::Error::InvalidArgs(details)) => {
+                    if details.contains(interface) {
+                        Err(crate::Error::PortalNotFound(
+                            // We are sure it is a valid interface name, should fix the type
+                            // system here
+                            zbus::names::OwnedInterfaceName::try_from(interface).unwrap(),
+                        ))
+                    } else {
+                        Ok(1)
+                    }
                 }
+                _ => Ok(1),
             }
-            _ => Ok(1),
-        }?;
-        Ok(Self { inner, version })
+        })
+        .await?;
+        let proxy = Proxy {
+            inner,
+            version,
+            timeout: None,
+            cancel_on_drop: false,
+            cancellation: None,
+        };
+        if let Some(key) = key {
+            crate::cache::share_proxy(key, &proxy).await;
+        }
+        Ok(proxy)
+    }
+
+    /// Whether the object at `path` of `destination` exports `interface`.
+    ///
+    /// The `version` property of the interface is read, the errors meaning
+    /// that there is no such interface or no service behind `destination`
+    /// result in `false`.
+    pub async fn exists(interface: &str, path: &str, destination: &str) -> Result<bool, Error> {
+        let connection = Self::connection().await?;
+        let reply = connection
+            .call_method(
+                Some(destination),
+                path,
+                Some("org.freedesktop.DBus.Properties"),
+                "Get",
+                &(interface, "version"),
+            )
+            .await;
+        let err = match reply {
+            Ok(_) => return Ok(true),
+            Err(err) => Error::from(err),
+        };
+        let invalid_args = err
+            .dbus_error_name()
+            .is_some_and(|name| name == "org.freedesktop.DBus.Error.InvalidArgs");
+        if invalid_args || err.is_portal_missing() {
+            Ok(false)
+        } else {
+            Err(err)
+        }
     }
 
     pub async fn new_desktop_with_path<P>(interface: &'a str, path: P) -> Result<Proxy<'a>, Error>
@@ -130,6 +352,15 @@ impl<'a> Proxy<'a> {
         .await
     }
 
+    pub async fn new_permission_store(interface: &'a str) -> Result<Proxy<'a>, Error> {
+        Self::new(
+            interface,
+            PERMISSION_STORE_PATH,
+            PERMISSION_STORE_DESTINATION,
+        )
+        .await
+    }
+
     pub async fn request<T>(
         &self,
         handle_token: &HandleToken,
@@ -139,13 +370,56 @@ impl<'a> Proxy<'a> {
     where
         T: for<'de> Deserialize<'de> + Type + Debug,
     {
+        if self.cancellation.as_ref().is_some_and(|t| t.is_cancelled()) {
+            return Err(ResponseError::Cancelled.into());
+        }
         let mut request = Request::from_unique_name(handle_token).await?;
-        futures_util::try_join!(request.prepare_response(), async {
-            self.call_method(method_name, &body)
-                .await
-                .map_err::<PortalError, _>(From::from)
-                .map_err(From::from)
-        })?;
+        let guard = self.cancel_on_drop.then(|| request.close_on_drop());
+        #[cfg(feature = "log_dbus")]
+        crate::dbus_log::method_call(&self.inner, method_name, &body);
+        #[cfg(feature = "tracing")]
+        let span = tracing::info_span!(
+            "request",
+            interface = %self.interface(),
+            method = method_name,
+            handle = %request.path().as_str(),
+            response = std::any::type_name::<T>(),
+        );
+        let round_trip = async {
+            // The timeout covers the user interaction, up to the `Response` signal.
+            let response = self.with_timeout(futures_util::future::try_join(
+                request.prepare_response(),
+                self.call_method_portal(method_name, &body),
+            ));
+            match &self.cancellation {
+                Some(token) => match select(pin!(response), pin!(token.cancelled())).await {
+                    Either::Left((result, _)) => result.map(|_| false),
+                    Either::Right(_) => Ok(true),
+                },
+                None => response.await.map(|_| false),
+            }
+        };
+        #[cfg(feature = "tracing")]
+        let round_trip = tracing::Instrument::instrument(round_trip, span);
+        let cancelled = match round_trip.await {
+            Ok(cancelled) => cancelled,
+            Err(Error::Timeout) => {
+                // Dismiss the dialog the portal might still be showing.
+                let _ = request.close().await;
+                return Err(Error::Timeout);
+            }
+            Err(err) => return Err(err),
+        };
+        if let Some(guard) = guard {
+            guard.disarm();
+        }
+        if cancelled {
+            #[cfg(feature = "tracing")]
+            tracing::info!("Request {} was cancelled", self.interface());
+            // The portal might be showing a dialog already.
+            let _ = request.close().await;
+            return Err(ResponseError::Cancelled.into());
+        }
         Ok(request)
     }
 
@@ -163,6 +437,46 @@ impl<'a> Proxy<'a> {
         self.version
     }
 
+    /// See [`Timeout::set_timeout`].
+    pub(crate) fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    /// Closes the requests once `token` is cancelled, failing them with
+    /// [`ResponseError::Cancelled`].
+    pub(crate) fn set_cancellation(&mut self, token: Option<CancellationToken>) {
+        self.cancellation = token;
+    }
+
+    /// Closes the requests if they fail or if their future is dropped before
+    /// the `Response` signal is received, so the portal dismisses its dialog.
+    pub(crate) fn set_cancel_on_drop(&mut self, cancel_on_drop: bool) {
+        self.cancel_on_drop = cancel_on_drop;
+    }
+
+    async fn call_method_portal(
+        &self,
+        method_name: &'static str,
+        body: &(impl Serialize + Type),
+    ) -> Result<zbus::Message, Error> {
+        self.call_method(method_name, body)
+            .await
+            .map_err::<PortalError, _>(From::from)
+            .map_err(From::from)
+    }
+
+    /// Runs `future`, failing with [`Error::Timeout`] once the timeout of the
+    /// proxy, if any, expired.
+    async fn with_timeout<T>(
+        &self,
+        future: impl Future<Output = Result<T, Error>>,
+    ) -> Result<T, Error> {
+        match self.timeout {
+            Some(timeout) => crate::helpers::timeout(timeout, future).await,
+            None => future.await,
+        }
+    }
+
     pub(crate) async fn call<R>(
         &self,
         method_name: &'static str,
@@ -172,17 +486,31 @@ impl<'a> Proxy<'a> {
         R: for<'de> Deserialize<'de> + Type,
     {
         #[cfg(feature = "tracing")]
-        {
-            tracing::info!("Calling method {}:{}", self.interface(), method_name);
-            tracing::debug!("With body {:#?}", body);
-        }
-        let msg = self
-            .call_method(method_name, &body)
-            .await
-            .map_err::<PortalError, _>(From::from)?;
-        let reply = msg.body().deserialize::<R>()?;
-
-        Ok(reply)
+        let span = tracing::info_span!(
+            "call",
+            interface = %self.interface(),
+            method = method_name,
+            path = %self.path(),
+            reply = std::any::type_name::<R>(),
+        );
+        #[cfg(feature = "log_dbus")]
+        crate::dbus_log::method_call(&self.inner, method_name, &body);
+        let call = async {
+            #[cfg(feature = "tracing")]
+            {
+                tracing::info!("Calling method {}:{}", self.interface(), method_name);
+                #[cfg(not(feature = "log_dbus"))]
+                tracing::debug!("With body {:#?}", body);
+            }
+            let msg = self
+                .with_timeout(self.call_method_portal(method_name, &body))
+                .await?;
+            let reply = msg.body().deserialize::<R>()?;
+            Ok(reply)
+        };
+        #[cfg(feature = "tracing")]
+        let call = tracing::Instrument::instrument(call, span);
+        call.await
     }
 
     pub(crate) async fn call_versioned<R>(
@@ -207,10 +535,31 @@ impl<'a> Proxy<'a> {
         T: TryFrom<OwnedValue>,
         zbus::Error: From<<T as TryFrom<OwnedValue>>::Error>,
     {
-        self.inner
-            .get_property::<T>(property_name)
-            .await
-            .map_err(From::from)
+        #[cfg(feature = "log_dbus")]
+        tracing::debug!(
+            target: "ashpd::dbus",
+            "→ get property {}.{} on {}",
+            self.interface(),
+            property_name,
+            self.path(),
+        );
+        self.with_timeout(async {
+            self.inner
+                .get_property::<T>(property_name)
+                .await
+                .map_err(From::from)
+        })
+        .await
+    }
+
+    /// The value of the property, cached if [caching](crate::cache) is
+    /// enabled.
+    pub(crate) async fn cached_property<T>(&self, property_name: &'static str) -> Result<T, Error>
+    where
+        T: TryFrom<OwnedValue> + Clone + Send + Sync + 'static,
+        zbus::Error: From<<T as TryFrom<OwnedValue>>::Error>,
+    {
+        crate::cache::get_or_fetch(&self.inner, property_name, self.property(property_name)).await
     }
 
     pub(crate) async fn property_versioned<T>(
@@ -255,6 +604,38 @@ impl<'a> Proxy<'a> {
             }))
     }
 
+    /// Same as [`Proxy::signal`] but only receives the signals whose first
+    /// argument is `session`, the bus does the filtering.
+    pub(crate) async fn signal_for_session<I>(
+        &self,
+        name: &'static str,
+        session: &ObjectPath<'_>,
+    ) -> Result<impl Stream<Item = I>, Error>
+    where
+        I: for<'de> Deserialize<'de> + Type + Debug,
+    {
+        let rule = zbus::MatchRule::builder()
+            .msg_type(zbus::message::Type::Signal)
+            .sender(self.destination())?
+            .path(self.path())?
+            .interface(self.interface())?
+            .member(name)?
+            .arg_path(0, session)?
+            .build();
+        let stream = zbus::MessageStream::for_match_rule(rule, self.connection(), None).await?;
+        Ok(stream.filter_map({
+            #[cfg(not(feature = "tracing"))]
+            {
+                move |msg| ready(msg.ok().and_then(|msg| msg.body().deserialize().ok()))
+            }
+            #[cfg(feature = "tracing")]
+            {
+                let ifc = self.interface().to_owned();
+                move |msg| ready(msg.ok().and_then(|msg| trace_body(name, &ifc, msg)))
+            }
+        }))
+    }
+
     pub(crate) async fn signal<I>(&self, name: &'static str) -> Result<impl Stream<Item = I>, Error>
     where
         I: for<'de> Deserialize<'de> + Type + Debug,
@@ -271,6 +652,144 @@ impl<'a> Proxy<'a> {
             }
         }))
     }
+
+    /// Same as [`Proxy::signal`] but subscribes again to the signal once the
+    /// portal restarted, after it crashed for example.
+    pub(crate) async fn signal_reconnecting<I>(
+        &self,
+        name: &'static str,
+    ) -> Result<impl Stream<Item = SignalEvent<I>> + Unpin + 'a, Error>
+    where
+        I: for<'de> Deserialize<'de> + Type + Debug + 'a,
+    {
+        // A peer-to-peer connection has no bus to report the owner changes,
+        // the portal can't be restarted behind it then.
+        let owner_changes = if self.connection().is_bus() {
+            let dbus = zbus::fdo::DBusProxy::new(self.connection()).await?;
+            let changes = dbus
+                .receive_name_owner_changed_with_args(&[(0, self.destination().as_str())])
+                .await?;
+            Some(changes)
+        } else {
+            None
+        };
+        let signals = self.inner.receive_signal(name).await?;
+        let state = (self.inner.clone(), signals, owner_changes);
+        // Boxed so that the stream can be polled without pinning it first, like
+        // the other signal streams.
+        Ok(Box::pin(futures_util::stream::unfold(
+            state,
+            move |(inner, mut signals, mut owner_changes)| async move {
+                loop {
+                    let owner_change = match owner_changes.as_mut() {
+                        Some(changes) => Either::Left(changes.next()),
+                        None => Either::Right(std::future::pending()),
+                    };
+                    let next = match select(signals.next(), owner_change).await {
+                        Either::Left((msg, _)) => Either::Left(msg?),
+                        Either::Right((change, _)) => Either::Right(change?),
+                    };
+                    match next {
+                        Either::Left(msg) => {
+                            #[cfg(not(feature = "tracing"))]
+                            let body = msg.body().deserialize().ok();
+                            #[cfg(feature = "tracing")]
+                            let body = trace_body(name, inner.interface(), msg);
+                            if let Some(body) = body {
+                                let state = (inner, signals, owner_changes);
+                                return Some((SignalEvent::Signal(body), state));
+                            }
+                        }
+                        Either::Right(change) => {
+                            // The portal exited, wait for it to start again.
+                            let Ok(args) = change.args() else {
+                                continue;
+                            };
+                            if args.new_owner().is_none() {
+                                continue;
+                            }
+                            #[cfg(feature = "tracing")]
+                            tracing::info!("Portal restarted, receiving '{name}' again");
+                            let signals = inner.receive_signal(name).await.ok()?;
+                            let state = (inner, signals, owner_changes);
+                            return Some((SignalEvent::Reconnected, state));
+                        }
+                    }
+                }
+            },
+        )))
+    }
+}
+
+#[cfg(test)]
+use zbus::zvariant::Signature;
+
+/// The interface, the method and the signatures of the body and the reply of a
+/// method called by the wrappers.
+#[cfg(test)]
+pub(crate) type WrapperSignature = (
+    &'static str,
+    &'static str,
+    &'static Signature,
+    &'static Signature,
+);
+
+/// Lists the methods called by the wrappers of an interface, with the types of
+/// their body and reply.
+#[cfg(test)]
+macro_rules! wrapper_signatures {
+    ($interface:literal { $($method:literal: $body:ty => $reply:ty),* $(,)? }) => {
+        &[$((
+            $interface,
+            $method,
+            <$body as zbus::zvariant::Type>::SIGNATURE,
+            <$reply as zbus::zvariant::Type>::SIGNATURE,
+        )),*]
+    };
+}
+#[cfg(test)]
+pub(crate) use wrapper_signatures;
+
+/// The signatures of the input and output arguments of the `method_name` of
+/// `interface`, according to the interfaces XML files.
+#[cfg(test)]
+fn method_signatures(interface: &str, method_name: &str) -> Option<(&'static str, &'static str)> {
+    signatures::METHODS
+        .iter()
+        .find(|(i, m, _, _)| *i == interface && *m == method_name)
+        .map(|(_, _, inputs, outputs)| (*inputs, *outputs))
+}
+
+/// Whether a body of type `signature` has the `expected` arguments.
+#[cfg(test)]
+fn matches(expected: &str, signature: &Signature) -> bool {
+    // The body of a message is the content of the structure.
+    match signature {
+        Signature::Structure(fields) => {
+            fields.iter().map(ToString::to_string).collect::<String>() == expected
+        }
+        signature => signature.to_string() == expected,
+    }
+}
+
+/// Whether a body of type `signature` is accepted by the `method_name` of
+/// `interface`, according to the interfaces XML files.
+///
+/// The methods missing from the XML files, like the ones of newer portals,
+/// are always accepted.
+#[cfg(test)]
+fn accepts(interface: &str, method_name: &str, signature: &Signature) -> bool {
+    method_signatures(interface, method_name).map_or(true, |(inputs, _)| matches(inputs, signature))
+}
+
+/// Whether a reply of type `signature` is returned by the `method_name` of
+/// `interface`, according to the interfaces XML files.
+///
+/// The methods missing from the XML files are assumed to return it.
+#[cfg(test)]
+fn returns(interface: &str, method_name: &str, signature: &Signature) -> bool {
+    method_signatures(interface, method_name)
+        .map_or(true, |(_, outputs)| matches(outputs, signature))
 }
 
 #[cfg(feature = "tracing")]
@@ -298,3 +817,254 @@ impl<'a> Deref for Proxy<'a> {
         &self.inner
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use zbus::zvariant::{OwnedObjectPath, Type, Value};
+
+    use super::{accepts, matches, returns};
+
+    #[test]
+    fn method_signatures() {
+        let screenshot = <(&str, HashMap<&str, Value<'_>>)>::SIGNATURE;
+        assert!(accepts(
+            "org.freedesktop.portal.Screenshot",
+            "Screenshot",
+            screenshot
+        ));
+        assert!(!accepts(
+            "org.freedesktop.portal.Screenshot",
+            "Screenshot",
+            <(&str, u32)>::SIGNATURE
+        ));
+        assert!(!accepts(
+            "org.freedesktop.portal.Trash",
+            "TrashFile",
+            i32::SIGNATURE
+        ));
+        assert!(accepts(
+            "org.freedesktop.portal.Example",
+            "Unknown",
+            u32::SIGNATURE
+        ));
+    }
+
+    #[test]
+    fn reply_signatures() {
+        use crate::{
+            desktop::{dynamic_launcher::LauncherIcon, game_mode, network_monitor},
+            documents::Permissions,
+            FilePath,
+        };
+
+        let replies = [
+            (
+                "org.freedesktop.portal.Screenshot",
+                "Screenshot",
+                OwnedObjectPath::SIGNATURE,
+            ),
+            (
+                "org.freedesktop.portal.DynamicLauncher",
+                "GetIcon",
+                LauncherIcon::SIGNATURE,
+            ),
+            (
+                "org.freedesktop.portal.GameMode",
+                "QueryStatus",
+                game_mode::Status::SIGNATURE,
+            ),
+            (
+                "org.freedesktop.portal.NetworkMonitor",
+                "GetConnectivity",
+                network_monitor::Connectivity::SIGNATURE,
+            ),
+            (
+                "org.freedesktop.portal.Documents",
+                "Info",
+                <(FilePath, Permissions)>::SIGNATURE,
+            ),
+            (
+                "org.freedesktop.portal.Documents",
+                "List",
+                HashMap::<String, FilePath>::SIGNATURE,
+            ),
+            ("org.freedesktop.portal.Trash", "TrashFile", u32::SIGNATURE),
+            ("org.freedesktop.portal.Session", "Close", <()>::SIGNATURE),
+        ];
+        for (interface, method, signature) in replies {
+            assert!(
+                returns(interface, method, signature),
+                "{interface}.{method}"
+            );
+        }
+        assert!(!returns(
+            "org.freedesktop.portal.Documents",
+            "Lookup",
+            u32::SIGNATURE
+        ));
+        assert!(returns(
+            "org.freedesktop.portal.Example",
+            "Unknown",
+            u32::SIGNATURE
+        ));
+    }
+
+    #[test]
+    fn wrapper_signatures() {
+        use super::{signatures::METHODS, WrapperSignature};
+        use crate::{desktop, documents, flatpak};
+
+        let wrappers: &[&[WrapperSignature]] = &[
+            desktop::account::SIGNATURES,
+            desktop::background::SIGNATURES,
+            desktop::camera::SIGNATURES,
+            desktop::clipboard::SIGNATURES,
+            #[allow(deprecated)]
+            desktop::device::SIGNATURES,
+            desktop::device_access::SIGNATURES,
+            desktop::dynamic_launcher::SIGNATURES,
+            desktop::email::SIGNATURES,
+            desktop::file_chooser::SIGNATURES,
+            desktop::game_mode::SIGNATURES,
+            desktop::global_shortcuts::SIGNATURES,
+            desktop::inhibit::SIGNATURES,
+            desktop::input_capture::SIGNATURES,
+            desktop::location::SIGNATURES,
+            desktop::network_monitor::SIGNATURES,
+            desktop::notification::SIGNATURES,
+            desktop::open_uri::SIGNATURES,
+            desktop::print::SIGNATURES,
+            desktop::proxy_resolver::SIGNATURES,
+            desktop::realtime::SIGNATURES,
+            desktop::remote_desktop::SIGNATURES,
+            desktop::request::SIGNATURES,
+            desktop::SESSION_SIGNATURES,
+            desktop::screencast::SIGNATURES,
+            desktop::screenshot::SIGNATURES,
+            desktop::secret::SIGNATURES,
+            desktop::settings::SIGNATURES,
+            desktop::trash::SIGNATURES,
+            desktop::wallpaper::SIGNATURES,
+            documents::SIGNATURES,
+            documents::FILE_TRANSFER_SIGNATURES,
+            flatpak::SIGNATURES,
+            flatpak::DEVELOPMENT_SIGNATURES,
+            flatpak::UPDATE_MONITOR_SIGNATURES,
+        ];
+        let wrappers = wrappers.iter().flat_map(|signatures| signatures.iter());
+
+        // The tables list every method of the interfaces they cover.
+        for (interface, method, _, _) in METHODS {
+            if wrappers.clone().any(|(i, _, _, _)| i == interface) {
+                assert!(
+                    wrappers
+                        .clone()
+                        .any(|(i, m, _, _)| i == interface && m == method),
+                    "{interface}.{method} is missing from the signatures of the wrapper"
+                );
+            }
+        }
+
+        for (interface, method, inputs, outputs) in METHODS {
+            for (_, _, body, reply) in wrappers
+                .clone()
+                .filter(|(i, m, _, _)| i == interface && m == method)
+            {
+                assert!(
+                    matches(inputs, body),
+                    "The body of {interface}.{method} has the wrong signature {body}"
+                );
+                assert!(
+                    matches(outputs, reply),
+                    "The reply of {interface}.{method} has the wrong signature {reply}"
+                );
+            }
+        }
+    }
+
+    #[cfg(feature = "test")]
+    #[tokio::test]
+    async fn timeout() {
+        use std::time::Duration;
+
+        use zbus::zvariant::OwnedValue;
+
+        use crate::{
+            desktop::screenshot::Screenshot,
+            proxy::Proxy,
+            test::{MockPortal, MockResponse},
+            Error,
+        };
+
+        let _lock = crate::test::CONNECTION_LOCK.lock().await;
+        let mock = MockPortal::new().await.unwrap();
+        mock.respond(
+            "org.freedesktop.portal.Screenshot",
+            "Screenshot",
+            MockResponse::success([("uri", Value::from("file:///tmp/screenshot.png"))])
+                .delay(Duration::from_millis(50)),
+        );
+        mock.respond(
+            "org.freedesktop.portal.Settings",
+            "Read",
+            MockResponse::reply((Value::new(Value::from(1u32)),)).delay(Duration::from_millis(50)),
+        );
+
+        // The timeout covers the whole round-trip, the request gets closed.
+        let request = Screenshot::request()
+            .timeout(Duration::from_millis(10))
+            .send()
+            .await;
+        assert!(matches!(request, Err(Error::Timeout)));
+        assert!(mock.calls().iter().any(|call| {
+            let header = call.header();
+            header.interface().map(|i| i.as_str()) == Some("org.freedesktop.portal.Request")
+                && header.member().map(|m| m.as_str()) == Some("Close")
+        }));
+
+        let mut proxy = Proxy::new_desktop("org.freedesktop.portal.Settings")
+            .await
+            .unwrap();
+        proxy.set_timeout(Some(Duration::from_millis(10)));
+        let reply = proxy
+            .call::<OwnedValue>("Read", &("org.freedesktop.appearance", "color-scheme"))
+            .await;
+        assert!(matches!(reply, Err(Error::Timeout)));
+    }
+
+    #[cfg(feature = "test")]
+    #[tokio::test]
+    async fn exists() {
+        use crate::{desktop::is_portal_available, test::MockPortal};
+
+        let _lock = crate::test::CONNECTION_LOCK.lock().await;
+        let mock = MockPortal::new().await.unwrap();
+        mock.set_version("org.freedesktop.portal.Screenshot", 2);
+        assert!(is_portal_available("org.freedesktop.portal.Screenshot")
+            .await
+            .unwrap());
+        assert!(!is_portal_available("org.freedesktop.portal.Camera")
+            .await
+            .unwrap());
+    }
+
+    #[cfg(feature = "test")]
+    #[tokio::test]
+    async fn versioned() {
+        use crate::{desktop::settings::Settings, test::MockPortal, Error, Feature, Versioned};
+
+        let _lock = crate::test::CONNECTION_LOCK.lock().await;
+        let mock = MockPortal::new().await.unwrap();
+        mock.set_version("org.freedesktop.portal.Settings", 2);
+        let settings = Settings::new().await.unwrap();
+        assert_eq!(settings.version(), 2);
+        assert!(settings.supports(Feature::since(2)));
+        assert!(!settings.supports(Feature::since(3)));
+        assert!(matches!(
+            settings.requires_version(3),
+            Err(Error::RequiresVersion(3, 2))
+        ));
+    }
+}
@@ -0,0 +1,698 @@
+// This file was generated from the interfaces XML files by
+// `cargo run --manifest-path xtask/Cargo.toml`, do not edit it.
+
+/// Raw proxy of [`org.freedesktop.portal.ProxyResolver`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.ProxyResolver.html).
+#[zbus::proxy(
+    interface = "org.freedesktop.portal.ProxyResolver",
+    default_service = "org.freedesktop.portal.Desktop",
+    default_path = "/org/freedesktop/portal/desktop",
+    gen_blocking = false
+)]
+pub trait ProxyResolver {
+    #[zbus(name = "Lookup")]
+    fn lookup(&self, uri: &str) -> zbus::Result<Vec<String>>;
+    #[zbus(property, name = "version")]
+    fn version(&self) -> zbus::Result<u32>;
+}
+
+/// Raw proxy of [`org.freedesktop.portal.Background`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Background.html).
+#[zbus::proxy(
+    interface = "org.freedesktop.portal.Background",
+    default_service = "org.freedesktop.portal.Desktop",
+    default_path = "/org/freedesktop/portal/desktop",
+    gen_blocking = false
+)]
+pub trait Background {
+    #[zbus(name = "RequestBackground")]
+    fn request_background(
+        &self,
+        parent_window: &str,
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+    ) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+    #[zbus(property, name = "version")]
+    fn version(&self) -> zbus::Result<u32>;
+}
+
+/// Raw proxy of [`org.freedesktop.portal.Location`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Location.html).
+#[zbus::proxy(
+    interface = "org.freedesktop.portal.Location",
+    default_service = "org.freedesktop.portal.Desktop",
+    default_path = "/org/freedesktop/portal/desktop",
+    gen_blocking = false
+)]
+pub trait Location {
+    #[zbus(name = "CreateSession")]
+    fn create_session(
+        &self,
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+    ) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+    #[zbus(name = "Start")]
+    fn start(
+        &self,
+        session_handle: &zbus::zvariant::ObjectPath<'_>,
+        parent_window: &str,
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+    ) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+    #[zbus(signal, name = "LocationUpdated")]
+    fn location_updated(
+        &self,
+        session_handle: zbus::zvariant::OwnedObjectPath,
+        location: std::collections::HashMap<String, zbus::zvariant::OwnedValue>,
+    ) -> zbus::Result<()>;
+    #[zbus(property, name = "version")]
+    fn version(&self) -> zbus::Result<u32>;
+}
+
+/// Raw proxy of [`org.freedesktop.portal.Notification`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Notification.html).
+#[zbus::proxy(
+    interface = "org.freedesktop.portal.Notification",
+    default_service = "org.freedesktop.portal.Desktop",
+    default_path = "/org/freedesktop/portal/desktop",
+    gen_blocking = false
+)]
+pub trait Notification {
+    #[zbus(name = "AddNotification")]
+    fn add_notification(
+        &self,
+        id: &str,
+        notification: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+    ) -> zbus::Result<()>;
+    #[zbus(name = "RemoveNotification")]
+    fn remove_notification(&self, id: &str) -> zbus::Result<()>;
+    #[zbus(signal, name = "ActionInvoked")]
+    fn action_invoked(
+        &self,
+        id: String,
+        action: String,
+        parameter: Vec<zbus::zvariant::OwnedValue>,
+    ) -> zbus::Result<()>;
+    #[zbus(property, name = "version")]
+    fn version(&self) -> zbus::Result<u32>;
+}
+
+/// Raw proxy of [`org.freedesktop.portal.Screenshot`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Screenshot.html).
+#[zbus::proxy(
+    interface = "org.freedesktop.portal.Screenshot",
+    default_service = "org.freedesktop.portal.Desktop",
+    default_path = "/org/freedesktop/portal/desktop",
+    gen_blocking = false
+)]
+pub trait Screenshot {
+    #[zbus(name = "Screenshot")]
+    fn screenshot(
+        &self,
+        parent_window: &str,
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+    ) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+    #[zbus(name = "PickColor")]
+    fn pick_color(
+        &self,
+        parent_window: &str,
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+    ) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+    #[zbus(property, name = "version")]
+    fn version(&self) -> zbus::Result<u32>;
+}
+
+/// Raw proxy of [`org.freedesktop.portal.Account`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Account.html).
+#[zbus::proxy(
+    interface = "org.freedesktop.portal.Account",
+    default_service = "org.freedesktop.portal.Desktop",
+    default_path = "/org/freedesktop/portal/desktop",
+    gen_blocking = false
+)]
+pub trait Account {
+    #[zbus(name = "GetUserInformation")]
+    fn get_user_information(
+        &self,
+        window: &str,
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+    ) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+    #[zbus(property, name = "version")]
+    fn version(&self) -> zbus::Result<u32>;
+}
+
+/// Raw proxy of [`org.freedesktop.portal.NetworkMonitor`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.NetworkMonitor.html).
+#[zbus::proxy(
+    interface = "org.freedesktop.portal.NetworkMonitor",
+    default_service = "org.freedesktop.portal.Desktop",
+    default_path = "/org/freedesktop/portal/desktop",
+    gen_blocking = false
+)]
+pub trait NetworkMonitor {
+    #[zbus(name = "GetAvailable")]
+    fn get_available(&self) -> zbus::Result<bool>;
+    #[zbus(name = "GetMetered")]
+    fn get_metered(&self) -> zbus::Result<bool>;
+    #[zbus(name = "GetConnectivity")]
+    fn get_connectivity(&self) -> zbus::Result<u32>;
+    #[zbus(name = "GetStatus")]
+    fn get_status(
+        &self,
+    ) -> zbus::Result<std::collections::HashMap<String, zbus::zvariant::OwnedValue>>;
+    #[zbus(name = "CanReach")]
+    fn can_reach(&self, hostname: &str, port: u32) -> zbus::Result<bool>;
+    #[zbus(signal, name = "changed")]
+    fn changed(&self) -> zbus::Result<()>;
+    #[zbus(property, name = "version")]
+    fn version(&self) -> zbus::Result<u32>;
+}
+
+/// Raw proxy of [`org.freedesktop.portal.Print`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Print.html).
+#[zbus::proxy(
+    interface = "org.freedesktop.portal.Print",
+    default_service = "org.freedesktop.portal.Desktop",
+    default_path = "/org/freedesktop/portal/desktop",
+    gen_blocking = false
+)]
+pub trait Print {
+    #[zbus(name = "Print")]
+    fn print(
+        &self,
+        parent_window: &str,
+        title: &str,
+        fd: zbus::zvariant::Fd<'_>,
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+    ) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+    #[zbus(name = "PreparePrint")]
+    fn prepare_print(
+        &self,
+        parent_window: &str,
+        title: &str,
+        settings: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+        page_setup: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+    ) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+    #[zbus(property, name = "version")]
+    fn version(&self) -> zbus::Result<u32>;
+}
+
+/// Raw proxy of [`org.freedesktop.portal.Settings`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Settings.html).
+#[zbus::proxy(
+    interface = "org.freedesktop.portal.Settings",
+    default_service = "org.freedesktop.portal.Desktop",
+    default_path = "/org/freedesktop/portal/desktop",
+    gen_blocking = false
+)]
+pub trait Settings {
+    #[zbus(name = "ReadAll")]
+    fn read_all(
+        &self,
+        namespaces: &[&str],
+    ) -> zbus::Result<
+        std::collections::HashMap<
+            String,
+            std::collections::HashMap<String, zbus::zvariant::OwnedValue>,
+        >,
+    >;
+    #[zbus(name = "Read")]
+    fn read(&self, namespace: &str, key: &str) -> zbus::Result<zbus::zvariant::OwnedValue>;
+    #[zbus(signal, name = "SettingChanged")]
+    fn setting_changed(
+        &self,
+        namespace: String,
+        key: String,
+        value: zbus::zvariant::OwnedValue,
+    ) -> zbus::Result<()>;
+    #[zbus(property, name = "version")]
+    fn version(&self) -> zbus::Result<u32>;
+}
+
+/// Raw proxy of [`org.freedesktop.portal.GameMode`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.GameMode.html).
+#[zbus::proxy(
+    interface = "org.freedesktop.portal.GameMode",
+    default_service = "org.freedesktop.portal.Desktop",
+    default_path = "/org/freedesktop/portal/desktop",
+    gen_blocking = false
+)]
+pub trait GameMode {
+    #[zbus(name = "QueryStatus")]
+    fn query_status(&self, pid: i32) -> zbus::Result<i32>;
+    #[zbus(name = "RegisterGame")]
+    fn register_game(&self, pid: i32) -> zbus::Result<i32>;
+    #[zbus(name = "UnregisterGame")]
+    fn unregister_game(&self, pid: i32) -> zbus::Result<i32>;
+    #[zbus(name = "QueryStatusByPid")]
+    fn query_status_by_pid(&self, target: i32, requester: i32) -> zbus::Result<i32>;
+    #[zbus(name = "RegisterGameByPid")]
+    fn register_game_by_pid(&self, target: i32, requester: i32) -> zbus::Result<i32>;
+    #[zbus(name = "UnregisterGameByPid")]
+    fn unregister_game_by_pid(&self, target: i32, requester: i32) -> zbus::Result<i32>;
+    #[zbus(name = "QueryStatusByPIDFd")]
+    fn query_status_by_pid_fd(
+        &self,
+        target: zbus::zvariant::Fd<'_>,
+        requester: zbus::zvariant::Fd<'_>,
+    ) -> zbus::Result<i32>;
+    #[zbus(name = "RegisterGameByPIDFd")]
+    fn register_game_by_pid_fd(
+        &self,
+        target: zbus::zvariant::Fd<'_>,
+        requester: zbus::zvariant::Fd<'_>,
+    ) -> zbus::Result<i32>;
+    #[zbus(name = "UnregisterGameByPIDFd")]
+    fn unregister_game_by_pid_fd(
+        &self,
+        target: zbus::zvariant::Fd<'_>,
+        requester: zbus::zvariant::Fd<'_>,
+    ) -> zbus::Result<i32>;
+    #[zbus(property, name = "version")]
+    fn version(&self) -> zbus::Result<u32>;
+}
+
+/// Raw proxy of [`org.freedesktop.portal.RemoteDesktop`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.RemoteDesktop.html).
+#[zbus::proxy(
+    interface = "org.freedesktop.portal.RemoteDesktop",
+    default_service = "org.freedesktop.portal.Desktop",
+    default_path = "/org/freedesktop/portal/desktop",
+    gen_blocking = false
+)]
+pub trait RemoteDesktop {
+    #[zbus(name = "CreateSession")]
+    fn create_session(
+        &self,
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+    ) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+    #[zbus(name = "SelectDevices")]
+    fn select_devices(
+        &self,
+        session_handle: &zbus::zvariant::ObjectPath<'_>,
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+    ) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+    #[zbus(name = "Start")]
+    fn start(
+        &self,
+        session_handle: &zbus::zvariant::ObjectPath<'_>,
+        parent_window: &str,
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+    ) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+    #[zbus(name = "NotifyPointerMotion")]
+    fn notify_pointer_motion(
+        &self,
+        session_handle: &zbus::zvariant::ObjectPath<'_>,
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+        dx: f64,
+        dy: f64,
+    ) -> zbus::Result<()>;
+    #[zbus(name = "NotifyPointerMotionAbsolute")]
+    fn notify_pointer_motion_absolute(
+        &self,
+        session_handle: &zbus::zvariant::ObjectPath<'_>,
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+        stream: u32,
+        x: f64,
+        y: f64,
+    ) -> zbus::Result<()>;
+    #[zbus(name = "NotifyPointerButton")]
+    fn notify_pointer_button(
+        &self,
+        session_handle: &zbus::zvariant::ObjectPath<'_>,
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+        button: i32,
+        state: u32,
+    ) -> zbus::Result<()>;
+    #[zbus(name = "NotifyPointerAxis")]
+    fn notify_pointer_axis(
+        &self,
+        session_handle: &zbus::zvariant::ObjectPath<'_>,
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+        dx: f64,
+        dy: f64,
+    ) -> zbus::Result<()>;
+    #[zbus(name = "NotifyPointerAxisDiscrete")]
+    fn notify_pointer_axis_discrete(
+        &self,
+        session_handle: &zbus::zvariant::ObjectPath<'_>,
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+        axis: u32,
+        steps: i32,
+    ) -> zbus::Result<()>;
+    #[zbus(name = "NotifyKeyboardKeycode")]
+    fn notify_keyboard_keycode(
+        &self,
+        session_handle: &zbus::zvariant::ObjectPath<'_>,
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+        keycode: i32,
+        state: u32,
+    ) -> zbus::Result<()>;
+    #[zbus(name = "NotifyKeyboardKeysym")]
+    fn notify_keyboard_keysym(
+        &self,
+        session_handle: &zbus::zvariant::ObjectPath<'_>,
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+        keysym: i32,
+        state: u32,
+    ) -> zbus::Result<()>;
+    #[zbus(name = "NotifyTouchDown")]
+    fn notify_touch_down(
+        &self,
+        session_handle: &zbus::zvariant::ObjectPath<'_>,
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+        stream: u32,
+        slot: u32,
+        x: f64,
+        y: f64,
+    ) -> zbus::Result<()>;
+    #[zbus(name = "NotifyTouchMotion")]
+    fn notify_touch_motion(
+        &self,
+        session_handle: &zbus::zvariant::ObjectPath<'_>,
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+        stream: u32,
+        slot: u32,
+        x: f64,
+        y: f64,
+    ) -> zbus::Result<()>;
+    #[zbus(name = "NotifyTouchUp")]
+    fn notify_touch_up(
+        &self,
+        session_handle: &zbus::zvariant::ObjectPath<'_>,
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+        slot: u32,
+    ) -> zbus::Result<()>;
+    #[zbus(property, name = "AvailableDeviceTypes")]
+    fn available_device_types(&self) -> zbus::Result<u32>;
+    #[zbus(property, name = "version")]
+    fn version(&self) -> zbus::Result<u32>;
+}
+
+/// Raw proxy of [`org.freedesktop.portal.MemoryMonitor`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.MemoryMonitor.html).
+#[zbus::proxy(
+    interface = "org.freedesktop.portal.MemoryMonitor",
+    default_service = "org.freedesktop.portal.Desktop",
+    default_path = "/org/freedesktop/portal/desktop",
+    gen_blocking = false
+)]
+pub trait MemoryMonitor {
+    #[zbus(signal, name = "LowMemoryWarning")]
+    fn low_memory_warning(&self, level: u8) -> zbus::Result<()>;
+    #[zbus(property, name = "version")]
+    fn version(&self) -> zbus::Result<u32>;
+}
+
+/// Raw proxy of [`org.freedesktop.portal.OpenURI`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.OpenURI.html).
+#[zbus::proxy(
+    interface = "org.freedesktop.portal.OpenURI",
+    default_service = "org.freedesktop.portal.Desktop",
+    default_path = "/org/freedesktop/portal/desktop",
+    gen_blocking = false
+)]
+pub trait OpenURI {
+    #[zbus(name = "OpenURI")]
+    fn open_uri(
+        &self,
+        parent_window: &str,
+        uri: &str,
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+    ) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+    #[zbus(name = "OpenFile")]
+    fn open_file(
+        &self,
+        parent_window: &str,
+        fd: zbus::zvariant::Fd<'_>,
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+    ) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+    #[zbus(name = "OpenDirectory")]
+    fn open_directory(
+        &self,
+        parent_window: &str,
+        fd: zbus::zvariant::Fd<'_>,
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+    ) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+    #[zbus(property, name = "version")]
+    fn version(&self) -> zbus::Result<u32>;
+}
+
+/// Raw proxy of [`org.freedesktop.portal.Realtime`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Realtime.html).
+#[zbus::proxy(
+    interface = "org.freedesktop.portal.Realtime",
+    default_service = "org.freedesktop.portal.Desktop",
+    default_path = "/org/freedesktop/portal/desktop",
+    gen_blocking = false
+)]
+pub trait Realtime {
+    #[zbus(name = "MakeThreadRealtimeWithPID")]
+    fn make_thread_realtime_with_pid(
+        &self,
+        process: u64,
+        thread: u64,
+        priority: u32,
+    ) -> zbus::Result<()>;
+    #[zbus(name = "MakeThreadHighPriorityWithPID")]
+    fn make_thread_high_priority_with_pid(
+        &self,
+        process: u64,
+        thread: u64,
+        priority: i32,
+    ) -> zbus::Result<()>;
+    #[zbus(property, name = "MaxRealtimePriority")]
+    fn max_realtime_priority(&self) -> zbus::Result<i64>;
+    #[zbus(property, name = "MinNiceLevel")]
+    fn min_nice_level(&self) -> zbus::Result<i32>;
+    #[zbus(property, name = "RTTimeUSecMax")]
+    fn rt_time_u_sec_max(&self) -> zbus::Result<i32>;
+    #[zbus(property, name = "version")]
+    fn version(&self) -> zbus::Result<u32>;
+}
+
+/// Raw proxy of [`org.freedesktop.portal.Secret`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Secret.html).
+#[zbus::proxy(
+    interface = "org.freedesktop.portal.Secret",
+    default_service = "org.freedesktop.portal.Desktop",
+    default_path = "/org/freedesktop/portal/desktop",
+    gen_blocking = false
+)]
+pub trait Secret {
+    #[zbus(name = "RetrieveSecret")]
+    fn retrieve_secret(
+        &self,
+        fd: zbus::zvariant::Fd<'_>,
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+    ) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+    #[zbus(property, name = "version")]
+    fn version(&self) -> zbus::Result<u32>;
+}
+
+/// Raw proxy of [`org.freedesktop.portal.Wallpaper`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Wallpaper.html).
+#[zbus::proxy(
+    interface = "org.freedesktop.portal.Wallpaper",
+    default_service = "org.freedesktop.portal.Desktop",
+    default_path = "/org/freedesktop/portal/desktop",
+    gen_blocking = false
+)]
+pub trait Wallpaper {
+    #[zbus(name = "SetWallpaperURI")]
+    fn set_wallpaper_uri(
+        &self,
+        parent_window: &str,
+        uri: &str,
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+    ) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+    #[zbus(name = "SetWallpaperFile")]
+    fn set_wallpaper_file(
+        &self,
+        parent_window: &str,
+        fd: zbus::zvariant::Fd<'_>,
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+    ) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+    #[zbus(property, name = "version")]
+    fn version(&self) -> zbus::Result<u32>;
+}
+
+/// Raw proxy of [`org.freedesktop.portal.Camera`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Camera.html).
+#[zbus::proxy(
+    interface = "org.freedesktop.portal.Camera",
+    default_service = "org.freedesktop.portal.Desktop",
+    default_path = "/org/freedesktop/portal/desktop",
+    gen_blocking = false
+)]
+pub trait Camera {
+    #[zbus(name = "AccessCamera")]
+    fn access_camera(
+        &self,
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+    ) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+    #[zbus(name = "OpenPipeWireRemote")]
+    fn open_pipe_wire_remote(
+        &self,
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+    ) -> zbus::Result<zbus::zvariant::OwnedFd>;
+    #[zbus(property, name = "IsCameraPresent")]
+    fn is_camera_present(&self) -> zbus::Result<bool>;
+    #[zbus(property, name = "version")]
+    fn version(&self) -> zbus::Result<u32>;
+}
+
+/// Raw proxy of [`org.freedesktop.portal.Device`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Device.html).
+#[zbus::proxy(
+    interface = "org.freedesktop.portal.Device",
+    default_service = "org.freedesktop.portal.Desktop",
+    default_path = "/org/freedesktop/portal/desktop",
+    gen_blocking = false
+)]
+pub trait Device {
+    #[zbus(name = "AccessDevice")]
+    fn access_device(
+        &self,
+        pid: u32,
+        devices: &[&str],
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+    ) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+    #[zbus(property, name = "version")]
+    fn version(&self) -> zbus::Result<u32>;
+}
+
+/// Raw proxy of [`org.freedesktop.portal.PowerProfileMonitor`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.PowerProfileMonitor.html).
+#[zbus::proxy(
+    interface = "org.freedesktop.portal.PowerProfileMonitor",
+    default_service = "org.freedesktop.portal.Desktop",
+    default_path = "/org/freedesktop/portal/desktop",
+    gen_blocking = false
+)]
+pub trait PowerProfileMonitor {
+    #[zbus(property, name = "power-saver-enabled")]
+    fn power_saver_enabled(&self) -> zbus::Result<bool>;
+    #[zbus(property, name = "version")]
+    fn version(&self) -> zbus::Result<u32>;
+}
+
+/// Raw proxy of [`org.freedesktop.portal.ScreenCast`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.ScreenCast.html).
+#[zbus::proxy(
+    interface = "org.freedesktop.portal.ScreenCast",
+    default_service = "org.freedesktop.portal.Desktop",
+    default_path = "/org/freedesktop/portal/desktop",
+    gen_blocking = false
+)]
+pub trait ScreenCast {
+    #[zbus(name = "CreateSession")]
+    fn create_session(
+        &self,
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+    ) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+    #[zbus(name = "SelectSources")]
+    fn select_sources(
+        &self,
+        session_handle: &zbus::zvariant::ObjectPath<'_>,
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+    ) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+    #[zbus(name = "Start")]
+    fn start(
+        &self,
+        session_handle: &zbus::zvariant::ObjectPath<'_>,
+        parent_window: &str,
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+    ) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+    #[zbus(name = "OpenPipeWireRemote")]
+    fn open_pipe_wire_remote(
+        &self,
+        session_handle: &zbus::zvariant::ObjectPath<'_>,
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+    ) -> zbus::Result<zbus::zvariant::OwnedFd>;
+    #[zbus(property, name = "AvailableSourceTypes")]
+    fn available_source_types(&self) -> zbus::Result<u32>;
+    #[zbus(property, name = "AvailableCursorModes")]
+    fn available_cursor_modes(&self) -> zbus::Result<u32>;
+    #[zbus(property, name = "version")]
+    fn version(&self) -> zbus::Result<u32>;
+}
+
+/// Raw proxy of [`org.freedesktop.portal.Email`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Email.html).
+#[zbus::proxy(
+    interface = "org.freedesktop.portal.Email",
+    default_service = "org.freedesktop.portal.Desktop",
+    default_path = "/org/freedesktop/portal/desktop",
+    gen_blocking = false
+)]
+pub trait Email {
+    #[zbus(name = "ComposeEmail")]
+    fn compose_email(
+        &self,
+        parent_window: &str,
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+    ) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+    #[zbus(property, name = "version")]
+    fn version(&self) -> zbus::Result<u32>;
+}
+
+/// Raw proxy of [`org.freedesktop.portal.Inhibit`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Inhibit.html).
+#[zbus::proxy(
+    interface = "org.freedesktop.portal.Inhibit",
+    default_service = "org.freedesktop.portal.Desktop",
+    default_path = "/org/freedesktop/portal/desktop",
+    gen_blocking = false
+)]
+pub trait Inhibit {
+    #[zbus(name = "Inhibit")]
+    fn inhibit(
+        &self,
+        window: &str,
+        flags: u32,
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+    ) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+    #[zbus(name = "CreateMonitor")]
+    fn create_monitor(
+        &self,
+        window: &str,
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+    ) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+    #[zbus(name = "QueryEndResponse")]
+    fn query_end_response(
+        &self,
+        session_handle: &zbus::zvariant::ObjectPath<'_>,
+    ) -> zbus::Result<()>;
+    #[zbus(signal, name = "StateChanged")]
+    fn state_changed(
+        &self,
+        session_handle: zbus::zvariant::OwnedObjectPath,
+        state: std::collections::HashMap<String, zbus::zvariant::OwnedValue>,
+    ) -> zbus::Result<()>;
+    #[zbus(property, name = "version")]
+    fn version(&self) -> zbus::Result<u32>;
+}
+
+/// Raw proxy of [`org.freedesktop.portal.Trash`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Trash.html).
+#[zbus::proxy(
+    interface = "org.freedesktop.portal.Trash",
+    default_service = "org.freedesktop.portal.Desktop",
+    default_path = "/org/freedesktop/portal/desktop",
+    gen_blocking = false
+)]
+pub trait Trash {
+    #[zbus(name = "TrashFile")]
+    fn trash_file(&self, fd: zbus::zvariant::Fd<'_>) -> zbus::Result<u32>;
+    #[zbus(property, name = "version")]
+    fn version(&self) -> zbus::Result<u32>;
+}
+
+/// Raw proxy of [`org.freedesktop.portal.FileChooser`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.FileChooser.html).
+#[zbus::proxy(
+    interface = "org.freedesktop.portal.FileChooser",
+    default_service = "org.freedesktop.portal.Desktop",
+    default_path = "/org/freedesktop/portal/desktop",
+    gen_blocking = false
+)]
+pub trait FileChooser {
+    #[zbus(name = "OpenFile")]
+    fn open_file(
+        &self,
+        parent_window: &str,
+        title: &str,
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+    ) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+    #[zbus(name = "SaveFile")]
+    fn save_file(
+        &self,
+        parent_window: &str,
+        title: &str,
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+    ) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+    #[zbus(name = "SaveFiles")]
+    fn save_files(
+        &self,
+        parent_window: &str,
+        title: &str,
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+    ) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+    #[zbus(property, name = "version")]
+    fn version(&self) -> zbus::Result<u32>;
+}
@@ -0,0 +1,113 @@
+// This file was generated from the interfaces XML files by
+// `cargo run --manifest-path xtask/Cargo.toml`, do not edit it.
+
+/// Raw proxy of [`org.freedesktop.portal.FileTransfer`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.FileTransfer.html).
+#[zbus::proxy(
+    interface = "org.freedesktop.portal.FileTransfer",
+    default_service = "org.freedesktop.portal.Documents",
+    default_path = "/org/freedesktop/portal/documents",
+    gen_blocking = false
+)]
+pub trait FileTransfer {
+    #[zbus(name = "StartTransfer")]
+    fn start_transfer(
+        &self,
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+    ) -> zbus::Result<String>;
+    #[zbus(name = "AddFiles")]
+    fn add_files(
+        &self,
+        key: &str,
+        fds: &[zbus::zvariant::Fd<'_>],
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+    ) -> zbus::Result<()>;
+    #[zbus(name = "RetrieveFiles")]
+    fn retrieve_files(
+        &self,
+        key: &str,
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+    ) -> zbus::Result<Vec<String>>;
+    #[zbus(name = "StopTransfer")]
+    fn stop_transfer(&self, key: &str) -> zbus::Result<()>;
+    #[zbus(signal, name = "TransferClosed")]
+    fn transfer_closed(&self, key: String) -> zbus::Result<()>;
+    #[zbus(property, name = "version")]
+    fn version(&self) -> zbus::Result<u32>;
+}
+
+/// Raw proxy of [`org.freedesktop.portal.Documents`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Documents.html).
+#[zbus::proxy(
+    interface = "org.freedesktop.portal.Documents",
+    default_service = "org.freedesktop.portal.Documents",
+    default_path = "/org/freedesktop/portal/documents",
+    gen_blocking = false
+)]
+pub trait Documents {
+    #[zbus(name = "GetMountPoint")]
+    fn get_mount_point(&self) -> zbus::Result<Vec<u8>>;
+    #[zbus(name = "Add")]
+    fn add(
+        &self,
+        o_path_fd: zbus::zvariant::Fd<'_>,
+        reuse_existing: bool,
+        persistent: bool,
+    ) -> zbus::Result<String>;
+    #[zbus(name = "AddNamed")]
+    fn add_named(
+        &self,
+        o_path_parent_fd: zbus::zvariant::Fd<'_>,
+        filename: &[u8],
+        reuse_existing: bool,
+        persistent: bool,
+    ) -> zbus::Result<String>;
+    #[zbus(name = "AddFull")]
+    fn add_full(
+        &self,
+        o_path_fds: &[zbus::zvariant::Fd<'_>],
+        flags: u32,
+        app_id: &str,
+        permissions: &[&str],
+    ) -> zbus::Result<(
+        Vec<String>,
+        std::collections::HashMap<String, zbus::zvariant::OwnedValue>,
+    )>;
+    #[zbus(name = "AddNamedFull")]
+    fn add_named_full(
+        &self,
+        o_path_fd: zbus::zvariant::Fd<'_>,
+        filename: &[u8],
+        flags: u32,
+        app_id: &str,
+        permissions: &[&str],
+    ) -> zbus::Result<(
+        String,
+        std::collections::HashMap<String, zbus::zvariant::OwnedValue>,
+    )>;
+    #[zbus(name = "GrantPermissions")]
+    fn grant_permissions(
+        &self,
+        doc_id: &str,
+        app_id: &str,
+        permissions: &[&str],
+    ) -> zbus::Result<()>;
+    #[zbus(name = "RevokePermissions")]
+    fn revoke_permissions(
+        &self,
+        doc_id: &str,
+        app_id: &str,
+        permissions: &[&str],
+    ) -> zbus::Result<()>;
+    #[zbus(name = "Delete")]
+    fn delete(&self, doc_id: &str) -> zbus::Result<()>;
+    #[zbus(name = "Lookup")]
+    fn lookup(&self, filename: &[u8]) -> zbus::Result<String>;
+    #[zbus(name = "Info")]
+    fn info(
+        &self,
+        doc_id: &str,
+    ) -> zbus::Result<(Vec<u8>, std::collections::HashMap<String, Vec<String>>)>;
+    #[zbus(name = "List")]
+    fn list(&self, app_id: &str) -> zbus::Result<std::collections::HashMap<String, Vec<u8>>>;
+    #[zbus(property, name = "version")]
+    fn version(&self) -> zbus::Result<u32>;
+}
@@ -0,0 +1,37 @@
+// This file was generated from the interfaces XML files by
+// `cargo run --manifest-path xtask/Cargo.toml`, do not edit it.
+
+/// Raw proxy of [`org.freedesktop.portal.Flatpak`](https://docs.flatpak.org/en/latest/portal-api-reference.html#gdbus-org.freedesktop.portal.Flatpak).
+#[zbus::proxy(
+    interface = "org.freedesktop.portal.Flatpak",
+    default_service = "org.freedesktop.portal.Flatpak",
+    default_path = "/org/freedesktop/portal/Flatpak",
+    gen_blocking = false
+)]
+pub trait Flatpak {
+    #[zbus(name = "Spawn")]
+    fn spawn(
+        &self,
+        cwd_path: &[u8],
+        argv: &[&[u8]],
+        fds: std::collections::HashMap<u32, zbus::zvariant::Fd<'_>>,
+        envs: std::collections::HashMap<&str, &str>,
+        flags: u32,
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+    ) -> zbus::Result<u32>;
+    #[zbus(name = "SpawnSignal")]
+    fn spawn_signal(&self, pid: u32, signal: u32, to_process_group: bool) -> zbus::Result<()>;
+    #[zbus(name = "CreateUpdateMonitor")]
+    fn create_update_monitor(
+        &self,
+        options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+    ) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+    #[zbus(signal, name = "SpawnStarted")]
+    fn spawn_started(&self, pid: u32, relpid: u32) -> zbus::Result<()>;
+    #[zbus(signal, name = "SpawnExited")]
+    fn spawn_exited(&self, pid: u32, exit_status: u32) -> zbus::Result<()>;
+    #[zbus(property, name = "version")]
+    fn version(&self) -> zbus::Result<u32>;
+    #[zbus(property, name = "supports")]
+    fn supports(&self) -> zbus::Result<u32>;
+}
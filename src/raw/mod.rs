@@ -0,0 +1,29 @@
+//! Raw proxies of the portals, generated from the interfaces XML files shipped
+//! with the crate.
+//!
+//! They map the D-Bus methods, signals and properties one to one, without
+//! the typed options, the requests handling or the version checks of the
+//! high level API. They are meant to give early access to interfaces, or to
+//! parts of them, that are not wrapped yet.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use ashpd::raw::desktop::ProxyResolverProxy;
+//!
+//! async fn run() -> ashpd::Result<()> {
+//!     let connection = zbus::Connection::session().await?;
+//!     let proxy = ProxyResolverProxy::new(&connection).await?;
+//!     let proxies = proxy.lookup("https://www.rust-lang.org").await?;
+//!     println!("{proxies:#?}");
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
+//! The code is generated with `cargo run --manifest-path xtask/Cargo.toml`.
+#![allow(missing_docs)]
+
+pub mod desktop;
+pub mod documents;
+pub mod flatpak;
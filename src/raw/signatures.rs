@@ -0,0 +1,402 @@
+// This file was generated from the interfaces XML files by
+// `cargo run --manifest-path xtask/Cargo.toml`, do not edit it.
+
+/// The interface, the method and the signatures of the input and output arguments of the methods.
+pub(super) const METHODS: &[(&str, &str, &str, &str)] = &[
+    (
+        "org.freedesktop.portal.Account",
+        "GetUserInformation",
+        "sa{sv}",
+        "o",
+    ),
+    (
+        "org.freedesktop.portal.Background",
+        "RequestBackground",
+        "sa{sv}",
+        "o",
+    ),
+    (
+        "org.freedesktop.portal.Camera",
+        "AccessCamera",
+        "a{sv}",
+        "o",
+    ),
+    (
+        "org.freedesktop.portal.Camera",
+        "OpenPipeWireRemote",
+        "a{sv}",
+        "h",
+    ),
+    (
+        "org.freedesktop.portal.Device",
+        "AccessDevice",
+        "uasa{sv}",
+        "o",
+    ),
+    ("org.freedesktop.portal.Documents", "Add", "hbb", "s"),
+    (
+        "org.freedesktop.portal.Documents",
+        "AddFull",
+        "ahusas",
+        "asa{sv}",
+    ),
+    ("org.freedesktop.portal.Documents", "AddNamed", "haybb", "s"),
+    (
+        "org.freedesktop.portal.Documents",
+        "AddNamedFull",
+        "hayusas",
+        "sa{sv}",
+    ),
+    ("org.freedesktop.portal.Documents", "Delete", "s", ""),
+    (
+        "org.freedesktop.portal.Documents",
+        "GetMountPoint",
+        "",
+        "ay",
+    ),
+    (
+        "org.freedesktop.portal.Documents",
+        "GrantPermissions",
+        "ssas",
+        "",
+    ),
+    ("org.freedesktop.portal.Documents", "Info", "s", "aya{sas}"),
+    ("org.freedesktop.portal.Documents", "List", "s", "a{say}"),
+    ("org.freedesktop.portal.Documents", "Lookup", "ay", "s"),
+    (
+        "org.freedesktop.portal.Documents",
+        "RevokePermissions",
+        "ssas",
+        "",
+    ),
+    (
+        "org.freedesktop.portal.Email",
+        "ComposeEmail",
+        "sa{sv}",
+        "o",
+    ),
+    (
+        "org.freedesktop.portal.FileChooser",
+        "OpenFile",
+        "ssa{sv}",
+        "o",
+    ),
+    (
+        "org.freedesktop.portal.FileChooser",
+        "SaveFile",
+        "ssa{sv}",
+        "o",
+    ),
+    (
+        "org.freedesktop.portal.FileChooser",
+        "SaveFiles",
+        "ssa{sv}",
+        "o",
+    ),
+    (
+        "org.freedesktop.portal.FileTransfer",
+        "AddFiles",
+        "saha{sv}",
+        "",
+    ),
+    (
+        "org.freedesktop.portal.FileTransfer",
+        "RetrieveFiles",
+        "sa{sv}",
+        "as",
+    ),
+    (
+        "org.freedesktop.portal.FileTransfer",
+        "StartTransfer",
+        "a{sv}",
+        "s",
+    ),
+    (
+        "org.freedesktop.portal.FileTransfer",
+        "StopTransfer",
+        "s",
+        "",
+    ),
+    (
+        "org.freedesktop.portal.Flatpak",
+        "CreateUpdateMonitor",
+        "a{sv}",
+        "o",
+    ),
+    (
+        "org.freedesktop.portal.Flatpak",
+        "Spawn",
+        "ayaaya{uh}a{ss}ua{sv}",
+        "u",
+    ),
+    ("org.freedesktop.portal.Flatpak", "SpawnSignal", "uub", ""),
+    ("org.freedesktop.portal.GameMode", "QueryStatus", "i", "i"),
+    (
+        "org.freedesktop.portal.GameMode",
+        "QueryStatusByPIDFd",
+        "hh",
+        "i",
+    ),
+    (
+        "org.freedesktop.portal.GameMode",
+        "QueryStatusByPid",
+        "ii",
+        "i",
+    ),
+    ("org.freedesktop.portal.GameMode", "RegisterGame", "i", "i"),
+    (
+        "org.freedesktop.portal.GameMode",
+        "RegisterGameByPIDFd",
+        "hh",
+        "i",
+    ),
+    (
+        "org.freedesktop.portal.GameMode",
+        "RegisterGameByPid",
+        "ii",
+        "i",
+    ),
+    (
+        "org.freedesktop.portal.GameMode",
+        "UnregisterGame",
+        "i",
+        "i",
+    ),
+    (
+        "org.freedesktop.portal.GameMode",
+        "UnregisterGameByPIDFd",
+        "hh",
+        "i",
+    ),
+    (
+        "org.freedesktop.portal.GameMode",
+        "UnregisterGameByPid",
+        "ii",
+        "i",
+    ),
+    (
+        "org.freedesktop.portal.Inhibit",
+        "CreateMonitor",
+        "sa{sv}",
+        "o",
+    ),
+    ("org.freedesktop.portal.Inhibit", "Inhibit", "sua{sv}", "o"),
+    (
+        "org.freedesktop.portal.Inhibit",
+        "QueryEndResponse",
+        "o",
+        "",
+    ),
+    (
+        "org.freedesktop.portal.Location",
+        "CreateSession",
+        "a{sv}",
+        "o",
+    ),
+    ("org.freedesktop.portal.Location", "Start", "osa{sv}", "o"),
+    (
+        "org.freedesktop.portal.NetworkMonitor",
+        "CanReach",
+        "su",
+        "b",
+    ),
+    (
+        "org.freedesktop.portal.NetworkMonitor",
+        "GetAvailable",
+        "",
+        "b",
+    ),
+    (
+        "org.freedesktop.portal.NetworkMonitor",
+        "GetConnectivity",
+        "",
+        "u",
+    ),
+    (
+        "org.freedesktop.portal.NetworkMonitor",
+        "GetMetered",
+        "",
+        "b",
+    ),
+    (
+        "org.freedesktop.portal.NetworkMonitor",
+        "GetStatus",
+        "",
+        "a{sv}",
+    ),
+    (
+        "org.freedesktop.portal.Notification",
+        "AddNotification",
+        "sa{sv}",
+        "",
+    ),
+    (
+        "org.freedesktop.portal.Notification",
+        "RemoveNotification",
+        "s",
+        "",
+    ),
+    (
+        "org.freedesktop.portal.OpenURI",
+        "OpenDirectory",
+        "sha{sv}",
+        "o",
+    ),
+    ("org.freedesktop.portal.OpenURI", "OpenFile", "sha{sv}", "o"),
+    ("org.freedesktop.portal.OpenURI", "OpenURI", "ssa{sv}", "o"),
+    (
+        "org.freedesktop.portal.Print",
+        "PreparePrint",
+        "ssa{sv}a{sv}a{sv}",
+        "o",
+    ),
+    ("org.freedesktop.portal.Print", "Print", "ssha{sv}", "o"),
+    ("org.freedesktop.portal.ProxyResolver", "Lookup", "s", "as"),
+    (
+        "org.freedesktop.portal.Realtime",
+        "MakeThreadHighPriorityWithPID",
+        "tti",
+        "",
+    ),
+    (
+        "org.freedesktop.portal.Realtime",
+        "MakeThreadRealtimeWithPID",
+        "ttu",
+        "",
+    ),
+    (
+        "org.freedesktop.portal.RemoteDesktop",
+        "CreateSession",
+        "a{sv}",
+        "o",
+    ),
+    (
+        "org.freedesktop.portal.RemoteDesktop",
+        "NotifyKeyboardKeycode",
+        "oa{sv}iu",
+        "",
+    ),
+    (
+        "org.freedesktop.portal.RemoteDesktop",
+        "NotifyKeyboardKeysym",
+        "oa{sv}iu",
+        "",
+    ),
+    (
+        "org.freedesktop.portal.RemoteDesktop",
+        "NotifyPointerAxis",
+        "oa{sv}dd",
+        "",
+    ),
+    (
+        "org.freedesktop.portal.RemoteDesktop",
+        "NotifyPointerAxisDiscrete",
+        "oa{sv}ui",
+        "",
+    ),
+    (
+        "org.freedesktop.portal.RemoteDesktop",
+        "NotifyPointerButton",
+        "oa{sv}iu",
+        "",
+    ),
+    (
+        "org.freedesktop.portal.RemoteDesktop",
+        "NotifyPointerMotion",
+        "oa{sv}dd",
+        "",
+    ),
+    (
+        "org.freedesktop.portal.RemoteDesktop",
+        "NotifyPointerMotionAbsolute",
+        "oa{sv}udd",
+        "",
+    ),
+    (
+        "org.freedesktop.portal.RemoteDesktop",
+        "NotifyTouchDown",
+        "oa{sv}uudd",
+        "",
+    ),
+    (
+        "org.freedesktop.portal.RemoteDesktop",
+        "NotifyTouchMotion",
+        "oa{sv}uudd",
+        "",
+    ),
+    (
+        "org.freedesktop.portal.RemoteDesktop",
+        "NotifyTouchUp",
+        "oa{sv}u",
+        "",
+    ),
+    (
+        "org.freedesktop.portal.RemoteDesktop",
+        "SelectDevices",
+        "oa{sv}",
+        "o",
+    ),
+    (
+        "org.freedesktop.portal.RemoteDesktop",
+        "Start",
+        "osa{sv}",
+        "o",
+    ),
+    (
+        "org.freedesktop.portal.ScreenCast",
+        "CreateSession",
+        "a{sv}",
+        "o",
+    ),
+    (
+        "org.freedesktop.portal.ScreenCast",
+        "OpenPipeWireRemote",
+        "oa{sv}",
+        "h",
+    ),
+    (
+        "org.freedesktop.portal.ScreenCast",
+        "SelectSources",
+        "oa{sv}",
+        "o",
+    ),
+    ("org.freedesktop.portal.ScreenCast", "Start", "osa{sv}", "o"),
+    (
+        "org.freedesktop.portal.Screenshot",
+        "PickColor",
+        "sa{sv}",
+        "o",
+    ),
+    (
+        "org.freedesktop.portal.Screenshot",
+        "Screenshot",
+        "sa{sv}",
+        "o",
+    ),
+    (
+        "org.freedesktop.portal.Secret",
+        "RetrieveSecret",
+        "ha{sv}",
+        "o",
+    ),
+    ("org.freedesktop.portal.Settings", "Read", "ss", "v"),
+    (
+        "org.freedesktop.portal.Settings",
+        "ReadAll",
+        "as",
+        "a{sa{sv}}",
+    ),
+    ("org.freedesktop.portal.Trash", "TrashFile", "h", "u"),
+    (
+        "org.freedesktop.portal.Wallpaper",
+        "SetWallpaperFile",
+        "sha{sv}",
+        "o",
+    ),
+    (
+        "org.freedesktop.portal.Wallpaper",
+        "SetWallpaperURI",
+        "ssa{sv}",
+        "o",
+    ),
+];
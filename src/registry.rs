@@ -1,6 +1,51 @@
+use std::sync::{Mutex, OnceLock};
+
 use zbus::zvariant;
 
-use crate::{proxy::Proxy, AppID, Error};
+use crate::{
+    proxy::{Proxy, DESKTOP_DESTINATION, DESKTOP_PATH},
+    AppID, Error,
+};
+
+static DEFAULT_APP: DefaultApp = DefaultApp::new();
+
+/// The application ID set with [`set_app_id`], which can't change anymore once
+/// the connection of ashpd was set up with it.
+struct DefaultApp {
+    app_id: OnceLock<AppID>,
+    locked: Mutex<bool>,
+}
+
+impl DefaultApp {
+    const fn new() -> Self {
+        Self {
+            app_id: OnceLock::new(),
+            locked: Mutex::new(false),
+        }
+    }
+
+    fn set(&self, app_id: AppID) -> crate::Result<()> {
+        let locked = self.locked.lock().unwrap();
+        if *locked {
+            return Err(Error::InvalidArgument(
+                "The application ID has to be set before the first portal request".to_owned(),
+            ));
+        }
+        self.app_id
+            .set(app_id)
+            .map_err(|_| Error::InvalidArgument("The application ID was already set".to_owned()))
+    }
+
+    fn get(&self) -> Option<&AppID> {
+        self.app_id.get()
+    }
+
+    /// The ID, which can't be set anymore afterwards.
+    fn lock(&self) -> Option<&AppID> {
+        *self.locked.lock().unwrap() = true;
+        self.app_id.get()
+    }
+}
 
 #[derive(Debug, zvariant::SerializeDict, zvariant::Type)]
 #[zvariant(signature = "dict")]
@@ -38,6 +83,8 @@ impl<'a> std::ops::Deref for RegistryProxy<'a> {
 /// communicating over DBus with the portal as the owner of the specified
 /// application ID.
 /// For more technical details, see <https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.host.portal.Registry.html>
+///
+/// The ID can be found with [`AppID::detect`] instead of being hardcoded.
 pub async fn register_host_app(app_id: AppID) -> crate::Result<()> {
     if crate::is_sandboxed().await {
         return Ok(());
@@ -46,3 +93,115 @@ pub async fn register_host_app(app_id: AppID) -> crate::Result<()> {
     proxy.register(app_id).await?;
     Ok(())
 }
+
+/// Sets the application ID of the process.
+///
+/// The ID is registered with [`register_host_app`] on the connection created
+/// by ashpd, before its first portal request. It is also used as the default
+/// application ID by the other helpers, the activation tokens or the
+/// [`DynamicLauncherProxy::desktop_file_id`][crate::desktop::dynamic_launcher::DynamicLauncherProxy::desktop_file_id]
+/// for example.
+///
+/// It has to be called once, before any portal request. Fails if an ID was
+/// already set, or once the connection of ashpd was created.
+pub fn set_app_id(app_id: AppID) -> crate::Result<()> {
+    DEFAULT_APP.set(app_id)
+}
+
+/// The application ID set with [`set_app_id`].
+pub fn app_id() -> Option<&'static AppID> {
+    DEFAULT_APP.get()
+}
+
+/// Registers the application ID set with [`set_app_id`] on `connection`,
+/// which is about to be used by ashpd. The ID can't be changed afterwards.
+///
+/// Failures are not fatal, older frontends don't provide the registry for
+/// example.
+pub(crate) async fn register_default_app(connection: &zbus::Connection) {
+    register_app(connection, &DEFAULT_APP).await;
+}
+
+async fn register_app(connection: &zbus::Connection, default_app: &DefaultApp) {
+    let Some(app_id) = default_app.lock() else {
+        return;
+    };
+    if crate::is_sandboxed().await {
+        return;
+    }
+    let register = async {
+        let proxy: zbus::Proxy = zbus::proxy::Builder::new(connection)
+            .interface("org.freedesktop.host.portal.Registry")?
+            .path(DESKTOP_PATH)?
+            .destination(DESKTOP_DESTINATION)?
+            .cache_properties(zbus::proxy::CacheProperties::No)
+            .build()
+            .await?;
+        proxy
+            .call_method("Register", &(app_id, &RegisterOptions {}))
+            .await?;
+        Ok::<_, zbus::Error>(())
+    };
+    if let Err(_err) = register.await {
+        #[cfg(feature = "tracing")]
+        tracing::warn!(
+            "Failed to register the host application {}: {}",
+            app_id,
+            _err
+        );
+    }
+}
+
+#[cfg(all(test, feature = "test"))]
+mod tests {
+    use std::str::FromStr;
+
+    use super::{register_app, DefaultApp};
+    use crate::{
+        test::{MockPortal, MockResponse},
+        AppID, Error,
+    };
+
+    #[tokio::test]
+    async fn register_once() {
+        let _lock = crate::test::CONNECTION_LOCK.lock().await;
+        let mock = MockPortal::new().await.unwrap();
+        mock.respond(
+            "org.freedesktop.host.portal.Registry",
+            "Register",
+            MockResponse::empty(),
+        );
+        let default_app = DefaultApp::new();
+        let app_id = AppID::from_str("org.example.App").unwrap();
+        default_app.set(app_id.clone()).unwrap();
+        assert!(matches!(
+            default_app.set(app_id.clone()),
+            Err(Error::InvalidArgument(_))
+        ));
+
+        // Like the session connection, set up once by the first task using it.
+        let connection = async_lock::OnceCell::new();
+        let setup = || async {
+            register_app(mock.connection(), &default_app).await;
+            mock.connection().clone()
+        };
+        futures_util::future::join(connection.get_or_init(setup), connection.get_or_init(setup))
+            .await;
+
+        let calls = mock.calls();
+        assert_eq!(calls.len(), 1);
+        let (registered, _) = calls[0]
+            .body()
+            .deserialize::<(
+                String,
+                std::collections::HashMap<String, zbus::zvariant::OwnedValue>,
+            )>()
+            .unwrap();
+        assert_eq!(registered, app_id.as_ref());
+        // The connection uses the ID now.
+        assert!(matches!(
+            default_app.set(AppID::from_str("org.example.Other").unwrap()),
+            Err(Error::InvalidArgument(_))
+        ));
+    }
+}
@@ -0,0 +1,118 @@
+use std::path::{Path, PathBuf};
+
+/// The metadata of the running snap, as set by snapd in the environment of
+/// its applications.
+///
+/// ```rust,no_run
+/// use ashpd::SnapInfo;
+///
+/// if let Some(snap) = SnapInfo::current() {
+///     println!("Running as {} {}", snap.instance_name(), snap.revision());
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapInfo {
+    name: String,
+    instance_name: String,
+    revision: String,
+    version: Option<String>,
+    path: Option<PathBuf>,
+}
+
+impl SnapInfo {
+    /// Retrieves the metadata of the current snap, or [`None`] if the
+    /// application is not running as a snap.
+    pub fn current() -> Option<Self> {
+        Self::from_env(crate::helpers::env_vars())
+    }
+
+    pub(crate) fn from_env(env: impl IntoIterator<Item = (String, String)>) -> Option<Self> {
+        let mut name = None;
+        let mut instance_name = None;
+        let mut revision = None;
+        let mut version = None;
+        let mut path = None;
+        for (key, value) in env {
+            match key.as_str() {
+                "SNAP_NAME" => name = Some(value),
+                "SNAP_INSTANCE_NAME" => instance_name = Some(value),
+                "SNAP_REVISION" => revision = Some(value),
+                "SNAP_VERSION" => version = Some(value),
+                "SNAP" => path = Some(PathBuf::from(value)),
+                _ => (),
+            }
+        }
+        let name = name.filter(|name| !name.is_empty())?;
+        Some(Self {
+            instance_name: instance_name
+                .filter(|instance| !instance.is_empty())
+                .unwrap_or_else(|| name.clone()),
+            name,
+            revision: revision.unwrap_or_default(),
+            version,
+            path,
+        })
+    }
+
+    /// The name of the snap, e.g. `firefox`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The name of the installed instance of the snap, e.g. `firefox_beta`
+    /// when parallel installs are used. Same as [`SnapInfo::name`] otherwise.
+    pub fn instance_name(&self) -> &str {
+        &self.instance_name
+    }
+
+    /// The revision of the snap, e.g. `4173` or `x1` for local installs.
+    pub fn revision(&self) -> &str {
+        &self.revision
+    }
+
+    /// The version of the snap, as set by the publisher.
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    /// The directory the snap is mounted at.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SnapInfo;
+
+    fn env(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+        vars.iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn snap_info_from_env() {
+        assert!(SnapInfo::from_env(env(&[("HOME", "/home/user")])).is_none());
+
+        let info = SnapInfo::from_env(env(&[
+            ("SNAP", "/snap/portal-test/x1"),
+            ("SNAP_NAME", "portal-test"),
+            ("SNAP_INSTANCE_NAME", "portal-test_beta"),
+            ("SNAP_REVISION", "x1"),
+            ("SNAP_VERSION", "1.0"),
+        ]))
+        .unwrap();
+        assert_eq!(info.name(), "portal-test");
+        assert_eq!(info.instance_name(), "portal-test_beta");
+        assert_eq!(info.revision(), "x1");
+        assert_eq!(info.version(), Some("1.0"));
+        assert_eq!(
+            info.path(),
+            Some(std::path::Path::new("/snap/portal-test/x1"))
+        );
+
+        let info = SnapInfo::from_env(env(&[("SNAP_NAME", "portal-test")])).unwrap();
+        assert_eq!(info.instance_name(), "portal-test");
+    }
+}
@@ -0,0 +1,597 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use futures_util::StreamExt;
+use zbus::{
+    message::Type as MessageType,
+    zvariant::{DynamicType, ObjectPath, OwnedValue, Structure, Value},
+    Message,
+};
+
+use super::{peer_connections, reply_bus_call, sleep, BUS_INTERFACE};
+use crate::{proxy, Error};
+
+/// The unique name given to the client side of the private connection, used
+/// to compute the object path of requests.
+const CLIENT_UNIQUE_NAME: &str = ":1.0";
+const REQUEST_INTERFACE: &str = "org.freedesktop.portal.Request";
+const SESSION_INTERFACE: &str = "org.freedesktop.portal.Session";
+const PROPERTIES_INTERFACE: &str = "org.freedesktop.DBus.Properties";
+
+#[derive(Debug, Clone)]
+enum Reply {
+    Empty,
+    Body(Arc<Structure<'static>>),
+    Error {
+        name: String,
+        message: String,
+    },
+    Response {
+        status: u32,
+        results: HashMap<String, OwnedValue>,
+    },
+}
+
+/// A canned response of a [`MockPortal`] method.
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    reply: Reply,
+    delay: Option<Duration>,
+}
+
+impl MockResponse {
+    /// Reply to the method call with `body`.
+    ///
+    /// Used for methods that do not go through a
+    /// [`Request`](crate::desktop::Request), such as
+    /// `org.freedesktop.portal.Settings.ReadOne`.
+    pub fn reply(body: impl Into<Structure<'static>>) -> Self {
+        Self {
+            reply: Reply::Body(Arc::new(body.into())),
+            delay: None,
+        }
+    }
+
+    /// Reply to the method call without a body, like
+    /// `org.freedesktop.portal.Notification.AddNotification` does.
+    pub fn empty() -> Self {
+        Self {
+            reply: Reply::Empty,
+            delay: None,
+        }
+    }
+
+    /// Fail the method call with the D-Bus error `name`.
+    pub fn error(name: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            reply: Reply::Error {
+                name: name.into(),
+                message: message.into(),
+            },
+            delay: None,
+        }
+    }
+
+    /// Successfully end the request, with the given results.
+    ///
+    /// The `session_handle` of the requests creating a session is added to
+    /// the results unless it is set.
+    pub fn success<'a>(results: impl IntoIterator<Item = (&'a str, Value<'a>)>) -> Self {
+        Self::response(0, results)
+    }
+
+    /// End the request as if the user cancelled the interaction.
+    pub fn cancelled() -> Self {
+        Self::response(1, [])
+    }
+
+    /// End the request as if the interaction was ended in some other way.
+    pub fn other() -> Self {
+        Self::response(2, [])
+    }
+
+    fn response<'a>(status: u32, results: impl IntoIterator<Item = (&'a str, Value<'a>)>) -> Self {
+        let results = results
+            .into_iter()
+            .map(|(key, value)| {
+                let value = value
+                    .try_to_owned()
+                    .expect("Results can't contain file descriptors");
+                (key.to_owned(), value)
+            })
+            .collect();
+        Self {
+            reply: Reply::Response { status, results },
+            delay: None,
+        }
+    }
+
+    /// Wait for `delay` before replying, or before emitting the `Response`
+    /// signal of the request.
+    #[must_use]
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    versions: HashMap<String, u32>,
+    properties: HashMap<(String, String), OwnedValue>,
+    responses: HashMap<(String, String), VecDeque<MockResponse>>,
+    calls: Vec<Message>,
+}
+
+impl State {
+    fn next_response(&mut self, interface: &str, method: &str) -> Option<MockResponse> {
+        let queue = self
+            .responses
+            .get_mut(&(interface.to_owned(), method.to_owned()))?;
+        // The last response is kept to answer all the following calls.
+        if queue.len() > 1 {
+            queue.pop_front()
+        } else {
+            queue.front().cloned()
+        }
+    }
+}
+
+/// A fake portal implementation to test portal interactions of an
+/// application.
+///
+/// The mock serves the interfaces it was given responses for on a private
+/// peer-to-peer connection, which the portal wrappers of this crate use
+/// instead of the session bus while the mock is alive. As that connection is
+/// shared by the whole process, tests using a mock should not run
+/// concurrently.
+///
+/// Besides answering method calls, the mock can emit the signals of the
+/// portals with [`MockPortal::emit_signal`].
+///
+/// ```rust,no_run
+/// use ashpd::{
+///     desktop::screenshot::Screenshot,
+///     test::{MockPortal, MockResponse},
+///     zvariant::Value,
+/// };
+///
+/// async fn run() -> ashpd::Result<()> {
+///     let mock = MockPortal::new().await?;
+///     mock.set_version("org.freedesktop.portal.Screenshot", 2);
+///     mock.respond(
+///         "org.freedesktop.portal.Screenshot",
+///         "Screenshot",
+///         MockResponse::success([("uri", Value::from("file:///tmp/screenshot.png"))]),
+///     );
+///
+///     let response = Screenshot::request().send().await?.response()?;
+///     assert_eq!(response.uri().as_str(), "file:///tmp/screenshot.png");
+///     assert_eq!(mock.calls().len(), 1);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct MockPortal {
+    connection: zbus::Connection,
+    server: zbus::Connection,
+    state: Arc<Mutex<State>>,
+    _task: zbus::Task<()>,
+}
+
+impl MockPortal {
+    /// Start a new mock and use it for all the following portal calls.
+    pub async fn new() -> Result<Self, Error> {
+        let (server, client) = peer_connections(CLIENT_UNIQUE_NAME).await?;
+
+        let state = Arc::new(Mutex::new(State::default()));
+        // Listen before the client can call, the messages received without a
+        // stream are dropped.
+        let stream = zbus::MessageStream::from(&server);
+        let task = server.executor().spawn(
+            serve(server.clone(), stream, Arc::clone(&state)),
+            "ashpd mock portal",
+        );
+        proxy::set_connection_override(Some(client.clone()));
+        Ok(Self {
+            connection: client,
+            server,
+            state,
+            _task: task,
+        })
+    }
+
+    /// Export `interface` with the given `version`.
+    ///
+    /// Interfaces with responses are exported with the version 1 by default.
+    pub fn set_version(&self, interface: &str, version: u32) {
+        self.state
+            .lock()
+            .unwrap()
+            .versions
+            .insert(interface.to_owned(), version);
+    }
+
+    /// Set the value of the property `name` of `interface`.
+    pub fn set_property<'a>(&self, interface: &str, name: &str, value: impl Into<Value<'a>>) {
+        let value = value
+            .into()
+            .try_to_owned()
+            .expect("Properties can't contain file descriptors");
+        let mut state = self.state.lock().unwrap();
+        state.versions.entry(interface.to_owned()).or_insert(1);
+        state
+            .properties
+            .insert((interface.to_owned(), name.to_owned()), value);
+    }
+
+    /// Queue `response` as the answer to the next call of `method` of
+    /// `interface`.
+    ///
+    /// Responses are used in the order they were queued, the last one
+    /// answering all the following calls.
+    pub fn respond(&self, interface: &str, method: &str, response: MockResponse) {
+        let mut state = self.state.lock().unwrap();
+        state.versions.entry(interface.to_owned()).or_insert(1);
+        state
+            .responses
+            .entry((interface.to_owned(), method.to_owned()))
+            .or_default()
+            .push_back(response);
+    }
+
+    /// Emit the signal `name` of `interface` on the object at `path`, like
+    /// `org.freedesktop.portal.Settings.SettingChanged` on
+    /// `/org/freedesktop/portal/desktop`.
+    pub async fn emit_signal<B>(
+        &self,
+        path: &str,
+        interface: &str,
+        name: &str,
+        body: &B,
+    ) -> Result<(), Error>
+    where
+        B: serde::Serialize + DynamicType,
+    {
+        let path = ObjectPath::try_from(path)?;
+        self.server
+            .emit_signal(None::<()>, &path, interface, name, body)
+            .await?;
+        Ok(())
+    }
+
+    /// The method calls received by the mock, excluding the properties and
+    /// names lookups.
+    pub fn calls(&self) -> Vec<Message> {
+        self.state.lock().unwrap().calls.clone()
+    }
+
+    /// The client side of the private connection.
+    pub fn connection(&self) -> &zbus::Connection {
+        &self.connection
+    }
+}
+
+impl Drop for MockPortal {
+    fn drop(&mut self) {
+        proxy::clear_connection_override(&self.connection);
+    }
+}
+
+/// The string option `key` passed in the options of a request, like its
+/// `handle_token`.
+fn option(message: &Message, key: &str) -> Option<String> {
+    let body = message.body();
+    let args = body.deserialize::<Structure>().ok()?;
+    args.fields().iter().rev().find_map(|arg| {
+        let Value::Dict(options) = arg else {
+            return None;
+        };
+        options.get::<_, String>(&key).ok().flatten()
+    })
+}
+
+/// The object path of the client object named `token`, a request or a
+/// session.
+fn client_path(kind: &str, token: &str) -> zbus::Result<ObjectPath<'static>> {
+    let path = format!(
+        "/org/freedesktop/portal/desktop/{kind}/{}/{token}",
+        CLIENT_UNIQUE_NAME.trim_start_matches(':').replace('.', "_")
+    );
+    Ok(ObjectPath::try_from(path)?)
+}
+
+async fn serve(
+    connection: zbus::Connection,
+    mut stream: zbus::MessageStream,
+    state: Arc<Mutex<State>>,
+) {
+    while let Some(Ok(message)) = stream.next().await {
+        if message.message_type() != MessageType::MethodCall {
+            continue;
+        }
+        let connection = connection.clone();
+        let state = Arc::clone(&state);
+        connection
+            .executor()
+            .clone()
+            .spawn(
+                async move {
+                    // Errors mean the client went away, there is nobody to report
+                    // them to.
+                    let _ = handle_call(&connection, &state, &message).await;
+                },
+                "ashpd mock portal call",
+            )
+            .detach();
+    }
+}
+
+async fn handle_call(
+    connection: &zbus::Connection,
+    state: &Mutex<State>,
+    message: &Message,
+) -> zbus::Result<()> {
+    let header = message.header();
+    let interface = header
+        .interface()
+        .map(|i| i.to_string())
+        .unwrap_or_default();
+    let member = header.member().map(|m| m.to_string()).unwrap_or_default();
+
+    if interface == BUS_INTERFACE {
+        return reply_bus_call(connection, message).await;
+    }
+    if interface == PROPERTIES_INTERFACE {
+        return handle_properties(connection, state, message, &member).await;
+    }
+    if (interface == REQUEST_INTERFACE || interface == SESSION_INTERFACE) && member == "Close" {
+        state.lock().unwrap().calls.push(message.clone());
+        return connection.reply(&header, &()).await;
+    }
+
+    let response = {
+        let mut state = state.lock().unwrap();
+        state.calls.push(message.clone());
+        state.next_response(&interface, &member)
+    };
+    let Some(response) = response else {
+        return connection
+            .reply_error(
+                &header,
+                "org.freedesktop.DBus.Error.UnknownMethod",
+                &format!("No mocked response for {interface}.{member}"),
+            )
+            .await;
+    };
+
+    match response.reply {
+        Reply::Empty => {
+            if let Some(delay) = response.delay {
+                sleep(delay).await;
+            }
+            connection.reply(&header, &()).await
+        }
+        Reply::Body(body) => {
+            if let Some(delay) = response.delay {
+                sleep(delay).await;
+            }
+            connection.reply(&header, &*body).await
+        }
+        Reply::Error { name, message } => {
+            if let Some(delay) = response.delay {
+                sleep(delay).await;
+            }
+            connection
+                .reply_error(&header, name.as_str(), &message)
+                .await
+        }
+        Reply::Response {
+            status,
+            mut results,
+        } => {
+            let token = option(message, "handle_token").unwrap_or_default();
+            let path = client_path("request", &token)?;
+            // The sessions are created with the handle the client picked.
+            if let Some(token) = option(message, "session_handle_token") {
+                let session = client_path("session", &token)?;
+                results
+                    .entry("session_handle".to_owned())
+                    .or_insert_with(|| OwnedValue::from(session));
+            }
+            connection.reply(&header, &path).await?;
+            if let Some(delay) = response.delay {
+                sleep(delay).await;
+            }
+            connection
+                .emit_signal(
+                    None::<()>,
+                    &path,
+                    REQUEST_INTERFACE,
+                    "Response",
+                    &(status, results),
+                )
+                .await
+        }
+    }
+}
+
+async fn handle_properties(
+    connection: &zbus::Connection,
+    state: &Mutex<State>,
+    message: &Message,
+    member: &str,
+) -> zbus::Result<()> {
+    let header = message.header();
+    let body = message.body();
+    let (interface, name) = match member {
+        "Get" => body.deserialize::<(String, String)>()?,
+        "GetAll" => (body.deserialize::<(String,)>()?.0, String::new()),
+        _ => {
+            return connection
+                .reply_error(
+                    &header,
+                    "org.freedesktop.DBus.Error.NotSupported",
+                    &"The mock portal properties are read-only",
+                )
+                .await
+        }
+    };
+
+    let properties = {
+        let state = state.lock().unwrap();
+        state.versions.get(&interface).map(|version| {
+            let mut properties = state
+                .properties
+                .iter()
+                .filter(|((i, _), _)| *i == interface)
+                .map(|((_, name), value)| (name.clone(), value.try_clone().unwrap()))
+                .collect::<HashMap<_, _>>();
+            properties.insert("version".to_owned(), OwnedValue::from(*version));
+            properties
+        })
+    };
+    let Some(mut properties) = properties else {
+        if interface == REQUEST_INTERFACE || interface == SESSION_INTERFACE {
+            return connection
+                .reply(&header, &HashMap::<String, OwnedValue>::new())
+                .await;
+        }
+        return connection
+            .reply_error(
+                &header,
+                "org.freedesktop.DBus.Error.InvalidArgs",
+                &format!("No such interface “{interface}”"),
+            )
+            .await;
+    };
+
+    if member == "GetAll" {
+        return connection.reply(&header, &properties).await;
+    }
+    match properties.remove(&name) {
+        Some(value) => connection.reply(&header, &value).await,
+        None => {
+            connection
+                .reply_error(
+                    &header,
+                    "org.freedesktop.DBus.Error.UnknownProperty",
+                    &format!("No such property “{name}”"),
+                )
+                .await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use zbus::zvariant::Value;
+
+    use super::{MockPortal, MockResponse};
+    use crate::{
+        desktop::{screenshot::Screenshot, settings::Settings, ResponseError},
+        Error, SignalEvent,
+    };
+
+    #[tokio::test]
+    async fn mock_portal() {
+        let _lock = super::super::CONNECTION_LOCK.lock().await;
+        let mock = MockPortal::new().await.unwrap();
+
+        // Interfaces without responses are not exported.
+        assert!(matches!(
+            Settings::new().await,
+            Err(Error::PortalNotFound(_))
+        ));
+
+        mock.set_version("org.freedesktop.portal.Settings", 2);
+        mock.respond(
+            "org.freedesktop.portal.Settings",
+            "Read",
+            MockResponse::reply((Value::new(Value::from(1u32)),)),
+        );
+        let settings = Settings::new().await.unwrap();
+        let value = settings
+            .read::<u32>("org.freedesktop.appearance", "color-scheme")
+            .await
+            .unwrap();
+        assert_eq!(value, 1);
+
+        mock.respond(
+            "org.freedesktop.portal.Screenshot",
+            "Screenshot",
+            MockResponse::success([("uri", Value::from("file:///tmp/screenshot.png"))])
+                .delay(Duration::from_millis(10)),
+        );
+        mock.respond(
+            "org.freedesktop.portal.Screenshot",
+            "Screenshot",
+            MockResponse::cancelled(),
+        );
+        let response = Screenshot::request()
+            .send()
+            .await
+            .unwrap()
+            .response()
+            .unwrap();
+        assert_eq!(response.uri().as_str(), "file:///tmp/screenshot.png");
+        let response = Screenshot::request().send().await.unwrap().response();
+        assert!(matches!(
+            response,
+            Err(Error::Response(ResponseError::Cancelled))
+        ));
+
+        let calls = mock.calls();
+        assert_eq!(calls.len(), 3);
+        assert_eq!(calls[2].header().member().unwrap().as_str(), "Screenshot");
+    }
+
+    #[tokio::test]
+    async fn emit_signal() {
+        use futures_util::StreamExt;
+
+        let _lock = super::super::CONNECTION_LOCK.lock().await;
+        let mock = MockPortal::new().await.unwrap();
+        mock.set_version("org.freedesktop.portal.Settings", 2);
+        let settings = Settings::new().await.unwrap();
+        let mut changes = settings.receive_setting_changed().await.unwrap();
+
+        mock.emit_signal(
+            "/org/freedesktop/portal/desktop",
+            "org.freedesktop.portal.Settings",
+            "SettingChanged",
+            &(
+                "org.freedesktop.appearance",
+                "color-scheme",
+                Value::from(1u32),
+            ),
+        )
+        .await
+        .unwrap();
+        let setting = changes.next().await.unwrap();
+        assert_eq!(setting.key(), "color-scheme");
+
+        // Without a bus, the portal can't restart.
+        let mut changes = settings
+            .receive_setting_changed_reconnecting()
+            .await
+            .unwrap();
+        mock.emit_signal(
+            "/org/freedesktop/portal/desktop",
+            "org.freedesktop.portal.Settings",
+            "SettingChanged",
+            &("org.freedesktop.appearance", "contrast", Value::from(1u32)),
+        )
+        .await
+        .unwrap();
+        assert!(matches!(
+            changes.next().await,
+            Some(SignalEvent::Signal(setting)) if setting.key() == "contrast"
+        ));
+    }
+}
@@ -0,0 +1,85 @@
+//! Utilities to test applications and portal backends built with ASHPD.
+//!
+//! The helpers replace the session bus connection used by the portal
+//! wrappers, so the same code paths as in production are exercised against
+//! a deterministic implementation.
+
+use std::time::Duration;
+
+use zbus::{Connection, Message};
+
+use crate::Error;
+
+mod mock_portal;
+mod private_bus;
+mod recorder;
+mod trace;
+
+pub use mock_portal::{MockPortal, MockResponse};
+pub use private_bus::PrivateBus;
+pub use recorder::{Recorder, Replayer};
+pub use trace::{Trace, TraceEvent, TraceEventKind};
+
+const BUS_INTERFACE: &str = "org.freedesktop.DBus";
+
+/// Serializes the tests replacing the connection used by the portals.
+#[cfg(test)]
+pub(crate) static CONNECTION_LOCK: futures_util::lock::Mutex<()> =
+    futures_util::lock::Mutex::new(());
+
+/// Creates a private peer-to-peer connection, returning the server and the
+/// client sides.
+///
+/// Peers have no unique name outside of a bus, the client is given
+/// `unique_name` so that requests and sessions get an object path.
+async fn peer_connections(unique_name: &str) -> Result<(Connection, Connection), Error> {
+    let (server, client) = std::os::unix::net::UnixStream::pair()?;
+    let guid = zbus::Guid::generate();
+    let (server, client) = futures_util::future::try_join(
+        stream_builder(server)?.server(guid)?.p2p().build(),
+        stream_builder(client)?.p2p().build(),
+    )
+    .await?;
+    client.set_unique_name(unique_name)?;
+    Ok((server, client))
+}
+
+fn stream_builder(
+    stream: std::os::unix::net::UnixStream,
+) -> std::io::Result<zbus::connection::Builder<'static>> {
+    #[cfg(feature = "tokio")]
+    {
+        stream.set_nonblocking(true)?;
+        Ok(zbus::connection::Builder::unix_stream(
+            tokio::net::UnixStream::from_std(stream)?,
+        ))
+    }
+    #[cfg(all(feature = "async-io", not(feature = "tokio")))]
+    {
+        Ok(zbus::connection::Builder::async_io_unix_stream(stream))
+    }
+}
+
+/// Replies to the calls made to the message bus itself, such as the name
+/// owner lookups done by proxies.
+///
+/// There is no bus on a peer-to-peer connection, so no names to resolve
+/// either.
+async fn reply_bus_call(connection: &Connection, message: &Message) -> zbus::Result<()> {
+    connection
+        .reply_error(
+            &message.header(),
+            "org.freedesktop.DBus.Error.NameHasNoOwner",
+            &"Not connected to a message bus",
+        )
+        .await
+}
+
+pub(crate) async fn sleep(delay: Duration) {
+    let (sender, receiver) = futures_channel::oneshot::channel();
+    std::thread::spawn(move || {
+        std::thread::sleep(delay);
+        let _ = sender.send(());
+    });
+    let _ = receiver.await;
+}
@@ -0,0 +1,401 @@
+use std::{
+    ffi::OsStr,
+    io::{self, BufRead, BufReader},
+    os::{
+        fd::AsRawFd,
+        unix::{net::UnixListener, process::CommandExt},
+    },
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use zbus::Connection;
+
+use crate::{proxy, Error};
+
+static BUS_COUNT: AtomicU32 = AtomicU32::new(0);
+
+/// The configuration of the bus, `listen` being the address to listen on for
+/// `dbus-daemon`, `dbus-broker` is given its socket instead.
+fn config(listen: Option<&str>) -> String {
+    let listen = listen
+        .map(|address| format!("\n  <listen>{address}</listen>"))
+        .unwrap_or_default();
+    format!(
+        r#"<!DOCTYPE busconfig PUBLIC "-//freedesktop//DTD D-Bus Bus Configuration 1.0//EN"
+ "http://www.freedesktop.org/standards/dbus/1.0/busconfig.dtd">
+<busconfig>
+  <type>session</type>{listen}
+  <policy context="default">
+    <allow send_destination="*" eavesdrop="true"/>
+    <allow eavesdrop="true"/>
+    <allow own="*"/>
+  </policy>
+</busconfig>
+"#
+    )
+}
+
+/// The path of `program` if it is in the `PATH`.
+fn find_program(program: &str) -> Option<PathBuf> {
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .map(|dir| dir.join(program))
+        .find(|path| path.is_file())
+}
+
+/// An isolated session bus, started with `dbus-daemon` or `dbus-broker` for
+/// the duration of a test.
+///
+/// While the bus is alive, the portal wrappers of this crate use it instead
+/// of the user's session bus. Portal backends, or a
+/// [`MockPortal`](super::MockPortal) like service, can then be served on it,
+/// from a connection to [`PrivateBus::address`], or spawned with
+/// [`PrivateBus::command`]. The environment of the process is left
+/// untouched. The bus is stopped when dropped.
+///
+/// As the connection of the portals is shared by the whole process, tests
+/// using a private bus should not run concurrently.
+///
+/// ```rust,no_run
+/// use ashpd::{desktop::settings::Settings, test::PrivateBus};
+///
+/// async fn run() -> ashpd::Result<()> {
+///     let bus = PrivateBus::start().await?;
+///     // Serve a portal implementation on the bus.
+///     bus.connection()
+///         .request_name("org.freedesktop.portal.Desktop")
+///         .await?;
+///
+///     let settings = Settings::new().await?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct PrivateBus {
+    daemon: Child,
+    dir: PathBuf,
+    address: String,
+    connection: Connection,
+}
+
+impl PrivateBus {
+    /// Start a new bus and use it for all the following portal calls.
+    ///
+    /// `dbus-daemon` or `dbus-broker-launch` has to be in the `PATH`, the
+    /// error is of the [`io::ErrorKind::NotFound`] kind otherwise.
+    pub async fn start() -> Result<Self, Error> {
+        let dir = std::env::temp_dir().join(format!(
+            "ashpd-test-bus-{}-{}",
+            std::process::id(),
+            BUS_COUNT.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::create_dir_all(&dir)?;
+        let result = match find_program("dbus-daemon") {
+            Some(program) => start_dbus_daemon(&program, &dir).await,
+            None => match find_program("dbus-broker-launch") {
+                Some(program) => start_dbus_broker(&program, &dir),
+                None => Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    "Neither dbus-daemon nor dbus-broker-launch is installed",
+                )),
+            },
+        };
+        let (daemon, address) = match result {
+            Ok(started) => started,
+            Err(err) => {
+                let _ = std::fs::remove_dir_all(&dir);
+                return Err(err.into());
+            }
+        };
+
+        let connection = zbus::connection::Builder::address(address.as_str())?
+            .build()
+            .await?;
+        proxy::set_connection_override(Some(connection.clone()));
+        Ok(Self {
+            daemon,
+            dir,
+            address,
+            connection,
+        })
+    }
+
+    /// The address of the bus, to connect the portal implementations to.
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// A connection to the bus, to serve portal implementations for example.
+    pub fn connection(&self) -> &Connection {
+        &self.connection
+    }
+
+    /// A command running `program` with the bus as its session bus, to
+    /// spawn a portal backend for example.
+    pub fn command(&self, program: impl AsRef<OsStr>) -> Command {
+        let mut command = Command::new(program);
+        command.env("DBUS_SESSION_BUS_ADDRESS", &self.address);
+        command
+    }
+
+    /// Starts a bus for the tests, [`None`] if no bus daemon is installed and
+    /// the test has to be skipped.
+    #[cfg(test)]
+    pub(crate) async fn start_or_skip() -> Option<Self> {
+        match Self::start().await {
+            Ok(bus) => Some(bus),
+            Err(Error::IO(err)) if err.kind() == io::ErrorKind::NotFound => {
+                eprintln!("Skipping the test: {err}");
+                None
+            }
+            Err(err) => panic!("Failed to start the bus: {err}"),
+        }
+    }
+}
+
+/// Starts `dbus-daemon`, returning it and the address of the bus.
+async fn start_dbus_daemon(program: &Path, dir: &Path) -> io::Result<(Child, String)> {
+    let config_path = dir.join("session.conf");
+    let address = format!("unix:path={}/bus", dir.display());
+    std::fs::write(&config_path, config(Some(&address)))?;
+
+    let mut daemon = Command::new(program)
+        .arg(format!("--config-file={}", config_path.display()))
+        .arg("--nofork")
+        .arg("--print-address")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .spawn()?;
+    // The address is printed once the daemon is ready, it is read from
+    // another thread not to block the runtime until then.
+    let (sender, receiver) = futures_channel::oneshot::channel();
+    let stdout = daemon.stdout.take();
+    std::thread::spawn(move || {
+        let mut address = String::new();
+        if let Some(stdout) = stdout {
+            let _ = BufReader::new(stdout).read_line(&mut address);
+        }
+        let _ = sender.send(address);
+    });
+    let address = receiver.await.unwrap_or_default();
+    let address = address.trim().to_owned();
+    if address.is_empty() {
+        let _ = daemon.kill();
+        let _ = daemon.wait();
+        return Err(io::Error::other("dbus-daemon exited without an address"));
+    }
+    Ok((daemon, address))
+}
+
+/// Starts `dbus-broker-launch`, returning it and the address of the bus.
+///
+/// The launcher doesn't listen by itself, the socket is passed to it with the
+/// systemd socket activation protocol.
+fn start_dbus_broker(program: &Path, dir: &Path) -> io::Result<(Child, String)> {
+    let config_path = dir.join("session.conf");
+    std::fs::write(&config_path, config(None))?;
+    let socket_path = dir.join("bus");
+    // The connections are queued by the socket until the broker is ready.
+    let listener = UnixListener::bind(&socket_path)?;
+    let fd = listener.as_raw_fd();
+
+    let mut command = Command::new("sh");
+    command
+        // `LISTEN_PID` has to be the PID of the launcher, which is only known
+        // once forked.
+        .args(["-c", r#"export LISTEN_PID=$$ LISTEN_FDS=1; exec "$0" "$@""#])
+        .arg(program)
+        .args(["--scope", "user"])
+        .arg(format!("--config-file={}", config_path.display()))
+        .stdin(Stdio::null());
+    // SAFETY: only async-signal-safe functions are called between the fork
+    // and the exec.
+    unsafe {
+        command.pre_exec(move || {
+            // The passed sockets start at the file descriptor 3, which must
+            // be inherited.
+            let ret = if fd == 3 {
+                libc::fcntl(fd, libc::F_SETFD, 0)
+            } else {
+                libc::dup2(fd, 3)
+            };
+            if ret < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+    let daemon = command.spawn()?;
+    Ok((daemon, format!("unix:path={}", socket_path.display())))
+}
+
+impl Drop for PrivateBus {
+    fn drop(&mut self) {
+        proxy::clear_connection_override(&self.connection);
+        let _ = self.daemon.kill();
+        let _ = self.daemon.wait();
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::StreamExt;
+    use zbus::zvariant::{ObjectPath, OwnedObjectPath, Value};
+
+    use super::PrivateBus;
+    use crate::{desktop::settings::Settings, proxy::Proxy, SignalEvent};
+
+    #[tokio::test]
+    async fn private_bus() {
+        let _lock = super::super::CONNECTION_LOCK.lock().await;
+        let Some(bus) = PrivateBus::start_or_skip().await else {
+            return;
+        };
+        assert_ne!(
+            std::env::var("DBUS_SESSION_BUS_ADDRESS").ok().as_deref(),
+            Some(bus.address())
+        );
+        let output = bus
+            .command("sh")
+            .args(["-c", r#"printf %s "$DBUS_SESSION_BUS_ADDRESS""#])
+            .output()
+            .unwrap();
+        assert_eq!(output.stdout, bus.address().as_bytes());
+
+        // Nothing implements the portals on the new bus.
+        let settings = Settings::new().await.unwrap();
+        assert!(settings.color_scheme().await.is_err());
+        assert_eq!(
+            settings.connection().unique_name(),
+            bus.connection().unique_name()
+        );
+    }
+
+    #[tokio::test]
+    async fn signal_match_rules() {
+        let _lock = super::super::CONNECTION_LOCK.lock().await;
+        let Some(bus) = PrivateBus::start_or_skip().await else {
+            return;
+        };
+        // The proxies query the version of the portals, use another connection
+        // with an object server replying to them.
+        let connection = zbus::connection::Builder::address(bus.address())
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+        connection.object_server();
+        connection
+            .request_name("org.freedesktop.portal.Desktop")
+            .await
+            .unwrap();
+        let path = "/org/freedesktop/portal/desktop";
+        let session =
+            ObjectPath::from_static_str_unchecked("/org/freedesktop/portal/desktop/session/1");
+        let other_session =
+            ObjectPath::from_static_str_unchecked("/org/freedesktop/portal/desktop/session/2");
+
+        let settings = Settings::new().await.unwrap();
+        let mut namespace_changed = settings
+            .receive_namespace_changed("org.gnome.desktop.interface")
+            .await
+            .unwrap();
+        let shortcuts = Proxy::new_desktop("org.freedesktop.portal.GlobalShortcuts")
+            .await
+            .unwrap();
+        let mut activated = shortcuts
+            .signal_for_session::<(OwnedObjectPath, String)>("Activated", &session)
+            .await
+            .unwrap();
+
+        for namespace in ["org.freedesktop.appearance", "org.gnome.desktop.interface"] {
+            connection
+                .emit_signal(
+                    None::<&str>,
+                    path,
+                    "org.freedesktop.portal.Settings",
+                    "SettingChanged",
+                    &(namespace, "key", Value::from(1u32)),
+                )
+                .await
+                .unwrap();
+        }
+        for session in [&other_session, &session] {
+            connection
+                .emit_signal(
+                    None::<&str>,
+                    path,
+                    "org.freedesktop.portal.GlobalShortcuts",
+                    "Activated",
+                    &(session, "shortcut"),
+                )
+                .await
+                .unwrap();
+        }
+
+        let setting = namespace_changed.next().await.unwrap();
+        assert_eq!(setting.namespace(), "org.gnome.desktop.interface");
+        let (activated_session, _) = activated.next().await.unwrap();
+        assert_eq!(activated_session.as_ref(), session);
+    }
+
+    #[tokio::test]
+    async fn signal_reconnecting() {
+        let _lock = super::super::CONNECTION_LOCK.lock().await;
+        let Some(bus) = PrivateBus::start_or_skip().await else {
+            return;
+        };
+        let portal = || async {
+            let connection = zbus::connection::Builder::address(bus.address())
+                .unwrap()
+                .build()
+                .await
+                .unwrap();
+            connection.object_server();
+            connection
+                .request_name("org.freedesktop.portal.Desktop")
+                .await
+                .unwrap();
+            connection
+        };
+        let emit = |connection: zbus::Connection, key: &'static str| async move {
+            connection
+                .emit_signal(
+                    None::<&str>,
+                    "/org/freedesktop/portal/desktop",
+                    "org.freedesktop.portal.Settings",
+                    "SettingChanged",
+                    &("org.freedesktop.appearance", key, Value::from(1u32)),
+                )
+                .await
+                .unwrap();
+        };
+
+        let connection = portal().await;
+        let settings = Settings::new().await.unwrap();
+        let mut changes = settings
+            .receive_setting_changed_reconnecting()
+            .await
+            .unwrap();
+        emit(connection.clone(), "before").await;
+        assert!(matches!(
+            changes.next().await,
+            Some(SignalEvent::Signal(setting)) if setting.key() == "before"
+        ));
+
+        // The portal restarts.
+        connection.close().await.unwrap();
+        let connection = portal().await;
+        assert!(matches!(
+            changes.next().await,
+            Some(SignalEvent::Reconnected)
+        ));
+        emit(connection, "after").await;
+        assert!(matches!(
+            changes.next().await,
+            Some(SignalEvent::Signal(setting)) if setting.key() == "after"
+        ));
+    }
+}
@@ -0,0 +1,515 @@
+use std::sync::{Arc, Mutex};
+
+use futures_util::StreamExt;
+use serde::{Serialize, Serializer};
+use zbus::{
+    message::Type as MessageType,
+    zvariant::{DynamicType, OwnedStructure, Signature, Structure, Type},
+    Connection, MatchRule, Message, MessageStream,
+};
+
+use super::{
+    peer_connections, reply_bus_call,
+    trace::{Trace, TraceEvent, TraceEventKind},
+    BUS_INTERFACE,
+};
+use crate::{proxy, Error};
+
+/// The services whose signals are recorded.
+const PORTAL_DESTINATIONS: &[&str] = &[
+    proxy::DESKTOP_DESTINATION,
+    proxy::DOCUMENTS_DESTINATION,
+    proxy::FLATPAK_DESTINATION,
+    proxy::FLATPAK_DEVELOPMENT_DESTINATION,
+    proxy::PERMISSION_STORE_DESTINATION,
+];
+
+/// The arguments of a message, which might have none.
+#[derive(Debug)]
+struct Args(Option<Structure<'static>>);
+
+impl Args {
+    fn from_message(message: &Message) -> zbus::Result<Self> {
+        let body = message.body();
+        if body.is_empty() {
+            return Ok(Self(None));
+        }
+        let args = body.deserialize::<OwnedStructure>()?;
+        Ok(Self(Some(args.0)))
+    }
+
+    fn from_event(event: &TraceEvent) -> Result<Self, Error> {
+        Ok(Self(event.body()?.map(|body| body.0)))
+    }
+}
+
+impl Serialize for Args {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match &self.0 {
+            Some(args) => args.serialize(serializer),
+            None => ().serialize(serializer),
+        }
+    }
+}
+
+impl DynamicType for Args {
+    fn signature(&self) -> Signature {
+        match &self.0 {
+            Some(args) => args.signature().clone(),
+            None => <()>::SIGNATURE.clone(),
+        }
+    }
+}
+
+/// Records the interactions of the application with the portals.
+///
+/// While the recorder is alive, the portal wrappers of this crate go through
+/// it instead of talking to the session bus directly. The calls and the
+/// signals of the portals are forwarded, and stored in a [`Trace`] that can
+/// later be served by a [`Replayer`].
+///
+/// File descriptors are forwarded but not recorded.
+///
+/// ```rust,no_run
+/// use ashpd::{desktop::screenshot::Screenshot, test::Recorder};
+///
+/// async fn run() -> ashpd::Result<()> {
+///     let recorder = Recorder::new().await?;
+///     Screenshot::request().send().await?.response()?;
+///     recorder.trace().save("screenshot.trace")?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Recorder {
+    connection: Connection,
+    trace: Arc<Mutex<Trace>>,
+    _calls: zbus::Task<()>,
+    _signals: zbus::Task<()>,
+}
+
+impl Recorder {
+    /// Start recording the portal interactions.
+    pub async fn new() -> Result<Self, Error> {
+        let session = Connection::session().await?;
+        let unique_name = session
+            .unique_name()
+            .map(ToString::to_string)
+            .unwrap_or_default();
+        // Using the same unique name keeps the request and session object
+        // paths valid on both connections.
+        let (server, client) = peer_connections(&unique_name).await?;
+        let trace = Arc::new(Mutex::new(Trace::new(unique_name)));
+
+        let mut signals = Vec::new();
+        for destination in PORTAL_DESTINATIONS {
+            let rule = MatchRule::builder()
+                .msg_type(MessageType::Signal)
+                .sender(*destination)?
+                .build();
+            signals.push(MessageStream::for_match_rule(rule, &session, None).await?);
+        }
+        let signals = futures_util::stream::select_all(signals);
+
+        // Listen before the application can call, the messages received
+        // without a stream are dropped.
+        let stream = MessageStream::from(&server);
+        let calls = server.executor().spawn(
+            forward_calls(server.clone(), stream, session, Arc::clone(&trace)),
+            "ashpd recorder calls",
+        );
+        let signals = server.executor().spawn(
+            forward_signals(server.clone(), signals, Arc::clone(&trace)),
+            "ashpd recorder signals",
+        );
+        proxy::set_connection_override(Some(client.clone()));
+        Ok(Self {
+            connection: client,
+            trace,
+            _calls: calls,
+            _signals: signals,
+        })
+    }
+
+    /// The interactions recorded so far.
+    pub fn trace(&self) -> Trace {
+        self.trace.lock().unwrap().clone()
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        proxy::clear_connection_override(&self.connection);
+    }
+}
+
+fn record(trace: &Mutex<Trace>, kind: TraceEventKind, serial: u32, message: &Message) {
+    // Messages that can't be represented are left out of the trace, the
+    // replay reports them as missing.
+    if let Ok(event) = TraceEvent::from_message(kind, serial, message) {
+        trace.lock().unwrap().push(event);
+    }
+}
+
+async fn forward_calls(
+    server: Connection,
+    mut stream: MessageStream,
+    session: Connection,
+    trace: Arc<Mutex<Trace>>,
+) {
+    while let Some(Ok(message)) = stream.next().await {
+        if message.message_type() != MessageType::MethodCall {
+            continue;
+        }
+        let (server, session, trace) = (server.clone(), session.clone(), Arc::clone(&trace));
+        server
+            .executor()
+            .clone()
+            .spawn(
+                async move {
+                    // Errors mean one of the peers went away, there is nobody
+                    // to report them to.
+                    let _ = forward_call(&server, &session, &trace, &message).await;
+                },
+                "ashpd recorder call",
+            )
+            .detach();
+    }
+}
+
+async fn forward_call(
+    server: &Connection,
+    session: &Connection,
+    trace: &Mutex<Trace>,
+    message: &Message,
+) -> zbus::Result<()> {
+    let header = message.header();
+    if header.interface().is_some_and(|i| i == BUS_INTERFACE) {
+        return reply_bus_call(server, message).await;
+    }
+    let serial = header.primary().serial_num().get();
+    record(trace, TraceEventKind::Call, serial, message);
+
+    let (Some(path), Some(member)) = (header.path(), header.member()) else {
+        return Ok(());
+    };
+    let reply = session
+        .call_method(
+            header.destination(),
+            path,
+            header.interface(),
+            member,
+            &Args::from_message(message)?,
+        )
+        .await;
+    match reply {
+        Ok(reply) => {
+            record(trace, TraceEventKind::Reply, serial, &reply);
+            server.reply(&header, &Args::from_message(&reply)?).await
+        }
+        Err(zbus::Error::MethodError(name, _, reply)) => {
+            record(trace, TraceEventKind::Error, serial, &reply);
+            server
+                .reply_error(&header, name, &Args::from_message(&reply)?)
+                .await
+        }
+        Err(err) => {
+            server
+                .reply_error(
+                    &header,
+                    "org.freedesktop.DBus.Error.Failed",
+                    &err.to_string(),
+                )
+                .await
+        }
+    }
+}
+
+async fn forward_signals(
+    server: Connection,
+    mut signals: impl futures_util::Stream<Item = zbus::Result<Message>> + Unpin,
+    trace: Arc<Mutex<Trace>>,
+) {
+    while let Some(Ok(message)) = signals.next().await {
+        let header = message.header();
+        let (Some(path), Some(interface), Some(member)) =
+            (header.path(), header.interface(), header.member())
+        else {
+            continue;
+        };
+        let Ok(args) = Args::from_message(&message) else {
+            continue;
+        };
+        record(&trace, TraceEventKind::Signal, 0, &message);
+        let _ = server
+            .emit_signal(None::<()>, path, interface, member, &args)
+            .await;
+    }
+}
+
+#[derive(Debug)]
+struct ReplayState {
+    events: Vec<TraceEvent>,
+    replayed: Vec<bool>,
+    /// The handle tokens of the recorded calls, and the ones of the replayed
+    /// calls.
+    tokens: Vec<(String, String)>,
+}
+
+impl ReplayState {
+    /// Finds the recorded call matching `call`, returning the events to send
+    /// back.
+    fn replay(&mut self, call: &TraceEvent) -> Option<(TraceEvent, Vec<TraceEvent>)> {
+        let mut candidates = self
+            .events
+            .iter()
+            .enumerate()
+            .filter(|(i, event)| {
+                !self.replayed[*i]
+                    && event.kind() == TraceEventKind::Call
+                    && event.interface() == call.interface()
+                    && event.member() == call.member()
+            })
+            .map(|(i, _)| i);
+        let first = candidates.clone().next();
+        let mut index = candidates.find(|i| self.events[*i].body().ok() == call.body().ok());
+        // The arguments of the properties calls tell which property is read,
+        // they have to match.
+        if call.interface() != "org.freedesktop.DBus.Properties" {
+            index = index.or(first);
+        }
+        let index = index?;
+        self.replayed[index] = true;
+
+        for pair in self.events[index]
+            .handle_tokens()
+            .into_iter()
+            .zip(call.handle_tokens())
+        {
+            if !self.tokens.contains(&pair) {
+                self.tokens.push(pair);
+            }
+        }
+
+        let serial = self.events[index].serial();
+        let (reply_index, reply) = self.events.iter().enumerate().skip(index).find(|(_, e)| {
+            matches!(e.kind(), TraceEventKind::Reply | TraceEventKind::Error)
+                && e.serial() == serial
+        })?;
+        let signals = self.events[reply_index + 1..]
+            .iter()
+            .take_while(|e| e.kind() != TraceEventKind::Call)
+            .filter(|e| e.kind() == TraceEventKind::Signal)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let mut reply = reply.clone();
+        reply.replace_tokens(&self.tokens);
+        let signals = signals
+            .into_iter()
+            .map(|mut signal| {
+                signal.replace_tokens(&self.tokens);
+                signal
+            })
+            .collect();
+        Some((reply, signals))
+    }
+}
+
+/// Serves a [`Trace`] recorded with a [`Recorder`] back to the application.
+///
+/// Each method call is answered with the reply of the matching recorded call,
+/// followed by the signals that were emitted after it. Calls are matched by
+/// their interface and name, in the order they were recorded. The handle
+/// tokens generated by ASHPD differ between runs, they are translated in the
+/// replayed object paths and arguments.
+///
+/// Like with [`MockPortal`](super::MockPortal), tests using a replayer should
+/// not run concurrently.
+///
+/// ```rust,no_run
+/// use ashpd::{
+///     desktop::screenshot::Screenshot,
+///     test::{Replayer, Trace},
+/// };
+///
+/// async fn run() -> ashpd::Result<()> {
+///     let trace = Trace::load("screenshot.trace")?;
+///     let replayer = Replayer::new(trace).await?;
+///     Screenshot::request().send().await?.response()?;
+///     assert!(replayer.is_finished());
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Replayer {
+    connection: Connection,
+    state: Arc<Mutex<ReplayState>>,
+    _task: zbus::Task<()>,
+}
+
+impl Replayer {
+    /// Start serving `trace` and use it for all the following portal calls.
+    pub async fn new(trace: Trace) -> Result<Self, Error> {
+        let (server, client) = peer_connections(trace.unique_name()).await?;
+        let events = trace.events().to_vec();
+        let state = Arc::new(Mutex::new(ReplayState {
+            replayed: vec![false; events.len()],
+            events,
+            tokens: Vec::new(),
+        }));
+        let stream = MessageStream::from(&server);
+        let task = server.executor().spawn(
+            replay_calls(server.clone(), stream, Arc::clone(&state)),
+            "ashpd replayer",
+        );
+        proxy::set_connection_override(Some(client.clone()));
+        Ok(Self {
+            connection: client,
+            state,
+            _task: task,
+        })
+    }
+
+    /// Whether all the recorded calls were replayed.
+    pub fn is_finished(&self) -> bool {
+        let state = self.state.lock().unwrap();
+        state
+            .events
+            .iter()
+            .zip(&state.replayed)
+            .all(|(event, replayed)| *replayed || event.kind() != TraceEventKind::Call)
+    }
+}
+
+impl Drop for Replayer {
+    fn drop(&mut self) {
+        proxy::clear_connection_override(&self.connection);
+    }
+}
+
+async fn replay_calls(
+    server: Connection,
+    mut stream: MessageStream,
+    state: Arc<Mutex<ReplayState>>,
+) {
+    while let Some(Ok(message)) = stream.next().await {
+        if message.message_type() != MessageType::MethodCall {
+            continue;
+        }
+        // Errors mean the application went away, there is nobody to report
+        // them to.
+        let _ = replay_call(&server, &state, &message).await;
+    }
+}
+
+async fn replay_call(
+    server: &Connection,
+    state: &Mutex<ReplayState>,
+    message: &Message,
+) -> zbus::Result<()> {
+    let header = message.header();
+    if header.interface().is_some_and(|i| i == BUS_INTERFACE) {
+        return reply_bus_call(server, message).await;
+    }
+    let replay = TraceEvent::from_message(TraceEventKind::Call, 0, message)
+        .ok()
+        .and_then(|call| state.lock().unwrap().replay(&call));
+    let Some((reply, signals)) = replay else {
+        return server
+            .reply_error(
+                &header,
+                "org.freedesktop.DBus.Error.UnknownMethod",
+                &format!(
+                    "No recorded call of {}.{}",
+                    header.interface().map(|i| i.as_str()).unwrap_or_default(),
+                    header.member().map(|m| m.as_str()).unwrap_or_default(),
+                ),
+            )
+            .await;
+    };
+
+    let args = Args::from_event(&reply).map_err(|e| zbus::Error::Failure(e.to_string()))?;
+    if reply.kind() == TraceEventKind::Error {
+        server.reply_error(&header, reply.member(), &args).await?;
+    } else {
+        server.reply(&header, &args).await?;
+    }
+    for signal in signals {
+        let args = Args::from_event(&signal).map_err(|e| zbus::Error::Failure(e.to_string()))?;
+        server
+            .emit_signal(
+                None::<()>,
+                signal.path(),
+                signal.interface(),
+                signal.member(),
+                &args,
+            )
+            .await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use zbus::{
+        zvariant::{ObjectPath, Value},
+        Message,
+    };
+
+    use super::Replayer;
+    use crate::{
+        desktop::screenshot::Screenshot,
+        test::{Trace, TraceEvent, TraceEventKind},
+    };
+
+    #[tokio::test]
+    async fn replay() {
+        let _lock = super::super::CONNECTION_LOCK.lock().await;
+
+        let call = Message::method_call("/org/freedesktop/portal/desktop", "Screenshot")
+            .unwrap()
+            .interface("org.freedesktop.portal.Screenshot")
+            .unwrap()
+            .build(&(
+                "",
+                HashMap::from([("handle_token", Value::from("ashpd_0123456789"))]),
+            ))
+            .unwrap();
+        let path = "/org/freedesktop/portal/desktop/request/1_7/ashpd_0123456789";
+        let reply = Message::method_return(&call.header())
+            .unwrap()
+            .build(&(ObjectPath::try_from(path).unwrap(),))
+            .unwrap();
+        let response = Message::signal(path, "org.freedesktop.portal.Request", "Response")
+            .unwrap()
+            .build(&(
+                0u32,
+                HashMap::from([("uri", Value::from("file:///tmp/screenshot.png"))]),
+            ))
+            .unwrap();
+
+        let mut trace = Trace::new(":1.7".to_owned());
+        for (kind, message) in [
+            (TraceEventKind::Call, &call),
+            (TraceEventKind::Reply, &reply),
+            (TraceEventKind::Signal, &response),
+        ] {
+            trace.push(TraceEvent::from_message(kind, 1, message).unwrap());
+        }
+        let trace = Trace::from_bytes(&trace.to_bytes().unwrap()).unwrap();
+
+        let replayer = Replayer::new(trace).await.unwrap();
+        assert!(!replayer.is_finished());
+        let response = Screenshot::request()
+            .send()
+            .await
+            .unwrap()
+            .response()
+            .unwrap();
+        assert_eq!(response.uri().as_str(), "file:///tmp/screenshot.png");
+        assert!(replayer.is_finished());
+    }
+}
@@ -0,0 +1,275 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+use zbus::{
+    zvariant::{
+        serialized::{Context, Data},
+        OwnedStructure, Structure, Type, LE,
+    },
+    Message,
+};
+
+use crate::Error;
+
+/// The prefix of the handle tokens generated by
+/// [`HandleToken`](crate::desktop::HandleToken).
+const HANDLE_TOKEN_PREFIX: &[u8] = b"ashpd_";
+const HANDLE_TOKEN_LEN: usize = HANDLE_TOKEN_PREFIX.len() + 10;
+
+#[derive(Serialize_repr, Deserialize_repr, PartialEq, Eq, Debug, Clone, Copy, Type)]
+#[repr(u8)]
+/// The kind of a [`TraceEvent`].
+pub enum TraceEventKind {
+    /// A method call made by the application.
+    Call,
+    /// The reply to the preceding method call.
+    Reply,
+    /// The error returned for the preceding method call.
+    Error,
+    /// A signal emitted by a portal.
+    Signal,
+}
+
+/// A message exchanged between an application and the portals.
+#[derive(Serialize, Deserialize, Type, Debug, Clone, PartialEq, Eq)]
+pub struct TraceEvent {
+    kind: TraceEventKind,
+    serial: u32,
+    destination: String,
+    path: String,
+    interface: String,
+    member: String,
+    signature: String,
+    body: Vec<u8>,
+}
+
+impl TraceEvent {
+    /// Creates an event from `message`.
+    ///
+    /// `serial` is the serial number of the method call, for calls and their
+    /// replies.
+    pub(super) fn from_message(
+        kind: TraceEventKind,
+        serial: u32,
+        message: &Message,
+    ) -> Result<Self, Error> {
+        let header = message.header();
+        let member = match kind {
+            TraceEventKind::Error => header.error_name().map(|e| e.to_string()),
+            _ => header.member().map(|m| m.to_string()),
+        };
+        let body = message.body();
+        let (signature, body) = if body.is_empty() {
+            (String::new(), Vec::new())
+        } else {
+            let body = body.deserialize::<Structure>()?;
+            let data = zbus::zvariant::to_bytes(Context::new_dbus(LE, 0), &body)?;
+            (body.signature().to_string(), data.bytes().to_vec())
+        };
+        Ok(Self {
+            kind,
+            serial,
+            destination: header
+                .destination()
+                .map(|d| d.to_string())
+                .unwrap_or_default(),
+            path: header.path().map(|p| p.to_string()).unwrap_or_default(),
+            interface: header
+                .interface()
+                .map(|i| i.to_string())
+                .unwrap_or_default(),
+            member: member.unwrap_or_default(),
+            signature,
+            body,
+        })
+    }
+
+    /// The kind of event.
+    pub fn kind(&self) -> TraceEventKind {
+        self.kind
+    }
+
+    /// The serial number of the method call, for calls and their replies.
+    pub fn serial(&self) -> u32 {
+        self.serial
+    }
+
+    /// The destination of a method call.
+    pub fn destination(&self) -> &str {
+        &self.destination
+    }
+
+    /// The object path of a method call or a signal.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// The interface of a method call or a signal.
+    pub fn interface(&self) -> &str {
+        &self.interface
+    }
+
+    /// The name of the method or signal, or the name of the error.
+    pub fn member(&self) -> &str {
+        &self.member
+    }
+
+    /// The arguments of the message, [`None`] if it has none.
+    ///
+    /// File descriptors are not part of traces, the bodies carrying some
+    /// can't be decoded.
+    pub fn body(&self) -> Result<Option<OwnedStructure>, Error> {
+        if self.signature.is_empty() {
+            return Ok(None);
+        }
+        let data = Data::new(self.body.as_slice(), Context::new_dbus(LE, 0));
+        let (body, _) =
+            data.deserialize_for_dynamic_signature::<_, OwnedStructure>(self.signature.as_str())?;
+        Ok(Some(body))
+    }
+
+    /// The handle tokens generated by ASHPD found in the arguments, in order.
+    pub(super) fn handle_tokens(&self) -> Vec<String> {
+        let mut tokens = Vec::new();
+        let mut rest = self.body.as_slice();
+        while let Some(start) = rest
+            .windows(HANDLE_TOKEN_PREFIX.len())
+            .position(|w| w == HANDLE_TOKEN_PREFIX)
+        {
+            let candidate = &rest[start..];
+            if candidate.len() >= HANDLE_TOKEN_LEN
+                && candidate[HANDLE_TOKEN_PREFIX.len()..HANDLE_TOKEN_LEN]
+                    .iter()
+                    .all(u8::is_ascii_alphanumeric)
+            {
+                tokens.push(String::from_utf8_lossy(&candidate[..HANDLE_TOKEN_LEN]).into_owned());
+                rest = &candidate[HANDLE_TOKEN_LEN..];
+            } else {
+                rest = &candidate[HANDLE_TOKEN_PREFIX.len()..];
+            }
+        }
+        tokens
+    }
+
+    /// Replaces the recorded handle tokens by the ones of the replayed call.
+    ///
+    /// Tokens all have the same length, so the serialized arguments stay
+    /// valid.
+    pub(super) fn replace_tokens(&mut self, tokens: &[(String, String)]) {
+        for (recorded, replayed) in tokens {
+            if recorded.len() != replayed.len() {
+                continue;
+            }
+            self.path = self.path.replace(recorded, replayed);
+            let (recorded, replayed) = (recorded.as_bytes(), replayed.as_bytes());
+            let mut i = 0;
+            while i + recorded.len() <= self.body.len() {
+                if &self.body[i..i + recorded.len()] == recorded {
+                    self.body[i..i + recorded.len()].copy_from_slice(replayed);
+                    i += recorded.len();
+                } else {
+                    i += 1;
+                }
+            }
+        }
+    }
+}
+
+/// A serializable record of the interactions of an application with the
+/// portals, see [`Recorder`](super::Recorder) and
+/// [`Replayer`](super::Replayer).
+#[derive(Serialize, Deserialize, Type, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Trace {
+    unique_name: String,
+    events: Vec<TraceEvent>,
+}
+
+impl Trace {
+    pub(super) fn new(unique_name: String) -> Self {
+        Self {
+            unique_name,
+            events: Vec::new(),
+        }
+    }
+
+    pub(super) fn push(&mut self, event: TraceEvent) {
+        self.events.push(event);
+    }
+
+    /// The unique name the application had on the bus while recording.
+    pub fn unique_name(&self) -> &str {
+        &self.unique_name
+    }
+
+    /// The recorded events, in the order they happened.
+    pub fn events(&self) -> &[TraceEvent] {
+        &self.events
+    }
+
+    /// Serializes the trace, to store it alongside the tests.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        let data = zbus::zvariant::to_bytes(Context::new_dbus(LE, 0), self)?;
+        Ok(data.bytes().to_vec())
+    }
+
+    /// Deserializes a trace created with [`Trace::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let data = Data::new(bytes, Context::new_dbus(LE, 0));
+        let (trace, _) = data.deserialize()?;
+        Ok(trace)
+    }
+
+    /// Writes the trace to the file at `path`, replacing it if it exists.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        std::fs::write(path, self.to_bytes()?)?;
+        Ok(())
+    }
+
+    /// Reads a trace written with [`Trace::save`].
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zbus::{zvariant::Value, Message};
+
+    use super::{Trace, TraceEvent, TraceEventKind};
+
+    #[test]
+    fn trace_serialization() {
+        let message = Message::method_call("/org/freedesktop/portal/desktop", "Screenshot")
+            .unwrap()
+            .interface("org.freedesktop.portal.Screenshot")
+            .unwrap()
+            .build(&(
+                "",
+                std::collections::HashMap::from([(
+                    "handle_token",
+                    Value::from("ashpd_0123456789"),
+                )]),
+            ))
+            .unwrap();
+        let mut event = TraceEvent::from_message(TraceEventKind::Call, 1, &message).unwrap();
+        assert_eq!(event.member(), "Screenshot");
+        assert_eq!(event.handle_tokens(), vec!["ashpd_0123456789"]);
+
+        event.replace_tokens(&[("ashpd_0123456789".to_owned(), "ashpd_abcdefghij".to_owned())]);
+        assert_eq!(event.handle_tokens(), vec!["ashpd_abcdefghij"]);
+        let body = event.body().unwrap().unwrap();
+        assert_eq!(body.0.fields().len(), 2);
+
+        let mut trace = Trace::new(":1.42".to_owned());
+        trace.push(event);
+        let bytes = trace.to_bytes().unwrap();
+        assert_eq!(Trace::from_bytes(&bytes).unwrap(), trace);
+
+        let path = std::env::temp_dir().join(format!("ashpd-{}.trace", std::process::id()));
+        trace.save(&path).unwrap();
+        let loaded = Trace::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.unwrap(), trace);
+    }
+}
@@ -0,0 +1,241 @@
+use std::{fmt, ops::Deref, path::Path, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use zbus::zvariant::Type;
+
+use crate::Error;
+
+/// A URI to pass to the portals, to open with
+/// [`OpenFileRequest::send_uri`](crate::desktop::open_uri::OpenFileRequest::send_uri)
+/// or to set as wallpaper for example.
+///
+/// Unlike [`url::Url::parse`], which silently percent-encodes the characters
+/// that are not allowed in a URI, [`Uri::parse`] rejects them. The
+/// constructors take care of the encoding instead.
+///
+/// It dereferences to a [`url::Url`], so it can be passed to the functions
+/// taking one.
+///
+/// ```rust
+/// use ashpd::Uri;
+///
+/// let uri = Uri::from_file_path("/home/user/My Pictures/night.jpg").unwrap();
+/// assert_eq!(uri.as_str(), "file:///home/user/My%20Pictures/night.jpg");
+///
+/// let uri = Uri::mailto("user@example.org").unwrap();
+/// assert_eq!(uri.scheme(), "mailto");
+///
+/// assert!(Uri::parse("https://example.org/a b").is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Type)]
+#[zvariant(signature = "s")]
+pub struct Uri(url::Url);
+
+impl Uri {
+    /// Parses `uri`, which has to have a scheme and be properly
+    /// percent-encoded.
+    pub fn parse(uri: &str) -> Result<Self, Error> {
+        let invalid = |reason: &str| Error::InvalidArgument(format!("Invalid URI {uri}: {reason}"));
+        let mut bytes = uri.bytes();
+        while let Some(byte) = bytes.next() {
+            if byte == b'%' {
+                let is_hex = |byte: Option<u8>| byte.is_some_and(|b| b.is_ascii_hexdigit());
+                if !is_hex(bytes.next()) || !is_hex(bytes.next()) {
+                    return Err(invalid("malformed percent-encoding"));
+                }
+            } else if !is_uri_byte(byte) {
+                return Err(invalid("the characters have to be percent-encoded"));
+            }
+        }
+        url::Url::parse(uri)
+            .map(Self)
+            .map_err(|err| invalid(&err.to_string()))
+    }
+
+    /// Creates a `file://` URI from an absolute path.
+    ///
+    /// The paths that are not valid UTF-8 are supported, their bytes are
+    /// percent-encoded.
+    pub fn from_file_path(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        url::Url::from_file_path(path).map(Self).map_err(|_| {
+            Error::InvalidArgument(format!("{} is not an absolute path", path.display()))
+        })
+    }
+
+    /// Creates a `mailto:` URI to write to `address`.
+    ///
+    /// See [`EmailRequest::mailto_uri`](crate::desktop::email::EmailRequest::mailto_uri)
+    /// to compose a whole email.
+    pub fn mailto(address: &str) -> Result<Self, Error> {
+        if !address.contains('@') {
+            return Err(Error::InvalidArgument(format!(
+                "{address} is not an email address"
+            )));
+        }
+        Self::parse(&format!("mailto:{}", percent_encode(address, b"@")))
+    }
+
+    /// Creates an `http://` URI to `path` on `host`.
+    pub fn http(host: &str, path: &str) -> Result<Self, Error> {
+        Self::with_host("http", host, path)
+    }
+
+    /// Creates an `https://` URI to `path` on `host`.
+    pub fn https(host: &str, path: &str) -> Result<Self, Error> {
+        Self::with_host("https", host, path)
+    }
+
+    fn with_host(scheme: &str, host: &str, path: &str) -> Result<Self, Error> {
+        // Only `host[:port]` is accepted, not the user information, a path, a
+        // query or a fragment that the URI parser would split off.
+        let mut uri = Some(host)
+            .filter(|host| !host.contains(['@', '/', '\\', '?', '#']))
+            .and_then(|host| url::Url::parse(&format!("{scheme}://{host}")).ok())
+            .filter(|uri| uri.host_str().is_some_and(|h| !h.is_empty()) && uri.path() == "/")
+            .ok_or_else(|| Error::InvalidArgument(format!("{host} is not a valid host")))?;
+        // The path is percent-encoded as needed.
+        uri.set_path(path);
+        Ok(Self(uri))
+    }
+
+    /// The URI as a [`url::Url`].
+    pub fn as_url(&self) -> &url::Url {
+        &self.0
+    }
+
+    /// Converts the URI to a [`url::Url`].
+    pub fn into_url(self) -> url::Url {
+        self.0
+    }
+}
+
+impl FromStr for Uri {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl<'de> Deserialize<'de> for Uri {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let uri = String::deserialize(deserializer)?;
+        Self::parse(&uri).map_err(serde::de::Error::custom)
+    }
+}
+
+impl From<url::Url> for Uri {
+    fn from(value: url::Url) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Uri> for url::Url {
+    fn from(value: Uri) -> Self {
+        value.0
+    }
+}
+
+impl Deref for Uri {
+    type Target = url::Url;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Uri {
+    fn as_ref(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl fmt::Display for Uri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0.as_str())
+    }
+}
+
+/// The unreserved and reserved characters of RFC 3986.
+fn is_uri_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || b"-._~:/?#[]@!$&'()*+,;=".contains(&byte)
+}
+
+/// Percent-encodes all the bytes of `value` but the unreserved characters of
+/// RFC 3986 and the ones in `keep`.
+pub(crate) fn percent_encode(value: &str, keep: &[u8]) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) || keep.contains(&byte) {
+            encoded.push(char::from(byte));
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+    use super::Uri;
+
+    #[test]
+    fn parse() {
+        let uri = Uri::parse("https://example.org/a%20b?q=1#top").unwrap();
+        assert_eq!(uri.as_str(), "https://example.org/a%20b?q=1#top");
+        assert_eq!(url::Url::from(uri.clone()), *uri.as_url());
+
+        assert!(Uri::parse("https://example.org/a b").is_err());
+        assert!(Uri::parse("https://example.org/caf\u{e9}").is_err());
+        assert!(Uri::parse("https://example.org/100%").is_err());
+        assert!(Uri::parse("https://example.org/%2x").is_err());
+        assert!(Uri::parse("/no/scheme").is_err());
+        assert!(Uri::parse("1http://example.org").is_err());
+    }
+
+    #[test]
+    fn constructors() {
+        let path = OsStr::from_bytes(b"/tmp/caf\xe9 au lait.txt");
+        assert_eq!(
+            Uri::from_file_path(path).unwrap().as_str(),
+            "file:///tmp/caf%E9%20au%20lait.txt"
+        );
+        assert!(Uri::from_file_path("relative/path").is_err());
+
+        assert_eq!(
+            Uri::mailto("user+tag@example.org").unwrap().as_str(),
+            "mailto:user%2Btag@example.org"
+        );
+        assert!(Uri::mailto("not an address").is_err());
+
+        assert_eq!(
+            Uri::https("example.org", "/docs/a b").unwrap().as_str(),
+            "https://example.org/docs/a%20b"
+        );
+        assert_eq!(
+            Uri::http("localhost:8080", "").unwrap().as_str(),
+            "http://localhost:8080/"
+        );
+        assert!(Uri::https("", "/").is_err());
+        assert!(Uri::https("example.org/path", "/").is_err());
+        assert!(Uri::https("user@example.org", "/").is_err());
+        assert!(Uri::https("user:secret@example.org", "/").is_err());
+        assert!(Uri::https("example.org?q=1", "/").is_err());
+        assert!(Uri::https("example.org#top", "/").is_err());
+        assert!(Uri::https("example.org\\path", "/").is_err());
+    }
+
+    #[test]
+    fn deserialize() {
+        let uri: Uri = serde_json::from_str(r#""https://example.org/a%20b""#).unwrap();
+        assert_eq!(uri.as_str(), "https://example.org/a%20b");
+        assert!(serde_json::from_str::<Uri>(r#""https://example.org/a b""#).is_err());
+        assert!(serde_json::from_str::<Uri>(r#""/no/scheme""#).is_err());
+    }
+}
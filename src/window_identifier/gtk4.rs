@@ -11,8 +11,8 @@ use glib::translate::ToGlibPtr;
 use gtk4::{gdk, glib, prelude::*};
 #[cfg(feature = "raw_handle")]
 use raw_window_handle::{
-    DisplayHandle, RawDisplayHandle, RawWindowHandle, WaylandDisplayHandle, WaylandWindowHandle,
-    WindowHandle, XlibDisplayHandle, XlibWindowHandle,
+    DisplayHandle, HandleError, RawDisplayHandle, RawWindowHandle, WaylandDisplayHandle,
+    WaylandWindowHandle, WindowHandle, XlibDisplayHandle, XlibWindowHandle,
 };
 
 use super::WindowIdentifierType;
@@ -93,74 +93,78 @@ impl Gtk4WindowIdentifier {
         }
     }
 
+    /// The native the identifier was created from.
+    #[cfg(feature = "gtk4_fallback")]
+    pub(crate) fn native(&self) -> &gtk4::Native {
+        &self.native
+    }
+
     #[cfg(feature = "raw_handle")]
-    pub fn as_raw_window_handle(&self) -> WindowHandle<'_> {
-        unsafe {
-            let raw_handle = match self.type_ {
-                #[cfg(feature = "gtk4_wayland")]
-                WindowIdentifierType::Wayland(_) => {
-                    let surface = self.native.surface().unwrap();
-                    RawWindowHandle::Wayland(WaylandWindowHandle::new(
-                        NonNull::new(gdk4wayland::ffi::gdk_wayland_surface_get_wl_surface(
-                            surface
-                                .downcast_ref::<gdk4wayland::WaylandSurface>()
-                                .unwrap()
-                                .to_glib_none()
-                                .0,
-                        ))
-                        .expect("Identifier must be attached to a wl_surface"),
-                    ))
-                }
-                #[cfg(feature = "gtk4_x11")]
-                WindowIdentifierType::X11(xid) => RawWindowHandle::Xlib(XlibWindowHandle::new(xid)),
-            };
-            WindowHandle::borrow_raw(raw_handle)
-        }
+    pub fn as_raw_window_handle(&self) -> Result<WindowHandle<'_>, HandleError> {
+        let surface = self.native.surface().ok_or(HandleError::Unavailable)?;
+        let raw_handle = match self.type_ {
+            #[cfg(feature = "gtk4_wayland")]
+            WindowIdentifierType::Wayland(_) => {
+                let surface = surface
+                    .downcast_ref::<gdk4wayland::WaylandSurface>()
+                    .ok_or(HandleError::Unavailable)?;
+                let wl_surface = unsafe {
+                    gdk4wayland::ffi::gdk_wayland_surface_get_wl_surface(surface.to_glib_none().0)
+                };
+                let wl_surface = NonNull::new(wl_surface).ok_or(HandleError::Unavailable)?;
+                RawWindowHandle::Wayland(WaylandWindowHandle::new(wl_surface.cast()))
+            }
+            #[cfg(feature = "gtk4_x11")]
+            WindowIdentifierType::X11(xid) => RawWindowHandle::Xlib(XlibWindowHandle::new(xid)),
+            #[allow(unreachable_patterns)]
+            _ => return Err(HandleError::Unavailable),
+        };
+        // SAFETY: the surface is kept alive by the native the identifier holds.
+        Ok(unsafe { WindowHandle::borrow_raw(raw_handle) })
     }
 
     #[cfg(feature = "raw_handle")]
-    pub fn as_raw_display_handle(&self) -> DisplayHandle<'_> {
-        let surface = self.native.surface().unwrap();
-        let display = surface.display();
-        unsafe {
-            let raw_handle = match self.type_ {
-                #[cfg(feature = "gtk4_wayland")]
-                WindowIdentifierType::Wayland(_) => {
-                    RawDisplayHandle::Wayland(WaylandDisplayHandle::new(
-                        NonNull::new(gdk4wayland::ffi::gdk_wayland_display_get_wl_display(
-                            display
-                                .downcast_ref::<gdk4wayland::WaylandDisplay>()
-                                .unwrap()
-                                .to_glib_none()
-                                .0,
-                        ))
-                        .expect("Identifier must be attached to a wl_display"),
-                    ))
-                }
-                #[cfg(feature = "gtk4_x11")]
-                WindowIdentifierType::X11(_xid) => RawDisplayHandle::Xlib(XlibDisplayHandle::new(
-                    NonNull::new(gdk4x11::ffi::gdk_x11_display_get_xdisplay(
-                        display
-                            .downcast_ref::<gdk4x11::X11Display>()
-                            .unwrap()
-                            .to_glib_none()
-                            .0,
-                    )),
-                    display
-                        .downcast_ref::<gdk4x11::X11Display>()
-                        .unwrap()
-                        .screen()
-                        .screen_number(),
-                )),
-            };
-            DisplayHandle::borrow_raw(raw_handle)
-        }
+    pub fn as_raw_display_handle(&self) -> Result<DisplayHandle<'_>, HandleError> {
+        let display = self
+            .native
+            .surface()
+            .ok_or(HandleError::Unavailable)?
+            .display();
+        let raw_handle = match self.type_ {
+            #[cfg(feature = "gtk4_wayland")]
+            WindowIdentifierType::Wayland(_) => {
+                let display = display
+                    .downcast_ref::<gdk4wayland::WaylandDisplay>()
+                    .ok_or(HandleError::Unavailable)?;
+                let wl_display = unsafe {
+                    gdk4wayland::ffi::gdk_wayland_display_get_wl_display(display.to_glib_none().0)
+                };
+                let wl_display = NonNull::new(wl_display).ok_or(HandleError::Unavailable)?;
+                RawDisplayHandle::Wayland(WaylandDisplayHandle::new(wl_display.cast()))
+            }
+            #[cfg(feature = "gtk4_x11")]
+            WindowIdentifierType::X11(_) => {
+                let display = display
+                    .downcast_ref::<gdk4x11::X11Display>()
+                    .ok_or(HandleError::Unavailable)?;
+                let xdisplay =
+                    unsafe { gdk4x11::ffi::gdk_x11_display_get_xdisplay(display.to_glib_none().0) };
+                RawDisplayHandle::Xlib(XlibDisplayHandle::new(
+                    NonNull::new(xdisplay.cast()),
+                    display.screen().screen_number(),
+                ))
+            }
+            #[allow(unreachable_patterns)]
+            _ => return Err(HandleError::Unavailable),
+        };
+        // SAFETY: the display outlives the surface the identifier holds.
+        Ok(unsafe { DisplayHandle::borrow_raw(raw_handle) })
     }
 }
 
 impl std::fmt::Display for Gtk4WindowIdentifier {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(&format!("{}", self.type_))
+        std::fmt::Display::fmt(&self.type_, f)
     }
 }
 
@@ -1,11 +1,10 @@
 use std::{fmt, str::FromStr};
 
-#[cfg(all(feature = "raw_handle", feature = "gtk4"))]
+#[cfg(feature = "raw_handle")]
 use raw_window_handle::{
-    DisplayHandle, HandleError, HasDisplayHandle, HasWindowHandle, WindowHandle,
+    DisplayHandle, HandleError, HasDisplayHandle, HasWindowHandle, RawDisplayHandle,
+    RawWindowHandle, WindowHandle, XlibWindowHandle,
 };
-#[cfg(feature = "raw_handle")]
-use raw_window_handle::{RawDisplayHandle, RawWindowHandle};
 use serde::{ser::Serializer, Deserialize, Serialize};
 use zbus::zvariant::Type;
 /// Most portals interact with the user by showing dialogs.
@@ -44,6 +43,8 @@ use zbus::zvariant::Type;
 /// /// Open some portals
 /// ```
 ///
+/// Or on the event queue of the application, with a `WaylandExporter`.
+///
 /// Or using a raw `wl_surface` pointer
 ///
 /// ```text
@@ -72,6 +73,30 @@ use zbus::zvariant::Type;
 /// The constructor should return a valid identifier under both X11 and Wayland
 /// and fallback to the [`Default`] implementation otherwise.
 ///
+/// ## From an X11 connection
+///
+/// The feature `x11` must be enabled. Unlike `from_xid`, the window is
+/// checked to exist and be viewable.
+///
+/// ```rust,ignore
+/// let (connection, _) = x11rb::connect(None)?;
+/// let identifier = WindowIdentifier::from_xcb(&connection, window)?;
+///
+/// /// Open some portals
+/// ```
+///
+/// ## With winit
+///
+/// The feature `winit` must be enabled, along with `wayland` for Wayland
+/// surfaces. The identifier can be passed to the portals opened for the
+/// window, from egui or iced applications as well.
+///
+/// ```rust, ignore
+/// let identifier = WindowIdentifier::from_winit(&window).await;
+///
+/// /// Open some portals
+/// ```
+///
 /// ## Other Toolkits
 ///
 /// If you have access to `RawWindowHandle` you can convert it to a
@@ -115,10 +140,10 @@ impl std::fmt::Display for WindowIdentifier {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             #[cfg(any(feature = "gtk4_wayland", feature = "gtk4_x11"))]
-            Self::Gtk4(identifier) => f.write_str(&format!("{identifier}")),
+            Self::Gtk4(identifier) => std::fmt::Display::fmt(identifier, f),
             #[cfg(feature = "wayland")]
-            Self::Wayland(identifier) => f.write_str(&format!("{identifier}")),
-            Self::X11(identifier) => f.write_str(&format!("{identifier}")),
+            Self::Wayland(identifier) => std::fmt::Display::fmt(identifier, f),
+            Self::X11(identifier) => std::fmt::Display::fmt(identifier, f),
         }
     }
 }
@@ -175,11 +200,40 @@ impl WindowIdentifier {
         }
     }
 
+    #[cfg(feature = "winit")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "winit")))]
+    /// Create an instance of [`WindowIdentifier`] from a
+    /// [`winit::window::Window`].
+    ///
+    /// The constructor returns a valid handle under X11, and under Wayland
+    /// if the `wayland` feature is enabled too, exporting the surface of the
+    /// window.
+    pub async fn from_winit(window: &winit::window::Window) -> Option<Self> {
+        let window_handle = window.window_handle().ok()?.as_raw();
+        let display_handle = window.display_handle().ok()?.as_raw();
+        Self::from_raw_handle(&window_handle, Some(&display_handle)).await
+    }
+
     /// Create an instance of [`WindowIdentifier`] from an X11 window's XID.
     pub fn from_xid(xid: std::os::raw::c_ulong) -> Self {
         Self::X11(WindowIdentifierType::X11(xid))
     }
 
+    #[cfg(feature = "x11")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "x11")))]
+    /// Create an instance of [`WindowIdentifier`] from an X11 window, checking
+    /// on the toolkit's own connection that it exists and is viewable.
+    ///
+    /// Toolkits using Xlib or libxcb can wrap their connection with
+    /// [`XCBConnection::from_raw_xcb_connection`](https://docs.rs/x11rb/latest/x11rb/xcb_ffi/struct.XCBConnection.html#method.from_raw_xcb_connection).
+    pub fn from_xcb(
+        connection: &impl x11rb::connection::Connection,
+        window: x11rb::protocol::xproto::Window,
+    ) -> Result<Self, X11Error> {
+        x11::validate(connection, window)?;
+        Ok(Self::from_xid(window.into()))
+    }
+
     #[cfg(feature = "wayland")]
     #[cfg_attr(docsrs, doc(cfg(feature = "wayland")))]
     /// Create an instance of [`WindowIdentifier`] from a Wayland surface.
@@ -210,40 +264,45 @@ impl WindowIdentifier {
     }
 }
 
-#[cfg(all(feature = "raw_handle", feature = "gtk4"))]
+#[cfg(feature = "raw_handle")]
 impl HasDisplayHandle for WindowIdentifier {
     /// Convert a [`WindowIdentifier`] to
     /// [`RawDisplayHandle`](raw_window_handle::RawDisplayHandle`).
     ///
-    /// # Panics
-    ///
-    /// If you attempt to convert a [`WindowIdentifier`] created from a
-    /// [`RawDisplayHandle`](raw_window_handle::RawDisplayHandle`) instead of
-    /// the gtk4 constructors.
+    /// Fails with [`HandleError::Unavailable`] for the identifiers created
+    /// from an X11 window's XID, the display they belong to is not known, or
+    /// when the window of a Gtk identifier is no longer realized.
     fn display_handle(&self) -> Result<DisplayHandle<'_>, HandleError> {
         match self {
-            #[cfg(feature = "gtk4")]
-            Self::Gtk4(identifier) => Ok(identifier.as_raw_display_handle()),
-            _ => unreachable!(),
+            #[cfg(any(feature = "gtk4_wayland", feature = "gtk4_x11"))]
+            Self::Gtk4(identifier) => identifier.as_raw_display_handle(),
+            #[cfg(feature = "wayland")]
+            Self::Wayland(identifier) => identifier.as_raw_display_handle(),
+            Self::X11(_) => Err(HandleError::Unavailable),
         }
     }
 }
 
-#[cfg(all(feature = "raw_handle", feature = "gtk4"))]
+#[cfg(feature = "raw_handle")]
 impl HasWindowHandle for WindowIdentifier {
     /// Convert a [`WindowIdentifier`] to
     /// [`RawWindowHandle`](raw_window_handle::RawWindowHandle`).
     ///
-    /// # Panics
-    ///
-    /// If you attempt to convert a [`WindowIdentifier`] created from a
-    /// [`RawWindowHandle`](raw_window_handle::RawWindowHandle`) instead of
-    /// the gtk4 constructors.
+    /// The handle is the Wayland surface or the X11 window the identifier was
+    /// created from. Fails with [`HandleError::Unavailable`] otherwise, for
+    /// example when the window of a Gtk identifier is no longer realized.
     fn window_handle(&self) -> Result<WindowHandle<'_>, HandleError> {
         match self {
-            #[cfg(feature = "gtk4")]
-            Self::Gtk4(identifier) => Ok(identifier.as_raw_window_handle()),
-            _ => unreachable!(),
+            #[cfg(any(feature = "gtk4_wayland", feature = "gtk4_x11"))]
+            Self::Gtk4(identifier) => identifier.as_raw_window_handle(),
+            #[cfg(feature = "wayland")]
+            Self::Wayland(identifier) => identifier.as_raw_window_handle(),
+            Self::X11(WindowIdentifierType::X11(xid)) => {
+                let raw_handle = RawWindowHandle::Xlib(XlibWindowHandle::new(*xid));
+                // SAFETY: an XID is not a pointer, it can't dangle.
+                Ok(unsafe { WindowHandle::borrow_raw(raw_handle) })
+            }
+            Self::X11(WindowIdentifierType::Wayland(_)) => Err(HandleError::Unavailable),
         }
     }
 }
@@ -262,8 +321,8 @@ pub enum WindowIdentifierType {
 impl fmt::Display for WindowIdentifierType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::X11(xid) => f.write_str(&format!("x11:0x{xid:x}")),
-            Self::Wayland(handle) => f.write_str(&format!("wayland:{handle}")),
+            Self::X11(xid) => write!(f, "x11:0x{xid:x}"),
+            Self::Wayland(handle) => write!(f, "wayland:{handle}"),
         }
     }
 }
@@ -311,8 +370,14 @@ use crate::PortalError;
 #[cfg(feature = "wayland")]
 mod wayland;
 
+#[cfg(feature = "x11")]
+mod x11;
+
+#[cfg(feature = "x11")]
+pub use self::x11::X11Error;
+
 #[cfg(feature = "wayland")]
-pub use self::wayland::WaylandWindowIdentifier;
+pub use self::wayland::{WaylandExportData, WaylandExporter, WaylandWindowIdentifier};
 
 #[cfg(test)]
 mod tests {
@@ -338,4 +403,32 @@ mod tests {
         assert!(WindowIdentifierType::from_str("some_handle").is_err());
         assert!(WindowIdentifierType::from_str("some_type:some_handle").is_err());
     }
+
+    #[cfg(feature = "raw_handle")]
+    #[test]
+    fn raw_handles() {
+        use raw_window_handle::{
+            HandleError, HasDisplayHandle, HasWindowHandle, RawWindowHandle, XlibWindowHandle,
+        };
+
+        let x11 = WindowIdentifier::from_xid(1024);
+        assert_eq!(
+            x11.window_handle().unwrap().as_raw(),
+            RawWindowHandle::Xlib(XlibWindowHandle::new(1024))
+        );
+        assert!(matches!(
+            x11.display_handle(),
+            Err(HandleError::Unavailable)
+        ));
+
+        let wayland = WindowIdentifier::X11(WindowIdentifierType::Wayland("handle".to_owned()));
+        assert!(matches!(
+            wayland.window_handle(),
+            Err(HandleError::Unavailable)
+        ));
+        assert!(matches!(
+            wayland.display_handle(),
+            Err(HandleError::Unavailable)
+        ));
+    }
 }
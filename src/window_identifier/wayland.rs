@@ -1,9 +1,17 @@
-use std::fmt;
+#[cfg(feature = "raw_handle")]
+use std::ptr::NonNull;
+use std::{fmt, sync::Mutex};
 
+#[cfg(feature = "raw_handle")]
+use raw_window_handle::{
+    DisplayHandle, HandleError, RawDisplayHandle, RawWindowHandle, WaylandDisplayHandle,
+    WaylandWindowHandle, WindowHandle,
+};
 use wayland_backend::sys::client::Backend;
 use wayland_client::{
+    globals::GlobalList,
     protocol::{wl_registry, wl_surface::WlSurface},
-    Proxy, QueueHandle,
+    Dispatch, Proxy, QueueHandle,
 };
 use wayland_protocols::xdg::foreign::{
     zv1::client::{
@@ -16,7 +24,7 @@ use wayland_protocols::xdg::foreign::{
     },
 };
 
-use super::WindowIdentifierType;
+use super::{WindowIdentifier, WindowIdentifierType};
 
 // Supported versions.
 const ZXDG_EXPORTER_V1: u32 = 1;
@@ -26,6 +34,12 @@ const ZXDG_EXPORTER_V2: u32 = 1;
 pub struct WaylandWindowIdentifier {
     exported: Exported,
     type_: WindowIdentifierType,
+    // Keep the display and the surface alive for as long as their raw handles
+    // can be borrowed.
+    #[cfg(feature = "raw_handle")]
+    conn: wayland_client::Connection,
+    #[cfg(feature = "raw_handle")]
+    surface: WlSurface,
 }
 
 #[derive(Debug)]
@@ -84,7 +98,7 @@ impl WaylandWindowIdentifier {
 
         // Cheap clone, protocol objects are essentially smart pointers
         let surface = surface.clone();
-        std::thread::spawn(move || match wayland_export_handle(conn, &surface) {
+        std::thread::spawn(move || match wayland_export_handle(conn, surface) {
             Ok(window_handle) => sender.send(Some(window_handle)).unwrap(),
             Err(_err) => {
                 #[cfg(feature = "tracing")]
@@ -97,9 +111,28 @@ impl WaylandWindowIdentifier {
     }
 }
 
+#[cfg(feature = "raw_handle")]
+impl WaylandWindowIdentifier {
+    pub fn as_raw_window_handle(&self) -> Result<WindowHandle<'_>, HandleError> {
+        let surface = NonNull::new(self.surface.id().as_ptr()).ok_or(HandleError::Unavailable)?;
+        let raw_handle = RawWindowHandle::Wayland(WaylandWindowHandle::new(surface.cast()));
+        // SAFETY: the surface is kept alive by the identifier.
+        Ok(unsafe { WindowHandle::borrow_raw(raw_handle) })
+    }
+
+    pub fn as_raw_display_handle(&self) -> Result<DisplayHandle<'_>, HandleError> {
+        let display =
+            NonNull::new(self.conn.backend().display_ptr()).ok_or(HandleError::Unavailable)?;
+        let raw_handle = RawDisplayHandle::Wayland(WaylandDisplayHandle::new(display.cast()));
+        // SAFETY: the connection, and so the display, is kept alive by the
+        // identifier.
+        Ok(unsafe { DisplayHandle::borrow_raw(raw_handle) })
+    }
+}
+
 impl fmt::Display for WaylandWindowIdentifier {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_str(&format!("{}", self.type_))
+        fmt::Display::fmt(&self.type_, f)
     }
 }
 
@@ -220,12 +253,178 @@ impl wayland_client::Dispatch<wl_registry::WlRegistry, ()> for State {
     }
 }
 
+/// Exports Wayland surfaces with the xdg-foreign protocol on the event queue
+/// of the application, instead of a queue of its own like
+/// [`WindowIdentifier::from_wayland`] does.
+///
+/// The state of the queue has to delegate the events of the protocol to the
+/// exporter, which only resolves the identifiers once the application
+/// dispatched them.
+///
+/// ```rust,ignore
+/// use ashpd::{WaylandExportData, WaylandExporter};
+/// use wayland_client::delegate_dispatch;
+/// use wayland_protocols::xdg::foreign::{
+///     zv1::client::{zxdg_exported_v1::ZxdgExportedV1, zxdg_exporter_v1::ZxdgExporterV1},
+///     zv2::client::{zxdg_exported_v2::ZxdgExportedV2, zxdg_exporter_v2::ZxdgExporterV2},
+/// };
+///
+/// delegate_dispatch!(App: [ZxdgExporterV1: ()] => WaylandExporter);
+/// delegate_dispatch!(App: [ZxdgExporterV2: ()] => WaylandExporter);
+/// delegate_dispatch!(App: [ZxdgExportedV1: WaylandExportData] => WaylandExporter);
+/// delegate_dispatch!(App: [ZxdgExportedV2: WaylandExportData] => WaylandExporter);
+///
+/// let exporter = WaylandExporter::bind(&globals, &queue_handle).unwrap();
+/// // Keep dispatching the queue while waiting for the identifier.
+/// let identifier = exporter.export(&surface, &queue_handle).await;
+/// ```
+#[derive(Debug)]
+pub struct WaylandExporter(Exporter);
+
+/// The user data of the exported surfaces, see [`WaylandExporter`].
+#[derive(Debug)]
+pub struct WaylandExportData(Mutex<Option<futures_channel::oneshot::Sender<String>>>);
+
+impl WaylandExportData {
+    fn handle(&self, handle: String) {
+        if let Some(sender) = self.0.lock().unwrap().take() {
+            let _ = sender.send(handle);
+        }
+    }
+}
+
+impl WaylandExporter {
+    /// Binds the xdg-foreign exporter advertised in `globals`, preferring
+    /// the second version of the protocol.
+    ///
+    /// Returns `None` if the compositor supports neither version.
+    pub fn bind<D>(globals: &GlobalList, qhandle: &QueueHandle<D>) -> Option<Self>
+    where
+        D: Dispatch<ZxdgExporterV1, ()> + Dispatch<ZxdgExporterV2, ()> + 'static,
+    {
+        if let Ok(exporter) = globals.bind(qhandle, 1..=ZXDG_EXPORTER_V2, ()) {
+            return Some(Self(Exporter::V2(exporter)));
+        }
+        let exporter = globals.bind(qhandle, 1..=ZXDG_EXPORTER_V1, ()).ok()?;
+        Some(Self(Exporter::V1(exporter)))
+    }
+
+    /// Exports `surface`, returning its identifier once the application
+    /// dispatched the handle sent by the compositor.
+    ///
+    /// The surface is unexported when the identifier is dropped.
+    pub async fn export<D>(
+        &self,
+        surface: &WlSurface,
+        qhandle: &QueueHandle<D>,
+    ) -> Option<WindowIdentifier>
+    where
+        D: Dispatch<ZxdgExportedV1, WaylandExportData>
+            + Dispatch<ZxdgExportedV2, WaylandExportData>
+            + 'static,
+    {
+        let backend = surface.backend().upgrade()?;
+        let conn = wayland_client::Connection::from_backend(backend);
+        let (sender, receiver) = futures_channel::oneshot::channel();
+        let data = WaylandExportData(Mutex::new(Some(sender)));
+        let exported = match &self.0 {
+            Exporter::V2(exporter) => {
+                Exported::V2(exporter.export_toplevel(surface, qhandle, data))
+            }
+            Exporter::V1(exporter) => Exported::V1(exporter.export(surface, qhandle, data)),
+        };
+        if conn.flush().is_err() {
+            exported.destroy();
+            return None;
+        }
+        // Dropped without a handle if the application destroyed its queue.
+        let Ok(handle) = receiver.await else {
+            exported.destroy();
+            return None;
+        };
+        Some(WindowIdentifier::Wayland(WaylandWindowIdentifier {
+            exported,
+            type_: WindowIdentifierType::Wayland(handle),
+            #[cfg(feature = "raw_handle")]
+            conn,
+            #[cfg(feature = "raw_handle")]
+            surface: surface.clone(),
+        }))
+    }
+}
+
+impl<D> Dispatch<ZxdgExportedV1, WaylandExportData, D> for WaylandExporter
+where
+    D: Dispatch<ZxdgExportedV1, WaylandExportData>,
+{
+    fn event(
+        _state: &mut D,
+        _proxy: &ZxdgExportedV1,
+        event: <ZxdgExportedV1 as Proxy>::Event,
+        data: &WaylandExportData,
+        _connhandle: &wayland_client::Connection,
+        _qhandle: &QueueHandle<D>,
+    ) {
+        if let zxdg_exported_v1::Event::Handle { handle } = event {
+            data.handle(handle);
+        }
+    }
+}
+
+impl<D> Dispatch<ZxdgExportedV2, WaylandExportData, D> for WaylandExporter
+where
+    D: Dispatch<ZxdgExportedV2, WaylandExportData>,
+{
+    fn event(
+        _state: &mut D,
+        _proxy: &ZxdgExportedV2,
+        event: <ZxdgExportedV2 as Proxy>::Event,
+        data: &WaylandExportData,
+        _connhandle: &wayland_client::Connection,
+        _qhandle: &QueueHandle<D>,
+    ) {
+        if let zxdg_exported_v2::Event::Handle { handle } = event {
+            data.handle(handle);
+        }
+    }
+}
+
+impl<D> Dispatch<ZxdgExporterV1, (), D> for WaylandExporter
+where
+    D: Dispatch<ZxdgExporterV1, ()>,
+{
+    fn event(
+        _state: &mut D,
+        _proxy: &ZxdgExporterV1,
+        _event: <ZxdgExporterV1 as Proxy>::Event,
+        _data: &(),
+        _connhandle: &wayland_client::Connection,
+        _qhandle: &QueueHandle<D>,
+    ) {
+    }
+}
+
+impl<D> Dispatch<ZxdgExporterV2, (), D> for WaylandExporter
+where
+    D: Dispatch<ZxdgExporterV2, ()>,
+{
+    fn event(
+        _state: &mut D,
+        _proxy: &ZxdgExporterV2,
+        _event: <ZxdgExporterV2 as Proxy>::Event,
+        _data: &(),
+        _connhandle: &wayland_client::Connection,
+        _qhandle: &QueueHandle<D>,
+    ) {
+    }
+}
+
 /// A helper to export a wayland handle from a surface and a connection
 ///
 /// Needed for converting a RawWindowHandle to a WindowIdentifier.
 fn wayland_export_handle(
     conn: wayland_client::Connection,
-    surface: &WlSurface,
+    surface: WlSurface,
 ) -> Result<WaylandWindowIdentifier, Box<dyn std::error::Error>> {
     let display = conn.display();
     let mut event_queue = conn.new_event_queue();
@@ -236,14 +435,14 @@ fn wayland_export_handle(
 
     let exported = match state.exporter.take() {
         Some(Exporter::V2(exporter)) => {
-            let exp = exporter.export_toplevel(surface, &qhandle, ());
+            let exp = exporter.export_toplevel(&surface, &qhandle, ());
             event_queue.roundtrip(&mut state)?;
             exporter.destroy();
 
             Some(Exported::V2(exp))
         }
         Some(Exporter::V1(exporter)) => {
-            let exp = exporter.export(surface, &qhandle, ());
+            let exp = exporter.export(&surface, &qhandle, ());
             event_queue.roundtrip(&mut state)?;
             exporter.destroy();
 
@@ -262,6 +461,10 @@ fn wayland_export_handle(
         Ok(WaylandWindowIdentifier {
             exported,
             type_: WindowIdentifierType::Wayland(state.handle),
+            #[cfg(feature = "raw_handle")]
+            conn,
+            #[cfg(feature = "raw_handle")]
+            surface,
         })
     } else {
         Err(Box::new(crate::Error::NoResponse))
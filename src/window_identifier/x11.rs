@@ -0,0 +1,98 @@
+use std::fmt;
+
+use x11rb::{
+    connection::Connection,
+    errors::{ConnectionError, ReplyError},
+    protocol::{
+        xproto::{ConnectionExt, MapState, Window},
+        ErrorKind,
+    },
+};
+
+#[derive(Debug)]
+/// The errors of [`WindowIdentifier::from_xcb`](super::WindowIdentifier::from_xcb).
+pub enum X11Error {
+    /// The X11 connection failed.
+    Connection(ConnectionError),
+    /// The X server replied with an error, a [`ReplyError::X11Error`].
+    Protocol(ReplyError),
+    /// The window doesn't exist.
+    NoSuchWindow(Window),
+    /// The window or one of its ancestors is not mapped, the dialogs of the
+    /// portals can't be placed on top of it.
+    NotViewable(Window),
+}
+
+impl std::error::Error for X11Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Connection(e) => Some(e),
+            Self::Protocol(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for X11Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Connection(e) => write!(f, "X11 connection: {e}"),
+            Self::Protocol(e) => write!(f, "X11 request failed: {e}"),
+            Self::NoSuchWindow(window) => write!(f, "The X11 window 0x{window:x} doesn't exist"),
+            Self::NotViewable(window) => write!(f, "The X11 window 0x{window:x} is not viewable"),
+        }
+    }
+}
+
+impl From<ConnectionError> for X11Error {
+    fn from(e: ConnectionError) -> Self {
+        Self::Connection(e)
+    }
+}
+
+/// Checks that `window` exists and is viewable on the display of
+/// `connection`.
+pub(super) fn validate(connection: &impl Connection, window: Window) -> Result<(), X11Error> {
+    let attributes = connection
+        .get_window_attributes(window)?
+        .reply()
+        .map_err(|e| match e {
+            ReplyError::ConnectionError(e) => X11Error::Connection(e),
+            ReplyError::X11Error(e) if e.error_kind == ErrorKind::Window => {
+                X11Error::NoSuchWindow(window)
+            }
+            e => X11Error::Protocol(e),
+        })?;
+    if attributes.map_state != MapState::VIEWABLE {
+        return Err(X11Error::NotViewable(window));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use x11rb::{errors::ReplyError, protocol::ErrorKind, x11_utils::X11Error as ProtocolError};
+
+    use super::X11Error;
+
+    #[test]
+    fn protocol_source() {
+        let protocol = ProtocolError {
+            error_kind: ErrorKind::Match,
+            error_code: 8,
+            sequence: 1,
+            bad_value: 0x400001,
+            minor_opcode: 0,
+            major_opcode: 3,
+            extension_name: None,
+            request_name: Some("GetWindowAttributes"),
+        };
+        let err = X11Error::Protocol(ReplyError::X11Error(protocol.clone()));
+        let source = err.source().unwrap().downcast_ref::<ReplyError>().unwrap();
+        assert!(matches!(source, ReplyError::X11Error(e) if *e == protocol));
+
+        assert!(X11Error::NoSuchWindow(0x400001).source().is_none());
+    }
+}
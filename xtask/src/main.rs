@@ -0,0 +1,324 @@
+//! Generates the `ashpd::raw` proxies and the table of method signatures from
+//! the interfaces XML files.
+//!
+//! Run `cargo run --manifest-path xtask/Cargo.toml` after updating the XML
+//! files, or with `--check` to verify the generated code is up to date.
+
+use std::{
+    fmt::Write as _,
+    fs::File,
+    io::{BufReader, Write as _},
+    path::{Path, PathBuf},
+    process::{Command, ExitCode, Stdio},
+};
+
+use zbus_xml::{ArgDirection, Interface, Node};
+
+/// An XML file and where the interfaces it describes are served.
+struct Source {
+    /// The module of `ashpd::raw` the proxies are generated in.
+    module: &'static str,
+    /// The name of the XML file, also the well-known name of the service.
+    service: &'static str,
+    path: &'static str,
+    /// The documentation URL of the interfaces, `{}` is replaced with the
+    /// interface name.
+    docs: &'static str,
+}
+
+const SOURCES: &[Source] = &[
+    Source {
+        module: "desktop",
+        service: "org.freedesktop.portal.Desktop",
+        path: "/org/freedesktop/portal/desktop",
+        docs: "https://flatpak.github.io/xdg-desktop-portal/docs/doc-{}.html",
+    },
+    Source {
+        module: "documents",
+        service: "org.freedesktop.portal.Documents",
+        path: "/org/freedesktop/portal/documents",
+        docs: "https://flatpak.github.io/xdg-desktop-portal/docs/doc-{}.html",
+    },
+    Source {
+        module: "flatpak",
+        service: "org.freedesktop.portal.Flatpak",
+        path: "/org/freedesktop/portal/Flatpak",
+        docs: "https://docs.flatpak.org/en/latest/portal-api-reference.html#gdbus-{}",
+    },
+];
+
+const HEADER: &str = "// This file was generated from the interfaces XML files by\n\
+                      // `cargo run --manifest-path xtask/Cargo.toml`, do not edit it.\n";
+
+fn main() -> ExitCode {
+    let check = std::env::args().any(|arg| arg == "--check");
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+
+    let mut outputs = Vec::new();
+    let mut methods = Vec::new();
+    for source in SOURCES {
+        let xml = root
+            .join("interfaces")
+            .join(format!("{}.xml", source.service));
+        let reader = BufReader::new(File::open(&xml).expect("Failed to open the XML file"));
+        let node = Node::from_reader(reader).expect("Failed to parse the XML file");
+
+        let mut code = String::from(HEADER);
+        for interface in node.interfaces() {
+            generate_proxy(&mut code, interface, source);
+            for method in interface.methods() {
+                let signature = |direction| {
+                    method
+                        .args()
+                        .iter()
+                        .filter(|arg| (arg.direction() == Some(ArgDirection::Out)) == direction)
+                        .map(|arg| arg.ty().to_string())
+                        .collect::<String>()
+                };
+                methods.push((
+                    interface.name().to_string(),
+                    method.name().to_string(),
+                    signature(false),
+                    signature(true),
+                ));
+            }
+        }
+        outputs.push((module_path(&root, source.module), code));
+    }
+
+    methods.sort();
+    let mut code = String::from(HEADER);
+    code.push_str(
+        "\n/// The interface, the method and the signatures of the input and output \
+         arguments of the methods.\npub(super) const METHODS: &[(&str, &str, &str, &str)] = \
+         &[\n",
+    );
+    for (interface, method, inputs, outputs) in methods {
+        writeln!(
+            code,
+            "(\"{interface}\", \"{method}\", \"{inputs}\", \"{outputs}\"),"
+        )
+        .unwrap();
+    }
+    code.push_str("];\n");
+    outputs.push((module_path(&root, "signatures"), code));
+
+    let mut outdated = false;
+    for (path, code) in outputs {
+        let code = rustfmt(&root, &code);
+        if check {
+            if std::fs::read_to_string(&path).ok().as_deref() != Some(code.as_str()) {
+                eprintln!("{} is outdated", path.display());
+                outdated = true;
+            }
+        } else {
+            std::fs::write(&path, code).expect("Failed to write the generated code");
+        }
+    }
+    if outdated {
+        eprintln!("Run `cargo run --manifest-path xtask/Cargo.toml` to update it");
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
+
+fn module_path(root: &Path, module: &str) -> PathBuf {
+    root.join("src").join("raw").join(format!("{module}.rs"))
+}
+
+fn generate_proxy(code: &mut String, interface: &Interface<'_>, source: &Source) {
+    let name = interface.name();
+    let trait_name = name.rsplit('.').next().unwrap();
+    let docs = source.docs.replace("{}", &name);
+    writeln!(code, "\n/// Raw proxy of [`{name}`]({docs}).").unwrap();
+    writeln!(
+        code,
+        "#[zbus::proxy(interface = \"{name}\", default_service = \"{}\", \
+         default_path = \"{}\", gen_blocking = false)]",
+        source.service, source.path,
+    )
+    .unwrap();
+    writeln!(code, "pub trait {trait_name} {{").unwrap();
+
+    for method in interface.methods() {
+        let member = method.name();
+        let mut inputs = String::new();
+        let mut outputs = Vec::new();
+        for (i, arg) in method.args().iter().enumerate() {
+            let ty = arg.ty().to_string();
+            if arg.direction() == Some(ArgDirection::Out) {
+                outputs.push(rust_type(&ty, false));
+            } else {
+                let arg_name = arg_name(arg.name(), i);
+                write!(inputs, ", {arg_name}: {}", rust_type(&ty, true)).unwrap();
+            }
+        }
+        writeln!(code, "#[zbus(name = \"{member}\")]").unwrap();
+        writeln!(
+            code,
+            "fn {}(&self{inputs}) -> zbus::Result<{}>;",
+            snake_case(&member),
+            tuple(outputs)
+        )
+        .unwrap();
+    }
+
+    for signal in interface.signals() {
+        let member = signal.name();
+        let mut args = String::new();
+        for (i, arg) in signal.args().iter().enumerate() {
+            let arg_name = arg_name(arg.name(), i);
+            write!(
+                args,
+                ", {arg_name}: {}",
+                rust_type(&arg.ty().to_string(), false)
+            )
+            .unwrap();
+        }
+        writeln!(code, "#[zbus(signal, name = \"{member}\")]").unwrap();
+        writeln!(
+            code,
+            "fn {}(&self{args}) -> zbus::Result<()>;",
+            snake_case(&member)
+        )
+        .unwrap();
+    }
+
+    for property in interface.properties() {
+        let member = property.name();
+        let ty = rust_type(&property.ty().to_string(), false);
+        writeln!(code, "#[zbus(property, name = \"{member}\")]").unwrap();
+        writeln!(
+            code,
+            "fn {}(&self) -> zbus::Result<{ty}>;",
+            snake_case(&member)
+        )
+        .unwrap();
+    }
+
+    code.push_str("}\n");
+}
+
+fn arg_name(name: Option<&str>, position: usize) -> String {
+    match name {
+        Some("type") => "type_".to_owned(),
+        Some(name) => snake_case(name),
+        None => format!("arg_{position}"),
+    }
+}
+
+fn tuple(mut types: Vec<String>) -> String {
+    match types.len() {
+        0 => "()".to_owned(),
+        1 => types.remove(0),
+        _ => format!("({})", types.join(", ")),
+    }
+}
+
+/// The Rust type of a complete D-Bus type signature, borrowed for the input
+/// arguments and owned otherwise.
+fn rust_type(signature: &str, input: bool) -> String {
+    let mut chars = signature.chars().peekable();
+    let ty = parse_type(&mut chars, input, true);
+    assert!(chars.next().is_none(), "Unexpected signature {signature}");
+    ty
+}
+
+fn parse_type(
+    chars: &mut std::iter::Peekable<std::str::Chars<'_>>,
+    input: bool,
+    top_level: bool,
+) -> String {
+    let reference = |ty: &str| {
+        if input && top_level {
+            format!("&{ty}")
+        } else {
+            ty.to_owned()
+        }
+    };
+    match chars.next().expect("Incomplete signature") {
+        'y' => "u8".to_owned(),
+        'b' => "bool".to_owned(),
+        'n' => "i16".to_owned(),
+        'q' => "u16".to_owned(),
+        'i' => "i32".to_owned(),
+        'u' => "u32".to_owned(),
+        'x' => "i64".to_owned(),
+        't' => "u64".to_owned(),
+        'd' => "f64".to_owned(),
+        's' if input => "&str".to_owned(),
+        's' => "String".to_owned(),
+        'o' if input => reference("zbus::zvariant::ObjectPath<'_>"),
+        'o' => "zbus::zvariant::OwnedObjectPath".to_owned(),
+        'g' if input => reference("zbus::zvariant::Signature"),
+        'g' => "zbus::zvariant::Signature".to_owned(),
+        'v' if input => "&zbus::zvariant::Value<'_>".to_owned(),
+        'v' => "zbus::zvariant::OwnedValue".to_owned(),
+        'h' if input => "zbus::zvariant::Fd<'_>".to_owned(),
+        'h' => "zbus::zvariant::OwnedFd".to_owned(),
+        'a' if chars.peek() == Some(&'{') => {
+            chars.next();
+            let key = parse_type(chars, input, false);
+            let value = parse_type(chars, input, false);
+            assert_eq!(chars.next(), Some('}'));
+            format!("std::collections::HashMap<{key}, {value}>")
+        }
+        'a' => {
+            let element = parse_type(chars, input, false);
+            if input {
+                format!("&[{element}]")
+            } else {
+                format!("Vec<{element}>")
+            }
+        }
+        '(' => {
+            let mut fields = Vec::new();
+            while chars.peek() != Some(&')') {
+                fields.push(parse_type(chars, input, false));
+            }
+            chars.next();
+            reference(&format!("({},)", fields.join(", ")))
+        }
+        c => panic!("Unsupported type {c}"),
+    }
+}
+
+fn snake_case(name: &str) -> String {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut snake = String::new();
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            let previous = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            if previous.is_lowercase()
+                || previous.is_ascii_digit()
+                || (previous.is_uppercase() && next_is_lower)
+            {
+                snake.push('_');
+            }
+        }
+        snake.extend(c.to_lowercase());
+    }
+    snake.replace('-', "_")
+}
+
+/// Formats `code` with the configuration of the repository.
+fn rustfmt(root: &Path, code: &str) -> String {
+    let mut child = Command::new("rustfmt")
+        .args(["--edition", "2021", "--emit", "stdout"])
+        .current_dir(root)
+        .stderr(Stdio::null())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to run rustfmt");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(code.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success(), "rustfmt failed");
+    String::from_utf8(output.stdout).unwrap()
+}